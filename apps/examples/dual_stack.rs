@@ -0,0 +1,354 @@
+//! Dual Stack Example (smoltcp + raw EtherType)
+//!
+//! This example runs the smoltcp TCP echo server and a raw EtherType
+//! ping-pong responder on the same EMAC at the same time. Every received
+//! frame passes through a [`PrefilterDevice`] callback first: frames with
+//! EtherType `0x88B5` are answered directly from the callback and never reach
+//! smoltcp, everything else goes to the IP stack.
+//!
+//! # Features Demonstrated
+//!
+//! - Pre-filter/demux of raw frames in front of smoltcp
+//! - TCP echo server on port 7 with DHCP IPv4 configuration
+//! - Raw `0x88B5` (IEEE local experimental) ping-pong
+//! - Periodic counters for both paths, so a regression in either shows up
+//!   in the log
+//!
+//! # Hardware
+//!
+//! Tested on WT32-ETH01 board with:
+//! - ESP32 (WT32-S1 module)
+//! - LAN8720A PHY at address 1
+//! - External 50 MHz oscillator (enabled via GPIO16)
+//!
+//! # Building
+//!
+//! ```bash
+//! cargo xtask run ex-dual-stack
+//! ```
+//!
+//! # Testing
+//!
+//! 1. Connect the board to your network and note the DHCP address and MAC.
+//! 2. TCP: `nc <assigned-ip> 7`, type text and see it echoed back.
+//! 3. Raw: send an `0x88B5` frame whose payload starts with `PING` to the
+//!    board MAC, e.g. with scapy:
+//!
+//!    ```python
+//!    sendp(Ether(dst="02:00:00:12:34:57", type=0x88B5) / b"PING\x00\x00\x00\x01", iface="eth0")
+//!    ```
+//!
+//!    The board answers with the same payload, `PING` replaced by `PONG`.
+//! 4. Run both at once; the status line every 10 seconds reports frames
+//!    seen on each path and any raw replies that could not be sent.
+
+#![no_std]
+#![no_main]
+
+use esp_backtrace as _;
+use esp_bootloader_esp_idf::esp_app_desc;
+use esp_hal::{
+    delay::Delay,
+    gpio::{Level, Output, OutputConfig},
+    main,
+    rng::Rng,
+    time::Instant,
+};
+use log::{debug, info, warn};
+
+use smoltcp::{
+    iface::{Config, Interface, SocketSet},
+    socket::dhcpv4,
+    socket::tcp::{Socket as TcpSocket, SocketBuffer as TcpSocketBuffer},
+    time::Instant as SmolInstant,
+    wire::{EthernetAddress, IpCidr},
+};
+
+use ph_esp32_mac::boards::wt32_eth01::Wt32Eth01;
+use ph_esp32_mac::hal::MdioController;
+use ph_esp32_mac::integration::smoltcp::{PrefilterDevice, RxFilterAction};
+use ph_esp32_mac::{Duplex, Emac, PhyDriver, Speed};
+
+// =============================================================================
+// Configuration
+// =============================================================================
+
+/// TCP echo server port.
+const ECHO_PORT: u16 = 7;
+
+/// MAC address (locally administered).
+const MAC_ADDRESS: [u8; 6] = [0x02, 0x00, 0x00, 0x12, 0x34, 0x57];
+
+/// EtherType handled outside smoltcp (IEEE 802 local experimental 1).
+const RAW_ETHERTYPE: u16 = 0x88B5;
+
+/// Payload prefix of a raw request.
+const RAW_PING: &[u8; 4] = b"PING";
+
+/// Payload prefix of a raw reply.
+const RAW_PONG: &[u8; 4] = b"PONG";
+
+/// Status log interval (seconds).
+const STATUS_INTERVAL_SECS: u64 = 10;
+
+/// Link poll interval while waiting for link-up.
+const LINK_POLL_MS: u32 = 100;
+
+// =============================================================================
+// Static EMAC Instance
+// =============================================================================
+
+ph_esp32_mac::emac_static_sync!(EMAC, 10, 10, 1600);
+
+// =============================================================================
+// Raw EtherType Handler
+// =============================================================================
+
+/// Counters for both receive paths.
+#[derive(Default)]
+struct DualStats {
+    /// Frames handed to smoltcp
+    ip_frames: u32,
+    /// Raw requests answered
+    raw_pongs: u32,
+    /// Raw frames with an unknown payload (consumed, not answered)
+    raw_ignored: u32,
+    /// Raw replies that failed to transmit
+    raw_tx_errors: u32,
+}
+
+/// Ethernet header length (dst + src + EtherType).
+const ETH_HEADER_LEN: usize = 14;
+
+/// Pre-filter callback: answer raw pings, pass everything else on.
+fn raw_filter<const RX: usize, const TX: usize, const BUF: usize>(
+    frame: &[u8],
+    emac: &mut Emac<RX, TX, BUF>,
+    stats: &mut DualStats,
+) -> RxFilterAction {
+    if frame.len() < ETH_HEADER_LEN
+        || u16::from_be_bytes([frame[12], frame[13]]) != RAW_ETHERTYPE
+    {
+        stats.ip_frames = stats.ip_frames.wrapping_add(1);
+        return RxFilterAction::Pass;
+    }
+
+    let payload = &frame[ETH_HEADER_LEN..];
+    if !payload.starts_with(RAW_PING) {
+        stats.raw_ignored = stats.raw_ignored.wrapping_add(1);
+        return RxFilterAction::Consume;
+    }
+
+    // Reply to the sender with the same payload, PING -> PONG
+    let mut reply = [0u8; 1514];
+    let len = frame.len().min(reply.len());
+    reply[..len].copy_from_slice(&frame[..len]);
+    reply[0..6].copy_from_slice(&frame[6..12]);
+    reply[6..12].copy_from_slice(emac.mac_address());
+    reply[ETH_HEADER_LEN..ETH_HEADER_LEN + RAW_PONG.len()].copy_from_slice(RAW_PONG);
+
+    match emac.transmit(&reply[..len]) {
+        Ok(_) => {
+            stats.raw_pongs = stats.raw_pongs.wrapping_add(1);
+            debug!("Raw PONG sent ({} bytes)", len);
+        }
+        Err(e) => {
+            stats.raw_tx_errors = stats.raw_tx_errors.wrapping_add(1);
+            warn!("Raw PONG transmit failed: {:?}", e);
+        }
+    }
+
+    RxFilterAction::Consume
+}
+
+// =============================================================================
+// Main Entry Point
+// =============================================================================
+
+esp_app_desc!();
+
+#[main]
+fn main() -> ! {
+    let peripherals = esp_hal::init(esp_hal::Config::default());
+
+    // Initialize logging
+    esp_println::logger::init_logger_from_env();
+    info!("Dual stack example (smoltcp + raw 0x{:04X}) starting...", RAW_ETHERTYPE);
+
+    let mut delay = Delay::new();
+    let mut mdio = MdioController::new(Delay::new());
+
+    // Enable external oscillator (WT32-ETH01 specific)
+    let mut clk_en = Output::new(peripherals.GPIO16, Level::Low, OutputConfig::default());
+    clk_en.set_high();
+    delay.delay_millis(Wt32Eth01::OSC_STARTUP_MS);
+
+    // Configure EMAC (board defaults + MAC address)
+    let config = Wt32Eth01::emac_config_with_mac(MAC_ADDRESS);
+
+    info!("Initializing EMAC...");
+    EMAC.with(|emac| emac.init(config, &mut delay))
+        .expect("EMAC init failed");
+
+    // Initialize PHY
+    info!("Initializing PHY...");
+    let mut phy = Wt32Eth01::lan8720a();
+    phy.init(&mut mdio).expect("PHY init failed");
+
+    // Wait for link
+    info!("Waiting for link...");
+    loop {
+        delay.delay_millis(LINK_POLL_MS);
+        if let Ok(Some(status)) = phy.poll_link(&mut mdio) {
+            info!(
+                "Link UP: {} {}",
+                if matches!(status.speed, Speed::Mbps100) {
+                    "100Mbps"
+                } else {
+                    "10Mbps"
+                },
+                if matches!(status.duplex, Duplex::Full) { "FD" } else { "HD" }
+            );
+            EMAC.with(|emac| {
+                emac.set_speed(status.speed);
+                emac.set_duplex(status.duplex);
+            });
+            break;
+        }
+    }
+
+    // Start EMAC
+    EMAC.with(|emac| emac.start()).expect("EMAC start failed");
+    EMAC.with(|emac| {
+        emac.set_broadcast_enabled(true);
+        emac.set_pass_all_multicast(true);
+    });
+    info!(
+        "EMAC started, MAC {:02X}:{:02X}:{:02X}:{:02X}:{:02X}:{:02X}",
+        MAC_ADDRESS[0], MAC_ADDRESS[1], MAC_ADDRESS[2], MAC_ADDRESS[3], MAC_ADDRESS[4], MAC_ADDRESS[5]
+    );
+
+    // ======================================================================
+    // smoltcp Interface Setup
+    // ======================================================================
+
+    let hw_addr = EMAC.with(|emac| EthernetAddress(*emac.mac_address()));
+    let mut smol_config = Config::new(hw_addr.into());
+    let rng = Rng::new();
+    smol_config.random_seed = ((rng.random() as u64) << 32) | (rng.random() as u64);
+
+    let mut iface = EMAC.with(|emac| Interface::new(smol_config, emac, SmolInstant::from_millis(0)));
+    iface.set_any_ip(true);
+
+    let mut socket_storage = [smoltcp::iface::SocketStorage::EMPTY; 4];
+    let mut sockets = SocketSet::new(&mut socket_storage[..]);
+
+    let mut tcp_rx_buffer = [0u8; 1024];
+    let mut tcp_tx_buffer = [0u8; 1024];
+    let tcp_handle = sockets.add(TcpSocket::new(
+        TcpSocketBuffer::new(&mut tcp_rx_buffer[..]),
+        TcpSocketBuffer::new(&mut tcp_tx_buffer[..]),
+    ));
+    let dhcp_handle = sockets.add(dhcpv4::Socket::new());
+
+    sockets
+        .get_mut::<TcpSocket>(tcp_handle)
+        .listen(ECHO_PORT)
+        .unwrap();
+    info!("TCP echo server listening on port {}", ECHO_PORT);
+
+    // ======================================================================
+    // Main Network Loop
+    // ======================================================================
+
+    let mut stats = DualStats::default();
+    let mut last_status_time = Instant::now();
+    let mut connections = 0u32;
+    let mut bytes_echoed = 0u64;
+    let mut echo_buf = [0u8; 1024];
+
+    info!("Entering main loop...");
+
+    loop {
+        let now = Instant::now();
+        let smol_now = SmolInstant::from_millis(now.duration_since_epoch().as_millis() as i64);
+
+        // Raw frames are answered inside the pre-filter; smoltcp only sees
+        // what the filter passes on
+        EMAC.with(|emac| {
+            let mut device =
+                PrefilterDevice::new(emac, |frame: &[u8], emac: &mut Emac<10, 10, 1600>| {
+                    raw_filter(frame, emac, &mut stats)
+                });
+            let _activity = iface.poll(smol_now, &mut device, &mut sockets);
+        });
+
+        if let Some(event) = sockets.get_mut::<dhcpv4::Socket>(dhcp_handle).poll() {
+            match event {
+                dhcpv4::Event::Configured(config) => {
+                    iface.update_ip_addrs(|addrs| {
+                        addrs.clear();
+                        addrs.push(IpCidr::Ipv4(config.address)).unwrap();
+                    });
+                    iface.set_any_ip(false);
+                    if let Some(router) = config.router {
+                        iface.routes_mut().add_default_ipv4_route(router).ok();
+                    } else {
+                        iface.routes_mut().remove_default_ipv4_route();
+                    }
+                    info!("DHCP address: {}", config.address);
+                    info!("Test with: nc {} {}", config.address.address(), ECHO_PORT);
+                }
+                dhcpv4::Event::Deconfigured => {
+                    iface.update_ip_addrs(|addrs| addrs.clear());
+                    iface.routes_mut().remove_default_ipv4_route();
+                    iface.set_any_ip(true);
+                    warn!("DHCP deconfigured");
+                }
+            }
+        }
+
+        {
+            let socket = sockets.get_mut::<TcpSocket>(tcp_handle);
+
+            if socket.can_recv() {
+                match socket.recv_slice(&mut echo_buf) {
+                    Ok(len) if len > 0 && socket.can_send() => {
+                        match socket.send_slice(&echo_buf[..len]) {
+                            Ok(sent) => bytes_echoed += sent as u64,
+                            Err(e) => warn!("Send error: {:?}", e),
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => warn!("Recv error: {:?}", e),
+                }
+            }
+
+            if !socket.is_active() && !socket.is_listening() {
+                socket.abort();
+                socket.listen(ECHO_PORT).unwrap();
+                connections += 1;
+            }
+        }
+
+        if (now - last_status_time).as_secs() >= STATUS_INTERVAL_SECS {
+            info!(
+                "Status: ip_frames={} tcp_conns={} tcp_bytes={} raw_pongs={} raw_ignored={} raw_tx_errors={}",
+                stats.ip_frames,
+                connections,
+                bytes_echoed,
+                stats.raw_pongs,
+                stats.raw_ignored,
+                stats.raw_tx_errors
+            );
+
+            if let Ok(false) = phy.is_link_up(&mut mdio) {
+                warn!("Link is DOWN!");
+            }
+
+            last_status_time = now;
+        }
+
+        delay.delay_micros(10);
+    }
+}
@@ -0,0 +1,574 @@
+//! Embassy MQTT Reference Example
+//!
+//! This example wires every integration seam of ph-esp32-mac together into a
+//! long-running MQTT telemetry client. It is meant both as living
+//! documentation and as a soak test: leave it running and flap the cable.
+//!
+//! # Features Demonstrated
+//!
+//! - Board profile bring-up via `EmacBuilder` / `EmacPhyBundle` (WT32-ETH01)
+//! - Link supervision with [`PhyStateMachine`] (debounce + flap damping)
+//!   feeding the embassy-net link state
+//! - DHCP with a static IPv4 fallback when no server answers
+//! - Minimal MQTT 3.1.1 client (CONNECT / PUBLISH QoS 0 / PINGREQ) over an
+//!   embassy-net TCP socket
+//! - Automatic MQTT reconnect with exponential backoff on link flap, lost
+//!   address, or broker disconnect
+//!
+//! # Hardware
+//!
+//! Tested on WT32-ETH01 board:
+//! - ESP32 (WT32-S1 module)
+//! - LAN8720A PHY at MDIO address 1
+//! - External 50 MHz oscillator enabled via GPIO16
+//!
+//! # Building
+//!
+//! ```bash
+//! cargo xtask run ex-mqtt
+//! ```
+//!
+//! # Testing
+//!
+//! 1. Set [`BROKER_ADDR`] to a reachable MQTT broker (e.g. mosquitto).
+//! 2. Subscribe on the host: `mosquitto_sub -h <broker> -t 'ph-esp32-mac/#' -v`
+//! 3. The board publishes a status line every [`PUBLISH_INTERVAL_SECS`].
+//! 4. Unplug and replug the cable; the log shows the link supervisor events,
+//!    DHCP re-acquisition, and the MQTT session reconnecting. The `flaps` and
+//!    `reconnects` counters in the payload keep counting across sessions.
+//!
+//! # Architecture
+//!
+//! ```text
+//!   ┌──────────────┬──────────────┬──────────────┬──────────────┐
+//!   │ net_task     │ link_task    │ dhcp_task    │ mqtt_task    │
+//!   │ (runner)     │ (supervisor) │ (+ fallback) │ (reconnect)  │
+//!   └──────┬───────┴──────┬───────┴──────┬───────┴──────┬───────┘
+//!          │              │ LinkState    │ ConfigV4     │ TcpSocket
+//!          └──────────────┴──────┬───────┴──────────────┘
+//!                                │
+//!                   ┌────────────┴────────────┐
+//!                   │  embassy_net::Stack     │
+//!                   └────────────┬────────────┘
+//!                   ┌────────────┴────────────┐
+//!                   │  EmbassyEmac + Emac     │
+//!                   └────────────┬────────────┘
+//!                   ┌────────────┴────────────┐
+//!                   │  LAN8720A PHY           │
+//!                   └─────────────────────────┘
+//! ```
+
+#![no_std]
+#![no_main]
+
+// =============================================================================
+// Imports
+// =============================================================================
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use embassy_executor::Spawner;
+use embassy_net::{
+    tcp::TcpSocket, Config, ConfigV4, DhcpConfig, IpAddress, Ipv4Address, Ipv4Cidr, Stack,
+    StaticConfigV4,
+};
+use embassy_net_driver::LinkState;
+use embassy_time::{Duration, Instant, Timer};
+use esp_backtrace as _;
+use esp_bootloader_esp_idf::esp_app_desc;
+use esp_hal::{
+    delay::Delay,
+    gpio::{Level, Output, OutputConfig},
+    interrupt::Priority,
+    rng::Rng,
+    timer::timg::TimerGroup,
+};
+use log::{info, warn};
+
+use ph_esp32_mac::esp_hal::{EmacBuilder, EmacExt, EmacPhyBundle, Wt32Eth01};
+use ph_esp32_mac::hal::MdioController;
+use ph_esp32_mac::{emac_isr, Emac, EmbassyEmac, FlapDamping, PhyEvent, PhyStateMachine};
+
+// =============================================================================
+// Configuration
+// =============================================================================
+
+/// MAC address for this device (locally administered).
+const MAC_ADDRESS: [u8; 6] = [0x02, 0x00, 0x00, 0x12, 0x34, 0x58];
+
+/// MQTT broker address.
+const BROKER_ADDR: IpAddress = IpAddress::v4(192, 168, 1, 10);
+
+/// MQTT broker port.
+const BROKER_PORT: u16 = 1883;
+
+/// MQTT client identifier.
+const CLIENT_ID: &str = "ph-esp32-mac";
+
+/// Topic the status line is published to.
+const STATUS_TOPIC: &str = "ph-esp32-mac/status";
+
+/// MQTT keep-alive (seconds). A PINGREQ is sent at half this interval.
+const KEEP_ALIVE_SECS: u16 = 30;
+
+/// Status publish interval (seconds).
+const PUBLISH_INTERVAL_SECS: u64 = 10;
+
+/// Initial reconnect backoff (milliseconds).
+const RECONNECT_MIN_MS: u64 = 500;
+
+/// Maximum reconnect backoff (milliseconds).
+const RECONNECT_MAX_MS: u64 = 30_000;
+
+/// How long to wait for a DHCP lease before using the static fallback.
+const DHCP_TIMEOUT_SECS: u64 = 15;
+
+/// Static fallback address when DHCP does not answer.
+const FALLBACK_ADDR: Ipv4Address = Ipv4Address::new(192, 168, 1, 200);
+
+/// Static fallback prefix length.
+const FALLBACK_PREFIX: u8 = 24;
+
+/// Static fallback gateway.
+const FALLBACK_GATEWAY: Ipv4Address = Ipv4Address::new(192, 168, 1, 1);
+
+/// PHY link polling interval.
+const LINK_POLL_MS: u64 = 250;
+
+/// Consecutive link-up samples required before reporting link up.
+const LINK_UP_DEBOUNCE: u8 = 2;
+
+/// Flap damping: hold the link down after 4 flaps within 40 samples
+/// (10 s at [`LINK_POLL_MS`]) for 40 samples.
+const LINK_FLAP_DAMPING: FlapDamping = FlapDamping::new(4, 40, 40);
+
+// =============================================================================
+// Static Allocations
+// =============================================================================
+
+// EMAC hardware instance, driver state, and embassy-net resources.
+ph_esp32_mac::embassy_net_statics!(EMAC, EMAC_STATE, NET_RESOURCES, 10, 10, 1600, 4);
+
+/// Link flaps seen by the supervisor (published in the status line).
+static LINK_FLAPS: AtomicU32 = AtomicU32::new(0);
+
+/// MQTT sessions established (published in the status line).
+static MQTT_SESSIONS: AtomicU32 = AtomicU32::new(0);
+
+// =============================================================================
+// Interrupt Handler
+// =============================================================================
+
+emac_isr!(EMAC_IRQ, Priority::Priority1, {
+    EMAC_STATE.handle_interrupt();
+});
+
+// =============================================================================
+// Minimal MQTT 3.1.1 Encoding
+// =============================================================================
+
+// MQTT control packet types (fixed header byte 1)
+const MQTT_CONNECT: u8 = 0x10;
+const MQTT_CONNACK: u8 = 0x20;
+const MQTT_PUBLISH: u8 = 0x30;
+const MQTT_PINGREQ: u8 = 0xC0;
+const MQTT_PINGRESP: u8 = 0xD0;
+
+/// CONNECT flags: clean session.
+const MQTT_CLEAN_SESSION: u8 = 0x02;
+
+/// Reasons an MQTT session ends.
+#[derive(Debug)]
+enum MqttError {
+    /// TCP connect failed
+    Connect(embassy_net::tcp::ConnectError),
+    /// TCP read/write failed
+    Io(embassy_net::tcp::Error),
+    /// Broker closed the connection
+    Closed,
+    /// Broker refused the CONNECT (return code)
+    Refused(u8),
+    /// Unexpected packet from the broker
+    Protocol,
+    /// Encoded packet does not fit the buffer
+    Overflow,
+    /// Link or address went away
+    LinkLost,
+}
+
+/// Fixed-capacity packet writer.
+struct PacketBuf<const N: usize> {
+    buf: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> PacketBuf<N> {
+    const fn new() -> Self {
+        Self {
+            buf: [0u8; N],
+            len: 0,
+        }
+    }
+
+    fn push(&mut self, bytes: &[u8]) -> Result<(), MqttError> {
+        let end = self.len + bytes.len();
+        if end > N {
+            return Err(MqttError::Overflow);
+        }
+        self.buf[self.len..end].copy_from_slice(bytes);
+        self.len = end;
+        Ok(())
+    }
+
+    fn push_str(&mut self, s: &str) -> Result<(), MqttError> {
+        self.push(&(s.len() as u16).to_be_bytes())?;
+        self.push(s.as_bytes())
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+}
+
+impl<const N: usize> core::fmt::Write for PacketBuf<N> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.push(s.as_bytes()).map_err(|_| core::fmt::Error)
+    }
+}
+
+/// Frame `body` with a fixed header and variable-length remaining length.
+fn mqtt_frame<const N: usize>(header: u8, body: &[u8]) -> Result<PacketBuf<N>, MqttError> {
+    let mut out = PacketBuf::new();
+    out.push(&[header])?;
+    let mut remaining = body.len();
+    loop {
+        let mut byte = (remaining % 128) as u8;
+        remaining /= 128;
+        if remaining > 0 {
+            byte |= 0x80;
+        }
+        out.push(&[byte])?;
+        if remaining == 0 {
+            break;
+        }
+    }
+    out.push(body)?;
+    Ok(out)
+}
+
+async fn write_all(socket: &mut TcpSocket<'_>, mut data: &[u8]) -> Result<(), MqttError> {
+    while !data.is_empty() {
+        match socket.write(data).await.map_err(MqttError::Io)? {
+            0 => return Err(MqttError::Closed),
+            n => data = &data[n..],
+        }
+    }
+    socket.flush().await.map_err(MqttError::Io)
+}
+
+async fn read_exact(socket: &mut TcpSocket<'_>, buf: &mut [u8]) -> Result<(), MqttError> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match socket.read(&mut buf[filled..]).await.map_err(MqttError::Io)? {
+            0 => return Err(MqttError::Closed),
+            n => filled += n,
+        }
+    }
+    Ok(())
+}
+
+async fn mqtt_connect(socket: &mut TcpSocket<'_>) -> Result<(), MqttError> {
+    let mut body = PacketBuf::<64>::new();
+    body.push_str("MQTT")?;
+    body.push(&[0x04, MQTT_CLEAN_SESSION])?; // Protocol level 4 (3.1.1)
+    body.push(&KEEP_ALIVE_SECS.to_be_bytes())?;
+    body.push_str(CLIENT_ID)?;
+    let packet = mqtt_frame::<72>(MQTT_CONNECT, body.as_bytes())?;
+    write_all(socket, packet.as_bytes()).await?;
+
+    let mut connack = [0u8; 4];
+    read_exact(socket, &mut connack).await?;
+    if connack[0] != MQTT_CONNACK || connack[1] != 0x02 {
+        return Err(MqttError::Protocol);
+    }
+    match connack[3] {
+        0 => Ok(()),
+        code => Err(MqttError::Refused(code)),
+    }
+}
+
+async fn mqtt_publish(
+    socket: &mut TcpSocket<'_>,
+    topic: &str,
+    payload: &[u8],
+) -> Result<(), MqttError> {
+    let mut body = PacketBuf::<256>::new();
+    body.push_str(topic)?;
+    body.push(payload)?;
+    let packet = mqtt_frame::<264>(MQTT_PUBLISH, body.as_bytes())?;
+    write_all(socket, packet.as_bytes()).await
+}
+
+async fn mqtt_ping(socket: &mut TcpSocket<'_>) -> Result<(), MqttError> {
+    write_all(socket, &[MQTT_PINGREQ, 0x00]).await?;
+    let mut resp = [0u8; 2];
+    read_exact(socket, &mut resp).await?;
+    if resp != [MQTT_PINGRESP, 0x00] {
+        return Err(MqttError::Protocol);
+    }
+    Ok(())
+}
+
+// =============================================================================
+// Embassy Tasks
+// =============================================================================
+
+/// Network stack runner task.
+#[embassy_executor::task]
+async fn net_task(mut runner: embassy_net::Runner<'static, EmbassyEmac<'static, 10, 10, 1600>>) -> ! {
+    runner.run().await
+}
+
+/// Link supervisor task.
+///
+/// Feeds PHY samples through a [`PhyStateMachine`] and only reports debounced
+/// transitions to embassy-net, so a bouncing cable does not restart DHCP and
+/// MQTT on every glitch.
+#[embassy_executor::task]
+async fn link_task(emac_ptr: *mut Emac<10, 10, 1600>) -> ! {
+    let mut mdio = MdioController::new(Delay::new());
+    let phy = Wt32Eth01::lan8720a();
+    let mut supervisor = PhyStateMachine::new()
+        .with_up_debounce(LINK_UP_DEBOUNCE)
+        .with_flap_damping(LINK_FLAP_DAMPING);
+
+    loop {
+        match supervisor.poll(&phy, &mut mdio) {
+            Some(PhyEvent::LinkUp(status) | PhyEvent::LinkChanged(status)) => {
+                info!("Link up: {:?} {:?}", status.speed, status.duplex);
+                // SAFETY: EMAC is static and only this task changes speed/duplex
+                unsafe {
+                    let emac = &mut *emac_ptr;
+                    emac.set_speed(status.speed);
+                    emac.set_duplex(status.duplex);
+                }
+                EMAC_STATE.set_link_state(LinkState::Up);
+            }
+            Some(PhyEvent::LinkDown) => {
+                let flaps = LINK_FLAPS.fetch_add(1, Ordering::Relaxed) + 1;
+                warn!("Link down (flap #{})", flaps);
+                EMAC_STATE.set_link_state(LinkState::Down);
+            }
+            Some(PhyEvent::Fault) => {
+                warn!("PHY fault: MDIO reads failing");
+                EMAC_STATE.set_link_state(LinkState::Down);
+            }
+            Some(event) => info!("PHY event: {:?}", event),
+            None => {}
+        }
+
+        if supervisor.is_held_down() && supervisor.hold_down_remaining() == 1 {
+            info!("Link flap hold-down ending");
+        }
+
+        Timer::after(Duration::from_millis(LINK_POLL_MS)).await;
+    }
+}
+
+/// DHCP task with static fallback.
+///
+/// On every link-up, tries DHCP for [`DHCP_TIMEOUT_SECS`] and falls back to a
+/// static address if no lease arrives. Starts over when the link drops.
+#[embassy_executor::task]
+async fn dhcp_task(stack: Stack<'static>) -> ! {
+    loop {
+        stack.wait_link_up().await;
+        info!("Link up - starting DHCP");
+        stack.set_config_v4(ConfigV4::Dhcp(DhcpConfig::default()));
+
+        let deadline = Instant::now() + Duration::from_secs(DHCP_TIMEOUT_SECS);
+        while stack.config_v4().is_none() && Instant::now() < deadline && stack.is_link_up() {
+            Timer::after(Duration::from_millis(250)).await;
+        }
+
+        match stack.config_v4() {
+            Some(config) => info!("DHCP acquired: {}", config.address),
+            None if stack.is_link_up() => {
+                warn!(
+                    "No DHCP lease after {}s, using fallback {}/{}",
+                    DHCP_TIMEOUT_SECS, FALLBACK_ADDR, FALLBACK_PREFIX
+                );
+                stack.set_config_v4(ConfigV4::Static(StaticConfigV4 {
+                    address: Ipv4Cidr::new(FALLBACK_ADDR, FALLBACK_PREFIX),
+                    gateway: Some(FALLBACK_GATEWAY),
+                    dns_servers: Default::default(),
+                }));
+            }
+            None => {}
+        }
+
+        // Retry DHCP from scratch after the next link-up
+        stack.wait_link_down().await;
+        warn!("Link lost - network configuration dropped");
+        stack.set_config_v4(ConfigV4::None);
+    }
+}
+
+/// MQTT client task.
+///
+/// Connects, publishes a status line periodically, and keeps the session
+/// alive with PINGREQ. Any failure (including link or address loss) tears
+/// the session down and reconnects with exponential backoff.
+#[embassy_executor::task]
+async fn mqtt_task(stack: Stack<'static>) -> ! {
+    let mut rx_buf = [0u8; 512];
+    let mut tx_buf = [0u8; 512];
+    let mut backoff_ms = RECONNECT_MIN_MS;
+
+    loop {
+        stack.wait_config_up().await;
+
+        let mut socket = TcpSocket::new(stack, &mut rx_buf, &mut tx_buf);
+        socket.set_timeout(Some(Duration::from_secs(u64::from(KEEP_ALIVE_SECS))));
+
+        let sessions_before = MQTT_SESSIONS.load(Ordering::Relaxed);
+        let result = mqtt_session(stack, &mut socket).await;
+        socket.abort();
+        let _ = socket.flush().await;
+
+        match result {
+            Err(MqttError::LinkLost) => warn!("MQTT session ended: link lost"),
+            Err(e) => warn!("MQTT session ended: {:?}", e),
+            Ok(()) => {}
+        }
+
+        // Back off only while the broker keeps failing; a session that was
+        // established (e.g. dropped by a link flap) reconnects quickly
+        if MQTT_SESSIONS.load(Ordering::Relaxed) != sessions_before {
+            backoff_ms = RECONNECT_MIN_MS;
+        }
+
+        info!("MQTT reconnect in {} ms", backoff_ms);
+        Timer::after(Duration::from_millis(backoff_ms)).await;
+        backoff_ms = (backoff_ms * 2).min(RECONNECT_MAX_MS);
+    }
+}
+
+/// Run one MQTT session until it fails.
+async fn mqtt_session(stack: Stack<'static>, socket: &mut TcpSocket<'_>) -> Result<(), MqttError> {
+    info!("MQTT connecting to {}:{}", BROKER_ADDR, BROKER_PORT);
+    socket
+        .connect((BROKER_ADDR, BROKER_PORT))
+        .await
+        .map_err(MqttError::Connect)?;
+    mqtt_connect(socket).await?;
+
+    let sessions = MQTT_SESSIONS.fetch_add(1, Ordering::Relaxed) + 1;
+    info!("MQTT connected (session #{})", sessions);
+
+    let started = Instant::now();
+    let mut last_ping = Instant::now();
+    let mut seq = 0u32;
+
+    loop {
+        if !stack.is_link_up() || !stack.is_config_up() {
+            return Err(MqttError::LinkLost);
+        }
+
+        let mut payload = PacketBuf::<160>::new();
+        let _ = core::fmt::write(
+            &mut payload,
+            format_args!(
+                "seq={} uptime={}s session={} flaps={} reconnects={}",
+                seq,
+                started.elapsed().as_secs(),
+                sessions,
+                LINK_FLAPS.load(Ordering::Relaxed),
+                sessions - 1
+            ),
+        );
+        mqtt_publish(socket, STATUS_TOPIC, payload.as_bytes()).await?;
+        seq = seq.wrapping_add(1);
+
+        if last_ping.elapsed() >= Duration::from_secs(u64::from(KEEP_ALIVE_SECS / 2)) {
+            mqtt_ping(socket).await?;
+            last_ping = Instant::now();
+        }
+
+        Timer::after(Duration::from_secs(PUBLISH_INTERVAL_SECS)).await;
+    }
+}
+
+// =============================================================================
+// Main Entry Point
+// =============================================================================
+
+esp_app_desc!();
+
+#[esp_rtos::main]
+async fn main(spawner: Spawner) -> ! {
+    esp_println::logger::init_logger_from_env();
+    info!("Embassy MQTT example starting...");
+
+    let peripherals = esp_hal::init(esp_hal::Config::default());
+
+    let timg0 = TimerGroup::new(peripherals.TIMG0);
+    esp_rtos::start(timg0.timer0);
+
+    // -------------------------------------------------------------------------
+    // Board Bring-Up (WT32-ETH01 profile)
+    // -------------------------------------------------------------------------
+
+    let mut clk_en = Output::new(peripherals.GPIO16, Level::High, OutputConfig::default());
+    clk_en.set_high();
+    let mut delay = Delay::new();
+    delay.delay_millis(Wt32Eth01::OSC_STARTUP_MS);
+    info!("{}", Wt32Eth01::description());
+
+    let emac_ptr = EMAC.init(Emac::new()) as *mut Emac<10, 10, 1600>;
+
+    // SAFETY: emac_ptr points to static storage valid for program lifetime
+    let emac = unsafe { &mut *emac_ptr };
+
+    EmacBuilder::wt32_eth01_with_mac(emac, MAC_ADDRESS)
+        .init(&mut delay)
+        .expect("EMAC init failed");
+
+    {
+        let mut phy_bundle = EmacPhyBundle::wt32_eth01_lan8720a(emac, Delay::new());
+        phy_bundle.init_phy().expect("PHY init failed");
+    }
+
+    // The link supervisor reports the first link-up
+    EMAC_STATE.set_link_state(LinkState::Down);
+
+    emac.start().expect("EMAC start failed");
+    emac.bind_interrupt(EMAC_IRQ);
+    info!("EMAC started (memory: {} bytes)", Emac::<10, 10, 1600>::memory_usage());
+
+    // -------------------------------------------------------------------------
+    // Embassy-net Stack Setup
+    // -------------------------------------------------------------------------
+
+    let driver = ph_esp32_mac::embassy_net_driver!(emac_ptr, &EMAC_STATE);
+
+    let rng = Rng::new();
+    let seed = ((rng.random() as u64) << 32) | (rng.random() as u64);
+
+    let (stack, runner) = ph_esp32_mac::embassy_net_stack!(driver, NET_RESOURCES, Config::default(), seed);
+
+    // -------------------------------------------------------------------------
+    // Spawn Tasks
+    // -------------------------------------------------------------------------
+
+    spawner.spawn(net_task(runner)).unwrap();
+    spawner.spawn(link_task(emac_ptr)).unwrap();
+    spawner.spawn(dhcp_task(stack)).unwrap();
+    spawner.spawn(mqtt_task(stack)).unwrap();
+
+    info!("All tasks spawned - system running");
+
+    loop {
+        Timer::after(Duration::from_secs(60)).await;
+    }
+}
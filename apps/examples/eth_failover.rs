@@ -0,0 +1,283 @@
+//! Ethernet-Primary / WiFi-Fallback Failover Example
+//!
+//! This example shows how to drive uplink failover from the driver's link
+//! supervisor. A [`PhyStateMachine`] polls the PHY and reports debounced
+//! transitions through the [`NetLinkStatus`] trait; the implementation here
+//! updates the MAC and embassy-net link state and records which uplink the
+//! application should use. The application task then picks the matching
+//! `embassy_net::Stack` for each new connection.
+//!
+//! # Features Demonstrated
+//!
+//! - [`NetLinkStatus`] implementation fed by [`PhyStateMachine::poll_notify`]
+//! - Flap damping so a bouncing cable does not ping-pong between uplinks
+//! - embassy-net "stack switching": one `Stack` per uplink, chosen per
+//!   connection attempt
+//!
+//! # WiFi Side
+//!
+//! The WiFi stack is created by the radio driver (esp-radio), which needs its
+//! own blobs and features and is not a dependency of these examples. Build it
+//! as in the esp-radio embassy examples and pass its `Stack` where
+//! [`wifi_stack`] returns `None`. Without it this example still runs: during
+//! an Ethernet outage the application simply waits for the cable to return.
+//!
+//! # Hardware
+//!
+//! Tested on WT32-ETH01 board:
+//! - ESP32 (WT32-S1 module)
+//! - LAN8720A PHY at MDIO address 1
+//! - External 50 MHz oscillator enabled via GPIO16
+//!
+//! # Building
+//!
+//! ```bash
+//! cargo xtask run ex-failover
+//! ```
+//!
+//! # Testing
+//!
+//! Unplug the cable: after the down debounce the log shows
+//! `failover: ethernet -> wifi`, and plugging it back in shows the failback
+//! once the link has been up for [`LINK_UP_DEBOUNCE`] samples.
+//!
+//! # Architecture
+//!
+//! ```text
+//!   ┌──────────────┬──────────────────────────┬──────────────────────┐
+//!   │ net_task     │ link_task                │ app_task             │
+//!   │ (eth runner) │ PhyStateMachine          │ active_stack()       │
+//!   │              │   └─ NetLinkStatus ──────┼─▶ UPLINK             │
+//!   └──────┬───────┴──────────────────────────┴───────┬──────┬───────┘
+//!          │                                          │      │
+//!   ┌──────┴──────────────────┐           ┌───────────┴─┐  ┌─┴───────────┐
+//!   │ embassy_net::Stack (eth)│◀──────────│ Ethernet    │  │ WiFi stack  │
+//!   └──────┬──────────────────┘           └─────────────┘  │ (esp-radio) │
+//!   ┌──────┴──────────────────┐                            └─────────────┘
+//!   │ EmbassyEmac + Emac      │
+//!   └─────────────────────────┘
+//! ```
+
+#![no_std]
+#![no_main]
+
+// =============================================================================
+// Imports
+// =============================================================================
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use embassy_executor::Spawner;
+use embassy_net::{Config, DhcpConfig, Stack};
+use embassy_net_driver::LinkState;
+use embassy_time::{Duration, Timer};
+use esp_backtrace as _;
+use esp_bootloader_esp_idf::esp_app_desc;
+use esp_hal::{
+    delay::Delay,
+    gpio::{Level, Output, OutputConfig},
+    interrupt::Priority,
+    rng::Rng,
+    timer::timg::TimerGroup,
+};
+use log::{info, warn};
+
+use ph_esp32_mac::esp_hal::{EmacBuilder, EmacExt, EmacPhyBundle, Wt32Eth01};
+use ph_esp32_mac::hal::MdioController;
+use ph_esp32_mac::{
+    emac_isr, Emac, EmbassyEmac, FlapDamping, LinkStatus, NetLinkStatus, PhyStateMachine,
+};
+
+// =============================================================================
+// Configuration
+// =============================================================================
+
+/// MAC address for this device (locally administered).
+const MAC_ADDRESS: [u8; 6] = [0x02, 0x00, 0x00, 0x12, 0x34, 0x59];
+
+/// PHY link polling interval.
+const LINK_POLL_MS: u64 = 250;
+
+/// Consecutive link-up samples before failing back to Ethernet (1 s).
+const LINK_UP_DEBOUNCE: u8 = 4;
+
+/// Consecutive link-down samples before failing over to WiFi.
+const LINK_DOWN_DEBOUNCE: u8 = 2;
+
+/// Flap damping: after 3 flaps within 10 s, stay on WiFi for 30 s.
+const LINK_FLAP_DAMPING: FlapDamping = FlapDamping::new(3, 40, 120);
+
+/// Interval at which the application reports its uplink.
+const APP_INTERVAL_SECS: u64 = 5;
+
+// =============================================================================
+// Static Allocations
+// =============================================================================
+
+// EMAC hardware instance, driver state, and embassy-net resources.
+ph_esp32_mac::embassy_net_statics!(EMAC, EMAC_STATE, NET_RESOURCES, 10, 10, 1600, 4);
+
+/// True while Ethernet is the active uplink.
+static ETHERNET_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+// =============================================================================
+// Interrupt Handler
+// =============================================================================
+
+emac_isr!(EMAC_IRQ, Priority::Priority1, {
+    EMAC_STATE.handle_interrupt();
+});
+
+// =============================================================================
+// Failover Policy
+// =============================================================================
+
+/// Uplink selector driven by the link supervisor.
+///
+/// Ethernet is preferred whenever its link is up; otherwise the application
+/// falls back to WiFi.
+struct Failover {
+    emac: *mut Emac<10, 10, 1600>,
+}
+
+impl NetLinkStatus for Failover {
+    fn link_up(&mut self, status: LinkStatus) {
+        info!("Ethernet up: {:?} {:?}", status.speed, status.duplex);
+        // SAFETY: EMAC is static and only the link task changes speed/duplex
+        unsafe { (*self.emac).update_link(status.speed, status.duplex) };
+        EMAC_STATE.set_link_state(LinkState::Up);
+        if !ETHERNET_ACTIVE.swap(true, Ordering::AcqRel) {
+            info!("failback: wifi -> ethernet");
+        }
+    }
+
+    fn link_down(&mut self) {
+        EMAC_STATE.set_link_state(LinkState::Down);
+        if ETHERNET_ACTIVE.swap(false, Ordering::AcqRel) {
+            warn!("failover: ethernet -> wifi");
+        }
+    }
+}
+
+/// WiFi stack, if the radio has been brought up.
+///
+/// Replace with the `Stack` created by esp-radio; see the module docs.
+fn wifi_stack() -> Option<Stack<'static>> {
+    None
+}
+
+/// Stack the application should open new connections on.
+fn active_stack(ethernet: Stack<'static>) -> Option<Stack<'static>> {
+    if ETHERNET_ACTIVE.load(Ordering::Acquire) {
+        Some(ethernet)
+    } else {
+        wifi_stack()
+    }
+}
+
+// =============================================================================
+// Embassy Tasks
+// =============================================================================
+
+/// Ethernet stack runner task.
+#[embassy_executor::task]
+async fn net_task(mut runner: embassy_net::Runner<'static, EmbassyEmac<'static, 10, 10, 1600>>) -> ! {
+    runner.run().await
+}
+
+/// Link supervisor task.
+///
+/// Only debounced, damped transitions reach [`Failover`], so the uplink does
+/// not change on every glitch.
+#[embassy_executor::task]
+async fn link_task(emac_ptr: *mut Emac<10, 10, 1600>) -> ! {
+    let mut mdio = MdioController::new(Delay::new());
+    let phy = Wt32Eth01::lan8720a();
+    let mut supervisor = PhyStateMachine::new()
+        .with_up_debounce(LINK_UP_DEBOUNCE)
+        .with_down_debounce(LINK_DOWN_DEBOUNCE)
+        .with_flap_damping(LINK_FLAP_DAMPING);
+    let mut failover = Failover { emac: emac_ptr };
+
+    loop {
+        supervisor.poll_notify(&phy, &mut mdio, &mut failover);
+        Timer::after(Duration::from_millis(LINK_POLL_MS)).await;
+    }
+}
+
+/// Application task.
+///
+/// Picks the active stack for each unit of work. A real application would
+/// open its sockets on the returned stack and reconnect when it changes.
+#[embassy_executor::task]
+async fn app_task(ethernet: Stack<'static>) -> ! {
+    loop {
+        match active_stack(ethernet) {
+            Some(stack) => match stack.config_v4() {
+                Some(config) => info!(
+                    "uplink {}: {}",
+                    if ETHERNET_ACTIVE.load(Ordering::Relaxed) {
+                        "ethernet"
+                    } else {
+                        "wifi"
+                    },
+                    config.address
+                ),
+                None => info!("uplink up, waiting for an address"),
+            },
+            None => warn!("no uplink available"),
+        }
+        Timer::after(Duration::from_secs(APP_INTERVAL_SECS)).await;
+    }
+}
+
+// =============================================================================
+// Main Entry Point
+// =============================================================================
+
+esp_app_desc!();
+
+#[esp_rtos::main]
+async fn main(spawner: Spawner) -> ! {
+    esp_println::logger::init_logger_from_env();
+    info!("Failover example starting...");
+
+    let peripherals = esp_hal::init(esp_hal::Config::default());
+    let timg0 = TimerGroup::new(peripherals.TIMG0);
+    esp_rtos::start(timg0.timer0);
+
+    // Enable external 50 MHz oscillator via GPIO16
+    let mut clk_en = Output::new(peripherals.GPIO16, Level::High, OutputConfig::default());
+    clk_en.set_high();
+    let mut delay = Delay::new();
+    delay.delay_millis(Wt32Eth01::OSC_STARTUP_MS);
+
+    // EMAC and PHY bring-up; the link supervisor reports the first link-up
+    let emac_ptr = EMAC.init(Emac::new()) as *mut Emac<10, 10, 1600>;
+    // SAFETY: emac_ptr points to static storage valid for program lifetime
+    let emac = unsafe { &mut *emac_ptr };
+    EmacBuilder::wt32_eth01_with_mac(emac, MAC_ADDRESS)
+        .init(&mut delay)
+        .expect("EMAC init failed");
+    EmacPhyBundle::wt32_eth01_lan8720a(emac, Delay::new())
+        .init_phy()
+        .expect("PHY init failed");
+    EMAC_STATE.set_link_state(LinkState::Down);
+    emac.start().expect("EMAC start failed");
+    emac.bind_interrupt(EMAC_IRQ);
+
+    // Ethernet stack with DHCP
+    let driver = ph_esp32_mac::embassy_net_driver!(emac_ptr, &EMAC_STATE);
+    let rng = Rng::new();
+    let seed = ((rng.random() as u64) << 32) | (rng.random() as u64);
+    let config = Config::dhcpv4(DhcpConfig::default());
+    let (stack, runner) = ph_esp32_mac::embassy_net_stack!(driver, NET_RESOURCES, config, seed);
+
+    spawner.spawn(net_task(runner)).unwrap();
+    spawner.spawn(link_task(emac_ptr)).unwrap();
+    spawner.spawn(app_task(stack)).unwrap();
+
+    loop {
+        Timer::after(Duration::from_secs(60)).await;
+    }
+}
@@ -0,0 +1,658 @@
+//! Firmware Update (OTA) Example
+//!
+//! This example receives a firmware image over HTTP (smoltcp TCP) and writes
+//! it into the next OTA app partition using esp-bootloader-esp-idf's
+//! `OtaUpdater`, then marks the partition bootable and restarts.
+//!
+//! Flash writes block the CPU for tens of milliseconds per sector while the
+//! EMAC keeps receiving. The example therefore paces itself on RX descriptor
+//! occupancy: a full sector is only written once the RX ring has drained, and
+//! the TCP socket is not read while a sector is pending, so the receive window
+//! closes and the sender backs off instead of frames being dropped.
+//!
+//! # Features Demonstrated
+//!
+//! - Sustained TCP RX through smoltcp with DHCP IPv4 configuration
+//! - Minimal HTTP `POST` handling (`Content-Length` bodies only)
+//! - Sector-buffered streaming into an OTA partition
+//! - Descriptor occupancy-based pacing around blocking flash writes
+//!
+//! # Hardware
+//!
+//! Tested on WT32-ETH01 board with:
+//! - ESP32 (WT32-S1 module)
+//! - LAN8720A PHY at address 1
+//! - External 50 MHz oscillator (enabled via GPIO16)
+//!
+//! The flash must carry a partition table with `ota_0`/`ota_1` app partitions
+//! and an `otadata` partition (for example ESP-IDF's "two OTA" table).
+//!
+//! # Building
+//!
+//! ```bash
+//! cargo xtask run ex-ota
+//! ```
+//!
+//! # Testing
+//!
+//! 1. Flash the example and note the DHCP address in the log.
+//! 2. Produce an app image, e.g. `espflash save-image --chip esp32 <elf> fw.bin`.
+//! 3. Upload it: `curl --data-binary @fw.bin http://<assigned-ip>:8080/ota`.
+//! 4. The board answers `200 OK` with the byte count and throughput, then
+//!    restarts into the new image.
+
+#![no_std]
+#![no_main]
+
+use embedded_storage::{ReadStorage, Storage};
+use esp_backtrace as _;
+use esp_bootloader_esp_idf::{
+    esp_app_desc,
+    ota::OtaImageState,
+    ota_updater::OtaUpdater,
+    partitions::{FlashRegion, PARTITION_TABLE_MAX_LEN},
+};
+use esp_hal::{
+    delay::Delay,
+    gpio::{Level, Output, OutputConfig},
+    main,
+    rng::Rng,
+    time::Instant,
+};
+use esp_storage::FlashStorage;
+use log::{info, warn};
+
+use smoltcp::{
+    iface::{Config, Interface, SocketSet},
+    socket::dhcpv4,
+    socket::tcp::{Socket as TcpSocket, SocketBuffer as TcpSocketBuffer},
+    time::Instant as SmolInstant,
+    wire::{EthernetAddress, IpCidr},
+};
+
+use ph_esp32_mac::boards::wt32_eth01::Wt32Eth01;
+use ph_esp32_mac::hal::MdioController;
+use ph_esp32_mac::{Duplex, Emac, PhyDriver, Speed};
+
+// =============================================================================
+// Configuration
+// =============================================================================
+
+/// HTTP port accepting the firmware upload.
+const OTA_PORT: u16 = 8080;
+
+/// MAC address (locally administered).
+const MAC_ADDRESS: [u8; 6] = [0x02, 0x00, 0x00, 0x12, 0x34, 0x58];
+
+/// Flash sector size; the writer buffers one full sector per flash write.
+const SECTOR_SIZE: usize = 4096;
+
+/// Free RX descriptors required before starting a blocking flash write.
+const PACE_MIN_FREE_DESC: usize = 8;
+
+/// Write anyway after deferring this many polls, so constant background
+/// traffic cannot stall the update.
+const PACE_MAX_DEFER_POLLS: u32 = 200;
+
+/// Maximum size of the HTTP request head (request line + headers).
+const MAX_HEAD_LEN: usize = 512;
+
+/// First byte of every ESP32 app image.
+const ESP_IMAGE_MAGIC: u8 = 0xE9;
+
+/// Link poll interval while waiting for link-up.
+const LINK_POLL_MS: u32 = 100;
+
+/// Progress log interval in bytes.
+const PROGRESS_STEP: usize = 64 * 1024;
+
+// =============================================================================
+// Static EMAC Instance
+// =============================================================================
+
+ph_esp32_mac::emac_static_sync!(EMAC, 10, 10, 1600);
+
+// =============================================================================
+// OTA Helper Layer
+// =============================================================================
+
+/// Why an upload was rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OtaError {
+    /// Request head too long or not an HTTP `POST`
+    BadRequest,
+    /// No usable `Content-Length` header
+    LengthRequired,
+    /// Image does not fit the target partition
+    TooLarge,
+    /// Image does not start with the ESP app image magic byte
+    NotAnImage,
+    /// Connection closed before the whole body arrived
+    Truncated,
+    /// Flash write failed
+    Flash,
+}
+
+impl OtaError {
+    fn status_line(self) -> &'static str {
+        match self {
+            OtaError::BadRequest | OtaError::NotAnImage => "400 Bad Request",
+            OtaError::LengthRequired => "411 Length Required",
+            OtaError::TooLarge => "413 Payload Too Large",
+            OtaError::Truncated | OtaError::Flash => "500 Internal Server Error",
+        }
+    }
+}
+
+/// Collects the HTTP request head and extracts `Content-Length`.
+struct RequestHead {
+    buf: [u8; MAX_HEAD_LEN],
+    len: usize,
+}
+
+impl RequestHead {
+    const fn new() -> Self {
+        Self {
+            buf: [0; MAX_HEAD_LEN],
+            len: 0,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.len = 0;
+    }
+
+    /// Feed bytes from the socket. Returns the number of bytes consumed and,
+    /// once the head is complete, the body length.
+    fn feed(&mut self, data: &[u8]) -> (usize, Option<Result<usize, OtaError>>) {
+        for (i, &byte) in data.iter().enumerate() {
+            if self.len == MAX_HEAD_LEN {
+                return (i, Some(Err(OtaError::BadRequest)));
+            }
+            self.buf[self.len] = byte;
+            self.len += 1;
+            if self.buf[..self.len].ends_with(b"\r\n\r\n") {
+                return (i + 1, Some(self.content_length()));
+            }
+        }
+        (data.len(), None)
+    }
+
+    fn content_length(&self) -> Result<usize, OtaError> {
+        let head = &self.buf[..self.len];
+        if !head.starts_with(b"POST ") {
+            return Err(OtaError::BadRequest);
+        }
+        for line in head.split(|&b| b == b'\n') {
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+            let Some(colon) = line.iter().position(|&b| b == b':') else {
+                continue;
+            };
+            let (name, value) = line.split_at(colon);
+            if !name.eq_ignore_ascii_case(b"content-length") {
+                continue;
+            }
+            let value = core::str::from_utf8(&value[1..]).map_err(|_| OtaError::LengthRequired)?;
+            return value.trim().parse().map_err(|_| OtaError::LengthRequired);
+        }
+        Err(OtaError::LengthRequired)
+    }
+}
+
+/// Streams an image into an OTA partition one flash sector at a time.
+///
+/// Data is buffered until a full sector is ready; the sector is only written
+/// when the caller decides it is a good moment (see [`RxPacer`]).
+struct OtaWriter<'a> {
+    region: FlashRegion<'a, FlashStorage>,
+    sector: [u8; SECTOR_SIZE],
+    fill: usize,
+    written: usize,
+    expected: usize,
+}
+
+impl<'a> OtaWriter<'a> {
+    fn new(region: FlashRegion<'a, FlashStorage>) -> Self {
+        Self {
+            region,
+            sector: [0xFF; SECTOR_SIZE],
+            fill: 0,
+            written: 0,
+            expected: 0,
+        }
+    }
+
+    /// Start a new image of `len` bytes.
+    fn begin(&mut self, len: usize) -> Result<(), OtaError> {
+        if len == 0 {
+            return Err(OtaError::NotAnImage);
+        }
+        if len > self.region.capacity() {
+            return Err(OtaError::TooLarge);
+        }
+        self.fill = 0;
+        self.written = 0;
+        self.expected = len;
+        Ok(())
+    }
+
+    /// Bytes accepted so far (buffered or written).
+    fn received(&self) -> usize {
+        self.written + self.fill
+    }
+
+    /// Bytes still expected from the sender.
+    fn remaining(&self) -> usize {
+        self.expected - self.received()
+    }
+
+    /// True once the sector buffer is full or the image is complete.
+    fn sector_ready(&self) -> bool {
+        self.fill == SECTOR_SIZE || (self.fill > 0 && self.remaining() == 0)
+    }
+
+    /// Buffer image bytes; returns how many were taken.
+    fn push(&mut self, data: &[u8]) -> Result<usize, OtaError> {
+        if self.received() == 0 && data.first().is_some_and(|&b| b != ESP_IMAGE_MAGIC) {
+            return Err(OtaError::NotAnImage);
+        }
+        let take = data
+            .len()
+            .min(SECTOR_SIZE - self.fill)
+            .min(self.remaining());
+        self.sector[self.fill..self.fill + take].copy_from_slice(&data[..take]);
+        self.fill += take;
+        Ok(take)
+    }
+
+    /// Write the buffered sector to flash (blocks the CPU).
+    fn write_sector(&mut self) -> Result<(), OtaError> {
+        self.region
+            .write(self.written as u32, &self.sector[..self.fill])
+            .map_err(|_| OtaError::Flash)?;
+        self.written += self.fill;
+        self.fill = 0;
+        self.sector.fill(0xFF);
+        Ok(())
+    }
+
+    /// True once every byte has been written to flash.
+    fn is_complete(&self) -> bool {
+        self.expected > 0 && self.written == self.expected
+    }
+}
+
+/// Decides when a blocking flash write may run, based on RX ring occupancy.
+#[derive(Default)]
+struct RxPacer {
+    deferred_polls: u32,
+    deferrals: u32,
+    forced_writes: u32,
+}
+
+impl RxPacer {
+    /// Returns `true` if a blocking write may start now.
+    fn may_block(&mut self, rx_free: usize) -> bool {
+        if rx_free >= PACE_MIN_FREE_DESC {
+            self.deferred_polls = 0;
+            return true;
+        }
+        if self.deferred_polls == 0 {
+            self.deferrals += 1;
+        }
+        self.deferred_polls += 1;
+        if self.deferred_polls >= PACE_MAX_DEFER_POLLS {
+            self.deferred_polls = 0;
+            self.forced_writes += 1;
+            return true;
+        }
+        false
+    }
+}
+
+/// Upload progress for the current connection.
+#[derive(Clone, Copy)]
+enum Upload {
+    /// Reading the request head
+    Head,
+    /// Streaming the body into flash
+    Body { started: Instant },
+    /// Image written; reply sent, waiting for the client to close
+    Done,
+    /// Request rejected; reply sent, waiting for the client to close
+    Failed,
+}
+
+// =============================================================================
+// Main Entry Point
+// =============================================================================
+
+esp_app_desc!();
+
+#[main]
+fn main() -> ! {
+    let peripherals = esp_hal::init(esp_hal::Config::default());
+
+    // Initialize logging
+    esp_println::logger::init_logger_from_env();
+    info!("OTA example starting...");
+
+    let mut delay = Delay::new();
+    let mut mdio = MdioController::new(Delay::new());
+
+    // ======================================================================
+    // OTA Partition Setup
+    // ======================================================================
+
+    let mut flash = FlashStorage::new(peripherals.FLASH);
+    let mut pt_buf = [0u8; PARTITION_TABLE_MAX_LEN];
+    let mut ota = OtaUpdater::new(&mut flash, &mut pt_buf).expect("no OTA partition table");
+    let (region, target) = ota.next_partition().expect("no free OTA partition");
+    info!(
+        "Update target: {:?} ({} KiB)",
+        target,
+        region.capacity() / 1024
+    );
+    let mut writer = OtaWriter::new(region);
+
+    // ======================================================================
+    // EMAC / PHY Bring-Up
+    // ======================================================================
+
+    // Enable external oscillator (WT32-ETH01 specific)
+    let mut clk_en = Output::new(peripherals.GPIO16, Level::Low, OutputConfig::default());
+    clk_en.set_high();
+    delay.delay_millis(Wt32Eth01::OSC_STARTUP_MS);
+
+    // Configure EMAC (board defaults + MAC address)
+    let config = Wt32Eth01::emac_config_with_mac(MAC_ADDRESS);
+
+    info!("Initializing EMAC...");
+    EMAC.with(|emac| emac.init(config, &mut delay))
+        .expect("EMAC init failed");
+
+    // Initialize PHY
+    info!("Initializing PHY...");
+    let mut phy = Wt32Eth01::lan8720a();
+    phy.init(&mut mdio).expect("PHY init failed");
+
+    // Wait for link
+    info!("Waiting for link...");
+    loop {
+        delay.delay_millis(LINK_POLL_MS);
+        if let Ok(Some(status)) = phy.poll_link(&mut mdio) {
+            info!(
+                "Link UP: {} {}",
+                if matches!(status.speed, Speed::Mbps100) {
+                    "100Mbps"
+                } else {
+                    "10Mbps"
+                },
+                if matches!(status.duplex, Duplex::Full) { "FD" } else { "HD" }
+            );
+            EMAC.with(|emac| emac.update_link(status.speed, status.duplex));
+            break;
+        }
+    }
+
+    // Start EMAC
+    EMAC.with(|emac| emac.start()).expect("EMAC start failed");
+    EMAC.with(|emac| emac.set_broadcast_enabled(true));
+    info!(
+        "EMAC started (memory: {} bytes)",
+        Emac::<10, 10, 1600>::memory_usage()
+    );
+
+    // ======================================================================
+    // smoltcp Interface Setup
+    // ======================================================================
+
+    let hw_addr = EMAC.with(|emac| EthernetAddress(*emac.mac_address()));
+    let mut smol_config = Config::new(hw_addr.into());
+    let rng = Rng::new();
+    smol_config.random_seed = ((rng.random() as u64) << 32) | (rng.random() as u64);
+
+    let mut iface = EMAC.with(|emac| Interface::new(smol_config, emac, SmolInstant::from_millis(0)));
+    iface.set_any_ip(true);
+
+    let mut socket_storage = [smoltcp::iface::SocketStorage::EMPTY; 2];
+    let mut sockets = SocketSet::new(&mut socket_storage[..]);
+
+    // The RX buffer bounds the TCP window: while a sector write is pending
+    // the socket is not read, the window closes, and the sender pauses
+    let mut tcp_rx_buffer = [0u8; 8192];
+    let mut tcp_tx_buffer = [0u8; 256];
+    let tcp_handle = sockets.add(TcpSocket::new(
+        TcpSocketBuffer::new(&mut tcp_rx_buffer[..]),
+        TcpSocketBuffer::new(&mut tcp_tx_buffer[..]),
+    ));
+    let dhcp_handle = sockets.add(dhcpv4::Socket::new());
+
+    sockets
+        .get_mut::<TcpSocket>(tcp_handle)
+        .listen(OTA_PORT)
+        .unwrap();
+
+    // ======================================================================
+    // Main Network Loop
+    // ======================================================================
+
+    let mut head = RequestHead::new();
+    let mut pacer = RxPacer::default();
+    let mut upload = Upload::Head;
+    let mut next_progress = PROGRESS_STEP;
+    let mut chunk = [0u8; 1024];
+
+    loop {
+        let now = Instant::now();
+        let smol_now = SmolInstant::from_millis(now.duration_since_epoch().as_millis() as i64);
+        EMAC.with(|emac| {
+            let _activity = iface.poll(smol_now, emac, &mut sockets);
+        });
+
+        if let Some(event) = sockets.get_mut::<dhcpv4::Socket>(dhcp_handle).poll() {
+            match event {
+                dhcpv4::Event::Configured(config) => {
+                    iface.update_ip_addrs(|addrs| {
+                        addrs.clear();
+                        addrs.push(IpCidr::Ipv4(config.address)).unwrap();
+                    });
+                    iface.set_any_ip(false);
+                    if let Some(router) = config.router {
+                        iface.routes_mut().add_default_ipv4_route(router).ok();
+                    } else {
+                        iface.routes_mut().remove_default_ipv4_route();
+                    }
+                    info!(
+                        "Upload with: curl --data-binary @fw.bin http://{}:{}/ota",
+                        config.address.address(),
+                        OTA_PORT
+                    );
+                }
+                dhcpv4::Event::Deconfigured => {
+                    iface.update_ip_addrs(|addrs| addrs.clear());
+                    iface.routes_mut().remove_default_ipv4_route();
+                    iface.set_any_ip(true);
+                    warn!("DHCP deconfigured");
+                }
+            }
+        }
+
+        // Flush a full sector only when the RX ring has room to absorb the
+        // frames that arrive while the CPU is stuck in the flash driver
+        if let Upload::Body { started } = upload {
+            if writer.sector_ready() {
+                let rx_free = EMAC.with(|emac| emac.rx_descriptors_free());
+                if pacer.may_block(rx_free) {
+                    let socket = sockets.get_mut::<TcpSocket>(tcp_handle);
+                    upload = match writer.write_sector() {
+                        Ok(()) if writer.is_complete() => finish_upload(socket, &writer, &pacer, started),
+                        Ok(()) => upload,
+                        Err(e) => fail_upload(socket, e),
+                    };
+                    if writer.received() >= next_progress {
+                        info!("Received {} / {} bytes", writer.received(), writer.expected);
+                        next_progress += PROGRESS_STEP;
+                    }
+                }
+            }
+        }
+
+        let socket = sockets.get_mut::<TcpSocket>(tcp_handle);
+
+        if !socket.is_active() && !socket.is_listening() {
+            if matches!(upload, Upload::Done) {
+                break;
+            }
+            if matches!(upload, Upload::Body { .. }) {
+                warn!("Upload aborted: {:?}", OtaError::Truncated);
+            }
+            socket.abort();
+            socket.listen(OTA_PORT).unwrap();
+            head.reset();
+            upload = Upload::Head;
+            next_progress = PROGRESS_STEP;
+            continue;
+        }
+
+        // Peer closed its side before sending the whole body
+        if matches!(upload, Upload::Body { .. })
+            && !socket.may_recv()
+            && !socket.can_recv()
+            && !writer.sector_ready()
+        {
+            upload = fail_upload(socket, OtaError::Truncated);
+            continue;
+        }
+
+        // Leave data in the socket while a sector is waiting to be written
+        if !socket.can_recv() || writer.sector_ready() {
+            continue;
+        }
+
+        upload = match upload {
+            Upload::Head => {
+                let Ok(len) = socket.recv_slice(&mut chunk) else {
+                    continue;
+                };
+                match head.feed(&chunk[..len]) {
+                    (_, None) => Upload::Head,
+                    (used, Some(Ok(body_len))) => {
+                        info!("Receiving {} byte image", body_len);
+                        match writer
+                            .begin(body_len)
+                            .and_then(|()| writer.push(&chunk[used..len]).map(|_| ()))
+                        {
+                            Ok(()) => Upload::Body {
+                                started: Instant::now(),
+                            },
+                            Err(e) => fail_upload(socket, e),
+                        }
+                    }
+                    (_, Some(Err(e))) => fail_upload(socket, e),
+                }
+            }
+            Upload::Body { started } => {
+                // Only take what fits in the sector buffer, the rest stays
+                // queued in the socket
+                let room = (SECTOR_SIZE - writer.fill)
+                    .min(writer.remaining())
+                    .min(chunk.len());
+                match socket.recv_slice(&mut chunk[..room]) {
+                    Ok(len) => match writer.push(&chunk[..len]) {
+                        Ok(_) => Upload::Body { started },
+                        Err(e) => fail_upload(socket, e),
+                    },
+                    Err(_) => Upload::Body { started },
+                }
+            }
+            // Discard anything sent after the reply
+            done_or_failed => {
+                let _ = socket.recv_slice(&mut chunk);
+                done_or_failed
+            }
+        };
+    }
+
+    // ======================================================================
+    // Activate and Restart
+    // ======================================================================
+
+    drop(writer);
+    ota.activate_next_partition()
+        .expect("failed to activate OTA partition");
+    ota.set_current_ota_state(OtaImageState::New)
+        .expect("failed to update otadata");
+    info!("New image activated, restarting...");
+    delay.delay_millis(100);
+    esp_hal::system::software_reset()
+}
+
+/// Send the success reply and log throughput and pacing statistics.
+fn finish_upload(
+    socket: &mut TcpSocket<'_>,
+    writer: &OtaWriter<'_>,
+    pacer: &RxPacer,
+    started: Instant,
+) -> Upload {
+    let elapsed_ms = (Instant::now() - started).as_millis().max(1);
+    let kib_per_s = writer.written as u64 * 1000 / 1024 / elapsed_ms;
+    info!(
+        "Image written: {} bytes in {} ms ({} KiB/s), {} write deferrals, {} forced writes",
+        writer.written, elapsed_ms, kib_per_s, pacer.deferrals, pacer.forced_writes
+    );
+
+    let mut body = [0u8; 64];
+    let body_len = format_body(&mut body, writer.written, kib_per_s);
+    reply(socket, "200 OK", &body[..body_len]);
+    Upload::Done
+}
+
+/// Send an error reply and log the reason.
+fn fail_upload(socket: &mut TcpSocket<'_>, error: OtaError) -> Upload {
+    warn!("Upload rejected: {:?}", error);
+    reply(socket, error.status_line(), b"upload rejected\n");
+    Upload::Failed
+}
+
+/// Queue a minimal HTTP/1.0 response and close the connection.
+fn reply(socket: &mut TcpSocket<'_>, status: &str, body: &[u8]) {
+    for part in [b"HTTP/1.0 ".as_slice(), status.as_bytes(), b"\r\n\r\n", body] {
+        if socket.send_slice(part).is_err() {
+            break;
+        }
+    }
+    socket.close();
+}
+
+/// Format `"<bytes> bytes, <rate> KiB/s\n"` without `alloc`.
+fn format_body(out: &mut [u8; 64], bytes: usize, kib_per_s: u64) -> usize {
+    let mut len = 0;
+    len += write_decimal(&mut out[len..], bytes as u64);
+    len += copy(&mut out[len..], b" bytes, ");
+    len += write_decimal(&mut out[len..], kib_per_s);
+    len += copy(&mut out[len..], b" KiB/s\n");
+    len
+}
+
+fn write_decimal(out: &mut [u8], mut value: u64) -> usize {
+    let mut digits = [0u8; 20];
+    let mut n = 0;
+    loop {
+        digits[n] = b'0' + (value % 10) as u8;
+        n += 1;
+        value /= 10;
+        if value == 0 {
+            break;
+        }
+    }
+    for (dst, src) in out.iter_mut().zip(digits[..n].iter().rev()) {
+        *dst = *src;
+    }
+    n
+}
+
+fn copy(out: &mut [u8], src: &[u8]) -> usize {
+    out[..src.len()].copy_from_slice(src);
+    src.len()
+}
@@ -0,0 +1,265 @@
+//! UDP Multicast Receiver Example
+//!
+//! This example joins several IPv4 multicast groups through the MAC hash
+//! filter and counts the UDP datagrams that arrive for each one. It runs on
+//! the raw frame API, so the only thing deciding which multicast frames reach
+//! software is the EMAC's filtering: a realistic end-to-end check of
+//! [`Emac::join_ipv4_multicast`] beyond register-level unit tests.
+//!
+//! # Features Demonstrated
+//!
+//! - [`Emac::join_ipv4_multicast`] with hash multicast filtering (pass-all
+//!   multicast off)
+//! - IGMPv2 membership reports so IGMP-snooping switches forward the groups
+//! - Per-group counters, plus a "leaked" counter for multicast frames that
+//!   share a hash bit with a joined group but were not asked for
+//!
+//! # Hardware
+//!
+//! Tested on WT32-ETH01 board with:
+//! - ESP32 (WT32-S1 module)
+//! - LAN8720A PHY at address 1
+//! - External 50 MHz oscillator (enabled via GPIO16)
+//!
+//! # Building
+//!
+//! ```bash
+//! cargo xtask run ex-multicast
+//! ```
+//!
+//! # Testing
+//!
+//! From a host on the same segment, send to a joined group and to one that
+//! was not joined:
+//!
+//! ```bash
+//! echo hello | socat - UDP4-DATAGRAM:239.1.2.3:5000,ip-multicast-ttl=1
+//! echo hello | socat - UDP4-DATAGRAM:239.1.2.99:5000,ip-multicast-ttl=1
+//! ```
+//!
+//! The first increments the `239.1.2.3` counter. The second should not show
+//! up at all, unless its hash bit collides with a joined group, in which case
+//! it is counted as leaked.
+
+#![no_std]
+#![no_main]
+
+use esp_backtrace as _;
+use esp_bootloader_esp_idf::esp_app_desc;
+use esp_hal::{
+    delay::Delay,
+    gpio::{Level, Output, OutputConfig},
+    main, time,
+};
+use log::{error, info, warn};
+
+use ph_esp32_mac::esp_hal::{EmacBuilder, EmacPhyBundle, Wt32Eth01};
+use ph_esp32_mac::{Emac, ipv4_multicast_mac};
+
+// =============================================================================
+// Configuration
+// =============================================================================
+
+/// MAC address for this device (locally administered).
+const MAC_ADDRESS: [u8; 6] = [0x02, 0x00, 0x00, 0x12, 0x34, 0x5A];
+
+/// Source address for IGMP reports; adjust for your network.
+const DEVICE_IP: [u8; 4] = [192, 168, 1, 50];
+
+/// Multicast groups to join.
+const GROUPS: [[u8; 4]; 3] = [[239, 1, 2, 3], [239, 1, 2, 4], [224, 0, 0, 251]];
+
+/// Link-up timeout (milliseconds).
+const LINK_TIMEOUT_MS: u32 = 10_000;
+
+/// Link poll interval (milliseconds).
+const LINK_POLL_MS: u32 = 200;
+
+/// Interval between IGMP membership reports (below the 125 s query interval).
+const REPORT_INTERVAL_SECS: u64 = 60;
+
+/// Interval between counter printouts.
+const STATS_INTERVAL_SECS: u64 = 10;
+
+/// Frames handled per main loop iteration, so a flood cannot delay reports.
+const RX_BUDGET: usize = 16;
+
+// =============================================================================
+// Static EMAC Instance
+// =============================================================================
+
+ph_esp32_mac::emac_static_sync!(EMAC, 10, 10, 1600);
+
+// =============================================================================
+// Frame Helpers
+// =============================================================================
+
+const ETHERTYPE_IPV4: u16 = 0x0800;
+const IP_PROTO_IGMP: u8 = 2;
+const IP_PROTO_UDP: u8 = 17;
+
+/// Ethernet header + IPv4 header with Router Alert option + IGMPv2 message.
+const IGMP_REPORT_LEN: usize = 14 + 24 + 8;
+
+/// RFC 1071 Internet checksum
+fn internet_checksum(data: &[u8]) -> u16 {
+    let mut sum = 0u32;
+    for chunk in data.chunks(2) {
+        let word = u16::from_be_bytes([chunk[0], *chunk.get(1).unwrap_or(&0)]);
+        sum += word as u32;
+    }
+    while sum > 0xFFFF {
+        sum = (sum & 0xFFFF) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+/// Build an IGMPv2 membership report for `group`
+fn igmp_report(group: [u8; 4]) -> [u8; IGMP_REPORT_LEN] {
+    let mut f = [0u8; IGMP_REPORT_LEN];
+    let dst_mac = ipv4_multicast_mac(group).expect("group is multicast");
+
+    // Ethernet
+    f[0..6].copy_from_slice(&dst_mac);
+    f[6..12].copy_from_slice(&MAC_ADDRESS);
+    f[12..14].copy_from_slice(&ETHERTYPE_IPV4.to_be_bytes());
+
+    // IPv4, IHL 6 for the Router Alert option (RFC 2236)
+    let ip = &mut f[14..38];
+    ip[0] = 0x46;
+    ip[2..4].copy_from_slice(&(24u16 + 8).to_be_bytes());
+    ip[8] = 1; // TTL
+    ip[9] = IP_PROTO_IGMP;
+    ip[12..16].copy_from_slice(&DEVICE_IP);
+    ip[16..20].copy_from_slice(&group);
+    ip[20..24].copy_from_slice(&[0x94, 0x04, 0x00, 0x00]);
+    let csum = internet_checksum(ip);
+    ip[10..12].copy_from_slice(&csum.to_be_bytes());
+
+    // IGMPv2 membership report
+    let igmp = &mut f[38..46];
+    igmp[0] = 0x16;
+    igmp[4..8].copy_from_slice(&group);
+    let csum = internet_checksum(igmp);
+    igmp[2..4].copy_from_slice(&csum.to_be_bytes());
+
+    f
+}
+
+/// Destination address of a UDP-over-IPv4 frame
+fn udp_destination(frame: &[u8]) -> Option<[u8; 4]> {
+    if frame.len() < 14 + 20 + 8 {
+        return None;
+    }
+    if u16::from_be_bytes([frame[12], frame[13]]) != ETHERTYPE_IPV4 {
+        return None;
+    }
+    let ip = &frame[14..];
+    if ip[0] >> 4 != 4 || ip[9] != IP_PROTO_UDP {
+        return None;
+    }
+    Some([ip[16], ip[17], ip[18], ip[19]])
+}
+
+/// Send a membership report for every group
+fn send_reports(emac: &mut Emac<10, 10, 1600>) {
+    for group in GROUPS {
+        if let Err(e) = emac.transmit(&igmp_report(group)) {
+            warn!("IGMP report for {:?} failed: {:?}", group, e);
+        }
+    }
+}
+
+// =============================================================================
+// Main Entry Point
+// =============================================================================
+
+esp_app_desc!();
+
+#[main]
+fn main() -> ! {
+    let peripherals = esp_hal::init(esp_hal::Config::default());
+    esp_println::logger::init_logger_from_env();
+    info!("UDP multicast receiver example starting...");
+
+    let mut delay = Delay::new();
+
+    // Enable external 50 MHz oscillator (WT32-ETH01 specific)
+    let mut clk_en = Output::new(peripherals.GPIO16, Level::Low, OutputConfig::default());
+    clk_en.set_high();
+    delay.delay_millis(Wt32Eth01::OSC_STARTUP_MS);
+
+    EMAC.with(|emac| {
+        if let Err(e) = EmacBuilder::wt32_eth01_with_mac(emac, MAC_ADDRESS).init(&mut delay) {
+            error!("EMAC initialization failed: {:?}", e);
+            panic!("Cannot continue without EMAC");
+        }
+    });
+
+    info!("Waiting for Ethernet link...");
+    let link = EMAC.with(|emac| {
+        let mut emac_phy = EmacPhyBundle::wt32_eth01_lan8720a(emac, Delay::new());
+        emac_phy.init_and_wait_link_up(&mut delay, LINK_TIMEOUT_MS, LINK_POLL_MS)
+    });
+    match link {
+        Ok(status) => info!("Link UP: {:?} {:?}", status.speed, status.duplex),
+        Err(e) => {
+            error!("Link wait failed: {:?}", e);
+            panic!("No Ethernet link");
+        }
+    }
+
+    // Join the groups: hash filtering only, no pass-all multicast
+    EMAC.with(|emac| {
+        emac.set_pass_all_multicast(false);
+        for group in GROUPS {
+            let index = emac.join_ipv4_multicast(group).expect("group is multicast");
+            info!("joined {:?} (hash bit {})", group, index);
+        }
+        info!("hash table: {:#018x}", emac.hash_table());
+        emac.start().expect("EMAC start failed");
+        send_reports(emac);
+    });
+
+    let mut per_group = [0u32; GROUPS.len()];
+    let mut leaked = 0u32;
+    let mut last_report = time::Instant::now();
+    let mut last_stats = time::Instant::now();
+
+    loop {
+        EMAC.with(|emac| {
+            let polled = emac.poll_rx(RX_BUDGET, |frame| {
+                let Some(dst) = udp_destination(frame) else {
+                    return;
+                };
+                if let Some(i) = GROUPS.iter().position(|g| *g == dst) {
+                    per_group[i] += 1;
+                } else if dst[0] & 0xF0 == 0xE0 {
+                    leaked += 1;
+                }
+            });
+            if let Err(e) = polled {
+                warn!("RX error: {:?}", e);
+            }
+        });
+
+        let now = time::Instant::now();
+        if (now - last_report).as_secs() >= REPORT_INTERVAL_SECS {
+            EMAC.with(send_reports);
+            last_report = now;
+        }
+        if (now - last_stats).as_secs() >= STATS_INTERVAL_SECS {
+            for (group, count) in GROUPS.iter().zip(per_group) {
+                info!("{:?}: {} datagrams", group, count);
+            }
+            let stats = EMAC.with(|emac| *emac.stats());
+            info!(
+                "leaked: {}, rx multicast frames: {}",
+                leaked, stats.rx_multicast
+            );
+            last_stats = now;
+        }
+
+        delay.delay_micros(10);
+    }
+}
@@ -13,8 +13,45 @@
 //!
 //! ```ignore
 //! cargo xtask run qa-runner
+//! cargo xtask run qa-runner --features structured-results
 //! ```
 //!
+//! The `structured-results` feature adds one `QA-RESULT` JSON line per test
+//! and a final `QA-SUMMARY` line to the serial log for CI rigs to parse.
+//!
+//! # Peer Tests
+//!
+//! Two boards connected back-to-back can exercise paths a single board
+//! cannot (unicast filtering, flow control under load, forced link modes):
+//!
+//! ```ignore
+//! cargo xtask run qa-runner --features peer-responder   # board B
+//! cargo xtask run qa-runner --features peer-sender      # board A
+//! ```
+//!
+//! The responder stops after PHY link-up and serves the peer protocol
+//! forever; the sender runs all groups plus group 10.
+//!
+//! # Soak Mode
+//!
+//! ```ignore
+//! cargo xtask run qa-runner --features soak
+//! QA_SOAK_MINUTES=720 cargo xtask run qa-runner --features soak
+//! ```
+//!
+//! Runs group 11 after the regular groups: hours of looped-back TX/RX
+//! traffic with periodic ring, leak, counter, and stall checks.
+//!
+//! # Group Selection
+//!
+//! ```ignore
+//! QA_GROUPS=fast cargo xtask expect qa-runner
+//! QA_GROUPS=4,9 cargo xtask run qa-runner
+//! ```
+//!
+//! `QA_GROUPS` is read at build time; see [`tests::registry`]. Without it
+//! every group runs.
+//!
 //! # Test Groups
 //!
 //! | Group | ID Range | Category |
@@ -28,6 +65,9 @@
 //! | 7 | IT-7-xxx | State & Interrupts |
 //! | 8 | IT-8-xxx | Advanced Features |
 //! | 9 | IT-9-xxx | Edge Cases |
+//! | 10 | IT-10-xxx | Two-Board Peer (`peer-sender` only) |
+//! | 11 | IT-11-xxx | Soak (`soak` only) |
+//! | 12 | IT-12-xxx | Dual-Core |
 //!
 //! # Hardware
 //!
@@ -44,38 +84,18 @@ use esp_hal::{
     gpio::{Level, Output, OutputConfig},
     main,
 };
-use log::{error, info, warn};
+use log::{error, info};
 
 esp_app_desc!();
 
 // Import PhyDriver trait for method access
 use ph_esp32_mac::PhyDriver;
 
-// Re-export framework types for the run_test! macro
-use tests::{TestContext, TestResult, TestStats, EMAC};
+use tests::registry::{Selection, GROUPS};
+use tests::{TestContext, TestStats, EMAC};
 
 use ph_esp32_mac::boards::wt32_eth01::Wt32Eth01 as Board;
 
-// =============================================================================
-// Run Test Macro (with test ID)
-// =============================================================================
-
-/// Run a single test with ID, log the result, and record statistics
-macro_rules! run_test {
-    ($stats:expr, $id:expr, $name:expr, $test_fn:expr) => {{
-        info!("");
-        info!("▶ [{}] {}", $id, $name);
-        let result = $test_fn;
-        match result {
-            TestResult::Pass => info!("  {} PASS", result.symbol()),
-            TestResult::Fail => error!("  {} FAIL", result.symbol()),
-            TestResult::Skip => warn!("  {} SKIP", result.symbol()),
-        }
-        $stats.record(result);
-        result
-    }};
-}
-
 // =============================================================================
 // Main Entry Point
 // =============================================================================
@@ -103,166 +123,29 @@ fn main() -> ! {
     );
     info!("");
 
-    let mut stats = TestStats::new();
-    let mut ctx = TestContext::new(clk_pin);
-
-    // =========================================================================
-    // Test Group 1: Register Access
-    // =========================================================================
-    info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    info!("  GROUP 1: Register Access");
-    info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    
-    run_test!(stats, "IT-1-001", "EMAC clock enable", tests::group1_register::test_emac_clock_enable());
-    run_test!(stats, "IT-1-002", "DMA registers", tests::group1_register::test_dma_registers_accessible());
-    run_test!(stats, "IT-1-003", "MAC registers", tests::group1_register::test_mac_registers_accessible());
-    run_test!(stats, "IT-1-004", "Extension registers", tests::group1_register::test_extension_registers());
-
-    // =========================================================================
-    // Test Group 2: EMAC Initialization  
-    // =========================================================================
-    info!("");
-    info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    info!("  GROUP 2: EMAC Initialization");
-    info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    
-    let emac_ok = run_test!(stats, "IT-2-001", "EMAC init", tests::group2_init::test_emac_init(&mut ctx)) == TestResult::Pass;
-    
-    if emac_ok {
-        run_test!(stats, "IT-2-002", "RMII pin config", tests::group2_init::test_rmii_pins());
-        run_test!(stats, "IT-2-003", "DMA descriptor chain", tests::group2_init::test_dma_descriptor_chain());
-    } else {
-        warn!("  Skipping dependent tests");
-        stats.record(TestResult::Skip);
-        stats.record(TestResult::Skip);
-    }
+    let selection = Selection::from_env();
+    selection.log();
 
-    // =========================================================================
-    // Test Group 3: PHY Communication
-    // =========================================================================
-    info!("");
-    info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    info!("  GROUP 3: PHY Communication");
-    info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    
-    run_test!(stats, "IT-3-001", "PHY MDIO read", tests::group3_phy::test_phy_mdio_read(&mut ctx));
-    run_test!(stats, "IT-3-002", "PHY init", tests::group3_phy::test_phy_init(&mut ctx));
-    let link_ok = run_test!(stats, "IT-3-003", "PHY link up", tests::group3_phy::test_phy_link_up(&mut ctx, 5000)) == TestResult::Pass;
-
-    // =========================================================================
-    // Test Group 4: EMAC Operations
-    // =========================================================================
-    info!("");
-    info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    info!("  GROUP 4: EMAC Operations");
-    info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    
-    if emac_ok && link_ok {
-        run_test!(stats, "IT-4-001", "EMAC start", tests::group4_emac::test_emac_start());
-        run_test!(stats, "IT-4-002", "Packet TX", tests::group4_emac::test_packet_tx());
-        run_test!(stats, "IT-4-003", "Packet RX (3s)", tests::group4_emac::test_packet_rx(3));
-        run_test!(stats, "IT-4-004", "EMAC stop/start", tests::group4_emac::test_emac_stop_start());
-    } else {
-        warn!("  Skipping - requires EMAC init and link");
-        for _ in 0..4 { stats.record(TestResult::Skip); }
-    }
-
-    // =========================================================================
-    // Test Group 5: Link Status
-    // =========================================================================
-    info!("");
-    info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    info!("  GROUP 5: Link Status");
-    info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    
-    run_test!(stats, "IT-5-001", "Link status query", tests::group5_link::test_link_status_query(&mut ctx));
-
-    // =========================================================================
-    // Test Group 6: smoltcp Integration
-    // =========================================================================
-    info!("");
-    info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    info!("  GROUP 6: smoltcp Integration");
-    info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    
-    if emac_ok && link_ok {
-        run_test!(stats, "IT-6-001", "Interface creation", tests::group6_smoltcp::test_interface_creation());
-        run_test!(stats, "IT-6-002", "Device capabilities", tests::group6_smoltcp::test_device_capabilities());
-        run_test!(stats, "IT-6-003", "Interface poll", tests::group6_smoltcp::test_interface_poll());
-    } else {
-        warn!("  Skipping - requires EMAC init and link");
-        for _ in 0..3 { stats.record(TestResult::Skip); }
-    }
-
-    // =========================================================================
-    // Test Group 7: State & Interrupts
-    // =========================================================================
-    info!("");
-    info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    info!("  GROUP 7: State & Interrupts");
-    info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    
-    if emac_ok && link_ok {
-        run_test!(stats, "IT-7-001", "State transitions", tests::group7_state::test_state_transitions());
-        run_test!(stats, "IT-7-002", "State stop changes", tests::group7_state::test_state_stop_changes());
-        run_test!(stats, "IT-7-003", "TX ready", tests::group7_state::test_tx_ready());
-        run_test!(stats, "IT-7-004", "Can transmit sizes", tests::group7_state::test_can_transmit());
-        run_test!(stats, "IT-7-005", "TX backpressure", tests::group7_state::test_tx_backpressure());
-        run_test!(stats, "IT-7-006", "Peek RX length", tests::group7_state::test_peek_rx_length());
-        run_test!(stats, "IT-7-007", "RX frames waiting", tests::group7_state::test_rx_frames_waiting());
-        run_test!(stats, "IT-7-008", "Interrupt status", tests::group7_state::test_interrupt_status());
-        run_test!(stats, "IT-7-009", "Interrupt clear", tests::group7_state::test_interrupt_clear());
-        run_test!(stats, "IT-7-010", "Handle interrupt", tests::group7_state::test_handle_interrupt());
-        run_test!(stats, "IT-7-011", "Frame sizes TX", tests::group7_state::test_frame_sizes());
-    } else {
-        warn!("  Skipping - requires EMAC init and link");
-        for _ in 0..11 { stats.record(TestResult::Skip); }
-    }
+    let mut stats = TestStats::new();
+    let mut ctx = TestContext::new(clk_pin, peripherals.CPU_CTRL);
 
     // =========================================================================
-    // Test Group 8: Advanced Features
+    // Test Groups (see tests::registry)
     // =========================================================================
-    info!("");
-    info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    info!("  GROUP 8: Advanced Features");
-    info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    
-    if emac_ok && link_ok {
-        run_test!(stats, "IT-8-001", "Promiscuous mode", tests::group8_advanced::test_promiscuous_mode());
-        run_test!(stats, "IT-8-002", "Promiscuous RX", tests::group8_advanced::test_promiscuous_rx(2000));
-        run_test!(stats, "IT-8-003", "PHY capabilities", tests::group8_advanced::test_phy_capabilities(&mut ctx));
-        run_test!(stats, "IT-8-004", "Force link", tests::group8_advanced::test_force_link(&mut ctx));
-        run_test!(stats, "IT-8-005", "Enable TX interrupt", tests::group8_advanced::test_enable_tx_interrupt());
-        run_test!(stats, "IT-8-006", "Enable RX interrupt", tests::group8_advanced::test_enable_rx_interrupt());
-        run_test!(stats, "IT-8-007", "TX interrupt fires", tests::group8_advanced::test_tx_interrupt_fires());
-    } else {
-        warn!("  Skipping - requires EMAC init and link");
-        for _ in 0..7 { stats.record(TestResult::Skip); }
-    }
+    for group in GROUPS {
+        tests::registry::run_group(group, &selection, &mut ctx, &mut stats);
 
-    // =========================================================================
-    // Test Group 9: Edge Cases
-    // =========================================================================
-    info!("");
-    info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    info!("  GROUP 9: Edge Cases");
-    info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    
-    if emac_ok && link_ok {
-        run_test!(stats, "IT-9-001", "MAC filtering", tests::group9_edge::test_mac_filtering());
-        run_test!(stats, "IT-9-002", "MAC filter multiple", tests::group9_edge::test_mac_filter_multiple());
-        run_test!(stats, "IT-9-003", "Hash filtering", tests::group9_edge::test_hash_filtering());
-        run_test!(stats, "IT-9-004", "Pass all multicast", tests::group9_edge::test_pass_all_multicast());
-        run_test!(stats, "IT-9-005", "VLAN filtering", tests::group9_edge::test_vlan_filtering());
-        run_test!(stats, "IT-9-006", "Flow control config", tests::group9_edge::test_flow_control_config());
-        run_test!(stats, "IT-9-007", "Flow control check", tests::group9_edge::test_flow_control_check());
-        run_test!(stats, "IT-9-008", "PHY energy detect", tests::group9_edge::test_energy_detect(&mut ctx));
-        run_test!(stats, "IT-9-009", "RX interrupt fires", tests::group9_edge::test_rx_interrupt_fires(2000));
-        run_test!(stats, "IT-9-010", "Async wakers", tests::group9_edge::test_async_wakers());
-        run_test!(stats, "IT-9-011", "Restore RX state", tests::group9_edge::test_restore_rx_state());
-    } else {
-        warn!("  Skipping - requires EMAC init and link");
-        for _ in 0..11 { stats.record(TestResult::Skip); }
+        // The responder only needs the EMAC and link from groups 2 and 3
+        #[cfg(feature = "peer-responder")]
+        if group.number == 3 {
+            if !(ctx.emac_initialized && ctx.link_up) {
+                error!("Peer responder requires EMAC init and link - halting");
+                loop {
+                    esp_hal::delay::Delay::new().delay_millis(1000);
+                }
+            }
+            tests::group10_peer::run_responder(&mut ctx);
+        }
     }
 
     // =========================================================================
@@ -278,6 +161,7 @@ fn main() -> ! {
     info!("  Failed:  {} ✗", stats.failed);
     info!("  Skipped: {} ○", stats.skipped);
     info!("");
+    tests::emit_summary(&stats);
     
     if stats.all_passed() {
         info!("╔══════════════════════════════════════════════════════════════╗");
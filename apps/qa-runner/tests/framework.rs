@@ -3,9 +3,11 @@
 //! Common types, macros, and utilities for integration tests.
 
 use core::cell::RefCell;
+use core::fmt::{self, Write};
 use critical_section::Mutex;
 
 use esp_hal::gpio::Output;
+use esp_hal::peripherals::CPU_CTRL;
 use ph_esp32_mac::{Duplex, Emac, Lan8720a, Speed};
 use ph_esp32_mac::boards::wt32_eth01::Wt32Eth01;
 use ph_esp32_mac::hal::MdioController;
@@ -30,6 +32,15 @@ impl TestResult {
             TestResult::Skip => "○",
         }
     }
+
+    /// Lowercase result name used in structured records
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TestResult::Pass => "pass",
+            TestResult::Fail => "fail",
+            TestResult::Skip => "skip",
+        }
+    }
 }
 
 // =============================================================================
@@ -69,6 +80,114 @@ impl TestStats {
     }
 }
 
+// =============================================================================
+// Structured Results
+// =============================================================================
+
+/// Maximum number of measured values recorded per test
+pub const MAX_METRICS: usize = 8;
+
+/// Measured values recorded by the currently running test
+struct Metrics {
+    entries: [(&'static str, i64); MAX_METRICS],
+    len: usize,
+}
+
+static METRICS: Mutex<RefCell<Metrics>> = Mutex::new(RefCell::new(Metrics {
+    entries: [("", 0); MAX_METRICS],
+    len: 0,
+}));
+
+/// Record a measured value for the currently running test
+///
+/// Values are attached to the test's structured result record and cleared
+/// once the record is emitted. Extra values beyond [`MAX_METRICS`] are dropped.
+pub fn record_metric(key: &'static str, value: i64) {
+    critical_section::with(|cs| {
+        let mut metrics = METRICS.borrow_ref_mut(cs);
+        let len = metrics.len;
+        if len < MAX_METRICS {
+            metrics.entries[len] = (key, value);
+            metrics.len += 1;
+        }
+    });
+}
+
+/// String written as the contents of a JSON string literal
+///
+/// Escapes quotes, backslashes and control characters; test names are
+/// free text.
+struct JsonStr<'a>(&'a str);
+
+impl fmt::Display for JsonStr<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                '\r' => f.write_str("\\r")?,
+                '\t' => f.write_str("\\t")?,
+                c if c.is_control() => write!(f, "\\u{:04x}", u32::from(c))?,
+                c => f.write_char(c)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Emit one machine-readable result line and clear recorded metrics
+///
+/// With the `structured-results` feature enabled, each test produces a line
+/// of the form:
+///
+/// ```text
+/// QA-RESULT {"id":"IT-4-003","name":"Packet RX (3s)","result":"pass","duration_ms":3004,"metrics":{"rx_frames":17}}
+/// ```
+///
+/// Without the feature the metrics are discarded and nothing is printed.
+pub fn emit_record(id: &str, name: &str, result: TestResult, duration_ms: u64) {
+    let metrics = critical_section::with(|cs| {
+        let mut metrics = METRICS.borrow_ref_mut(cs);
+        let snapshot = (metrics.entries, metrics.len);
+        metrics.len = 0;
+        snapshot
+    });
+
+    if !cfg!(feature = "structured-results") {
+        return;
+    }
+
+    let (entries, len) = metrics;
+    esp_println::print!(
+        "QA-RESULT {{\"id\":\"{}\",\"name\":\"{}\",\"result\":\"{}\",\"duration_ms\":{},\"metrics\":{{",
+        JsonStr(id),
+        JsonStr(name),
+        result.as_str(),
+        duration_ms
+    );
+    for (i, (key, value)) in entries[..len].iter().enumerate() {
+        let sep = if i == 0 { "" } else { "," };
+        esp_println::print!("{}\"{}\":{}", sep, JsonStr(key), value);
+    }
+    esp_println::println!("}}}}");
+}
+
+/// Emit the machine-readable summary line for the whole run
+pub fn emit_summary(stats: &TestStats) {
+    if !cfg!(feature = "structured-results") {
+        return;
+    }
+
+    esp_println::println!(
+        "QA-SUMMARY {{\"total\":{},\"passed\":{},\"failed\":{},\"skipped\":{}}}",
+        stats.total(),
+        stats.passed,
+        stats.failed,
+        stats.skipped
+    );
+}
+
 // =============================================================================
 // Test Context
 // =============================================================================
@@ -83,10 +202,18 @@ pub struct TestContext<'a> {
     pub link_duplex: Duplex,
     pub emac_initialized: bool,
     pub link_up: bool,
+    /// Second-core control, taken by the dual-core test
+    pub cpu_ctrl: Option<CPU_CTRL<'a>>,
+    /// Peer state shared by the group 10 tests
+    #[cfg(feature = "peer-sender")]
+    pub peer: super::group10_peer::PeerSession,
+    /// Set once peer discovery (IT-10-001) passes
+    #[cfg(feature = "peer-sender")]
+    pub peer_found: bool,
 }
 
 impl<'a> TestContext<'a> {
-    pub fn new(clk_pin: Output<'a>) -> Self {
+    pub fn new(clk_pin: Output<'a>, cpu_ctrl: CPU_CTRL<'a>) -> Self {
         Self {
             phy: Wt32Eth01::lan8720a(),
             mdio: MdioController::new(esp_hal::delay::Delay::new()),
@@ -95,6 +222,11 @@ impl<'a> TestContext<'a> {
             link_duplex: Duplex::Full,
             emac_initialized: false,
             link_up: false,
+            cpu_ctrl: Some(cpu_ctrl),
+            #[cfg(feature = "peer-sender")]
+            peer: super::group10_peer::PeerSession::new(),
+            #[cfg(feature = "peer-sender")]
+            peer_found: false,
         }
     }
 }
@@ -109,6 +241,24 @@ impl<'a> TestContext<'a> {
 /// Production code typically uses 8-16 buffers.
 pub static EMAC: Mutex<RefCell<Option<Emac<4, 4, 1600>>>> = Mutex::new(RefCell::new(None));
 
+// =============================================================================
+// Station Address
+// =============================================================================
+
+/// MAC address used by the runner
+///
+/// The peer responder uses a different address so two boards can share a
+/// cable without colliding.
+#[cfg(not(feature = "peer-responder"))]
+pub const LOCAL_MAC: [u8; 6] = [0x02, 0x00, 0x00, 0x12, 0x34, 0x56];
+
+/// MAC address used by the runner
+///
+/// The peer responder uses a different address so two boards can share a
+/// cable without colliding.
+#[cfg(feature = "peer-responder")]
+pub const LOCAL_MAC: [u8; 6] = [0x02, 0x00, 0x00, 0x12, 0x34, 0x57];
+
 // =============================================================================
 // Hardware Constants
 // =============================================================================
@@ -0,0 +1,618 @@
+//! Group 10: Two-Board Peer Tests
+//!
+//! Hardware-in-the-loop tests that need a second board running the
+//! responder role, connected with a crossover cable (or a direct cable with
+//! auto-MDIX).
+//!
+//! | Test ID | Name | Description |
+//! |---------|------|-------------|
+//! | IT-10-001 | Peer discovery | Find the responder and learn its MAC |
+//! | IT-10-002 | Unicast filtering | Peer answers own MAC, ignores others |
+//! | IT-10-003 | Frame-size sweep | Echo frames from 60 to 1514 bytes |
+//! | IT-10-004 | Flow control load | Blast frames with PAUSE enabled, no loss |
+//! | IT-10-005 | Forced link modes | 10/100 half/full forced on both ends |
+//!
+//! # Roles
+//!
+//! - `peer-sender`: runs groups 1-9, then this group, then the summary
+//! - `peer-responder`: brings up the link, then serves [`run_responder`] forever
+//!
+//! # Control Protocol
+//!
+//! All control frames use EtherType `0x88B5` (local experimental) with this
+//! payload header:
+//!
+//! | Offset | Size | Field |
+//! |--------|------|-------|
+//! | 0 | 2 | Magic `"PT"` |
+//! | 2 | 1 | Opcode |
+//! | 3 | 1 | Status (0 = OK) |
+//! | 4 | 2 | Sequence number (big endian) |
+//! | 6 | 4 | Argument (big endian) |
+//! | 10 | n | Data |
+//!
+//! | Opcode | Name | Direction | Argument |
+//! |--------|------|-----------|----------|
+//! | 0x01 | HELLO | S → broadcast | - |
+//! | 0x02 | HELLO_ACK | R → S | - |
+//! | 0x03 | ECHO | S → R | - |
+//! | 0x04 | ECHO_REPLY | R → S | - (data echoed) |
+//! | 0x05 | SET_LINK | S → R | Link mode code |
+//! | 0x06 | SET_LINK_ACK | R → S | Link mode code |
+//! | 0x07 | FLOW | S → R | 1 = enable, 0 = disable |
+//! | 0x08 | FLOW_ACK | R → S | Same as request |
+//! | 0x09 | BLAST_BEGIN | S → R | Frames to expect |
+//! | 0x0A | BLAST_BEGIN_ACK | R → S | Same as request |
+//! | 0x0B | BLAST_DATA | S → R | Frame index |
+//! | 0x0C | BLAST_REPORT | S → R | - |
+//! | 0x0D | BLAST_REPORT_REPLY | R → S | Frames received |
+//!
+//! The responder acknowledges `SET_LINK` before changing its PHY mode, so the
+//! sender must wait for the link to return before the next exchange.
+
+use log::{error, info, warn};
+
+use ph_esp32_mac::{Emac, LinkStatus, PhyDriver};
+
+use super::framework::{record_metric, TestContext, TestResult, EMAC};
+
+// =============================================================================
+// Protocol Constants
+// =============================================================================
+
+/// EtherType used for peer control frames
+pub const PEER_ETHERTYPE: u16 = 0x88B5;
+
+/// Magic bytes at the start of every peer payload
+const PEER_MAGIC: [u8; 2] = *b"PT";
+
+/// Ethernet header length
+const ETH_HDR_LEN: usize = 14;
+
+/// Peer payload header length
+const PEER_HDR_LEN: usize = 10;
+
+/// Offset of peer data within the frame
+const DATA_OFFSET: usize = ETH_HDR_LEN + PEER_HDR_LEN;
+
+/// Maximum frame size without FCS
+const MAX_FRAME: usize = 1514;
+
+/// Broadcast MAC address
+const BROADCAST: [u8; 6] = [0xFF; 6];
+
+const OP_HELLO: u8 = 0x01;
+const OP_HELLO_ACK: u8 = 0x02;
+const OP_ECHO: u8 = 0x03;
+const OP_ECHO_REPLY: u8 = 0x04;
+const OP_SET_LINK: u8 = 0x05;
+const OP_SET_LINK_ACK: u8 = 0x06;
+const OP_FLOW: u8 = 0x07;
+const OP_FLOW_ACK: u8 = 0x08;
+const OP_BLAST_BEGIN: u8 = 0x09;
+const OP_BLAST_BEGIN_ACK: u8 = 0x0A;
+const OP_BLAST_DATA: u8 = 0x0B;
+const OP_BLAST_REPORT: u8 = 0x0C;
+const OP_BLAST_REPORT_REPLY: u8 = 0x0D;
+
+/// Link mode codes carried in `SET_LINK`
+const LINK_AUTO: u32 = 0;
+const LINK_10_HALF: u32 = 1;
+const LINK_10_FULL: u32 = 2;
+const LINK_100_HALF: u32 = 3;
+const LINK_100_FULL: u32 = 4;
+
+/// Reply timeout for control exchanges
+const REPLY_TIMEOUT_MS: u32 = 200;
+
+/// Number of frames sent in the flow control blast
+const BLAST_FRAMES: u32 = 500;
+
+/// Payload size of each blast frame
+const BLAST_PAYLOAD: usize = 1000;
+
+/// Frame sizes (without FCS) used by the sweep
+const SWEEP_SIZES: [usize; 9] = [60, 64, 128, 256, 512, 1024, 1280, 1500, MAX_FRAME];
+
+type TestEmac = Emac<4, 4, 1600>;
+
+// =============================================================================
+// Peer Session
+// =============================================================================
+
+/// State shared across the sender-side peer tests
+pub struct PeerSession {
+    /// Responder MAC address, learned during discovery
+    pub peer_mac: Option<[u8; 6]>,
+    /// Next sequence number
+    seq: u16,
+}
+
+impl PeerSession {
+    pub const fn new() -> Self {
+        Self {
+            peer_mac: None,
+            seq: 0,
+        }
+    }
+
+    fn next_seq(&mut self) -> u16 {
+        self.seq = self.seq.wrapping_add(1);
+        self.seq
+    }
+}
+
+/// Parsed peer payload header
+#[derive(Clone, Copy)]
+struct PeerHeader {
+    src: [u8; 6],
+    opcode: u8,
+    seq: u16,
+    arg: u32,
+}
+
+// =============================================================================
+// Frame Helpers
+// =============================================================================
+
+fn with_emac<R>(f: impl FnOnce(&mut TestEmac) -> R) -> Option<R> {
+    critical_section::with(|cs| EMAC.borrow_ref_mut(cs).as_mut().map(f))
+}
+
+fn local_mac() -> [u8; 6] {
+    with_emac(|emac| *emac.mac_address()).unwrap_or([0; 6])
+}
+
+/// Write the Ethernet and peer headers
+fn write_header(
+    buf: &mut [u8],
+    dst: &[u8; 6],
+    src: &[u8; 6],
+    opcode: u8,
+    seq: u16,
+    arg: u32,
+) {
+    buf[0..6].copy_from_slice(dst);
+    buf[6..12].copy_from_slice(src);
+    buf[12..14].copy_from_slice(&PEER_ETHERTYPE.to_be_bytes());
+    buf[14..16].copy_from_slice(&PEER_MAGIC);
+    buf[16] = opcode;
+    buf[17] = 0;
+    buf[18..20].copy_from_slice(&seq.to_be_bytes());
+    buf[20..24].copy_from_slice(&arg.to_be_bytes());
+}
+
+fn parse_header(frame: &[u8]) -> Option<PeerHeader> {
+    if frame.len() < DATA_OFFSET
+        || u16::from_be_bytes([frame[12], frame[13]]) != PEER_ETHERTYPE
+        || frame[14..16] != PEER_MAGIC
+    {
+        return None;
+    }
+
+    let mut src = [0u8; 6];
+    src.copy_from_slice(&frame[6..12]);
+    Some(PeerHeader {
+        src,
+        opcode: frame[16],
+        seq: u16::from_be_bytes([frame[18], frame[19]]),
+        arg: u32::from_be_bytes([frame[20], frame[21], frame[22], frame[23]]),
+    })
+}
+
+/// Fill data bytes with a pattern derived from the sequence number
+fn fill_pattern(data: &mut [u8], seq: u16) {
+    for (i, b) in data.iter_mut().enumerate() {
+        *b = (i as u8).wrapping_add(seq as u8);
+    }
+}
+
+/// Transmit a frame, waiting briefly for a free descriptor
+fn send_frame(frame: &[u8]) -> bool {
+    let delay = esp_hal::delay::Delay::new();
+    for _ in 0..1000 {
+        match with_emac(|emac| {
+            if emac.can_transmit(frame.len()) {
+                Some(emac.transmit(frame).is_ok())
+            } else {
+                None
+            }
+        }) {
+            Some(Some(ok)) => return ok,
+            Some(None) => delay.delay_micros(10),
+            None => return false,
+        }
+    }
+    false
+}
+
+/// Send a header-only control frame
+fn send_control(dst: &[u8; 6], opcode: u8, seq: u16, arg: u32) -> bool {
+    let mut frame = [0u8; 60];
+    write_header(&mut frame, dst, &local_mac(), opcode, seq, arg);
+    send_frame(&frame)
+}
+
+/// Receive one frame if available
+fn try_receive(buf: &mut [u8]) -> Option<usize> {
+    with_emac(|emac| {
+        if emac.rx_available() {
+            emac.receive(buf).ok()
+        } else {
+            None
+        }
+    })
+    .flatten()
+}
+
+/// Wait for a peer frame with the given opcode and sequence number
+///
+/// Non-matching frames are discarded.
+fn wait_for_reply(buf: &mut [u8], opcode: u8, seq: u16, timeout_ms: u32) -> Option<PeerHeader> {
+    let delay = esp_hal::delay::Delay::new();
+    for _ in 0..timeout_ms * 10 {
+        while let Some(len) = try_receive(buf) {
+            if let Some(hdr) = parse_header(&buf[..len]) {
+                if hdr.opcode == opcode && hdr.seq == seq {
+                    return Some(hdr);
+                }
+            }
+        }
+        delay.delay_micros(100);
+    }
+    None
+}
+
+/// Send an ECHO of `frame_len` bytes to `dst` and verify the reply
+fn echo_roundtrip(session: &mut PeerSession, dst: &[u8; 6], frame_len: usize) -> bool {
+    let mut tx = [0u8; MAX_FRAME];
+    let mut rx = [0u8; 1600];
+    let seq = session.next_seq();
+
+    write_header(&mut tx, dst, &local_mac(), OP_ECHO, seq, 0);
+    fill_pattern(&mut tx[DATA_OFFSET..frame_len], seq);
+    if !send_frame(&tx[..frame_len]) {
+        return false;
+    }
+
+    // The responder echoes the frame unchanged apart from the headers
+    wait_for_reply(&mut rx, OP_ECHO_REPLY, seq, REPLY_TIMEOUT_MS).is_some()
+        && rx[DATA_OFFSET..frame_len] == tx[DATA_OFFSET..frame_len]
+}
+
+fn link_mode_status(code: u32) -> Option<LinkStatus> {
+    match code {
+        LINK_10_HALF => Some(LinkStatus::slow_half()),
+        LINK_10_FULL => Some(LinkStatus::slow_full()),
+        LINK_100_HALF => Some(LinkStatus::fast_half()),
+        LINK_100_FULL => Some(LinkStatus::fast_full()),
+        _ => None,
+    }
+}
+
+/// Apply a link mode locally and wait for the link to come back
+fn apply_link_mode(ctx: &mut TestContext, code: u32, timeout_ms: u32) -> Option<LinkStatus> {
+    let result = match link_mode_status(code) {
+        Some(status) => ctx.phy.force_link(&mut ctx.mdio, status),
+        None => ctx.phy.init(&mut ctx.mdio),
+    };
+    if let Err(e) = result {
+        error!("  PHY link mode change failed: {:?}", e);
+        return None;
+    }
+
+    // Give the PHY time to drop the old link before polling
+    let delay = esp_hal::delay::Delay::new();
+    delay.delay_millis(500);
+
+    for _ in 0..timeout_ms / 100 {
+        if let Ok(Some(status)) = ctx.phy.link_status(&mut ctx.mdio) {
+            with_emac(|emac| emac.update_link(status.speed, status.duplex));
+            ctx.link_speed = status.speed;
+            ctx.link_duplex = status.duplex;
+            ctx.link_up = true;
+            return Some(status);
+        }
+        delay.delay_millis(100);
+    }
+    ctx.link_up = false;
+    None
+}
+
+// =============================================================================
+// Sender Tests
+// =============================================================================
+
+/// IT-10-001: Discover the responder by broadcasting HELLO
+pub fn test_peer_discovery(session: &mut PeerSession, timeout_ms: u32) -> TestResult {
+    let mut rx = [0u8; 1600];
+
+    for attempt in 0..timeout_ms / REPLY_TIMEOUT_MS {
+        let seq = session.next_seq();
+        if !send_control(&BROADCAST, OP_HELLO, seq, 0) {
+            error!("  HELLO transmit failed");
+            return TestResult::Fail;
+        }
+        if let Some(hdr) = wait_for_reply(&mut rx, OP_HELLO_ACK, seq, REPLY_TIMEOUT_MS) {
+            info!(
+                "  Peer found: {:02X}:{:02X}:{:02X}:{:02X}:{:02X}:{:02X} ({} attempts)",
+                hdr.src[0], hdr.src[1], hdr.src[2], hdr.src[3], hdr.src[4], hdr.src[5],
+                attempt + 1
+            );
+            session.peer_mac = Some(hdr.src);
+            return TestResult::Pass;
+        }
+    }
+
+    error!("  No responder answered within {}ms", timeout_ms);
+    TestResult::Fail
+}
+
+/// IT-10-002: Responder answers frames to its MAC and drops other unicasts
+pub fn test_peer_unicast_filtering(session: &mut PeerSession) -> TestResult {
+    let Some(peer) = session.peer_mac else {
+        warn!("  No peer - skipping");
+        return TestResult::Skip;
+    };
+
+    if !echo_roundtrip(session, &peer, 64) {
+        error!("  No echo for frame addressed to peer MAC");
+        return TestResult::Fail;
+    }
+    info!("  Echo to peer MAC answered");
+
+    let mut other = peer;
+    other[5] ^= 0x80;
+    if echo_roundtrip(session, &other, 64) {
+        error!("  Peer answered a frame for a foreign unicast MAC");
+        return TestResult::Fail;
+    }
+    info!("  Frame to foreign unicast MAC was filtered");
+
+    TestResult::Pass
+}
+
+/// IT-10-003: Echo a sweep of frame sizes and verify payloads
+pub fn test_peer_frame_size_sweep(session: &mut PeerSession) -> TestResult {
+    let Some(peer) = session.peer_mac else {
+        warn!("  No peer - skipping");
+        return TestResult::Skip;
+    };
+
+    let mut failures = 0u32;
+    for size in SWEEP_SIZES {
+        if echo_roundtrip(session, &peer, size) {
+            info!("    {} bytes: OK", size);
+        } else {
+            error!("    {} bytes: FAILED", size);
+            failures += 1;
+        }
+    }
+
+    record_metric("sizes", SWEEP_SIZES.len() as i64);
+    record_metric("failures", failures as i64);
+
+    if failures == 0 {
+        TestResult::Pass
+    } else {
+        TestResult::Fail
+    }
+}
+
+/// IT-10-004: Blast frames with flow control enabled on both ends
+pub fn test_peer_flow_control_load(session: &mut PeerSession) -> TestResult {
+    let Some(peer) = session.peer_mac else {
+        warn!("  No peer - skipping");
+        return TestResult::Skip;
+    };
+    let mut rx = [0u8; 1600];
+
+    // Enable flow control on the responder, then locally
+    let seq = session.next_seq();
+    if !send_control(&peer, OP_FLOW, seq, 1)
+        || wait_for_reply(&mut rx, OP_FLOW_ACK, seq, REPLY_TIMEOUT_MS).is_none()
+    {
+        error!("  Peer did not acknowledge FLOW enable");
+        return TestResult::Fail;
+    }
+    with_emac(|emac| {
+        emac.set_peer_pause_ability(true);
+        emac.enable_flow_control(true);
+    });
+
+    let seq = session.next_seq();
+    if !send_control(&peer, OP_BLAST_BEGIN, seq, BLAST_FRAMES)
+        || wait_for_reply(&mut rx, OP_BLAST_BEGIN_ACK, seq, REPLY_TIMEOUT_MS).is_none()
+    {
+        error!("  Peer did not acknowledge BLAST_BEGIN");
+        return TestResult::Fail;
+    }
+
+    let mut frame = [0u8; ETH_HDR_LEN + PEER_HDR_LEN + BLAST_PAYLOAD];
+    let mac = local_mac();
+    let mut sent = 0u32;
+    for i in 0..BLAST_FRAMES {
+        write_header(&mut frame, &peer, &mac, OP_BLAST_DATA, i as u16, i);
+        if send_frame(&frame) {
+            sent += 1;
+        }
+    }
+
+    // Let the responder drain its ring before asking for the count
+    esp_hal::delay::Delay::new().delay_millis(200);
+
+    let seq = session.next_seq();
+    let received = if send_control(&peer, OP_BLAST_REPORT, seq, 0) {
+        wait_for_reply(&mut rx, OP_BLAST_REPORT_REPLY, seq, 1000).map(|hdr| hdr.arg)
+    } else {
+        None
+    };
+
+    // Restore flow control to disabled on both ends
+    let seq = session.next_seq();
+    send_control(&peer, OP_FLOW, seq, 0);
+    let _ = wait_for_reply(&mut rx, OP_FLOW_ACK, seq, REPLY_TIMEOUT_MS);
+    with_emac(|emac| {
+        emac.enable_flow_control(false);
+        emac.set_peer_pause_ability(false);
+    });
+
+    let Some(received) = received else {
+        error!("  Peer did not report blast results");
+        return TestResult::Fail;
+    };
+
+    info!("  Sent {} / received {} frames", sent, received);
+    record_metric("sent", sent as i64);
+    record_metric("received", received as i64);
+
+    if sent == BLAST_FRAMES && received == sent {
+        TestResult::Pass
+    } else {
+        error!("  Lost {} frames under flow control", sent.saturating_sub(received));
+        TestResult::Fail
+    }
+}
+
+/// IT-10-005: Force each 10/100 half/full mode on both ends and echo
+pub fn test_peer_forced_modes(session: &mut PeerSession, ctx: &mut TestContext) -> TestResult {
+    let Some(peer) = session.peer_mac else {
+        warn!("  No peer - skipping");
+        return TestResult::Skip;
+    };
+    let mut rx = [0u8; 1600];
+    let mut passed = 0i64;
+    let mut all_ok = true;
+
+    let modes = [
+        (LINK_10_HALF, "10M half"),
+        (LINK_10_FULL, "10M full"),
+        (LINK_100_HALF, "100M half"),
+        (LINK_100_FULL, "100M full"),
+        (LINK_AUTO, "auto-negotiation"),
+    ];
+
+    for (code, name) in modes {
+        let seq = session.next_seq();
+        if !send_control(&peer, OP_SET_LINK, seq, code)
+            || wait_for_reply(&mut rx, OP_SET_LINK_ACK, seq, REPLY_TIMEOUT_MS).is_none()
+        {
+            error!("  {}: peer did not acknowledge SET_LINK", name);
+            all_ok = false;
+            continue;
+        }
+
+        let Some(status) = apply_link_mode(ctx, code, 5000) else {
+            error!("  {}: link did not come up", name);
+            all_ok = false;
+            continue;
+        };
+
+        let expected = link_mode_status(code);
+        let mode_ok = expected.is_none_or(|exp| exp == status);
+        let echo_ok = mode_ok && echo_roundtrip(session, &peer, 512);
+
+        if mode_ok && echo_ok {
+            info!("  {}: link {:?} {:?}, echo OK", name, status.speed, status.duplex);
+            passed += 1;
+        } else {
+            error!(
+                "  {}: link {:?} {:?} mode_ok={} echo_ok={}",
+                name, status.speed, status.duplex, mode_ok, echo_ok
+            );
+            all_ok = false;
+        }
+    }
+
+    record_metric("modes_passed", passed);
+
+    if all_ok {
+        TestResult::Pass
+    } else {
+        TestResult::Fail
+    }
+}
+
+// =============================================================================
+// Responder
+// =============================================================================
+
+/// Serve the peer control protocol forever
+///
+/// The EMAC must be initialized and the link up. The responder starts the
+/// EMAC, answers control frames, and follows `SET_LINK` requests.
+pub fn run_responder(ctx: &mut TestContext) -> ! {
+    with_emac(|emac| {
+        let _ = emac.start();
+    });
+
+    let mac = local_mac();
+    info!(
+        "Peer responder ready on {:02X}:{:02X}:{:02X}:{:02X}:{:02X}:{:02X}",
+        mac[0], mac[1], mac[2], mac[3], mac[4], mac[5]
+    );
+
+    let delay = esp_hal::delay::Delay::new();
+    let mut rx = [0u8; 1600];
+    let mut tx = [0u8; MAX_FRAME];
+    let mut blast_expected = 0u32;
+    let mut blast_received = 0u32;
+
+    loop {
+        with_emac(|emac| emac.check_flow_control());
+
+        let Some(len) = try_receive(&mut rx) else {
+            delay.delay_micros(50);
+            continue;
+        };
+        let Some(hdr) = parse_header(&rx[..len]) else {
+            continue;
+        };
+
+        match hdr.opcode {
+            OP_HELLO => {
+                send_control(&hdr.src, OP_HELLO_ACK, hdr.seq, 0);
+            }
+            OP_ECHO => {
+                tx[..len].copy_from_slice(&rx[..len]);
+                write_header(&mut tx, &hdr.src, &mac, OP_ECHO_REPLY, hdr.seq, hdr.arg);
+                send_frame(&tx[..len]);
+            }
+            OP_FLOW => {
+                let enable = hdr.arg != 0;
+                with_emac(|emac| {
+                    emac.set_peer_pause_ability(enable);
+                    emac.enable_flow_control(enable);
+                });
+                send_control(&hdr.src, OP_FLOW_ACK, hdr.seq, hdr.arg);
+                info!("Flow control {}", if enable { "enabled" } else { "disabled" });
+            }
+            OP_BLAST_BEGIN => {
+                blast_expected = hdr.arg;
+                blast_received = 0;
+                send_control(&hdr.src, OP_BLAST_BEGIN_ACK, hdr.seq, hdr.arg);
+            }
+            OP_BLAST_DATA => {
+                blast_received += 1;
+                // Drain slowly so the RX ring fills and PAUSE frames are needed
+                delay.delay_micros(200);
+            }
+            OP_BLAST_REPORT => {
+                info!("Blast: {} / {} frames", blast_received, blast_expected);
+                send_control(&hdr.src, OP_BLAST_REPORT_REPLY, hdr.seq, blast_received);
+            }
+            OP_SET_LINK => {
+                send_control(&hdr.src, OP_SET_LINK_ACK, hdr.seq, hdr.arg);
+                // Let the ACK leave before the link drops
+                delay.delay_millis(20);
+                match apply_link_mode(ctx, hdr.arg, 5000) {
+                    Some(status) => info!("Link mode {}: {:?} {:?}", hdr.arg, status.speed, status.duplex),
+                    None => warn!("Link mode {}: link did not come up", hdr.arg),
+                }
+            }
+            _ => {
+                warn!("Unknown peer opcode 0x{:02X}", hdr.opcode);
+            }
+        }
+    }
+}
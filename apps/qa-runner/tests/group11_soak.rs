@@ -0,0 +1,458 @@
+//! Group 11: Soak Test
+//!
+//! Long-running TX/RX traffic with periodic consistency checks, to catch
+//! slow descriptor-management bugs that the short tests miss.
+//!
+//! | Test ID | Name | Description |
+//! |---------|------|-------------|
+//! | IT-11-001 | Soak | TX/RX for hours with ring, counter, and stall checks |
+//!
+//! # Traffic
+//!
+//! The MAC is put in internal loopback so every transmitted frame comes back
+//! on the RX path without touching the network. Soak frames use EtherType
+//! `0x88B5` with this payload:
+//!
+//! | Offset | Size | Field |
+//! |--------|------|-------|
+//! | 0 | 2 | Magic `"SK"` |
+//! | 2 | 4 | Sequence number (big endian) |
+//! | 6 | n | Pattern: `(seq + i) as u8` |
+//!
+//! Frame lengths cycle through [`FRAME_SIZES`] so short and full-size frames
+//! both exercise the ring.
+//!
+//! # Checks
+//!
+//! Every [`CHECK_INTERVAL_SECS`]:
+//!
+//! - **Ring integrity**: both descriptor chains still loop over exactly
+//!   4 descriptors and the DMA current-descriptor registers point inside them
+//! - **No leaks**: after a short drain, all TX descriptors are reclaimed and
+//!   all RX descriptors are back with the DMA
+//! - **Monotonic statistics**: no counter went backwards since the previous
+//!   check, and no more frames were looped back than transmitted
+//! - **No stalls**: looped-back frames kept arriving within
+//!   [`STALL_TIMEOUT_SECS`]
+//!
+//! Every received soak frame is also checked for an increasing sequence
+//! number and an intact payload pattern.
+//!
+//! A one-line digest is printed every [`DIGEST_INTERVAL_SECS`] and at the end.
+//!
+//! # Duration
+//!
+//! Defaults to [`DEFAULT_SOAK_MINUTES`]; set `QA_SOAK_MINUTES` at build time
+//! to override:
+//!
+//! ```ignore
+//! QA_SOAK_MINUTES=720 cargo xtask run qa-runner --features soak
+//! ```
+
+use esp_hal::time::{Duration, Instant};
+use log::{error, info, warn};
+
+use super::framework::{record_metric, TestResult, DMA_BASE, EMAC, LOCAL_MAC, MAC_BASE};
+
+// =============================================================================
+// Configuration
+// =============================================================================
+
+/// Soak duration when `QA_SOAK_MINUTES` is not set
+pub const DEFAULT_SOAK_MINUTES: u64 = 240;
+
+/// Interval between consistency checks
+pub const CHECK_INTERVAL_SECS: u64 = 10;
+
+/// Interval between digest lines
+pub const DIGEST_INTERVAL_SECS: u64 = 60;
+
+/// Fail the stall watchdog if no frame is looped back for this long
+pub const STALL_TIMEOUT_SECS: u64 = 5;
+
+/// Frame lengths (without FCS) cycled through by the sender
+pub const FRAME_SIZES: [usize; 5] = [60, 128, 512, 1024, 1514];
+
+/// Frames queued per loop iteration
+const FRAMES_PER_TICK: u32 = 2;
+
+/// EtherType used for soak frames
+const SOAK_ETHERTYPE: u16 = 0x88B5;
+
+/// Magic bytes at the start of every soak payload
+const SOAK_MAGIC: [u8; 2] = *b"SK";
+
+/// Ethernet header length
+const ETH_HDR_LEN: usize = 14;
+
+/// Soak payload header length (magic + sequence)
+const SOAK_HDR_LEN: usize = 6;
+
+/// Number of descriptors per ring (matches the static EMAC)
+const RING_LEN: u32 = 4;
+
+/// Descriptor stride in bytes
+const DESC_SIZE: u32 = 32;
+
+/// DMA register offsets
+const DMA_RX_BASE: u32 = DMA_BASE + 0x0C;
+const DMA_TX_BASE: u32 = DMA_BASE + 0x10;
+const DMA_MISSED_FRAMES: u32 = DMA_BASE + 0x20;
+const DMA_CUR_TX_DESC: u32 = DMA_BASE + 0x48;
+const DMA_CUR_RX_DESC: u32 = DMA_BASE + 0x4C;
+
+/// MAC configuration loopback bit
+const GMACCONFIG_LM: u32 = 1 << 12;
+
+// =============================================================================
+// Counters
+// =============================================================================
+
+/// Cumulative soak counters
+#[derive(Clone, Copy, Default)]
+pub struct SoakCounters {
+    pub tx_frames: u64,
+    pub tx_busy: u64,
+    pub tx_errors: u64,
+    pub rx_frames: u64,
+    pub rx_other: u64,
+    pub rx_errors: u64,
+    pub missed: u64,
+    pub seq_errors: u64,
+    pub payload_errors: u64,
+    pub ring_faults: u64,
+    pub leak_faults: u64,
+    pub monotonic_faults: u64,
+    pub stalls: u64,
+    pub checks: u64,
+}
+
+impl SoakCounters {
+    /// True if no check has failed
+    pub fn healthy(&self) -> bool {
+        self.seq_errors == 0
+            && self.payload_errors == 0
+            && self.ring_faults == 0
+            && self.leak_faults == 0
+            && self.monotonic_faults == 0
+            && self.stalls == 0
+    }
+
+    /// True if no traffic counter went backwards since `prev`
+    fn monotonic_since(&self, prev: &SoakCounters) -> bool {
+        self.tx_frames >= prev.tx_frames
+            && self.tx_busy >= prev.tx_busy
+            && self.tx_errors >= prev.tx_errors
+            && self.rx_frames >= prev.rx_frames
+            && self.rx_other >= prev.rx_other
+            && self.rx_errors >= prev.rx_errors
+            && self.missed >= prev.missed
+    }
+}
+
+/// Print the one-line digest
+fn print_digest(label: &str, elapsed_secs: u64, c: &SoakCounters) {
+    info!(
+        "  SOAK {} t={}s tx={} busy={} tx_err={} rx={} other={} rx_err={} missed={} seq_err={} payload_err={} ring={} leak={} mono={} stalls={} checks={} [{}]",
+        label,
+        elapsed_secs,
+        c.tx_frames,
+        c.tx_busy,
+        c.tx_errors,
+        c.rx_frames,
+        c.rx_other,
+        c.rx_errors,
+        c.missed,
+        c.seq_errors,
+        c.payload_errors,
+        c.ring_faults,
+        c.leak_faults,
+        c.monotonic_faults,
+        c.stalls,
+        c.checks,
+        if c.healthy() { "OK" } else { "FAULT" }
+    );
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+fn read_reg(addr: u32) -> u32 {
+    // SAFETY: addr is a valid, always-readable EMAC register on ESP32
+    unsafe { core::ptr::read_volatile(addr as *const u32) }
+}
+
+fn write_reg(addr: u32, value: u32) {
+    // SAFETY: addr is a valid EMAC register on ESP32
+    unsafe { core::ptr::write_volatile(addr as *mut u32, value) }
+}
+
+fn set_loopback(enable: bool) {
+    let cfg = read_reg(MAC_BASE);
+    if enable {
+        write_reg(MAC_BASE, cfg | GMACCONFIG_LM);
+    } else {
+        write_reg(MAC_BASE, cfg & !GMACCONFIG_LM);
+    }
+}
+
+/// Soak duration from `QA_SOAK_MINUTES`, falling back to the default
+fn soak_minutes() -> u64 {
+    option_env!("QA_SOAK_MINUTES")
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(DEFAULT_SOAK_MINUTES)
+}
+
+fn build_frame(frame: &mut [u8], seq: u32) {
+    frame[0..6].copy_from_slice(&LOCAL_MAC);
+    frame[6..12].copy_from_slice(&LOCAL_MAC);
+    frame[12..14].copy_from_slice(&SOAK_ETHERTYPE.to_be_bytes());
+    frame[14..16].copy_from_slice(&SOAK_MAGIC);
+    frame[16..20].copy_from_slice(&seq.to_be_bytes());
+    for (i, b) in frame[ETH_HDR_LEN + SOAK_HDR_LEN..].iter_mut().enumerate() {
+        *b = seq.wrapping_add(i as u32) as u8;
+    }
+}
+
+/// Returns the sequence number if `frame` is a soak frame
+fn parse_seq(frame: &[u8]) -> Option<u32> {
+    if frame.len() < ETH_HDR_LEN + SOAK_HDR_LEN
+        || u16::from_be_bytes([frame[12], frame[13]]) != SOAK_ETHERTYPE
+        || frame[14..16] != SOAK_MAGIC
+    {
+        return None;
+    }
+    Some(u32::from_be_bytes([frame[16], frame[17], frame[18], frame[19]]))
+}
+
+fn payload_intact(frame: &[u8], seq: u32) -> bool {
+    frame[ETH_HDR_LEN + SOAK_HDR_LEN..]
+        .iter()
+        .enumerate()
+        .all(|(i, &b)| b == seq.wrapping_add(i as u32) as u8)
+}
+
+/// Verify one descriptor chain loops over exactly [`RING_LEN`] descriptors
+/// and the DMA's current descriptor lies inside it
+fn ring_intact(name: &str, base: u32, current: u32) -> bool {
+    for i in 0..RING_LEN {
+        let next = read_reg(base + i * DESC_SIZE + 12);
+        let expected = if i == RING_LEN - 1 { base } else { base + (i + 1) * DESC_SIZE };
+        if next != expected {
+            error!(
+                "  {} desc[{}] NEXT=0x{:04X}_{:04X} expected 0x{:04X}_{:04X}",
+                name,
+                i,
+                (next >> 16) & 0xFFFF,
+                next & 0xFFFF,
+                (expected >> 16) & 0xFFFF,
+                expected & 0xFFFF
+            );
+            return false;
+        }
+    }
+
+    let offset = current.wrapping_sub(base);
+    if offset >= RING_LEN * DESC_SIZE || offset % DESC_SIZE != 0 {
+        error!(
+            "  {} current desc 0x{:04X}_{:04X} outside ring",
+            name,
+            (current >> 16) & 0xFFFF,
+            current & 0xFFFF
+        );
+        return false;
+    }
+    true
+}
+
+// =============================================================================
+// Soak Loop
+// =============================================================================
+
+/// Receive everything pending, validating soak frames
+fn drain_rx(c: &mut SoakCounters, next_seq: &mut u32, buf: &mut [u8]) {
+    critical_section::with(|cs| {
+        let mut emac_ref = EMAC.borrow_ref_mut(cs);
+        let Some(emac) = emac_ref.as_mut() else {
+            return;
+        };
+        while emac.rx_available() {
+            let len = match emac.receive(buf) {
+                Ok(len) => len,
+                Err(_) => {
+                    c.rx_errors += 1;
+                    continue;
+                }
+            };
+            let frame = &buf[..len];
+            let Some(seq) = parse_seq(frame) else {
+                c.rx_other += 1;
+                continue;
+            };
+            c.rx_frames += 1;
+            if seq < *next_seq {
+                c.seq_errors += 1;
+                error!("  Sequence went backwards: got {}, expected >= {}", seq, *next_seq);
+            }
+            *next_seq = seq.wrapping_add(1);
+            if !payload_intact(frame, seq) {
+                c.payload_errors += 1;
+                error!("  Payload corrupted in frame seq={} len={}", seq, len);
+            }
+        }
+    });
+}
+
+/// Run ring, leak, and monotonicity checks
+fn run_checks(c: &mut SoakCounters, prev: &SoakCounters, next_seq: &mut u32, buf: &mut [u8]) {
+    c.checks += 1;
+
+    // Let in-flight frames complete, then everything must be back home
+    let delay = esp_hal::delay::Delay::new();
+    for _ in 0..20 {
+        delay.delay_millis(1);
+        drain_rx(c, next_seq, buf);
+    }
+
+    let (tx_free, rx_waiting) = critical_section::with(|cs| {
+        EMAC.borrow_ref(cs)
+            .as_ref()
+            .map(|emac| (emac.tx_descriptors_available(), emac.rx_frames_waiting()))
+            .unwrap_or((0, 0))
+    });
+
+    let rx_base = read_reg(DMA_RX_BASE);
+    let tx_base = read_reg(DMA_TX_BASE);
+    let rx_ok = ring_intact("RX", rx_base, read_reg(DMA_CUR_RX_DESC));
+    let tx_ok = ring_intact("TX", tx_base, read_reg(DMA_CUR_TX_DESC));
+    if !(rx_ok && tx_ok) {
+        c.ring_faults += 1;
+    }
+
+    let rx_dma_owned = (0..RING_LEN)
+        .filter(|i| read_reg(rx_base + i * DESC_SIZE) & 0x8000_0000 != 0)
+        .count();
+    if tx_free != RING_LEN as usize || rx_waiting != 0 || rx_dma_owned != RING_LEN as usize {
+        c.leak_faults += 1;
+        error!(
+            "  Descriptor leak: tx_free={} rx_waiting={} rx_dma_owned={} (expected {}/0/{})",
+            tx_free, rx_waiting, rx_dma_owned, RING_LEN, RING_LEN
+        );
+    }
+
+    if !c.monotonic_since(prev) || c.rx_frames > c.tx_frames {
+        c.monotonic_faults += 1;
+        error!(
+            "  Counter inconsistency: tx {}->{} rx {}->{}",
+            prev.tx_frames, c.tx_frames, prev.rx_frames, c.rx_frames
+        );
+    }
+}
+
+/// IT-11-001: Soak the TX/RX paths for hours
+pub fn test_soak() -> TestResult {
+    let minutes = soak_minutes();
+    let duration = Duration::from_secs(minutes * 60);
+    info!(
+        "  Soaking for {} min (check every {}s, digest every {}s)",
+        minutes, CHECK_INTERVAL_SECS, DIGEST_INTERVAL_SECS
+    );
+
+    set_loopback(true);
+    // Reading the missed-frame register clears it; start from zero
+    let _ = read_reg(DMA_MISSED_FRAMES);
+
+    let mut buf = [0u8; 1600];
+    let mut frame = [0u8; 1514];
+    let mut c = SoakCounters::default();
+    let mut prev = c;
+    let mut tx_seq = 0u32;
+    let mut rx_next_seq = 0u32;
+
+    let start = Instant::now();
+    let mut last_check = start;
+    let mut last_digest = start;
+    let mut last_rx_progress = start;
+    let mut last_rx_frames = 0u64;
+
+    while start.elapsed() < duration {
+        // TX: queue a few frames, counting backpressure separately
+        for _ in 0..FRAMES_PER_TICK {
+            let len = FRAME_SIZES[(tx_seq as usize) % FRAME_SIZES.len()];
+            build_frame(&mut frame[..len], tx_seq);
+            let sent = critical_section::with(|cs| {
+                EMAC.borrow_ref_mut(cs)
+                    .as_mut()
+                    .map(|emac| emac.transmit(&frame[..len]))
+            });
+            match sent {
+                Some(Ok(_)) => {
+                    c.tx_frames += 1;
+                    tx_seq = tx_seq.wrapping_add(1);
+                }
+                Some(Err(ph_esp32_mac::Error::Dma(ph_esp32_mac::DmaError::NoDescriptorsAvailable))) => {
+                    c.tx_busy += 1;
+                    break;
+                }
+                _ => {
+                    c.tx_errors += 1;
+                    break;
+                }
+            }
+        }
+
+        drain_rx(&mut c, &mut rx_next_seq, &mut buf);
+        c.missed += u64::from(read_reg(DMA_MISSED_FRAMES) & 0xFFFF);
+
+        // Stall watchdog on the looped-back frame counter
+        if c.rx_frames != last_rx_frames {
+            last_rx_frames = c.rx_frames;
+            last_rx_progress = Instant::now();
+        } else if last_rx_progress.elapsed() > Duration::from_secs(STALL_TIMEOUT_SECS) {
+            c.stalls += 1;
+            warn!(
+                "  RX stalled for {}s (tx={}, rx={})",
+                STALL_TIMEOUT_SECS, c.tx_frames, c.rx_frames
+            );
+            last_rx_progress = Instant::now();
+        }
+
+        if last_check.elapsed() >= Duration::from_secs(CHECK_INTERVAL_SECS) {
+            run_checks(&mut c, &prev, &mut rx_next_seq, &mut buf);
+            prev = c;
+            last_check = Instant::now();
+            // The drain inside the checks pauses TX; do not count it as a stall
+            last_rx_progress = last_check;
+        }
+
+        if last_digest.elapsed() >= Duration::from_secs(DIGEST_INTERVAL_SECS) {
+            print_digest("digest", start.elapsed().as_secs(), &c);
+            last_digest = Instant::now();
+        }
+    }
+
+    run_checks(&mut c, &prev, &mut rx_next_seq, &mut buf);
+    set_loopback(false);
+    print_digest("final", start.elapsed().as_secs(), &c);
+
+    record_metric("tx_frames", c.tx_frames as i64);
+    record_metric("rx_frames", c.rx_frames as i64);
+    record_metric("missed", c.missed as i64);
+    record_metric("ring_faults", c.ring_faults as i64);
+    record_metric("leak_faults", c.leak_faults as i64);
+    record_metric("stalls", c.stalls as i64);
+    record_metric("seq_errors", (c.seq_errors + c.payload_errors) as i64);
+    record_metric("checks", c.checks as i64);
+
+    if c.tx_frames == 0 || c.rx_frames == 0 {
+        error!("  No soak traffic completed");
+        TestResult::Fail
+    } else if c.healthy() {
+        info!("  Soak completed with no faults");
+        TestResult::Pass
+    } else {
+        error!("  Soak detected faults (see digest)");
+        TestResult::Fail
+    }
+}
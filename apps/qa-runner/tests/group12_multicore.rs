@@ -0,0 +1,331 @@
+//! Group 12: Dual-Core
+//!
+//! Runs the EMAC interrupt handler on the app core (core 1) while the test
+//! task queues and reclaims frames on the pro core (core 0), the split used
+//! by applications that keep networking interrupts off the main core.
+//!
+//! | Test ID | Name | Description |
+//! |---------|------|-------------|
+//! | IT-12-001 | Cross-core ISR | ISR on core 1, TX task on core 0, MAC loopback |
+//!
+//! # Traffic
+//!
+//! The MAC is put in internal loopback. The task transmits numbered frames
+//! (EtherType `0x88B5`, magic `"MC"`, big-endian sequence, then the pattern
+//! `(seq + i) as u8`); the ISR acknowledges interrupts and drains the RX
+//! ring, checking every frame. Both cores therefore touch the descriptor
+//! rings, serialized only by `critical-section`.
+//!
+//! This relies on esp-hal's multicore-aware `critical-section` implementation
+//! and on the driver's descriptor ownership fences. A missing fence shows up
+//! as a corrupted payload or a sequence going backwards.
+
+use core::ptr::addr_of_mut;
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+use esp_hal::handler;
+use esp_hal::interrupt::{self, Priority};
+use esp_hal::peripherals::{Interrupt, CPU_CTRL};
+use esp_hal::system::{Cpu, CpuControl, Stack};
+use esp_hal::time::{Duration, Instant};
+use log::{error, info, warn};
+
+use super::framework::{record_metric, TestResult, EMAC, LOCAL_MAC, MAC_BASE};
+
+// =============================================================================
+// Configuration
+// =============================================================================
+
+/// Frames sent by the core 0 task
+const FRAMES: u32 = 2000;
+
+/// Frame length (without FCS)
+const FRAME_LEN: usize = 256;
+
+/// Time allowed for the app core to bind its interrupt
+const START_TIMEOUT_MS: u64 = 1000;
+
+/// Time allowed for the last looped-back frames to arrive
+const DRAIN_TIMEOUT_MS: u64 = 500;
+
+/// EtherType used for test frames
+const MC_ETHERTYPE: u16 = 0x88B5;
+
+/// Magic bytes at the start of every test payload
+const MC_MAGIC: [u8; 2] = *b"MC";
+
+/// Ethernet header length
+const ETH_HDR_LEN: usize = 14;
+
+/// Payload header length (magic + sequence)
+const MC_HDR_LEN: usize = 6;
+
+/// MAC configuration loopback bit
+const GMACCONFIG_LM: u32 = 1 << 12;
+
+/// App core stack (the ISR runs on it)
+static mut APP_CORE_STACK: Stack<8192> = Stack::new();
+
+// =============================================================================
+// Shared State
+// =============================================================================
+
+static APP_READY: AtomicBool = AtomicBool::new(false);
+static APP_STOP: AtomicBool = AtomicBool::new(false);
+static APP_DONE: AtomicBool = AtomicBool::new(false);
+
+static ISR_CALLS: AtomicU32 = AtomicU32::new(0);
+static ISR_WRONG_CORE: AtomicU32 = AtomicU32::new(0);
+static RX_FRAMES: AtomicU32 = AtomicU32::new(0);
+static RX_SEQ_ERRORS: AtomicU32 = AtomicU32::new(0);
+static RX_PAYLOAD_ERRORS: AtomicU32 = AtomicU32::new(0);
+/// Next expected sequence number; only the ISR touches it
+static RX_NEXT_SEQ: AtomicU32 = AtomicU32::new(0);
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+fn read_reg(addr: u32) -> u32 {
+    // SAFETY: addr is a valid, always-readable EMAC register on ESP32
+    unsafe { core::ptr::read_volatile(addr as *const u32) }
+}
+
+fn write_reg(addr: u32, value: u32) {
+    // SAFETY: addr is a valid EMAC register on ESP32
+    unsafe { core::ptr::write_volatile(addr as *mut u32, value) }
+}
+
+fn set_loopback(enable: bool) {
+    let cfg = read_reg(MAC_BASE);
+    if enable {
+        write_reg(MAC_BASE, cfg | GMACCONFIG_LM);
+    } else {
+        write_reg(MAC_BASE, cfg & !GMACCONFIG_LM);
+    }
+}
+
+fn build_frame(frame: &mut [u8], seq: u32) {
+    frame[0..6].copy_from_slice(&LOCAL_MAC);
+    frame[6..12].copy_from_slice(&LOCAL_MAC);
+    frame[12..14].copy_from_slice(&MC_ETHERTYPE.to_be_bytes());
+    frame[14..16].copy_from_slice(&MC_MAGIC);
+    frame[16..20].copy_from_slice(&seq.to_be_bytes());
+    for (i, b) in frame[ETH_HDR_LEN + MC_HDR_LEN..].iter_mut().enumerate() {
+        *b = seq.wrapping_add(i as u32) as u8;
+    }
+}
+
+/// Check one received frame, updating the RX counters
+fn check_frame(frame: &[u8]) {
+    if frame.len() < ETH_HDR_LEN + MC_HDR_LEN
+        || u16::from_be_bytes([frame[12], frame[13]]) != MC_ETHERTYPE
+        || frame[14..16] != MC_MAGIC
+    {
+        return;
+    }
+    let seq = u32::from_be_bytes([frame[16], frame[17], frame[18], frame[19]]);
+    RX_FRAMES.fetch_add(1, Ordering::Relaxed);
+
+    // Gaps are missed frames; going backwards means a stale descriptor
+    if seq < RX_NEXT_SEQ.load(Ordering::Relaxed) {
+        RX_SEQ_ERRORS.fetch_add(1, Ordering::Relaxed);
+    }
+    RX_NEXT_SEQ.store(seq.wrapping_add(1), Ordering::Relaxed);
+
+    let intact = frame[ETH_HDR_LEN + MC_HDR_LEN..]
+        .iter()
+        .enumerate()
+        .all(|(i, &b)| b == seq.wrapping_add(i as u32) as u8);
+    if !intact {
+        RX_PAYLOAD_ERRORS.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+fn reset_counters() {
+    for counter in [
+        &ISR_CALLS,
+        &ISR_WRONG_CORE,
+        &RX_FRAMES,
+        &RX_SEQ_ERRORS,
+        &RX_PAYLOAD_ERRORS,
+        &RX_NEXT_SEQ,
+    ] {
+        counter.store(0, Ordering::Relaxed);
+    }
+    APP_READY.store(false, Ordering::Relaxed);
+    APP_STOP.store(false, Ordering::Relaxed);
+    APP_DONE.store(false, Ordering::Relaxed);
+}
+
+/// Spin until `flag` is set or `timeout_ms` passes
+fn wait_for(flag: &AtomicBool, timeout_ms: u64) -> bool {
+    let start = Instant::now();
+    while !flag.load(Ordering::Acquire) {
+        if start.elapsed() > Duration::from_millis(timeout_ms) {
+            return false;
+        }
+        core::hint::spin_loop();
+    }
+    true
+}
+
+// =============================================================================
+// App Core
+// =============================================================================
+
+#[handler(priority = Priority::Priority1)]
+fn cross_core_isr() {
+    ISR_CALLS.fetch_add(1, Ordering::Relaxed);
+    if Cpu::current() != Cpu::AppCpu {
+        ISR_WRONG_CORE.fetch_add(1, Ordering::Relaxed);
+    }
+
+    let mut buf = [0u8; FRAME_LEN + 64];
+    critical_section::with(|cs| {
+        if let Some(ref mut emac) = *EMAC.borrow_ref_mut(cs) {
+            let status = emac.handle_interrupt();
+            if status.rx_complete || status.rx_buf_unavailable {
+                while let Ok(len) = emac.receive(&mut buf) {
+                    check_frame(&buf[..len]);
+                }
+            }
+        }
+    });
+}
+
+/// Entry point of the app core: own the EMAC interrupt until told to stop
+fn app_core_main() {
+    // SAFETY: the handler only touches the EMAC through critical sections
+    // and atomics
+    unsafe { interrupt::bind_interrupt(Interrupt::ETH_MAC, cross_core_isr.handler()) };
+    if interrupt::enable(Interrupt::ETH_MAC, cross_core_isr.priority()).is_err() {
+        APP_DONE.store(true, Ordering::Release);
+        return;
+    }
+    APP_READY.store(true, Ordering::Release);
+
+    while !APP_STOP.load(Ordering::Acquire) {
+        core::hint::spin_loop();
+    }
+
+    interrupt::disable(Cpu::AppCpu, Interrupt::ETH_MAC);
+    APP_DONE.store(true, Ordering::Release);
+}
+
+// =============================================================================
+// Test
+// =============================================================================
+
+/// IT-12-001: Service EMAC interrupts on core 1 while core 0 transmits
+pub fn test_cross_core_isr(cpu_ctrl: CPU_CTRL<'_>) -> TestResult {
+    reset_counters();
+    set_loopback(true);
+
+    // Core 0 must not take the interrupt
+    interrupt::disable(Cpu::ProCpu, Interrupt::ETH_MAC);
+    critical_section::with(|cs| {
+        if let Some(ref mut emac) = *EMAC.borrow_ref_mut(cs) {
+            let mut buf = [0u8; FRAME_LEN + 64];
+            while emac.receive(&mut buf).is_ok() {}
+            emac.clear_all_interrupts();
+            emac.enable_rx_interrupt(true);
+        }
+    });
+
+    let mut cpu_control = CpuControl::new(cpu_ctrl);
+    // SAFETY: the stack is only handed to the app core once per boot
+    let stack = unsafe { &mut *addr_of_mut!(APP_CORE_STACK) };
+    let guard = match cpu_control.start_app_core(stack, app_core_main) {
+        Ok(guard) => guard,
+        Err(e) => {
+            error!("  Could not start app core: {:?}", e);
+            set_loopback(false);
+            return TestResult::Fail;
+        }
+    };
+
+    if !wait_for(&APP_READY, START_TIMEOUT_MS) {
+        error!("  App core did not bind the EMAC interrupt");
+        APP_STOP.store(true, Ordering::Release);
+        drop(guard);
+        set_loopback(false);
+        return TestResult::Fail;
+    }
+    info!("  ISR bound on core 1, sending {} frames from core 0", FRAMES);
+
+    let mut frame = [0u8; FRAME_LEN];
+    let mut sent = 0u32;
+    let mut busy = 0u32;
+    let mut tx_errors = 0u32;
+    while sent < FRAMES {
+        build_frame(&mut frame, sent);
+        let result = critical_section::with(|cs| {
+            EMAC.borrow_ref_mut(cs).as_mut().map(|emac| emac.transmit(&frame))
+        });
+        match result {
+            Some(Ok(_)) => sent += 1,
+            Some(Err(ph_esp32_mac::Error::Dma(ph_esp32_mac::DmaError::NoDescriptorsAvailable))) => {
+                busy += 1;
+                core::hint::spin_loop();
+            }
+            _ => {
+                tx_errors += 1;
+                break;
+            }
+        }
+    }
+
+    // Let the last frames loop back and be drained by the ISR
+    let start = Instant::now();
+    while RX_FRAMES.load(Ordering::Relaxed) < sent
+        && start.elapsed() < Duration::from_millis(DRAIN_TIMEOUT_MS)
+    {
+        core::hint::spin_loop();
+    }
+
+    APP_STOP.store(true, Ordering::Release);
+    let stopped = wait_for(&APP_DONE, START_TIMEOUT_MS);
+    drop(guard);
+    critical_section::with(|cs| {
+        if let Some(ref mut emac) = *EMAC.borrow_ref_mut(cs) {
+            emac.enable_rx_interrupt(false);
+            emac.clear_all_interrupts();
+        }
+    });
+    set_loopback(false);
+
+    let received = RX_FRAMES.load(Ordering::Relaxed);
+    let isr_calls = ISR_CALLS.load(Ordering::Relaxed);
+    let wrong_core = ISR_WRONG_CORE.load(Ordering::Relaxed);
+    let seq_errors = RX_SEQ_ERRORS.load(Ordering::Relaxed);
+    let payload_errors = RX_PAYLOAD_ERRORS.load(Ordering::Relaxed);
+    info!(
+        "  sent={} received={} busy={} isr_calls={} seq_errors={} payload_errors={}",
+        sent, received, busy, isr_calls, seq_errors, payload_errors
+    );
+
+    record_metric("tx_frames", i64::from(sent));
+    record_metric("rx_frames", i64::from(received));
+    record_metric("isr_calls", i64::from(isr_calls));
+    record_metric("seq_errors", i64::from(seq_errors + payload_errors));
+
+    if !stopped {
+        warn!("  App core did not acknowledge stop");
+    }
+    if tx_errors > 0 || received == 0 || isr_calls == 0 {
+        error!("  No cross-core traffic completed");
+        TestResult::Fail
+    } else if wrong_core > 0 {
+        error!("  {} interrupts ran on core 0", wrong_core);
+        TestResult::Fail
+    } else if seq_errors > 0 || payload_errors > 0 {
+        error!("  Frames corrupted or reordered across cores");
+        TestResult::Fail
+    } else {
+        if received < sent {
+            warn!("  {} frames missed (ring overrun, not a fault)", sent - received);
+        }
+        TestResult::Pass
+    }
+}
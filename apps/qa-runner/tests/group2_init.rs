@@ -13,10 +13,10 @@ use log::{error, info};
 use ph_esp32_mac::Emac;
 use ph_esp32_mac::boards::wt32_eth01::Wt32Eth01 as Board;
 
-use super::framework::{TestContext, TestResult, EMAC, IO_MUX_BASE, DMA_BASE};
+use super::framework::{TestContext, TestResult, EMAC, IO_MUX_BASE, DMA_BASE, LOCAL_MAC};
 /// IT-2-001: Test EMAC initialization with board-specific configuration
 pub fn test_emac_init(ctx: &mut TestContext) -> TestResult {
-    let config = Board::emac_config_with_mac([0x02, 0x00, 0x00, 0x12, 0x34, 0x56]);
+    let config = Board::emac_config_with_mac(LOCAL_MAC);
 
     // Place EMAC in static location BEFORE init (required for DMA descriptors)
     critical_section::with(|cs| {
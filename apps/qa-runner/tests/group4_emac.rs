@@ -8,10 +8,12 @@
 //! | IT-4-002 | Packet TX | Transmit broadcast frame |
 //! | IT-4-003 | Packet RX | Receive packets (timed) |
 //! | IT-4-004 | EMAC stop/start | Stop and restart cycle |
+//! | IT-4-005 | RX path timing | Poll/peek/receive cost per looped-back frame |
 
+use esp_hal::time::{Duration, Instant};
 use log::{error, info, warn};
 
-use super::framework::{TestResult, EMAC};
+use super::framework::{record_metric, TestResult, EMAC, LOCAL_MAC, MAC_BASE};
 
 /// IT-4-001: Test EMAC can be started
 pub fn test_emac_start() -> TestResult {
@@ -95,6 +97,7 @@ pub fn test_packet_rx(duration_secs: u32) -> TestResult {
     }
     
     info!("  Received {} packets", packet_count);
+    record_metric("rx_frames", packet_count as i64);
     
     if packet_count > 0 {
         TestResult::Pass
@@ -142,3 +145,91 @@ pub fn test_emac_stop_start() -> TestResult {
         }
     }
 }
+
+// =============================================================================
+// RX Path Timing
+// =============================================================================
+
+/// Batches looped back for IT-4-005 (one ring's worth each)
+const TIMING_BATCHES: u32 = 250;
+
+/// Frames per batch (matches the static EMAC's RX ring)
+const TIMING_BATCH: u32 = 4;
+
+/// MAC configuration loopback bit
+const GMACCONFIG_LM: u32 = 1 << 12;
+
+fn set_loopback(enable: bool) {
+    // SAFETY: MAC_BASE is the always-accessible MAC configuration register
+    unsafe {
+        let cfg = core::ptr::read_volatile(MAC_BASE as *const u32);
+        let cfg = if enable { cfg | GMACCONFIG_LM } else { cfg & !GMACCONFIG_LM };
+        core::ptr::write_volatile(MAC_BASE as *mut u32, cfg);
+    }
+}
+
+/// IT-4-005: Time the RX hot path on target
+///
+/// Frames are looped back by the MAC until a ring's worth is waiting, then
+/// drained the way applications do: `rx_available()`, `peek_rx_length()`,
+/// `receive()`. Only the drain is timed. The `rx_ns_per_frame` metric is
+/// meant for before/after comparisons of driver changes on the same board.
+pub fn test_rx_path_timing() -> TestResult {
+    set_loopback(true);
+    let mut frame = [0u8; 64];
+    frame[0..6].copy_from_slice(&LOCAL_MAC);
+    frame[6..12].copy_from_slice(&LOCAL_MAC);
+    frame[12..14].copy_from_slice(&[0x88, 0xB5]);
+    let mut buf = [0u8; 1600];
+
+    let mut frames = 0u32;
+    let mut elapsed = Duration::from_micros(0);
+    for _ in 0..TIMING_BATCHES {
+        let batch = critical_section::with(|cs| {
+            let mut guard = EMAC.borrow_ref_mut(cs);
+            let emac = guard.as_mut()?;
+            while emac.receive(&mut buf).is_ok() {}
+            for _ in 0..TIMING_BATCH {
+                emac.transmit(&frame).ok()?;
+            }
+            Some(())
+        });
+        if batch.is_none() {
+            break;
+        }
+
+        let wait = Instant::now();
+        while critical_section::with(|cs| {
+            EMAC.borrow_ref(cs).as_ref().map_or(0, |emac| emac.rx_frames_waiting())
+        }) < TIMING_BATCH as usize
+        {
+            if wait.elapsed() > Duration::from_millis(10) {
+                break;
+            }
+        }
+
+        critical_section::with(|cs| {
+            if let Some(ref mut emac) = *EMAC.borrow_ref_mut(cs) {
+                let start = Instant::now();
+                while emac.rx_available() {
+                    let _ = emac.peek_rx_length();
+                    if emac.receive(&mut buf).is_ok() {
+                        frames += 1;
+                    }
+                }
+                elapsed += start.elapsed();
+            }
+        });
+    }
+    set_loopback(false);
+
+    if frames == 0 {
+        error!("  No frames looped back");
+        return TestResult::Fail;
+    }
+    let ns_per_frame = elapsed.as_micros() * 1000 / u64::from(frames);
+    info!("  {} frames drained, {} ns/frame", frames, ns_per_frame);
+    record_metric("rx_frames", i64::from(frames));
+    record_metric("rx_ns_per_frame", ns_per_frame as i64);
+    TestResult::Pass
+}
@@ -17,6 +17,12 @@
 //! | 7 | IT-7-xxx | State & Interrupts |
 //! | 8 | IT-8-xxx | Advanced Features |
 //! | 9 | IT-9-xxx | Edge Cases |
+//! | 10 | IT-10-xxx | Two-Board Peer (optional) |
+//! | 11 | IT-11-xxx | Soak (optional) |
+//! | 12 | IT-12-xxx | Dual-Core |
+//!
+//! Tests are listed as descriptors in [`registry`], which also handles the
+//! `QA_GROUPS` group selection.
 
 pub mod framework;
 pub mod group1_register;
@@ -28,6 +34,12 @@ pub mod group6_smoltcp;
 pub mod group7_state;
 pub mod group8_advanced;
 pub mod group9_edge;
+#[cfg(any(feature = "peer-sender", feature = "peer-responder"))]
+pub mod group10_peer;
+#[cfg(feature = "soak")]
+pub mod group11_soak;
+pub mod group12_multicore;
+pub mod registry;
 
 // Re-export everything needed
 pub use framework::*;
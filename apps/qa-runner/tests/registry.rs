@@ -0,0 +1,415 @@
+//! Test Registry
+//!
+//! Every test the runner knows about, as descriptors grouped in run order,
+//! plus the group selection applied at boot.
+//!
+//! # Group Selection
+//!
+//! Set `QA_GROUPS` at build time to run a subset of the groups:
+//!
+//! ```ignore
+//! QA_GROUPS=fast cargo xtask expect qa-runner      # per-commit
+//! QA_GROUPS=4,9 cargo xtask run qa-runner          # just groups 4 and 9
+//! QA_GROUPS=fast,8 cargo xtask run qa-runner       # fast groups plus 8
+//! cargo xtask run qa-runner                        # everything (nightly)
+//! ```
+//!
+//! `fast` selects the groups without fixed listening windows or traffic
+//! loops (see [`TestGroup::fast`]). The setup tests that bring up the EMAC
+//! and the link (IT-2-001, IT-3-002, IT-3-003) run whatever is selected,
+//! since nearly every other test depends on them.
+
+use log::{error, info, warn};
+
+use super::framework::{emit_record, TestContext, TestResult, TestStats};
+use super::{
+    group1_register, group2_init, group3_phy, group4_emac, group5_link, group6_smoltcp,
+    group7_state, group8_advanced, group9_edge, group12_multicore,
+};
+#[cfg(feature = "peer-sender")]
+use super::group10_peer;
+#[cfg(feature = "soak")]
+use super::group11_soak;
+
+// =============================================================================
+// Descriptors
+// =============================================================================
+
+/// State a test needs before it can run
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Requires {
+    /// Runs on a bare board
+    Nothing,
+    /// EMAC initialized (IT-2-001 passed)
+    Emac,
+    /// EMAC initialized and link up (IT-3-003 passed)
+    Link,
+    /// A peer responder answered discovery (IT-10-001 passed)
+    #[cfg(feature = "peer-sender")]
+    Peer,
+}
+
+impl Requires {
+    /// True if `ctx` satisfies the requirement
+    pub fn met(&self, ctx: &TestContext) -> bool {
+        match self {
+            Requires::Nothing => true,
+            Requires::Emac => ctx.emac_initialized,
+            Requires::Link => ctx.emac_initialized && ctx.link_up,
+            #[cfg(feature = "peer-sender")]
+            Requires::Peer => ctx.emac_initialized && ctx.link_up && ctx.peer_found,
+        }
+    }
+
+    /// Reason logged when a test is skipped
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Requires::Nothing => "nothing",
+            Requires::Emac => "EMAC init",
+            Requires::Link => "EMAC init and link",
+            #[cfg(feature = "peer-sender")]
+            Requires::Peer => "a peer responder",
+        }
+    }
+}
+
+/// One test
+pub struct TestDescriptor {
+    /// Stable ID, `IT-{GROUP}-{NUMBER}`
+    pub id: &'static str,
+    /// Short name for the log
+    pub name: &'static str,
+    /// Prerequisite; the test is recorded as skipped when it is not met
+    pub requires: Requires,
+    /// Runs even when its group is not selected
+    pub setup: bool,
+    /// Test body
+    pub run: fn(&mut TestContext) -> TestResult,
+}
+
+/// A numbered group of tests
+pub struct TestGroup {
+    /// Group number, as in the test IDs
+    pub number: u8,
+    /// Heading printed before the group
+    pub name: &'static str,
+    /// Part of the `fast` selection: no fixed listening windows, traffic
+    /// loops, or second board
+    pub fast: bool,
+    /// Tests in run order
+    pub tests: &'static [TestDescriptor],
+}
+
+/// Descriptor for a test with no setup role
+const fn test(
+    id: &'static str,
+    name: &'static str,
+    requires: Requires,
+    run: fn(&mut TestContext) -> TestResult,
+) -> TestDescriptor {
+    TestDescriptor {
+        id,
+        name,
+        requires,
+        setup: false,
+        run,
+    }
+}
+
+/// Descriptor for a setup test that always runs
+const fn setup(
+    id: &'static str,
+    name: &'static str,
+    requires: Requires,
+    run: fn(&mut TestContext) -> TestResult,
+) -> TestDescriptor {
+    TestDescriptor {
+        id,
+        name,
+        requires,
+        setup: true,
+        run,
+    }
+}
+
+// =============================================================================
+// Registry
+// =============================================================================
+
+/// All groups, in run order
+pub static GROUPS: &[TestGroup] = &[
+    TestGroup {
+        number: 1,
+        name: "Register Access",
+        fast: true,
+        tests: &[
+            test("IT-1-001", "EMAC clock enable", Requires::Nothing, |_| group1_register::test_emac_clock_enable()),
+            test("IT-1-002", "DMA registers", Requires::Nothing, |_| group1_register::test_dma_registers_accessible()),
+            test("IT-1-003", "MAC registers", Requires::Nothing, |_| group1_register::test_mac_registers_accessible()),
+            test("IT-1-004", "Extension registers", Requires::Nothing, |_| group1_register::test_extension_registers()),
+        ],
+    },
+    TestGroup {
+        number: 2,
+        name: "EMAC Initialization",
+        fast: true,
+        tests: &[
+            setup("IT-2-001", "EMAC init", Requires::Nothing, group2_init::test_emac_init),
+            test("IT-2-002", "RMII pin config", Requires::Emac, |_| group2_init::test_rmii_pins()),
+            test("IT-2-003", "DMA descriptor chain", Requires::Emac, |_| group2_init::test_dma_descriptor_chain()),
+        ],
+    },
+    TestGroup {
+        number: 3,
+        name: "PHY Communication",
+        fast: true,
+        tests: &[
+            test("IT-3-001", "PHY MDIO read", Requires::Nothing, group3_phy::test_phy_mdio_read),
+            setup("IT-3-002", "PHY init", Requires::Nothing, group3_phy::test_phy_init),
+            setup("IT-3-003", "PHY link up", Requires::Nothing, |ctx| group3_phy::test_phy_link_up(ctx, 5000)),
+        ],
+    },
+    TestGroup {
+        number: 4,
+        name: "EMAC Operations",
+        fast: false,
+        tests: &[
+            test("IT-4-001", "EMAC start", Requires::Link, |_| group4_emac::test_emac_start()),
+            test("IT-4-002", "Packet TX", Requires::Link, |_| group4_emac::test_packet_tx()),
+            test("IT-4-003", "Packet RX (3s)", Requires::Link, |_| group4_emac::test_packet_rx(3)),
+            test("IT-4-004", "EMAC stop/start", Requires::Link, |_| group4_emac::test_emac_stop_start()),
+            test("IT-4-005", "RX path timing", Requires::Link, |_| group4_emac::test_rx_path_timing()),
+        ],
+    },
+    TestGroup {
+        number: 5,
+        name: "Link Status",
+        fast: true,
+        tests: &[
+            test("IT-5-001", "Link status query", Requires::Nothing, group5_link::test_link_status_query),
+        ],
+    },
+    TestGroup {
+        number: 6,
+        name: "smoltcp Integration",
+        fast: true,
+        tests: &[
+            test("IT-6-001", "Interface creation", Requires::Link, |_| group6_smoltcp::test_interface_creation()),
+            test("IT-6-002", "Device capabilities", Requires::Link, |_| group6_smoltcp::test_device_capabilities()),
+            test("IT-6-003", "Interface poll", Requires::Link, |_| group6_smoltcp::test_interface_poll()),
+        ],
+    },
+    TestGroup {
+        number: 7,
+        name: "State & Interrupts",
+        fast: true,
+        tests: &[
+            test("IT-7-001", "State transitions", Requires::Link, |_| group7_state::test_state_transitions()),
+            test("IT-7-002", "State stop changes", Requires::Link, |_| group7_state::test_state_stop_changes()),
+            test("IT-7-003", "TX ready", Requires::Link, |_| group7_state::test_tx_ready()),
+            test("IT-7-004", "Can transmit sizes", Requires::Link, |_| group7_state::test_can_transmit()),
+            test("IT-7-005", "TX backpressure", Requires::Link, |_| group7_state::test_tx_backpressure()),
+            test("IT-7-006", "Peek RX length", Requires::Link, |_| group7_state::test_peek_rx_length()),
+            test("IT-7-007", "RX frames waiting", Requires::Link, |_| group7_state::test_rx_frames_waiting()),
+            test("IT-7-008", "Interrupt status", Requires::Link, |_| group7_state::test_interrupt_status()),
+            test("IT-7-009", "Interrupt clear", Requires::Link, |_| group7_state::test_interrupt_clear()),
+            test("IT-7-010", "Handle interrupt", Requires::Link, |_| group7_state::test_handle_interrupt()),
+            test("IT-7-011", "Frame sizes TX", Requires::Link, |_| group7_state::test_frame_sizes()),
+        ],
+    },
+    TestGroup {
+        number: 8,
+        name: "Advanced Features",
+        fast: false,
+        tests: &[
+            test("IT-8-001", "Promiscuous mode", Requires::Link, |_| group8_advanced::test_promiscuous_mode()),
+            test("IT-8-002", "Promiscuous RX", Requires::Link, |_| group8_advanced::test_promiscuous_rx(2000)),
+            test("IT-8-003", "PHY capabilities", Requires::Link, group8_advanced::test_phy_capabilities),
+            test("IT-8-004", "Force link", Requires::Link, group8_advanced::test_force_link),
+            test("IT-8-005", "Enable TX interrupt", Requires::Link, |_| group8_advanced::test_enable_tx_interrupt()),
+            test("IT-8-006", "Enable RX interrupt", Requires::Link, |_| group8_advanced::test_enable_rx_interrupt()),
+            test("IT-8-007", "TX interrupt fires", Requires::Link, |_| group8_advanced::test_tx_interrupt_fires()),
+        ],
+    },
+    TestGroup {
+        number: 9,
+        name: "Edge Cases",
+        fast: false,
+        tests: &[
+            test("IT-9-001", "MAC filtering", Requires::Link, |_| group9_edge::test_mac_filtering()),
+            test("IT-9-002", "MAC filter multiple", Requires::Link, |_| group9_edge::test_mac_filter_multiple()),
+            test("IT-9-003", "Hash filtering", Requires::Link, |_| group9_edge::test_hash_filtering()),
+            test("IT-9-004", "Pass all multicast", Requires::Link, |_| group9_edge::test_pass_all_multicast()),
+            test("IT-9-005", "VLAN filtering", Requires::Link, |_| group9_edge::test_vlan_filtering()),
+            test("IT-9-006", "Flow control config", Requires::Link, |_| group9_edge::test_flow_control_config()),
+            test("IT-9-007", "Flow control check", Requires::Link, |_| group9_edge::test_flow_control_check()),
+            test("IT-9-008", "PHY energy detect", Requires::Link, group9_edge::test_energy_detect),
+            test("IT-9-009", "RX interrupt fires", Requires::Link, |_| group9_edge::test_rx_interrupt_fires(2000)),
+            test("IT-9-010", "Async wakers", Requires::Link, |_| group9_edge::test_async_wakers()),
+            test("IT-9-011", "Restore RX state", Requires::Link, |_| group9_edge::test_restore_rx_state()),
+        ],
+    },
+    TestGroup {
+        number: 12,
+        name: "Dual-Core",
+        fast: false,
+        tests: &[
+            test("IT-12-001", "Cross-core ISR", Requires::Link, |ctx| match ctx.cpu_ctrl.take() {
+                Some(cpu_ctrl) => group12_multicore::test_cross_core_isr(cpu_ctrl),
+                None => {
+                    warn!("  CPU_CTRL already taken");
+                    TestResult::Skip
+                }
+            }),
+        ],
+    },
+    #[cfg(feature = "peer-sender")]
+    TestGroup {
+        number: 10,
+        name: "Two-Board Peer",
+        fast: false,
+        tests: &[
+            test("IT-10-001", "Peer discovery", Requires::Link, |ctx| {
+                let result = group10_peer::test_peer_discovery(&mut ctx.peer, 10_000);
+                ctx.peer_found = result == TestResult::Pass;
+                result
+            }),
+            test("IT-10-002", "Unicast filtering", Requires::Peer, |ctx| group10_peer::test_peer_unicast_filtering(&mut ctx.peer)),
+            test("IT-10-003", "Frame-size sweep", Requires::Peer, |ctx| group10_peer::test_peer_frame_size_sweep(&mut ctx.peer)),
+            test("IT-10-004", "Flow control load", Requires::Peer, |ctx| group10_peer::test_peer_flow_control_load(&mut ctx.peer)),
+            test("IT-10-005", "Forced link modes", Requires::Peer, |ctx| {
+                let mut peer = core::mem::replace(&mut ctx.peer, group10_peer::PeerSession::new());
+                let result = group10_peer::test_peer_forced_modes(&mut peer, ctx);
+                ctx.peer = peer;
+                result
+            }),
+        ],
+    },
+    #[cfg(feature = "soak")]
+    TestGroup {
+        number: 11,
+        name: "Soak",
+        fast: false,
+        tests: &[test("IT-11-001", "Soak", Requires::Link, |_| group11_soak::test_soak())],
+    },
+];
+
+// =============================================================================
+// Selection
+// =============================================================================
+
+/// Groups chosen with `QA_GROUPS`
+#[derive(Clone, Copy)]
+pub struct Selection {
+    /// Every group (no `QA_GROUPS`, or `all`)
+    all: bool,
+    /// The `fast` groups
+    fast: bool,
+    /// Explicit group numbers, one bit per group
+    groups: u32,
+}
+
+impl Selection {
+    /// Parse a comma-separated list of `all`, `fast`, and group numbers
+    ///
+    /// Unknown entries are logged and ignored; an empty list selects all.
+    pub fn parse(spec: &str) -> Self {
+        let mut selection = Self {
+            all: false,
+            fast: false,
+            groups: 0,
+        };
+        for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            match entry {
+                "all" => selection.all = true,
+                "fast" => selection.fast = true,
+                _ => match entry.parse::<u8>() {
+                    Ok(number) if number < 32 => selection.groups |= 1 << number,
+                    _ => warn!("QA_GROUPS: ignoring unknown entry '{}'", entry),
+                },
+            }
+        }
+        if !selection.fast && selection.groups == 0 {
+            selection.all = true;
+        }
+        selection
+    }
+
+    /// Selection from the build-time `QA_GROUPS` variable
+    pub fn from_env() -> Self {
+        Self::parse(option_env!("QA_GROUPS").unwrap_or("all"))
+    }
+
+    /// True if the tests of `group` should run
+    pub fn includes(&self, group: &TestGroup) -> bool {
+        self.all || (self.fast && group.fast) || self.groups & (1 << group.number) != 0
+    }
+
+    /// Log the groups that will run
+    pub fn log(&self) {
+        if self.all {
+            info!("Test groups: all");
+            return;
+        }
+        info!("Test groups (QA_GROUPS={}):", option_env!("QA_GROUPS").unwrap_or(""));
+        for group in GROUPS.iter().filter(|group| self.includes(group)) {
+            info!("  {:>2}: {}", group.number, group.name);
+        }
+    }
+}
+
+// =============================================================================
+// Runner
+// =============================================================================
+
+/// Run the selected tests of `group`, plus its setup tests
+///
+/// Returns without printing anything if nothing in the group runs.
+pub fn run_group(group: &TestGroup, selection: &Selection, ctx: &mut TestContext, stats: &mut TestStats) {
+    let selected = selection.includes(group);
+    if !selected && !group.tests.iter().any(|test| test.setup) {
+        return;
+    }
+
+    info!("");
+    info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    if selected {
+        info!("  GROUP {}: {}", group.number, group.name);
+    } else {
+        info!("  GROUP {}: {} (setup only)", group.number, group.name);
+    }
+    info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+
+    for test in group.tests.iter().filter(|test| selected || test.setup) {
+        run_test(test, ctx, stats);
+    }
+}
+
+/// Run a single test, log the result, and record statistics
+///
+/// A test whose [`Requires`] is not met is recorded as skipped without
+/// running. With the `structured-results` feature, a `QA-RESULT` line is
+/// emitted either way (see [`emit_record`]).
+pub fn run_test(test: &TestDescriptor, ctx: &mut TestContext, stats: &mut TestStats) -> TestResult {
+    info!("");
+    info!("▶ [{}] {}", test.id, test.name);
+
+    if !test.requires.met(ctx) {
+        warn!("  Skipping - requires {}", test.requires.as_str());
+        emit_record(test.id, test.name, TestResult::Skip, 0);
+        stats.record(TestResult::Skip);
+        return TestResult::Skip;
+    }
+
+    let start = esp_hal::time::Instant::now();
+    let result = (test.run)(ctx);
+    let duration_ms = start.elapsed().as_millis();
+    match result {
+        TestResult::Pass => info!("  {} PASS", result.symbol()),
+        TestResult::Fail => error!("  {} FAIL", result.symbol()),
+        TestResult::Skip => warn!("  {} SKIP", result.symbol()),
+    }
+    emit_record(test.id, test.name, result, duration_ms);
+    stats.record(result);
+    result
+}
@@ -0,0 +1,327 @@
+//! Rate-limited frame hexdumps for debug logging.
+//!
+//! [`hexdump_frame`] logs one compact line per frame: source, destination,
+//! EtherType, length and the first few bytes, through `defmt` or `log`
+//! (whichever is enabled; with neither it does nothing). [`FrameDump`] is the
+//! formatting part on its own, implementing both `Display` and
+//! `defmt::Format`, for callers that log through something else.
+//!
+//! Dumping every frame in the RX path quickly saturates an RTT or UART log
+//! link. [`FrameDumper`] puts a [`RateLimiter`] in front and reports how many
+//! frames it skipped with the next line it prints.
+//!
+//! Like [`Shaper`](crate::Shaper), the limiter has no clock of its own; the
+//! caller passes the current time in milliseconds.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use ph_esp32_mac::debug::FrameDumper;
+//!
+//! // At most 5 frames per second, 32 bytes each
+//! let mut dumper = FrameDumper::new(5, 1000, 32);
+//!
+//! if let Ok(len) = emac.receive(&mut buf) {
+//!     dumper.dump(&buf[..len], now_ms());
+//! }
+//! ```
+
+use core::fmt;
+
+/// Dump length that keeps a line short while covering the IP header
+pub const DEFAULT_DUMP_BYTES: usize = 32;
+
+// =============================================================================
+// FrameDump
+// =============================================================================
+
+/// One-line summary of an Ethernet frame
+///
+/// Formats as `02:00:00:00:00:01 > ff:ff:ff:ff:ff:ff 0x0806 len 60: 00 01 ..`,
+/// showing at most `max_bytes` bytes of the frame after the header; `..`
+/// marks a truncated dump. Frames shorter than an Ethernet header are shown
+/// as `runt len N: ..`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameDump<'a> {
+    frame: &'a [u8],
+    max_bytes: usize,
+}
+
+impl<'a> FrameDump<'a> {
+    /// Summarize `frame`, showing at most `max_bytes` bytes of its contents
+    #[must_use]
+    pub const fn new(frame: &'a [u8], max_bytes: usize) -> Self {
+        Self { frame, max_bytes }
+    }
+
+    /// Destination MAC address, if the frame has a full header
+    #[must_use]
+    pub fn destination(&self) -> Option<[u8; 6]> {
+        self.header().map(|(dst, _, _)| dst)
+    }
+
+    /// Source MAC address, if the frame has a full header
+    #[must_use]
+    pub fn source(&self) -> Option<[u8; 6]> {
+        self.header().map(|(_, src, _)| src)
+    }
+
+    /// EtherType (or 802.3 length), if the frame has a full header
+    #[must_use]
+    pub fn ethertype(&self) -> Option<u16> {
+        self.header().map(|(_, _, ethertype)| ethertype)
+    }
+
+    /// Bytes after the header that the dump shows
+    #[must_use]
+    pub fn shown(&self) -> &'a [u8] {
+        let body = self.frame.get(14..).unwrap_or(self.frame);
+        &body[..body.len().min(self.max_bytes)]
+    }
+
+    /// True if the dump leaves bytes out
+    #[must_use]
+    pub fn is_truncated(&self) -> bool {
+        self.shown().len() < self.frame.get(14..).unwrap_or(self.frame).len()
+    }
+
+    fn header(&self) -> Option<([u8; 6], [u8; 6], u16)> {
+        let header = self.frame.first_chunk::<14>()?;
+        let mut dst = [0u8; 6];
+        let mut src = [0u8; 6];
+        dst.copy_from_slice(&header[..6]);
+        src.copy_from_slice(&header[6..12]);
+        Some((dst, src, u16::from_be_bytes([header[12], header[13]])))
+    }
+}
+
+/// Write a MAC address as `aa:bb:cc:dd:ee:ff`
+fn write_mac(f: &mut fmt::Formatter<'_>, mac: &[u8; 6]) -> fmt::Result {
+    write!(
+        f,
+        "{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}",
+        mac[0], mac[1], mac[2], mac[3], mac[4], mac[5]
+    )
+}
+
+impl fmt::Display for FrameDump<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.header() {
+            Some((dst, src, ethertype)) => {
+                write_mac(f, &src)?;
+                f.write_str(" > ")?;
+                write_mac(f, &dst)?;
+                write!(f, " {ethertype:#06x} len {}:", self.frame.len())?;
+            }
+            None => write!(f, "runt len {}:", self.frame.len())?,
+        }
+        for byte in self.shown() {
+            write!(f, " {byte:02x}")?;
+        }
+        if self.is_truncated() {
+            f.write_str(" ..")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for FrameDump<'_> {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        let more = if self.is_truncated() { " .." } else { "" };
+        match self.header() {
+            Some((dst, src, ethertype)) => defmt::write!(
+                f,
+                "{=[u8]:02x} > {=[u8]:02x} {=u16:#06x} len {=usize}: {=[u8]:02x}{=str}",
+                src,
+                dst,
+                ethertype,
+                self.frame.len(),
+                self.shown(),
+                more
+            ),
+            None => defmt::write!(
+                f,
+                "runt len {=usize}: {=[u8]:02x}{=str}",
+                self.frame.len(),
+                self.shown(),
+                more
+            ),
+        }
+    }
+}
+
+/// Log a one-line summary of `frame` at debug level
+///
+/// Shows at most `max_bytes` bytes after the header. Goes to `defmt` and/or
+/// `log`; compiles to nothing when neither feature is enabled. Use a
+/// [`FrameDumper`] in hot paths.
+#[cfg_attr(not(any(feature = "defmt", feature = "log")), allow(unused_variables))]
+pub fn hexdump_frame(frame: &[u8], max_bytes: usize) {
+    #[cfg(feature = "defmt")]
+    defmt::debug!("{}", FrameDump::new(frame, max_bytes));
+    #[cfg(feature = "log")]
+    log::debug!("{}", FrameDump::new(frame, max_bytes));
+}
+
+// =============================================================================
+// Rate Limiting
+// =============================================================================
+
+/// Allows at most `burst` events per `window_ms` milliseconds
+///
+/// Fixed windows: the first event after a window has elapsed opens a new
+/// one. Elapsed time uses wrapping arithmetic, so a free-running 32-bit
+/// millisecond counter works.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RateLimiter {
+    burst: u32,
+    window_ms: u32,
+    window_start: Option<u32>,
+    used: u32,
+    suppressed: u32,
+}
+
+impl RateLimiter {
+    /// Create a limiter for `burst` events per `window_ms` milliseconds
+    #[must_use]
+    pub const fn new(burst: u32, window_ms: u32) -> Self {
+        Self {
+            burst,
+            window_ms,
+            window_start: None,
+            used: 0,
+            suppressed: 0,
+        }
+    }
+
+    /// Record an event at `now_ms`; `true` if it is within the limit
+    pub fn allow(&mut self, now_ms: u32) -> bool {
+        let expired = self
+            .window_start
+            .is_none_or(|start| now_ms.wrapping_sub(start) >= self.window_ms);
+        if expired {
+            self.window_start = Some(now_ms);
+            self.used = 0;
+        }
+        if self.used < self.burst {
+            self.used += 1;
+            true
+        } else {
+            self.suppressed = self.suppressed.wrapping_add(1);
+            false
+        }
+    }
+
+    /// Events refused since the last call, which resets the count
+    pub fn take_suppressed(&mut self) -> u32 {
+        core::mem::take(&mut self.suppressed)
+    }
+}
+
+/// [`hexdump_frame`] behind a [`RateLimiter`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FrameDumper {
+    limiter: RateLimiter,
+    max_bytes: usize,
+}
+
+impl FrameDumper {
+    /// Dump at most `burst` frames per `window_ms`, `max_bytes` bytes each
+    #[must_use]
+    pub const fn new(burst: u32, window_ms: u32, max_bytes: usize) -> Self {
+        Self {
+            limiter: RateLimiter::new(burst, window_ms),
+            max_bytes,
+        }
+    }
+
+    /// Log `frame` if the rate limit allows it
+    ///
+    /// A line reporting the number of skipped frames precedes the first
+    /// dump after a suppressed stretch. Returns `true` if the frame was
+    /// logged.
+    pub fn dump(&mut self, frame: &[u8], now_ms: u32) -> bool {
+        if !self.limiter.allow(now_ms) {
+            return false;
+        }
+        let skipped = self.limiter.take_suppressed();
+        if skipped > 0 {
+            #[cfg(feature = "defmt")]
+            defmt::debug!("({=u32} frames not dumped)", skipped);
+            #[cfg(feature = "log")]
+            log::debug!("({skipped} frames not dumped)");
+        }
+        hexdump_frame(frame, self.max_bytes);
+        true
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::std_instead_of_alloc)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use std::format;
+
+    fn arp_frame() -> [u8; 60] {
+        let mut f = [0u8; 60];
+        f[..6].copy_from_slice(&[0xFF; 6]);
+        f[6..12].copy_from_slice(&[0x02, 0, 0, 0, 0, 0x01]);
+        f[12..16].copy_from_slice(&[0x08, 0x06, 0x00, 0x01]);
+        f
+    }
+
+    #[test]
+    fn formats_header_and_truncates() {
+        let frame = arp_frame();
+        let dump = FrameDump::new(&frame, 2);
+        assert_eq!(dump.ethertype(), Some(0x0806));
+        assert_eq!(dump.destination(), Some([0xFF; 6]));
+        assert_eq!(
+            format!("{dump}"),
+            "02:00:00:00:00:01 > ff:ff:ff:ff:ff:ff 0x0806 len 60: 00 01 .."
+        );
+
+        let whole = FrameDump::new(&frame[..16], 8);
+        assert!(!whole.is_truncated());
+        assert!(format!("{whole}").ends_with("len 16: 00 01"));
+    }
+
+    #[test]
+    fn runt_frames() {
+        let dump = FrameDump::new(&[0xAA, 0xBB, 0xCC], 2);
+        assert_eq!(dump.source(), None);
+        assert_eq!(format!("{dump}"), "runt len 3: aa bb ..");
+    }
+
+    #[test]
+    fn rate_limiter_windows() {
+        let mut limiter = RateLimiter::new(2, 100);
+        assert!(limiter.allow(0));
+        assert!(limiter.allow(10));
+        assert!(!limiter.allow(50));
+        assert!(!limiter.allow(99));
+        assert_eq!(limiter.take_suppressed(), 2);
+        assert_eq!(limiter.take_suppressed(), 0);
+        assert!(limiter.allow(100));
+
+        // Wrapping millisecond counter
+        let mut limiter = RateLimiter::new(1, 100);
+        assert!(limiter.allow(u32::MAX - 10));
+        assert!(!limiter.allow(u32::MAX));
+        assert!(limiter.allow(89));
+    }
+
+    #[test]
+    fn dumper_respects_limit() {
+        let mut dumper = FrameDumper::new(1, 1000, DEFAULT_DUMP_BYTES);
+        let frame = arp_frame();
+        assert!(dumper.dump(&frame, 0));
+        assert!(!dumper.dump(&frame, 500));
+        assert!(dumper.dump(&frame, 1000));
+    }
+}
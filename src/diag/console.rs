@@ -0,0 +1,917 @@
+//! Authenticated debug console over a raw EtherType.
+//!
+//! [`DebugConsole`] answers requests from a host tool on the local segment:
+//! driver statistics, ring occupancy, EMAC register reads (and, if allowed,
+//! writes) and a PHY auto-negotiation restart. It lets field units be
+//! inspected without a serial cable, using nothing but the raw frame API.
+//!
+//! # Wire Format
+//!
+//! Requests and replies are Ethernet frames with EtherType
+//! [`CONSOLE_ETHERTYPE`] (IEEE local experimental). After the Ethernet
+//! header:
+//!
+//! | Offset | Size | Field                                   |
+//! |--------|------|-----------------------------------------|
+//! | 0      | 1    | Version ([`CONSOLE_VERSION`])           |
+//! | 1      | 1    | Opcode ([`Opcode`]; replies set `0x80`) |
+//! | 2      | 2    | Sequence number (big endian)            |
+//! | 4      | 2    | Payload length (big endian)             |
+//! | 6      | 8    | Challenge (big endian)                  |
+//! | 14     | 8    | Authentication tag                      |
+//! | 22     | n    | Payload                                 |
+//!
+//! The tag is SipHash-2-4 under a shared 128-bit key, over the Ethernet
+//! addresses, bytes 0-13 and the payload. Replies echo the sequence number,
+//! start their payload with a [`Status`] byte and are tagged with the same
+//! key.
+//!
+//! # Replay Protection
+//!
+//! Every reply carries the device's current challenge, and every request
+//! except [`Opcode::Hello`] must echo it. The device issues a new challenge
+//! after each request it accepts, so a captured request is only good until
+//! the next one. A host starts with `Hello`, which is answered whatever
+//! challenge it carries, and then uses the challenge from each reply for
+//! its next request. Requests with any other challenge are dropped and
+//! counted in [`ConsoleStats::replays`].
+//!
+//! Challenges are derived from the key, the seed passed to
+//! [`DebugConsole::new`] and a counter. They only stay unique across
+//! reboots if the seed differs on every boot, e.g. a value from the
+//! hardware RNG.
+//!
+//! The tag only proves the sender knows the key; payloads are not
+//! encrypted. Register writes are refused unless enabled with
+//! [`DebugConsole::with_register_writes`].
+//!
+//! # Example
+//!
+//! ```ignore
+//! use ph_esp32_mac::diag::DebugConsole;
+//!
+//! // `boot_seed` must change on every boot, e.g. from the hardware RNG
+//! let mut console = DebugConsole::new(*emac.mac_address(), KEY, PHY_ADDR, boot_seed);
+//!
+//! loop {
+//!     // Receives at most one frame; non-console frames are dropped
+//!     console.poll(&mut emac)?;
+//! }
+//! ```
+//!
+//! Firmware with its own RX loop calls [`DebugConsole::process`] on frames
+//! with EtherType [`CONSOLE_ETHERTYPE`] instead. Host tools build requests
+//! with [`encode_request`] and check captured frames with [`decode_request`]
+//! and [`decode_reply`]; `tools/ph-debug` in the repository is one.
+
+use crate::driver::config::State;
+use crate::driver::emac::Emac;
+use crate::driver::error::Result;
+use crate::internal::constants::{ETH_HEADER_SIZE, MAX_FRAME_SIZE};
+use crate::internal::phy_regs::standard::{bmcr, phy_reg};
+use crate::internal::register::{DMA_BASE, EXT_BASE, MAC_BASE, read_reg, write_reg};
+
+// =============================================================================
+// Protocol Constants
+// =============================================================================
+
+/// EtherType of console frames (IEEE 802 local experimental 2)
+pub const CONSOLE_ETHERTYPE: u16 = 0x88B6;
+
+/// Protocol version carried in every frame
+pub const CONSOLE_VERSION: u8 = 2;
+
+/// Console header length (after the Ethernet header)
+pub const CONSOLE_HEADER_LEN: usize = 22;
+
+/// Largest request or reply payload
+pub const CONSOLE_MAX_PAYLOAD: usize = 64;
+
+/// Opcode bit set in replies
+const REPLY_BIT: u8 = 0x80;
+
+/// Register blocks the console may touch: (base, length in bytes)
+const REGISTER_WINDOWS: [(usize, usize); 3] =
+    [(DMA_BASE, 0x100), (EXT_BASE, 0x100), (MAC_BASE, 0x1000)];
+
+/// Console request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum Opcode {
+    /// Liveness check; reply payload is the protocol version
+    Hello = 0x00,
+    /// Driver [`Statistics`](crate::Statistics), see [`DebugConsole`]
+    Stats = 0x01,
+    /// Read a 32-bit EMAC register; payload is the address (u32)
+    ReadReg = 0x02,
+    /// Write a 32-bit EMAC register; payload is address and value (u32 each)
+    WriteReg = 0x03,
+    /// Restart PHY auto-negotiation
+    RestartAutoneg = 0x04,
+    /// DMA ring occupancy and driver state
+    DumpRings = 0x05,
+}
+
+impl Opcode {
+    /// Decode an opcode byte (without the reply bit)
+    #[must_use]
+    pub const fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0x00 => Some(Self::Hello),
+            0x01 => Some(Self::Stats),
+            0x02 => Some(Self::ReadReg),
+            0x03 => Some(Self::WriteReg),
+            0x04 => Some(Self::RestartAutoneg),
+            0x05 => Some(Self::DumpRings),
+            _ => None,
+        }
+    }
+
+    /// Returns the opcode name
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Hello => "hello",
+            Self::Stats => "stats",
+            Self::ReadReg => "read-reg",
+            Self::WriteReg => "write-reg",
+            Self::RestartAutoneg => "restart-autoneg",
+            Self::DumpRings => "dump-rings",
+        }
+    }
+}
+
+impl core::fmt::Display for Opcode {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Result code leading every reply payload
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum Status {
+    /// Request carried out
+    Ok = 0,
+    /// Unknown opcode
+    UnknownOpcode = 1,
+    /// Payload malformed or address outside the EMAC register blocks
+    BadArgument = 2,
+    /// Operation disabled on this console
+    Denied = 3,
+    /// The driver reported an error
+    Failed = 4,
+}
+
+impl Status {
+    /// Decode a status byte
+    #[must_use]
+    pub const fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Self::Ok),
+            1 => Some(Self::UnknownOpcode),
+            2 => Some(Self::BadArgument),
+            3 => Some(Self::Denied),
+            4 => Some(Self::Failed),
+            _ => None,
+        }
+    }
+
+    /// Returns a human-readable description of the status
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ok => "ok",
+            Self::UnknownOpcode => "unknown opcode",
+            Self::BadArgument => "bad argument",
+            Self::Denied => "denied",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+impl core::fmt::Display for Status {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// =============================================================================
+// Authentication
+// =============================================================================
+
+/// Incremental SipHash-2-4
+struct SipHasher {
+    v: [u64; 4],
+    tail: u64,
+    len: usize,
+}
+
+impl SipHasher {
+    fn new(key: &[u8; 16]) -> Self {
+        let mut k = [0u8; 8];
+        k.copy_from_slice(&key[..8]);
+        let k0 = u64::from_le_bytes(k);
+        k.copy_from_slice(&key[8..]);
+        let k1 = u64::from_le_bytes(k);
+        Self {
+            v: [
+                k0 ^ 0x736F_6D65_7073_6575,
+                k1 ^ 0x646F_7261_6E64_6F6D,
+                k0 ^ 0x6C79_6765_6E65_7261,
+                k1 ^ 0x7465_6462_7974_6573,
+            ],
+            tail: 0,
+            len: 0,
+        }
+    }
+
+    fn round(&mut self) {
+        let [v0, v1, v2, v3] = &mut self.v;
+        *v0 = v0.wrapping_add(*v1);
+        *v1 = v1.rotate_left(13) ^ *v0;
+        *v0 = v0.rotate_left(32);
+        *v2 = v2.wrapping_add(*v3);
+        *v3 = v3.rotate_left(16) ^ *v2;
+        *v0 = v0.wrapping_add(*v3);
+        *v3 = v3.rotate_left(21) ^ *v0;
+        *v2 = v2.wrapping_add(*v1);
+        *v1 = v1.rotate_left(17) ^ *v2;
+        *v2 = v2.rotate_left(32);
+    }
+
+    fn compress(&mut self, m: u64) {
+        self.v[3] ^= m;
+        self.round();
+        self.round();
+        self.v[0] ^= m;
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.tail |= (b as u64) << (8 * (self.len % 8));
+            self.len += 1;
+            if self.len.is_multiple_of(8) {
+                self.compress(self.tail);
+                self.tail = 0;
+            }
+        }
+    }
+
+    fn finish(mut self) -> u64 {
+        self.compress(((self.len as u64) << 56) | self.tail);
+        self.v[2] ^= 0xFF;
+        for _ in 0..4 {
+            self.round();
+        }
+        self.v[0] ^ self.v[1] ^ self.v[2] ^ self.v[3]
+    }
+}
+
+/// Tag over the Ethernet addresses, header fields and payload of `frame`
+fn frame_tag(key: &[u8; 16], frame: &[u8], payload_len: usize) -> [u8; 8] {
+    let body = ETH_HEADER_SIZE + CONSOLE_HEADER_LEN;
+    let mut h = SipHasher::new(key);
+    h.write(&frame[..12]);
+    h.write(&frame[ETH_HEADER_SIZE..ETH_HEADER_SIZE + 14]);
+    h.write(&frame[body..body + payload_len]);
+    h.finish().to_le_bytes()
+}
+
+/// Constant-time tag comparison
+fn tags_equal(a: &[u8; 8], b: &[u8]) -> bool {
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Header fields of a console frame besides its length and tag
+#[derive(Clone, Copy)]
+struct Header {
+    opcode: u8,
+    seq: u16,
+    challenge: u64,
+}
+
+/// Write header, payload and tag into `out`; returns the frame length
+fn encode(
+    key: &[u8; 16],
+    dst: [u8; 6],
+    src: [u8; 6],
+    header: Header,
+    payload: &[u8],
+    out: &mut [u8],
+) -> Option<usize> {
+    let body = ETH_HEADER_SIZE + CONSOLE_HEADER_LEN;
+    let len = body + payload.len();
+    if payload.len() > CONSOLE_MAX_PAYLOAD || out.len() < len {
+        return None;
+    }
+    out[0..6].copy_from_slice(&dst);
+    out[6..12].copy_from_slice(&src);
+    out[12..14].copy_from_slice(&CONSOLE_ETHERTYPE.to_be_bytes());
+    let hdr = &mut out[ETH_HEADER_SIZE..body];
+    hdr[0] = CONSOLE_VERSION;
+    hdr[1] = header.opcode;
+    hdr[2..4].copy_from_slice(&header.seq.to_be_bytes());
+    hdr[4..6].copy_from_slice(&(payload.len() as u16).to_be_bytes());
+    hdr[6..14].copy_from_slice(&header.challenge.to_be_bytes());
+    out[body..len].copy_from_slice(payload);
+    let tag = frame_tag(key, out, payload.len());
+    out[ETH_HEADER_SIZE + 14..body].copy_from_slice(&tag);
+    Some(len)
+}
+
+/// Console frame with a valid tag
+struct Verified<'a> {
+    header: Header,
+    payload: &'a [u8],
+}
+
+/// Parse `frame` and check its tag; `None` if malformed or forged
+fn verify<'a>(key: &[u8; 16], frame: &'a [u8]) -> Option<Verified<'a>> {
+    let body = ETH_HEADER_SIZE + CONSOLE_HEADER_LEN;
+    if frame.len() < body
+        || u16::from_be_bytes([frame[12], frame[13]]) != CONSOLE_ETHERTYPE
+        || frame[ETH_HEADER_SIZE] != CONSOLE_VERSION
+    {
+        return None;
+    }
+    let hdr = &frame[ETH_HEADER_SIZE..body];
+    let payload_len = u16::from_be_bytes([hdr[4], hdr[5]]) as usize;
+    if payload_len > CONSOLE_MAX_PAYLOAD || frame.len() < body + payload_len {
+        return None;
+    }
+    if !tags_equal(&frame_tag(key, frame, payload_len), &hdr[14..22]) {
+        return None;
+    }
+    let mut challenge = [0u8; 8];
+    challenge.copy_from_slice(&hdr[6..14]);
+    Some(Verified {
+        header: Header {
+            opcode: hdr[1],
+            seq: u16::from_be_bytes([hdr[2], hdr[3]]),
+            challenge: u64::from_be_bytes(challenge),
+        },
+        payload: &frame[body..body + payload_len],
+    })
+}
+
+// =============================================================================
+// Host Side
+// =============================================================================
+
+/// Build a tagged request frame in `out`
+///
+/// Returns the frame length, or `None` if `payload` is longer than
+/// [`CONSOLE_MAX_PAYLOAD`] or `out` is too small. `challenge` is the one
+/// carried by the device's last reply (anything for [`Opcode::Hello`]);
+/// `seq` is only echoed, to match replies to requests.
+pub fn encode_request(
+    key: &[u8; 16],
+    device: [u8; 6],
+    host: [u8; 6],
+    opcode: Opcode,
+    seq: u16,
+    challenge: u64,
+    payload: &[u8],
+    out: &mut [u8],
+) -> Option<usize> {
+    let header = Header {
+        opcode: opcode as u8,
+        seq,
+        challenge,
+    };
+    encode(key, device, host, header, payload, out)
+}
+
+/// A request with a valid tag
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Request<'a> {
+    /// Requested operation
+    pub opcode: Opcode,
+    /// Sequence number
+    pub seq: u16,
+    /// Challenge echoed from the device's last reply
+    pub challenge: u64,
+    /// Request arguments
+    pub payload: &'a [u8],
+}
+
+/// Check and decode a request frame
+///
+/// For host tools inspecting captures; does not check the challenge.
+/// Returns `None` for anything that is not a console request tagged with
+/// `key`.
+#[must_use]
+pub fn decode_request<'a>(key: &[u8; 16], frame: &'a [u8]) -> Option<Request<'a>> {
+    let v = verify(key, frame)?;
+    if v.header.opcode & REPLY_BIT != 0 {
+        return None;
+    }
+    Some(Request {
+        opcode: Opcode::from_u8(v.header.opcode)?,
+        seq: v.header.seq,
+        challenge: v.header.challenge,
+        payload: v.payload,
+    })
+}
+
+/// A reply with a valid tag
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reply<'a> {
+    /// Opcode of the request this answers
+    pub opcode: Opcode,
+    /// Sequence number of the request
+    pub seq: u16,
+    /// Challenge the next request must echo
+    pub challenge: u64,
+    /// Result code
+    pub status: Status,
+    /// Payload after the status byte
+    pub data: &'a [u8],
+}
+
+/// Check and decode a reply frame
+///
+/// Returns `None` for anything that is not a console reply tagged with
+/// `key`.
+#[must_use]
+pub fn decode_reply<'a>(key: &[u8; 16], frame: &'a [u8]) -> Option<Reply<'a>> {
+    let v = verify(key, frame)?;
+    if v.header.opcode & REPLY_BIT == 0 {
+        return None;
+    }
+    let (&status, data) = v.payload.split_first()?;
+    Some(Reply {
+        opcode: Opcode::from_u8(v.header.opcode & !REPLY_BIT)?,
+        seq: v.header.seq,
+        challenge: v.header.challenge,
+        status: Status::from_u8(status)?,
+        data,
+    })
+}
+
+// =============================================================================
+// Statistics
+// =============================================================================
+
+/// Counters kept by [`DebugConsole`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ConsoleStats {
+    /// Authenticated requests answered
+    pub requests: u32,
+    /// Console frames with a bad tag
+    pub auth_failures: u32,
+    /// Authenticated requests that did not echo the current challenge
+    pub replays: u32,
+    /// Frames that were not console requests
+    pub ignored: u32,
+}
+
+// =============================================================================
+// Device Side
+// =============================================================================
+
+/// Debug console responder
+///
+/// Reply payloads after the status byte, all big endian:
+///
+/// - `Hello`: version (u8), register writes enabled (u8)
+/// - `Stats`: `rx_frames` (u32), `rx_bytes` (u64), then `rx_unicast`,
+///   `rx_broadcast`, `rx_multicast`, `rx_promiscuous_other`, `rx_errors`,
+///   `rx_fcs_verified`, `rx_fcs_mismatch` (u32 each)
+/// - `ReadReg`: register value (u32)
+/// - `WriteReg`, `RestartAutoneg`: nothing
+/// - `DumpRings`: active RX and TX descriptors, free RX descriptors,
+///   waiting RX frames, available TX descriptors (u16 each), state (u8:
+///   0 uninitialized, 1 initialized, 2 running, 3 stopped, 4 RX only,
+///   5 TX only)
+///
+/// Later protocol versions only append fields.
+#[derive(Debug, Clone)]
+pub struct DebugConsole {
+    mac: [u8; 6],
+    key: [u8; 16],
+    phy_addr: u8,
+    allow_writes: bool,
+    /// Per-boot input to the challenge derivation
+    seed: u64,
+    /// Challenges issued so far
+    issued: u64,
+    /// Challenge the next request must echo
+    challenge: u64,
+    stats: ConsoleStats,
+}
+
+impl DebugConsole {
+    /// Create a console answering on `mac`, authenticated with `key`
+    ///
+    /// `phy_addr` is the PHY used for [`Opcode::RestartAutoneg`]. `seed`
+    /// must differ on every boot (the hardware RNG is the simplest source),
+    /// otherwise requests captured before a reboot are accepted again after
+    /// it. Register writes are refused until enabled.
+    pub fn new(mac: [u8; 6], key: [u8; 16], phy_addr: u8, seed: u64) -> Self {
+        let mut console = Self {
+            mac,
+            key,
+            phy_addr,
+            allow_writes: false,
+            seed,
+            issued: 0,
+            challenge: 0,
+            stats: ConsoleStats {
+                requests: 0,
+                auth_failures: 0,
+                replays: 0,
+                ignored: 0,
+            },
+        };
+        console.next_challenge();
+        console
+    }
+
+    /// Allow [`Opcode::WriteReg`]
+    #[must_use]
+    pub const fn with_register_writes(mut self, allow: bool) -> Self {
+        self.allow_writes = allow;
+        self
+    }
+
+    /// Counters since creation or the last [`reset_stats`](Self::reset_stats)
+    pub const fn stats(&self) -> ConsoleStats {
+        self.stats
+    }
+
+    /// Reset all counters to zero
+    pub fn reset_stats(&mut self) {
+        self.stats = ConsoleStats::default();
+    }
+
+    /// Challenge the next request must echo
+    pub const fn challenge(&self) -> u64 {
+        self.challenge
+    }
+
+    /// Issue a new challenge, retiring the current one
+    fn next_challenge(&mut self) {
+        let mut h = SipHasher::new(&self.key);
+        h.write(&self.seed.to_le_bytes());
+        h.write(&self.issued.to_le_bytes());
+        self.issued = self.issued.wrapping_add(1);
+        self.challenge = h.finish();
+    }
+
+    /// Process one received frame and build the reply in place
+    ///
+    /// `frame[..len]` must hold a complete Ethernet frame without FCS. If it
+    /// is an authenticated request addressed to us that echoes the current
+    /// challenge (or is a [`Hello`](Opcode::Hello)), the request is carried
+    /// out, `frame` is rewritten into the reply and its length is returned.
+    /// Otherwise `None` is returned and `frame` is left untouched.
+    pub fn process<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>(
+        &mut self,
+        emac: &mut Emac<RX_BUFS, TX_BUFS, BUF_SIZE>,
+        frame: &mut [u8],
+        len: usize,
+    ) -> Option<usize> {
+        let len = len.min(frame.len());
+        if len < ETH_HEADER_SIZE
+            || frame[0..6] != self.mac
+            || u16::from_be_bytes([frame[12], frame[13]]) != CONSOLE_ETHERTYPE
+        {
+            self.stats.ignored = self.stats.ignored.wrapping_add(1);
+            return None;
+        }
+
+        let Some(request) = verify(&self.key, &frame[..len]) else {
+            self.stats.auth_failures = self.stats.auth_failures.wrapping_add(1);
+            return None;
+        };
+        let Header {
+            opcode,
+            seq,
+            challenge,
+        } = request.header;
+        if opcode & REPLY_BIT != 0 {
+            self.stats.ignored = self.stats.ignored.wrapping_add(1);
+            return None;
+        }
+        // Hello has no effect and is how a host learns the challenge
+        if opcode != Opcode::Hello as u8 {
+            if !tags_equal(&challenge.to_be_bytes(), &self.challenge.to_be_bytes()) {
+                self.stats.replays = self.stats.replays.wrapping_add(1);
+                return None;
+            }
+            self.next_challenge();
+        }
+        self.stats.requests = self.stats.requests.wrapping_add(1);
+
+        let mut reply = [0u8; CONSOLE_MAX_PAYLOAD];
+        let reply_len = self.execute(emac, opcode, request.payload, &mut reply);
+
+        let mut host = [0u8; 6];
+        host.copy_from_slice(&frame[6..12]);
+        let header = Header {
+            opcode: opcode | REPLY_BIT,
+            seq,
+            challenge: self.challenge,
+        };
+        encode(
+            &self.key,
+            host,
+            self.mac,
+            header,
+            &reply[..reply_len],
+            frame,
+        )
+    }
+
+    /// Receive at most one frame from `emac` and answer it if needed
+    ///
+    /// Returns `Ok(true)` if a frame was received, whether or not a reply was
+    /// sent. Non-console frames are dropped, so do not mix this with another
+    /// consumer of the RX ring.
+    ///
+    /// # Errors
+    ///
+    /// Returns receive errors other than "no frame available", and any
+    /// transmit error for the reply.
+    pub fn poll<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>(
+        &mut self,
+        emac: &mut Emac<RX_BUFS, TX_BUFS, BUF_SIZE>,
+    ) -> Result<bool> {
+        if !emac.rx_available() {
+            return Ok(false);
+        }
+
+        let mut frame = [0u8; MAX_FRAME_SIZE];
+        let len = emac.receive(&mut frame)?;
+        if let Some(reply_len) = self.process(emac, &mut frame, len) {
+            emac.transmit(&frame[..reply_len])?;
+        }
+        Ok(true)
+    }
+
+    /// Carry out a request; writes status and data to `out`, returns length
+    fn execute<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>(
+        &self,
+        emac: &mut Emac<RX_BUFS, TX_BUFS, BUF_SIZE>,
+        opcode: u8,
+        payload: &[u8],
+        out: &mut [u8; CONSOLE_MAX_PAYLOAD],
+    ) -> usize {
+        let mut w = Writer { buf: out, len: 1 };
+        let status = match Opcode::from_u8(opcode) {
+            None => Status::UnknownOpcode,
+            Some(Opcode::Hello) => {
+                w.put(&[CONSOLE_VERSION, self.allow_writes as u8]);
+                Status::Ok
+            }
+            Some(Opcode::Stats) => {
+                let s = emac.stats();
+                w.put(&s.rx_frames.to_be_bytes());
+                w.put(&s.rx_bytes.to_be_bytes());
+                for v in [
+                    s.rx_unicast,
+                    s.rx_broadcast,
+                    s.rx_multicast,
+                    s.rx_promiscuous_other,
+                    s.rx_errors,
+                    s.rx_fcs_verified,
+                    s.rx_fcs_mismatch,
+                ] {
+                    w.put(&v.to_be_bytes());
+                }
+                Status::Ok
+            }
+            Some(Opcode::ReadReg) => match register_arg(payload, 4) {
+                Some(addr) => {
+                    // SAFETY: `register_arg` only accepts aligned addresses
+                    // inside the EMAC register blocks.
+                    let value = unsafe { read_reg(addr) };
+                    w.put(&value.to_be_bytes());
+                    Status::Ok
+                }
+                None => Status::BadArgument,
+            },
+            Some(Opcode::WriteReg) if !self.allow_writes => Status::Denied,
+            Some(Opcode::WriteReg) => match register_arg(payload, 8) {
+                Some(addr) => {
+                    let value =
+                        u32::from_be_bytes([payload[4], payload[5], payload[6], payload[7]]);
+                    // SAFETY: `register_arg` only accepts aligned addresses
+                    // inside the EMAC register blocks.
+                    unsafe { write_reg(addr, value) };
+                    Status::Ok
+                }
+                None => Status::BadArgument,
+            },
+            Some(Opcode::RestartAutoneg) => {
+                match emac
+                    .read_phy_reg(self.phy_addr, phy_reg::BMCR)
+                    .and_then(|v| {
+                        emac.write_phy_reg(
+                            self.phy_addr,
+                            phy_reg::BMCR,
+                            v | bmcr::AN_ENABLE | bmcr::AN_RESTART,
+                        )
+                    }) {
+                    Ok(()) => Status::Ok,
+                    Err(_) => Status::Failed,
+                }
+            }
+            Some(Opcode::DumpRings) => {
+                let (rx_active, tx_active) = emac.active_ring_sizes();
+                for v in [
+                    rx_active,
+                    tx_active,
+                    emac.rx_descriptors_free(),
+                    emac.rx_frames_waiting(),
+                    emac.tx_descriptors_available(),
+                ] {
+                    w.put(&(v as u16).to_be_bytes());
+                }
+                w.put(&[match emac.state() {
+                    State::Uninitialized => 0,
+                    State::Initialized => 1,
+                    State::Running => 2,
+                    State::Stopped => 3,
+                    State::RxOnly => 4,
+                    State::TxOnly => 5,
+                }]);
+                Status::Ok
+            }
+        };
+        if status != Status::Ok {
+            w.len = 1;
+        }
+        w.buf[0] = status as u8;
+        w.len
+    }
+}
+
+/// Register address from the first four payload bytes, if the payload is
+/// exactly `len` bytes and the address is an aligned EMAC register
+fn register_arg(payload: &[u8], len: usize) -> Option<usize> {
+    if payload.len() != len {
+        return None;
+    }
+    let addr = u32::from_be_bytes([payload[0], payload[1], payload[2], payload[3]]) as usize;
+    let inside = REGISTER_WINDOWS
+        .iter()
+        .any(|&(base, size)| addr >= base && addr < base + size);
+    (inside && addr.is_multiple_of(4)).then_some(addr)
+}
+
+/// Bounded reply payload writer
+struct Writer<'a> {
+    buf: &'a mut [u8; CONSOLE_MAX_PAYLOAD],
+    len: usize,
+}
+
+impl Writer<'_> {
+    fn put(&mut self, bytes: &[u8]) {
+        let end = (self.len + bytes.len()).min(CONSOLE_MAX_PAYLOAD);
+        self.buf[self.len..end].copy_from_slice(&bytes[..end - self.len]);
+        self.len = end;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::internal::register::sim;
+
+    const KEY: [u8; 16] = [7; 16];
+    const DEVICE: [u8; 6] = [0x02, 0, 0, 0x12, 0x34, 0x56];
+    const HOST: [u8; 6] = [0x02, 0, 0, 0, 0, 0x01];
+
+    fn request(op: Opcode, seq: u16, challenge: u64, payload: &[u8]) -> ([u8; 128], usize) {
+        let mut f = [0u8; 128];
+        let len = encode_request(&KEY, DEVICE, HOST, op, seq, challenge, payload, &mut f).unwrap();
+        (f, len)
+    }
+
+    /// Send `op` with the console's current challenge and decode the reply
+    fn call(
+        console: &mut DebugConsole,
+        emac: &mut Emac<2, 2, 256>,
+        op: Opcode,
+        payload: &[u8],
+    ) -> ([u8; 128], usize) {
+        let (mut f, len) = request(op, 1, console.challenge(), payload);
+        let n = console.process(emac, &mut f, len).unwrap();
+        (f, n)
+    }
+
+    #[test]
+    fn siphash_reference_vector() {
+        // SipHash-2-4 paper, key 00..0f, message 00..0e
+        let key: [u8; 16] = core::array::from_fn(|i| i as u8);
+        let msg: [u8; 15] = core::array::from_fn(|i| i as u8);
+        let mut h = SipHasher::new(&key);
+        h.write(&msg[..5]);
+        h.write(&msg[5..]);
+        assert_eq!(h.finish(), 0xA129_CA61_49BE_45E5);
+    }
+
+    #[test]
+    fn hello_round_trip() {
+        sim::reset();
+        let mut emac: Emac<2, 2, 256> = Emac::new();
+        let mut console = DebugConsole::new(DEVICE, KEY, 1, 42);
+
+        // Hello is answered without knowing the challenge
+        let (mut f, len) = request(Opcode::Hello, 1, 0, &[]);
+        let reply_len = console.process(&mut emac, &mut f, len).unwrap();
+        let reply = decode_reply(&KEY, &f[..reply_len]).unwrap();
+        assert_eq!(f[0..6], HOST);
+        assert_eq!(reply.opcode, Opcode::Hello);
+        assert_eq!(reply.seq, 1);
+        assert_eq!(reply.challenge, console.challenge());
+        assert_eq!(reply.status, Status::Ok);
+        assert_eq!(reply.data, &[CONSOLE_VERSION, 0]);
+        // Replies are not accepted as requests
+        assert_eq!(console.process(&mut emac, &mut f, reply_len), None);
+    }
+
+    #[test]
+    fn decode_request_checks_tag_and_direction() {
+        let (f, len) = request(Opcode::ReadReg, 9, 0xC0FFEE, &[0x3F, 0xF6, 0x90, 0x00]);
+        let req = decode_request(&KEY, &f[..len]).unwrap();
+        assert_eq!(req.opcode, Opcode::ReadReg);
+        assert_eq!(req.seq, 9);
+        assert_eq!(req.challenge, 0xC0FFEE);
+        assert_eq!(req.payload, &[0x3F, 0xF6, 0x90, 0x00]);
+        assert_eq!(decode_request(&[0; 16], &f[..len]), None);
+        assert_eq!(decode_reply(&KEY, &f[..len]), None);
+    }
+
+    #[test]
+    fn rejects_forged_and_replayed_requests() {
+        sim::reset();
+        let mut emac: Emac<2, 2, 256> = Emac::new();
+        let mut console = DebugConsole::new(DEVICE, KEY, 1, 42);
+
+        let (mut f, len) = request(Opcode::Stats, 5, console.challenge(), &[]);
+        let original = f;
+        f[ETH_HEADER_SIZE + 14] ^= 1;
+        assert_eq!(console.process(&mut emac, &mut f, len), None);
+        assert_eq!(console.stats().auth_failures, 1);
+
+        let mut f = original;
+        let reply_len = console.process(&mut emac, &mut f, len).unwrap();
+        let reply = decode_reply(&KEY, &f[..reply_len]).unwrap();
+        assert_eq!(reply.data.len(), 40);
+        assert_eq!(reply.challenge, console.challenge());
+
+        // The challenge was used up, whatever the sequence number
+        let mut f = original;
+        assert_eq!(console.process(&mut emac, &mut f, len), None);
+        assert_eq!(console.stats().replays, 1);
+
+        // Another boot issues other challenges
+        let mut rebooted = DebugConsole::new(DEVICE, KEY, 1, 43);
+        let mut f = original;
+        assert_eq!(rebooted.process(&mut emac, &mut f, len), None);
+        assert_eq!(rebooted.stats().replays, 1);
+
+        let (mut f, len) = request(Opcode::Stats, 5, reply.challenge, &[]);
+        assert!(console.process(&mut emac, &mut f, len).is_some());
+    }
+
+    #[test]
+    fn register_access_is_bounded() {
+        sim::reset();
+        let mut emac: Emac<2, 2, 256> = Emac::new();
+        let mut console = DebugConsole::new(DEVICE, KEY, 1, 42);
+        let addr = (MAC_BASE as u32 + 0x8).to_be_bytes();
+
+        let mut write = [0u8; 8];
+        write[..4].copy_from_slice(&addr);
+        write[4..].copy_from_slice(&0x1234_5678u32.to_be_bytes());
+        let (f, n) = call(&mut console, &mut emac, Opcode::WriteReg, &write);
+        assert_eq!(decode_reply(&KEY, &f[..n]).unwrap().status, Status::Denied);
+
+        let mut console = console.with_register_writes(true);
+        let (f, n) = call(&mut console, &mut emac, Opcode::WriteReg, &write);
+        assert_eq!(decode_reply(&KEY, &f[..n]).unwrap().status, Status::Ok);
+        assert_eq!(sim::read(MAC_BASE + 0x8), 0x1234_5678);
+
+        let (f, n) = call(&mut console, &mut emac, Opcode::ReadReg, &addr);
+        assert_eq!(
+            decode_reply(&KEY, &f[..n]).unwrap().data,
+            &0x1234_5678u32.to_be_bytes()
+        );
+
+        for bad in [0x3FF0_00CCu32, MAC_BASE as u32 + 2] {
+            let (f, n) = call(&mut console, &mut emac, Opcode::ReadReg, &bad.to_be_bytes());
+            assert_eq!(
+                decode_reply(&KEY, &f[..n]).unwrap().status,
+                Status::BadArgument
+            );
+        }
+    }
+}
@@ -0,0 +1,24 @@
+//! Diagnostics helpers that run on the raw frame API.
+//!
+//! These helpers let firmware verify connectivity to the board without
+//! pulling in a TCP/IP stack. They only use [`Emac::receive`] and
+//! [`Emac::transmit`], so they work in any build.
+//!
+//! # Overview
+//!
+//! - `ping`: Minimal ARP + ICMP echo responder (`diag` feature)
+//! - `console`: Authenticated register/stats console over a raw
+//!   EtherType (`debug-console` feature)
+//!
+//! [`Emac::receive`]: crate::Emac::receive
+//! [`Emac::transmit`]: crate::Emac::transmit
+
+#[cfg(feature = "debug-console")]
+pub mod console;
+#[cfg(feature = "diag")]
+pub mod ping;
+
+#[cfg(feature = "debug-console")]
+pub use console::{ConsoleStats, DebugConsole};
+#[cfg(feature = "diag")]
+pub use ping::{PingResponder, PingStats};
@@ -0,0 +1,527 @@
+//! Minimal ARP and ICMP echo responder.
+//!
+//! [`PingResponder`] answers ARP requests for one IPv4 address and replies
+//! to ICMP echo requests sent to it. Nothing else is handled: no routing,
+//! no fragmentation, no ARP cache. Replies are built in place in the
+//! received frame buffer, so no extra memory is needed.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use ph_esp32_mac::diag::PingResponder;
+//!
+//! let mut ping = PingResponder::new(*emac.mac_address(), [192, 168, 1, 50]);
+//!
+//! loop {
+//!     // Receives at most one frame; non-ARP/ICMP frames are dropped
+//!     ping.poll(&mut emac)?;
+//! }
+//! ```
+//!
+//! Firmware that already has its own RX loop can call
+//! [`PingResponder::process`] on each frame instead and transmit the reply
+//! when one is returned.
+
+use crate::driver::emac::Emac;
+use crate::driver::error::Result;
+use crate::internal::constants::{ETH_HEADER_SIZE, MAX_FRAME_SIZE, MIN_FRAME_SIZE};
+
+// =============================================================================
+// Protocol Constants
+// =============================================================================
+
+const ETHERTYPE_IPV4: u16 = 0x0800;
+const ETHERTYPE_ARP: u16 = 0x0806;
+
+const ARP_HTYPE_ETHERNET: u16 = 1;
+const ARP_OPER_REQUEST: u16 = 1;
+const ARP_OPER_REPLY: u16 = 2;
+/// ARP packet length for Ethernet/IPv4
+const ARP_LEN: usize = 28;
+
+const IPV4_MIN_HEADER: usize = 20;
+const IP_PROTO_ICMP: u8 = 1;
+const IP_DEFAULT_TTL: u8 = 64;
+
+const ICMP_ECHO_REPLY: u8 = 0;
+const ICMP_ECHO_REQUEST: u8 = 8;
+const ICMP_HEADER: usize = 8;
+
+const BROADCAST_MAC: [u8; 6] = [0xFF; 6];
+
+// =============================================================================
+// Statistics
+// =============================================================================
+
+/// Counters kept by [`PingResponder`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PingStats {
+    /// ARP replies sent
+    pub arp_replies: u32,
+    /// ICMP echo replies sent
+    pub echo_replies: u32,
+    /// Frames that were not for this responder or were malformed
+    pub ignored: u32,
+}
+
+// =============================================================================
+// Responder
+// =============================================================================
+
+/// ARP + ICMP echo responder for a single IPv4 address
+#[derive(Debug, Clone)]
+pub struct PingResponder {
+    mac: [u8; 6],
+    ip: [u8; 4],
+    stats: PingStats,
+}
+
+impl PingResponder {
+    /// Create a responder for the given MAC and IPv4 address
+    pub const fn new(mac: [u8; 6], ip: [u8; 4]) -> Self {
+        Self {
+            mac,
+            ip,
+            stats: PingStats {
+                arp_replies: 0,
+                echo_replies: 0,
+                ignored: 0,
+            },
+        }
+    }
+
+    /// MAC address used in replies
+    pub const fn mac_address(&self) -> &[u8; 6] {
+        &self.mac
+    }
+
+    /// IPv4 address this responder answers for
+    pub const fn ip_address(&self) -> &[u8; 4] {
+        &self.ip
+    }
+
+    /// Change the IPv4 address this responder answers for
+    pub fn set_ip_address(&mut self, ip: [u8; 4]) {
+        self.ip = ip;
+    }
+
+    /// Counters since creation or the last [`reset_stats`](Self::reset_stats)
+    pub const fn stats(&self) -> PingStats {
+        self.stats
+    }
+
+    /// Reset all counters to zero
+    pub fn reset_stats(&mut self) {
+        self.stats = PingStats::default();
+    }
+
+    /// Process one received frame and build the reply in place
+    ///
+    /// `frame[..len]` must hold a complete Ethernet frame without FCS. If the
+    /// frame is an ARP request for our address or an ICMP echo request to
+    /// us, `frame` is rewritten into the reply and its length is returned.
+    /// Otherwise `None` is returned and the contents of `frame` are left
+    /// untouched.
+    pub fn process(&mut self, frame: &mut [u8], len: usize) -> Option<usize> {
+        let len = len.min(frame.len());
+        let reply = if len < ETH_HEADER_SIZE {
+            None
+        } else {
+            match u16::from_be_bytes([frame[12], frame[13]]) {
+                ETHERTYPE_ARP => self.process_arp(frame, len),
+                ETHERTYPE_IPV4 => self.process_icmp(frame, len),
+                _ => None,
+            }
+        };
+
+        if reply.is_none() {
+            self.stats.ignored = self.stats.ignored.wrapping_add(1);
+        }
+        reply
+    }
+
+    /// Receive at most one frame from `emac` and answer it if needed
+    ///
+    /// Returns `Ok(true)` if a frame was received, whether or not a reply was
+    /// sent. Frames other than ARP and ICMP echo requests are dropped, so
+    /// do not mix this with another consumer of the RX ring.
+    ///
+    /// # Errors
+    ///
+    /// Returns receive errors other than "no frame available", and any
+    /// transmit error for the reply.
+    pub fn poll<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>(
+        &mut self,
+        emac: &mut Emac<RX_BUFS, TX_BUFS, BUF_SIZE>,
+    ) -> Result<bool> {
+        if !emac.rx_available() {
+            return Ok(false);
+        }
+
+        let mut frame = [0u8; MAX_FRAME_SIZE];
+        let len = emac.receive(&mut frame)?;
+        if let Some(reply_len) = self.process(&mut frame, len) {
+            emac.transmit(&frame[..reply_len])?;
+        }
+        Ok(true)
+    }
+
+    fn process_arp(&mut self, frame: &mut [u8], len: usize) -> Option<usize> {
+        if len < ETH_HEADER_SIZE + ARP_LEN {
+            return None;
+        }
+
+        let dst = &frame[0..6];
+        if dst != BROADCAST_MAC && dst != self.mac {
+            return None;
+        }
+
+        let arp = &frame[ETH_HEADER_SIZE..ETH_HEADER_SIZE + ARP_LEN];
+        if u16::from_be_bytes([arp[0], arp[1]]) != ARP_HTYPE_ETHERNET
+            || u16::from_be_bytes([arp[2], arp[3]]) != ETHERTYPE_IPV4
+            || arp[4] != 6
+            || arp[5] != 4
+            || u16::from_be_bytes([arp[6], arp[7]]) != ARP_OPER_REQUEST
+            || arp[24..28] != self.ip
+        {
+            return None;
+        }
+
+        let mut sender_mac = [0u8; 6];
+        let mut sender_ip = [0u8; 4];
+        sender_mac.copy_from_slice(&arp[8..14]);
+        sender_ip.copy_from_slice(&arp[14..18]);
+
+        // Ethernet header
+        frame[0..6].copy_from_slice(&sender_mac);
+        frame[6..12].copy_from_slice(&self.mac);
+
+        // ARP reply: we are the sender, the requester is the target
+        let arp = &mut frame[ETH_HEADER_SIZE..ETH_HEADER_SIZE + ARP_LEN];
+        arp[6..8].copy_from_slice(&ARP_OPER_REPLY.to_be_bytes());
+        arp[8..14].copy_from_slice(&self.mac);
+        arp[14..18].copy_from_slice(&self.ip);
+        arp[18..24].copy_from_slice(&sender_mac);
+        arp[24..28].copy_from_slice(&sender_ip);
+
+        // Zero the padding so stale request bytes are not echoed back
+        let reply_len = (ETH_HEADER_SIZE + ARP_LEN).max(MIN_FRAME_SIZE.min(frame.len()));
+        frame[ETH_HEADER_SIZE + ARP_LEN..reply_len].fill(0);
+
+        self.stats.arp_replies = self.stats.arp_replies.wrapping_add(1);
+        Some(reply_len)
+    }
+
+    fn process_icmp(&mut self, frame: &mut [u8], len: usize) -> Option<usize> {
+        if frame[0..6] != self.mac || len < ETH_HEADER_SIZE + IPV4_MIN_HEADER {
+            return None;
+        }
+
+        let ip = &frame[ETH_HEADER_SIZE..len];
+        let ihl = usize::from(ip[0] & 0x0F) * 4;
+        let total_len = usize::from(u16::from_be_bytes([ip[2], ip[3]]));
+        let fragmented = u16::from_be_bytes([ip[6], ip[7]]) & 0x3FFF != 0;
+        if ip[0] >> 4 != 4
+            || ihl < IPV4_MIN_HEADER
+            || total_len < ihl + ICMP_HEADER
+            || total_len > ip.len()
+            || fragmented
+            || ip[9] != IP_PROTO_ICMP
+            || ip[16..20] != self.ip
+            || checksum(&ip[..ihl]) != 0
+        {
+            return None;
+        }
+
+        let icmp = &ip[ihl..total_len];
+        if icmp[0] != ICMP_ECHO_REQUEST || icmp[1] != 0 || checksum(icmp) != 0 {
+            return None;
+        }
+
+        // Ethernet header: reply to the sender's MAC
+        let mut peer_mac = [0u8; 6];
+        peer_mac.copy_from_slice(&frame[6..12]);
+        frame[0..6].copy_from_slice(&peer_mac);
+        frame[6..12].copy_from_slice(&self.mac);
+
+        // IPv4 header: swap addresses, reset TTL, recompute checksum
+        let ip = &mut frame[ETH_HEADER_SIZE..ETH_HEADER_SIZE + total_len];
+        let mut peer_ip = [0u8; 4];
+        peer_ip.copy_from_slice(&ip[12..16]);
+        ip[12..16].copy_from_slice(&self.ip);
+        ip[16..20].copy_from_slice(&peer_ip);
+        ip[8] = IP_DEFAULT_TTL;
+        ip[10..12].fill(0);
+        let ip_sum = checksum(&ip[..ihl]);
+        ip[10..12].copy_from_slice(&ip_sum.to_be_bytes());
+
+        // ICMP: echo reply with identifier, sequence and data unchanged
+        let icmp = &mut ip[ihl..];
+        icmp[0] = ICMP_ECHO_REPLY;
+        icmp[2..4].fill(0);
+        let icmp_sum = checksum(icmp);
+        icmp[2..4].copy_from_slice(&icmp_sum.to_be_bytes());
+
+        self.stats.echo_replies = self.stats.echo_replies.wrapping_add(1);
+        Some(ETH_HEADER_SIZE + total_len)
+    }
+}
+
+/// RFC 1071 Internet checksum
+///
+/// Returns the one's complement of the one's complement sum. Running it
+/// over data that already contains a valid checksum yields zero.
+fn checksum(data: &[u8]) -> u16 {
+    let mut sum = 0u32;
+    let mut chunks = data.chunks_exact(2);
+    for pair in &mut chunks {
+        sum += u32::from(u16::from_be_bytes([pair[0], pair[1]]));
+    }
+    if let [last] = chunks.remainder() {
+        sum += u32::from(*last) << 8;
+    }
+    while sum > 0xFFFF {
+        sum = (sum & 0xFFFF) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OUR_MAC: [u8; 6] = [0x02, 0x00, 0x00, 0x12, 0x34, 0x56];
+    const OUR_IP: [u8; 4] = [192, 168, 1, 50];
+    const PEER_MAC: [u8; 6] = [0x02, 0xAA, 0xBB, 0xCC, 0xDD, 0xEE];
+    const PEER_IP: [u8; 4] = [192, 168, 1, 10];
+
+    fn arp_request(target_ip: [u8; 4]) -> [u8; 60] {
+        let mut f = [0u8; 60];
+        f[0..6].copy_from_slice(&BROADCAST_MAC);
+        f[6..12].copy_from_slice(&PEER_MAC);
+        f[12..14].copy_from_slice(&ETHERTYPE_ARP.to_be_bytes());
+        f[14..16].copy_from_slice(&1u16.to_be_bytes());
+        f[16..18].copy_from_slice(&ETHERTYPE_IPV4.to_be_bytes());
+        f[18] = 6;
+        f[19] = 4;
+        f[20..22].copy_from_slice(&ARP_OPER_REQUEST.to_be_bytes());
+        f[22..28].copy_from_slice(&PEER_MAC);
+        f[28..32].copy_from_slice(&PEER_IP);
+        f[38..42].copy_from_slice(&target_ip);
+        f[42..60].fill(0xA5);
+        f
+    }
+
+    /// Build an echo request with `data_len` bytes of payload; returns frame length
+    fn echo_request(buf: &mut [u8], dst_ip: [u8; 4], data_len: usize) -> usize {
+        let total = IPV4_MIN_HEADER + ICMP_HEADER + data_len;
+        buf[0..6].copy_from_slice(&OUR_MAC);
+        buf[6..12].copy_from_slice(&PEER_MAC);
+        buf[12..14].copy_from_slice(&ETHERTYPE_IPV4.to_be_bytes());
+
+        let ip = &mut buf[14..14 + total];
+        ip[0] = 0x45;
+        ip[2..4].copy_from_slice(&(total as u16).to_be_bytes());
+        ip[4..6].copy_from_slice(&0x1234u16.to_be_bytes());
+        ip[6] = 0x40; // DF
+        ip[8] = 128;
+        ip[9] = IP_PROTO_ICMP;
+        ip[12..16].copy_from_slice(&PEER_IP);
+        ip[16..20].copy_from_slice(&dst_ip);
+        let sum = checksum(&ip[..20]);
+        ip[10..12].copy_from_slice(&sum.to_be_bytes());
+
+        let icmp = &mut ip[20..];
+        icmp[0] = ICMP_ECHO_REQUEST;
+        icmp[4..6].copy_from_slice(&0x0001u16.to_be_bytes());
+        icmp[6..8].copy_from_slice(&0x0007u16.to_be_bytes());
+        for (i, b) in icmp[8..].iter_mut().enumerate() {
+            *b = i as u8;
+        }
+        let sum = checksum(icmp);
+        icmp[2..4].copy_from_slice(&sum.to_be_bytes());
+
+        14 + total
+    }
+
+    #[test]
+    fn checksum_rfc1071_example() {
+        // Example from RFC 1071 section 3
+        let data = [0x00, 0x01, 0xF2, 0x03, 0xF4, 0xF5, 0xF6, 0xF7];
+        assert_eq!(checksum(&data), !0xDDF2);
+    }
+
+    #[test]
+    fn checksum_odd_length() {
+        assert_eq!(checksum(&[0x01]), !0x0100);
+    }
+
+    #[test]
+    fn arp_request_for_our_ip_gets_reply() {
+        let mut ping = PingResponder::new(OUR_MAC, OUR_IP);
+        let mut f = arp_request(OUR_IP);
+
+        assert_eq!(ping.process(&mut f, 60), Some(60));
+        assert_eq!(f[0..6], PEER_MAC);
+        assert_eq!(f[6..12], OUR_MAC);
+        assert_eq!(u16::from_be_bytes([f[20], f[21]]), ARP_OPER_REPLY);
+        assert_eq!(f[22..28], OUR_MAC);
+        assert_eq!(f[28..32], OUR_IP);
+        assert_eq!(f[32..38], PEER_MAC);
+        assert_eq!(f[38..42], PEER_IP);
+        assert!(f[42..60].iter().all(|&b| b == 0));
+        assert_eq!(ping.stats().arp_replies, 1);
+    }
+
+    #[test]
+    fn arp_request_for_other_ip_ignored() {
+        let mut ping = PingResponder::new(OUR_MAC, OUR_IP);
+        let mut f = arp_request([192, 168, 1, 99]);
+        let orig = f;
+
+        assert_eq!(ping.process(&mut f, 60), None);
+        assert_eq!(f, orig);
+        assert_eq!(ping.stats().ignored, 1);
+    }
+
+    #[test]
+    fn arp_reply_is_not_answered() {
+        let mut ping = PingResponder::new(OUR_MAC, OUR_IP);
+        let mut f = arp_request(OUR_IP);
+        f[20..22].copy_from_slice(&ARP_OPER_REPLY.to_be_bytes());
+
+        assert_eq!(ping.process(&mut f, 60), None);
+    }
+
+    #[test]
+    fn arp_reply_fits_short_buffer() {
+        let mut ping = PingResponder::new(OUR_MAC, OUR_IP);
+        let full = arp_request(OUR_IP);
+        let mut f = [0u8; 42];
+        f.copy_from_slice(&full[..42]);
+
+        assert_eq!(ping.process(&mut f, 42), Some(42));
+    }
+
+    #[test]
+    fn echo_request_gets_reply() {
+        let mut ping = PingResponder::new(OUR_MAC, OUR_IP);
+        let mut f = [0u8; 128];
+        let len = echo_request(&mut f, OUR_IP, 32);
+
+        assert_eq!(ping.process(&mut f, len), Some(len));
+        assert_eq!(f[0..6], PEER_MAC);
+        assert_eq!(f[6..12], OUR_MAC);
+
+        let ip = &f[14..len];
+        assert_eq!(ip[12..16], OUR_IP);
+        assert_eq!(ip[16..20], PEER_IP);
+        assert_eq!(ip[8], IP_DEFAULT_TTL);
+        assert_eq!(checksum(&ip[..20]), 0);
+
+        let icmp = &ip[20..];
+        assert_eq!(icmp[0], ICMP_ECHO_REPLY);
+        assert_eq!(u16::from_be_bytes([icmp[4], icmp[5]]), 0x0001);
+        assert_eq!(u16::from_be_bytes([icmp[6], icmp[7]]), 0x0007);
+        assert_eq!(checksum(icmp), 0);
+        for (i, &b) in icmp[8..].iter().enumerate() {
+            assert_eq!(b, i as u8);
+        }
+        assert_eq!(ping.stats().echo_replies, 1);
+    }
+
+    #[test]
+    fn echo_request_with_odd_payload() {
+        let mut ping = PingResponder::new(OUR_MAC, OUR_IP);
+        let mut f = [0u8; 128];
+        let len = echo_request(&mut f, OUR_IP, 33);
+
+        assert_eq!(ping.process(&mut f, len), Some(len));
+        assert_eq!(checksum(&f[34..len]), 0);
+    }
+
+    #[test]
+    fn echo_request_ignores_trailing_padding() {
+        let mut ping = PingResponder::new(OUR_MAC, OUR_IP);
+        let mut f = [0u8; 128];
+        let len = echo_request(&mut f, OUR_IP, 4);
+
+        // Short frames arrive padded to the Ethernet minimum
+        assert!(len < MIN_FRAME_SIZE);
+        assert_eq!(ping.process(&mut f, MIN_FRAME_SIZE), Some(len));
+    }
+
+    #[test]
+    fn echo_request_for_other_ip_ignored() {
+        let mut ping = PingResponder::new(OUR_MAC, OUR_IP);
+        let mut f = [0u8; 128];
+        let len = echo_request(&mut f, [10, 0, 0, 1], 16);
+
+        assert_eq!(ping.process(&mut f, len), None);
+    }
+
+    #[test]
+    fn echo_request_to_other_mac_ignored() {
+        let mut ping = PingResponder::new(OUR_MAC, OUR_IP);
+        let mut f = [0u8; 128];
+        let len = echo_request(&mut f, OUR_IP, 16);
+        f[0..6].copy_from_slice(&BROADCAST_MAC);
+
+        assert_eq!(ping.process(&mut f, len), None);
+    }
+
+    #[test]
+    fn bad_ip_checksum_ignored() {
+        let mut ping = PingResponder::new(OUR_MAC, OUR_IP);
+        let mut f = [0u8; 128];
+        let len = echo_request(&mut f, OUR_IP, 16);
+        f[24] ^= 0xFF;
+
+        assert_eq!(ping.process(&mut f, len), None);
+    }
+
+    #[test]
+    fn bad_icmp_checksum_ignored() {
+        let mut ping = PingResponder::new(OUR_MAC, OUR_IP);
+        let mut f = [0u8; 128];
+        let len = echo_request(&mut f, OUR_IP, 16);
+        f[len - 1] ^= 0xFF;
+
+        assert_eq!(ping.process(&mut f, len), None);
+    }
+
+    #[test]
+    fn fragment_ignored() {
+        let mut ping = PingResponder::new(OUR_MAC, OUR_IP);
+        let mut f = [0u8; 128];
+        let len = echo_request(&mut f, OUR_IP, 16);
+        f[20] = 0x20; // MF
+        f[24..26].fill(0);
+        let sum = checksum(&f[14..34]);
+        f[24..26].copy_from_slice(&sum.to_be_bytes());
+
+        assert_eq!(ping.process(&mut f, len), None);
+    }
+
+    #[test]
+    fn truncated_frame_ignored() {
+        let mut ping = PingResponder::new(OUR_MAC, OUR_IP);
+        let mut f = [0u8; 128];
+        let len = echo_request(&mut f, OUR_IP, 16);
+
+        assert_eq!(ping.process(&mut f, len - 1), None);
+        assert_eq!(ping.process(&mut f, 10), None);
+    }
+
+    #[test]
+    fn reset_stats_clears_counters() {
+        let mut ping = PingResponder::new(OUR_MAC, OUR_IP);
+        let mut f = [0u8; 20];
+        ping.process(&mut f, 20);
+        assert_eq!(ping.stats().ignored, 1);
+
+        ping.reset_stats();
+        assert_eq!(ping.stats(), PingStats::default());
+    }
+}
@@ -0,0 +1,71 @@
+//! Zero-copy transmit from `'static` memory.
+//!
+//! [`Emac::transmit`](super::emac::Emac::transmit) copies every frame into a
+//! TX ring buffer. Large frames that never change, like firmware chunks
+//! served over a bootloader protocol or canned responses, can skip the copy:
+//! [`Emac::transmit_borrowed`](super::emac::Emac::transmit_borrowed) points
+//! the TX descriptor at the caller's memory and hands it to the DMA as is.
+//!
+//! # Requirements
+//!
+//! The DMA reads the frame straight from memory, so it must be:
+//!
+//! - in internal SRAM. Plain `const` and immutable `static` data is linked
+//!   into flash (`.rodata`), which the EMAC DMA cannot read; put the frame
+//!   in a RAM section (e.g. esp-hal's `#[ram]`), or build it at run time
+//!   into a static buffer;
+//! - 4-byte aligned;
+//! - a complete frame including headers, at most one descriptor
+//!   (8188 bytes). The MAC appends the FCS as usual.
+//!
+//! Anything else fails with `DmaError::BufferNotDmaCapable`.
+//!
+//! # Borrow Contract
+//!
+//! `&'static [u8]` keeps the memory alive, but memory that is written
+//! through another path (a `static` with interior mutability, a buffer
+//! refilled between chunks) must not change while the DMA may still read
+//! it. The returned [`BorrowedTx`] tells when it is done:
+//! [`Emac::borrowed_tx_done`](super::emac::Emac::borrowed_tx_done) turns
+//! true once the DMA has released the descriptor. TX header rewrite rules
+//! do not apply to borrowed frames.
+//!
+//! ```ignore
+//! #[repr(align(4))]
+//! struct Frame([u8; 1514]);
+//!
+//! #[ram]
+//! static RESPONSE: Frame = Frame(*include_bytes!("response.bin"));
+//!
+//! let tx = emac.transmit_borrowed(&RESPONSE.0)?;
+//! // ... later, before changing memory the frame lives in:
+//! while !emac.borrowed_tx_done(&tx) {}
+//! ```
+
+/// Handle of a frame queued with
+/// [`Emac::transmit_borrowed`](super::emac::Emac::transmit_borrowed)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[must_use = "check borrowed_tx_done before reusing the frame memory"]
+pub struct BorrowedTx {
+    /// TX slot whose descriptor points at the frame
+    pub(crate) slot: usize,
+    /// Bus address of the frame
+    pub(crate) addr: u32,
+    /// Frame length in bytes
+    pub(crate) len: usize,
+}
+
+impl BorrowedTx {
+    /// Frame length in bytes
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Always false: empty frames are rejected
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
@@ -0,0 +1,670 @@
+//! Step-by-step bring-up report.
+//!
+//! First-time bring-up of a new board usually fails in one of a few ways:
+//! no 50 MHz reference clock (the DMA reset never completes), the wrong PHY
+//! address or MDIO pins (the PHY ID reads as all zeros or ones), or simply
+//! no cable. [`BringUpReport`] records the outcome of each bring-up step and
+//! maps the first failure to a human-readable hint, so a new user gets
+//! "no REF_CLK" instead of a bare `ResetFailed`.
+//!
+//! The esp-hal `EmacBuilder::bring_up` fills in a report for the whole
+//! sequence. Without esp-hal, record `Emac::init`'s result with
+//! [`record_init`](BringUpReport::record_init) and run the PHY steps with
+//! [`probe_phy`](BringUpReport::probe_phy).
+//!
+//! # Example
+//!
+//! ```ignore
+//! let mut report = BringUpReport::new();
+//! report.record_init(&emac.init(config, &mut delay));
+//! if let Some(link) = report.probe_phy(&mut phy, &mut mdio, &mut delay, 5_000) {
+//!     emac.update_link(link.speed, link.duplex);
+//! }
+//! println!("{report}");
+//! ```
+//!
+//! # Sequenced PHY Reset
+//!
+//! A PHY must see a stable 50 MHz REF_CLK before its nRST is released, and
+//! must not be accessed over MDIO until it has recovered from reset.
+//! [`reset_and_probe_phy`] runs exactly that sequence on an initialized
+//! EMAC and reports which [`PhyBringUpStage`] failed; the esp-hal
+//! `EmacPhyBundle::bring_up` wraps it for a bundle that owns the reset GPIO.
+
+use core::convert::Infallible;
+
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::{ErrorType, OutputPin};
+
+use super::config::State;
+use super::emac::Emac;
+use super::error::{ConfigError, Error, IoError, Result};
+use crate::hal::mdio::MdioBus;
+use crate::phy::{LinkStatus, PhyDriver};
+
+/// Interval between link polls in [`BringUpReport::probe_phy`]
+const LINK_POLL_MS: u32 = 100;
+
+/// Number of bring-up steps
+const STEP_COUNT: usize = 7;
+
+/// One stage of EMAC + PHY bring-up, in execution order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum BringUpStep {
+    /// Configuration validation
+    Config,
+    /// Peripheral and EMAC clock enable
+    ClockEnable,
+    /// SMI and RMII pin routing
+    PinRouting,
+    /// DMA software reset (needs the reference clock)
+    Reset,
+    /// PHY identifier read over MDIO
+    PhyId,
+    /// PHY reset and auto-negotiation start
+    PhyInit,
+    /// Auto-negotiation result / link up
+    Link,
+}
+
+impl BringUpStep {
+    /// All steps in execution order
+    pub const ALL: [Self; STEP_COUNT] = [
+        Self::Config,
+        Self::ClockEnable,
+        Self::PinRouting,
+        Self::Reset,
+        Self::PhyId,
+        Self::PhyInit,
+        Self::Link,
+    ];
+
+    /// Short name of the step
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Config => "config",
+            Self::ClockEnable => "clock enable",
+            Self::PinRouting => "pin routing",
+            Self::Reset => "DMA reset",
+            Self::PhyId => "PHY ID",
+            Self::PhyInit => "PHY init",
+            Self::Link => "link",
+        }
+    }
+}
+
+/// Result of one bring-up step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum StepOutcome {
+    /// Not attempted (an earlier step failed, or the caller skipped it)
+    Skipped,
+    /// Completed successfully
+    Passed,
+    /// Failed with the given error
+    Failed(Error),
+}
+
+/// Outcome of every bring-up step plus what was learned on the way
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BringUpReport {
+    outcomes: [StepOutcome; STEP_COUNT],
+    phy_id: Option<u32>,
+    link: Option<LinkStatus>,
+}
+
+impl Default for BringUpReport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BringUpReport {
+    /// Create a report with every step skipped
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            outcomes: [StepOutcome::Skipped; STEP_COUNT],
+            phy_id: None,
+            link: None,
+        }
+    }
+
+    /// Outcome of `step`
+    #[must_use]
+    pub const fn outcome(&self, step: BringUpStep) -> StepOutcome {
+        self.outcomes[step as usize]
+    }
+
+    /// Set the outcome of `step`
+    pub fn record(&mut self, step: BringUpStep, outcome: StepOutcome) {
+        self.outcomes[step as usize] = outcome;
+    }
+
+    /// Steps and their outcomes in execution order
+    pub fn steps(&self) -> impl Iterator<Item = (BringUpStep, StepOutcome)> + '_ {
+        BringUpStep::ALL
+            .iter()
+            .map(|&step| (step, self.outcome(step)))
+    }
+
+    /// True if every step passed
+    #[must_use]
+    pub fn is_success(&self) -> bool {
+        self.outcomes.iter().all(|o| *o == StepOutcome::Passed)
+    }
+
+    /// First failed step and its error
+    #[must_use]
+    pub fn first_failure(&self) -> Option<(BringUpStep, Error)> {
+        self.steps().find_map(|(step, outcome)| match outcome {
+            StepOutcome::Failed(e) => Some((step, e)),
+            _ => None,
+        })
+    }
+
+    /// Hint for the first failure, if any
+    #[must_use]
+    pub fn hint(&self) -> Option<&'static str> {
+        self.first_failure()
+            .and_then(|(step, error)| hint_for(step, error))
+    }
+
+    /// PHY identifier read during [`BringUpStep::PhyId`]
+    #[must_use]
+    pub const fn phy_id(&self) -> Option<u32> {
+        self.phy_id
+    }
+
+    /// Link parameters once [`BringUpStep::Link`] passed
+    #[must_use]
+    pub const fn link(&self) -> Option<LinkStatus> {
+        self.link
+    }
+
+    /// Record the result of `Emac::init` against the EMAC-side steps
+    ///
+    /// `init` validates the configuration, enables clocks, routes pins and
+    /// then resets the DMA; only validation and the reset can fail.
+    pub fn record_init(&mut self, result: &Result<()>) {
+        use BringUpStep::{ClockEnable, Config, PinRouting, Reset};
+
+        match *result {
+            Ok(()) => {
+                for step in [Config, ClockEnable, PinRouting, Reset] {
+                    self.record(step, StepOutcome::Passed);
+                }
+            }
+            Err(e @ Error::Config(ConfigError::ResetFailed)) => {
+                for step in [Config, ClockEnable, PinRouting] {
+                    self.record(step, StepOutcome::Passed);
+                }
+                self.record(Reset, StepOutcome::Failed(e));
+            }
+            Err(e) => self.record(Config, StepOutcome::Failed(e)),
+        }
+    }
+
+    /// Identify and initialize the PHY, then wait up to `link_timeout_ms`
+    /// for the link
+    ///
+    /// Records [`BringUpStep::PhyId`], [`BringUpStep::PhyInit`] and
+    /// [`BringUpStep::Link`], stopping at the first failure. Returns the
+    /// link parameters if the link came up.
+    pub fn probe_phy<P: PhyDriver, M: MdioBus, D: DelayNs>(
+        &mut self,
+        phy: &mut P,
+        mdio: &mut M,
+        delay: &mut D,
+        link_timeout_ms: u32,
+    ) -> Option<LinkStatus> {
+        match phy.phy_id(mdio) {
+            // A floating or unpowered MDIO line reads as all ones, a missing
+            // pull-up or wrong address as all zeros
+            Ok(0 | 0xFFFF_FFFF) => {
+                let e = ConfigError::InvalidPhyAddress.into();
+                self.record(BringUpStep::PhyId, StepOutcome::Failed(e));
+                return None;
+            }
+            Ok(id) => {
+                self.phy_id = Some(id);
+                self.record(BringUpStep::PhyId, StepOutcome::Passed);
+            }
+            Err(e) => {
+                self.record(BringUpStep::PhyId, StepOutcome::Failed(e));
+                return None;
+            }
+        }
+
+        if let Err(e) = phy.init(mdio) {
+            self.record(BringUpStep::PhyInit, StepOutcome::Failed(e));
+            return None;
+        }
+        self.record(BringUpStep::PhyInit, StepOutcome::Passed);
+
+        let mut elapsed_ms = 0u32;
+        loop {
+            match phy.link_status(mdio) {
+                Ok(Some(status)) => {
+                    self.link = Some(status);
+                    self.record(BringUpStep::Link, StepOutcome::Passed);
+                    return Some(status);
+                }
+                Ok(None) if elapsed_ms < link_timeout_ms => {
+                    delay.delay_ms(LINK_POLL_MS);
+                    elapsed_ms = elapsed_ms.saturating_add(LINK_POLL_MS);
+                }
+                Ok(None) => {
+                    let e = IoError::Timeout.into();
+                    self.record(BringUpStep::Link, StepOutcome::Failed(e));
+                    return None;
+                }
+                Err(e) => {
+                    self.record(BringUpStep::Link, StepOutcome::Failed(e));
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+/// Likely cause of `error` during `step`, for first-time bring-up
+#[must_use]
+pub const fn hint_for(step: BringUpStep, error: Error) -> Option<&'static str> {
+    Some(match (step, error) {
+        (BringUpStep::Config, Error::Config(ConfigError::InvalidMacAddress)) => {
+            "MAC address must be unicast (bit 0 of the first byte clear)"
+        }
+        (BringUpStep::Config, Error::Config(ConfigError::AlreadyInitialized)) => {
+            "EMAC is already initialized; reset the chip before running init again"
+        }
+        (BringUpStep::Config, _) => {
+            "check EmacConfig: flow control water marks, RMII timing, dual-buffer ring sizes"
+        }
+        (BringUpStep::Reset, _) => {
+            "no 50 MHz REF_CLK: check the PHY oscillator or its enable pin, and that the \
+             RMII clock mode (external GPIO0 / internal GPIO16-17) matches the board"
+        }
+        (BringUpStep::PhyId, Error::Config(ConfigError::InvalidPhyAddress)) => {
+            "PHY ID reads as all zeros or ones: wrong PHY address, PHY unpowered, or \
+             MDC/MDIO not on GPIO23/GPIO18"
+        }
+        (BringUpStep::PhyId, _) => "MDIO access failed: check the MDC/MDIO pins and PHY power",
+        (BringUpStep::PhyInit, _) => "PHY did not finish its reset: check its power and reset pin",
+        (BringUpStep::Link, Error::Io(IoError::Timeout)) => {
+            "no link: cable unplugged or link partner switched off"
+        }
+        (BringUpStep::Link, _) => "reading link status over MDIO failed",
+        (BringUpStep::ClockEnable | BringUpStep::PinRouting, _) => return None,
+    })
+}
+
+impl core::fmt::Display for BringUpReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for (step, outcome) in self.steps() {
+            match outcome {
+                StepOutcome::Skipped => writeln!(f, "  [skip] {}", step.as_str())?,
+                StepOutcome::Passed => writeln!(f, "  [ ok ] {}", step.as_str())?,
+                StepOutcome::Failed(e) => {
+                    writeln!(f, "  [FAIL] {}: {}", step.as_str(), e)?;
+                    if let Some(hint) = hint_for(step, e) {
+                        writeln!(f, "         hint: {hint}")?;
+                    }
+                }
+            }
+        }
+        if let Some(id) = self.phy_id {
+            writeln!(f, "  PHY ID {id:#010x}")?;
+        }
+        if let Some(link) = self.link {
+            writeln!(f, "  link {:?} {:?}", link.speed, link.duplex)?;
+        }
+        Ok(())
+    }
+}
+
+// =============================================================================
+// Sequenced PHY Reset
+// =============================================================================
+
+/// Stage of [`reset_and_probe_phy`], in execution order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PhyBringUpStage {
+    /// The EMAC is initialized, so REF_CLK is running (the DMA reset in
+    /// `Emac::init` cannot complete without it)
+    ClockStable,
+    /// Drive nRST low and hold it for the reset pulse
+    ResetAssert,
+    /// Drive nRST high and wait for the PHY to recover
+    ResetRelease,
+    /// Read the PHY identifier over MDIO
+    MdioProbe,
+}
+
+impl PhyBringUpStage {
+    /// Short name of the stage
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::ClockStable => "REF_CLK stable",
+            Self::ResetAssert => "nRST assert",
+            Self::ResetRelease => "nRST release",
+            Self::MdioProbe => "MDIO probe",
+        }
+    }
+}
+
+/// Failure of [`reset_and_probe_phy`]: the stage and its error
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PhyBringUpError {
+    /// Stage that failed; later stages were not run
+    pub stage: PhyBringUpStage,
+    /// Error reported by the stage
+    pub error: Error,
+}
+
+impl core::fmt::Display for PhyBringUpError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}: {}", self.stage.as_str(), self.error)
+    }
+}
+
+impl From<PhyBringUpError> for Error {
+    fn from(e: PhyBringUpError) -> Self {
+        e.error
+    }
+}
+
+/// nRST timing for [`reset_and_probe_phy`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PhyResetTiming {
+    /// How long nRST is held low, in microseconds
+    pub pulse_us: u32,
+    /// Wait after releasing nRST before the first MDIO access, in microseconds
+    pub recovery_us: u32,
+}
+
+impl PhyResetTiming {
+    /// LAN8720A: 100 µs minimum pulse and 800 µs recovery, with margin
+    pub const LAN8720A: Self = Self {
+        pulse_us: 200,
+        recovery_us: 1000,
+    };
+}
+
+impl Default for PhyResetTiming {
+    fn default() -> Self {
+        Self::LAN8720A
+    }
+}
+
+/// Placeholder reset "pin" for boards without a PHY reset GPIO
+///
+/// Setting it does nothing; [`reset_and_probe_phy`] skips the reset stages
+/// when given `None::<&mut NoResetPin>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct NoResetPin;
+
+impl ErrorType for NoResetPin {
+    type Error = Infallible;
+}
+
+impl OutputPin for NoResetPin {
+    fn set_low(&mut self) -> core::result::Result<(), Infallible> {
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> core::result::Result<(), Infallible> {
+        Ok(())
+    }
+}
+
+/// Reset the PHY in datasheet order and confirm it answers on MDIO
+///
+/// 1. [`ClockStable`](PhyBringUpStage::ClockStable): `emac` must be
+///    initialized, which proves REF_CLK is running.
+/// 2. [`ResetAssert`](PhyBringUpStage::ResetAssert): nRST low for
+///    `timing.pulse_us`.
+/// 3. [`ResetRelease`](PhyBringUpStage::ResetRelease): nRST high, then
+///    wait `timing.recovery_us`.
+/// 4. [`MdioProbe`](PhyBringUpStage::MdioProbe): read the PHY identifier.
+///
+/// Without a reset pin (`None`) the two reset stages are skipped. Returns
+/// the PHY identifier.
+///
+/// # Errors
+/// The first failing stage with its error: `IoError::InvalidState` if the
+/// EMAC is not initialized, `ConfigError::GpioError` if the pin cannot be
+/// driven, `ConfigError::InvalidPhyAddress` if the identifier reads as all
+/// zeros or ones, or the MDIO error.
+pub fn reset_and_probe_phy<
+    const RX_BUFS: usize,
+    const TX_BUFS: usize,
+    const BUF_SIZE: usize,
+    R: OutputPin,
+    P: PhyDriver,
+    M: MdioBus,
+    D: DelayNs,
+>(
+    emac: &Emac<RX_BUFS, TX_BUFS, BUF_SIZE>,
+    reset: Option<&mut R>,
+    timing: PhyResetTiming,
+    phy: &P,
+    mdio: &mut M,
+    delay: &mut D,
+) -> core::result::Result<u32, PhyBringUpError> {
+    let fail = |stage, error: Error| PhyBringUpError { stage, error };
+
+    if emac.state() == State::Uninitialized {
+        return Err(fail(
+            PhyBringUpStage::ClockStable,
+            IoError::InvalidState.into(),
+        ));
+    }
+
+    if let Some(pin) = reset {
+        pin.set_low()
+            .map_err(|_| fail(PhyBringUpStage::ResetAssert, ConfigError::GpioError.into()))?;
+        delay.delay_us(timing.pulse_us);
+        pin.set_high()
+            .map_err(|_| fail(PhyBringUpStage::ResetRelease, ConfigError::GpioError.into()))?;
+        delay.delay_us(timing.recovery_us);
+    }
+
+    match phy.phy_id(mdio) {
+        Ok(0 | 0xFFFF_FFFF) => Err(fail(
+            PhyBringUpStage::MdioProbe,
+            ConfigError::InvalidPhyAddress.into(),
+        )),
+        Ok(id) => Ok(id),
+        Err(e) => Err(fail(PhyBringUpStage::MdioProbe, e)),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::std_instead_of_alloc)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::driver::config::{Duplex, EmacConfig, Speed};
+    use crate::internal::register::sim;
+    use crate::phy::Lan8720a;
+    use crate::phy::lan8720a::{pscsr, reg};
+    use crate::testing::{MockDelay, MockMdioBus};
+    use std::format;
+
+    #[test]
+    fn new_report_skips_everything() {
+        let report = BringUpReport::new();
+        assert!(report.steps().all(|(_, o)| o == StepOutcome::Skipped));
+        assert!(!report.is_success());
+        assert_eq!(report.first_failure(), None);
+    }
+
+    #[test]
+    fn reset_failure_points_at_ref_clk() {
+        let mut report = BringUpReport::new();
+        report.record_init(&Err(ConfigError::ResetFailed.into()));
+
+        assert_eq!(report.outcome(BringUpStep::PinRouting), StepOutcome::Passed);
+        assert_eq!(
+            report.first_failure(),
+            Some((BringUpStep::Reset, ConfigError::ResetFailed.into()))
+        );
+        assert!(report.hint().unwrap().contains("REF_CLK"));
+    }
+
+    #[test]
+    fn invalid_config_fails_first_step() {
+        let mut report = BringUpReport::new();
+        report.record_init(&Err(ConfigError::InvalidMacAddress.into()));
+        assert_eq!(
+            report.first_failure().map(|(step, _)| step),
+            Some(BringUpStep::Config)
+        );
+        assert_eq!(report.outcome(BringUpStep::Reset), StepOutcome::Skipped);
+    }
+
+    #[test]
+    fn probe_phy_reports_wrong_address() {
+        let mut mdio = MockMdioBus::new();
+        mdio.setup_lan8720a(1);
+        let mut report = BringUpReport::new();
+
+        // PHY sits at address 1; address 0 reads back zeros
+        let link = report.probe_phy(&mut Lan8720a::new(0), &mut mdio, &mut MockDelay::new(), 100);
+        assert_eq!(link, None);
+        assert_eq!(
+            report.first_failure(),
+            Some((BringUpStep::PhyId, ConfigError::InvalidPhyAddress.into()))
+        );
+        assert!(report.hint().unwrap().contains("PHY address"));
+        assert_eq!(report.outcome(BringUpStep::PhyInit), StepOutcome::Skipped);
+    }
+
+    #[test]
+    fn probe_phy_times_out_without_cable() {
+        let mut mdio = MockMdioBus::new();
+        mdio.setup_lan8720a(1);
+        let mut delay = MockDelay::new();
+        let mut report = BringUpReport::new();
+
+        let link = report.probe_phy(&mut Lan8720a::new(1), &mut mdio, &mut delay, 500);
+        assert_eq!(link, None);
+        assert_eq!(report.outcome(BringUpStep::PhyInit), StepOutcome::Passed);
+        assert_eq!(
+            report.outcome(BringUpStep::Link),
+            StepOutcome::Failed(IoError::Timeout.into())
+        );
+        assert!(report.hint().unwrap().contains("cable"));
+        assert!(delay.total_ms() >= 500);
+    }
+
+    #[test]
+    fn full_success() {
+        let mut mdio = MockMdioBus::new();
+        mdio.setup_lan8720a(1);
+        mdio.simulate_link_up_100_fd(1);
+        mdio.set_register(1, reg::PSCSR, pscsr::AUTODONE | pscsr::HCDSPEED_100FD);
+        let mut report = BringUpReport::new();
+
+        report.record_init(&Ok(()));
+        let link = report
+            .probe_phy(&mut Lan8720a::new(1), &mut mdio, &mut MockDelay::new(), 100)
+            .unwrap();
+        assert_eq!((link.speed, link.duplex), (Speed::Mbps100, Duplex::Full));
+        assert!(report.is_success());
+        assert_eq!(report.hint(), None);
+        assert_eq!(report.phy_id(), Some(0x0007_C0F1));
+
+        let text = format!("{report}");
+        assert!(text.contains("[ ok ] DMA reset"));
+        assert!(!text.contains("FAIL"));
+    }
+
+    /// Reset pin recording every level it is driven to
+    #[derive(Default)]
+    struct RecordingPin {
+        levels: std::vec::Vec<bool>,
+        broken: bool,
+    }
+
+    impl ErrorType for RecordingPin {
+        type Error = embedded_hal::digital::ErrorKind;
+    }
+
+    impl OutputPin for RecordingPin {
+        fn set_low(&mut self) -> core::result::Result<(), Self::Error> {
+            self.set(false)
+        }
+
+        fn set_high(&mut self) -> core::result::Result<(), Self::Error> {
+            self.set(true)
+        }
+    }
+
+    impl RecordingPin {
+        fn set(
+            &mut self,
+            level: bool,
+        ) -> core::result::Result<(), embedded_hal::digital::ErrorKind> {
+            if self.broken {
+                return Err(embedded_hal::digital::ErrorKind::Other);
+            }
+            self.levels.push(level);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn phy_reset_runs_in_datasheet_order() {
+        sim::reset();
+        let mut emac: Emac<4, 4, 256> = Emac::new();
+        let mut mdio = MockMdioBus::new();
+        mdio.setup_lan8720a(1);
+        let phy = Lan8720a::new(1);
+        let mut pin = RecordingPin::default();
+        let mut delay = MockDelay::new();
+        let timing = PhyResetTiming::LAN8720A;
+
+        // No REF_CLK proof yet: the pin is left alone
+        let err = reset_and_probe_phy(&emac, Some(&mut pin), timing, &phy, &mut mdio, &mut delay)
+            .unwrap_err();
+        assert_eq!(err.stage, PhyBringUpStage::ClockStable);
+        assert!(pin.levels.is_empty());
+
+        emac.sim_init(EmacConfig::new());
+        let id = reset_and_probe_phy(&emac, Some(&mut pin), timing, &phy, &mut mdio, &mut delay);
+        assert_eq!(id, Ok(0x0007_C0F1));
+        assert_eq!(pin.levels, [false, true]);
+        assert_eq!(delay.total_ns(), 1_200_000);
+
+        let none = None::<&mut NoResetPin>;
+        let err = reset_and_probe_phy(
+            &emac,
+            none,
+            timing,
+            &Lan8720a::new(0),
+            &mut mdio,
+            &mut delay,
+        )
+        .unwrap_err();
+        assert_eq!(err.stage, PhyBringUpStage::MdioProbe);
+        assert_eq!(Error::from(err), ConfigError::InvalidPhyAddress.into());
+        assert_eq!(format!("{err}"), "MDIO probe: config: invalid PHY address");
+
+        pin.broken = true;
+        let err = reset_and_probe_phy(&emac, Some(&mut pin), timing, &phy, &mut mdio, &mut delay)
+            .unwrap_err();
+        assert_eq!(err.stage, PhyBringUpStage::ResetAssert);
+        assert_eq!(err.error, ConfigError::GpioError.into());
+    }
+}
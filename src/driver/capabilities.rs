@@ -0,0 +1,326 @@
+//! Self-describing capability report.
+//!
+//! [`Emac::capabilities`] gathers what this build of the driver can do
+//! (compiled features) and how the running instance is set up (ring sizes,
+//! offloads, filters, where the DMA buffers live) into one
+//! [`DriverCapabilities`] value. Network stacks can use it to adapt, e.g. to
+//! skip software checksums when offload is on; support tooling can print it
+//! as a banner with its `Display` impl.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let caps = emac.capabilities();
+//! if caps.rx_checksum_offload { /* trust hardware checksums */ }
+//! println!("{caps}");
+//! ```
+
+use super::config::{State, TxChecksumMode};
+use super::emac::Emac;
+use crate::internal::register::mac::MacRegs;
+
+/// Optional crate features this build was compiled with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CompiledFeatures {
+    /// `smoltcp` device integration
+    pub smoltcp: bool,
+    /// `embassy-net` driver integration
+    pub embassy_net: bool,
+    /// `esp-hal` integration
+    pub esp_hal: bool,
+    /// `async` wakers
+    pub async_support: bool,
+    /// `critical-section` shared access helpers
+    pub critical_section: bool,
+    /// `defmt` logging
+    pub defmt: bool,
+    /// `log` logging
+    pub log: bool,
+    /// `diag` ARP/ICMP responder
+    pub diag: bool,
+    /// `debug-checks` descriptor assertions
+    pub debug_checks: bool,
+    /// `crc-verify` software FCS check
+    pub crc_verify: bool,
+    /// `debug-console` Ethernet debug console
+    pub debug_console: bool,
+    /// `frame-dump` frame hexdumps
+    pub frame_dump: bool,
+    /// `frame-trace` TX frame lifecycle trace
+    pub frame_trace: bool,
+    /// `test-hooks` RX frame injection
+    pub test_hooks: bool,
+    /// `iram` hot paths placed in IRAM
+    pub iram: bool,
+}
+
+impl CompiledFeatures {
+    /// Features of the current build
+    #[must_use]
+    pub const fn current() -> Self {
+        Self {
+            smoltcp: cfg!(feature = "smoltcp"),
+            embassy_net: cfg!(feature = "embassy-net"),
+            esp_hal: cfg!(feature = "esp-hal"),
+            async_support: cfg!(feature = "async"),
+            critical_section: cfg!(feature = "critical-section"),
+            defmt: cfg!(feature = "defmt"),
+            log: cfg!(feature = "log"),
+            diag: cfg!(feature = "diag"),
+            debug_checks: cfg!(feature = "debug-checks"),
+            crc_verify: cfg!(feature = "crc-verify"),
+            debug_console: cfg!(feature = "debug-console"),
+            frame_dump: cfg!(feature = "frame-dump"),
+            frame_trace: cfg!(feature = "frame-trace"),
+            test_hooks: cfg!(feature = "test-hooks"),
+            iram: cfg!(feature = "iram"),
+        }
+    }
+}
+
+/// Memory region holding the DMA buffers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum BufferLocation {
+    /// Internal SRAM (required: the EMAC DMA cannot reach external RAM)
+    InternalSram,
+    /// External PSRAM (the DMA will not work from here)
+    ExternalPsram,
+    /// Any other address
+    Other,
+}
+
+impl BufferLocation {
+    /// Classify a data-bus address
+    #[must_use]
+    pub const fn of_address(addr: usize) -> Self {
+        #[cfg(feature = "esp32")]
+        let (sram, psram) = (0x3FFA_E000..0x4000_0000, 0x3F80_0000..0x3FC0_0000);
+        #[cfg(feature = "esp32p4")]
+        let (sram, psram) = (0x4FF0_0000..0x4FFC_0000, 0x4800_0000..0x4C00_0000);
+
+        if addr >= sram.start && addr < sram.end {
+            Self::InternalSram
+        } else if addr >= psram.start && addr < psram.end {
+            Self::ExternalPsram
+        } else {
+            Self::Other
+        }
+    }
+
+    /// Whether `len` bytes at `addr` lie entirely in memory the EMAC DMA
+    /// can read
+    #[must_use]
+    pub const fn dma_capable(addr: usize, len: usize) -> bool {
+        len > 0
+            && matches!(Self::of_address(addr), Self::InternalSram)
+            && matches!(Self::of_address(addr + (len - 1)), Self::InternalSram)
+    }
+
+    /// Short name for banners
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::InternalSram => "internal SRAM",
+            Self::ExternalPsram => "external PSRAM",
+            Self::Other => "other",
+        }
+    }
+}
+
+/// Compiled features and current setup of an EMAC instance
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DriverCapabilities {
+    /// Crate version
+    pub version: &'static str,
+    /// Target chip
+    pub chip: &'static str,
+    /// Optional features compiled in
+    pub features: CompiledFeatures,
+    /// Driver state when the report was taken
+    pub state: State,
+    /// RX checksum offload enabled
+    pub rx_checksum_offload: bool,
+    /// TX checksum insertion mode
+    pub tx_checksum: TxChecksumMode,
+    /// IEEE 1588 timestamping supported by the driver (not yet implemented)
+    pub ptp: bool,
+    /// VLAN tag filter active (always `false` before `init()`)
+    pub vlan_filter: bool,
+    /// Promiscuous mode enabled
+    pub promiscuous: bool,
+    /// Flow control enabled
+    pub flow_control: bool,
+    /// Dual-buffer (ring mode) descriptors in use
+    pub dual_buffer: bool,
+    /// Usable RX descriptor slots
+    pub rx_descriptors: usize,
+    /// Usable TX descriptor slots
+    pub tx_descriptors: usize,
+    /// Bytes per DMA buffer
+    pub buffer_size: usize,
+    /// Bytes of RAM used by the EMAC instance
+    pub memory_usage: usize,
+    /// Where the DMA buffers live
+    pub buffer_location: BufferLocation,
+}
+
+impl core::fmt::Display for DriverCapabilities {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let on = |b: bool| if b { "on" } else { "off" };
+        let features = self.features;
+
+        writeln!(f, "ph-esp32-mac {} ({})", self.version, self.chip)?;
+        write!(f, "  features:")?;
+        for (name, enabled) in [
+            ("smoltcp", features.smoltcp),
+            ("embassy-net", features.embassy_net),
+            ("esp-hal", features.esp_hal),
+            ("async", features.async_support),
+            ("critical-section", features.critical_section),
+            ("defmt", features.defmt),
+            ("log", features.log),
+            ("diag", features.diag),
+            ("debug-checks", features.debug_checks),
+            ("crc-verify", features.crc_verify),
+            ("debug-console", features.debug_console),
+            ("frame-dump", features.frame_dump),
+            ("frame-trace", features.frame_trace),
+            ("test-hooks", features.test_hooks),
+            ("iram", features.iram),
+        ] {
+            if enabled {
+                write!(f, " {name}")?;
+            }
+        }
+        writeln!(f)?;
+        writeln!(f, "  state: {:?}", self.state)?;
+        writeln!(
+            f,
+            "  rings: {} rx / {} tx x {} B{} ({} B total, {})",
+            self.rx_descriptors,
+            self.tx_descriptors,
+            self.buffer_size,
+            if self.dual_buffer { " dual-buffer" } else { "" },
+            self.memory_usage,
+            self.buffer_location.as_str()
+        )?;
+        writeln!(
+            f,
+            "  checksum: rx {}, tx {:?}",
+            on(self.rx_checksum_offload),
+            self.tx_checksum
+        )?;
+        write!(
+            f,
+            "  vlan filter {}, promiscuous {}, flow control {}, ptp {}",
+            on(self.vlan_filter),
+            on(self.promiscuous),
+            on(self.flow_control),
+            on(self.ptp)
+        )
+    }
+}
+
+impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
+    Emac<RX_BUFS, TX_BUFS, BUF_SIZE>
+{
+    /// Report compiled features and the current setup of this instance
+    ///
+    /// Safe to call in any state; registers are only read once the EMAC
+    /// has been initialized.
+    pub fn capabilities(&self) -> DriverCapabilities {
+        let buffer_location = if RX_BUFS > 0 {
+            BufferLocation::of_address(self.dma.rx_buffer(0).as_ptr() as usize)
+        } else {
+            BufferLocation::of_address(self.dma.tx_buffer(0).as_ptr() as usize)
+        };
+
+        DriverCapabilities {
+            version: env!("CARGO_PKG_VERSION"),
+            chip: if cfg!(feature = "esp32p4") {
+                "esp32p4"
+            } else {
+                "esp32"
+            },
+            features: CompiledFeatures::current(),
+            state: self.state(),
+            rx_checksum_offload: self.config.checksum.rx_checksum,
+            tx_checksum: self.config.checksum.tx_checksum,
+            ptp: false,
+            vlan_filter: self.state() != State::Uninitialized && MacRegs::is_vlan_filter_enabled(),
+            promiscuous: self.settings.promiscuous,
+            flow_control: self.settings.flow_control.enabled,
+            dual_buffer: self.dma.is_dual_buffer(),
+            rx_descriptors: self.dma.rx_slots(),
+            tx_descriptors: self.dma.tx_slots(),
+            buffer_size: self.dma.slot_capacity(),
+            memory_usage: Self::memory_usage(),
+            buffer_location,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::std_instead_of_alloc)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::driver::emac::EmacSmall;
+    use std::format;
+
+    #[test]
+    fn report_before_init() {
+        let emac = EmacSmall::new();
+        let caps = emac.capabilities();
+        assert_eq!(caps.state, State::Uninitialized);
+        assert_eq!(caps.rx_descriptors, 4);
+        assert_eq!(caps.tx_descriptors, 4);
+        assert_eq!(caps.buffer_size, EmacSmall::buffer_size());
+        assert!(!caps.vlan_filter);
+        assert!(!caps.ptp);
+        assert_eq!(caps.features, CompiledFeatures::current());
+        // Host test buffers are nowhere near the chip memory map
+        assert_eq!(caps.buffer_location, BufferLocation::Other);
+    }
+
+    #[cfg(feature = "esp32")]
+    #[test]
+    fn classifies_buffer_addresses() {
+        assert_eq!(
+            BufferLocation::of_address(0x3FFB_0000),
+            BufferLocation::InternalSram
+        );
+        assert_eq!(
+            BufferLocation::of_address(0x3F80_1000),
+            BufferLocation::ExternalPsram
+        );
+        assert_eq!(
+            BufferLocation::of_address(0x4008_0000),
+            BufferLocation::Other
+        );
+    }
+
+    #[cfg(feature = "esp32")]
+    #[test]
+    fn dma_capable_needs_the_whole_buffer_in_sram() {
+        assert!(BufferLocation::dma_capable(0x3FFB_0000, 1514));
+        assert!(BufferLocation::dma_capable(0x3FFF_FFFC, 4));
+        // Runs off the end of SRAM
+        assert!(!BufferLocation::dma_capable(0x3FFF_FFFC, 8));
+        // Starts before SRAM
+        assert!(!BufferLocation::dma_capable(0x3FFA_DFFC, 8));
+        assert!(!BufferLocation::dma_capable(0x3F80_1000, 64));
+        assert!(!BufferLocation::dma_capable(0x3FFB_0000, 0));
+    }
+
+    #[test]
+    fn banner_lists_rings() {
+        let banner = format!("{}", EmacSmall::new().capabilities());
+        assert!(banner.starts_with("ph-esp32-mac "));
+        assert!(banner.contains("rings: 4 rx / 4 tx"));
+    }
+}
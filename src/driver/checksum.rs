@@ -0,0 +1,157 @@
+//! RX checksum offload status of received frames.
+//!
+//! With RX checksum offload enabled
+//! ([`EmacConfig::with_rx_checksum`](super::config::EmacConfig::with_rx_checksum),
+//! GMAC `IPC`), the MAC checks the IPv4 header checksum and the TCP, UDP or
+//! ICMP payload checksum of every frame and reports the result in the
+//! extended status word (RDES4) of the RX descriptor.
+//! [`Emac::last_rx_checksum`](super::emac::Emac::last_rx_checksum) reports
+//! it for the last frame returned by `receive()`.
+//!
+//! The DMA drops frames that fail a check, so software normally only sees
+//! passes and frames the engine did not check: non-IP traffic, IP fragments
+//! and packets with IPv6 extension headers it does not parse.
+
+use crate::internal::dma::bits::{rdes0, rdes4};
+
+/// Transport protocol the checksum engine recognised
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum IpPayloadType {
+    /// Not IP, or a payload the engine did not process
+    Unknown,
+    /// UDP
+    Udp,
+    /// TCP
+    Tcp,
+    /// ICMP
+    Icmp,
+}
+
+impl IpPayloadType {
+    /// Decode the RDES4 IP payload type field
+    #[must_use]
+    pub const fn from_rdes4(rdes4_status: u32) -> Self {
+        match (rdes4_status & rdes4::IP_PAYLOAD_TYPE_MASK) >> rdes4::IP_PAYLOAD_TYPE_SHIFT {
+            1 => Self::Udp,
+            2 => Self::Tcp,
+            3 => Self::Icmp,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// Checksum offload verdict for one received frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RxChecksumStatus {
+    /// Frame carries an IPv4 packet
+    pub ipv4: bool,
+    /// Frame carries an IPv6 packet
+    pub ipv6: bool,
+    /// Transport protocol of the payload
+    pub payload_type: IpPayloadType,
+    /// IPv4 header checksum result; `None` if it was not checked
+    pub header_ok: Option<bool>,
+    /// TCP/UDP/ICMP checksum result; `None` if it was not checked
+    pub payload_ok: Option<bool>,
+}
+
+impl RxChecksumStatus {
+    /// Verdict from a frame's RDES0 and RDES4 status words
+    ///
+    /// Returns `None` if the descriptor carries no extended status, which
+    /// is the case for every frame while checksum offload is off.
+    #[must_use]
+    #[inline(always)]
+    pub const fn from_descriptor(rdes0_status: u32, rdes4_status: u32) -> Option<Self> {
+        if rdes0_status & rdes0::EXT_STATUS == 0 {
+            return None;
+        }
+        let ipv4 = rdes4_status & rdes4::IPV4_PKT != 0;
+        let ipv6 = rdes4_status & rdes4::IPV6_PKT != 0;
+        let checked = (ipv4 || ipv6) && rdes4_status & rdes4::IP_CHECKSUM_BYPASS == 0;
+        let payload_type = IpPayloadType::from_rdes4(rdes4_status);
+
+        Some(Self {
+            ipv4,
+            ipv6,
+            payload_type,
+            header_ok: if checked && ipv4 {
+                Some(rdes4_status & rdes4::IP_HEADER_ERR == 0)
+            } else {
+                None
+            },
+            payload_ok: if checked && !matches!(payload_type, IpPayloadType::Unknown) {
+                Some(rdes4_status & rdes4::IP_PAYLOAD_ERR == 0)
+            } else {
+                None
+            },
+        })
+    }
+
+    /// The hardware checked the transport checksum and every check passed
+    ///
+    /// When this is `false` software has to validate the checksums itself.
+    #[must_use]
+    pub const fn verified(&self) -> bool {
+        !matches!(self.header_ok, Some(false)) && matches!(self.payload_ok, Some(true))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_extended_status_means_unchecked() {
+        assert_eq!(
+            RxChecksumStatus::from_descriptor(0, rdes4::IPV4_PKT | 2),
+            None
+        );
+    }
+
+    #[test]
+    fn tcp_over_ipv4_verified() {
+        let status =
+            RxChecksumStatus::from_descriptor(rdes0::EXT_STATUS, rdes4::IPV4_PKT | 2).unwrap();
+        assert_eq!(status.payload_type, IpPayloadType::Tcp);
+        assert_eq!(status.header_ok, Some(true));
+        assert_eq!(status.payload_ok, Some(true));
+        assert!(status.verified());
+    }
+
+    #[test]
+    fn ipv6_has_no_header_checksum() {
+        let status =
+            RxChecksumStatus::from_descriptor(rdes0::EXT_STATUS, rdes4::IPV6_PKT | 1).unwrap();
+        assert_eq!(status.header_ok, None);
+        assert_eq!(status.payload_ok, Some(true));
+        assert!(status.verified());
+    }
+
+    #[test]
+    fn errors_and_bypass() {
+        let bad = RxChecksumStatus::from_descriptor(
+            rdes0::EXT_STATUS,
+            rdes4::IPV4_PKT | rdes4::IP_PAYLOAD_ERR | 1,
+        )
+        .unwrap();
+        assert_eq!(bad.payload_ok, Some(false));
+        assert!(!bad.verified());
+
+        let bypassed = RxChecksumStatus::from_descriptor(
+            rdes0::EXT_STATUS,
+            rdes4::IPV4_PKT | rdes4::IP_CHECKSUM_BYPASS | 1,
+        )
+        .unwrap();
+        assert_eq!((bypassed.header_ok, bypassed.payload_ok), (None, None));
+        assert!(!bypassed.verified());
+
+        // Fragments: header checked, payload type unknown
+        let fragment =
+            RxChecksumStatus::from_descriptor(rdes0::EXT_STATUS, rdes4::IPV4_PKT).unwrap();
+        assert_eq!(fragment.header_ok, Some(true));
+        assert!(!fragment.verified());
+    }
+}
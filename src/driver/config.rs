@@ -1,9 +1,15 @@
 //! Configuration types for ESP32 EMAC driver
 
+use super::error::{ConfigError, ConfigResult};
+use super::frame_format::FrameFormat;
+use super::keepalive::Keepalive;
 use crate::internal::constants::{
-    DEFAULT_FLOW_HIGH_WATER, DEFAULT_FLOW_LOW_WATER, DEFAULT_MAC_ADDR, MDC_MAX_FREQ_HZ,
-    PAUSE_TIME_MAX, SOFT_RESET_TIMEOUT_MS,
+    DEFAULT_FLOW_HIGH_WATER, DEFAULT_FLOW_LOW_WATER, DEFAULT_MAC_ADDR, FLUSH_TIMEOUT_US,
+    MDC_MAX_FREQ_HZ, MII_BUSY_TIMEOUT_US, PAUSE_TIME_MAX, SOFT_RESET_TIMEOUT_MS,
 };
+use crate::internal::register::mac::pcf;
+use crate::logic::frame::hash_index;
+pub use crate::logic::frame::{ipv4_multicast_mac, is_multicast_mac, mac_from_unique_id};
 
 /// Ethernet link speed
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -60,7 +66,137 @@ impl Default for RmiiClockMode {
     }
 }
 
+/// IO_MUX pad drive strength (`FUN_DRV` field)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum DriveStrength {
+    /// ~5 mA
+    Ma5 = 0,
+    /// ~10 mA
+    Ma10 = 1,
+    /// ~20 mA (reset value)
+    #[default]
+    Ma20 = 2,
+    /// ~40 mA
+    Ma40 = 3,
+}
+
+/// Weak pull resistor on an input pad (`FUN_WPU`/`FUN_WPD`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PadPull {
+    /// Floating input
+    #[default]
+    None,
+    /// Weak pull-up
+    Up,
+    /// Weak pull-down
+    Down,
+}
+
+/// RMII clock timing and pad electrical settings
+///
+/// Knobs for boards whose RMII traces are marginal, typically seen as CRC
+/// errors at 100 Mbps that go away at 10 Mbps, or for PHY modules on long
+/// flying leads. The clock delay and clock drive only apply when the ESP32
+/// generates the clock ([`RmiiClockMode::InternalOutput`]); an external
+/// oscillator's phase and edge rate are set by the board.
+///
+/// The ESP32 IO_MUX has no input filter or Schmitt trigger control, so the
+/// RX pads only offer a weak pull; a pull-down keeps floating RXD/CRS_DV
+/// lines quiet while the PHY is held in reset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RmiiTiming {
+    /// Output clock delay in `EX_CLKOUT_CONF` delay steps
+    /// (0..=[`MAX_CLOCK_DELAY`](Self::MAX_CLOCK_DELAY))
+    pub clock_delay: u8,
+    /// Drive strength of the clock output pad (`None` leaves the pad as is)
+    pub clock_drive: Option<DriveStrength>,
+    /// Drive strength of the TXD0/TXD1/TX_EN pads
+    pub tx_drive: DriveStrength,
+    /// Pull on the RXD0/RXD1/CRS_DV pads
+    pub rx_pull: PadPull,
+}
+
+impl Default for RmiiTiming {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RmiiTiming {
+    /// Largest supported clock delay (2-bit field)
+    pub const MAX_CLOCK_DELAY: u8 = 3;
+
+    /// No added delay, clock pad left unchanged, strongest TX drive and
+    /// floating RX pads
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            clock_delay: 0,
+            clock_drive: None,
+            tx_drive: DriveStrength::Ma40,
+            rx_pull: PadPull::None,
+        }
+    }
+
+    /// Set the output clock delay
+    #[must_use]
+    pub const fn with_clock_delay(mut self, delay: u8) -> Self {
+        self.clock_delay = delay;
+        self
+    }
+
+    /// Set the drive strength of the clock output pad
+    #[must_use]
+    pub const fn with_clock_drive(mut self, drive: DriveStrength) -> Self {
+        self.clock_drive = Some(drive);
+        self
+    }
+
+    /// Set the drive strength of the TX data pads
+    ///
+    /// A weaker drive slows the edges, which reduces ringing and EMI on
+    /// short traces to an on-board PHY.
+    #[must_use]
+    pub const fn with_tx_drive(mut self, drive: DriveStrength) -> Self {
+        self.tx_drive = drive;
+        self
+    }
+
+    /// Set the pull on the RX data pads
+    #[must_use]
+    pub const fn with_rx_pull(mut self, pull: PadPull) -> Self {
+        self.rx_pull = pull;
+        self
+    }
+
+    /// Check the timing values fit their register fields
+    ///
+    /// # Errors
+    /// - `InvalidConfig` - `clock_delay` is above [`MAX_CLOCK_DELAY`](Self::MAX_CLOCK_DELAY)
+    pub const fn validate(&self) -> ConfigResult<()> {
+        if self.clock_delay > Self::MAX_CLOCK_DELAY {
+            return Err(ConfigError::InvalidConfig);
+        }
+        Ok(())
+    }
+}
+
 /// DMA burst length configuration
+///
+/// These are all the programmable burst length (PBL) values the DMA
+/// accepts. With 8xPBL mode ([`EmacConfig::with_dma_pbl_x8`]) each is
+/// multiplied by 8, giving 8 to 256 beats; see [`beats`](Self::beats).
+///
+/// Longer bursts move a frame in fewer bus transactions but hold the bus
+/// longer per transaction. Small frames (ARP, TCP ACKs) rarely fill a long
+/// burst, so 8 or 16 beats lose little for them while leaving more room to
+/// other bus masters; bulk transfers of full-size frames favour 32 beats.
+/// The effect depends on what else uses internal SRAM, so measure with the
+/// application's own traffic before moving away from the default.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[repr(u8)]
@@ -75,7 +211,7 @@ pub enum DmaBurstLen {
     Burst8 = 8,
     /// 16 beat burst
     Burst16 = 16,
-    /// 32 beat burst (default, best performance)
+    /// 32 beat burst (default)
     #[default]
     Burst32 = 32,
 }
@@ -86,6 +222,99 @@ impl DmaBurstLen {
     pub const fn to_pbl(self) -> u32 {
         self as u32
     }
+
+    /// Every burst length, shortest first
+    pub const ALL: [DmaBurstLen; 6] = [
+        DmaBurstLen::Burst1,
+        DmaBurstLen::Burst2,
+        DmaBurstLen::Burst4,
+        DmaBurstLen::Burst8,
+        DmaBurstLen::Burst16,
+        DmaBurstLen::Burst32,
+    ];
+
+    /// Maximum beats per burst, taking 8xPBL mode into account
+    #[must_use]
+    pub const fn beats(self, pbl_x8: bool) -> u32 {
+        if pbl_x8 {
+            self.to_pbl() * 8
+        } else {
+            self.to_pbl()
+        }
+    }
+}
+
+/// DMA burst tuning presets
+///
+/// Each preset sets the TX and RX programmable burst lengths plus the
+/// 8xPBL, fixed-burst and mixed-burst options in one step. Use
+/// [`EmacConfig::with_dma_burst_preset`] to apply one.
+///
+/// AHB bursts needed to move one full-size frame (1518 bytes, 380 beats of
+/// 32 bits), counted from the burst lengths rather than timed on hardware:
+///
+/// | Preset       | TX bursts | RX bursts |
+/// |--------------|-----------|-----------|
+/// | `Throughput` | 12        | 12        |
+/// | `Balanced`   | 24        | 12        |
+/// | `SharedBus`  | 48        | 48        |
+/// | `Minimal`    | 380       | 380       |
+///
+/// Fewer bursts mean less arbitration overhead per frame; shorter bursts
+/// let other masters in between. No throughput or latency figures have
+/// been recorded on an ESP32 for these presets, and how they trade off
+/// depends on the other SRAM traffic, so time the application's own
+/// workload before moving off the default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum DmaBurstPreset {
+    /// 32-beat TX and RX bursts (default, fewest bursts per frame)
+    #[default]
+    Throughput,
+    /// 16-beat TX and 32-beat RX bursts, keeping RX bursts long
+    Balanced,
+    /// 8-beat bursts with mixed burst enabled and fixed bursts off, for
+    /// sharing SRAM bandwidth with WiFi or other bus masters
+    SharedBus,
+    /// Single-beat bursts (shortest bus hold, most bursts per frame)
+    Minimal,
+}
+
+impl DmaBurstPreset {
+    /// TX programmable burst length for this preset
+    #[must_use]
+    pub const fn tx_burst_len(self) -> DmaBurstLen {
+        match self {
+            Self::Throughput => DmaBurstLen::Burst32,
+            Self::Balanced => DmaBurstLen::Burst16,
+            Self::SharedBus => DmaBurstLen::Burst8,
+            Self::Minimal => DmaBurstLen::Burst1,
+        }
+    }
+
+    /// RX programmable burst length for this preset
+    #[must_use]
+    pub const fn rx_burst_len(self) -> DmaBurstLen {
+        match self {
+            Self::Throughput | Self::Balanced => DmaBurstLen::Burst32,
+            Self::SharedBus => DmaBurstLen::Burst8,
+            Self::Minimal => DmaBurstLen::Burst1,
+        }
+    }
+
+    /// Whether this preset enables mixed burst mode
+    #[must_use]
+    pub const fn mixed_burst(self) -> bool {
+        matches!(self, Self::SharedBus)
+    }
+
+    /// Whether this preset uses fixed-length bursts
+    ///
+    /// Off for presets with mixed burst, which only applies without it.
+    #[must_use]
+    pub const fn fixed_burst(self) -> bool {
+        !self.mixed_burst()
+    }
 }
 
 /// Maximum number of additional MAC address filter slots
@@ -148,6 +377,133 @@ impl MacAddressFilter {
     }
 }
 
+/// VLAN tag filter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct VlanFilter {
+    /// VLAN Identifier (0-4095), or the full 16-bit tag if `vid_only` is off
+    pub vid: u16,
+    /// Compare only the 12-bit VID instead of the full tag
+    pub vid_only: bool,
+    /// Pass frames that do NOT match (exclusion filter)
+    pub inverse: bool,
+    /// Match S-VLAN (0x88A8) instead of C-VLAN (0x8100) tags
+    pub svlan: bool,
+}
+
+impl VlanFilter {
+    /// Accept only C-VLAN frames with VLAN ID `vid`
+    #[must_use]
+    pub const fn new(vid: u16) -> Self {
+        Self {
+            vid,
+            vid_only: true,
+            inverse: false,
+            svlan: false,
+        }
+    }
+
+    /// Pass the frames that do not match instead
+    #[must_use]
+    pub const fn with_inverse(mut self, inverse: bool) -> Self {
+        self.inverse = inverse;
+        self
+    }
+
+    /// Match S-VLAN (802.1ad) tags
+    #[must_use]
+    pub const fn with_svlan(mut self, svlan: bool) -> Self {
+        self.svlan = svlan;
+        self
+    }
+}
+
+/// Receive filters programmed by `Emac::init`
+///
+/// Written after the software reset and before RX can be enabled, so the
+/// first frame received already goes through them. Change them afterwards
+/// with the runtime filter methods on `Emac`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FilterConfig {
+    /// Perfect address filters for slots 1-4
+    pub mac_filters: [Option<MacAddressFilter>; MAC_FILTER_SLOTS],
+    /// A filter was added with every slot already taken; see
+    /// [`validate`](Self::validate)
+    pub mac_filter_overflow: bool,
+    /// Initial 64-bit hash table
+    pub hash_table: u64,
+    /// Filter multicast destinations through the hash table
+    pub hash_multicast: bool,
+    /// Filter unicast destinations through the hash table as well
+    pub hash_unicast: bool,
+    /// VLAN tag filter (`None` leaves VLAN filtering off)
+    pub vlan: Option<VlanFilter>,
+}
+
+impl Default for FilterConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FilterConfig {
+    /// No filters beyond the primary MAC address
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            mac_filters: [None; MAC_FILTER_SLOTS],
+            mac_filter_overflow: false,
+            hash_table: 0,
+            hash_multicast: false,
+            hash_unicast: false,
+            vlan: None,
+        }
+    }
+
+    /// Number of perfect filter slots in use
+    #[must_use]
+    pub const fn mac_filter_count(&self) -> usize {
+        let mut count = 0;
+        let mut i = 0;
+        while i < MAC_FILTER_SLOTS {
+            if self.mac_filters[i].is_some() {
+                count += 1;
+            }
+            i += 1;
+        }
+        count
+    }
+
+    /// Check the filters for values the hardware cannot use
+    ///
+    /// # Errors
+    /// - `InvalidConfig` - More perfect filters were added than
+    ///   [`MAC_FILTER_SLOTS`], a byte mask covers more than the six address
+    ///   bytes, or a VID-only VLAN filter has a VID above 4095
+    pub const fn validate(&self) -> ConfigResult<()> {
+        if self.mac_filter_overflow {
+            return Err(ConfigError::InvalidConfig);
+        }
+        let mut i = 0;
+        while i < MAC_FILTER_SLOTS {
+            if let Some(filter) = &self.mac_filters[i]
+                && filter.byte_mask & !0x3F != 0
+            {
+                return Err(ConfigError::InvalidConfig);
+            }
+            i += 1;
+        }
+        if let Some(vlan) = &self.vlan
+            && vlan.vid_only
+            && vlan.vid > 0x0FFF
+        {
+            return Err(ConfigError::InvalidConfig);
+        }
+        Ok(())
+    }
+}
+
 // =============================================================================
 // Hardware-Fixed Pin Assignments
 // =============================================================================
@@ -267,7 +623,249 @@ pub enum TxChecksumMode {
     Full = 3,
 }
 
+/// When TX descriptors request an interrupt on completion
+///
+/// Setting the IC bit on every frame costs one interrupt per frame. Bulk
+/// senders can batch completions so only every Nth frame interrupts. The
+/// driver also sets IC on a frame that fills the TX ring and on frames sent
+/// with `Emac::transmit_burst_end`, and enables the TX buffer unavailable
+/// interrupt so a drained ring always wakes TX waiters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TxInterruptPolicy {
+    /// Interrupt on every frame
+    #[default]
+    EveryFrame,
+    /// Interrupt on every Nth frame (0 and 1 behave like `EveryFrame`)
+    EveryNth(u16),
+}
+
+impl TxInterruptPolicy {
+    /// Number of frames per completion interrupt
+    #[must_use]
+    pub const fn interval(self) -> u16 {
+        match self {
+            TxInterruptPolicy::EveryFrame | TxInterruptPolicy::EveryNth(0) => 1,
+            TxInterruptPolicy::EveryNth(n) => n,
+        }
+    }
+
+    /// Whether completions are batched across frames
+    #[must_use]
+    pub const fn is_batched(self) -> bool {
+        self.interval() > 1
+    }
+}
+
+/// Where received MAC control frames (EtherType `0x8808`) go
+///
+/// The MAC acts on PAUSE frames for flow control whatever the mode; this
+/// only decides whether control frames also reach the RX ring. PAUSE frames
+/// that reach the driver are counted in `Statistics::rx_pause`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ControlFrameFilter {
+    /// The MAC drops every control frame; nothing is counted
+    DropAll,
+    /// The driver counts and drops PAUSE frames, other control frames are
+    /// delivered
+    #[default]
+    DropPause,
+    /// Deliver control frames that pass the destination address filter
+    PassFiltered,
+    /// Deliver every control frame
+    PassAll,
+}
+
+impl ControlFrameFilter {
+    /// GMACFF `PCF` field value for this mode
+    ///
+    /// `DropPause` lets PAUSE frames into the ring (`PCF` = pass all) so
+    /// the driver can count them before dropping them.
+    #[must_use]
+    pub const fn pcf(self) -> u32 {
+        match self {
+            ControlFrameFilter::DropAll => pcf::NONE,
+            ControlFrameFilter::DropPause | ControlFrameFilter::PassAll => pcf::ALL,
+            ControlFrameFilter::PassFiltered => pcf::FILTERED,
+        }
+    }
+}
+
+/// Which IEEE 802.3 length-field frames `receive()` delivers
+///
+/// The MAC passes both Ethernet II and 802.3/LLC frames; the driver reads
+/// each frame's header (see `FrameFormat`) and drops the ones this mode
+/// rejects, counting them in `Statistics::rx_llc_dropped`. Ethernet II
+/// frames are always delivered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum LlcFrameFilter {
+    /// Deliver every 802.3 frame
+    #[default]
+    PassAll,
+    /// Deliver SNAP frames carrying an EtherType, drop all other 802.3
+    /// frames
+    PassSnap,
+    /// Drop every 802.3 frame
+    DropAll,
+}
+
+impl LlcFrameFilter {
+    /// True if a frame of this format is delivered
+    #[must_use]
+    #[inline(always)]
+    pub const fn accepts(self, format: &FrameFormat) -> bool {
+        match self {
+            LlcFrameFilter::PassAll => true,
+            LlcFrameFilter::PassSnap => format.ether_type().is_some(),
+            LlcFrameFilter::DropAll => !format.is_8023(),
+        }
+    }
+}
+
+/// How the smoltcp adapter hands frames to the stack
+///
+/// Only read by the `smoltcp` integration; other users of the driver
+/// choose per call between `receive()` and borrowing APIs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TokenStrategy {
+    /// Copy each frame through a stack buffer
+    ///
+    /// The ring descriptor is released before smoltcp sees the frame, so
+    /// a slow consumer cannot hold up the DMA.
+    #[default]
+    Copy,
+    /// Let smoltcp read and write frames in the DMA ring buffers
+    ///
+    /// Saves one frame copy per direction, but the descriptor stays with
+    /// the CPU until the token's closure returns; an application that does
+    /// heavy work inside the socket callbacks starves the ring. Frames
+    /// that span more than one buffer, and TX frames matching a header
+    /// rewrite rule, still take the copy path.
+    ZeroCopy,
+}
+
+/// Parameters that can be changed while the EMAC is running
+///
+/// [`EmacConfig`] supplies the initial values at `init()`; afterwards the
+/// driver owns the live copy, read it with `Emac::settings()` and change it
+/// with `Emac::apply_settings()`. Everything else in [`EmacConfig`] is
+/// structural and fixed until the EMAC is re-initialized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RuntimeSettings {
+    /// Enable promiscuous mode (receive all frames)
+    pub promiscuous: bool,
+    /// Flow control configuration (water marks, pause time)
+    pub flow_control: FlowControlConfig,
+    /// TX completion interrupt batching
+    pub tx_interrupt_policy: TxInterruptPolicy,
+    /// Disposition of received MAC control frames
+    pub control_frames: ControlFrameFilter,
+    /// Disposition of received IEEE 802.3 length-field frames
+    pub llc_frames: LlcFrameFilter,
+    /// Stamp received frames with the clock time of their RX interrupt
+    pub rx_soft_timestamps: bool,
+    /// Frame hand-off used by the smoltcp adapter
+    pub token_strategy: TokenStrategy,
+    /// Broadcast sent after a transmit silence; `None` disables it
+    pub keepalive: Option<Keepalive>,
+}
+
+impl Default for RuntimeSettings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RuntimeSettings {
+    /// Create settings matching the [`EmacConfig::new`] defaults
+    #[must_use]
+    pub const fn new() -> Self {
+        EmacConfig::new().runtime_settings()
+    }
+
+    /// Enable or disable promiscuous mode
+    #[must_use]
+    pub const fn with_promiscuous(mut self, enabled: bool) -> Self {
+        self.promiscuous = enabled;
+        self
+    }
+
+    /// Set the flow control configuration
+    #[must_use]
+    pub const fn with_flow_control(mut self, flow_control: FlowControlConfig) -> Self {
+        self.flow_control = flow_control;
+        self
+    }
+
+    /// Set the TX completion interrupt policy
+    #[must_use]
+    pub const fn with_tx_interrupt_policy(mut self, policy: TxInterruptPolicy) -> Self {
+        self.tx_interrupt_policy = policy;
+        self
+    }
+
+    /// Set where received MAC control frames go
+    #[must_use]
+    pub const fn with_control_frames(mut self, filter: ControlFrameFilter) -> Self {
+        self.control_frames = filter;
+        self
+    }
+
+    /// Set which received IEEE 802.3 length-field frames are delivered
+    #[must_use]
+    pub const fn with_llc_frames(mut self, filter: LlcFrameFilter) -> Self {
+        self.llc_frames = filter;
+        self
+    }
+
+    /// Enable or disable software RX timestamps
+    ///
+    /// Needs a [`Clock`](crate::hal::Clock) installed on the EMAC; see
+    /// [`rx_timestamp`](super::rx_timestamp) for the accuracy.
+    #[must_use]
+    pub const fn with_rx_soft_timestamps(mut self, enabled: bool) -> Self {
+        self.rx_soft_timestamps = enabled;
+        self
+    }
+
+    /// Set how the smoltcp adapter hands frames to the stack
+    #[must_use]
+    pub const fn with_token_strategy(mut self, strategy: TokenStrategy) -> Self {
+        self.token_strategy = strategy;
+        self
+    }
+
+    /// Set the link-layer keepalive; see
+    /// [`keepalive`](super::keepalive)
+    #[must_use]
+    pub const fn with_keepalive(mut self, keepalive: Option<Keepalive>) -> Self {
+        self.keepalive = keepalive;
+        self
+    }
+
+    /// Check the settings for consistency
+    ///
+    /// # Errors
+    /// - `InvalidConfig` - Flow control low water mark is above the high
+    ///   water mark
+    pub const fn validate(&self) -> ConfigResult<()> {
+        if self.flow_control.low_water_mark > self.flow_control.high_water_mark {
+            return Err(ConfigError::InvalidConfig);
+        }
+        Ok(())
+    }
+}
+
 /// Complete EMAC configuration
+///
+/// `promiscuous`, `flow_control`, `tx_interrupt_policy`, `control_frames`,
+/// `llc_frames`, `rx_soft_timestamps`, `token_strategy` and `keepalive` are
+/// only the initial [`RuntimeSettings`]; change them on a running EMAC with
+/// `Emac::apply_settings()`.
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct EmacConfig {
@@ -275,12 +873,41 @@ pub struct EmacConfig {
     pub phy_interface: PhyInterface,
     /// RMII clock mode (only used if phy_interface is RMII)
     pub rmii_clock: RmiiClockMode,
+    /// RMII clock delay and pad drive/pull settings
+    pub rmii_timing: RmiiTiming,
     /// MAC address (6 bytes)
     pub mac_address: [u8; 6],
-    /// DMA burst length
+    /// DMA burst length (TX PBL, and RX PBL unless `dma_rx_burst_len` is set)
     pub dma_burst_len: DmaBurstLen,
+    /// Separate RX DMA burst length (`None` uses `dma_burst_len`)
+    pub dma_rx_burst_len: Option<DmaBurstLen>,
+    /// Multiply both burst lengths by 8 (8xPBL mode)
+    pub dma_pbl_x8: bool,
+    /// Enable mixed burst (fixed bursts up to 16 beats, INCR above)
+    ///
+    /// The DMA ignores it while `dma_fixed_burst` is on, so
+    /// [`validate`](Self::validate) rejects both being set.
+    pub dma_mixed_burst: bool,
+    /// Use fixed-length AHB bursts (SINGLE, INCR4, INCR8, INCR16)
+    ///
+    /// When off, the DMA issues undefined-length INCR bursts, which end
+    /// early when a transfer does not fill a whole burst.
+    pub dma_fixed_burst: bool,
+    /// Align bursts to the burst size (address-aligned beats)
+    ///
+    /// With fixed bursts, the first burst of an unaligned buffer is split
+    /// so every later one starts on a burst boundary.
+    pub dma_address_aligned: bool,
     /// Software reset timeout in milliseconds
     pub sw_reset_timeout_ms: u32,
+    /// Time `Emac::stop` waits for the transmit DMA to stop, in
+    /// microseconds
+    pub tx_stop_timeout_us: u32,
+    /// Time `Emac::stop` waits for the TX FIFO flush, in microseconds
+    pub fifo_flush_timeout_us: u32,
+    /// Time a PHY register access waits for the MII busy bit, in
+    /// microseconds
+    pub mii_busy_timeout_us: u32,
     /// MDC clock frequency in Hz (max 2.5 MHz per IEEE 802.3)
     pub mdc_freq_hz: u32,
     /// Enable promiscuous mode (receive all frames)
@@ -289,6 +916,60 @@ pub struct EmacConfig {
     pub checksum: ChecksumConfig,
     /// Flow control configuration
     pub flow_control: FlowControlConfig,
+    /// TX completion interrupt batching
+    pub tx_interrupt_policy: TxInterruptPolicy,
+    /// Disposition of received MAC control frames
+    pub control_frames: ControlFrameFilter,
+    /// Disposition of received IEEE 802.3 length-field frames
+    pub llc_frames: LlcFrameFilter,
+    /// Stamp received frames with the clock time of their RX interrupt
+    pub rx_soft_timestamps: bool,
+    /// Frame hand-off used by the smoltcp adapter
+    pub token_strategy: TokenStrategy,
+    /// Broadcast sent after a transmit silence; `None` disables it
+    pub keepalive: Option<Keepalive>,
+    /// Use ring-mode descriptors with two buffers each
+    ///
+    /// A frame that spans two buffers then needs a single descriptor, at
+    /// the cost of halving the descriptor count.
+    pub dma_dual_buffer: bool,
+    /// TX descriptors held back for `Emac::transmit_reserved`
+    ///
+    /// Ordinary transmits report a full ring while only this many
+    /// descriptors are free, so PTP event messages and control frames can
+    /// still be queued under load.
+    pub tx_reserved_descriptors: usize,
+    /// RX descriptors kept for unicast frames addressed to us
+    ///
+    /// Broadcast, multicast and promiscuous frames waiting in the RX ring
+    /// may hold at most the other descriptors; the driver drops the oldest
+    /// of them beyond that in `Emac::receive` and `Emac::rebalance_rx`, so
+    /// a flood cannot starve our own traffic. 0 disables the partition.
+    pub rx_unicast_reserve: usize,
+    /// Leave RX disabled in `Emac::start` until `Emac::enable_rx`
+    ///
+    /// Keeps broadcast traffic from filling the RX ring while the
+    /// application is still bringing up its network stack.
+    pub deferred_rx: bool,
+    /// Receive every frame for this many milliseconds after RX is enabled
+    ///
+    /// For provisioning flows that must catch the first broadcast (e.g. a
+    /// discovery beacon) before the filters are final. When the window
+    /// ends, the configured filter set takes over in one register write.
+    /// Timed with the driver clock (`Emac::set_clock` or
+    /// `Emac::set_event_clock`); without one the window stays open until
+    /// `Emac::end_receive_all_grace`. 0 disables the window.
+    pub startup_receive_all_ms: u32,
+    /// Hand frames with receive errors and undersized frames to the driver
+    ///
+    /// The MAC normally drops them in its FIFO, so they never show up in
+    /// the statistics. With this set, `receive()` counts them
+    /// (`Statistics::rx_crc_errors`, `Statistics::rx_runts`) and drops
+    /// them; [`DuplexMismatchDetector`](super::duplex::DuplexMismatchDetector)
+    /// needs this to spot a half-duplex link partner.
+    pub forward_error_frames: bool,
+    /// Address, hash and VLAN filters programmed during `Emac::init`
+    pub filters: FilterConfig,
 }
 
 impl Default for EmacConfig {
@@ -296,13 +977,35 @@ impl Default for EmacConfig {
         Self {
             phy_interface: PhyInterface::default(),
             rmii_clock: RmiiClockMode::default(),
+            rmii_timing: RmiiTiming::new(),
             mac_address: DEFAULT_MAC_ADDR,
             dma_burst_len: DmaBurstLen::default(),
+            dma_rx_burst_len: None,
+            dma_pbl_x8: false,
+            dma_mixed_burst: false,
+            dma_fixed_burst: true,
+            dma_address_aligned: true,
             sw_reset_timeout_ms: SOFT_RESET_TIMEOUT_MS,
+            tx_stop_timeout_us: FLUSH_TIMEOUT_US,
+            fifo_flush_timeout_us: FLUSH_TIMEOUT_US,
+            mii_busy_timeout_us: MII_BUSY_TIMEOUT_US,
             mdc_freq_hz: MDC_MAX_FREQ_HZ,
             promiscuous: false,
             checksum: ChecksumConfig::default(),
             flow_control: FlowControlConfig::default(),
+            tx_interrupt_policy: TxInterruptPolicy::EveryFrame,
+            control_frames: ControlFrameFilter::DropPause,
+            llc_frames: LlcFrameFilter::PassAll,
+            rx_soft_timestamps: false,
+            token_strategy: TokenStrategy::Copy,
+            keepalive: None,
+            dma_dual_buffer: false,
+            tx_reserved_descriptors: 0,
+            rx_unicast_reserve: 0,
+            deferred_rx: false,
+            startup_receive_all_ms: 0,
+            forward_error_frames: false,
+            filters: FilterConfig::new(),
         }
     }
 }
@@ -314,9 +1017,18 @@ impl EmacConfig {
         Self {
             phy_interface: PhyInterface::Rmii,
             rmii_clock: RmiiClockMode::ExternalInput { gpio: 0 },
+            rmii_timing: RmiiTiming::new(),
             mac_address: DEFAULT_MAC_ADDR,
             dma_burst_len: DmaBurstLen::Burst32,
+            dma_rx_burst_len: None,
+            dma_pbl_x8: false,
+            dma_mixed_burst: false,
+            dma_fixed_burst: true,
+            dma_address_aligned: true,
             sw_reset_timeout_ms: SOFT_RESET_TIMEOUT_MS,
+            tx_stop_timeout_us: FLUSH_TIMEOUT_US,
+            fifo_flush_timeout_us: FLUSH_TIMEOUT_US,
+            mii_busy_timeout_us: MII_BUSY_TIMEOUT_US,
             mdc_freq_hz: MDC_MAX_FREQ_HZ,
             promiscuous: false,
             checksum: ChecksumConfig {
@@ -331,6 +1043,19 @@ impl EmacConfig {
                 pause_low_threshold: PauseLowThreshold::Minus4,
                 unicast_pause_detect: false,
             },
+            tx_interrupt_policy: TxInterruptPolicy::EveryFrame,
+            control_frames: ControlFrameFilter::DropPause,
+            llc_frames: LlcFrameFilter::PassAll,
+            rx_soft_timestamps: false,
+            token_strategy: TokenStrategy::Copy,
+            keepalive: None,
+            dma_dual_buffer: false,
+            tx_reserved_descriptors: 0,
+            rx_unicast_reserve: 0,
+            deferred_rx: false,
+            startup_receive_all_ms: 0,
+            forward_error_frames: false,
+            filters: FilterConfig::new(),
         }
     }
 
@@ -387,10 +1112,21 @@ impl EmacConfig {
         self
     }
 
+    /// Set the RMII clock timing and pad settings
+    ///
+    /// Try a clock delay or a different drive strength on boards that show
+    /// CRC errors at 100 Mbps.
+    #[must_use]
+    pub const fn with_rmii_timing(mut self, timing: RmiiTiming) -> Self {
+        self.rmii_timing = timing;
+        self
+    }
+
     /// Set the MAC address
     ///
     /// The MAC address should be 6 bytes. If not set, a default locally-administered
-    /// address (02:00:00:00:00:01) will be used.
+    /// address (02:00:00:00:00:01) will be used. Multicast addresses are rejected
+    /// by [`validate`](Self::validate) during `Emac::init`.
     #[must_use]
     pub const fn with_mac_address(mut self, addr: [u8; 6]) -> Self {
         self.mac_address = addr;
@@ -398,12 +1134,78 @@ impl EmacConfig {
     }
 
     /// Set the DMA burst length
+    ///
+    /// This applies to both TX and RX unless a separate RX burst length is
+    /// set with [`with_dma_rx_burst_len`](Self::with_dma_rx_burst_len).
     #[must_use]
     pub const fn with_dma_burst_len(mut self, burst_len: DmaBurstLen) -> Self {
         self.dma_burst_len = burst_len;
         self
     }
 
+    /// Set a separate RX DMA burst length
+    #[must_use]
+    pub const fn with_dma_rx_burst_len(mut self, burst_len: DmaBurstLen) -> Self {
+        self.dma_rx_burst_len = Some(burst_len);
+        self
+    }
+
+    /// Enable or disable 8xPBL mode (burst lengths multiplied by 8)
+    #[must_use]
+    pub const fn with_dma_pbl_x8(mut self, enabled: bool) -> Self {
+        self.dma_pbl_x8 = enabled;
+        self
+    }
+
+    /// Enable or disable mixed burst mode
+    ///
+    /// The DMA ignores mixed burst while fixed bursts are on, so turn them
+    /// off with [`with_dma_fixed_burst`](Self::with_dma_fixed_burst);
+    /// [`validate`](Self::validate) rejects the combination.
+    #[must_use]
+    pub const fn with_dma_mixed_burst(mut self, enabled: bool) -> Self {
+        self.dma_mixed_burst = enabled;
+        self
+    }
+
+    /// Enable or disable fixed-length DMA bursts
+    #[must_use]
+    pub const fn with_dma_fixed_burst(mut self, enabled: bool) -> Self {
+        self.dma_fixed_burst = enabled;
+        self
+    }
+
+    /// Enable or disable address-aligned DMA bursts
+    #[must_use]
+    pub const fn with_dma_address_aligned(mut self, enabled: bool) -> Self {
+        self.dma_address_aligned = enabled;
+        self
+    }
+
+    /// Apply a DMA burst tuning preset
+    ///
+    /// Overwrites the TX/RX burst lengths and the fixed-burst and
+    /// mixed-burst settings. 8xPBL mode is cleared; address-aligned beats
+    /// are left as they are.
+    #[must_use]
+    pub const fn with_dma_burst_preset(mut self, preset: DmaBurstPreset) -> Self {
+        self.dma_burst_len = preset.tx_burst_len();
+        self.dma_rx_burst_len = Some(preset.rx_burst_len());
+        self.dma_pbl_x8 = false;
+        self.dma_mixed_burst = preset.mixed_burst();
+        self.dma_fixed_burst = preset.fixed_burst();
+        self
+    }
+
+    /// Effective RX DMA burst length
+    #[must_use]
+    pub const fn rx_burst_len(&self) -> DmaBurstLen {
+        match self.dma_rx_burst_len {
+            Some(len) => len,
+            None => self.dma_burst_len,
+        }
+    }
+
     /// Set the software reset timeout
     #[must_use]
     pub const fn with_reset_timeout_ms(mut self, timeout_ms: u32) -> Self {
@@ -411,6 +1213,26 @@ impl EmacConfig {
         self
     }
 
+    /// Set how long `Emac::stop` waits for the transmit DMA to stop and
+    /// for the TX FIFO flush, each in microseconds
+    ///
+    /// A frame still being sent has to finish first: 1518 bytes take
+    /// about 1.2 ms at 10 Mbps.
+    #[must_use]
+    pub const fn with_stop_timeouts_us(mut self, tx_stop_us: u32, fifo_flush_us: u32) -> Self {
+        self.tx_stop_timeout_us = tx_stop_us;
+        self.fifo_flush_timeout_us = fifo_flush_us;
+        self
+    }
+
+    /// Set how long a PHY register access waits for the MII busy bit, in
+    /// microseconds
+    #[must_use]
+    pub const fn with_mii_busy_timeout_us(mut self, timeout_us: u32) -> Self {
+        self.mii_busy_timeout_us = timeout_us;
+        self
+    }
+
     /// Set the MDC clock frequency
     #[must_use]
     pub const fn with_mdc_freq_hz(mut self, freq_hz: u32) -> Self {
@@ -459,6 +1281,221 @@ impl EmacConfig {
         self.flow_control.enabled = enabled;
         self
     }
+
+    /// Set the TX completion interrupt policy
+    #[must_use]
+    pub const fn with_tx_interrupt_policy(mut self, policy: TxInterruptPolicy) -> Self {
+        self.tx_interrupt_policy = policy;
+        self
+    }
+
+    /// Set where received MAC control frames go
+    #[must_use]
+    pub const fn with_control_frames(mut self, filter: ControlFrameFilter) -> Self {
+        self.control_frames = filter;
+        self
+    }
+
+    /// Set which received IEEE 802.3 length-field frames are delivered
+    #[must_use]
+    pub const fn with_llc_frames(mut self, filter: LlcFrameFilter) -> Self {
+        self.llc_frames = filter;
+        self
+    }
+
+    /// Enable or disable software RX timestamps
+    ///
+    /// Needs a [`Clock`](crate::hal::Clock) installed on the EMAC; see
+    /// [`rx_timestamp`](super::rx_timestamp) for the accuracy.
+    #[must_use]
+    pub const fn with_rx_soft_timestamps(mut self, enabled: bool) -> Self {
+        self.rx_soft_timestamps = enabled;
+        self
+    }
+
+    /// Set how the smoltcp adapter hands frames to the stack
+    #[must_use]
+    pub const fn with_token_strategy(mut self, strategy: TokenStrategy) -> Self {
+        self.token_strategy = strategy;
+        self
+    }
+
+    /// Set the link-layer keepalive; see
+    /// [`keepalive`](super::keepalive)
+    #[must_use]
+    pub const fn with_keepalive(mut self, keepalive: Option<Keepalive>) -> Self {
+        self.keepalive = keepalive;
+        self
+    }
+
+    /// Set all runtime-tunable fields at once
+    #[must_use]
+    pub const fn with_runtime_settings(mut self, settings: RuntimeSettings) -> Self {
+        self.promiscuous = settings.promiscuous;
+        self.flow_control = settings.flow_control;
+        self.tx_interrupt_policy = settings.tx_interrupt_policy;
+        self.control_frames = settings.control_frames;
+        self.llc_frames = settings.llc_frames;
+        self.rx_soft_timestamps = settings.rx_soft_timestamps;
+        self.token_strategy = settings.token_strategy;
+        self.keepalive = settings.keepalive;
+        self
+    }
+
+    /// Runtime-tunable fields of this configuration
+    #[must_use]
+    pub const fn runtime_settings(&self) -> RuntimeSettings {
+        RuntimeSettings {
+            promiscuous: self.promiscuous,
+            flow_control: self.flow_control,
+            tx_interrupt_policy: self.tx_interrupt_policy,
+            control_frames: self.control_frames,
+            llc_frames: self.llc_frames,
+            rx_soft_timestamps: self.rx_soft_timestamps,
+            token_strategy: self.token_strategy,
+            keepalive: self.keepalive,
+        }
+    }
+
+    /// Enable dual-buffer (ring mode) DMA descriptors
+    #[must_use]
+    pub const fn with_dma_dual_buffer(mut self, enabled: bool) -> Self {
+        self.dma_dual_buffer = enabled;
+        self
+    }
+
+    /// Hold back `count` TX descriptors for high-priority frames
+    #[must_use]
+    pub const fn with_tx_reserved_descriptors(mut self, count: usize) -> Self {
+        self.tx_reserved_descriptors = count;
+        self
+    }
+
+    /// Keep `count` RX descriptors for unicast frames addressed to us
+    #[must_use]
+    pub const fn with_rx_unicast_reserve(mut self, count: usize) -> Self {
+        self.rx_unicast_reserve = count;
+        self
+    }
+
+    /// Defer enabling RX until `Emac::enable_rx`
+    #[must_use]
+    pub const fn with_deferred_rx(mut self, enabled: bool) -> Self {
+        self.deferred_rx = enabled;
+        self
+    }
+
+    /// Receive every frame for `ms` milliseconds after RX is enabled
+    #[must_use]
+    pub const fn with_startup_receive_all_ms(mut self, ms: u32) -> Self {
+        self.startup_receive_all_ms = ms;
+        self
+    }
+
+    /// Pass errored and undersized frames to the driver for counting
+    #[must_use]
+    pub const fn with_forward_error_frames(mut self, enabled: bool) -> Self {
+        self.forward_error_frames = enabled;
+        self
+    }
+
+    /// Add a perfect address filter in the first free slot
+    ///
+    /// With all [`MAC_FILTER_SLOTS`] slots taken the filter is dropped and
+    /// [`validate`](Self::validate), and so `Emac::init`, fails with
+    /// `InvalidConfig`.
+    #[must_use]
+    pub const fn with_mac_filter(mut self, filter: MacAddressFilter) -> Self {
+        let mut i = 0;
+        while i < MAC_FILTER_SLOTS {
+            if self.filters.mac_filters[i].is_none() {
+                self.filters.mac_filters[i] = Some(filter);
+                return self;
+            }
+            i += 1;
+        }
+        self.filters.mac_filter_overflow = true;
+        self
+    }
+
+    /// Preload the hash table and filter multicast through it
+    #[must_use]
+    pub const fn with_hash_table(mut self, table: u64) -> Self {
+        self.filters.hash_table = table;
+        self.filters.hash_multicast = true;
+        self
+    }
+
+    /// Set the hash table bit for `addr`
+    ///
+    /// Enables hash filtering for multicast or unicast destinations,
+    /// depending on the kind of address.
+    #[must_use]
+    pub const fn with_hash_address(mut self, addr: [u8; 6]) -> Self {
+        self.filters.hash_table |= 1 << hash_index(&addr);
+        if is_multicast_mac(&addr) {
+            self.filters.hash_multicast = true;
+        } else {
+            self.filters.hash_unicast = true;
+        }
+        self
+    }
+
+    /// Filter received frames by VLAN tag
+    #[must_use]
+    pub const fn with_vlan_filter(mut self, vlan: VlanFilter) -> Self {
+        self.filters.vlan = Some(vlan);
+        self
+    }
+
+    /// Check the configuration for values the hardware cannot use
+    ///
+    /// Called by `Emac::init` before touching any registers.
+    ///
+    /// # Errors
+    ///
+    /// - `InvalidMacAddress` - MAC address has the multicast bit set
+    /// - `InvalidConfig` - Initial [`RuntimeSettings`] are inconsistent,
+    ///   [`RmiiTiming`] or [`FilterConfig`] is out of range, or mixed and
+    ///   fixed DMA bursts are both on
+    pub const fn validate(&self) -> ConfigResult<()> {
+        if is_multicast_mac(&self.mac_address) {
+            return Err(ConfigError::InvalidMacAddress);
+        }
+        if self.dma_mixed_burst && self.dma_fixed_burst {
+            return Err(ConfigError::InvalidConfig);
+        }
+        if let Err(e) = self.rmii_timing.validate() {
+            return Err(e);
+        }
+        if let Err(e) = self.filters.validate() {
+            return Err(e);
+        }
+        self.runtime_settings().validate()
+    }
+
+    /// Check the descriptor reservations fit rings of `rx_buffers` RX and
+    /// `tx_buffers` TX buffers
+    ///
+    /// Called by `Emac::init` with the active ring sizes, and by
+    /// `Emac::set_active_ring_sizes` with the new ones. In dual-buffer mode
+    /// two buffers form one descriptor.
+    ///
+    /// # Errors
+    ///
+    /// - `InvalidConfig` - No TX descriptor is left outside
+    ///   `tx_reserved_descriptors`, or no RX descriptor outside
+    ///   `rx_unicast_reserve`
+    pub const fn validate_rings(&self, rx_buffers: usize, tx_buffers: usize) -> ConfigResult<()> {
+        let per_descriptor = if self.dma_dual_buffer { 2 } else { 1 };
+        if self.tx_reserved_descriptors >= tx_buffers / per_descriptor {
+            return Err(ConfigError::InvalidConfig);
+        }
+        if self.rx_unicast_reserve > 0 && self.rx_unicast_reserve >= rx_buffers / per_descriptor {
+            return Err(ConfigError::InvalidConfig);
+        }
+        Ok(())
+    }
 }
 
 /// EMAC driver state
@@ -474,6 +1511,30 @@ pub enum State {
     Running,
     /// Stopped (TX/RX disabled but still initialized)
     Stopped,
+    /// Receiver only (`Emac::enable_rx_only`); transmitting is refused
+    RxOnly,
+    /// Transmitter only (`Emac::enable_tx_only`); the receiver is off
+    TxOnly,
+}
+
+impl State {
+    /// True if TX or RX (or both) is enabled
+    #[must_use]
+    pub const fn is_active(self) -> bool {
+        matches!(self, Self::Running | Self::RxOnly | Self::TxOnly)
+    }
+
+    /// True if frames may be transmitted (`Running` or `TxOnly`)
+    #[must_use]
+    pub const fn tx_allowed(self) -> bool {
+        matches!(self, Self::Running | Self::TxOnly)
+    }
+
+    /// True if frames may be received (`Running` or `RxOnly`)
+    #[must_use]
+    pub const fn rx_allowed(self) -> bool {
+        matches!(self, Self::Running | Self::RxOnly)
+    }
 }
 
 // =============================================================================
@@ -636,6 +1697,92 @@ mod tests {
         assert_eq!(DmaBurstLen::Burst32.to_pbl(), 32);
     }
 
+    #[test]
+    fn dma_burst_len_beats() {
+        assert_eq!(DmaBurstLen::ALL.len(), 6);
+        assert!(
+            DmaBurstLen::ALL
+                .windows(2)
+                .all(|w| w[0].to_pbl() < w[1].to_pbl())
+        );
+        assert_eq!(DmaBurstLen::Burst4.beats(false), 4);
+        assert_eq!(DmaBurstLen::Burst1.beats(true), 8);
+        assert_eq!(DmaBurstLen::Burst32.beats(true), 256);
+    }
+
+    #[test]
+    fn config_builder_burst_alignment() {
+        let config = EmacConfig::new();
+        assert!(config.dma_fixed_burst);
+        assert!(config.dma_address_aligned);
+
+        let config = config
+            .with_dma_fixed_burst(false)
+            .with_dma_address_aligned(false);
+        assert!(!config.dma_fixed_burst);
+        assert!(!config.dma_address_aligned);
+
+        // Mixed burst needs fixed bursts off, and is not turned off for it
+        let config = EmacConfig::new().with_dma_mixed_burst(true);
+        assert!(config.dma_fixed_burst);
+        assert_eq!(config.validate(), Err(ConfigError::InvalidConfig));
+        let config = config.with_dma_fixed_burst(false);
+        assert_eq!(config.validate(), Ok(()));
+    }
+
+    #[test]
+    fn config_rx_burst_len_defaults_to_tx() {
+        let config = EmacConfig::new().with_dma_burst_len(DmaBurstLen::Burst16);
+        assert_eq!(config.dma_rx_burst_len, None);
+        assert_eq!(config.rx_burst_len(), DmaBurstLen::Burst16);
+        assert!(!config.dma_pbl_x8);
+        assert!(!config.dma_mixed_burst);
+    }
+
+    #[test]
+    fn config_builder_split_burst() {
+        let config = EmacConfig::new()
+            .with_dma_burst_len(DmaBurstLen::Burst8)
+            .with_dma_rx_burst_len(DmaBurstLen::Burst32)
+            .with_dma_pbl_x8(true)
+            .with_dma_fixed_burst(false)
+            .with_dma_mixed_burst(true);
+
+        assert_eq!(config.dma_burst_len, DmaBurstLen::Burst8);
+        assert_eq!(config.rx_burst_len(), DmaBurstLen::Burst32);
+        assert!(config.dma_pbl_x8);
+        assert!(config.dma_mixed_burst);
+    }
+
+    #[test]
+    fn config_builder_burst_preset() {
+        let config = EmacConfig::new()
+            .with_dma_pbl_x8(true)
+            .with_dma_burst_preset(DmaBurstPreset::SharedBus);
+
+        assert_eq!(config.dma_burst_len, DmaBurstLen::Burst8);
+        assert_eq!(config.rx_burst_len(), DmaBurstLen::Burst8);
+        assert!(config.dma_mixed_burst);
+        assert!(!config.dma_fixed_burst);
+        assert!(!config.dma_pbl_x8);
+        assert_eq!(config.validate(), Ok(()));
+
+        let config = EmacConfig::new().with_dma_burst_preset(DmaBurstPreset::Balanced);
+        assert_eq!(config.dma_burst_len, DmaBurstLen::Burst16);
+        assert_eq!(config.rx_burst_len(), DmaBurstLen::Burst32);
+        assert!(!config.dma_mixed_burst);
+    }
+
+    #[test]
+    fn burst_preset_default_matches_config_default() {
+        let preset = DmaBurstPreset::default();
+        let config = EmacConfig::new();
+        assert_eq!(preset.tx_burst_len(), config.dma_burst_len);
+        assert_eq!(preset.rx_burst_len(), config.rx_burst_len());
+        assert_eq!(preset.mixed_burst(), config.dma_mixed_burst);
+        assert_eq!(preset.fixed_burst(), config.dma_fixed_burst);
+    }
+
     #[test]
     fn speed_default() {
         assert_eq!(Speed::default(), Speed::Mbps100);
@@ -711,4 +1858,278 @@ mod tests {
         assert_eq!(fc.low_water_mark, 2);
         assert_eq!(fc.high_water_mark, 8);
     }
+
+    // =========================================================================
+    // TX Interrupt Policy Tests
+    // =========================================================================
+
+    #[test]
+    fn tx_interrupt_policy_default_every_frame() {
+        assert_eq!(TxInterruptPolicy::default(), TxInterruptPolicy::EveryFrame);
+        assert_eq!(
+            EmacConfig::new().tx_interrupt_policy,
+            TxInterruptPolicy::EveryFrame
+        );
+        assert_eq!(
+            EmacConfig::default().tx_interrupt_policy,
+            TxInterruptPolicy::EveryFrame
+        );
+    }
+
+    #[test]
+    fn tx_interrupt_policy_interval() {
+        assert_eq!(TxInterruptPolicy::EveryFrame.interval(), 1);
+        assert_eq!(TxInterruptPolicy::EveryNth(0).interval(), 1);
+        assert_eq!(TxInterruptPolicy::EveryNth(1).interval(), 1);
+        assert_eq!(TxInterruptPolicy::EveryNth(8).interval(), 8);
+    }
+
+    #[test]
+    fn tx_interrupt_policy_is_batched() {
+        assert!(!TxInterruptPolicy::EveryFrame.is_batched());
+        assert!(!TxInterruptPolicy::EveryNth(1).is_batched());
+        assert!(TxInterruptPolicy::EveryNth(4).is_batched());
+    }
+
+    #[test]
+    fn config_builder_tx_interrupt_policy() {
+        let config = EmacConfig::new().with_tx_interrupt_policy(TxInterruptPolicy::EveryNth(16));
+        assert_eq!(config.tx_interrupt_policy, TxInterruptPolicy::EveryNth(16));
+    }
+
+    #[test]
+    fn llc_frame_filter_modes() {
+        let ipv4 = FrameFormat::EthernetII { ether_type: 0x0800 };
+        let bpdu = FrameFormat::Llc {
+            length: 38,
+            dsap: 0x42,
+            ssap: 0x42,
+        };
+        let snap_ip = FrameFormat::Snap {
+            length: 46,
+            oui: [0; 3],
+            protocol: 0x0800,
+        };
+
+        assert_eq!(EmacConfig::new().llc_frames, LlcFrameFilter::PassAll);
+        assert!(LlcFrameFilter::PassAll.accepts(&bpdu));
+        assert!(LlcFrameFilter::PassSnap.accepts(&snap_ip));
+        assert!(!LlcFrameFilter::PassSnap.accepts(&bpdu));
+        assert!(!LlcFrameFilter::DropAll.accepts(&snap_ip));
+        assert!(LlcFrameFilter::DropAll.accepts(&ipv4));
+    }
+
+    #[test]
+    fn control_frames_default_drops_pause() {
+        assert_eq!(
+            EmacConfig::new().control_frames,
+            ControlFrameFilter::DropPause
+        );
+        assert_eq!(
+            EmacConfig::default().control_frames,
+            ControlFrameFilter::DropPause
+        );
+        // PAUSE frames must reach the ring to be counted
+        assert_eq!(ControlFrameFilter::DropPause.pcf(), pcf::ALL);
+        assert_eq!(ControlFrameFilter::DropAll.pcf(), pcf::NONE);
+        assert_eq!(ControlFrameFilter::PassFiltered.pcf(), pcf::FILTERED);
+    }
+
+    #[test]
+    fn config_builder_dma_dual_buffer() {
+        assert!(!EmacConfig::new().dma_dual_buffer);
+        assert!(!EmacConfig::default().dma_dual_buffer);
+        assert!(EmacConfig::new().with_dma_dual_buffer(true).dma_dual_buffer);
+    }
+
+    #[test]
+    fn config_builder_tx_reserved_descriptors() {
+        assert_eq!(EmacConfig::new().tx_reserved_descriptors, 0);
+        assert_eq!(EmacConfig::default().tx_reserved_descriptors, 0);
+        let config = EmacConfig::new().with_tx_reserved_descriptors(2);
+        assert_eq!(config.tx_reserved_descriptors, 2);
+        assert_eq!(EmacConfig::new().rx_unicast_reserve, 0);
+        let config = EmacConfig::new().with_rx_unicast_reserve(3);
+        assert_eq!(config.rx_unicast_reserve, 3);
+    }
+
+    #[test]
+    fn validate_rings_keeps_a_descriptor_outside_the_reserves() {
+        let config = EmacConfig::new().with_rx_unicast_reserve(3);
+        assert_eq!(config.validate_rings(4, 4), Ok(()));
+        assert_eq!(config.validate_rings(3, 4), Err(ConfigError::InvalidConfig));
+        // Two buffers make one descriptor in dual-buffer mode
+        let config = config.with_dma_dual_buffer(true);
+        assert_eq!(config.validate_rings(6, 4), Err(ConfigError::InvalidConfig));
+        assert_eq!(config.validate_rings(8, 4), Ok(()));
+
+        let config = EmacConfig::new().with_tx_reserved_descriptors(2);
+        assert_eq!(config.validate_rings(4, 2), Err(ConfigError::InvalidConfig));
+        assert_eq!(config.validate_rings(4, 3), Ok(()));
+        // No reserve fits any RX ring, including none at all
+        assert_eq!(EmacConfig::new().validate_rings(0, 1), Ok(()));
+    }
+
+    #[test]
+    fn config_builder_deferred_rx() {
+        assert!(!EmacConfig::new().deferred_rx);
+        assert!(!EmacConfig::default().deferred_rx);
+        assert!(EmacConfig::new().with_deferred_rx(true).deferred_rx);
+        assert_eq!(EmacConfig::new().startup_receive_all_ms, 0);
+        assert_eq!(
+            EmacConfig::new()
+                .with_startup_receive_all_ms(500)
+                .startup_receive_all_ms,
+            500
+        );
+    }
+
+    // =========================================================================
+    // MAC Address Helper Tests
+    // =========================================================================
+
+    #[test]
+    fn validate_accepts_default_config() {
+        assert_eq!(EmacConfig::new().validate(), Ok(()));
+    }
+
+    #[test]
+    fn rmii_timing_defaults_leave_clock_untouched() {
+        let config = EmacConfig::new();
+        assert_eq!(config.rmii_timing, RmiiTiming::default());
+        assert_eq!(config.rmii_timing.clock_delay, 0);
+        assert_eq!(config.rmii_timing.clock_drive, None);
+        // Matches the pad setup used before the settings were exposed
+        assert_eq!(config.rmii_timing.tx_drive, DriveStrength::Ma40);
+        assert_eq!(config.rmii_timing.rx_pull, PadPull::None);
+    }
+
+    #[test]
+    fn rmii_timing_builder_and_validation() {
+        let timing = RmiiTiming::new()
+            .with_clock_delay(2)
+            .with_clock_drive(DriveStrength::Ma40);
+        let config = EmacConfig::new()
+            .with_rmii_internal_clock(17)
+            .with_rmii_timing(timing);
+        assert_eq!(config.rmii_timing.clock_drive, Some(DriveStrength::Ma40));
+        assert_eq!(config.validate(), Ok(()));
+
+        let timing = timing
+            .with_tx_drive(DriveStrength::Ma10)
+            .with_rx_pull(PadPull::Down);
+        assert_eq!(timing.clock_delay, 2);
+        assert_eq!(timing.tx_drive, DriveStrength::Ma10);
+        assert_eq!(timing.rx_pull, PadPull::Down);
+
+        let config = config.with_rmii_timing(RmiiTiming::new().with_clock_delay(4));
+        assert_eq!(config.validate(), Err(ConfigError::InvalidConfig));
+    }
+
+    #[test]
+    fn runtime_settings_round_trip_through_config() {
+        let settings = RuntimeSettings::new()
+            .with_promiscuous(true)
+            .with_flow_control(FlowControlConfig::with_water_marks(2, 8))
+            .with_tx_interrupt_policy(TxInterruptPolicy::EveryNth(4))
+            .with_control_frames(ControlFrameFilter::PassAll)
+            .with_llc_frames(LlcFrameFilter::DropAll);
+        let config = EmacConfig::new().with_runtime_settings(settings);
+
+        assert!(config.promiscuous);
+        assert_eq!(config.flow_control.high_water_mark, 8);
+        assert_eq!(config.runtime_settings(), settings);
+    }
+
+    #[test]
+    fn runtime_settings_default_matches_config_default() {
+        assert_eq!(
+            RuntimeSettings::default(),
+            EmacConfig::default().runtime_settings()
+        );
+    }
+
+    #[test]
+    fn runtime_settings_reject_inverted_water_marks() {
+        let settings =
+            RuntimeSettings::new().with_flow_control(FlowControlConfig::with_water_marks(8, 2));
+        assert_eq!(settings.validate(), Err(ConfigError::InvalidConfig));
+
+        let config = EmacConfig::new().with_runtime_settings(settings);
+        assert_eq!(config.validate(), Err(ConfigError::InvalidConfig));
+    }
+
+    #[test]
+    fn validate_rejects_multicast_mac() {
+        let config = EmacConfig::new().with_mac_address([0x01, 0x00, 0x5E, 0x00, 0x00, 0x01]);
+        assert_eq!(config.validate(), Err(ConfigError::InvalidMacAddress));
+    }
+
+    #[test]
+    fn validate_rejects_broadcast_mac() {
+        let config = EmacConfig::new().with_mac_address([0xFF; 6]);
+        assert_eq!(config.validate(), Err(ConfigError::InvalidMacAddress));
+    }
+
+    #[test]
+    fn validate_accepts_vendor_unicast_mac() {
+        let config = EmacConfig::new().with_mac_address([0x24, 0x0A, 0xC4, 0x12, 0x34, 0x56]);
+        assert_eq!(config.validate(), Ok(()));
+    }
+
+    #[test]
+    fn const_filter_config_fills_slots_in_order() {
+        const MDNS: [u8; 6] = [0x01, 0x00, 0x5E, 0x00, 0x00, 0xFB];
+        const CONFIG: EmacConfig = EmacConfig::new()
+            .with_mac_filter(MacAddressFilter::new([0x02, 0, 0, 0, 0, 1]))
+            .with_mac_filter(MacAddressFilter::source([0x02, 0, 0, 0, 0, 2]))
+            .with_hash_address(MDNS)
+            .with_vlan_filter(VlanFilter::new(100).with_svlan(true));
+
+        let filters = CONFIG.filters;
+        assert_eq!(filters.mac_filter_count(), 2);
+        assert_eq!(
+            filters.mac_filters[1].map(|f| f.filter_type),
+            Some(MacFilterType::Source)
+        );
+        assert_eq!(filters.hash_table, 1 << hash_index(&MDNS));
+        assert!(filters.hash_multicast && !filters.hash_unicast);
+        assert_eq!(filters.vlan.map(|v| (v.vid, v.svlan)), Some((100, true)));
+        assert_eq!(CONFIG.validate(), Ok(()));
+    }
+
+    #[test]
+    fn validate_rejects_more_mac_filters_than_slots() {
+        let mut config = EmacConfig::new();
+        for i in 0..MAC_FILTER_SLOTS as u8 {
+            config = config.with_mac_filter(MacAddressFilter::new([0x02, 0, 0, 0, 0, i]));
+        }
+        assert_eq!(config.validate(), Ok(()));
+
+        let config = config.with_mac_filter(MacAddressFilter::new([0x02, 0, 0, 0, 0, 0xFF]));
+        assert!(config.filters.mac_filter_overflow);
+        assert_eq!(config.filters.mac_filter_count(), MAC_FILTER_SLOTS);
+        assert_eq!(config.validate(), Err(ConfigError::InvalidConfig));
+    }
+
+    #[test]
+    fn validate_rejects_out_of_range_filters() {
+        let config =
+            EmacConfig::new().with_mac_filter(MacAddressFilter::with_mask([0x02; 6], 0x40));
+        assert_eq!(config.validate(), Err(ConfigError::InvalidConfig));
+
+        let config = EmacConfig::new().with_vlan_filter(VlanFilter::new(4096));
+        assert_eq!(config.validate(), Err(ConfigError::InvalidConfig));
+
+        // The full 16-bit tag is fine when not comparing the VID only
+        let mut vlan = VlanFilter::new(0xE064);
+        vlan.vid_only = false;
+        assert_eq!(EmacConfig::new().with_vlan_filter(vlan).validate(), Ok(()));
+    }
+
+    #[test]
+    fn mac_from_unique_id_passes_validation() {
+        let config = EmacConfig::new().with_mac_address(mac_from_unique_id(b"serial-0042"));
+        assert_eq!(config.validate(), Ok(()));
+    }
 }
@@ -0,0 +1,255 @@
+//! Duplex mismatch detection.
+//!
+//! A link partner with a forced speed and duplex does not auto-negotiate,
+//! and the PHY then falls back to half duplex through parallel detection.
+//! Forcing the ESP32 side to full duplex against an auto-negotiating
+//! partner has the mirror effect. Either way the link comes up and mostly
+//! works, but throughput collapses under load, and nothing reports an
+//! error.
+//!
+//! The symptoms are visible in the statistics, though:
+//!
+//! - **We are half duplex, the partner full duplex**: the partner transmits
+//!   whenever it likes, so our frames see collisions after the first 64
+//!   bytes (`TxDiagnostics::late_collisions`).
+//! - **We are full duplex, the partner half duplex**: the partner aborts
+//!   its frames when we talk over it, so we receive fragments, i.e. runts
+//!   and CRC errors (`Statistics::rx_runts`, `Statistics::rx_crc_errors`).
+//!   These are only counted with `EmacConfig::forward_error_frames`.
+//!
+//! [`DuplexMismatchDetector`] compares those counters between two checks.
+//! Install it with
+//! [`Emac::set_duplex_mismatch_detector`](super::emac::Emac::set_duplex_mismatch_detector)
+//! and call
+//! [`Emac::check_duplex_mismatch`](super::emac::Emac::check_duplex_mismatch)
+//! periodically (e.g. with the link poll); a detected mismatch is logged as
+//! [`EventKind::DuplexMismatch`](super::event_log::EventKind::DuplexMismatch).
+//!
+//! # Example
+//!
+//! ```ignore
+//! use ph_esp32_mac::DuplexMismatchDetector;
+//!
+//! emac.set_duplex_mismatch_detector(Some(DuplexMismatchDetector::new(5)));
+//!
+//! // Every few seconds:
+//! if let Some(mismatch) = emac.check_duplex_mismatch() {
+//!     println!("{}: {}", mismatch.as_str(), mismatch.remediation());
+//! }
+//! ```
+
+use super::config::Duplex;
+use super::stats::Statistics;
+
+/// Errors per check interval that [`DuplexMismatchDetector::default`]
+/// treats as a mismatch
+pub const DEFAULT_MISMATCH_THRESHOLD: u32 = 5;
+
+/// Probable duplex mismatch and the evidence for it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum DuplexMismatch {
+    /// We run half duplex but see late collisions: the partner is full
+    /// duplex
+    PartnerFullDuplex {
+        /// Late collisions since the previous check
+        late_collisions: u32,
+    },
+    /// We run full duplex but receive fragments: the partner is half duplex
+    PartnerHalfDuplex {
+        /// CRC errors since the previous check
+        crc_errors: u32,
+        /// Runt frames since the previous check
+        runts: u32,
+    },
+}
+
+impl DuplexMismatch {
+    /// Short description
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::PartnerFullDuplex { .. } => "duplex mismatch: link partner is full duplex",
+            Self::PartnerHalfDuplex { .. } => "duplex mismatch: link partner is half duplex",
+        }
+    }
+
+    /// Suggested fix
+    #[must_use]
+    pub const fn remediation(&self) -> &'static str {
+        match self {
+            Self::PartnerFullDuplex { .. } => {
+                "the partner is probably forced to full duplex: enable auto-negotiation \
+                 on the switch port, or force the PHY to the same speed and full duplex"
+            }
+            Self::PartnerHalfDuplex { .. } => {
+                "the partner is probably auto-negotiating or forced to half duplex: \
+                 re-enable auto-negotiation on the PHY, or force the partner to full duplex"
+            }
+        }
+    }
+}
+
+impl core::fmt::Display for DuplexMismatch {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            Self::PartnerFullDuplex { late_collisions } => {
+                write!(f, "{} ({late_collisions} late collisions)", self.as_str())
+            }
+            Self::PartnerHalfDuplex { crc_errors, runts } => write!(
+                f,
+                "{} ({crc_errors} CRC errors, {runts} runts)",
+                self.as_str()
+            ),
+        }
+    }
+}
+
+/// Flags a probable duplex mismatch from statistics deltas
+///
+/// Each [`check`](Self::check) looks at the counters since the previous
+/// one. A mismatch is reported once when the relevant errors reach the
+/// threshold, and again only after a check without errors has cleared it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DuplexMismatchDetector {
+    threshold: u32,
+    prev: Option<Statistics>,
+    flagged: bool,
+}
+
+impl Default for DuplexMismatchDetector {
+    fn default() -> Self {
+        Self::new(DEFAULT_MISMATCH_THRESHOLD)
+    }
+}
+
+impl DuplexMismatchDetector {
+    /// Report a mismatch once `threshold` symptom errors occur between two
+    /// checks (a threshold of 0 is treated as 1)
+    #[must_use]
+    pub const fn new(threshold: u32) -> Self {
+        Self {
+            threshold: if threshold == 0 { 1 } else { threshold },
+            prev: None,
+            flagged: false,
+        }
+    }
+
+    /// True while a reported mismatch has not cleared
+    #[must_use]
+    pub const fn is_flagged(&self) -> bool {
+        self.flagged
+    }
+
+    /// Evaluate the counters since the previous check
+    ///
+    /// `duplex` is the mode the MAC runs in. The first call only takes a
+    /// baseline. Returns the mismatch when it is first detected.
+    pub fn check(&mut self, stats: &Statistics, duplex: Duplex) -> Option<DuplexMismatch> {
+        let prev = self.prev.replace(*stats)?;
+        let delta = stats.since(&prev);
+        let symptom = match duplex {
+            Duplex::Half => {
+                let late_collisions = delta.tx_diagnostics.late_collisions;
+                (late_collisions >= self.threshold)
+                    .then_some(DuplexMismatch::PartnerFullDuplex { late_collisions })
+            }
+            Duplex::Full => {
+                let (crc_errors, runts) = (delta.rx_crc_errors, delta.rx_runts);
+                (crc_errors.saturating_add(runts) >= self.threshold)
+                    .then_some(DuplexMismatch::PartnerHalfDuplex { crc_errors, runts })
+            }
+        };
+
+        match symptom {
+            Some(mismatch) if !self.flagged => {
+                self.flagged = true;
+                Some(mismatch)
+            }
+            Some(_) => None,
+            None => {
+                self.flagged = false;
+                None
+            }
+        }
+    }
+
+    /// Forget the baseline and any reported mismatch, e.g. after a link
+    /// change or `reset_stats()`
+    pub fn reset(&mut self) {
+        self.prev = None;
+        self.flagged = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_late_collisions(n: u32) -> Statistics {
+        let mut stats = Statistics::new();
+        stats.tx_diagnostics.late_collisions = n;
+        stats
+    }
+
+    #[test]
+    fn late_collisions_in_half_duplex() {
+        let mut detector = DuplexMismatchDetector::new(3);
+        assert_eq!(
+            detector.check(&with_late_collisions(10), Duplex::Half),
+            None
+        );
+        assert_eq!(
+            detector.check(&with_late_collisions(12), Duplex::Half),
+            None
+        );
+
+        let mismatch = detector.check(&with_late_collisions(15), Duplex::Half);
+        assert_eq!(
+            mismatch,
+            Some(DuplexMismatch::PartnerFullDuplex { late_collisions: 3 })
+        );
+        assert!(detector.is_flagged());
+        // Reported once per episode
+        assert_eq!(
+            detector.check(&with_late_collisions(20), Duplex::Half),
+            None
+        );
+        assert_eq!(
+            detector.check(&with_late_collisions(20), Duplex::Half),
+            None
+        );
+        assert!(!detector.is_flagged());
+        assert!(
+            detector
+                .check(&with_late_collisions(30), Duplex::Half)
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn fragments_in_full_duplex() {
+        let mut detector = DuplexMismatchDetector::default();
+        let mut stats = Statistics::new();
+        assert_eq!(detector.check(&stats, Duplex::Full), None);
+
+        // Late collisions cannot happen in full duplex and are ignored
+        stats.tx_diagnostics.late_collisions = 50;
+        stats.rx_crc_errors = 2;
+        stats.rx_runts = 3;
+        let mismatch = detector.check(&stats, Duplex::Full).unwrap();
+        assert_eq!(
+            mismatch,
+            DuplexMismatch::PartnerHalfDuplex {
+                crc_errors: 2,
+                runts: 3
+            }
+        );
+        assert!(mismatch.remediation().contains("auto-negotiation"));
+
+        detector.reset();
+        assert!(!detector.is_flagged());
+        assert_eq!(detector.check(&stats, Duplex::Full), None);
+    }
+}
@@ -12,27 +12,54 @@
 //! For filtering capabilities, see the [`filtering`](super::filtering) module.
 //! For flow control, see the [`flow`](super::flow) module.
 
+use core::sync::atomic::{AtomicBool, Ordering};
+
 use embedded_hal::delay::DelayNs;
 
-use super::config::{Duplex, EmacConfig, PhyInterface, RmiiClockMode, Speed, State};
-use super::error::{ConfigError, IoError, Result};
+use super::borrowed_tx::BorrowedTx;
+use super::checksum::RxChecksumStatus;
+use super::config::{
+    ControlFrameFilter, Duplex, EmacConfig, LlcFrameFilter, PadPull, PhyInterface, RmiiClockMode,
+    RuntimeSettings, Speed, State, is_multicast_mac,
+};
+use super::duplex::{DuplexMismatch, DuplexMismatchDetector};
+use super::error::{ConfigError, DmaError, Error, IoError, Result};
+use super::event_log::{EmacEventSink, EventKind, EventLog, EventThresholds, ThresholdMonitor};
+use super::fcs::RxFcsStatus;
+use super::filtering::FilterSnapshot;
+use super::frame_buf::FrameBuf;
+use super::frame_format::{FRAME_FORMAT_HEAD_LEN, FrameFormat};
+#[cfg(feature = "frame-trace")]
+use super::frame_trace::FrameTraceLog;
+use super::hw_state::HwStateLoss;
 use super::interrupt::InterruptStatus;
+use super::liveness::{LivenessReport, RxProcessState, TxProcessState};
+use super::rx_timestamp::{RxStampSlot, SoftRxTimestamp};
+use super::stats::{RxFrameClass, RxOccupancy, Statistics, is_own_unicast, is_pause_frame};
+use super::tx_rewrite::{TX_REWRITE_HEAD_LEN, TxRewriteTable};
+use crate::hal::clock::PeripheralClockGate;
 use crate::hal::reset::ResetController;
-use crate::internal::constants::{
-    CSR_CLOCK_DIV_42, FLUSH_TIMEOUT, MII_BUSY_TIMEOUT, TX_DMA_STATE_MASK, TX_DMA_STATE_SHIFT,
-};
+use crate::hal::time::{Clock, PollTimeout};
+use crate::internal::constants::{CSR_CLOCK_DIV_42, INTERRUPT_ACK_ROUNDS, MAX_FRAME_SIZE};
 use crate::internal::dma::DmaEngine;
+#[cfg(feature = "crc-verify")]
+use crate::internal::dma::bits::rdes0;
 use crate::internal::register::dma::{
-    DMABUSMODE_AAL, DMABUSMODE_ATDS, DMABUSMODE_FB, DMABUSMODE_PBL_MASK, DMABUSMODE_PBL_SHIFT,
-    DMABUSMODE_USP, DMAOPERATION_RSF, DMAOPERATION_TSF, DmaRegs,
+    DMAINTEN_TUE, DMAOPERATION_SR, DMAOPERATION_ST, DMASTATUS_FBI, DmaBusMode, DmaOperation,
+    DmaRegs,
+};
+use crate::internal::register::ext::{
+    EX_CLK_EN, EX_PHYINF_PHY_INTF_RMII, EX_PHYINF_PHY_INTF_SEL_MASK, EX_PHYINF_PHY_INTF_SEL_SHIFT,
+    ExtRegs,
 };
-use crate::internal::register::ext::ExtRegs;
-use crate::internal::register::gpio::GpioMatrix;
+use crate::internal::register::gpio::{GpioMatrix, IO_MUX_FUN_WPD, IO_MUX_FUN_WPU};
 use crate::internal::register::mac::{
     GMACCONFIG_ACS, GMACCONFIG_DM, GMACCONFIG_FES, GMACCONFIG_IPC, GMACCONFIG_JD, GMACCONFIG_PS,
-    GMACCONFIG_WD, GMACFF_PM, GMACFF_PR, GMACMIIADDR_CR_MASK, GMACMIIADDR_CR_SHIFT, GMACMIIADDR_GB,
-    GMACMIIADDR_GR_SHIFT, GMACMIIADDR_GW, GMACMIIADDR_PA_SHIFT, MacRegs,
+    GMACCONFIG_RE, GMACCONFIG_TE, GMACCONFIG_WD, GMACFF_PCF_MASK, GMACFF_PCF_SHIFT, GMACFF_PM,
+    GMACFF_PR, GMACMIIADDR_CR_MASK, GMACMIIADDR_CR_SHIFT, GMACMIIADDR_GB, GMACMIIADDR_GR_SHIFT,
+    GMACMIIADDR_GW, GMACMIIADDR_PA_SHIFT, MAC_ADDR_FILTER_COUNT, MacRegs,
 };
+use crate::phy::LinkStatus;
 
 // =============================================================================
 // Helper Types
@@ -47,6 +74,42 @@ impl<D: DelayNs> DelayNs for BorrowedDelay<'_, D> {
     }
 }
 
+/// What the driver learns from a received frame's bytes, taken while they
+/// are still at hand
+struct RxInspection {
+    /// RDES0 of the frame's last descriptor
+    status: u32,
+    /// Frame length in bytes
+    len: usize,
+    fcs: RxFcsStatus,
+    format: Option<FrameFormat>,
+    class: RxFrameClass,
+}
+
+impl RxInspection {
+    iram! {
+        /// Inspect `frame`, checking `fcs` in software if given
+        #[cfg_attr(not(feature = "crc-verify"), allow(unused_mut, unused_variables))]
+        fn new(status: u32, frame: &[u8], fcs: Option<[u8; 4]>) -> Self {
+            let mut fcs_status = RxFcsStatus::from_rdes0(status);
+            // Length-field frames have their FCS stripped by the MAC (ACS)
+            #[cfg(feature = "crc-verify")]
+            if let Some(fcs) = fcs
+                && status & rdes0::FRAME_TYPE != 0
+            {
+                fcs_status.software_ok = Some(super::fcs::fcs_matches(frame, fcs));
+            }
+            Self {
+                status,
+                len: frame.len(),
+                fcs: fcs_status,
+                format: FrameFormat::parse(frame),
+                class: RxFrameClass::classify(status, frame),
+            }
+        }
+    }
+}
+
 // =============================================================================
 // EMAC Driver
 // =============================================================================
@@ -61,6 +124,14 @@ impl<D: DelayNs> DelayNs for BorrowedDelay<'_, D> {
 /// * `TX_BUFS` - Number of transmit buffers (typically 10)
 /// * `BUF_SIZE` - Size of each buffer in bytes (typically 1600)
 ///
+/// `BUF_SIZE` need not be a multiple of 4: buffers are always word-aligned
+/// and the size handed to the DMA is rounded down, see [`Emac::buffer_size`].
+///
+/// TX-only applications can set `RX_BUFS` to 0, which drops the RX ring and
+/// its buffers from the instance: `start()` then never enables the
+/// receiver, and `receive()`, `enable_rx()` and `enable_rx_only()` fail with
+/// `DmaError::NoRxBuffers`. `TX_BUFS` must be at least 1.
+///
 /// # Important: Self-Referential Descriptor Chain
 ///
 /// **The EMAC must be placed in its final memory location BEFORE calling `init()`.**
@@ -98,11 +169,18 @@ impl<D: DelayNs> DelayNs for BorrowedDelay<'_, D> {
 /// - Core operations (this module): init, start/stop, tx/rx, link config
 /// - [`filtering`](super::filtering): MAC address, hash, and VLAN filtering
 /// - [`flow`](super::flow): IEEE 802.3 flow control
+/// - [`ops`](super::ops): object-safe [`EmacOps`](super::ops::EmacOps) trait
+/// - [`lifecycle`](super::lifecycle): type-state [`TypedEmac`](super::lifecycle::TypedEmac) handle
+/// - [`capabilities`](super::capabilities): capability report
+/// - [`stats`](super::stats): frame counters
+/// - [`fcs`](super::fcs): per-frame FCS status
 pub struct Emac<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize> {
     /// DMA engine
     pub(super) dma: DmaEngine<RX_BUFS, TX_BUFS, BUF_SIZE>,
-    /// Current configuration
+    /// Structural configuration from `init()`
     pub(super) config: EmacConfig,
+    /// Live runtime-tunable settings
+    pub(super) settings: RuntimeSettings,
     /// Current state
     state: State,
     /// MAC address
@@ -111,10 +189,62 @@ pub struct Emac<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usiz
     speed: Speed,
     /// Current duplex mode
     duplex: Duplex,
-    /// Flow control state: peer supports PAUSE frames
-    pub(super) peer_pause_ability: bool,
+    /// Flow control state: PAUSE resolved for our transmit direction
+    /// (we may send PAUSE frames)
+    pub(super) pause_tx: bool,
+    /// Flow control state: PAUSE resolved for our receive direction
+    /// (we honour received PAUSE frames)
+    pub(super) pause_rx: bool,
     /// Flow control state: currently applying backpressure
     pub(super) flow_control_active: bool,
+    /// Recent driver events for postmortem analysis
+    events: EventLog,
+    /// Ring sizes to apply at the next `start()`
+    pending_ring_sizes: Option<(usize, usize)>,
+    /// Frame counters
+    stats: Statistics,
+    /// FCS verdict for the last frame returned by `receive()`
+    last_rx_fcs: Option<RxFcsStatus>,
+    /// Checksum offload verdict for the last frame returned by `receive()`
+    last_rx_checksum: Option<RxChecksumStatus>,
+    /// Encapsulation of the last frame returned by `receive()`
+    last_rx_format: Option<FrameFormat>,
+    /// Software timestamp of the last frame returned by `receive()`
+    last_rx_timestamp: Option<SoftRxTimestamp>,
+    /// RX interrupt time handed from the ISR to `receive()`
+    rx_stamps: RxStampSlot,
+    /// A descriptor ring operation is in progress (reentrancy tripwire)
+    ring_busy: AtomicBool,
+    /// Optional duplex mismatch heuristic
+    duplex_detector: Option<DuplexMismatchDetector>,
+    /// Observer notified of every logged event
+    event_sink: Option<&'static dyn EmacEventSink>,
+    /// Monotonic time source for timeouts and timestamps
+    clock: Option<&'static dyn Clock>,
+    /// Peripheral clock gate owned by a power manager (DPORT if `None`)
+    clock_gate: Option<&'static dyn PeripheralClockGate>,
+    /// CPU cycle counter timing critical-section holds
+    cycle_counter: Option<fn() -> u32>,
+    /// Threshold crossing state for `check_thresholds`
+    thresholds: ThresholdMonitor,
+    /// RX DMA and MAC receiver are on (may lag `start()` with deferred RX)
+    rx_enabled: bool,
+    /// Event clock at the last frame returned by `receive()`
+    last_rx_tick: Option<u32>,
+    /// Event clock at the last frame queued for transmission
+    last_tx_tick: Option<u32>,
+    /// Filter state to restore when sniffer mode ends
+    pub(super) sniffer: Option<FilterSnapshot>,
+    /// Clock at the start of the startup receive-all window, while open
+    pub(super) receive_all_since: Option<u32>,
+    /// Header rewrite rules applied while frames are copied for TX
+    tx_rewrite: TxRewriteTable,
+    /// Recompute the CRC of received frames in software
+    #[cfg(feature = "crc-verify")]
+    fcs_verify: bool,
+    /// Recent transmit attempts
+    #[cfg(feature = "frame-trace")]
+    frame_trace: FrameTraceLog,
 }
 
 impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
@@ -128,12 +258,39 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
         Self {
             dma: DmaEngine::new(),
             config: EmacConfig::new(),
+            settings: RuntimeSettings::new(),
             state: State::Uninitialized,
             mac_addr: [0u8; 6],
             speed: Speed::Mbps100,
             duplex: Duplex::Full,
-            peer_pause_ability: false,
+            pause_tx: false,
+            pause_rx: false,
             flow_control_active: false,
+            events: EventLog::new(),
+            pending_ring_sizes: None,
+            stats: Statistics::new(),
+            last_rx_fcs: None,
+            last_rx_checksum: None,
+            last_rx_format: None,
+            last_rx_timestamp: None,
+            rx_stamps: RxStampSlot::new(),
+            ring_busy: AtomicBool::new(false),
+            duplex_detector: None,
+            event_sink: None,
+            clock: None,
+            clock_gate: None,
+            cycle_counter: None,
+            thresholds: ThresholdMonitor::new(),
+            rx_enabled: false,
+            last_rx_tick: None,
+            last_tx_tick: None,
+            sniffer: None,
+            receive_all_since: None,
+            tx_rewrite: TxRewriteTable::new(),
+            #[cfg(feature = "crc-verify")]
+            fcs_verify: false,
+            #[cfg(feature = "frame-trace")]
+            frame_trace: FrameTraceLog::new(),
         }
     }
 
@@ -187,13 +344,37 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
     ///
     /// # Errors
     /// - `AlreadyInitialized` - EMAC was already initialized
+    /// - `InvalidMacAddress` - Configured MAC address is multicast
+    /// - `InvalidConfig` - Dual-buffer descriptors requested with fewer than
+    ///   two RX or TX buffers, no TX descriptor left outside
+    ///   `tx_reserved_descriptors`, or no RX descriptor left outside
+    ///   `rx_unicast_reserve`
     /// - `ResetFailed` - Software reset did not complete
-    pub fn init<D: DelayNs>(&mut self, config: EmacConfig, mut delay: D) -> Result<()> {
+    pub fn init<D: DelayNs>(&mut self, config: EmacConfig, delay: D) -> Result<()> {
+        let result = self.init_hardware(config, delay);
+        match result {
+            Ok(()) => self.set_state(State::Initialized),
+            Err(e) => self.emit(EventKind::Error(e)),
+        }
+        result
+    }
+
+    /// Initialization sequence behind [`init`](Self::init)
+    fn init_hardware<D: DelayNs>(&mut self, config: EmacConfig, mut delay: D) -> Result<()> {
         if self.state != State::Uninitialized {
             return Err(ConfigError::AlreadyInitialized.into());
         }
 
+        config.validate()?;
+        if !self.dma.set_dual_buffer(config.dma_dual_buffer) {
+            return Err(ConfigError::InvalidConfig.into());
+        }
+        config.validate_rings(self.dma.rx_active_buffers(), self.dma.tx_active_buffers())?;
+        self.dma.set_tx_reserved(config.tx_reserved_descriptors);
+        self.settings = config.runtime_settings();
         self.config = config;
+        self.sniffer = None;
+        self.receive_all_since = None;
 
         // === STEP 1: Configure GPIO routing BEFORE any EMAC operations ===
         if matches!(self.config.rmii_clock, RmiiClockMode::ExternalInput { .. }) {
@@ -212,13 +393,19 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
 
         // Configure RMII data pins via IO_MUX (fixed pins, function 5)
         // This MUST be done for TX/RX to work
-        GpioMatrix::configure_rmii_pins();
+        let timing = self.config.rmii_timing;
+        let rx_pull = match timing.rx_pull {
+            PadPull::None => 0,
+            PadPull::Up => IO_MUX_FUN_WPU,
+            PadPull::Down => IO_MUX_FUN_WPD,
+        };
+        GpioMatrix::configure_rmii_pins_with(timing.tx_drive as u32, rx_pull);
 
         #[cfg(feature = "defmt")]
         defmt::info!("RMII data pins configured via IO_MUX");
 
         // === STEP 2: Enable DPORT peripheral clock ===
-        ExtRegs::enable_peripheral_clock();
+        self.enable_peripheral_clock();
 
         #[cfg(feature = "defmt")]
         defmt::info!("EMAC peripheral clock enabled via DPORT");
@@ -239,17 +426,43 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
         // Configure DMA defaults
         self.configure_dma_defaults();
 
-        // Initialize DMA engine (descriptor chains)
+        // Initialize DMA engine (descriptor chains or dual-buffer ring)
         self.dma.init();
+        self.dma
+            .set_tx_ic_interval(self.settings.tx_interrupt_policy.interval());
 
         // Set MAC address from configuration
         self.mac_addr = self.config.mac_address;
         MacRegs::set_mac_address(&self.mac_addr);
 
-        self.state = State::Initialized;
+        // Program initial filters before RX can be enabled
+        self.apply_filter_config();
+
         Ok(())
     }
 
+    /// Change state, logging the transition
+    fn set_state(&mut self, to: State) {
+        if self.state != to {
+            self.emit(EventKind::StateChanged {
+                from: self.state,
+                to,
+            });
+            self.state = to;
+        }
+    }
+
+    iram! {
+        /// Log a failed operation unless it is routine backpressure
+        fn log_error<T>(&mut self, result: Result<T>) -> Result<T> {
+            match result {
+                Err(e) if !e.is_routine() => self.emit(EventKind::Error(e)),
+                _ => {}
+            }
+            result
+        }
+    }
+
     /// Configure PHY interface extension registers (MII/RMII mode and clock source)
     fn configure_phy_interface_regs(&self) {
         match self.config.phy_interface {
@@ -260,8 +473,14 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
                     RmiiClockMode::ExternalInput { .. } => {
                         ExtRegs::set_rmii_clock_external();
                     }
-                    RmiiClockMode::InternalOutput { .. } => {
+                    RmiiClockMode::InternalOutput { gpio } => {
                         ExtRegs::set_rmii_clock_internal();
+
+                        let timing = self.config.rmii_timing;
+                        ExtRegs::set_clkout_delay(timing.clock_delay as u32);
+                        if let Some(drive) = timing.clock_drive {
+                            GpioMatrix::set_drive_strength(gpio, drive as u32);
+                        }
                     }
                 }
             }
@@ -306,13 +525,15 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
         // Configure frame filter
         let mut filter = 0u32;
 
-        if self.config.promiscuous {
+        if self.settings.promiscuous {
             filter |= GMACFF_PR;
         }
 
         // Pass all multicast (for now)
         filter |= GMACFF_PM;
 
+        filter |= self.settings.control_frames.pcf() << GMACFF_PCF_SHIFT;
+
         MacRegs::set_frame_filter(filter);
 
         // Clear hash tables
@@ -322,19 +543,14 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
 
     /// Configure DMA defaults
     fn configure_dma_defaults(&self) {
-        let pbl = self.config.dma_burst_len.to_pbl();
-        let bus_mode = DMABUSMODE_FB  // Fixed burst
-            | DMABUSMODE_AAL           // Address-aligned beats
-            | DMABUSMODE_USP           // Use separate PBL
-            | DMABUSMODE_ATDS          // Alternate descriptor size (8 words)
-            | ((pbl << DMABUSMODE_PBL_SHIFT) & DMABUSMODE_PBL_MASK);
-
-        DmaRegs::set_bus_mode(bus_mode);
+        DmaRegs::set_bus_mode_fields(self.dma_bus_mode());
 
-        let op_mode = DMAOPERATION_TSF  // TX store and forward
-            | DMAOPERATION_RSF; // RX store and forward
-
-        DmaRegs::set_operation_mode(op_mode);
+        let op_mode = DmaOperation::default()
+            .with_tx_store_forward(true)
+            .with_rx_store_forward(true)
+            .with_forward_errors(self.config.forward_error_frames)
+            .with_forward_undersized(self.config.forward_error_frames);
+        DmaRegs::set_operation_mode_fields(op_mode);
 
         // Disable all interrupts initially
         DmaRegs::disable_all_interrupts();
@@ -343,6 +559,211 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
         DmaRegs::clear_all_interrupts();
     }
 
+    /// DMA bus mode for the configuration
+    fn dma_bus_mode(&self) -> DmaBusMode {
+        let pbl = self.config.dma_burst_len.to_pbl();
+        let rpbl = self.config.rx_burst_len().to_pbl();
+        let dsl = if self.config.dma_dual_buffer {
+            // Ring mode: skip any padding between descriptors
+            DmaEngine::<RX_BUFS, TX_BUFS, BUF_SIZE>::ring_skip_words()
+        } else {
+            0
+        };
+        DmaBusMode::default()
+            .with_fixed_burst(self.config.dma_fixed_burst)
+            .with_address_aligned(self.config.dma_address_aligned)
+            .with_separate_pbl(true)
+            .with_alt_desc_size(true)
+            .with_burst_len(pbl)
+            .with_rx_burst_len(rpbl)
+            .with_desc_skip_len(dsl)
+            .with_pbl_x8(self.config.dma_pbl_x8)
+            .with_mixed_burst(self.config.dma_mixed_burst)
+    }
+
+    // =========================================================================
+    // Hardware Reset
+    // =========================================================================
+
+    /// Return the EMAC to its power-on state without a power cycle
+    ///
+    /// Pulses the EMAC module reset so every DMA, MAC and extension register
+    /// (filters, hash tables, VLAN tag, flow control, clock configuration)
+    /// goes back to its reset value, verifies the registers a previous run
+    /// typically leaves behind, and gates the peripheral clock again. The
+    /// driver returns to `Uninitialized` with default settings, so
+    /// [`init`](Self::init) can run again as after a cold boot.
+    ///
+    /// Statistics and the event log are kept; call
+    /// [`reset_stats`](Self::reset_stats) for a clean slate.
+    ///
+    /// # Errors
+    /// - `ResetFailed` - A register did not read back its power-on value
+    pub fn full_hardware_reset<D: DelayNs>(&mut self, mut delay: D) -> Result<()> {
+        self.enable_peripheral_clock();
+        ExtRegs::reset_peripheral();
+        delay.delay_us(10);
+
+        let verified = Self::registers_at_reset_values();
+        self.disable_peripheral_clock();
+
+        self.dma.set_dual_buffer(false);
+        self.dma.set_active_buffers(RX_BUFS, TX_BUFS);
+        self.dma.set_tx_reserved(0);
+        self.config = EmacConfig::new();
+        self.settings = RuntimeSettings::new();
+        self.mac_addr = [0u8; 6];
+        self.speed = Speed::Mbps100;
+        self.duplex = Duplex::Full;
+        self.pause_tx = false;
+        self.pause_rx = false;
+        self.flow_control_active = false;
+        self.pending_ring_sizes = None;
+        self.last_rx_fcs = None;
+        self.last_rx_checksum = None;
+        self.last_rx_format = None;
+        self.last_rx_timestamp = None;
+        self.rx_stamps.clear();
+        self.rx_enabled = false;
+        self.last_rx_tick = None;
+        self.last_tx_tick = None;
+        self.sniffer = None;
+        self.receive_all_since = None;
+        self.tx_rewrite.clear();
+        self.set_state(State::Uninitialized);
+
+        if verified {
+            Ok(())
+        } else {
+            let e = ConfigError::ResetFailed.into();
+            self.emit(EventKind::Error(e));
+            Err(e)
+        }
+    }
+
+    /// True if the registers `init()` and the filtering APIs program are
+    /// back at their (all-zero) power-on values
+    fn registers_at_reset_values() -> bool {
+        MacRegs::frame_filter() == 0
+            && MacRegs::hash_table_high() == 0
+            && MacRegs::hash_table_low() == 0
+            && MacRegs::flow_control() == 0
+            && MacRegs::vlan_tag() == 0
+            && (1..=MAC_ADDR_FILTER_COUNT)
+                .all(|slot| MacRegs::is_mac_filter_enabled(slot) == Some(false))
+            && DmaRegs::operation_mode() == 0
+            && DmaRegs::interrupt_enable() == 0
+    }
+
+    // =========================================================================
+    // Sleep Resume
+    // =========================================================================
+
+    /// Compare the key EMAC registers with what the driver programmed
+    ///
+    /// Cheap enough to run after every wake-up from light sleep; see
+    /// [`hw_state`](super::hw_state). Before `init()` nothing is expected
+    /// and the result is always intact.
+    pub fn verify_hw_state(&self) -> HwStateLoss {
+        let mut loss = HwStateLoss::default();
+        if self.state == State::Uninitialized {
+            return loss;
+        }
+
+        loss.clock =
+            !ExtRegs::is_peripheral_clock_enabled() || ExtRegs::clk_ctrl() & EX_CLK_EN == 0;
+
+        let intf =
+            (ExtRegs::phy_inf_conf() & EX_PHYINF_PHY_INTF_SEL_MASK) >> EX_PHYINF_PHY_INTF_SEL_SHIFT;
+        let expected_intf = match self.config.phy_interface {
+            PhyInterface::Rmii => EX_PHYINF_PHY_INTF_RMII,
+            PhyInterface::Mii => 0,
+        };
+        loss.interface = intf != expected_intf;
+
+        let cfg = MacRegs::config();
+        let bit = |mask: u32, on: bool| (cfg & mask != 0) == on;
+        loss.mac = !bit(GMACCONFIG_PS, true)
+            || !bit(GMACCONFIG_FES, self.speed == Speed::Mbps100)
+            || !bit(GMACCONFIG_DM, self.duplex == Duplex::Full)
+            || !bit(GMACCONFIG_TE, self.state.tx_allowed())
+            || !bit(GMACCONFIG_RE, self.rx_enabled)
+            || MacRegs::get_mac_address() != self.mac_addr;
+
+        let ff = MacRegs::frame_filter();
+        loss.filter = (ff & GMACFF_PR != 0) != self.settings.promiscuous
+            || (ff & GMACFF_PCF_MASK) >> GMACFF_PCF_SHIFT != self.settings.control_frames.pcf();
+
+        let op = DmaRegs::operation_mode();
+        loss.dma = DmaRegs::bus_mode() != self.dma_bus_mode().bits()
+            || (op & DMAOPERATION_ST != 0) != self.state.tx_allowed()
+            || (op & DMAOPERATION_SR != 0) != self.rx_enabled
+            || (self.state.is_active() && DmaRegs::interrupt_enable() == 0)
+            || (Self::HAS_RX
+                && !self
+                    .dma
+                    .is_ring_descriptor(DmaRegs::rx_desc_list_addr(), true))
+            || !self
+                .dma
+                .is_ring_descriptor(DmaRegs::tx_desc_list_addr(), false);
+
+        loss
+    }
+
+    /// Reprogram the EMAC after light sleep without resetting the rings
+    ///
+    /// Re-enables the clocks, soft-resets the MAC and DMA, and programs them
+    /// again from the cached configuration, link parameters and runtime
+    /// settings. The descriptor rings are left alone: the DMA lists are
+    /// pointed at the next descriptors the DMA owns, so received frames not
+    /// yet read stay in the RX ring and queued frames are still sent. If the
+    /// EMAC was running, the directions that were on are restarted.
+    ///
+    /// Receive filters are rebuilt from `EmacConfig::filters`; restore
+    /// filters changed at run time with
+    /// [`restore_filters`](Self::restore_filters). A frame the DMA was in
+    /// the middle of when the state was lost is dropped.
+    ///
+    /// # Errors
+    /// - `InvalidState` - EMAC is not initialized
+    /// - `ResetFailed` - Software reset did not complete (no RX clock from
+    ///   the PHY)
+    pub fn fast_resume<D: DelayNs>(&mut self, mut delay: D) -> Result<()> {
+        if self.state == State::Uninitialized {
+            return Err(IoError::InvalidState.into());
+        }
+
+        self.enable_peripheral_clock();
+        self.configure_phy_interface_regs();
+        ExtRegs::enable_clocks();
+        ExtRegs::power_up_ram();
+        let reset = self.software_reset(&mut delay);
+        self.log_error(reset)?;
+
+        self.configure_mac_defaults();
+        MacRegs::set_speed_100mbps(self.speed == Speed::Mbps100);
+        MacRegs::set_duplex_full(self.duplex == Duplex::Full);
+        MacRegs::set_mac_address(&self.mac_addr);
+        self.configure_dma_defaults();
+        self.dma.program_resume_bases();
+        self.apply_filter_config();
+        self.apply_flow_control(self.settings.flow_control.enabled);
+
+        if self.state.is_active() {
+            DmaRegs::enable_default_interrupts();
+            if self.state.tx_allowed() {
+                self.apply_tx_unavailable_interrupt();
+                self.mac_tx_enable(true);
+                DmaRegs::start_tx();
+                DmaRegs::tx_poll_demand();
+            }
+            if self.rx_enabled {
+                self.start_rx();
+            }
+        }
+        Ok(())
+    }
+
     // =========================================================================
     // Start / Stop
     // =========================================================================
@@ -350,31 +771,131 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
     /// Start the EMAC (enable TX and RX)
     ///
     /// After calling this, the EMAC will begin receiving frames into
-    /// the RX buffers and is ready to transmit frames.
+    /// the RX buffers and is ready to transmit frames. With
+    /// [`EmacConfig::with_deferred_rx`] only TX is started; call
+    /// [`enable_rx`](Self::enable_rx) once the stack is polling.
     ///
     /// # Errors
-    /// - `InvalidState` - EMAC is not initialized
+    /// - `InvalidState` - EMAC is not initialized, or was started in one
+    ///   direction only
     pub fn start(&mut self) -> Result<()> {
+        self.start_directions(true, !self.config.deferred_rx, State::Running)
+    }
+
+    /// Start only the receiver, for listen-only monitors
+    ///
+    /// The MAC transmitter and TX DMA stay off, and every transmit call
+    /// fails with `InvalidState` until `stop()`, so a monitoring tap cannot
+    /// put a frame on the wire. The state becomes [`State::RxOnly`].
+    /// Flow control PAUSE frames are generated by the transmitter and are
+    /// not sent either.
+    ///
+    /// # Errors
+    /// - `InvalidState` - EMAC is not initialized, or already started with
+    ///   TX enabled
+    /// - `NoRxBuffers` - There is no RX ring (`RX_BUFS = 0`)
+    pub fn enable_rx_only(&mut self) -> Result<()> {
+        if !Self::HAS_RX {
+            return Err(DmaError::NoRxBuffers.into());
+        }
+        self.start_directions(false, true, State::RxOnly)
+    }
+
+    /// Start only the transmitter, for TX-only streamers
+    ///
+    /// The MAC receiver and RX DMA stay off, so nothing is written to the
+    /// RX ring and `receive()` fails with `InvalidState`. The state becomes
+    /// [`State::TxOnly`].
+    ///
+    /// # Errors
+    /// - `InvalidState` - EMAC is not initialized, or already started with
+    ///   RX enabled
+    pub fn enable_tx_only(&mut self) -> Result<()> {
+        self.start_directions(true, false, State::TxOnly)
+    }
+
+    /// Reset the rings and start the requested directions, entering `to`
+    fn start_directions(&mut self, tx: bool, rx: bool, to: State) -> Result<()> {
         match self.state {
             State::Initialized | State::Stopped => {}
-            State::Running => return Ok(()), // Already running
-            State::Uninitialized => return Err(IoError::InvalidState.into()),
+            state if state == to => return Ok(()), // Already started
+            _ => return Err(IoError::InvalidState.into()),
+        }
+
+        // Whatever the DMA did not release before it stopped is lost
+        #[cfg(feature = "frame-trace")]
+        {
+            self.update_frame_trace();
+            self.frame_trace.abandon_pending(self.now());
         }
 
-        // Reset DMA descriptors
-        self.dma.reset();
+        // Relink the rings if their size changed, otherwise just reset them
+        if let Some((rx, tx)) = self.pending_ring_sizes.take() {
+            self.dma.set_active_buffers(rx, tx);
+            self.dma.init();
+        } else {
+            self.dma.reset();
+        }
 
-        // Clear pending interrupts
+        // Clear pending interrupts, and the stamps of frames they announced
         DmaRegs::clear_all_interrupts();
+        self.rx_stamps.clear();
+        self.last_rx_timestamp = None;
 
         // Enable interrupts
         DmaRegs::enable_default_interrupts();
 
-        // Enable MAC transmitter
-        self.mac_tx_enable(true);
+        if tx {
+            self.apply_tx_unavailable_interrupt();
 
-        // Start DMA TX
-        DmaRegs::start_tx();
+            // Enable MAC transmitter
+            self.mac_tx_enable(true);
+
+            // Start DMA TX
+            DmaRegs::start_tx();
+        }
+
+        if rx && Self::HAS_RX {
+            self.start_rx();
+        }
+
+        self.set_state(to);
+        Ok(())
+    }
+
+    /// Enable RX after a [deferred](EmacConfig::with_deferred_rx) start
+    ///
+    /// Does nothing if RX is already enabled. The RX ring is empty at this
+    /// point: frames that arrived before the call were never accepted.
+    ///
+    /// # Errors
+    /// - `InvalidState` - EMAC is not running
+    /// - `NoRxBuffers` - There is no RX ring (`RX_BUFS = 0`)
+    pub fn enable_rx(&mut self) -> Result<()> {
+        if self.state != State::Running {
+            return Err(IoError::InvalidState.into());
+        }
+        if !Self::HAS_RX {
+            return Err(DmaError::NoRxBuffers.into());
+        }
+        if !self.rx_enabled {
+            self.start_rx();
+        }
+        Ok(())
+    }
+
+    /// True if the receiver is enabled
+    ///
+    /// `false` while stopped, and after a deferred-RX `start()` until
+    /// [`enable_rx`](Self::enable_rx).
+    pub fn is_rx_enabled(&self) -> bool {
+        self.rx_enabled
+    }
+
+    /// Start RX DMA and the MAC receiver
+    fn start_rx(&mut self) {
+        // Open the receive-all window before the first frame can arrive
+        self.begin_receive_all_grace();
 
         // Start DMA RX
         DmaRegs::start_rx();
@@ -385,19 +906,42 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
         // Issue RX poll demand to start receiving
         DmaRegs::rx_poll_demand();
 
-        self.state = State::Running;
-        Ok(())
+        self.rx_enabled = true;
     }
 
     /// Stop the EMAC (disable TX and RX)
     ///
-    /// This gracefully stops all DMA operations and disables the MAC.
+    /// This gracefully stops all DMA operations and disables the MAC. Also
+    /// ends an [`enable_rx_only`](Self::enable_rx_only) or
+    /// [`enable_tx_only`](Self::enable_tx_only) start.
+    ///
+    /// The waits for the transmit DMA and the TX FIFO are bounded by
+    /// `EmacConfig::tx_stop_timeout_us` and `fifo_flush_timeout_us`, timed
+    /// with the installed [`Clock`] or else by counting polls; use
+    /// [`stop_with_delay`](Self::stop_with_delay) to time them without a
+    /// clock.
     ///
     /// # Errors
     /// - `InvalidState` - EMAC is not running
-    /// - `Timeout` - DMA did not stop in time
+    /// - `TxStopTimeout` - TX DMA did not stop in time
+    /// - `FifoFlushTimeout` - TX FIFO did not flush in time
     pub fn stop(&mut self) -> Result<()> {
-        if self.state != State::Running {
+        self.stop_timed(None)
+    }
+
+    /// Stop the EMAC, timing the hardware waits with `delay`
+    ///
+    /// Same as [`stop`](Self::stop), but without a [`Clock`] the timeouts
+    /// are measured by delaying between polls rather than counting them.
+    ///
+    /// # Errors
+    /// Same as [`stop`](Self::stop).
+    pub fn stop_with_delay<D: DelayNs>(&mut self, delay: &mut D) -> Result<()> {
+        self.stop_timed(Some(delay))
+    }
+
+    fn stop_timed(&mut self, mut delay: Option<&mut dyn DelayNs>) -> Result<()> {
+        if !self.state.is_active() {
             return Err(IoError::InvalidState.into());
         }
 
@@ -405,7 +949,8 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
         DmaRegs::stop_tx();
 
         // Wait for TX to complete
-        self.wait_tx_idle()?;
+        let idle = self.wait_tx_idle(delay.as_mut().map(|d| &mut **d as &mut dyn DelayNs));
+        self.log_error(idle)?;
 
         // Stop DMA RX
         DmaRegs::stop_rx();
@@ -413,9 +958,12 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
         // Disable MAC TX/RX
         self.mac_tx_enable(false);
         self.mac_rx_enable(false);
+        self.rx_enabled = false;
+        self.end_receive_all_grace();
 
         // Flush TX FIFO
-        self.flush_tx_fifo()?;
+        let flushed = self.flush_tx_fifo(delay);
+        self.log_error(flushed)?;
 
         // Disable interrupts
         DmaRegs::disable_all_interrupts();
@@ -423,7 +971,7 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
         // Clear pending interrupts
         DmaRegs::clear_all_interrupts();
 
-        self.state = State::Stopped;
+        self.set_state(State::Stopped);
         Ok(())
     }
 
@@ -446,50 +994,255 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
     }
 
     /// Wait for TX DMA to become idle
-    fn wait_tx_idle(&self) -> Result<()> {
-        for _ in 0..FLUSH_TIMEOUT {
-            let status = DmaRegs::status();
-            let tx_state = (status >> TX_DMA_STATE_SHIFT) & TX_DMA_STATE_MASK;
-            if tx_state == 0 {
+    fn wait_tx_idle(&self, delay: Option<&mut dyn DelayNs>) -> Result<()> {
+        let mut timeout = PollTimeout::start(self.clock, delay, self.config.tx_stop_timeout_us);
+        loop {
+            if TxProcessState::from_status(DmaRegs::status()) == TxProcessState::Stopped {
                 return Ok(());
             }
-            core::hint::spin_loop();
+            if timeout.expired() {
+                return Err(IoError::TxStopTimeout.into());
+            }
         }
-        Err(IoError::Timeout.into())
     }
 
     /// Flush TX FIFO
-    fn flush_tx_fifo(&self) -> Result<()> {
+    fn flush_tx_fifo(&self, delay: Option<&mut dyn DelayNs>) -> Result<()> {
         DmaRegs::flush_tx_fifo();
 
-        for _ in 0..FLUSH_TIMEOUT {
+        let mut timeout = PollTimeout::start(self.clock, delay, self.config.fifo_flush_timeout_us);
+        loop {
             if DmaRegs::is_tx_fifo_flush_complete() {
                 return Ok(());
             }
-            core::hint::spin_loop();
+            if timeout.expired() {
+                return Err(IoError::FifoFlushTimeout.into());
+            }
         }
-        Err(IoError::Timeout.into())
     }
 
     // =========================================================================
     // TX / RX Operations
     // =========================================================================
 
-    /// Transmit a frame
+    iram! {
+        /// Transmit a frame
+        ///
+        /// Copies the frame data to TX buffers and submits to DMA.
+        /// Returns the number of bytes submitted.
+        ///
+        /// # Errors
+        /// - `InvalidState` - EMAC not running, or started with
+        ///   [`enable_rx_only`](Self::enable_rx_only)
+        /// - `InvalidLength` - Empty frame
+        /// - `FrameTooLarge` - Frame exceeds buffer capacity
+        /// - `NoDescriptorsAvailable` - No free TX descriptors (descriptors
+        ///   reserved for [`transmit_reserved`](Self::transmit_reserved) do not
+        ///   count)
+        /// - `Reentered` - Called while another descriptor ring operation was
+        ///   in progress, see [`ring_busy`](Self::ring_busy)
+        pub fn transmit(&mut self, data: &[u8]) -> Result<usize> {
+            self.queue_frame(data, |dma, head, body| {
+                dma.transmit_split(head, body, false, false)
+            })
+        }
+    }
+
+    /// Transmit the last frame of a burst
     ///
-    /// Copies the frame data to TX buffers and submits to DMA.
-    /// Returns the number of bytes submitted.
+    /// Same as [`transmit`](Self::transmit), but the frame always requests a
+    /// completion interrupt regardless of
+    /// [`TxInterruptPolicy`](crate::TxInterruptPolicy). Use it to close a
+    /// batch so waiters are woken as soon as the burst is on the wire.
     ///
     /// # Errors
-    /// - `InvalidState` - EMAC not running
-    /// - `InvalidLength` - Empty frame
-    /// - `FrameTooLarge` - Frame exceeds buffer capacity
-    /// - `NoDescriptorsAvailable` - No free TX descriptors
-    pub fn transmit(&mut self, data: &[u8]) -> Result<usize> {
-        if self.state != State::Running {
+    /// Same as [`transmit`](Self::transmit).
+    pub fn transmit_burst_end(&mut self, data: &[u8]) -> Result<usize> {
+        self.queue_frame(data, |dma, head, body| {
+            dma.transmit_split(head, body, true, false)
+        })
+    }
+
+    /// Transmit a high-priority frame
+    ///
+    /// Like [`transmit`](Self::transmit), but may also use the descriptors
+    /// held back by
+    /// [`EmacConfig::with_tx_reserved_descriptors`](crate::EmacConfig::with_tx_reserved_descriptors),
+    /// so PTP event messages, PAUSE and other control frames are accepted
+    /// while ordinary traffic has saturated the ring. The ESP32 has a single
+    /// TX queue: the frame is sent after those already queued, not ahead of
+    /// them. It always requests a completion interrupt.
+    ///
+    /// # Errors
+    /// Same as [`transmit`](Self::transmit); `NoDescriptorsAvailable` means
+    /// the reserve is used up as well.
+    pub fn transmit_reserved(&mut self, data: &[u8]) -> Result<usize> {
+        self.queue_frame(data, |dma, head, body| {
+            dma.transmit_split(head, body, true, true)
+        })
+    }
+
+    /// Transmit a frame straight from `'static` memory without copying it
+    ///
+    /// The TX descriptor points at `data` itself; see
+    /// [`borrowed_tx`](super::borrowed_tx) for the memory requirements.
+    /// Memory that can change while the frame is queued must be left alone
+    /// until [`borrowed_tx_done`](Self::borrowed_tx_done) reports the
+    /// returned handle done. Header rewrite rules are not applied.
+    ///
+    /// # Errors
+    /// Same as [`transmit`](Self::transmit), plus
+    /// - `BufferNotDmaCapable` - `data` is not 4-byte aligned in internal
+    ///   SRAM
+    /// - `FrameTooLarge` - `data` does not fit in one descriptor
+    pub fn transmit_borrowed(&mut self, data: &'static [u8]) -> Result<BorrowedTx> {
+        if !self.state.tx_allowed() {
+            return Err(IoError::InvalidState.into());
+        }
+        let result = self.with_ring_guard(|emac| {
+            emac.collect_tx_completions();
+            #[cfg(feature = "frame-trace")]
+            let first = emac.dma.tx_current_index();
+            let keep_free = emac.dma.tx_reserved();
+            let result = emac.dma.transmit_borrowed(data, keep_free);
+            #[cfg(feature = "frame-trace")]
+            emac.trace_submit(data.len(), first, &result);
+            result
+        });
+        if result.is_ok() {
+            self.last_tx_tick = Some(self.now());
+        }
+        self.log_error(result).map(|slot| BorrowedTx {
+            slot,
+            addr: data.as_ptr() as u32,
+            len: data.len(),
+        })
+    }
+
+    /// True once the DMA no longer reads the frame of `tx`
+    pub fn borrowed_tx_done(&self, tx: &BorrowedTx) -> bool {
+        !self.dma.tx_borrowed_pending(tx.slot, tx.addr)
+    }
+
+    /// Build a `len`-byte frame with `f` straight in a TX ring buffer and
+    /// queue it
+    ///
+    /// Returns `None`, without calling `f`, if the frame cannot be built
+    /// in place: the EMAC cannot transmit, another ring operation is in
+    /// progress, the frame does not fit one buffer, no descriptor is free,
+    /// or header rewrite rules are installed (they must see the finished
+    /// frame before it goes into the ring). [`transmit`](Self::transmit)
+    /// handles those.
+    #[cfg(feature = "smoltcp")]
+    pub(crate) fn transmit_in_place<R>(
+        &mut self,
+        len: usize,
+        f: impl FnOnce(&mut [u8]) -> R,
+    ) -> Option<R> {
+        if !self.state.tx_allowed() || !self.tx_rewrite.is_empty() {
+            return None;
+        }
+        let result = self.with_ring_guard(|emac| {
+            emac.collect_tx_completions();
+            #[cfg(feature = "frame-trace")]
+            let first = emac.dma.tx_current_index();
+            let keep_free = emac.dma.tx_reserved();
+            let built = emac.dma.transmit_in_place(len, keep_free, f);
+            #[cfg(feature = "frame-trace")]
+            if built.is_some() {
+                emac.trace_submit(len, first, &Ok(len));
+            }
+            Ok(built)
+        });
+        let built = result.ok().flatten()?;
+        self.last_tx_tick = Some(self.now());
+        Some(built)
+    }
+
+    /// Body of the `transmit*()` calls: collect the status of completed
+    /// frames, apply the header rewrite, queue the frame with `queue`, and
+    /// note the attempt
+    fn queue_frame(
+        &mut self,
+        data: &[u8],
+        queue: impl FnOnce(&mut DmaEngine<RX_BUFS, TX_BUFS, BUF_SIZE>, &[u8], &[u8]) -> Result<usize>,
+    ) -> Result<usize> {
+        if !self.state.tx_allowed() {
             return Err(IoError::InvalidState.into());
         }
-        self.dma.transmit(data)
+        let result = self.with_ring_guard(|emac| {
+            emac.collect_tx_completions();
+            #[cfg(feature = "frame-trace")]
+            let first = emac.dma.tx_current_index();
+            let mut rewritten = [0u8; TX_REWRITE_HEAD_LEN];
+            let (head, body) = emac
+                .tx_rewrite
+                .lookup(data)
+                .and_then(|rule| rule.rewrite(data, &mut rewritten))
+                .unwrap_or((&[], data));
+            let result = queue(&mut emac.dma, head, body);
+            if !head.is_empty() && result.is_ok() {
+                emac.tx_rewrite.record_rewrite();
+            }
+            #[cfg(feature = "frame-trace")]
+            emac.trace_submit(head.len() + body.len(), first, &result);
+            result
+        });
+        if result.is_ok() {
+            self.last_tx_tick = Some(self.now());
+        }
+        self.log_error(result)
+    }
+
+    iram! {
+        /// Record completed TX frames in the statistics and frame trace
+        ///
+        /// The trace reads the TDES0 status before the error bits are cleared.
+        fn collect_tx_completions(&mut self) {
+            #[cfg(feature = "frame-trace")]
+            self.update_frame_trace();
+            self.dma.collect_tx_errors(&mut self.stats.tx_diagnostics);
+        }
+    }
+
+    /// Complete frame trace entries the DMA has released
+    #[cfg(feature = "frame-trace")]
+    fn update_frame_trace(&mut self) {
+        let (now, slots) = (self.now(), self.dma.tx_slots());
+        let dma = &self.dma;
+        self.frame_trace
+            .update(now, slots, |idx| dma.tx_released_status(idx));
+    }
+
+    /// Trace a transmit attempt that started at descriptor `first`
+    #[cfg(feature = "frame-trace")]
+    fn trace_submit(&mut self, len: usize, first: usize, result: &Result<usize>) {
+        let now = self.now();
+        match result {
+            Ok(_) => {
+                let count = len.div_ceil(self.dma.slot_capacity());
+                self.frame_trace
+                    .record_submit(now, len, first, count, self.dma.tx_slots());
+            }
+            Err(err) => self.frame_trace.record_rejected(now, len, *err),
+        }
+    }
+
+    /// Header rewrite rules applied to transmitted frames
+    pub fn tx_rewrite(&self) -> &TxRewriteTable {
+        &self.tx_rewrite
+    }
+
+    /// Edit the header rewrite rules
+    ///
+    /// Changes apply from the next `transmit*()` call; frames already in
+    /// the ring are not touched. A rule that inserts a VLAN tag makes the
+    /// frame 4 bytes longer than `data`, which the returned length and the
+    /// `FrameTooLarge` check include. The rules survive `start()`/`stop()`
+    /// and are cleared by
+    /// [`full_hardware_reset`](Self::full_hardware_reset).
+    pub fn tx_rewrite_mut(&mut self) -> &mut TxRewriteTable {
+        &mut self.tx_rewrite
     }
 
     /// Check if a frame is available for receiving
@@ -503,87 +1256,574 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
         self.dma.peek_frame_length()
     }
 
-    /// Receive a frame
+    iram! {
+        /// Receive a frame
+        ///
+        /// Copies received frame data to the provided buffer.
+        /// Returns the actual frame length (excluding CRC).
+        ///
+        /// # Errors
+        /// - `InvalidState` - EMAC not running, or started with
+        ///   [`enable_tx_only`](Self::enable_tx_only)
+        /// - `BufferTooSmall` - Buffer smaller than frame
+        /// - `IncompleteFrame` - No complete frame available
+        /// - `FrameError` - Frame has receive errors
+        /// - `FcsMismatch` - Software FCS check failed (`crc-verify`); the frame
+        ///   is consumed and `buffer` holds its unverified bytes
+        /// - `NoRxBuffers` - There is no RX ring (`RX_BUFS = 0`)
+        /// - `Reentered` - Called while another descriptor ring operation was
+        ///   in progress, see [`ring_busy`](Self::ring_busy)
+        pub fn receive(&mut self, buffer: &mut [u8]) -> Result<usize> {
+            if !Self::HAS_RX {
+                return Err(DmaError::NoRxBuffers.into());
+            }
+            if !self.state.rx_allowed() {
+                return Err(IoError::InvalidState.into());
+            }
+            let result = self.with_ring_guard(|emac| emac.receive_frame(buffer));
+            self.log_error(result)
+        }
+    }
+
+    /// Receive a frame, keeping its head when `buffer` is too small
     ///
-    /// Copies received frame data to the provided buffer.
-    /// Returns the actual frame length (excluding CRC).
+    /// Like [`receive`](Self::receive), but a frame longer than `buffer`
+    /// fills it with the frame's first bytes instead of being dropped with
+    /// `BufferTooSmall`, which suits packet monitors that only look at
+    /// headers. Returns `(copied_len, frame_len)`; the frame was truncated
+    /// if `copied_len < frame_len`. Truncated frames are counted at their
+    /// full length and skip the software FCS check (`crc-verify`).
     ///
     /// # Errors
-    /// - `InvalidState` - EMAC not running
-    /// - `BufferTooSmall` - Buffer smaller than frame
-    /// - `IncompleteFrame` - No complete frame available
-    /// - `FrameError` - Frame has receive errors
-    pub fn receive(&mut self, buffer: &mut [u8]) -> Result<usize> {
-        if self.state != State::Running {
+    /// Those of [`receive`](Self::receive) other than `BufferTooSmall`.
+    pub fn receive_truncating(&mut self, buffer: &mut [u8]) -> Result<(usize, usize)> {
+        if !Self::HAS_RX {
+            return Err(DmaError::NoRxBuffers.into());
+        }
+        if !self.state.rx_allowed() {
             return Err(IoError::InvalidState.into());
         }
-        self.dma.receive(buffer)
+        let result = self.with_ring_guard(|emac| emac.receive_frame_truncating(buffer));
+        self.log_error(result)
     }
 
-    /// Check if TX is ready (descriptors available)
-    pub fn tx_ready(&self) -> bool {
-        self.dma.tx_available() > 0
+    /// Receive the waiting frames into `bufs`, in one ring pass
+    ///
+    /// Fills `bufs` front to back until the ring is drained or every
+    /// buffer holds a frame, and returns how many were filled: `bufs[..n]`
+    /// hold frames in arrival order. The DMA gets a single poll demand for
+    /// the whole batch, and with `SharedEmac` one
+    /// `with()` call takes the lock once for all of them, which matters for
+    /// high-rate ingestion.
+    ///
+    /// Frames with receive errors, failing the software FCS check, or
+    /// longer than their buffer are dropped and counted in [`Statistics`],
+    /// as by [`poll_rx`](Self::poll_rx). Returns 0 when
+    /// [`receive`](Self::receive) would fail for every frame (EMAC not
+    /// receiving, no RX ring, ring busy).
+    pub fn receive_batch<const N: usize>(&mut self, bufs: &mut [FrameBuf<N>]) -> usize {
+        if !Self::HAS_RX || !self.state.rx_allowed() {
+            return 0;
+        }
+        let result = self.with_ring_guard(|emac| {
+            emac.dma.begin_rx_batch();
+            let mut filled = 0;
+            // Dropped frames count too, so a stream of bad frames ends the pass
+            for _ in 0..bufs.len() + RX_BUFS {
+                let Some(buf) = bufs.get_mut(filled) else {
+                    break;
+                };
+                match emac.receive_frame(buf.storage_mut()) {
+                    Ok(len) => {
+                        buf.set_len(len);
+                        filled += 1;
+                    }
+                    Err(Error::Io(
+                        IoError::FrameError | IoError::FcsMismatch | IoError::BufferTooSmall,
+                    )) => {}
+                    Err(_) => break,
+                }
+            }
+            emac.dma.end_rx_batch();
+            Ok(filled)
+        });
+        self.log_error(result).unwrap_or(0)
     }
 
-    /// Check if TX can accept a frame of given size
-    pub fn can_transmit(&self, len: usize) -> bool {
-        self.dma.can_transmit(len)
+    /// Receive at most `budget` frames, handing each to `f`
+    ///
+    /// For superloop and RTOS main loops that must bound the time spent on
+    /// the network per iteration: unlike a `while rx_available()` drain, a
+    /// flood of frames cannot starve the rest of the loop. Frames with
+    /// receive errors, or longer than [`MAX_FRAME_SIZE`], are dropped (and
+    /// counted in [`Statistics`]) and use up budget like delivered ones.
+    ///
+    /// # Returns
+    /// * `Ok(true)` - The budget ran out with frames still waiting; poll
+    ///   again soon
+    /// * `Ok(false)` - The RX ring is drained
+    ///
+    /// # Errors
+    /// Those of [`receive`](Self::receive) that are not about a single
+    /// frame: `InvalidState`, `NoRxBuffers`, `Reentered`.
+    pub fn poll_rx(&mut self, budget: usize, mut f: impl FnMut(&[u8])) -> Result<bool> {
+        let mut buffer = [0u8; MAX_FRAME_SIZE];
+        for _ in 0..budget {
+            match self.receive(&mut buffer) {
+                Ok(len) => f(buffer.get(..len).unwrap_or_default()),
+                Err(Error::Io(IoError::IncompleteFrame)) => return Ok(false),
+                Err(Error::Io(
+                    IoError::FrameError | IoError::FcsMismatch | IoError::BufferTooSmall,
+                )) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(self.rx_available())
     }
 
-    // =========================================================================
-    // Link Configuration
-    // =========================================================================
+    iram! {
+        /// Body of [`receive`](Self::receive), run under the ring guard
+        fn receive_frame(&mut self, buffer: &mut [u8]) -> Result<usize> {
+            self.skip_unwanted_rx();
+            let status = self.dma.peek_frame_status();
+            let ext_status = self.peek_rx_ext_status();
+            let fcs = self.fcs_to_verify();
+            let result = self.dma.receive(buffer);
+            match result {
+                Ok(len) => {
+                    let frame = buffer.get(..len).unwrap_or_default();
+                    let seen = RxInspection::new(status.unwrap_or(0), frame, fcs);
+                    self.record_rx_frame(&seen, ext_status).map(|()| len)
+                }
+                Err(e) => Err(self.record_rx_failure(e, status)),
+            }
+        }
+    }
 
-    /// Set the MAC address
-    pub fn set_mac_address(&mut self, addr: &[u8; 6]) {
-        self.mac_addr = *addr;
-        self.config.mac_address = *addr;
-        MacRegs::set_mac_address(addr);
+    /// Body of [`receive_truncating`](Self::receive_truncating), run under
+    /// the ring guard
+    fn receive_frame_truncating(&mut self, buffer: &mut [u8]) -> Result<(usize, usize)> {
+        self.skip_unwanted_rx();
+        let status = self.dma.peek_frame_status();
+        let ext_status = self.peek_rx_ext_status();
+        let fcs = self.fcs_to_verify();
+        match self.dma.receive_truncating(buffer) {
+            Ok((copied, len)) => {
+                let head = buffer.get(..copied).unwrap_or_default();
+                // The FCS covers bytes that were not copied
+                let fcs = if copied == len { fcs } else { None };
+                let mut seen = RxInspection::new(status.unwrap_or(0), head, fcs);
+                seen.len = len;
+                self.record_rx_frame(&seen, ext_status)
+                    .map(|()| (copied, len))
+            }
+            Err(e) => Err(self.record_rx_failure(e, status)),
+        }
     }
 
-    /// Set the link speed
-    ///
-    /// This should be called when link status changes (from PHY).
-    pub fn set_speed(&mut self, speed: Speed) {
-        self.speed = speed;
-        MacRegs::set_speed_100mbps(matches!(speed, Speed::Mbps100));
+    iram! {
+        /// Count a frame the DMA engine refused to deliver
+        fn record_rx_failure(&mut self, error: Error, status: Option<u32>) -> Error {
+            if error == Error::Io(IoError::FrameError) {
+                match status {
+                    Some(status) => self.stats.record_rx_frame_error(status),
+                    None => self.stats.record_rx_error(),
+                }
+            }
+            error
+        }
     }
 
-    /// Set the duplex mode
+    /// Hand the next frame to `f` straight from its RX ring buffer
     ///
-    /// This should be called when link status changes (from PHY).
-    pub fn set_duplex(&mut self, duplex: Duplex) {
-        self.duplex = duplex;
-        MacRegs::set_duplex_full(matches!(duplex, Duplex::Full));
+    /// Returns `None`, without calling `f`, if no frame can be lent in
+    /// place: none is waiting, it has receive errors, it spans more than
+    /// one buffer, or another ring operation is in progress;
+    /// [`receive`](Self::receive) handles those. A delivered frame is
+    /// accounted as by `receive()`, and its descriptor goes back to the
+    /// DMA once `f` returns. A frame failing the software FCS check is
+    /// dropped without calling `f`.
+    #[cfg(feature = "smoltcp")]
+    pub(crate) fn receive_in_place<R>(&mut self, f: impl FnOnce(&[u8]) -> R) -> Option<R> {
+        if !Self::HAS_RX || !self.state.rx_allowed() {
+            return None;
+        }
+        let result = self.with_ring_guard(|emac| emac.receive_frame_in_place(f));
+        self.log_error(result).ok().flatten()
     }
 
-    /// Update link parameters (speed and duplex)
-    pub fn update_link(&mut self, speed: Speed, duplex: Duplex) {
-        self.set_speed(speed);
-        self.set_duplex(duplex);
+    /// Body of [`receive_in_place`](Self::receive_in_place), run under the
+    /// ring guard
+    #[cfg(feature = "smoltcp")]
+    fn receive_frame_in_place<R>(&mut self, f: impl FnOnce(&[u8]) -> R) -> Result<Option<R>> {
+        self.skip_unwanted_rx();
+        let ext_status = self.peek_rx_ext_status();
+        let fcs = self.fcs_to_verify();
+        let (Some(status), Some(frame)) =
+            (self.dma.peek_frame_status(), self.dma.peek_frame_in_place())
+        else {
+            return Ok(None);
+        };
+        let seen = RxInspection::new(status, frame, fcs);
+        let delivered = (seen.fcs.software_ok != Some(false)).then(|| f(frame));
+        self.dma.flush_rx_frame();
+        self.record_rx_frame(&seen, ext_status).map(|()| delivered)
     }
 
-    /// Enable/disable promiscuous mode
-    pub fn set_promiscuous(&mut self, enable: bool) {
-        self.config.promiscuous = enable;
-        MacRegs::set_promiscuous(enable);
+    iram! {
+        /// Drop frames the RX reserve, control frame and LLC settings reject
+        /// from the head of the ring
+        ///
+        /// Ends an expired startup receive-all window first.
+        fn skip_unwanted_rx(&mut self) {
+            self.poll_receive_all_grace();
+            while self.dma.drop_shed_rx_frame()
+                || self.shed_rx_frame()
+                || self.discard_pause_frame()
+                || self.discard_llc_frame()
+            {}
+        }
     }
 
-    /// Enable/disable pass all multicast frames
-    pub fn set_pass_all_multicast(&mut self, enable: bool) {
-        MacRegs::set_pass_all_multicast(enable);
+    /// RDES4 of the next frame, if RX checksum offload is on
+    #[inline(always)]
+    fn peek_rx_ext_status(&self) -> Option<u32> {
+        if self.config.checksum.rx_checksum {
+            self.dma.peek_frame_ext_status()
+        } else {
+            None
+        }
     }
 
-    /// Enable or disable broadcast frame reception.
+    iram! {
+        /// Note a frame taken off the RX ring in the per-frame state and the
+        /// statistics
+        ///
+        /// # Errors
+        /// - `FcsMismatch` - The software FCS check failed
+        fn record_rx_frame(&mut self, seen: &RxInspection, ext_status: Option<u32>) -> Result<()> {
+            #[cfg(feature = "crc-verify")]
+            if let Some(ok) = seen.fcs.software_ok {
+                self.stats.record_fcs_check(ok);
+            }
+            self.last_rx_fcs = Some(seen.fcs);
+            self.last_rx_checksum =
+                ext_status.and_then(|ext| RxChecksumStatus::from_descriptor(seen.status, ext));
+            self.last_rx_format = seen.format;
+            self.last_rx_timestamp = if self.settings.rx_soft_timestamps {
+                self.rx_stamps.take()
+            } else {
+                self.rx_stamps.clear();
+                None
+            };
+            if seen.fcs.software_ok == Some(false) {
+                return Err(IoError::FcsMismatch.into());
+            }
+            self.stats.record_rx(seen.class, seen.len);
+            self.last_rx_tick = Some(self.now());
+            Ok(())
+        }
+
+        /// Run a descriptor ring operation, refusing to start a second one
+        ///
+        /// `&mut self` already rules this out in safe code; the flag catches an
+        /// ISR that reaches the EMAC through its own raw pointer while task
+        /// code is in the middle of a ring operation, or the other way round.
+        fn with_ring_guard<T>(&mut self, op: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+            if self.ring_busy.swap(true, Ordering::Acquire) {
+                return Err(IoError::Reentered.into());
+            }
+            let result = op(self);
+            self.ring_busy.store(false, Ordering::Release);
+            result
+        }
+    }
+
+    /// True while `transmit*()`, `receive()` or `drain_rx_discarding()`
+    /// is working on the descriptor rings
     ///
-    /// When disabled, broadcast frames are filtered out.
-    pub fn set_broadcast_enabled(&mut self, enable: bool) {
-        MacRegs::set_broadcast_enabled(enable);
+    /// The supported way to use the EMAC from both an interrupt handler
+    /// and task code is `SharedEmac`, which serializes all access. Code
+    /// that wires up its own ISR must not run descriptor operations from
+    /// both contexts at once; those operations fail with
+    /// `IoError::Reentered` instead of corrupting the rings if it happens,
+    /// and an ISR can check this first to defer its work.
+    /// [`handle_interrupt`](Self::handle_interrupt) only touches the DMA
+    /// status register and is safe to call at any time.
+    pub fn ring_busy(&self) -> bool {
+        self.ring_busy.load(Ordering::Acquire)
+    }
+
+    iram! {
+        /// Count the next frame if it is a PAUSE frame, and drop it unless
+        /// control frames are delivered
+        ///
+        /// Returns `true` if a frame was dropped.
+        fn discard_pause_frame(&mut self) -> bool {
+            if self.settings.control_frames == ControlFrameFilter::DropAll {
+                return false;
+            }
+            match self.dma.peek_frame_head::<16>() {
+                Some(head) if is_pause_frame(&head) => {
+                    self.stats.record_pause();
+                    if self.settings.control_frames == ControlFrameFilter::DropPause {
+                        self.dma.flush_rx_frame();
+                        return true;
+                    }
+                    false
+                }
+                _ => false,
+            }
+        }
+
+        /// Drop the next frame if it is an IEEE 802.3 frame the LLC filter
+        /// rejects
+        ///
+        /// Returns `true` if a frame was dropped.
+        fn discard_llc_frame(&mut self) -> bool {
+            let filter = self.settings.llc_frames;
+            if filter == LlcFrameFilter::PassAll {
+                return false;
+            }
+            match self.dma.peek_frame_head::<FRAME_FORMAT_HEAD_LEN>() {
+                Some(head) if FrameFormat::parse(&head).is_some_and(|f| !filter.accepts(&f)) => {
+                    self.stats.record_llc_drop();
+                    self.dma.flush_rx_frame();
+                    true
+                }
+                _ => false,
+            }
+        }
+    }
+
+    /// Encapsulation of the last frame returned by
+    /// [`receive`](Self::receive)
+    ///
+    /// `None` until a frame has been received, and for frames too short to
+    /// classify. Dispatch on [`FrameFormat::ether_type`] rather than on
+    /// bytes 12-13, which hold a length in IEEE 802.3 frames.
+    pub fn last_rx_format(&self) -> Option<FrameFormat> {
+        self.last_rx_format
+    }
+
+    /// Software timestamp of the last frame returned by
+    /// [`receive`](Self::receive)
+    ///
+    /// `None` unless [`RuntimeSettings::rx_soft_timestamps`] is on and an RX
+    /// interrupt has been stamped since the EMAC started. See
+    /// [`rx_timestamp`](super::rx_timestamp) for what the stamp means.
+    pub fn last_rx_timestamp(&self) -> Option<SoftRxTimestamp> {
+        self.last_rx_timestamp
+    }
+
+    /// FCS verdict for the last frame returned by [`receive`](Self::receive)
+    ///
+    /// `None` until a frame has been received. Frames the MAC flagged with
+    /// a CRC error are dropped, so `hardware_ok` is normally `true`; the
+    /// software result is only present with `crc-verify` enabled via
+    /// `set_fcs_verify`.
+    pub fn last_rx_fcs(&self) -> Option<RxFcsStatus> {
+        self.last_rx_fcs
+    }
+
+    /// Checksum offload verdict for the last frame returned by
+    /// [`receive`](Self::receive)
+    ///
+    /// `None` while RX checksum offload is disabled, and for frames the MAC
+    /// reported no extended status for. See
+    /// [`RxChecksumStatus::verified`] for whether software can skip its own
+    /// checksum validation.
+    pub fn last_rx_checksum(&self) -> Option<RxChecksumStatus> {
+        self.last_rx_checksum
+    }
+
+    /// True if RX checksum offload is enabled
+    ///
+    /// The MAC then drops frames with a bad IPv4 header, TCP, UDP or ICMP
+    /// checksum before they reach the RX ring. The smoltcp and embassy-net
+    /// adapters then tell the stack to verify no received checksum at all,
+    /// so fragmented datagrams and packets the checksum engine bypassed
+    /// rely on the Ethernet FCS alone.
+    pub fn rx_checksum_offload(&self) -> bool {
+        self.config.checksum.rx_checksum
+    }
+
+    /// Enable/disable the software FCS check of received frames
+    ///
+    /// Every Ethernet II frame handed out by [`receive`](Self::receive) is
+    /// then checked with a table-less CRC-32, which costs roughly 8 cycles
+    /// per bit. Mismatches fail with `FcsMismatch` and are counted in
+    /// [`Statistics::rx_fcs_mismatch`].
+    #[cfg(feature = "crc-verify")]
+    pub fn set_fcs_verify(&mut self, enable: bool) {
+        self.fcs_verify = enable;
+    }
+
+    /// True if the software FCS check is enabled
+    #[cfg(feature = "crc-verify")]
+    pub fn fcs_verify(&self) -> bool {
+        self.fcs_verify
+    }
+
+    /// FCS of the next frame, if it is to be checked in software
+    #[inline(always)]
+    fn fcs_to_verify(&self) -> Option<[u8; 4]> {
+        #[cfg(feature = "crc-verify")]
+        if self.fcs_verify {
+            return self.dma.peek_frame_fcs();
+        }
+        None
+    }
+
+    /// Check if TX is ready (descriptors available)
+    pub fn tx_ready(&self) -> bool {
+        self.dma.tx_available() > 0
+    }
+
+    /// Check if TX can accept a frame of given size
+    pub fn can_transmit(&self, len: usize) -> bool {
+        self.dma.can_transmit(len)
+    }
+
+    // =========================================================================
+    // Link Configuration
+    // =========================================================================
+
+    /// Set the MAC address
+    ///
+    /// # Errors
+    /// - `InvalidMacAddress` - `addr` has the multicast bit set, as
+    ///   [`EmacConfig::validate`] rejects at init; nothing is written
+    pub fn set_mac_address(&mut self, addr: &[u8; 6]) -> Result<()> {
+        if is_multicast_mac(addr) {
+            return Err(ConfigError::InvalidMacAddress.into());
+        }
+        self.mac_addr = *addr;
+        self.config.mac_address = *addr;
+        MacRegs::set_mac_address(addr);
+        Ok(())
+    }
+
+    /// Set the link speed
+    ///
+    /// This should be called when link status changes (from PHY).
+    pub fn set_speed(&mut self, speed: Speed) {
+        if speed != self.speed {
+            self.emit(EventKind::LinkChanged {
+                speed,
+                duplex: self.duplex,
+            });
+        }
+        self.speed = speed;
+        MacRegs::set_speed_100mbps(matches!(speed, Speed::Mbps100));
+    }
+
+    /// Set the duplex mode
+    ///
+    /// This should be called when link status changes (from PHY).
+    pub fn set_duplex(&mut self, duplex: Duplex) {
+        if duplex != self.duplex {
+            self.emit(EventKind::LinkChanged {
+                speed: self.speed,
+                duplex,
+            });
+        }
+        self.duplex = duplex;
+        MacRegs::set_duplex_full(matches!(duplex, Duplex::Full));
+    }
+
+    /// Update link parameters (speed and duplex)
+    pub fn update_link(&mut self, speed: Speed, duplex: Duplex) {
+        self.set_speed(speed);
+        self.set_duplex(duplex);
+    }
+
+    /// Apply a link reported by the PHY: speed, duplex and the resolved
+    /// PAUSE directions
+    ///
+    /// TX flow control follows `tx_pause` and RX flow control `rx_pause`,
+    /// so flow control enabled with
+    /// [`enable_flow_control`](Self::enable_flow_control) follows the
+    /// partner, asymmetric results included, without reading ANLPAR by
+    /// hand.
+    pub fn apply_link_status(&mut self, status: &LinkStatus) {
+        self.update_link(status.speed, status.duplex);
+        self.set_peer_pause(status.tx_pause, status.rx_pause);
+    }
+
+    /// Enable/disable promiscuous mode
+    pub fn set_promiscuous(&mut self, enable: bool) {
+        self.settings.promiscuous = enable;
+        MacRegs::set_promiscuous(enable);
+    }
+
+    /// Enable/disable pass all multicast frames
+    pub fn set_pass_all_multicast(&mut self, enable: bool) {
+        MacRegs::set_pass_all_multicast(enable);
+    }
+
+    /// Enable or disable broadcast frame reception.
+    ///
+    /// When disabled, broadcast frames are filtered out.
+    pub fn set_broadcast_enabled(&mut self, enable: bool) {
+        MacRegs::set_broadcast_enabled(enable);
+    }
+
+    // =========================================================================
+    // Runtime Settings
+    // =========================================================================
+
+    /// Get the live runtime-tunable settings
+    #[inline(always)]
+    pub fn settings(&self) -> RuntimeSettings {
+        self.settings
+    }
+
+    /// Apply new runtime-tunable settings
+    ///
+    /// Reprograms promiscuous mode, flow control and TX interrupt batching
+    /// without a re-init; unchanged values are rewritten harmlessly. Works in
+    /// any initialized state, including while running. Flow control is only
+    /// activated on the wire if the peer advertised PAUSE ability.
+    ///
+    /// # Errors
+    /// - `InvalidState` - EMAC is not initialized
+    /// - `InvalidConfig` - Settings fail [`RuntimeSettings::validate`]
+    pub fn apply_settings(&mut self, settings: RuntimeSettings) -> Result<()> {
+        if self.state == State::Uninitialized {
+            return Err(IoError::InvalidState.into());
+        }
+        settings.validate()?;
+
+        self.settings = settings;
+        MacRegs::update_frame_filter(|ff| {
+            ff.with_promiscuous(settings.promiscuous)
+                .with_pass_control(settings.control_frames.pcf())
+        });
+        self.apply_flow_control(settings.flow_control.enabled);
+        self.dma
+            .set_tx_ic_interval(settings.tx_interrupt_policy.interval());
+        if self.state.tx_allowed() {
+            self.apply_tx_unavailable_interrupt();
+        }
+        Ok(())
+    }
+
+    /// Enable the TX buffer unavailable interrupt only when TX IC is batched
+    fn apply_tx_unavailable_interrupt(&self) {
+        // A drained ring raises TU, so frames sent without IC still
+        // wake TX waiters once the DMA goes idle
+        let int_en = DmaRegs::interrupt_enable();
+        if self.settings.tx_interrupt_policy.is_batched() {
+            DmaRegs::set_interrupt_enable(int_en | DMAINTEN_TUE);
+        } else {
+            DmaRegs::set_interrupt_enable(int_en & !DMAINTEN_TUE);
+        }
     }
 
     // =========================================================================
-    // MDIO / PHY Interface
-    // =========================================================================
+    // MDIO / PHY Interface
+    // =========================================================================
 
     /// Write to a PHY register via MDIO
     ///
@@ -594,6 +1834,10 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
     /// * `phy_addr` - PHY address (0-31)
     /// * `reg` - Register address (0-31)
     /// * `value` - Value to write
+    ///
+    /// # Errors
+    /// - `MiiBusyTimeout` - MII busy bit did not clear within
+    ///   `EmacConfig::mii_busy_timeout_us`
     pub fn write_phy_reg(&self, phy_addr: u8, reg: u8, value: u16) -> Result<()> {
         self.wait_mii_not_busy()?;
 
@@ -621,6 +1865,10 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
     ///
     /// # Returns
     /// The 16-bit register value
+    ///
+    /// # Errors
+    /// - `MiiBusyTimeout` - MII busy bit did not clear within
+    ///   `EmacConfig::mii_busy_timeout_us`
     pub fn read_phy_reg(&self, phy_addr: u8, reg: u8) -> Result<u16> {
         self.wait_mii_not_busy()?;
 
@@ -639,31 +1887,35 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
 
     /// Wait for MII to become not busy
     fn wait_mii_not_busy(&self) -> Result<()> {
-        for _ in 0..MII_BUSY_TIMEOUT {
+        let mut timeout = PollTimeout::start(self.clock, None, self.config.mii_busy_timeout_us);
+        loop {
             if !MacRegs::is_mii_busy() {
                 return Ok(());
             }
-            core::hint::spin_loop();
+            if timeout.expired() {
+                return Err(IoError::MiiBusyTimeout.into());
+            }
         }
-        Err(IoError::PhyError.into())
     }
 
     // =========================================================================
     // Interrupt Handling
     // =========================================================================
 
-    /// Get the current interrupt status
-    ///
-    /// This reads the DMA status register and returns the parsed flags.
-    pub fn interrupt_status(&self) -> InterruptStatus {
-        InterruptStatus::from_raw(DmaRegs::status())
-    }
+    iram! {
+        /// Get the current interrupt status
+        ///
+        /// This reads the DMA status register and returns the parsed flags.
+        pub fn interrupt_status(&self) -> InterruptStatus {
+            InterruptStatus::from_raw(DmaRegs::status())
+        }
 
-    /// Clear interrupt flags
-    ///
-    /// Write-1-to-clear the specified interrupt flags.
-    pub fn clear_interrupts(&self, status: InterruptStatus) {
-        DmaRegs::set_status(status.to_raw());
+        /// Clear interrupt flags
+        ///
+        /// Write-1-to-clear the specified interrupt flags.
+        pub fn clear_interrupts(&self, status: InterruptStatus) {
+            DmaRegs::set_status(status.to_raw());
+        }
     }
 
     /// Clear all pending interrupts
@@ -671,14 +1923,51 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
         DmaRegs::clear_all_interrupts();
     }
 
-    /// Handle interrupt (call from ISR)
-    ///
-    /// Reads and clears interrupt status, returns the status.
-    /// Use this in your interrupt handler to process EMAC events.
-    pub fn handle_interrupt(&self) -> InterruptStatus {
-        let status = self.interrupt_status();
-        self.clear_interrupts(status);
-        status
+    iram! {
+        /// Handle interrupt (call from ISR)
+        ///
+        /// Reads and clears interrupt status, returns the status.
+        /// Use this in your interrupt handler to process EMAC events.
+        ///
+        /// Events that arrive while the status is being acknowledged are
+        /// picked up by re-reading until nothing is pending (at most
+        /// `INTERRUPT_ACK_ROUNDS` times), and NIS/AIS are only cleared once
+        /// their events are, so an event is neither lost nor reported twice.
+        /// The returned status is everything acknowledged by this call.
+        ///
+        /// Only the DMA status register, the clock and the lock-free RX stamp
+        /// slot are accessed, so this may run while task code is inside
+        /// `receive()` or `transmit()`. Receiving or transmitting
+        /// from the ISR as well is only safe through
+        /// `SharedEmac`; see
+        /// [`ring_busy`](Self::ring_busy).
+        ///
+        /// With [`RuntimeSettings::rx_soft_timestamps`] on, an RX complete
+        /// status also stamps the frames it announced; see
+        /// [`stamp_rx_interrupt`](Self::stamp_rx_interrupt).
+        pub fn handle_interrupt(&self) -> InterruptStatus {
+            let status =
+                InterruptStatus::from_raw(DmaRegs::acknowledge_interrupts(INTERRUPT_ACK_ROUNDS));
+            if status.rx_complete {
+                self.stamp_rx_interrupt();
+            }
+            status
+        }
+
+        /// Record the clock time of an RX interrupt (call from ISR)
+        ///
+        /// [`handle_interrupt`](Self::handle_interrupt) does this itself. ISRs
+        /// that acknowledge the interrupt some other way (the async or shared
+        /// handlers) call it when their status shows RX complete. Does nothing
+        /// unless [`RuntimeSettings::rx_soft_timestamps`] is on and a
+        /// [`clock`](Self::set_clock) is installed.
+        pub fn stamp_rx_interrupt(&self) {
+            if self.settings.rx_soft_timestamps
+                && let Some(clock) = self.clock
+            {
+                self.rx_stamps.record(clock.now_us());
+            }
+        }
     }
 
     /// Enable/disable TX complete interrupt
@@ -707,16 +1996,474 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
     // Debug / Statistics
     // =========================================================================
 
-    /// Get the number of available TX descriptors
+    /// Get the number of TX descriptors available to [`transmit`](Self::transmit)
     pub fn tx_descriptors_available(&self) -> usize {
         self.dma.tx_available()
     }
 
+    /// Get the number of free TX descriptors, reserved ones included
+    pub fn tx_descriptors_free(&self) -> usize {
+        self.dma.tx_free()
+    }
+
     /// Get the number of complete RX frames waiting
     pub fn rx_frames_waiting(&self) -> usize {
         self.dma.rx_frame_count()
     }
 
+    /// Get the number of RX descriptors still free for the DMA to fill
+    ///
+    /// A low value means received frames are piling up faster than they are
+    /// read; callers about to block (flash writes, long computations) can
+    /// drain the ring first to avoid overflow drops.
+    pub fn rx_descriptors_free(&self) -> usize {
+        self.dma.rx_free_count()
+    }
+
+    /// True if every RX descriptor is owned by the DMA, i.e. the ring is
+    /// empty and ready to take a full burst
+    ///
+    /// Useful right before starting DHCP, whose offers tend to arrive
+    /// back to back.
+    pub fn rx_ring_prefilled(&self) -> bool {
+        self.dma.rx_free_count() == self.dma.rx_slots()
+    }
+
+    iram! {
+        /// RX descriptors held by waiting unicast and other frames
+        ///
+        /// Shows how close broadcast and multicast traffic is to the unicast
+        /// reserve set with
+        /// [`EmacConfig::with_rx_unicast_reserve`](crate::EmacConfig::with_rx_unicast_reserve).
+        pub fn rx_occupancy(&self) -> RxOccupancy {
+            let (unicast, other, shed) = self.dma.rx_occupancy(is_own_unicast);
+            RxOccupancy {
+                unicast,
+                other,
+                shed,
+                free: self.dma.rx_slots().saturating_sub(unicast + other + shed),
+                reserve: self.config.rx_unicast_reserve,
+            }
+        }
+    }
+
+    /// Shed the oldest broadcast, multicast and promiscuous frames while
+    /// they eat into the unicast reserve
+    ///
+    /// A shed frame is never handed out, wherever it sits in the ring. The
+    /// DMA reuses descriptors in ring order, so one queued behind a unicast
+    /// frame frees its descriptors once `receive()` has taken the frames
+    /// ahead of it. `receive()` sheds before every frame; call this from
+    /// the RX interrupt path as well so a flood is trimmed while the
+    /// application is busy. Shed frames are counted in
+    /// [`Statistics::rx_shed`]. Returns the number of frames shed; 0
+    /// without a reserve.
+    ///
+    /// # Errors
+    /// - `Reentered` - Called while another descriptor ring operation was
+    ///   in progress; nothing was dropped
+    pub fn rebalance_rx(&mut self) -> Result<usize> {
+        let result = self.with_ring_guard(|emac| {
+            let mut shed = 0;
+            while emac.shed_rx_frame() {
+                shed += 1;
+            }
+            Ok(shed)
+        });
+        self.log_error(result)
+    }
+
+    iram! {
+        /// Shed the oldest frame that is not our unicast if other frames are
+        /// over their share of the ring
+        ///
+        /// Only frames completed since the last call are classified, so this
+        /// costs little per received frame. Returns `true` if a frame was shed.
+        fn shed_rx_frame(&mut self) -> bool {
+            if self.config.rx_unicast_reserve == 0 {
+                return false;
+            }
+            self.dma.tally_rx(is_own_unicast);
+            if !self.rx_occupancy().over_budget() || !self.dma.shed_rx_frame() {
+                return false;
+            }
+            self.stats.record_rx_shed();
+            true
+        }
+    }
+
+    /// Discard up to `max_frames` frames waiting in the RX ring
+    ///
+    /// Frames buffered during bring-up (stale ARP, broadcast chatter, an
+    /// earlier DHCP exchange) can confuse a stack that is only now
+    /// starting; drop them instead of handing them up. Discarded frames
+    /// are not counted in [`Statistics`]. A frame the DMA is still writing
+    /// is left alone. Returns the number of frames discarded.
+    ///
+    /// # Errors
+    /// - `Reentered` - Called while another descriptor ring operation was
+    ///   in progress; nothing was discarded
+    pub fn drain_rx_discarding(&mut self, max_frames: usize) -> Result<usize> {
+        let result = self.with_ring_guard(|emac| {
+            let mut drained = 0;
+            while drained < max_frames && emac.dma.peek_frame_status().is_some() {
+                emac.dma.flush_rx_frame();
+                drained += 1;
+            }
+            Ok(drained)
+        });
+        self.log_error(result)
+    }
+
+    /// Change how many RX and TX buffers the rings use, from the next `start()`
+    ///
+    /// Counts are in buffers, like the `RX_BUFS`/`TX_BUFS` parameters (in
+    /// dual-buffer mode two buffers form one descriptor). Shrinking the RX
+    /// ring lowers how much the DMA can buffer, e.g. to keep an OTA update's
+    /// working set small, and restoring the full size later brings back full
+    /// throughput in the same binary. Frames still in the rings are dropped
+    /// when the new size is applied.
+    ///
+    /// # Errors
+    /// - `InvalidState` - EMAC is running; call `stop()` first
+    /// - `InvalidConfig` - A count is zero, above the const-generic size, or
+    ///   below two with dual-buffer descriptors; `rx` must be 0 when
+    ///   `RX_BUFS` is; or a ring leaves no descriptor outside the reserves
+    ///   (see [`EmacConfig::validate_rings`](crate::EmacConfig::validate_rings))
+    pub fn set_active_ring_sizes(&mut self, rx: usize, tx: usize) -> Result<()> {
+        if self.state.is_active() {
+            return Err(IoError::InvalidState.into());
+        }
+        let dual = self.config.dma_dual_buffer;
+        if !DmaEngine::<RX_BUFS, TX_BUFS, BUF_SIZE>::ring_sizes_valid(rx, tx, dual) {
+            return Err(ConfigError::InvalidConfig.into());
+        }
+        self.config.validate_rings(rx, tx)?;
+        self.pending_ring_sizes = Some((rx, tx));
+        Ok(())
+    }
+
+    /// RX and TX buffer counts in use, including a change still pending
+    /// until the next `start()`
+    pub fn active_ring_sizes(&self) -> (usize, usize) {
+        self.pending_ring_sizes
+            .unwrap_or((self.dma.rx_active_buffers(), self.dma.tx_active_buffers()))
+    }
+
+    /// Recent driver events (state changes, errors, link changes)
+    ///
+    /// Routine conditions (TX ring full, no frame pending, calls while
+    /// stopped) are not logged, so the log keeps the interesting history.
+    pub fn event_log(&self) -> &EventLog {
+        &self.events
+    }
+
+    /// Frame counters since `new()` or the last [`reset_stats`](Self::reset_stats)
+    pub fn stats(&self) -> &Statistics {
+        &self.stats
+    }
+
+    /// Zero all frame counters
+    pub fn reset_stats(&mut self) {
+        self.stats = Statistics::new();
+        if let Some(detector) = self.duplex_detector.as_mut() {
+            detector.reset();
+        }
+    }
+
+    /// Collect TX carrier and collision errors into
+    /// [`Statistics::tx_diagnostics`]
+    ///
+    /// Happens automatically whenever a frame is queued; call this to see
+    /// the errors of the last frames sent before the link went idle. With
+    /// `frame-trace` this also completes entries in `frame_trace()`. Does
+    /// nothing while another descriptor ring operation is in progress.
+    pub fn collect_tx_status(&mut self) {
+        let _ = self.with_ring_guard(|emac| {
+            emac.collect_tx_completions();
+            Ok(())
+        });
+    }
+
+    /// Add the hardware missed frame counter to
+    /// [`Statistics::rx_missed`] and [`Statistics::rx_fifo_overflows`]
+    ///
+    /// The register clears on read, so call this often enough that its
+    /// 16-bit (missed) and 11-bit (overflow) counters don't saturate.
+    /// [`check_thresholds`](Self::check_thresholds) calls it.
+    pub fn collect_missed_frames(&mut self) {
+        self.stats.record_missed_frames(DmaRegs::missed_frames());
+    }
+
+    /// Recent transmit attempts with their descriptors and outcome
+    ///
+    /// Completions are picked up whenever a frame is queued; call
+    /// [`collect_tx_status`](Self::collect_tx_status) first to include
+    /// frames sent since the last `transmit*()`.
+    #[cfg(feature = "frame-trace")]
+    pub fn frame_trace(&self) -> &FrameTraceLog {
+        &self.frame_trace
+    }
+
+    /// Drop all frame trace entries
+    #[cfg(feature = "frame-trace")]
+    pub fn clear_frame_trace(&mut self) {
+        self.frame_trace.clear();
+    }
+
+    /// Set the timestamp source for the event log
+    ///
+    /// Typically milliseconds since boot. `None` stamps events with the
+    /// [`clock`](Self::set_clock) in milliseconds, or `0` without one.
+    /// [`liveness`](Self::liveness) uses the same clock.
+    pub fn set_event_clock(&mut self, clock: Option<fn() -> u32>) {
+        self.events.set_clock(clock);
+    }
+
+    /// Install a monotonic microsecond clock
+    ///
+    /// Register polls (TX stop, FIFO flush, MII busy) are then bounded by
+    /// time rather than by iteration count. Without an event clock, the
+    /// event log, [`liveness`](Self::liveness) and activity ticks use this
+    /// clock in milliseconds.
+    pub fn set_clock(&mut self, clock: Option<&'static dyn Clock>) {
+        self.clock = clock;
+    }
+
+    /// The installed clock, for code that shares the driver's time base
+    pub fn clock(&self) -> Option<&'static dyn Clock> {
+        self.clock
+    }
+
+    /// Route the peripheral clock through a power manager's gate
+    ///
+    /// Install before [`init`](Self::init). With `None` (the default) the
+    /// driver writes the DPORT clock enable bit itself, like
+    /// [`DportClockGate`](crate::hal::DportClockGate).
+    pub fn set_clock_gate(&mut self, gate: Option<&'static dyn PeripheralClockGate>) {
+        self.clock_gate = gate;
+    }
+
+    /// Install a CPU cycle counter to time critical-section holds
+    ///
+    /// The shared wrappers (`SharedEmac`, `AsyncSharedEmac`) and the
+    /// embassy-net integration then record how long each of their critical
+    /// sections runs, in [`Statistics::cs_holds`] and
+    /// [`Statistics::cs_hold_max_cycles`]. On the ESP32 pass the CCOUNT
+    /// reader, e.g. `xtensa_lx::timer::get_cycle_count`; at 240 MHz one
+    /// cycle is 4.2 ns. `None` (the default) measures nothing.
+    pub fn set_cycle_counter(&mut self, counter: Option<fn() -> u32>) {
+        self.cycle_counter = counter;
+    }
+
+    /// Run `f` as one critical-section hold, timed with the cycle counter
+    ///
+    /// Called by the wrappers from inside their critical section, so the
+    /// measurement covers the driver's work but not waiting for the lock.
+    #[cfg(feature = "critical-section")]
+    #[inline]
+    pub(crate) fn timed_hold<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
+        let Some(counter) = self.cycle_counter else {
+            return f(self);
+        };
+        let start = counter();
+        let result = f(self);
+        self.stats.record_cs_hold(counter().wrapping_sub(start));
+        result
+    }
+
+    /// Turn the peripheral clock on through the installed gate
+    fn enable_peripheral_clock(&self) {
+        match self.clock_gate {
+            Some(gate) => gate.enable(),
+            None => ExtRegs::enable_peripheral_clock(),
+        }
+    }
+
+    /// Turn the peripheral clock off through the installed gate
+    fn disable_peripheral_clock(&self) {
+        match self.clock_gate {
+            Some(gate) => gate.disable(),
+            None => ExtRegs::disable_peripheral_clock(),
+        }
+    }
+
+    iram! {
+        /// Timestamp for events and activity: the event clock if set, else the
+        /// clock in milliseconds, else 0
+        pub(crate) fn now(&self) -> u32 {
+            match self.clock {
+                Some(clock) if !self.events.has_clock() => clock.now_ms(),
+                _ => self.events.now(),
+            }
+        }
+    }
+
+    /// Send the configured keepalive if nothing was transmitted for its
+    /// interval
+    ///
+    /// Call periodically, e.g. from the main loop; see
+    /// [`keepalive`](super::keepalive). Returns `Ok(true)` if a keepalive
+    /// was queued, `Ok(false)` if none is configured, none is due, or the
+    /// EMAC cannot transmit right now.
+    ///
+    /// # Errors
+    /// Those of [`transmit`](Self::transmit) other than `InvalidState`.
+    pub fn poll_keepalive(&mut self) -> Result<bool> {
+        let Some(keepalive) = self.settings.keepalive else {
+            return Ok(false);
+        };
+        if !self.state.tx_allowed() || !keepalive.is_due(self.last_tx_tick, self.now()) {
+            return Ok(false);
+        }
+        let frame = keepalive.build(&self.mac_addr);
+        self.transmit(&frame)?;
+        self.stats.record_keepalive();
+        Ok(true)
+    }
+
+    /// Current transmit and receive DMA process states
+    ///
+    /// Decoded from the DMA status register. A receive process stuck in
+    /// `Suspended` with descriptors free, or a process in `Stopped` while
+    /// the EMAC is running, points at a stalled ring; see also
+    /// [`liveness`](Self::liveness).
+    pub fn dma_states(&self) -> (TxProcessState, RxProcessState) {
+        let status = DmaRegs::status();
+        (
+            TxProcessState::from_status(status),
+            RxProcessState::from_status(status),
+        )
+    }
+
+    /// RX/TX activity and DMA process states, for a system watchdog
+    ///
+    /// Feed the watchdog only while
+    /// [`LivenessReport::fault`](super::liveness::LivenessReport::fault)
+    /// finds nothing, so a hung DMA or stalled receiver ends in a reset
+    /// instead of a silently dead interface.
+    pub fn liveness(&self) -> LivenessReport {
+        let status = DmaRegs::status();
+        LivenessReport {
+            now: self.now(),
+            last_rx: self.last_rx_tick,
+            last_tx: self.last_tx_tick,
+            rx_state: RxProcessState::from_status(status),
+            tx_state: TxProcessState::from_status(status),
+            fatal_bus_error: status & DMASTATUS_FBI != 0,
+            rx_enabled: self.state.rx_allowed() && self.rx_enabled,
+            tx_enabled: self.state.tx_allowed(),
+            rx_descriptors_free: self.dma.rx_free_count(),
+            tx_in_flight: self.dma.tx_slots() - self.dma.tx_free(),
+        }
+    }
+
+    /// Add an application event to the log
+    ///
+    /// Use this for conditions the driver cannot see itself, such as
+    /// [`EventKind::LinkDown`] from a PHY supervisor or
+    /// [`EventKind::InterruptError`] from an interrupt handler.
+    pub fn record_event(&mut self, kind: EventKind) {
+        self.emit(kind);
+    }
+
+    /// Log an event and pass it to the event sink
+    fn emit(&mut self, kind: EventKind) {
+        let event = self.events.record_at(kind, self.now());
+        if let Some(sink) = self.event_sink {
+            sink.on_event(&event);
+        }
+    }
+
+    /// Install or remove the observer that receives every logged event
+    ///
+    /// See [`EmacEventSink`]. The sink must live for the rest of the
+    /// program, like the EMAC itself usually does.
+    pub fn set_event_sink(&mut self, sink: Option<&'static dyn EmacEventSink>) {
+        self.event_sink = sink;
+    }
+
+    /// Set the levels [`check_thresholds`](Self::check_thresholds) watches
+    ///
+    /// Forgets earlier crossings and the error baseline.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConfigError::InvalidConfig`] and keeps the current
+    /// thresholds if [`EventThresholds::validate`] rejects `thresholds`.
+    pub fn set_event_thresholds(&mut self, thresholds: EventThresholds) -> Result<()> {
+        thresholds.validate()?;
+        self.thresholds.set_thresholds(thresholds);
+        Ok(())
+    }
+
+    /// Sample ring occupancy and the error count, and log an
+    /// [`EventKind::Threshold`] for each metric that crossed its level
+    ///
+    /// Call periodically; the error rate is the number of errors since the
+    /// previous call. Crossings in either direction are reported once.
+    /// Configured [`RateAlarm`](super::event_log::RateAlarm)s are checked against the counters and the
+    /// event clock, logging an [`EventKind::Alarm`] for each that fired.
+    pub fn check_thresholds(&mut self) {
+        self.collect_tx_status();
+        self.collect_missed_frames();
+        let rx_occupancy = self.dma.rx_slots() - self.dma.rx_free_count();
+        let tx_occupancy = self.dma.tx_slots() - self.dma.tx_free();
+        let stats = &self.stats;
+        let errors = stats
+            .rx_errors
+            .wrapping_add(stats.rx_fcs_mismatch)
+            .wrapping_add(stats.tx_diagnostics.total());
+        let crossings = self
+            .thresholds
+            .evaluate(rx_occupancy as u32, tx_occupancy as u32, errors);
+        for kind in crossings.into_iter().flatten() {
+            self.emit(kind);
+        }
+        let now = self.now();
+        let alarms = self.thresholds.evaluate_alarms(&self.stats, now);
+        for kind in alarms.into_iter().flatten() {
+            self.emit(kind);
+        }
+    }
+
+    /// Install or remove the duplex mismatch heuristic
+    ///
+    /// See [`DuplexMismatchDetector`]. Detecting a half-duplex partner
+    /// also needs `EmacConfig::forward_error_frames`.
+    pub fn set_duplex_mismatch_detector(&mut self, detector: Option<DuplexMismatchDetector>) {
+        self.duplex_detector = detector;
+    }
+
+    /// Run the duplex mismatch heuristic on the counters since the last call
+    ///
+    /// Collects pending TX status first. A newly detected mismatch is
+    /// recorded as [`EventKind::DuplexMismatch`], logged as a warning and
+    /// returned. Returns `None` without a detector installed.
+    pub fn check_duplex_mismatch(&mut self) -> Option<DuplexMismatch> {
+        self.collect_tx_status();
+        let duplex = self.duplex;
+        let mismatch = self.duplex_detector.as_mut()?.check(&self.stats, duplex)?;
+        self.emit(EventKind::DuplexMismatch(mismatch));
+        #[cfg(feature = "defmt")]
+        defmt::warn!("{}: {}", mismatch, mismatch.remediation());
+        #[cfg(feature = "log")]
+        log::warn!("{mismatch}: {}", mismatch.remediation());
+        Some(mismatch)
+    }
+
+    /// Whether this instance has an RX ring (`RX_BUFS > 0`)
+    pub const HAS_RX: bool = RX_BUFS > 0;
+
+    /// Bytes of each buffer usable by the DMA.
+    ///
+    /// This is `BUF_SIZE` rounded down to a multiple of 4 and capped at
+    /// 8188, the largest word-aligned value the descriptor size fields hold.
+    #[must_use]
+    pub const fn buffer_size() -> usize {
+        DmaEngine::<RX_BUFS, TX_BUFS, BUF_SIZE>::DESC_BUF_SIZE
+    }
+
     /// Get total memory usage of this EMAC instance
     pub const fn memory_usage() -> usize {
         DmaEngine::<RX_BUFS, TX_BUFS, BUF_SIZE>::memory_usage()
@@ -725,6 +2472,7 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
             + 6 // mac_addr
             + core::mem::size_of::<Speed>()
             + core::mem::size_of::<Duplex>()
+            + core::mem::size_of::<EventLog>()
     }
 }
 
@@ -748,6 +2496,103 @@ unsafe impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize> S
 {
 }
 
+// =============================================================================
+// Test Hooks
+// =============================================================================
+
+#[cfg(feature = "test-hooks")]
+#[cfg_attr(docsrs, doc(cfg(feature = "test-hooks")))]
+impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
+    Emac<RX_BUFS, TX_BUFS, BUF_SIZE>
+{
+    /// Place `frame` in the RX ring as if it had been received
+    ///
+    /// `frame` runs from the destination MAC to the end of the payload,
+    /// without FCS. The next [`receive`](Self::receive) (or stack poll)
+    /// returns it like any other frame, so protocol handling above the
+    /// driver can be tested deterministically without a traffic generator.
+    /// The descriptor status is error-free and flags Ethernet II frames
+    /// (EtherType 0x0600 and up) as such; with `crc-verify` a correct FCS is
+    /// appended.
+    ///
+    /// The receive DMA may be filling the same slot, so injection is
+    /// refused while the receiver is enabled: start with
+    /// `EmacConfig::with_deferred_rx` and inject before
+    /// [`enable_rx`](Self::enable_rx).
+    ///
+    /// # Errors
+    /// - `InvalidState` - EMAC not initialized, or receiver enabled
+    /// - `InvalidLength` - `frame` is shorter than an Ethernet header
+    /// - `FrameTooLarge` - `frame` and FCS do not fit one RX slot
+    /// - `NoDescriptorsAvailable` - Every RX descriptor holds an unread frame
+    /// - `NoRxBuffers` - There is no RX ring (`RX_BUFS = 0`)
+    /// - `Reentered` - Called while another descriptor ring operation was
+    ///   in progress
+    pub fn inject_rx_frame(&mut self, frame: &[u8]) -> Result<()> {
+        use crate::internal::constants::{CRC_SIZE, ETH_HEADER_SIZE};
+        use crate::internal::dma::bits::rdes0;
+
+        if !Self::HAS_RX {
+            return Err(DmaError::NoRxBuffers.into());
+        }
+        if self.state == State::Uninitialized || self.rx_enabled {
+            return Err(IoError::InvalidState.into());
+        }
+        if frame.len() < ETH_HEADER_SIZE {
+            return Err(DmaError::InvalidLength.into());
+        }
+        if frame.len() + CRC_SIZE > self.dma.slot_capacity() {
+            return Err(DmaError::FrameTooLarge.into());
+        }
+
+        let ethertype = u16::from_be_bytes([frame[12], frame[13]]);
+        let status = if ethertype >= 0x0600 {
+            rdes0::FRAME_TYPE
+        } else {
+            0
+        };
+        #[cfg(feature = "crc-verify")]
+        let fcs = super::fcs::crc32(frame).to_le_bytes();
+        #[cfg(not(feature = "crc-verify"))]
+        let fcs = [0; 4];
+
+        self.with_ring_guard(|emac| {
+            if emac.dma.inject_rx_frame(frame, fcs, status) {
+                Ok(())
+            } else {
+                Err(DmaError::NoDescriptorsAvailable.into())
+            }
+        })
+    }
+}
+
+// =============================================================================
+// Host Simulation
+// =============================================================================
+
+#[cfg(test)]
+impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
+    Emac<RX_BUFS, TX_BUFS, BUF_SIZE>
+{
+    /// Reach `Initialized` with `config` without the hardware bring-up,
+    /// which cannot complete against the simulated register file
+    pub(crate) fn sim_init(&mut self, config: EmacConfig) {
+        self.dma.set_dual_buffer(config.dma_dual_buffer);
+        self.dma.set_tx_reserved(config.tx_reserved_descriptors);
+        self.settings = config.runtime_settings();
+        self.config = config;
+        self.dma.init();
+        self.apply_filter_config();
+        self.set_state(State::Initialized);
+    }
+
+    /// The DMA engine, for tests outside the driver module
+    #[cfg(any(feature = "smoltcp", feature = "async"))]
+    pub(crate) fn sim_dma(&mut self) -> &mut DmaEngine<RX_BUFS, TX_BUFS, BUF_SIZE> {
+        &mut self.dma
+    }
+}
+
 // =============================================================================
 // Type Aliases
 // =============================================================================
@@ -760,3 +2605,1146 @@ pub type EmacSmall = Emac<4, 4, 1600>;
 
 /// Large EMAC configuration for high-throughput applications
 pub type EmacLarge = Emac<16, 16, 1600>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::internal::register::dma::{DMAOPERATION_OFFSET, DMAOPERATION_SR, DMAOPERATION_ST};
+    use crate::internal::register::mac::{
+        GMACCONFIG_OFFSET, GMACCONFIG_RE, GMACCONFIG_TE, GMACFF_OFFSET, GMACFF_PCF_MASK, pcf,
+    };
+    use crate::internal::register::{DMA_BASE, MAC_BASE, sim};
+
+    fn rx_running() -> bool {
+        sim::read(MAC_BASE + GMACCONFIG_OFFSET) & GMACCONFIG_RE != 0
+            && sim::read(DMA_BASE + DMAOPERATION_OFFSET) & DMAOPERATION_SR != 0
+    }
+
+    #[test]
+    fn start_enables_rx_by_default() {
+        sim::reset();
+        let mut emac: Emac<4, 4, 256> = Emac::new();
+        emac.sim_init(EmacConfig::new());
+        emac.start().unwrap();
+        assert!(emac.is_rx_enabled());
+        assert!(rx_running());
+    }
+
+    #[test]
+    fn deferred_rx_waits_for_enable_rx() {
+        sim::reset();
+        let mut emac: Emac<4, 4, 256> = Emac::new();
+        assert_eq!(emac.enable_rx(), Err(IoError::InvalidState.into()));
+
+        emac.sim_init(EmacConfig::new().with_deferred_rx(true));
+        emac.start().unwrap();
+        assert_eq!(emac.state(), State::Running);
+        assert!(!emac.is_rx_enabled());
+        assert!(!rx_running());
+
+        emac.enable_rx().unwrap();
+        assert!(emac.is_rx_enabled());
+        assert!(rx_running());
+        emac.enable_rx().unwrap();
+    }
+
+    fn tx_running() -> bool {
+        sim::read(MAC_BASE + GMACCONFIG_OFFSET) & GMACCONFIG_TE != 0
+            && sim::read(DMA_BASE + DMAOPERATION_OFFSET) & DMAOPERATION_ST != 0
+    }
+
+    #[test]
+    fn rx_only_never_transmits() {
+        sim::reset();
+        let mut emac: Emac<4, 4, 256> = Emac::new();
+        assert_eq!(emac.enable_rx_only(), Err(IoError::InvalidState.into()));
+
+        emac.sim_init(EmacConfig::new());
+        emac.enable_rx_only().unwrap();
+        assert_eq!(emac.state(), State::RxOnly);
+        assert!(rx_running());
+        assert!(!tx_running());
+        assert_eq!(
+            emac.transmit(&[0x5A; 60]),
+            Err(IoError::InvalidState.into())
+        );
+        assert_eq!(
+            emac.transmit_reserved(&[0x5A; 60]),
+            Err(IoError::InvalidState.into())
+        );
+
+        assert!(emac.dma.sim_rx_frame(&[0xFF; 60], 0));
+        let mut buf = [0u8; 256];
+        assert_eq!(emac.receive(&mut buf), Ok(60));
+
+        // Switching to full duplex traffic needs a stop first
+        assert_eq!(emac.start(), Err(IoError::InvalidState.into()));
+        assert_eq!(emac.enable_tx_only(), Err(IoError::InvalidState.into()));
+        emac.enable_rx_only().unwrap();
+    }
+
+    #[test]
+    fn borrowed_tx_done_after_dma_release() {
+        #[repr(align(4))]
+        struct Aligned([u8; 128]);
+        static FRAME: Aligned = Aligned([0xA5; 128]);
+
+        sim::reset();
+        let mut emac: Emac<4, 4, 256> = Emac::new();
+        emac.sim_init(EmacConfig::new());
+        emac.dma.sim_dma_capable_anywhere();
+        assert_eq!(
+            emac.transmit_borrowed(&FRAME.0),
+            Err(IoError::InvalidState.into())
+        );
+        emac.start().unwrap();
+
+        let tx = emac.transmit_borrowed(&FRAME.0).unwrap();
+        assert_eq!(tx.len(), 128);
+        assert!(!emac.borrowed_tx_done(&tx));
+        emac.dma.sim_tx_complete();
+        assert!(emac.borrowed_tx_done(&tx));
+    }
+
+    #[test]
+    fn keepalive_sent_only_after_tx_silence() {
+        use super::super::keepalive::Keepalive;
+        use core::sync::atomic::{AtomicU32, Ordering};
+
+        static NOW: AtomicU32 = AtomicU32::new(0);
+        fn now() -> u32 {
+            NOW.load(Ordering::Relaxed)
+        }
+
+        sim::reset();
+        let mut emac: Emac<4, 4, 256> = Emac::new();
+        emac.sim_init(EmacConfig::new().with_keepalive(Some(Keepalive::new(1000))));
+        emac.set_event_clock(Some(now));
+        assert_eq!(emac.poll_keepalive(), Ok(false));
+        emac.start().unwrap();
+
+        // Nothing sent yet: announce right away
+        assert_eq!(emac.poll_keepalive(), Ok(true));
+        assert_eq!(emac.dma.tx_buffer(0)[12..14], [0x88, 0xB5]);
+        assert_eq!(emac.poll_keepalive(), Ok(false));
+
+        // Other traffic restarts the interval
+        NOW.store(900, Ordering::Relaxed);
+        emac.transmit(&[0u8; 60]).unwrap();
+        NOW.store(1500, Ordering::Relaxed);
+        assert_eq!(emac.poll_keepalive(), Ok(false));
+        NOW.store(1900, Ordering::Relaxed);
+        assert_eq!(emac.poll_keepalive(), Ok(true));
+        assert_eq!(emac.stats().tx_keepalives, 2);
+
+        emac.apply_settings(emac.settings().with_keepalive(None))
+            .unwrap();
+        NOW.store(5000, Ordering::Relaxed);
+        assert_eq!(emac.poll_keepalive(), Ok(false));
+    }
+
+    #[test]
+    fn batch_receive_fills_buffers_with_one_poll_demand() {
+        use crate::internal::dma::bits::rdes0;
+        use crate::internal::register::dma::DMARXPOLLDEMAND_OFFSET;
+
+        sim::reset();
+        let mut emac: Emac<6, 4, 256> = Emac::new();
+        emac.sim_init(EmacConfig::new());
+        emac.start().unwrap();
+
+        emac.dma.sim_rx_frame(&[0x11; 60], 0);
+        emac.dma
+            .sim_rx_frame(&[0x22; 60], rdes0::ERR_SUMMARY | rdes0::CRC_ERR);
+        emac.dma.sim_rx_frame(&[0x33; 200], 0);
+        emac.dma.sim_rx_frame(&[0x44; 80], 0);
+        emac.dma.sim_rx_frame(&[0x55; 64], 0);
+
+        // The errored frame is dropped, the 200-byte one does not fit
+        let mut pool = [const { FrameBuf::<128>::new() }; 2];
+        let polls = sim::write_count(DMA_BASE + DMARXPOLLDEMAND_OFFSET);
+        assert_eq!(emac.receive_batch(&mut pool), 2);
+        assert_eq!(
+            sim::write_count(DMA_BASE + DMARXPOLLDEMAND_OFFSET),
+            polls + 1
+        );
+        assert_eq!(pool[0].as_slice(), [0x11; 60]);
+        assert_eq!(pool[1].as_slice(), [0x44; 80]);
+        assert_eq!(emac.stats().rx_frames, 2);
+
+        // The rest, and then an empty ring
+        assert_eq!(emac.receive_batch(&mut pool), 1);
+        assert_eq!(pool[0].as_slice(), [0x55; 64]);
+        assert_eq!(emac.receive_batch(&mut pool), 0);
+        assert!(!emac.rx_available());
+    }
+
+    #[test]
+    fn truncating_receive_keeps_oversized_frame_head() {
+        sim::reset();
+        let mut emac: Emac<4, 4, 256> = Emac::new();
+        emac.sim_init(EmacConfig::new());
+        emac.start().unwrap();
+
+        let frame: [u8; 200] = core::array::from_fn(|i| i as u8);
+        let mut head = [0u8; 64];
+        emac.dma.sim_rx_frame(&frame, 0);
+        assert_eq!(emac.receive(&mut head), Err(IoError::BufferTooSmall.into()));
+
+        emac.dma.sim_rx_frame(&frame, 0);
+        assert_eq!(emac.receive_truncating(&mut head), Ok((64, 200)));
+        assert_eq!(head, frame[..64]);
+        assert_eq!(emac.stats().rx_frames, 1);
+        assert_eq!(emac.stats().rx_bytes, 200);
+        assert_eq!(
+            emac.receive_truncating(&mut head),
+            Err(IoError::IncompleteFrame.into())
+        );
+    }
+
+    #[test]
+    fn tx_only_leaves_receiver_off() {
+        sim::reset();
+        let mut emac: Emac<4, 4, 256> = Emac::new();
+        emac.sim_init(EmacConfig::new());
+        emac.enable_tx_only().unwrap();
+        assert_eq!(emac.state(), State::TxOnly);
+        assert!(tx_running());
+        assert!(!rx_running());
+        assert!(!emac.is_rx_enabled());
+        assert_eq!(emac.transmit(&[0x5A; 60]), Ok(60));
+
+        let mut buf = [0u8; 256];
+        assert_eq!(emac.receive(&mut buf), Err(IoError::InvalidState.into()));
+        assert_eq!(emac.enable_rx(), Err(IoError::InvalidState.into()));
+    }
+
+    #[test]
+    fn ring_guard_rejects_reentry() {
+        sim::reset();
+        let mut emac: Emac<4, 4, 256> = Emac::new();
+        emac.sim_init(EmacConfig::new());
+        emac.start().unwrap();
+        assert!(emac.dma.sim_rx_frame(&[0xFF; 60], 0));
+
+        // As seen by an ISR that interrupted a ring operation
+        emac.ring_busy.store(true, Ordering::Relaxed);
+        assert!(emac.ring_busy());
+        let mut buf = [0u8; 256];
+        let reentered = Error::from(IoError::Reentered);
+        assert_eq!(emac.receive(&mut buf), Err(reentered));
+        assert_eq!(emac.transmit(&[0x5A; 60]), Err(reentered));
+        assert_eq!(emac.drain_rx_discarding(4), Err(reentered));
+        assert_eq!(emac.rebalance_rx(), Err(reentered));
+        assert!(emac.ring_busy());
+
+        emac.ring_busy.store(false, Ordering::Relaxed);
+        assert_eq!(emac.receive(&mut buf), Ok(60));
+        assert_eq!(emac.transmit(&[0x5A; 60]), Ok(60));
+        assert!(!emac.ring_busy());
+    }
+
+    #[test]
+    fn tx_carrier_errors_reach_stats_once() {
+        use crate::driver::stats::TxErrorKind;
+        use crate::internal::dma::bits::tdes0;
+
+        sim::reset();
+        let mut emac: Emac<4, 4, 256> = Emac::new();
+        emac.sim_init(EmacConfig::new());
+        emac.start().unwrap();
+
+        assert_eq!(emac.transmit(&[0x5A; 60]), Ok(60));
+        emac.dma
+            .sim_tx_complete_with_errors(tdes0::LATE_COLLISION | tdes0::UNDERFLOW_ERR);
+        assert_eq!(emac.stats().tx_diagnostics.total(), 0);
+
+        // Collected when the next frame is queued, and only once
+        assert_eq!(emac.transmit(&[0x5A; 60]), Ok(60));
+        emac.dma.sim_tx_complete_with_errors(tdes0::NO_CARRIER);
+        emac.collect_tx_status();
+        emac.collect_tx_status();
+        assert_eq!(emac.transmit(&[0x5A; 60]), Ok(60));
+
+        let diag = emac.stats().tx_diagnostics;
+        assert_eq!(diag.late_collisions, 1);
+        assert_eq!(diag.no_carrier, 1);
+        assert_eq!(diag.total(), 2);
+        assert_eq!(diag.last_error, Some(TxErrorKind::NoCarrier));
+    }
+
+    #[cfg(feature = "frame-trace")]
+    #[test]
+    fn frame_trace_follows_tx_lifecycle() {
+        use crate::driver::frame_trace::TraceOutcome;
+        use crate::internal::dma::bits::tdes0;
+
+        sim::reset();
+        let mut emac: Emac<4, 4, 256> = Emac::new();
+        emac.sim_init(EmacConfig::new());
+        emac.start().unwrap();
+
+        // 600 bytes take three 256-byte descriptors
+        assert_eq!(emac.transmit(&[0x5A; 600]), Ok(600));
+        emac.dma.sim_tx_complete_with_errors(tdes0::NO_CARRIER);
+        assert_eq!(emac.transmit(&[0x5A; 60]), Ok(60));
+        assert!(emac.transmit(&[0x5A; 900]).is_err());
+        emac.collect_tx_status();
+
+        let trace: [_; 3] = core::array::from_fn(|id| emac.frame_trace().get(id as u32).unwrap());
+        assert_eq!((trace[0].first_desc, trace[0].desc_count), (0, 3));
+        assert_eq!(trace[0].outcome, TraceOutcome::Failed);
+        assert_ne!(trace[0].status & tdes0::NO_CARRIER, 0);
+        assert_eq!((trace[1].first_desc, trace[1].desc_count), (3, 1));
+        assert_eq!(trace[1].outcome, TraceOutcome::Pending);
+        assert_eq!(
+            trace[2].outcome,
+            TraceOutcome::Rejected(DmaError::NoDescriptorsAvailable.into())
+        );
+
+        // Never released: abandoned when the rings are reset
+        emac.set_state(State::Stopped);
+        emac.start().unwrap();
+        assert_eq!(emac.frame_trace().pending(), 0);
+        assert_eq!(
+            emac.frame_trace().get(1).unwrap().outcome,
+            TraceOutcome::Abandoned
+        );
+    }
+
+    #[test]
+    fn apply_link_status_follows_resolved_pause() {
+        use crate::internal::register::MAC_BASE;
+        use crate::internal::register::mac::{GMACFC_OFFSET, GMACFC_RFE};
+
+        sim::reset();
+        let mut emac: Emac<4, 4, 256> = Emac::new();
+        emac.sim_init(EmacConfig::new());
+        emac.enable_flow_control(true);
+        assert!(!emac.peer_pause_ability());
+
+        emac.apply_link_status(&LinkStatus::fast_full().with_pause(true, true));
+        assert!(emac.peer_pause_ability());
+        assert_eq!(
+            (emac.speed(), emac.duplex()),
+            (Speed::Mbps100, Duplex::Full)
+        );
+        assert_ne!(sim::read(MAC_BASE + GMACFC_OFFSET) & GMACFC_RFE, 0);
+
+        emac.apply_link_status(&LinkStatus::slow_half());
+        assert!(!emac.peer_pause_ability());
+        assert_eq!(sim::read(MAC_BASE + GMACFC_OFFSET) & GMACFC_RFE, 0);
+    }
+
+    #[test]
+    fn set_mac_address_rejects_multicast() {
+        sim::reset();
+        let mut emac: Emac<4, 4, 256> = Emac::new();
+        emac.sim_init(EmacConfig::new());
+        let before = *emac.mac_address();
+
+        assert_eq!(
+            emac.set_mac_address(&[0x01, 0x00, 0x5E, 0x00, 0x00, 0xFB]),
+            Err(ConfigError::InvalidMacAddress.into())
+        );
+        assert_eq!(*emac.mac_address(), before);
+
+        let addr = [0x02, 0x00, 0x00, 0x12, 0x34, 0x56];
+        assert_eq!(emac.set_mac_address(&addr), Ok(()));
+        assert_eq!(*emac.mac_address(), addr);
+    }
+
+    #[test]
+    fn apply_link_status_programs_asymmetric_pause() {
+        use crate::internal::register::MAC_BASE;
+        use crate::internal::register::mac::{GMACFC_OFFSET, GMACFC_RFE, GMACFC_TFE};
+
+        sim::reset();
+        let mut emac: Emac<4, 4, 256> = Emac::new();
+        emac.sim_init(EmacConfig::new());
+        emac.enable_flow_control(true);
+        let fc = || sim::read(MAC_BASE + GMACFC_OFFSET) & (GMACFC_TFE | GMACFC_RFE);
+
+        // We may send PAUSE, but must not honour the partner's
+        emac.apply_link_status(&LinkStatus::fast_full().with_pause(true, false));
+        assert_eq!(emac.peer_pause(), (true, false));
+        assert_eq!(fc(), GMACFC_TFE);
+
+        // We honour PAUSE, but must not send it
+        emac.apply_link_status(&LinkStatus::fast_full().with_pause(false, true));
+        assert_eq!(emac.peer_pause(), (false, true));
+        assert_eq!(fc(), GMACFC_RFE);
+    }
+
+    #[test]
+    fn duplex_mismatch_reported_from_forwarded_fragments() {
+        use crate::driver::duplex::DuplexMismatchDetector;
+        use crate::internal::dma::bits::rdes0;
+        use crate::internal::register::dma::{DMAOPERATION_FEF, DMAOPERATION_FUF};
+
+        sim::reset();
+        let mut emac: Emac<4, 4, 256> = Emac::new();
+        assert_eq!(emac.check_duplex_mismatch(), None);
+        emac.sim_init(EmacConfig::new().with_forward_error_frames(true));
+        emac.configure_dma_defaults();
+        let op_mode = sim::read(DMA_BASE + DMAOPERATION_OFFSET);
+        assert_eq!(op_mode & DMAOPERATION_FEF, DMAOPERATION_FEF);
+        assert_eq!(op_mode & DMAOPERATION_FUF, DMAOPERATION_FUF);
+        emac.start().unwrap();
+        emac.set_duplex_mismatch_detector(Some(DuplexMismatchDetector::new(2)));
+        assert_eq!(emac.check_duplex_mismatch(), None);
+
+        let mut buf = [0u8; 256];
+        let crc = rdes0::ERR_SUMMARY | rdes0::CRC_ERR;
+        assert!(emac.dma.sim_rx_frame(&[0x11; 100], crc));
+        assert!(emac.dma.sim_rx_frame(&[0x22; 20], crc));
+        for _ in 0..2 {
+            assert_eq!(emac.receive(&mut buf), Err(IoError::FrameError.into()));
+        }
+        assert_eq!(emac.stats().rx_errors, 2);
+        assert_eq!((emac.stats().rx_crc_errors, emac.stats().rx_runts), (2, 1));
+
+        let mismatch = DuplexMismatch::PartnerHalfDuplex {
+            crc_errors: 2,
+            runts: 1,
+        };
+        assert_eq!(emac.check_duplex_mismatch(), Some(mismatch));
+        assert_eq!(
+            emac.event_log().latest().map(|e| e.kind),
+            Some(EventKind::DuplexMismatch(mismatch))
+        );
+        assert_eq!(emac.check_duplex_mismatch(), None);
+
+        // A stats reset must not look like a burst of errors
+        emac.reset_stats();
+        assert_eq!(emac.check_duplex_mismatch(), None);
+    }
+
+    #[test]
+    fn event_sink_sees_logged_events_and_thresholds() {
+        use crate::driver::event_log::{Event, ThresholdMetric};
+        use core::sync::atomic::AtomicU32;
+
+        struct Counter {
+            events: AtomicU32,
+            thresholds: AtomicU32,
+        }
+
+        impl EmacEventSink for Counter {
+            fn on_event(&self, event: &Event) {
+                self.events.fetch_add(1, Ordering::Relaxed);
+                if let EventKind::Threshold {
+                    metric: ThresholdMetric::RxRingOccupancy,
+                    value,
+                    above: true,
+                } = event.kind
+                {
+                    self.thresholds.store(value, Ordering::Relaxed);
+                }
+            }
+        }
+
+        static SINK: Counter = Counter {
+            events: AtomicU32::new(0),
+            thresholds: AtomicU32::new(0),
+        };
+
+        sim::reset();
+        let mut emac: Emac<4, 4, 256> = Emac::new();
+        emac.set_event_sink(Some(&SINK));
+        emac.sim_init(EmacConfig::new());
+        emac.start().unwrap();
+        emac.record_event(EventKind::LinkDown);
+        let logged = emac.event_log().total();
+        assert_eq!(SINK.events.load(Ordering::Relaxed), logged);
+
+        emac.set_event_thresholds(EventThresholds::new().with_rx_ring_occupancy(2))
+            .unwrap();
+        emac.check_thresholds();
+        assert!(emac.dma.sim_rx_frame(&[0xFF; 60], 0));
+        assert!(emac.dma.sim_rx_frame(&[0xFF; 60], 0));
+        emac.check_thresholds();
+        assert_eq!(SINK.thresholds.load(Ordering::Relaxed), 2);
+        assert_eq!(SINK.events.load(Ordering::Relaxed), logged + 1);
+
+        emac.set_event_sink(None);
+        emac.record_event(EventKind::LinkDown);
+        assert_eq!(SINK.events.load(Ordering::Relaxed), logged + 1);
+    }
+
+    #[test]
+    fn fifo_overflows_raise_rate_alarm() {
+        use super::super::event_log::{AlarmCounter, RateAlarm};
+        use crate::internal::register::dma::DMAMISSEDFR_OFFSET;
+        use crate::logic::stats::DMAMISSEDFR_OVFFRMCNT_SHIFT;
+        use core::sync::atomic::{AtomicU32, Ordering};
+
+        static NOW: AtomicU32 = AtomicU32::new(0);
+        fn now() -> u32 {
+            NOW.load(Ordering::Relaxed)
+        }
+
+        sim::reset();
+        let mut emac: Emac<4, 4, 256> = Emac::new();
+        emac.sim_init(EmacConfig::new());
+        emac.set_event_clock(Some(now));
+        emac.start().unwrap();
+        emac.set_event_thresholds(EventThresholds::new().with_alarm(RateAlarm::new(
+            AlarmCounter::RxFifoOverflows,
+            2,
+            60_000,
+        )))
+        .unwrap();
+        emac.check_thresholds();
+
+        NOW.store(5_000, Ordering::Relaxed);
+        sim::write(
+            DMA_BASE + DMAMISSEDFR_OFFSET,
+            2 << DMAMISSEDFR_OVFFRMCNT_SHIFT,
+        );
+        emac.check_thresholds();
+        assert_eq!(emac.stats().rx_fifo_overflows, 2);
+        let event = emac.event_log().latest().unwrap();
+        assert_eq!(event.timestamp, 5_000);
+        assert_eq!(
+            event.kind,
+            EventKind::Alarm {
+                counter: AlarmCounter::RxFifoOverflows,
+                count: 2,
+                window_ms: 60_000
+            }
+        );
+    }
+
+    #[test]
+    fn zero_rx_buffers_is_tx_only() {
+        sim::reset();
+        let mut emac: Emac<0, 4, 256> = Emac::new();
+        emac.sim_init(EmacConfig::new());
+        emac.start().unwrap();
+        assert!(!emac.is_rx_enabled());
+        assert!(!rx_running());
+        assert!(tx_running());
+        assert_eq!(emac.transmit(&[0x5A; 60]), Ok(60));
+
+        let mut buf = [0u8; 256];
+        let no_rx = Error::from(DmaError::NoRxBuffers);
+        assert_eq!(emac.receive(&mut buf), Err(no_rx));
+        assert_eq!(emac.enable_rx(), Err(no_rx));
+        assert_eq!(emac.enable_rx_only(), Err(no_rx));
+        assert_eq!(emac.drain_rx_discarding(4), Ok(0));
+        assert_eq!(emac.active_ring_sizes(), (0, 4));
+        assert_eq!(
+            emac.set_active_ring_sizes(0, 2),
+            Err(IoError::InvalidState.into())
+        );
+    }
+
+    #[test]
+    fn rx_unicast_reserve_sheds_oldest_broadcast() {
+        sim::reset();
+        let mut emac: Emac<6, 4, 256> = Emac::new();
+        emac.sim_init(EmacConfig::new().with_rx_unicast_reserve(2));
+        emac.start().unwrap();
+
+        let mut unicast = [0x11u8; 60];
+        unicast[..6].copy_from_slice(&[0x02, 0, 0, 0, 0, 0x01]);
+        for _ in 0..4 {
+            assert!(emac.dma.sim_rx_frame(&[0xFF; 60], 0));
+        }
+        let occupancy = emac.rx_occupancy();
+        assert_eq!((occupancy.other, occupancy.free), (4, 2));
+        assert!(!occupancy.over_budget());
+
+        // A fifth broadcast eats into the reserve and the oldest one goes
+        assert!(emac.dma.sim_rx_frame(&[0xFF; 60], 0));
+        assert!(emac.rx_occupancy().over_budget());
+        assert_eq!(emac.rebalance_rx(), Ok(1));
+        assert_eq!(emac.rx_occupancy().other, 4);
+        assert_eq!(emac.stats().rx_shed, 1);
+
+        // A broadcast behind a unicast frame is shed where it sits and
+        // dropped once the unicast frame has been received
+        emac.drain_rx_discarding(4).unwrap();
+        assert!(emac.dma.sim_rx_frame(&unicast, 0));
+        let mut broadcast = [0xFFu8; 60];
+        for i in 0..5 {
+            broadcast[14] = i;
+            assert!(emac.dma.sim_rx_frame(&broadcast, 0));
+        }
+        assert_eq!(emac.rebalance_rx(), Ok(1));
+        let occupancy = emac.rx_occupancy();
+        assert_eq!(
+            (occupancy.unicast, occupancy.other, occupancy.shed),
+            (1, 4, 1)
+        );
+        assert!(!occupancy.over_budget());
+        let mut buf = [0u8; 256];
+        assert_eq!(emac.receive(&mut buf), Ok(60));
+        assert_eq!(buf[0], 0x02);
+        assert_eq!(emac.rx_occupancy().shed, 1);
+
+        // The shed frame never reaches the application
+        assert_eq!(emac.receive(&mut buf), Ok(60));
+        assert_eq!(buf[14], 1);
+        let occupancy = emac.rx_occupancy();
+        assert_eq!((occupancy.other, occupancy.shed, occupancy.free), (3, 0, 3));
+        assert_eq!(emac.stats().rx_frames, 2);
+        assert_eq!(emac.stats().rx_shed, 2);
+    }
+
+    #[test]
+    fn rx_unicast_reserve_is_checked_against_the_ring() {
+        sim::reset();
+        let mut emac: Emac<4, 4, 256> = Emac::new();
+        assert_eq!(
+            emac.init(
+                EmacConfig::new().with_rx_unicast_reserve(4),
+                crate::testing::MockDelay::new()
+            ),
+            Err(ConfigError::InvalidConfig.into())
+        );
+        emac.sim_init(EmacConfig::new().with_rx_unicast_reserve(2));
+        assert_eq!(
+            emac.set_active_ring_sizes(2, 4),
+            Err(ConfigError::InvalidConfig.into())
+        );
+        assert_eq!(emac.set_active_ring_sizes(3, 4), Ok(()));
+    }
+
+    #[test]
+    fn drain_rx_discarding_empties_ring() {
+        sim::reset();
+        let mut emac: Emac<4, 4, 256> = Emac::new();
+        emac.sim_init(EmacConfig::new());
+        emac.start().unwrap();
+        assert!(emac.rx_ring_prefilled());
+
+        for _ in 0..3 {
+            assert!(emac.dma.sim_rx_frame(&[0xFF; 60], 0));
+        }
+        assert!(!emac.rx_ring_prefilled());
+        assert_eq!(emac.drain_rx_discarding(2), Ok(2));
+        assert_eq!(emac.rx_frames_waiting(), 1);
+        assert_eq!(emac.drain_rx_discarding(8), Ok(1));
+        assert!(emac.rx_ring_prefilled());
+        assert_eq!(emac.stats().rx_frames, 0);
+    }
+
+    fn pause_frame() -> [u8; 60] {
+        let mut f = [0u8; 60];
+        f[..6].copy_from_slice(&[0x01, 0x80, 0xC2, 0x00, 0x00, 0x01]);
+        f[12..16].copy_from_slice(&[0x88, 0x08, 0x00, 0x01]);
+        f
+    }
+
+    #[test]
+    fn pause_frames_counted_and_dropped_by_default() {
+        sim::reset();
+        let mut emac: Emac<4, 4, 256> = Emac::new();
+        emac.sim_init(EmacConfig::new());
+        emac.start().unwrap();
+
+        assert!(emac.dma.sim_rx_frame(&pause_frame(), 0));
+        assert!(emac.dma.sim_rx_frame(&[0xFF; 60], 0));
+        let mut buf = [0u8; 256];
+        assert_eq!(emac.receive(&mut buf), Ok(60));
+        assert_eq!(buf[..6], [0xFF; 6]);
+        assert_eq!(emac.stats().rx_pause, 1);
+        assert_eq!(emac.stats().rx_frames, 1);
+    }
+
+    #[test]
+    fn pass_all_delivers_pause_frames() {
+        sim::reset();
+        let mut emac: Emac<4, 4, 256> = Emac::new();
+        emac.sim_init(EmacConfig::new());
+        emac.start().unwrap();
+        emac.apply_settings(
+            emac.settings()
+                .with_control_frames(ControlFrameFilter::PassAll),
+        )
+        .unwrap();
+        let ff = sim::read(MAC_BASE + GMACFF_OFFSET);
+        assert_eq!((ff & GMACFF_PCF_MASK) >> GMACFF_PCF_SHIFT, pcf::ALL);
+
+        assert!(emac.dma.sim_rx_frame(&pause_frame(), 0));
+        let mut buf = [0u8; 256];
+        assert_eq!(emac.receive(&mut buf), Ok(60));
+        assert_eq!(buf[12..14], [0x88, 0x08]);
+        assert_eq!(emac.stats().rx_pause, 1);
+    }
+
+    #[test]
+    fn tx_rewrite_sets_source_and_inserts_tag_during_copy() {
+        use super::super::tx_rewrite::{TxRewriteRule, VlanTag};
+
+        const PEER: [u8; 6] = [0x02, 0, 0, 0, 0, 0x10];
+        const OURS: [u8; 6] = [0x02, 0, 0, 0, 0, 0x01];
+
+        sim::reset();
+        let mut emac: Emac<4, 4, 256> = Emac::new();
+        emac.sim_init(EmacConfig::new());
+        emac.start().unwrap();
+        emac.tx_rewrite_mut()
+            .add(
+                TxRewriteRule::for_destination(PEER)
+                    .with_source(OURS)
+                    .with_vlan(VlanTag::new(7)),
+            )
+            .unwrap();
+
+        // Spans two descriptors once the tag is inserted
+        let mut frame: [u8; 300] = core::array::from_fn(|i| i as u8);
+        frame[..6].copy_from_slice(&PEER);
+        assert_eq!(emac.transmit(&frame), Ok(304));
+        let first = emac.dma.tx_buffer(0);
+        assert_eq!(first[..6], PEER);
+        assert_eq!(first[6..12], OURS);
+        assert_eq!(first[12..16], [0x81, 0x00, 0x00, 0x07]);
+        assert_eq!(first[16..], frame[12..252]);
+        assert_eq!(emac.dma.tx_buffer(1)[..48], frame[252..]);
+        assert_eq!(emac.tx_rewrite().rewritten(), 1);
+
+        // Other destinations go out untouched
+        let mut other = [0x55u8; 60];
+        other[..6].copy_from_slice(&[0xFF; 6]);
+        assert_eq!(emac.transmit(&other), Ok(60));
+        assert_eq!(emac.dma.tx_buffer(2)[..60], other);
+        assert_eq!(emac.tx_rewrite().rewritten(), 1);
+    }
+
+    #[test]
+    fn llc_filter_drops_8023_frames_and_reports_format() {
+        use super::super::frame_format::FrameFormat;
+
+        sim::reset();
+        let mut emac: Emac<4, 4, 256> = Emac::new();
+        emac.sim_init(EmacConfig::new().with_llc_frames(LlcFrameFilter::PassSnap));
+        emac.start().unwrap();
+
+        let mut bpdu = [0u8; 60];
+        bpdu[12..17].copy_from_slice(&[0x00, 0x26, 0x42, 0x42, 0x03]);
+        let mut snap = [0u8; 60];
+        snap[12..22].copy_from_slice(&[0x00, 0x2E, 0xAA, 0xAA, 0x03, 0, 0, 0, 0x08, 0x06]);
+        assert!(emac.dma.sim_rx_frame(&bpdu, 0));
+        assert!(emac.dma.sim_rx_frame(&snap, 0));
+
+        let mut buf = [0u8; 256];
+        assert_eq!(emac.receive(&mut buf), Ok(60));
+        let format = emac.last_rx_format().unwrap();
+        assert!(matches!(format, FrameFormat::Snap { length: 0x2E, .. }));
+        assert_eq!(format.ether_type(), Some(0x0806));
+        assert_eq!(emac.stats().rx_llc_dropped, 1);
+        assert_eq!(emac.stats().rx_frames, 1);
+    }
+
+    #[test]
+    fn handle_interrupt_reports_racing_event_once() {
+        use crate::internal::register::dma::{
+            DMASTATUS_NIS, DMASTATUS_OFFSET, DMASTATUS_RI, DMASTATUS_TI,
+        };
+
+        sim::reset();
+        let status_reg = DMA_BASE + DMASTATUS_OFFSET;
+        sim::write_one_to_clear(status_reg);
+        let emac: Emac<4, 4, 256> = Emac::new();
+
+        sim::raise(status_reg, DMASTATUS_RI | DMASTATUS_NIS);
+        sim::raise_on_read(status_reg, 1, DMASTATUS_TI | DMASTATUS_NIS);
+        let status = emac.handle_interrupt();
+        assert!(status.rx_complete && status.tx_complete && status.normal_summary);
+        assert_eq!(sim::read(status_reg), 0);
+
+        // Nothing left over to report again
+        assert!(!emac.handle_interrupt().any());
+    }
+
+    #[test]
+    fn rx_interrupt_stamps_received_frames() {
+        use crate::internal::register::dma::{DMASTATUS_NIS, DMASTATUS_OFFSET, DMASTATUS_RI};
+        use core::sync::atomic::AtomicU64;
+
+        struct TestClock(AtomicU64);
+        impl Clock for TestClock {
+            fn now_us(&self) -> u64 {
+                self.0.load(Ordering::Relaxed)
+            }
+        }
+        static CLOCK: TestClock = TestClock(AtomicU64::new(0));
+
+        sim::reset();
+        let status_reg = DMA_BASE + DMASTATUS_OFFSET;
+        let mut emac: Emac<4, 4, 256> = Emac::new();
+        emac.sim_init(EmacConfig::new().with_rx_soft_timestamps(true));
+        emac.set_clock(Some(&CLOCK));
+        emac.start().unwrap();
+        sim::write_one_to_clear(status_reg);
+
+        let frame = [0xFFu8; 60];
+        let mut buf = [0u8; 256];
+        let interrupt_at = |us: u64| {
+            CLOCK.0.store(us, Ordering::Relaxed);
+            sim::raise(status_reg, DMASTATUS_RI | DMASTATUS_NIS);
+        };
+
+        // Two frames announced by one interrupt share its stamp
+        assert!(emac.dma.sim_rx_frame(&frame, 0));
+        assert!(emac.dma.sim_rx_frame(&frame, 0));
+        interrupt_at(7_250);
+        emac.handle_interrupt();
+        interrupt_at(7_900); // ignored: the first stamp is not taken yet
+        emac.handle_interrupt();
+        assert_eq!(emac.receive(&mut buf), Ok(60));
+        assert_eq!(
+            emac.last_rx_timestamp(),
+            Some(SoftRxTimestamp {
+                us: 7_250,
+                shared: false
+            })
+        );
+        assert_eq!(emac.receive(&mut buf), Ok(60));
+        assert_eq!(
+            emac.last_rx_timestamp().map(|t| (t.us, t.shared)),
+            Some((7_250, true))
+        );
+
+        // The next interrupt after the burst gives a fresh stamp
+        assert!(emac.dma.sim_rx_frame(&frame, 0));
+        interrupt_at(9_000);
+        emac.handle_interrupt();
+        assert_eq!(emac.receive(&mut buf), Ok(60));
+        assert_eq!(
+            emac.last_rx_timestamp().map(|t| (t.us, t.shared)),
+            Some((9_000, false))
+        );
+
+        // Turned off, interrupts are no longer stamped
+        emac.apply_settings(emac.settings().with_rx_soft_timestamps(false))
+            .unwrap();
+        assert!(emac.dma.sim_rx_frame(&frame, 0));
+        interrupt_at(10_000);
+        emac.handle_interrupt();
+        assert_eq!(emac.receive(&mut buf), Ok(60));
+        assert_eq!(emac.last_rx_timestamp(), None);
+    }
+
+    #[test]
+    fn poll_rx_stops_at_budget() {
+        use crate::internal::dma::bits::rdes0;
+
+        sim::reset();
+        let mut emac: Emac<4, 4, 256> = Emac::new();
+        emac.sim_init(EmacConfig::new());
+        assert_eq!(emac.poll_rx(4, |_| {}), Err(IoError::InvalidState.into()));
+        emac.start().unwrap();
+
+        assert!(emac.dma.sim_rx_frame(&[0x11; 60], 0));
+        assert!(
+            emac.dma
+                .sim_rx_frame(&[0x22; 60], rdes0::ERR_SUMMARY | rdes0::CRC_ERR)
+        );
+        assert!(emac.dma.sim_rx_frame(&[0x33; 70], 0));
+        assert!(emac.dma.sim_rx_frame(&[0x44; 80], 0));
+
+        let mut seen = [0u8; 4];
+        let mut count = 0;
+        // The errored frame uses up budget too
+        assert_eq!(
+            emac.poll_rx(3, |frame| {
+                seen[count] = frame[0];
+                count += 1;
+            }),
+            Ok(true)
+        );
+        assert_eq!(seen[..count], [0x11, 0x33]);
+        assert_eq!(emac.stats().rx_errors, 1);
+
+        assert_eq!(
+            emac.poll_rx(3, |frame| {
+                assert_eq!(frame.len(), 80);
+                count += 1;
+            }),
+            Ok(false)
+        );
+        assert_eq!(count, 3);
+        assert_eq!(emac.poll_rx(0, |_| {}), Ok(false));
+    }
+
+    #[cfg(feature = "test-hooks")]
+    #[test]
+    fn injected_frame_is_received() {
+        sim::reset();
+        let mut emac: Emac<2, 2, 256> = Emac::new();
+        let mut arp = [0u8; 42];
+        arp[..6].copy_from_slice(&[0xFF; 6]);
+        arp[12..14].copy_from_slice(&[0x08, 0x06]);
+        arp[14..].fill(0xA5);
+        assert_eq!(
+            emac.inject_rx_frame(&arp),
+            Err(Error::Io(IoError::InvalidState))
+        );
+
+        emac.sim_init(EmacConfig::new().with_deferred_rx(true));
+        #[cfg(feature = "crc-verify")]
+        emac.set_fcs_verify(true);
+        emac.start().unwrap();
+        assert_eq!(
+            emac.inject_rx_frame(&arp[..10]),
+            Err(Error::Dma(DmaError::InvalidLength))
+        );
+        assert_eq!(
+            emac.inject_rx_frame(&[0; 256]),
+            Err(Error::Dma(DmaError::FrameTooLarge))
+        );
+        assert_eq!(emac.inject_rx_frame(&arp), Ok(()));
+        assert_eq!(emac.inject_rx_frame(&arp), Ok(()));
+        assert_eq!(
+            emac.inject_rx_frame(&arp),
+            Err(Error::Dma(DmaError::NoDescriptorsAvailable))
+        );
+
+        emac.enable_rx().unwrap();
+        let mut buf = [0u8; 256];
+        assert_eq!(emac.receive(&mut buf), Ok(arp.len()));
+        assert_eq!(buf[..arp.len()], arp);
+        assert!(emac.last_rx_fcs().unwrap().passed());
+        #[cfg(feature = "crc-verify")]
+        assert_eq!(emac.last_rx_fcs().unwrap().software_ok, Some(true));
+        assert_eq!(emac.stats().rx_frames, 1);
+    }
+
+    #[cfg(feature = "test-hooks")]
+    #[test]
+    fn inject_refused_while_receiver_runs() {
+        sim::reset();
+        let mut emac: Emac<2, 2, 256> = Emac::new();
+        emac.sim_init(EmacConfig::new());
+        emac.start().unwrap();
+        assert!(emac.is_rx_enabled());
+        assert_eq!(
+            emac.inject_rx_frame(&[0xFF; 60]),
+            Err(Error::Io(IoError::InvalidState))
+        );
+        assert_eq!(emac.rx_frames_waiting(), 0);
+    }
+
+    #[test]
+    fn clock_bounds_polls_and_stamps_events() {
+        use crate::internal::register::mac::{GMACMIIADDR_GB, GMACMIIADDR_OFFSET};
+        use core::sync::atomic::AtomicU64;
+
+        /// Advances 1 ms per read
+        struct FastClock(AtomicU64);
+        impl Clock for FastClock {
+            fn now_us(&self) -> u64 {
+                self.0.fetch_add(1_000, Ordering::Relaxed)
+            }
+        }
+        static CLOCK: FastClock = FastClock(AtomicU64::new(5_000));
+
+        sim::reset();
+        let mut emac: Emac<4, 4, 256> = Emac::new();
+        emac.sim_init(EmacConfig::new().with_mii_busy_timeout_us(3_000));
+        emac.set_clock(Some(&CLOCK));
+
+        // A stuck MII busy bit times out after the configured budget, not after
+        // a count of register reads
+        sim::write(MAC_BASE + GMACMIIADDR_OFFSET, GMACMIIADDR_GB);
+        let before = CLOCK.0.load(Ordering::Relaxed);
+        assert_eq!(
+            emac.wait_mii_not_busy(),
+            Err(IoError::MiiBusyTimeout.into())
+        );
+        let reads = (CLOCK.0.load(Ordering::Relaxed) - before) / 1_000;
+        assert_eq!(reads, 3 + 2);
+
+        // Without an event clock, events are stamped in clock milliseconds
+        emac.record_event(EventKind::Marker(1));
+        let stamped = emac.event_log().latest().unwrap().timestamp;
+        assert_eq!(
+            stamped,
+            (CLOCK.0.load(Ordering::Relaxed) / 1_000) as u32 - 1
+        );
+        emac.set_event_clock(Some(|| 7));
+        emac.record_event(EventKind::Marker(2));
+        assert_eq!(emac.event_log().latest().unwrap().timestamp, 7);
+    }
+
+    #[test]
+    fn clock_gate_replaces_dport_writes() {
+        use crate::hal::PeripheralClockGate;
+        use crate::internal::register::{DPORT_WIFI_CLK_EMAC_EN, DPORT_WIFI_CLK_EN_REG};
+        use core::sync::atomic::AtomicU32;
+
+        /// Counts enables and disables
+        struct CountingGate(AtomicU32, AtomicU32);
+        impl PeripheralClockGate for CountingGate {
+            fn enable(&self) {
+                self.0.fetch_add(1, Ordering::Relaxed);
+            }
+            fn disable(&self) {
+                self.1.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        static GATE: CountingGate = CountingGate(AtomicU32::new(0), AtomicU32::new(0));
+
+        struct NoDelay;
+        impl DelayNs for NoDelay {
+            fn delay_ns(&mut self, _ns: u32) {}
+        }
+
+        sim::reset();
+        let mut emac: Emac<4, 4, 256> = Emac::new();
+        emac.set_clock_gate(Some(&GATE));
+        let _ = emac.full_hardware_reset(NoDelay);
+        assert_eq!(GATE.0.load(Ordering::Relaxed), 1);
+        assert_eq!(GATE.1.load(Ordering::Relaxed), 1);
+        assert_eq!(sim::read(DPORT_WIFI_CLK_EN_REG) & DPORT_WIFI_CLK_EMAC_EN, 0);
+
+        // Without a gate the driver writes DPORT itself
+        emac.set_clock_gate(None);
+        emac.enable_peripheral_clock();
+        assert_ne!(sim::read(DPORT_WIFI_CLK_EN_REG) & DPORT_WIFI_CLK_EMAC_EN, 0);
+        assert_eq!(GATE.0.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn fast_resume_restores_registers_and_keeps_rings() {
+        use crate::driver::hw_state::HwStateLoss;
+        use crate::internal::register::dma::{DMABUSMODE_OFFSET, DMABUSMODE_SW_RST};
+        use crate::internal::register::mac::GMACCONFIG_OFFSET;
+
+        struct NoDelay;
+        impl DelayNs for NoDelay {
+            fn delay_ns(&mut self, _ns: u32) {}
+        }
+
+        sim::reset();
+        sim::self_clearing(DMA_BASE + DMABUSMODE_OFFSET, DMABUSMODE_SW_RST);
+        let mut emac: Emac<4, 4, 256> = Emac::new();
+        assert!(!emac.verify_hw_state().any());
+        assert_eq!(emac.fast_resume(NoDelay), Err(IoError::InvalidState.into()));
+
+        // sim_init() skips the register setup; a resume does all of it
+        emac.sim_init(EmacConfig::new());
+        emac.start().unwrap();
+        emac.fast_resume(NoDelay).unwrap();
+        assert_eq!(emac.verify_hw_state(), HwStateLoss::default());
+
+        // A received frame not yet read, and a frame not yet sent
+        assert!(emac.dma.sim_rx_frame(&[0x5A; 60], 0));
+        emac.transmit(&[0xA5; 60]).unwrap();
+
+        // Light sleep wipes the MAC configuration and DMA operation mode
+        sim::write(MAC_BASE + GMACCONFIG_OFFSET, 0);
+        sim::write(DMA_BASE + DMAOPERATION_OFFSET, 0);
+        let loss = emac.verify_hw_state();
+        assert!(loss.mac && loss.dma);
+        assert!(!loss.clock && !loss.interface && !loss.filter);
+
+        emac.fast_resume(NoDelay).unwrap();
+        assert!(!emac.verify_hw_state().any());
+        let op = sim::read(DMA_BASE + DMAOPERATION_OFFSET);
+        assert_eq!(
+            op & (DMAOPERATION_SR | DMAOPERATION_ST),
+            DMAOPERATION_SR | DMAOPERATION_ST
+        );
+
+        // The DMA continues after the waiting frame and at the queued one
+        let (rx, tx) = emac.dma.next_dma_descriptors();
+        assert_ne!(rx, emac.dma.rx_ring_base());
+        assert_eq!(DmaRegs::rx_desc_list_addr(), rx);
+        assert_eq!(DmaRegs::tx_desc_list_addr(), tx);
+        let mut buf = [0u8; 256];
+        assert_eq!(emac.receive(&mut buf), Ok(60));
+        assert_eq!(buf[0], 0x5A);
+        assert_eq!(emac.dma.sim_tx_complete(), 1);
+    }
+
+    #[test]
+    fn stop_with_delay_times_out_after_configured_budget() {
+        /// Records the total requested delay
+        struct CountingDelay(u64);
+        impl DelayNs for CountingDelay {
+            fn delay_ns(&mut self, ns: u32) {
+                self.0 += u64::from(ns);
+            }
+        }
+
+        sim::reset();
+        let mut emac: Emac<4, 4, 256> = Emac::new();
+        emac.sim_init(EmacConfig::new().with_stop_timeouts_us(250, 100));
+        emac.set_state(State::Running);
+
+        // The simulated TX DMA reads as stopped, but its FIFO flush bit
+        // never self-clears
+        let mut delay = CountingDelay(0);
+        assert_eq!(
+            emac.stop_with_delay(&mut delay),
+            Err(IoError::FifoFlushTimeout.into())
+        );
+        assert_eq!(delay.0, 100_000);
+    }
+
+    #[test]
+    fn liveness_tracks_activity_and_dma_states() {
+        use crate::driver::liveness::LivenessFault;
+        use crate::internal::register::dma::{
+            DMASTATUS_OFFSET, DMASTATUS_RS_SHIFT, DMASTATUS_TS_SHIFT,
+        };
+        use core::sync::atomic::AtomicU32;
+
+        static NOW: AtomicU32 = AtomicU32::new(100);
+        fn now() -> u32 {
+            NOW.load(Ordering::Relaxed)
+        }
+
+        sim::reset();
+        let mut emac: Emac<4, 4, 256> = Emac::new();
+        emac.sim_init(EmacConfig::new());
+        emac.set_event_clock(Some(now));
+        emac.start().unwrap();
+        let status_reg = DMA_BASE + DMASTATUS_OFFSET;
+        sim::write(
+            status_reg,
+            (3 << DMASTATUS_RS_SHIFT) | (6 << DMASTATUS_TS_SHIFT),
+        );
+        assert_eq!(emac.liveness().last_rx, None);
+        assert_eq!(
+            emac.dma_states(),
+            (TxProcessState::Suspended, RxProcessState::WaitingForPacket)
+        );
+
+        assert!(emac.dma.sim_rx_frame(&[0xFF; 60], 0));
+        let mut buf = [0u8; 256];
+        assert_eq!(emac.receive(&mut buf), Ok(60));
+        NOW.store(150, Ordering::Relaxed);
+        assert_eq!(emac.transmit(&[0x5A; 60]), Ok(60));
+
+        let report = emac.liveness();
+        assert_eq!((report.last_rx, report.last_tx), (Some(100), Some(150)));
+        assert_eq!(report.rx_idle(), Some(50));
+        assert_eq!(report.tx_in_flight, 1);
+        assert_eq!(report.fault(Some(60)), None);
+        assert_eq!(
+            report.fault(Some(40)),
+            Some(LivenessFault::RxSilent { idle: 50 })
+        );
+
+        // RU left unhandled: suspended with every descriptor free
+        sim::write(
+            status_reg,
+            4 << DMASTATUS_RS_SHIFT | 6 << DMASTATUS_TS_SHIFT,
+        );
+        assert_eq!(
+            emac.liveness().fault(None),
+            Some(LivenessFault::RxSuspended)
+        );
+        sim::write(status_reg, 0);
+        assert_eq!(
+            emac.liveness().fault(None),
+            Some(LivenessFault::RxDmaStopped)
+        );
+    }
+}
@@ -4,9 +4,27 @@
 //! - [`ConfigError`]: Initialization and configuration failures
 //! - [`DmaError`]: DMA buffer and descriptor issues
 //! - [`IoError`]: Runtime TX/RX failures
+//! - [`PhyError`]: PHY failures, carried by [`IoError::PhyError`]
 //!
 //! The unified [`Error`] enum wraps all domain errors and is returned
 //! by most driver methods.
+//!
+//! # Error Codes
+//!
+//! Every variant has a stable numeric code, returned by `code()` on the
+//! domain error and on [`Error`]. The hundreds digit is the domain and the
+//! rest numbers the variant:
+//!
+//! | Range | Domain |
+//! |-------|--------|
+//! | 101-199 | [`ConfigError`] |
+//! | 201-299 | [`DmaError`] |
+//! | 301-399 | [`IoError`] |
+//!
+//! Codes are never reused or renumbered, so products can forward them to
+//! their own telemetry or diagnostics tables. All error enums are
+//! `#[non_exhaustive]`: match the variants you handle and fall back on the
+//! code for the rest.
 
 // =============================================================================
 // Configuration Errors
@@ -18,6 +36,7 @@
 /// or PHY/GPIO initialization.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
 pub enum ConfigError {
     /// Driver already initialized
     AlreadyInitialized,
@@ -31,6 +50,8 @@ pub enum ConfigError {
     GpioError,
     /// Software reset failed or timed out
     ResetFailed,
+    /// MAC address is not a valid unicast station address
+    InvalidMacAddress,
 }
 
 impl core::fmt::Display for ConfigError {
@@ -50,6 +71,21 @@ impl ConfigError {
             ConfigError::ClockError => "clock configuration error",
             ConfigError::GpioError => "GPIO configuration error",
             ConfigError::ResetFailed => "software reset failed",
+            ConfigError::InvalidMacAddress => "invalid MAC address",
+        }
+    }
+
+    /// Stable numeric code (101-199)
+    #[must_use]
+    pub const fn code(&self) -> u16 {
+        match self {
+            ConfigError::AlreadyInitialized => 101,
+            ConfigError::InvalidConfig => 102,
+            ConfigError::InvalidPhyAddress => 103,
+            ConfigError::ClockError => 104,
+            ConfigError::GpioError => 105,
+            ConfigError::ResetFailed => 106,
+            ConfigError::InvalidMacAddress => 107,
         }
     }
 }
@@ -63,6 +99,7 @@ impl ConfigError {
 /// These errors relate to descriptor ring management and buffer allocation.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
 pub enum DmaError {
     /// No descriptors available for transmission
     NoDescriptorsAvailable,
@@ -74,6 +111,11 @@ pub enum DmaError {
     InvalidLength,
     /// Fatal bus error (unrecoverable DMA error)
     FatalBusError,
+    /// No RX ring: the EMAC was built with `RX_BUFS = 0`
+    NoRxBuffers,
+    /// Caller buffer the DMA cannot read: outside internal SRAM or not
+    /// 4-byte aligned
+    BufferNotDmaCapable,
 }
 
 impl core::fmt::Display for DmaError {
@@ -92,6 +134,22 @@ impl DmaError {
             DmaError::FrameTooLarge => "frame too large for buffers",
             DmaError::InvalidLength => "invalid frame length",
             DmaError::FatalBusError => "fatal DMA bus error",
+            DmaError::NoRxBuffers => "no RX buffers configured",
+            DmaError::BufferNotDmaCapable => "buffer not DMA-capable",
+        }
+    }
+
+    /// Stable numeric code (201-299)
+    #[must_use]
+    pub const fn code(&self) -> u16 {
+        match self {
+            DmaError::NoDescriptorsAvailable => 201,
+            DmaError::DescriptorBusy => 202,
+            DmaError::FrameTooLarge => 203,
+            DmaError::InvalidLength => 204,
+            DmaError::FatalBusError => 205,
+            DmaError::NoRxBuffers => 206,
+            DmaError::BufferNotDmaCapable => 207,
         }
     }
 }
@@ -105,6 +163,7 @@ impl DmaError {
 /// These errors occur during frame transmission or reception.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
 pub enum IoError {
     /// Operation timed out
     Timeout,
@@ -116,8 +175,23 @@ pub enum IoError {
     IncompleteFrame,
     /// Frame has receive errors (CRC, overflow, etc.)
     FrameError,
-    /// PHY communication error (MDIO timeout or failure)
-    PhyError,
+    /// PHY failure; see [`PhyError`]
+    PhyError(PhyError),
+    /// Frame held back by a transmit rate shaper
+    RateLimited,
+    /// Software CRC-32 disagrees with the received FCS
+    FcsMismatch,
+    /// Another descriptor ring operation was still in progress, i.e. the
+    /// EMAC is used from interrupt and task context without `SharedEmac`
+    Reentered,
+    /// Transmit DMA did not reach the stopped state within
+    /// `EmacConfig::tx_stop_timeout_us`
+    TxStopTimeout,
+    /// TX FIFO flush did not complete within
+    /// `EmacConfig::fifo_flush_timeout_us`
+    FifoFlushTimeout,
+    /// MII busy bit did not clear within `EmacConfig::mii_busy_timeout_us`
+    MiiBusyTimeout,
 }
 
 impl core::fmt::Display for IoError {
@@ -136,7 +210,91 @@ impl IoError {
             IoError::BufferTooSmall => "buffer too small for frame",
             IoError::IncompleteFrame => "incomplete frame",
             IoError::FrameError => "frame error",
-            IoError::PhyError => "PHY communication error",
+            IoError::PhyError(e) => e.as_str(),
+            IoError::RateLimited => "transmit rate limit exceeded",
+            IoError::FcsMismatch => "frame check sequence mismatch",
+            IoError::Reentered => "concurrent descriptor ring access",
+            IoError::TxStopTimeout => "TX DMA stop timed out",
+            IoError::FifoFlushTimeout => "TX FIFO flush timed out",
+            IoError::MiiBusyTimeout => "MII busy timed out",
+        }
+    }
+
+    /// Stable numeric code (301-399)
+    #[must_use]
+    pub const fn code(&self) -> u16 {
+        match self {
+            IoError::Timeout => 301,
+            IoError::InvalidState => 302,
+            IoError::BufferTooSmall => 303,
+            IoError::IncompleteFrame => 304,
+            IoError::FrameError => 305,
+            IoError::PhyError(e) => e.code(),
+            IoError::RateLimited => 307,
+            IoError::FcsMismatch => 308,
+            IoError::Reentered => 309,
+            IoError::TxStopTimeout => 310,
+            IoError::FifoFlushTimeout => 311,
+            IoError::MiiBusyTimeout => 312,
+        }
+    }
+
+    /// Returns true for any timeout, generic or specific to one wait
+    #[must_use]
+    pub const fn is_timeout(&self) -> bool {
+        matches!(
+            self,
+            IoError::Timeout
+                | IoError::TxStopTimeout
+                | IoError::FifoFlushTimeout
+                | IoError::MiiBusyTimeout
+                | IoError::PhyError(PhyError::ResetTimeout)
+        )
+    }
+}
+
+// =============================================================================
+// PHY Errors
+// =============================================================================
+
+/// PHY failures
+///
+/// Kept apart so a PHY that does not answer at all can be told from one
+/// that answers but is stuck in reset: the first points at wiring, strap
+/// pins or the PHY address, the second at the PHY's clock or power.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum PhyError {
+    /// MDIO access failed or no PHY answered at the address
+    Communication,
+    /// PHY answered on MDIO but did not clear BMCR.RESET within the reset
+    /// budget
+    ResetTimeout,
+}
+
+impl core::fmt::Display for PhyError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl PhyError {
+    /// Returns a human-readable description of the error
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            PhyError::Communication => "PHY communication error",
+            PhyError::ResetTimeout => "PHY soft reset timed out",
+        }
+    }
+
+    /// Stable numeric code, in the [`IoError`] range
+    #[must_use]
+    pub const fn code(&self) -> u16 {
+        match self {
+            PhyError::Communication => 306,
+            PhyError::ResetTimeout => 313,
         }
     }
 }
@@ -157,7 +315,7 @@ impl IoError {
 /// }
 /// ```
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
 pub enum Error {
     /// Configuration error
     Config(ConfigError),
@@ -167,14 +325,72 @@ pub enum Error {
     Io(IoError),
 }
 
+/// `io: operation timed out`, or `E301` with the alternate flag (`{:#}`)
 impl core::fmt::Display for Error {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if f.alternate() {
+            return write!(f, "E{}", self.code());
+        }
+        write!(f, "{}: {}", self.domain(), self.as_str())
+    }
+}
+
+/// Logged as `E301 io: operation timed out`
+#[cfg(feature = "defmt")]
+impl defmt::Format for Error {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(
+            f,
+            "E{=u16} {=str}: {=str}",
+            self.code(),
+            self.domain(),
+            self.as_str()
+        );
+    }
+}
+
+impl Error {
+    /// Stable numeric code of the wrapped error; see the module docs
+    #[must_use]
+    pub const fn code(&self) -> u16 {
         match self {
-            Error::Config(e) => write!(f, "config: {}", e.as_str()),
-            Error::Dma(e) => write!(f, "dma: {}", e.as_str()),
-            Error::Io(e) => write!(f, "io: {}", e.as_str()),
+            Error::Config(e) => e.code(),
+            Error::Dma(e) => e.code(),
+            Error::Io(e) => e.code(),
         }
     }
+
+    /// Short domain name: `config`, `dma` or `io`
+    #[must_use]
+    pub const fn domain(&self) -> &'static str {
+        match self {
+            Error::Config(_) => "config",
+            Error::Dma(_) => "dma",
+            Error::Io(_) => "io",
+        }
+    }
+
+    /// Human-readable description of the wrapped error
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Error::Config(e) => e.as_str(),
+            Error::Dma(e) => e.as_str(),
+            Error::Io(e) => e.as_str(),
+        }
+    }
+
+    /// True for backpressure and polling results that are not worth logging
+    /// in the [`EventLog`](super::event_log::EventLog)
+    pub(crate) const fn is_routine(&self) -> bool {
+        matches!(
+            self,
+            Error::Dma(DmaError::NoDescriptorsAvailable | DmaError::DescriptorBusy)
+                | Error::Io(
+                    IoError::IncompleteFrame | IoError::InvalidState | IoError::RateLimited
+                )
+        )
+    }
 }
 
 // From impls for automatic conversion
@@ -196,6 +412,18 @@ impl From<IoError> for Error {
     }
 }
 
+impl From<PhyError> for IoError {
+    fn from(e: PhyError) -> Self {
+        IoError::PhyError(e)
+    }
+}
+
+impl From<PhyError> for Error {
+    fn from(e: PhyError) -> Self {
+        Error::Io(IoError::PhyError(e))
+    }
+}
+
 /// Result type alias for EMAC operations
 pub type Result<T> = core::result::Result<T, Error>;
 
@@ -232,6 +460,7 @@ mod tests {
             ConfigError::ClockError,
             ConfigError::GpioError,
             ConfigError::ResetFailed,
+            ConfigError::InvalidMacAddress,
         ];
 
         for variant in variants {
@@ -272,6 +501,8 @@ mod tests {
             DmaError::FrameTooLarge,
             DmaError::InvalidLength,
             DmaError::FatalBusError,
+            DmaError::NoRxBuffers,
+            DmaError::BufferNotDmaCapable,
         ];
 
         for variant in variants {
@@ -297,6 +528,17 @@ mod tests {
     // IoError Tests
     // =========================================================================
 
+    #[test]
+    fn io_error_is_timeout() {
+        assert!(IoError::Timeout.is_timeout());
+        assert!(IoError::TxStopTimeout.is_timeout());
+        assert!(IoError::FifoFlushTimeout.is_timeout());
+        assert!(IoError::MiiBusyTimeout.is_timeout());
+        assert!(IoError::PhyError(PhyError::ResetTimeout).is_timeout());
+        assert!(!IoError::PhyError(PhyError::Communication).is_timeout());
+        assert!(!IoError::InvalidState.is_timeout());
+    }
+
     #[test]
     fn io_error_as_str_non_empty() {
         let variants = [
@@ -305,7 +547,14 @@ mod tests {
             IoError::BufferTooSmall,
             IoError::IncompleteFrame,
             IoError::FrameError,
-            IoError::PhyError,
+            IoError::PhyError(PhyError::Communication),
+            IoError::PhyError(PhyError::ResetTimeout),
+            IoError::RateLimited,
+            IoError::FcsMismatch,
+            IoError::Reentered,
+            IoError::TxStopTimeout,
+            IoError::FifoFlushTimeout,
+            IoError::MiiBusyTimeout,
         ];
 
         for variant in variants {
@@ -323,8 +572,15 @@ mod tests {
 
     #[test]
     fn io_error_equality() {
-        assert_eq!(IoError::PhyError, IoError::PhyError);
-        assert_ne!(IoError::PhyError, IoError::Timeout);
+        assert_eq!(
+            IoError::PhyError(PhyError::Communication),
+            PhyError::Communication.into()
+        );
+        assert_ne!(
+            IoError::PhyError(PhyError::Communication),
+            IoError::PhyError(PhyError::ResetTimeout)
+        );
+        assert_ne!(IoError::PhyError(PhyError::Communication), IoError::Timeout);
     }
 
     // =========================================================================
@@ -444,4 +700,66 @@ mod tests {
 
         assert!(test_fn().is_err());
     }
+
+    #[test]
+    fn codes_are_unique_and_in_domain_range() {
+        let errors: [Error; 27] = [
+            ConfigError::AlreadyInitialized.into(),
+            ConfigError::InvalidConfig.into(),
+            ConfigError::InvalidPhyAddress.into(),
+            ConfigError::ClockError.into(),
+            ConfigError::GpioError.into(),
+            ConfigError::ResetFailed.into(),
+            ConfigError::InvalidMacAddress.into(),
+            DmaError::NoDescriptorsAvailable.into(),
+            DmaError::DescriptorBusy.into(),
+            DmaError::FrameTooLarge.into(),
+            DmaError::InvalidLength.into(),
+            DmaError::FatalBusError.into(),
+            DmaError::NoRxBuffers.into(),
+            DmaError::BufferNotDmaCapable.into(),
+            IoError::Timeout.into(),
+            IoError::InvalidState.into(),
+            IoError::BufferTooSmall.into(),
+            IoError::IncompleteFrame.into(),
+            IoError::FrameError.into(),
+            PhyError::Communication.into(),
+            IoError::RateLimited.into(),
+            IoError::FcsMismatch.into(),
+            IoError::Reentered.into(),
+            IoError::TxStopTimeout.into(),
+            IoError::FifoFlushTimeout.into(),
+            IoError::MiiBusyTimeout.into(),
+            PhyError::ResetTimeout.into(),
+        ];
+
+        for (i, a) in errors.iter().enumerate() {
+            let base = match a {
+                Error::Config(_) => 100,
+                Error::Dma(_) => 200,
+                Error::Io(_) => 300,
+            };
+            assert!(a.code() > base && a.code() < base + 100, "{a:?}");
+            assert!(errors[i + 1..].iter().all(|b| b.code() != a.code()));
+        }
+    }
+
+    #[test]
+    fn error_display_compact() {
+        let err = Error::Io(IoError::Timeout);
+        assert_eq!(format!("{err}"), "io: operation timed out");
+        assert_eq!(format!("{err:#}"), "E301");
+        assert_eq!(Error::from(DmaError::NoRxBuffers).code(), 206);
+        assert_eq!(Error::from(PhyError::ResetTimeout).code(), 313);
+    }
+
+    #[test]
+    fn routine_errors_are_backpressure_only() {
+        assert!(Error::from(DmaError::NoDescriptorsAvailable).is_routine());
+        assert!(Error::from(IoError::IncompleteFrame).is_routine());
+        assert!(Error::from(IoError::RateLimited).is_routine());
+        assert!(!Error::from(IoError::FrameError).is_routine());
+        assert!(!Error::from(DmaError::FatalBusError).is_routine());
+        assert!(!Error::from(ConfigError::ResetFailed).is_routine());
+    }
 }
@@ -0,0 +1,723 @@
+//! Fixed-size driver event log for postmortem analysis.
+//!
+//! The EMAC keeps the last [`EVENT_LOG_LEN`] driver events (state
+//! transitions, errors, link changes) in a small ring stored inside the
+//! [`Emac`](super::emac::Emac) itself. Nothing is allocated and nothing is
+//! printed; after a field failure the application reads the log with
+//! [`Emac::event_log`](super::emac::Emac::event_log) and dumps it however it
+//! likes.
+//!
+//! The driver has no clock of its own. Install one with
+//! [`Emac::set_event_clock`](super::emac::Emac::set_event_clock) to get
+//! timestamps; without it every event is stamped `0` and only the sequence
+//! number orders them.
+//!
+//! # Event Sinks
+//!
+//! A monitoring agent (an SNMP-like MIB, a telemetry uplink) that wants
+//! events as they happen rather than reading the log afterwards registers
+//! an [`EmacEventSink`] with
+//! [`Emac::set_event_sink`](super::emac::Emac::set_event_sink). It sees
+//! every logged event, with the same sequence number and timestamp, plus
+//! [`EventKind::Threshold`] crossings that
+//! [`Emac::check_thresholds`](super::emac::Emac::check_thresholds) detects
+//! against the configured [`EventThresholds`].
+//!
+//! ```ignore
+//! use core::sync::atomic::{AtomicU32, Ordering};
+//! use ph_esp32_mac::{EmacEventSink, Event, EventKind, EventThresholds};
+//!
+//! struct Agent {
+//!     link_changes: AtomicU32,
+//! }
+//!
+//! impl EmacEventSink for Agent {
+//!     fn on_event(&self, event: &Event) {
+//!         if let EventKind::LinkChanged { .. } | EventKind::LinkDown = event.kind {
+//!             self.link_changes.fetch_add(1, Ordering::Relaxed);
+//!         }
+//!     }
+//! }
+//!
+//! static AGENT: Agent = Agent { link_changes: AtomicU32::new(0) };
+//!
+//! emac.set_event_sink(Some(&AGENT));
+//! emac.set_event_thresholds(EventThresholds::new().with_rx_ring_occupancy(8))?;
+//! // Periodically:
+//! emac.check_thresholds();
+//! ```
+//!
+//! # Error-Rate Alarms
+//!
+//! A [`RateAlarm`] watches one [`AlarmCounter`] over a fixed time window,
+//! e.g. "10 CRC errors within 10 s" or "1 FIFO overflow per minute", and
+//! reports [`EventKind::Alarm`] once per window in which the counter rose by
+//! at least the limit. Windows are measured with the event clock (or the
+//! driver [`Clock`](crate::hal::Clock)) at each `check_thresholds()` call,
+//! so alarms need a clock and are only as precise as the check interval.
+//!
+//! ```ignore
+//! use ph_esp32_mac::{AlarmCounter, EventThresholds, RateAlarm};
+//!
+//! emac.set_event_thresholds(
+//!     EventThresholds::new()
+//!         .with_alarm(RateAlarm::new(AlarmCounter::RxCrcErrors, 10, 10_000))
+//!         .with_alarm(RateAlarm::new(AlarmCounter::RxFifoOverflows, 1, 60_000)),
+//! )?;
+//! ```
+
+use super::config::{Duplex, Speed, State};
+use super::duplex::DuplexMismatch;
+use super::error::{ConfigError, ConfigResult, Error};
+use super::stats::Statistics;
+
+/// Number of events kept in the log
+pub const EVENT_LOG_LEN: usize = 16;
+
+/// What happened
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum EventKind {
+    /// Driver state changed
+    StateChanged {
+        /// Previous state
+        from: State,
+        /// New state
+        to: State,
+    },
+    /// MAC speed/duplex reprogrammed
+    LinkChanged {
+        /// New speed
+        speed: Speed,
+        /// New duplex
+        duplex: Duplex,
+    },
+    /// Link reported down by the application
+    LinkDown,
+    /// A driver operation failed
+    Error(Error),
+    /// Error bits seen in the DMA status register (raw value)
+    InterruptError(u32),
+    /// Probable duplex mismatch flagged by `Emac::check_duplex_mismatch`
+    DuplexMismatch(DuplexMismatch),
+    /// Application-defined marker
+    Marker(u32),
+    /// A watched quantity crossed its [`EventThresholds`] level
+    Threshold {
+        /// What was measured
+        metric: ThresholdMetric,
+        /// Measured value
+        value: u32,
+        /// `true` when the value rose to the level or above, `false` when
+        /// it fell back below
+        above: bool,
+    },
+    /// A counter rose by at least its [`RateAlarm`] limit within one window
+    Alarm {
+        /// Counter that fired
+        counter: AlarmCounter,
+        /// Increase since the window started
+        count: u32,
+        /// Window length in milliseconds
+        window_ms: u32,
+    },
+}
+
+/// Quantity watched by `Emac::check_thresholds`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ThresholdMetric {
+    /// RX descriptors holding received data not yet read by `receive()`
+    RxRingOccupancy,
+    /// TX descriptors still owned by the DMA
+    TxRingOccupancy,
+    /// RX errors, software FCS mismatches and TX carrier/collision errors
+    /// since the previous check
+    ErrorRate,
+}
+
+impl ThresholdMetric {
+    const ALL: [Self; 3] = [
+        Self::RxRingOccupancy,
+        Self::TxRingOccupancy,
+        Self::ErrorRate,
+    ];
+}
+
+/// One logged event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Event {
+    /// Sequence number, counting every event ever recorded
+    pub seq: u32,
+    /// Value of the event clock when recorded (0 without a clock)
+    pub timestamp: u32,
+    /// What happened
+    pub kind: EventKind,
+}
+
+/// Ring of the most recent driver events
+///
+/// Oldest events are overwritten once the log is full; [`total`](Self::total)
+/// tells how many were recorded overall.
+#[derive(Debug, Clone, Copy)]
+pub struct EventLog {
+    entries: [Option<Event>; EVENT_LOG_LEN],
+    next: usize,
+    total: u32,
+    clock: Option<fn() -> u32>,
+}
+
+impl EventLog {
+    /// Create an empty log without a clock
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            entries: [None; EVENT_LOG_LEN],
+            next: 0,
+            total: 0,
+            clock: None,
+        }
+    }
+
+    /// Set the timestamp source (e.g. milliseconds since boot)
+    pub fn set_clock(&mut self, clock: Option<fn() -> u32>) {
+        self.clock = clock;
+    }
+
+    /// Current value of the clock (0 without one)
+    pub fn now(&self) -> u32 {
+        self.clock.map_or(0, |clock| clock())
+    }
+
+    /// True if a clock is installed
+    #[must_use]
+    pub const fn has_clock(&self) -> bool {
+        self.clock.is_some()
+    }
+
+    /// Record an event, overwriting the oldest one if full
+    ///
+    /// Returns the event as stored, with its sequence number and timestamp.
+    pub fn record(&mut self, kind: EventKind) -> Event {
+        self.record_at(kind, self.now())
+    }
+
+    /// Record an event stamped with `timestamp` instead of the log's clock
+    pub fn record_at(&mut self, kind: EventKind, timestamp: u32) -> Event {
+        let event = Event {
+            seq: self.total,
+            timestamp,
+            kind,
+        };
+        if let Some(entry) = self.entries.get_mut(self.next) {
+            *entry = Some(event);
+        }
+        self.next = (self.next + 1) % EVENT_LOG_LEN;
+        self.total = self.total.wrapping_add(1);
+        event
+    }
+
+    /// Number of events currently held
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.iter().filter(|e| e.is_some()).count()
+    }
+
+    /// True if nothing has been recorded since creation or [`clear`](Self::clear)
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.iter().all(Option::is_none)
+    }
+
+    /// Number of events recorded overall, including overwritten ones
+    #[must_use]
+    pub const fn total(&self) -> u32 {
+        self.total
+    }
+
+    /// Most recent event
+    #[must_use]
+    pub fn latest(&self) -> Option<Event> {
+        self.entries[(self.next + EVENT_LOG_LEN - 1) % EVENT_LOG_LEN]
+    }
+
+    /// Events from oldest to newest
+    pub fn iter(&self) -> impl Iterator<Item = Event> + '_ {
+        (0..EVENT_LOG_LEN).filter_map(move |i| self.entries[(self.next + i) % EVENT_LOG_LEN])
+    }
+
+    /// Drop all events (the clock and total count are kept)
+    pub fn clear(&mut self) {
+        self.entries = [None; EVENT_LOG_LEN];
+        self.next = 0;
+    }
+}
+
+impl Default for EventLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// =============================================================================
+// Event Sinks and Thresholds
+// =============================================================================
+
+/// Receiver for driver events as they are recorded
+///
+/// Called synchronously from whatever context made the driver call, which
+/// can be an interrupt handler, so keep it short: bump a counter, push to a
+/// queue, wake a task. `on_event` takes `&self`; keep state in atomics or
+/// behind a critical section.
+pub trait EmacEventSink: Sync {
+    /// Handle one event, exactly as it was entered in the event log
+    fn on_event(&self, event: &Event);
+}
+
+/// Number of [`RateAlarm`]s an [`EventThresholds`] holds
+pub const RATE_ALARM_SLOTS: usize = 4;
+
+/// Counter watched by a [`RateAlarm`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum AlarmCounter {
+    /// [`Statistics::rx_errors`]
+    RxErrors,
+    /// [`Statistics::rx_crc_errors`]
+    RxCrcErrors,
+    /// [`Statistics::rx_fcs_mismatch`]
+    RxFcsMismatch,
+    /// [`Statistics::rx_missed`]
+    RxMissed,
+    /// [`Statistics::rx_fifo_overflows`]
+    RxFifoOverflows,
+    /// Total of [`Statistics::tx_diagnostics`]
+    TxErrors,
+}
+
+impl AlarmCounter {
+    /// Current value of the counter in `stats`
+    #[must_use]
+    pub const fn read(&self, stats: &Statistics) -> u32 {
+        match self {
+            Self::RxErrors => stats.rx_errors,
+            Self::RxCrcErrors => stats.rx_crc_errors,
+            Self::RxFcsMismatch => stats.rx_fcs_mismatch,
+            Self::RxMissed => stats.rx_missed,
+            Self::RxFifoOverflows => stats.rx_fifo_overflows,
+            Self::TxErrors => stats.tx_diagnostics.total(),
+        }
+    }
+}
+
+/// Alarm on a counter rising by `limit` or more within `window_ms`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RateAlarm {
+    /// Counter to watch
+    pub counter: AlarmCounter,
+    /// Increase within one window that fires the alarm
+    pub limit: u32,
+    /// Window length in milliseconds
+    pub window_ms: u32,
+}
+
+impl RateAlarm {
+    /// Alarm when `counter` rises by `limit` within `window_ms`
+    #[must_use]
+    pub const fn new(counter: AlarmCounter, limit: u32, window_ms: u32) -> Self {
+        Self {
+            counter,
+            limit,
+            window_ms,
+        }
+    }
+}
+
+/// Levels at which `Emac::check_thresholds` reports crossings
+///
+/// `None` leaves a metric unwatched; all are unwatched by default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct EventThresholds {
+    /// See [`ThresholdMetric::RxRingOccupancy`]
+    pub rx_ring_occupancy: Option<u32>,
+    /// See [`ThresholdMetric::TxRingOccupancy`]
+    pub tx_ring_occupancy: Option<u32>,
+    /// See [`ThresholdMetric::ErrorRate`]
+    pub errors_per_check: Option<u32>,
+    /// Counter rate alarms, filled from the front
+    pub alarms: [Option<RateAlarm>; RATE_ALARM_SLOTS],
+    /// An alarm was added with every slot already taken; see
+    /// [`validate`](Self::validate)
+    pub alarm_overflow: bool,
+}
+
+impl EventThresholds {
+    /// Nothing watched
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            rx_ring_occupancy: None,
+            tx_ring_occupancy: None,
+            errors_per_check: None,
+            alarms: [None; RATE_ALARM_SLOTS],
+            alarm_overflow: false,
+        }
+    }
+
+    /// Watch RX ring occupancy against `descriptors`
+    #[must_use]
+    pub const fn with_rx_ring_occupancy(mut self, descriptors: u32) -> Self {
+        self.rx_ring_occupancy = Some(descriptors);
+        self
+    }
+
+    /// Watch TX ring occupancy against `descriptors`
+    #[must_use]
+    pub const fn with_tx_ring_occupancy(mut self, descriptors: u32) -> Self {
+        self.tx_ring_occupancy = Some(descriptors);
+        self
+    }
+
+    /// Watch the error count per check against `errors`
+    #[must_use]
+    pub const fn with_errors_per_check(mut self, errors: u32) -> Self {
+        self.errors_per_check = Some(errors);
+        self
+    }
+
+    /// Add a rate alarm in the first free slot
+    ///
+    /// With all [`RATE_ALARM_SLOTS`] taken the alarm is not stored and
+    /// [`validate`](Self::validate) fails.
+    #[must_use]
+    pub const fn with_alarm(mut self, alarm: RateAlarm) -> Self {
+        let mut i = 0;
+        while i < RATE_ALARM_SLOTS {
+            if self.alarms[i].is_none() {
+                self.alarms[i] = Some(alarm);
+                return self;
+            }
+            i += 1;
+        }
+        self.alarm_overflow = true;
+        self
+    }
+
+    /// Check that every requested alarm was stored
+    ///
+    /// # Errors
+    ///
+    /// - [`ConfigError::InvalidConfig`] if more alarms were added than
+    ///   [`RATE_ALARM_SLOTS`]
+    pub const fn validate(&self) -> ConfigResult<()> {
+        if self.alarm_overflow {
+            return Err(ConfigError::InvalidConfig);
+        }
+        Ok(())
+    }
+
+    /// Level for `metric`
+    #[must_use]
+    pub const fn level(&self, metric: ThresholdMetric) -> Option<u32> {
+        match metric {
+            ThresholdMetric::RxRingOccupancy => self.rx_ring_occupancy,
+            ThresholdMetric::TxRingOccupancy => self.tx_ring_occupancy,
+            ThresholdMetric::ErrorRate => self.errors_per_check,
+        }
+    }
+}
+
+/// Edge detector behind `Emac::check_thresholds`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ThresholdMonitor {
+    thresholds: EventThresholds,
+    above: [bool; 3],
+    prev_errors: Option<u32>,
+    /// Start time and counter value of each alarm's current window
+    windows: [Option<(u32, u32)>; RATE_ALARM_SLOTS],
+    /// The alarm already fired in its current window
+    fired: [bool; RATE_ALARM_SLOTS],
+}
+
+impl ThresholdMonitor {
+    pub(crate) const fn new() -> Self {
+        Self {
+            thresholds: EventThresholds::new(),
+            above: [false; 3],
+            prev_errors: None,
+            windows: [None; RATE_ALARM_SLOTS],
+            fired: [false; RATE_ALARM_SLOTS],
+        }
+    }
+
+    /// Replace the levels and forget previous crossings
+    pub(crate) fn set_thresholds(&mut self, thresholds: EventThresholds) {
+        *self = Self {
+            thresholds,
+            ..Self::new()
+        };
+    }
+
+    /// Compare new samples against the levels
+    ///
+    /// `errors` is a running error total; the rate is its increase since
+    /// the previous call (zero on the first). Returns a crossing event per
+    /// metric, `None` where nothing changed.
+    pub(crate) fn evaluate(
+        &mut self,
+        rx_occupancy: u32,
+        tx_occupancy: u32,
+        errors: u32,
+    ) -> [Option<EventKind>; 3] {
+        let rate = errors.wrapping_sub(self.prev_errors.unwrap_or(errors));
+        self.prev_errors = Some(errors);
+        let values = [rx_occupancy, tx_occupancy, rate];
+
+        core::array::from_fn(|i| {
+            let metric = ThresholdMetric::ALL[i];
+            let level = self.thresholds.level(metric)?;
+            let above = values[i] >= level;
+            if above == self.above[i] {
+                return None;
+            }
+            self.above[i] = above;
+            Some(EventKind::Threshold {
+                metric,
+                value: values[i],
+                above,
+            })
+        })
+    }
+
+    /// Check the rate alarms against `stats` at `now_ms`
+    ///
+    /// The first call for an alarm opens its window. An alarm fires once
+    /// per window, as soon as its counter has risen by the limit; a new
+    /// window opens at the first call after `window_ms` has elapsed. A
+    /// counter that went backwards (`reset_stats`) restarts the window.
+    pub(crate) fn evaluate_alarms(
+        &mut self,
+        stats: &Statistics,
+        now_ms: u32,
+    ) -> [Option<EventKind>; RATE_ALARM_SLOTS] {
+        core::array::from_fn(|i| {
+            let alarm = self.thresholds.alarms[i]?;
+            let value = alarm.counter.read(stats);
+            let base = match self.windows[i] {
+                Some((start, base))
+                    if now_ms.wrapping_sub(start) < alarm.window_ms && value >= base =>
+                {
+                    base
+                }
+                _ => {
+                    self.windows[i] = Some((now_ms, value));
+                    self.fired[i] = false;
+                    value
+                }
+            };
+            let count = value - base;
+            if self.fired[i] || count < alarm.limit {
+                return None;
+            }
+            self.fired[i] = true;
+            Some(EventKind::Alarm {
+                counter: alarm.counter,
+                count,
+                window_ms: alarm.window_ms,
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::driver::error::IoError;
+
+    #[test]
+    fn empty_log() {
+        let log = EventLog::new();
+        assert!(log.is_empty());
+        assert_eq!(log.len(), 0);
+        assert_eq!(log.latest(), None);
+        assert_eq!(log.iter().count(), 0);
+    }
+
+    #[test]
+    fn records_in_order() {
+        let mut log = EventLog::new();
+        log.record(EventKind::Marker(1));
+        log.record(EventKind::LinkDown);
+
+        let events: [Event; 2] = core::array::from_fn(|i| log.iter().nth(i).unwrap());
+        assert_eq!(events[0].kind, EventKind::Marker(1));
+        assert_eq!(events[0].seq, 0);
+        assert_eq!(events[1].kind, EventKind::LinkDown);
+        assert_eq!(events[1].seq, 1);
+        assert_eq!(log.latest().unwrap().kind, EventKind::LinkDown);
+    }
+
+    #[test]
+    fn wraps_keeping_newest() {
+        let mut log = EventLog::new();
+        for i in 0..(EVENT_LOG_LEN as u32 + 5) {
+            log.record(EventKind::Marker(i));
+        }
+
+        assert_eq!(log.len(), EVENT_LOG_LEN);
+        assert_eq!(log.total(), EVENT_LOG_LEN as u32 + 5);
+        assert_eq!(log.iter().next().unwrap().kind, EventKind::Marker(5));
+        assert_eq!(
+            log.latest().unwrap().kind,
+            EventKind::Marker(EVENT_LOG_LEN as u32 + 4)
+        );
+        assert!(
+            log.iter()
+                .zip(log.iter().skip(1))
+                .all(|(a, b)| b.seq == a.seq + 1)
+        );
+    }
+
+    #[test]
+    fn clock_stamps_events() {
+        fn clock() -> u32 {
+            1234
+        }
+
+        let mut log = EventLog::new();
+        log.record(EventKind::Error(IoError::Timeout.into()));
+        log.set_clock(Some(clock));
+        log.record(EventKind::Marker(0));
+
+        assert_eq!(log.iter().next().unwrap().timestamp, 0);
+        assert_eq!(log.latest().unwrap().timestamp, 1234);
+    }
+
+    #[test]
+    fn clear_keeps_total() {
+        let mut log = EventLog::new();
+        log.record(EventKind::LinkDown);
+        log.clear();
+        assert!(log.is_empty());
+        assert_eq!(log.total(), 1);
+    }
+
+    #[test]
+    fn threshold_crossings_are_edges() {
+        let mut monitor = ThresholdMonitor::new();
+        assert_eq!(monitor.evaluate(10, 10, 100), [None; 3]);
+
+        monitor.set_thresholds(
+            EventThresholds::new()
+                .with_rx_ring_occupancy(4)
+                .with_errors_per_check(3),
+        );
+        // First check only takes the error baseline
+        assert_eq!(monitor.evaluate(2, 10, 100), [None; 3]);
+        let [rx, tx, errors] = monitor.evaluate(5, 10, 103);
+        assert_eq!(
+            rx,
+            Some(EventKind::Threshold {
+                metric: ThresholdMetric::RxRingOccupancy,
+                value: 5,
+                above: true
+            })
+        );
+        assert_eq!(tx, None);
+        assert_eq!(
+            errors,
+            Some(EventKind::Threshold {
+                metric: ThresholdMetric::ErrorRate,
+                value: 3,
+                above: true
+            })
+        );
+
+        assert_eq!(monitor.evaluate(6, 10, 110), [None; 3]);
+        let [rx, _, errors] = monitor.evaluate(3, 10, 110);
+        assert!(matches!(
+            rx,
+            Some(EventKind::Threshold { above: false, .. })
+        ));
+        assert!(matches!(
+            errors,
+            Some(EventKind::Threshold {
+                value: 0,
+                above: false,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn rate_alarm_fires_once_per_window() {
+        let mut monitor = ThresholdMonitor::new();
+        monitor.set_thresholds(EventThresholds::new().with_alarm(RateAlarm::new(
+            AlarmCounter::RxCrcErrors,
+            3,
+            1_000,
+        )));
+        let mut stats = Statistics::new();
+        stats.rx_crc_errors = 50;
+
+        // Opens the window at 50 errors
+        assert_eq!(monitor.evaluate_alarms(&stats, 0), [None; RATE_ALARM_SLOTS]);
+        stats.rx_crc_errors = 52;
+        assert_eq!(
+            monitor.evaluate_alarms(&stats, 400),
+            [None; RATE_ALARM_SLOTS]
+        );
+        stats.rx_crc_errors = 54;
+        let [alarm, ..] = monitor.evaluate_alarms(&stats, 800);
+        assert_eq!(
+            alarm,
+            Some(EventKind::Alarm {
+                counter: AlarmCounter::RxCrcErrors,
+                count: 4,
+                window_ms: 1_000
+            })
+        );
+        stats.rx_crc_errors = 60;
+        assert_eq!(
+            monitor.evaluate_alarms(&stats, 900),
+            [None; RATE_ALARM_SLOTS]
+        );
+
+        // New window opens at 60; two errors stay below the limit
+        assert_eq!(
+            monitor.evaluate_alarms(&stats, 1_000),
+            [None; RATE_ALARM_SLOTS]
+        );
+        stats.rx_crc_errors = 62;
+        assert_eq!(
+            monitor.evaluate_alarms(&stats, 1_500),
+            [None; RATE_ALARM_SLOTS]
+        );
+
+        // A reset counter restarts the window instead of firing
+        stats.rx_crc_errors = 0;
+        assert_eq!(
+            monitor.evaluate_alarms(&stats, 1_600),
+            [None; RATE_ALARM_SLOTS]
+        );
+    }
+
+    #[test]
+    fn validate_rejects_more_alarms_than_slots() {
+        let alarm = RateAlarm::new(AlarmCounter::RxMissed, 1, 60_000);
+        let mut thresholds = EventThresholds::new();
+        for _ in 0..RATE_ALARM_SLOTS {
+            thresholds = thresholds.with_alarm(alarm);
+        }
+        assert_eq!(thresholds.alarms, [Some(alarm); RATE_ALARM_SLOTS]);
+        assert_eq!(thresholds.validate(), Ok(()));
+
+        let thresholds = thresholds.with_alarm(alarm);
+        assert!(thresholds.alarm_overflow);
+        assert_eq!(thresholds.alarms, [Some(alarm); RATE_ALARM_SLOTS]);
+        assert_eq!(thresholds.validate(), Err(ConfigError::InvalidConfig));
+    }
+}
@@ -0,0 +1,115 @@
+//! Frame check sequence (FCS) status of received frames.
+//!
+//! The MAC checks the CRC-32 of every frame and flags failures in the RX
+//! descriptor; [`Emac::last_rx_fcs`](super::emac::Emac::last_rx_fcs) reports
+//! that verdict for the last frame returned by `receive()`.
+//!
+//! With the `crc-verify` feature the driver can also recompute the CRC in
+//! software and compare it with the FCS the DMA left in the buffer, for
+//! applications that do not want to trust the hardware check alone. Enable
+//! it at runtime with `Emac::set_fcs_verify`; mismatching frames fail with
+//! [`IoError::FcsMismatch`](super::error::IoError::FcsMismatch) and are
+//! counted in [`Statistics`](super::stats::Statistics).
+//!
+//! Only Ethernet II frames are verified. For 802.3 length-field frames the
+//! MAC strips padding and FCS (`ACS`), so there is nothing left to check.
+
+use crate::internal::dma::bits::rdes0;
+
+/// FCS verdict for one received frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RxFcsStatus {
+    /// The MAC checked the FCS and reported no CRC error
+    pub hardware_ok: bool,
+    /// Software CRC-32 result; `None` if it was not run
+    pub software_ok: Option<bool>,
+}
+
+impl RxFcsStatus {
+    /// Hardware verdict from the frame's RDES0 status
+    #[must_use]
+    #[inline(always)]
+    pub const fn from_rdes0(rdes0_status: u32) -> Self {
+        Self {
+            hardware_ok: rdes0_status & rdes0::CRC_ERR == 0,
+            software_ok: None,
+        }
+    }
+
+    /// No check that was run reported a mismatch
+    #[must_use]
+    pub const fn passed(&self) -> bool {
+        self.hardware_ok && !matches!(self.software_ok, Some(false))
+    }
+}
+
+iram! {
+    /// Ethernet CRC-32 (IEEE 802.3, reflected, bitwise)
+    ///
+    /// Table-less to keep flash usage down; fast enough for occasional checks
+    /// but roughly 8 cycles per bit.
+    #[cfg(feature = "crc-verify")]
+    #[must_use]
+    pub fn crc32(data: &[u8]) -> u32 {
+        const POLY: u32 = 0xEDB8_8320;
+        let mut crc = 0xFFFF_FFFFu32;
+        for &byte in data {
+            crc ^= byte as u32;
+            for _ in 0..8 {
+                crc = if crc & 1 != 0 {
+                    (crc >> 1) ^ POLY
+                } else {
+                    crc >> 1
+                };
+            }
+        }
+        !crc
+    }
+}
+
+/// True if `fcs` (as received, least significant byte first) is the
+/// CRC-32 of `frame`
+#[cfg(feature = "crc-verify")]
+#[must_use]
+#[inline(always)]
+pub fn fcs_matches(frame: &[u8], fcs: [u8; 4]) -> bool {
+    crc32(frame) == u32::from_le_bytes(fcs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hardware_verdict_from_status() {
+        assert!(RxFcsStatus::from_rdes0(rdes0::FIRST_DESC | rdes0::LAST_DESC).hardware_ok);
+        assert!(!RxFcsStatus::from_rdes0(rdes0::CRC_ERR).hardware_ok);
+    }
+
+    #[test]
+    fn passed_requires_every_check() {
+        let mut status = RxFcsStatus::from_rdes0(0);
+        assert!(status.passed());
+        status.software_ok = Some(true);
+        assert!(status.passed());
+        status.software_ok = Some(false);
+        assert!(!status.passed());
+    }
+
+    #[cfg(feature = "crc-verify")]
+    #[test]
+    fn crc32_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(&[]), 0);
+    }
+
+    #[cfg(feature = "crc-verify")]
+    #[test]
+    fn fcs_matches_wire_order() {
+        let frame = [0x5Au8; 60];
+        let fcs = crc32(&frame).to_le_bytes();
+        assert!(fcs_matches(&frame, fcs));
+        assert!(!fcs_matches(&frame[..59], fcs));
+    }
+}
@@ -18,20 +18,49 @@
 //! The 64-bit hash table uses a CRC-based index. Note that collisions are
 //! possible - multiple addresses may map to the same bit.
 //!
+//! For IPv4 multicast, [`Emac::join_ipv4_multicast`] maps the group to its
+//! `01:00:5E` address and sets the matching hash bit in one call.
+//!
 //! # VLAN Filtering
 //!
 //! The MAC can filter frames based on 802.1Q VLAN tags, accepting only frames
 //! with a specific VLAN ID.
 //!
+//! # Sniffer Mode
+//!
+//! [`Emac::enter_sniffer_mode`] saves the complete filter state in a
+//! [`FilterSnapshot`] and makes the MAC receive every frame, including
+//! control frames and frames failing the VLAN or source address filters;
+//! [`Emac::exit_sniffer_mode`] puts the saved state back exactly. Snapshots
+//! can also be taken and restored directly with [`Emac::filter_snapshot`]
+//! and [`Emac::restore_filters`].
+//!
+//! # Startup Receive-All Window
+//!
+//! With `EmacConfig::startup_receive_all_ms` set, the MAC receives every
+//! frame for that long after RX is enabled, so a provisioning flow can
+//! catch the first broadcast while its filters are still being set up.
+//! Filter changes made meanwhile are programmed as usual; only the
+//! receive-all bit is held. The next receive after the window expires
+//! clears that bit in a single frame filter write, so the configured
+//! filter set takes over at once. Frames already in the RX ring are still
+//! delivered. [`Emac::end_receive_all_grace`] closes the window early.
+//!
 //! # Testing Notes
 //!
 //! These filtering features are advanced and have limited hardware validation
 //! so far. Treat them as best-effort until broader testing confirms behavior.
 
-use super::config::{MAC_FILTER_SLOTS, MacAddressFilter, MacFilterType};
+use super::config::{
+    ControlFrameFilter, MAC_FILTER_SLOTS, MacAddressFilter, MacFilterType, State,
+    ipv4_multicast_mac,
+};
 use super::emac::Emac;
-use super::error::{ConfigError, DmaError, Result};
-use crate::internal::register::mac::MacRegs;
+use super::error::{ConfigError, DmaError, IoError, Result};
+use crate::internal::register::mac::{
+    GMACFF_PCF_SHIFT, GMACFF_PR, GMACFF_RA, GmacFrameFilter, MacRegs, pcf,
+};
+use crate::logic::frame::hash_index;
 
 // =============================================================================
 // MAC Address Filtering
@@ -166,7 +195,7 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
     /// emac.add_hash_filter(&multicast_addr);
     /// ```
     pub fn add_hash_filter(&mut self, addr: &[u8; 6]) -> u8 {
-        let index = MacRegs::compute_hash_index(addr);
+        let index = hash_index(addr);
         MacRegs::set_hash_bit(index);
         index
     }
@@ -183,7 +212,7 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
     /// # Returns
     /// The hash index (0-63) that was cleared
     pub fn remove_hash_filter(&mut self, addr: &[u8; 6]) -> u8 {
-        let index = MacRegs::compute_hash_index(addr);
+        let index = hash_index(addr);
         MacRegs::clear_hash_bit(index);
         index
     }
@@ -196,7 +225,7 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
     /// # Returns
     /// `true` if the address's hash bit is set
     pub fn check_hash_filter(&self, addr: &[u8; 6]) -> bool {
-        let index = MacRegs::compute_hash_index(addr);
+        let index = hash_index(addr);
         MacRegs::is_hash_bit_set(index)
     }
 
@@ -228,12 +257,15 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
     /// # Arguments
     /// * `enable` - `true` to enable hash multicast filtering
     pub fn enable_hash_multicast(&mut self, enable: bool) {
-        MacRegs::enable_hash_multicast(enable);
-
         // If enabling hash multicast, disable pass-all-multicast
-        if enable {
-            MacRegs::set_pass_all_multicast(false);
-        }
+        MacRegs::update_frame_filter(|ff| {
+            let ff = ff.with_hash_multicast(enable);
+            if enable {
+                ff.with_pass_all_multicast(false)
+            } else {
+                ff
+            }
+        });
     }
 
     /// Enable hash-based unicast filtering
@@ -252,7 +284,48 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
     ///
     /// Useful for debugging or checking for potential collisions.
     pub fn compute_hash_index(addr: &[u8; 6]) -> u8 {
-        MacRegs::compute_hash_index(addr)
+        hash_index(addr)
+    }
+
+    /// Receive frames for an IPv4 multicast group
+    ///
+    /// Adds the group's Ethernet address to the hash filter and enables hash
+    /// multicast filtering (which turns off pass-all-multicast). Frames for
+    /// other groups sharing the address or hash bit still get through, so
+    /// check the IP destination in software.
+    ///
+    /// This only opens the MAC filter; sending the IGMP membership report
+    /// is up to the IP stack or application.
+    ///
+    /// # Returns
+    /// The hash index (0-63) that was set
+    ///
+    /// # Errors
+    /// - `InvalidConfig` - `group` is not in 224.0.0.0/4
+    ///
+    /// # Example
+    /// ```ignore
+    /// // mDNS
+    /// emac.join_ipv4_multicast([224, 0, 0, 251])?;
+    /// ```
+    pub fn join_ipv4_multicast(&mut self, group: [u8; 4]) -> Result<u8> {
+        let mac = ipv4_multicast_mac(group).ok_or(ConfigError::InvalidConfig)?;
+        let index = self.add_hash_filter(&mac);
+        self.enable_hash_multicast(true);
+        Ok(index)
+    }
+
+    /// Stop receiving frames for an IPv4 multicast group
+    ///
+    /// Clears the group's hash bit. **Warning:** this also drops every other
+    /// joined group on the same bit, see
+    /// [`remove_hash_filter`](Self::remove_hash_filter).
+    ///
+    /// # Errors
+    /// - `InvalidConfig` - `group` is not in 224.0.0.0/4
+    pub fn leave_ipv4_multicast(&mut self, group: [u8; 4]) -> Result<u8> {
+        let mac = ipv4_multicast_mac(group).ok_or(ConfigError::InvalidConfig)?;
+        Ok(self.remove_hash_filter(&mac))
     }
 }
 
@@ -312,3 +385,525 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
         MacRegs::get_vlan_id_filter()
     }
 }
+
+// =============================================================================
+// Batched Filter Updates
+// =============================================================================
+
+/// Frame filter bits changed together by [`Emac::configure_filtering`]
+///
+/// Starts from the current register value; every setter only changes the
+/// local copy, which is written back in one go.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameFilterUpdate {
+    bits: GmacFrameFilter,
+    control_frames: ControlFrameFilter,
+}
+
+impl FrameFilterUpdate {
+    /// Pass every frame regardless of its destination address
+    pub fn set_promiscuous(&mut self, enable: bool) -> &mut Self {
+        self.bits = self.bits.with_promiscuous(enable);
+        self
+    }
+
+    /// Pass all multicast frames
+    pub fn set_pass_all_multicast(&mut self, enable: bool) -> &mut Self {
+        self.bits = self.bits.with_pass_all_multicast(enable);
+        self
+    }
+
+    /// Receive broadcast frames
+    pub fn set_broadcast_enabled(&mut self, enable: bool) -> &mut Self {
+        self.bits = self.bits.with_disable_broadcast(!enable);
+        self
+    }
+
+    /// Filter multicast frames through the hash table
+    ///
+    /// Unlike [`Emac::enable_hash_multicast`] this leaves pass-all-multicast
+    /// alone; clear it in the same batch if needed.
+    pub fn set_hash_multicast(&mut self, enable: bool) -> &mut Self {
+        self.bits = self.bits.with_hash_multicast(enable);
+        self
+    }
+
+    /// Filter unicast frames through the hash table
+    pub fn set_hash_unicast(&mut self, enable: bool) -> &mut Self {
+        self.bits = self.bits.with_hash_unicast(enable);
+        self
+    }
+
+    /// Filter VLAN tagged frames against the VLAN tag register
+    pub fn set_vlan_filter(&mut self, enable: bool) -> &mut Self {
+        self.bits = self.bits.with_vlan_filter(enable);
+        self
+    }
+
+    /// Handling of MAC control frames
+    pub fn set_control_frames(&mut self, filter: ControlFrameFilter) -> &mut Self {
+        self.control_frames = filter;
+        self
+    }
+
+    /// Promiscuous mode as it will be written
+    pub fn promiscuous(&self) -> bool {
+        self.bits.promiscuous()
+    }
+
+    /// Pass-all-multicast as it will be written
+    pub fn pass_all_multicast(&self) -> bool {
+        self.bits.pass_all_multicast()
+    }
+
+    /// Broadcast reception as it will be written
+    pub fn broadcast_enabled(&self) -> bool {
+        !self.bits.disable_broadcast()
+    }
+
+    /// Hash multicast filtering as it will be written
+    pub fn hash_multicast(&self) -> bool {
+        self.bits.hash_multicast()
+    }
+
+    /// Hash unicast filtering as it will be written
+    pub fn hash_unicast(&self) -> bool {
+        self.bits.hash_unicast()
+    }
+
+    /// VLAN filtering as it will be written
+    pub fn vlan_filter(&self) -> bool {
+        self.bits.vlan_filter()
+    }
+
+    /// Control frame handling as it will be written
+    pub fn control_frames(&self) -> ControlFrameFilter {
+        self.control_frames
+    }
+}
+
+impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
+    Emac<RX_BUFS, TX_BUFS, BUF_SIZE>
+{
+    /// Change several frame filter settings in one register write
+    ///
+    /// The individual `set_*` calls each read-modify-write the frame filter
+    /// register, so an interrupt handler updating the same register between
+    /// them can have its change overwritten, and the MAC briefly runs with a
+    /// half-applied configuration. Here `f` edits a copy, which is written
+    /// back inside a critical section (with the `critical-section` feature;
+    /// without it, keep other writers out yourself). `f` runs inside that
+    /// critical section, so keep it short.
+    ///
+    /// ```ignore
+    /// emac.configure_filtering(|f| {
+    ///     f.set_pass_all_multicast(false).set_hash_multicast(true);
+    /// });
+    /// ```
+    pub fn configure_filtering(&mut self, f: impl FnOnce(&mut FrameFilterUpdate)) {
+        let mut control_frames = self.settings.control_frames;
+        let written = MacRegs::update_frame_filter(|bits| {
+            let mut update = FrameFilterUpdate {
+                bits,
+                control_frames,
+            };
+            f(&mut update);
+            control_frames = update.control_frames;
+            update.bits.with_pass_control(control_frames.pcf())
+        });
+        self.settings.promiscuous = written.promiscuous();
+        self.settings.control_frames = control_frames;
+    }
+}
+
+// =============================================================================
+// Initial Filters
+// =============================================================================
+
+impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
+    Emac<RX_BUFS, TX_BUFS, BUF_SIZE>
+{
+    /// Program `EmacConfig::filters` into the freshly reset filter registers
+    pub(crate) fn apply_filter_config(&mut self) {
+        let filters = self.config.filters;
+        for (i, filter) in filters.mac_filters.iter().enumerate() {
+            if let Some(filter) = filter {
+                let is_source = matches!(filter.filter_type, MacFilterType::Source);
+                MacRegs::set_mac_filter(i + 1, &filter.address, is_source, filter.byte_mask);
+            }
+        }
+        if filters.hash_table != 0 {
+            self.set_hash_table(filters.hash_table);
+        }
+        if filters.hash_multicast {
+            self.enable_hash_multicast(true);
+        }
+        if filters.hash_unicast {
+            self.enable_hash_unicast(true);
+        }
+        if let Some(vlan) = filters.vlan {
+            self.configure_vlan_filter(vlan.vid, vlan.vid_only, vlan.inverse, vlan.svlan);
+        }
+    }
+}
+
+// =============================================================================
+// Filter Snapshots and Sniffer Mode
+// =============================================================================
+
+/// Saved receive filter state
+///
+/// Holds the frame filter, hash table, VLAN tag and address filter
+/// registers together with the promiscuous and control frame settings.
+/// Take one with [`Emac::filter_snapshot`] and put it back with
+/// [`Emac::restore_filters`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FilterSnapshot {
+    frame_filter: u32,
+    hash_table: u64,
+    vlan_tag: u32,
+    /// (high, low) register pairs of address filter slots 1-4
+    mac_filters: [(u32, u32); MAC_FILTER_SLOTS],
+    promiscuous: bool,
+    control_frames: ControlFrameFilter,
+}
+
+impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
+    Emac<RX_BUFS, TX_BUFS, BUF_SIZE>
+{
+    /// Capture the current receive filter state
+    pub fn filter_snapshot(&self) -> FilterSnapshot {
+        let mut mac_filters = [(0, 0); MAC_FILTER_SLOTS];
+        for (i, regs) in mac_filters.iter_mut().enumerate() {
+            *regs = MacRegs::mac_filter_raw(i + 1).unwrap_or_default();
+        }
+        // The receive-all window is not part of the configured filters
+        let mut frame_filter = MacRegs::frame_filter();
+        if self.receive_all_since.is_some() {
+            frame_filter &= !GMACFF_RA;
+        }
+        FilterSnapshot {
+            frame_filter,
+            hash_table: self.hash_table(),
+            vlan_tag: MacRegs::vlan_tag(),
+            mac_filters,
+            promiscuous: self.settings.promiscuous,
+            control_frames: self.settings.control_frames,
+        }
+    }
+
+    /// Restore receive filter state captured by
+    /// [`filter_snapshot`](Self::filter_snapshot)
+    ///
+    /// Every filter register is rewritten, so changes made since the
+    /// snapshot are undone.
+    pub fn restore_filters(&mut self, snapshot: &FilterSnapshot) {
+        for (i, &(high, low)) in snapshot.mac_filters.iter().enumerate() {
+            MacRegs::set_mac_filter_raw(i + 1, high, low);
+        }
+        MacRegs::set_vlan_tag(snapshot.vlan_tag);
+        self.set_hash_table(snapshot.hash_table);
+        MacRegs::set_frame_filter(snapshot.frame_filter);
+        self.settings.promiscuous = snapshot.promiscuous;
+        self.settings.control_frames = snapshot.control_frames;
+    }
+
+    /// Receive every frame on the wire until
+    /// [`exit_sniffer_mode`](Self::exit_sniffer_mode)
+    ///
+    /// Saves the current filter state, then sets promiscuous and
+    /// receive-all mode with every control frame passed, so frames failing
+    /// the address, hash, VLAN or source address filters are delivered as
+    /// well. Does nothing if sniffer mode is already active. Filter changes
+    /// made while sniffing are discarded on exit.
+    ///
+    /// # Errors
+    /// - `InvalidState` - EMAC is not initialized
+    pub fn enter_sniffer_mode(&mut self) -> Result<()> {
+        if self.state() == State::Uninitialized {
+            return Err(IoError::InvalidState.into());
+        }
+        if self.sniffer.is_some() {
+            return Ok(());
+        }
+        self.sniffer = Some(self.filter_snapshot());
+        MacRegs::set_frame_filter(GMACFF_PR | GMACFF_RA | (pcf::ALL << GMACFF_PCF_SHIFT));
+        self.settings.promiscuous = true;
+        self.settings.control_frames = ControlFrameFilter::PassAll;
+        Ok(())
+    }
+
+    /// Leave sniffer mode and restore the filter state saved on entry
+    ///
+    /// Does nothing if sniffer mode is not active.
+    pub fn exit_sniffer_mode(&mut self) {
+        if let Some(snapshot) = self.sniffer.take() {
+            self.restore_filters(&snapshot);
+        }
+    }
+
+    /// True while sniffer mode is active
+    pub fn is_sniffer_mode(&self) -> bool {
+        self.sniffer.is_some()
+    }
+}
+
+// =============================================================================
+// Startup Receive-All Window
+// =============================================================================
+
+impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
+    Emac<RX_BUFS, TX_BUFS, BUF_SIZE>
+{
+    /// Open the configured receive-all window as RX is enabled
+    pub(super) fn begin_receive_all_grace(&mut self) {
+        if self.config.startup_receive_all_ms == 0 {
+            return;
+        }
+        MacRegs::update_frame_filter(|ff| ff.with_receive_all(true));
+        self.receive_all_since = Some(self.now());
+    }
+
+    iram! {
+        /// Close the receive-all window once it has expired
+        pub(super) fn poll_receive_all_grace(&mut self) {
+            if let Some(since) = self.receive_all_since
+                && self.now().wrapping_sub(since) >= self.config.startup_receive_all_ms
+            {
+                self.end_receive_all_grace();
+            }
+        }
+
+        /// Close the startup receive-all window now
+        ///
+        /// The configured filter set takes over in one frame filter write.
+        /// Does nothing if no window is open. In sniffer mode the MAC keeps
+        /// receiving everything until [`exit_sniffer_mode`](Self::exit_sniffer_mode).
+        pub fn end_receive_all_grace(&mut self) {
+            if self.receive_all_since.take().is_none() {
+                return;
+            }
+            // A sniffer snapshot was taken without the receive-all bit
+            if self.sniffer.is_none() {
+                MacRegs::update_frame_filter(|ff| ff.with_receive_all(false));
+            }
+        }
+    }
+
+    /// True while the startup receive-all window is open
+    pub fn is_receive_all_grace(&self) -> bool {
+        self.receive_all_since.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::driver::error::Error;
+    use crate::internal::register::mac::{GMACFF_HMC, GMACFF_PM};
+    use crate::internal::register::sim;
+
+    #[test]
+    fn join_ipv4_multicast_sets_hash_bit() {
+        sim::reset();
+        let mut emac: Emac<2, 2, 256> = Emac::new();
+        emac.set_pass_all_multicast(true);
+
+        let index = emac.join_ipv4_multicast([224, 0, 0, 251]).unwrap();
+        assert_eq!(emac.hash_table(), 1u64 << index);
+        assert!(emac.check_hash_filter(&[0x01, 0x00, 0x5E, 0x00, 0x00, 0xFB]));
+        assert_ne!(MacRegs::frame_filter() & GMACFF_HMC, 0);
+        assert_eq!(MacRegs::frame_filter() & GMACFF_PM, 0);
+
+        assert_eq!(emac.leave_ipv4_multicast([224, 0, 0, 251]), Ok(index));
+        assert_eq!(emac.hash_table(), 0);
+    }
+
+    #[test]
+    fn configure_filtering_writes_batch_once() {
+        use crate::internal::register::mac::{GMACFF_DBF, GMACFF_PCF_MASK};
+
+        sim::reset();
+        let mut emac: Emac<2, 2, 256> = Emac::new();
+        emac.set_pass_all_multicast(true);
+        emac.set_broadcast_enabled(false);
+
+        emac.configure_filtering(|f| {
+            assert!(f.pass_all_multicast());
+            f.set_pass_all_multicast(false)
+                .set_hash_multicast(true)
+                .set_promiscuous(true)
+                .set_control_frames(ControlFrameFilter::PassAll);
+        });
+
+        let ff = MacRegs::frame_filter();
+        assert_eq!(ff & GMACFF_PM, 0);
+        assert_ne!(ff & GMACFF_HMC, 0);
+        assert_ne!(ff & GMACFF_PR, 0);
+        // Untouched bits are kept
+        assert_ne!(ff & GMACFF_DBF, 0);
+        assert_eq!((ff & GMACFF_PCF_MASK) >> GMACFF_PCF_SHIFT, pcf::ALL);
+        assert!(emac.settings().promiscuous);
+        assert_eq!(emac.settings().control_frames, ControlFrameFilter::PassAll);
+    }
+
+    #[test]
+    fn join_rejects_non_multicast_group() {
+        sim::reset();
+        let mut emac: Emac<2, 2, 256> = Emac::new();
+        assert_eq!(
+            emac.join_ipv4_multicast([10, 0, 0, 1]),
+            Err(Error::Config(ConfigError::InvalidConfig))
+        );
+        assert_eq!(emac.hash_table(), 0);
+    }
+
+    #[test]
+    fn init_programs_configured_filters() {
+        sim::reset();
+        let mut emac: Emac<2, 2, 256> = Emac::new();
+        let group = [0x01, 0x00, 0x5E, 0x01, 0x02, 0x03];
+        let config = crate::EmacConfig::new()
+            .with_mac_filter(MacAddressFilter::new([0x02, 0, 0, 0, 0, 0x42]))
+            .with_mac_filter(MacAddressFilter::with_mask([0x33, 0x33, 0, 0, 0, 0], 0x3C))
+            .with_hash_address(group)
+            .with_vlan_filter(crate::VlanFilter::new(42));
+        emac.sim_init(config);
+
+        assert_eq!(emac.mac_filter_count(), 2);
+        assert_eq!(MacRegs::find_mac_filter(&[0x02, 0, 0, 0, 0, 0x42]), Some(1));
+        assert_eq!(MacRegs::find_mac_filter(&[0x33, 0x33, 0, 0, 0, 0]), Some(2));
+        assert!(emac.check_hash_filter(&group));
+        assert_ne!(MacRegs::frame_filter() & GMACFF_HMC, 0);
+        assert!(emac.is_vlan_filter_enabled());
+        assert_eq!(emac.vlan_filter_id(), 42);
+    }
+
+    #[test]
+    fn default_config_leaves_filters_untouched() {
+        sim::reset();
+        let mut emac: Emac<2, 2, 256> = Emac::new();
+        emac.sim_init(crate::EmacConfig::new());
+
+        assert_eq!(emac.mac_filter_count(), 0);
+        assert_eq!(emac.hash_table(), 0);
+        assert_eq!(MacRegs::frame_filter() & GMACFF_HMC, 0);
+        assert!(!emac.is_vlan_filter_enabled());
+    }
+
+    #[test]
+    fn sniffer_mode_restores_filters_exactly() {
+        sim::reset();
+        let mut emac: Emac<2, 2, 256> = Emac::new();
+        assert_eq!(
+            emac.enter_sniffer_mode(),
+            Err(Error::Io(IoError::InvalidState))
+        );
+        emac.sim_init(crate::EmacConfig::new());
+
+        emac.add_mac_filter(&[0x02, 0, 0, 0, 0, 0x42]).unwrap();
+        emac.set_vlan_filter(100);
+        emac.join_ipv4_multicast([239, 1, 2, 3]).unwrap();
+        let before = emac.filter_snapshot();
+
+        emac.enter_sniffer_mode().unwrap();
+        assert!(emac.is_sniffer_mode());
+        let ff = MacRegs::frame_filter();
+        assert_ne!(ff & GMACFF_RA, 0);
+        assert_ne!(ff & GMACFF_PR, 0);
+        assert_eq!(emac.settings().control_frames, ControlFrameFilter::PassAll);
+
+        // Changes while sniffing are discarded on exit
+        emac.clear_mac_filters();
+        emac.clear_hash_table();
+        emac.exit_sniffer_mode();
+
+        assert!(!emac.is_sniffer_mode());
+        assert_eq!(emac.filter_snapshot(), before);
+        assert!(emac.is_vlan_filter_enabled());
+        assert_eq!(emac.mac_filter_count(), 1);
+        assert_eq!(
+            emac.settings().control_frames,
+            ControlFrameFilter::DropPause
+        );
+    }
+
+    #[test]
+    fn startup_receive_all_window_closes_into_configured_filters() {
+        use crate::driver::config::EmacConfig;
+        use crate::internal::register::MAC_BASE;
+        use crate::internal::register::mac::GMACFF_OFFSET;
+        use core::sync::atomic::{AtomicU32, Ordering};
+
+        static NOW: AtomicU32 = AtomicU32::new(0);
+        fn now() -> u32 {
+            NOW.load(Ordering::Relaxed)
+        }
+
+        sim::reset();
+        let mut emac: Emac<4, 4, 256> = Emac::new();
+        emac.sim_init(EmacConfig::new().with_startup_receive_all_ms(500));
+        emac.set_event_clock(Some(now));
+        NOW.store(1000, Ordering::Relaxed);
+        assert!(!emac.is_receive_all_grace());
+        emac.start().unwrap();
+        assert!(emac.is_receive_all_grace());
+        assert_ne!(MacRegs::frame_filter() & GMACFF_RA, 0);
+
+        // Filters set up during the window are kept, but not yet in effect
+        emac.enable_hash_multicast(true);
+        assert_eq!(emac.filter_snapshot().frame_filter & GMACFF_RA, 0);
+        let mut buf = [0u8; 256];
+        NOW.store(1499, Ordering::Relaxed);
+        let _ = emac.receive(&mut buf);
+        assert_ne!(MacRegs::frame_filter() & GMACFF_RA, 0);
+
+        // The first receive after the window applies the filters in one write
+        NOW.store(1500, Ordering::Relaxed);
+        let writes = sim::write_count(MAC_BASE + GMACFF_OFFSET);
+        let _ = emac.receive(&mut buf);
+        assert!(!emac.is_receive_all_grace());
+        assert_eq!(MacRegs::frame_filter() & GMACFF_RA, 0);
+        assert_ne!(MacRegs::frame_filter() & GMACFF_HMC, 0);
+        assert_eq!(sim::write_count(MAC_BASE + GMACFF_OFFSET), writes + 1);
+    }
+
+    #[test]
+    fn deferred_rx_opens_receive_all_window_on_enable() {
+        use crate::driver::config::EmacConfig;
+
+        sim::reset();
+        let mut emac: Emac<4, 4, 256> = Emac::new();
+        emac.sim_init(
+            EmacConfig::new()
+                .with_deferred_rx(true)
+                .with_startup_receive_all_ms(500),
+        );
+        emac.start().unwrap();
+        assert!(!emac.is_receive_all_grace());
+        emac.enable_rx().unwrap();
+        assert!(emac.is_receive_all_grace());
+
+        // Closed early by the application
+        emac.end_receive_all_grace();
+        assert!(!emac.is_receive_all_grace());
+        assert_eq!(MacRegs::frame_filter() & GMACFF_RA, 0);
+    }
+
+    #[test]
+    fn receive_all_window_ending_in_sniffer_mode_keeps_sniffing() {
+        use crate::driver::config::EmacConfig;
+
+        sim::reset();
+        let mut emac: Emac<4, 4, 256> = Emac::new();
+        emac.sim_init(EmacConfig::new().with_startup_receive_all_ms(500));
+        emac.start().unwrap();
+        emac.enter_sniffer_mode().unwrap();
+
+        emac.end_receive_all_grace();
+        assert_ne!(MacRegs::frame_filter() & GMACFF_RA, 0);
+        emac.exit_sniffer_mode();
+        assert_eq!(MacRegs::frame_filter() & GMACFF_RA, 0);
+    }
+}
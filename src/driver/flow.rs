@@ -15,10 +15,12 @@
 //! # Example
 //!
 //! ```ignore
-//! // After PHY auto-negotiation completes
-//! if link_partner_supports_pause {
-//!     emac.set_peer_pause_ability(true);
-//!     emac.enable_flow_control(true);
+//! emac.enable_flow_control(true);
+//!
+//! // After PHY auto-negotiation completes; sets the peer PAUSE ability
+//! // from the resolved `tx_pause` / `rx_pause`
+//! if let Some(link) = phy.poll_link(&mut mdio)? {
+//!     emac.apply_link_status(&link);
 //! }
 //!
 //! // In your RX processing loop
@@ -41,15 +43,15 @@ use crate::internal::register::mac::MacRegs;
 impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
     Emac<RX_BUFS, TX_BUFS, BUF_SIZE>
 {
-    fn apply_flow_control(&mut self, enable: bool) {
-        if enable {
-            let fc = &self.config.flow_control;
+    pub(super) fn apply_flow_control(&mut self, enable: bool) {
+        if enable && self.peer_pause_ability() {
+            let fc = &self.settings.flow_control;
             MacRegs::configure_flow_control(
                 fc.pause_time,
                 fc.pause_low_threshold as u8,
                 fc.unicast_pause_detect,
-                true, // TX flow control
-                true, // RX flow control
+                self.pause_tx,
+                self.pause_rx,
             );
         } else {
             MacRegs::configure_flow_control(0, 0, false, false, false);
@@ -68,8 +70,8 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
     ///
     /// [`set_peer_pause_ability`]: Self::set_peer_pause_ability
     pub fn enable_flow_control(&mut self, enable: bool) {
-        self.config.flow_control.enabled = enable;
-        self.apply_flow_control(enable && self.peer_pause_ability);
+        self.settings.flow_control.enabled = enable;
+        self.apply_flow_control(enable);
     }
 
     /// Set peer PAUSE frame ability
@@ -82,6 +84,9 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
     /// # Arguments
     /// * `ability` - true if peer advertised PAUSE capability
     ///
+    /// This enables PAUSE in both directions; see
+    /// [`set_peer_pause`](Self::set_peer_pause) for an asymmetric result.
+    ///
     /// # Example
     /// ```ignore
     /// // After reading link partner abilities from PHY ANLPAR register
@@ -89,10 +94,22 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
     /// emac.set_peer_pause_ability(supports_pause);
     /// ```
     pub fn set_peer_pause_ability(&mut self, ability: bool) {
-        self.peer_pause_ability = ability;
+        self.set_peer_pause(ability, ability);
+    }
+
+    /// Set the PAUSE directions resolved for the link
+    ///
+    /// `tx` lets the MAC send PAUSE frames (TX flow control), `rx` makes
+    /// it honour received ones (RX flow control). Auto-negotiation can
+    /// resolve only one of them when either side advertises asymmetric
+    /// PAUSE; [`apply_link_status`](Self::apply_link_status) passes the
+    /// result through.
+    pub fn set_peer_pause(&mut self, tx: bool, rx: bool) {
+        self.pause_tx = tx;
+        self.pause_rx = rx;
 
         // Re-configure flow control based on new peer ability
-        self.apply_flow_control(self.config.flow_control.enabled && ability);
+        self.apply_flow_control(self.settings.flow_control.enabled);
     }
 
     /// Check if flow control action is needed and send PAUSE frame if necessary
@@ -112,12 +129,12 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
     /// }
     /// ```
     pub fn check_flow_control(&mut self) -> bool {
-        // Only do flow control if enabled and peer supports it
-        if !self.config.flow_control.enabled || !self.peer_pause_ability {
+        // Only send PAUSE if enabled and resolved for our TX direction
+        if !self.settings.flow_control.enabled || !self.pause_tx {
             return false;
         }
 
-        let fc = &self.config.flow_control;
+        let fc = &self.settings.flow_control;
         let free_descriptors = self.dma.rx_free_count();
         let frames_remain = self.rx_frames_waiting() > 0;
 
@@ -150,14 +167,20 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
     /// Get flow control configuration
     #[inline(always)]
     pub fn flow_control_config(&self) -> &FlowControlConfig {
-        &self.config.flow_control
+        &self.settings.flow_control
     }
 
     /// Get peer PAUSE ability
     ///
-    /// Returns `true` if the link partner supports PAUSE frames.
+    /// Returns `true` if PAUSE is resolved in at least one direction.
     #[inline(always)]
     pub fn peer_pause_ability(&self) -> bool {
-        self.peer_pause_ability
+        self.pause_tx || self.pause_rx
+    }
+
+    /// Resolved PAUSE directions as `(tx, rx)`
+    #[inline(always)]
+    pub fn peer_pause(&self) -> (bool, bool) {
+        (self.pause_tx, self.pause_rx)
     }
 }
@@ -0,0 +1,84 @@
+//! Caller-owned frame buffers for batched receive.
+//!
+//! [`Emac::receive_batch`](super::emac::Emac::receive_batch) fills a slice
+//! of [`FrameBuf`]s in one ring pass. Keep the pool in a `static` or on the
+//! ingest task's stack and reuse it between batches:
+//!
+//! ```ignore
+//! use ph_esp32_mac::FrameBuf;
+//!
+//! let mut pool = [const { FrameBuf::new() }; 8];
+//! loop {
+//!     let n = shared.with(|emac| emac.receive_batch(&mut pool));
+//!     for frame in &pool[..n] {
+//!         handle_datagram(frame.as_slice());
+//!     }
+//! }
+//! ```
+
+use crate::internal::constants::MAX_FRAME_SIZE;
+
+/// A frame buffer holding up to `N` bytes and the length received into it
+///
+/// The default size takes any standard frame, VLAN-tagged included.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameBuf<const N: usize = MAX_FRAME_SIZE> {
+    data: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> Default for FrameBuf<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> FrameBuf<N> {
+    /// Empty buffer
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            data: [0; N],
+            len: 0,
+        }
+    }
+
+    /// The received frame (destination MAC onward, without FCS)
+    #[must_use]
+    pub fn as_slice(&self) -> &[u8] {
+        self.data.get(..self.len).unwrap_or_default()
+    }
+
+    /// Frame length in bytes
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// True if no frame has been received into the buffer
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Bytes the buffer can hold
+    #[must_use]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Forget the frame
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// The whole storage, for receiving into
+    pub(crate) fn storage_mut(&mut self) -> &mut [u8] {
+        &mut self.data
+    }
+
+    /// Record a frame of `len` bytes received into the storage
+    pub(crate) fn set_len(&mut self, len: usize) {
+        self.len = len.min(N);
+    }
+}
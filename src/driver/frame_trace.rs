@@ -0,0 +1,394 @@
+//! Descriptor-level trace of transmitted frames.
+//!
+//! When a frame "disappears" between the network stack and the wire, the
+//! statistics only say that something went wrong somewhere. With the
+//! `frame-trace` feature the EMAC keeps the last [`FRAME_TRACE_LEN`]
+//! transmit attempts in a ring: when each was submitted, which descriptors
+//! it occupied, when the DMA released them and with which TDES0 status
+//! bits. Each entry ends in a [`TraceOutcome`] that answers the question
+//! directly:
+//!
+//! - [`Rejected`](TraceOutcome::Rejected): refused at submit (ring full,
+//!   frame too large, ...); it never reached a descriptor
+//! - [`Failed`](TraceOutcome::Failed): the DMA completed it with error
+//!   bits set (see [`TxDiagnostics`](super::stats::TxDiagnostics))
+//! - [`Pending`](TraceOutcome::Pending) long after submission: the DMA
+//!   never released its descriptors
+//!
+//! Completion is observed, not interrupt-driven: the trace is updated
+//! whenever a frame is queued and by
+//! [`Emac::collect_tx_status`](super::emac::Emac::collect_tx_status), so
+//! completion ticks are the time the driver noticed, not the time the frame
+//! left. Ticks come from the event clock
+//! ([`Emac::set_event_clock`](super::emac::Emac::set_event_clock)).
+//!
+//! # Example
+//!
+//! ```ignore
+//! emac.collect_tx_status();
+//! for entry in emac.frame_trace().iter() {
+//!     println!("{entry}");
+//! }
+//! // or all at once:
+//! print!("{}", emac.frame_trace());
+//! ```
+
+use super::error::Error;
+use crate::internal::dma::bits::tdes0;
+
+/// Number of transmit attempts kept in the trace
+pub const FRAME_TRACE_LEN: usize = 32;
+
+/// How a traced frame ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TraceOutcome {
+    /// Queued; the DMA has not released the last descriptor yet
+    Pending,
+    /// Released by the DMA without error bits
+    Sent,
+    /// Released by the DMA with the TDES0 error summary set
+    Failed,
+    /// Refused before any descriptor was used
+    Rejected(Error),
+    /// Released by the DMA, but its descriptors were reused before the
+    /// status could be read
+    Superseded,
+    /// Still queued when the rings were reset by `start()`
+    Abandoned,
+}
+
+impl TraceOutcome {
+    /// Short description
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Sent => "sent",
+            Self::Failed => "failed",
+            Self::Rejected(_) => "rejected",
+            Self::Superseded => "superseded",
+            Self::Abandoned => "abandoned",
+        }
+    }
+}
+
+impl core::fmt::Display for TraceOutcome {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Rejected(err) => write!(f, "rejected ({err})"),
+            _ => f.write_str(self.as_str()),
+        }
+    }
+}
+
+/// One traced transmit attempt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FrameTrace {
+    /// Frame id, counting every traced attempt
+    pub id: u32,
+    /// Frame length in bytes
+    pub len: u16,
+    /// Event clock when `transmit*()` was called
+    pub submitted: u32,
+    /// Index of the first TX descriptor used
+    pub first_desc: u8,
+    /// Number of descriptors used (0 if rejected)
+    pub desc_count: u8,
+    /// Event clock when the driver saw the DMA release the frame
+    pub completed: Option<u32>,
+    /// TDES0 of the last descriptor when released (0 until then)
+    pub status: u32,
+    /// How the attempt ended so far
+    pub outcome: TraceOutcome,
+}
+
+impl FrameTrace {
+    /// Index of the last descriptor in a ring of `slots` descriptors
+    #[must_use]
+    pub const fn last_desc(&self, slots: usize) -> usize {
+        if slots == 0 {
+            return 0;
+        }
+        (self.first_desc as usize + self.desc_count.saturating_sub(1) as usize) % slots
+    }
+
+    /// True if the frame occupies descriptor `idx` of a ring of `slots`
+    const fn uses(&self, idx: usize, slots: usize) -> bool {
+        if slots == 0 {
+            return false;
+        }
+        (idx + slots - self.first_desc as usize % slots) % slots < self.desc_count as usize
+    }
+}
+
+impl core::fmt::Display for FrameTrace {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "#{} len={} submit@{}", self.id, self.len, self.submitted)?;
+        if self.desc_count > 0 {
+            write!(f, " desc={}+{}", self.first_desc, self.desc_count)?;
+        }
+        if let Some(at) = self.completed {
+            write!(f, " done@{at} tdes0=0x{:08x}", self.status)?;
+        }
+        write!(f, " {}", self.outcome)
+    }
+}
+
+/// Ring of the most recent transmit attempts
+///
+/// Oldest entries are overwritten once the ring is full;
+/// [`total`](Self::total) tells how many attempts were traced overall.
+#[derive(Debug, Clone, Copy)]
+pub struct FrameTraceLog {
+    entries: [Option<FrameTrace>; FRAME_TRACE_LEN],
+    next: usize,
+    total: u32,
+}
+
+impl FrameTraceLog {
+    /// Create an empty trace
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            entries: [None; FRAME_TRACE_LEN],
+            next: 0,
+            total: 0,
+        }
+    }
+
+    fn push(&mut self, entry: FrameTrace) {
+        if let Some(slot) = self.entries.get_mut(self.next) {
+            *slot = Some(entry);
+        }
+        self.next = (self.next + 1) % FRAME_TRACE_LEN;
+        self.total = self.total.wrapping_add(1);
+    }
+
+    /// Record a frame queued on descriptors `first..first + count` of a
+    /// ring of `slots`
+    ///
+    /// Pending frames on the same descriptors must have been released by
+    /// the DMA for the slots to be reused; they become
+    /// [`Superseded`](TraceOutcome::Superseded).
+    pub(crate) fn record_submit(
+        &mut self,
+        now: u32,
+        len: usize,
+        first: usize,
+        count: usize,
+        slots: usize,
+    ) {
+        let entry = FrameTrace {
+            id: self.total,
+            len: len as u16,
+            submitted: now,
+            first_desc: first as u8,
+            desc_count: count as u8,
+            completed: None,
+            status: 0,
+            outcome: TraceOutcome::Pending,
+        };
+        for pending in self.pending_mut() {
+            if (0..count).any(|i| pending.uses((first + i) % slots.max(1), slots)) {
+                pending.completed = Some(now);
+                pending.outcome = TraceOutcome::Superseded;
+            }
+        }
+        self.push(entry);
+    }
+
+    /// Record a frame refused with `error`
+    pub(crate) fn record_rejected(&mut self, now: u32, len: usize, error: Error) {
+        self.push(FrameTrace {
+            id: self.total,
+            len: len as u16,
+            submitted: now,
+            first_desc: 0,
+            desc_count: 0,
+            completed: None,
+            status: 0,
+            outcome: TraceOutcome::Rejected(error),
+        });
+    }
+
+    /// Complete pending frames whose last descriptor the DMA released
+    ///
+    /// `released` returns the TDES0 of a descriptor, or `None` while the
+    /// DMA still owns it.
+    pub(crate) fn update(
+        &mut self,
+        now: u32,
+        slots: usize,
+        released: impl Fn(usize) -> Option<u32>,
+    ) {
+        for pending in self.pending_mut() {
+            if let Some(status) = released(pending.last_desc(slots)) {
+                pending.completed = Some(now);
+                pending.status = status;
+                pending.outcome = if status & tdes0::ERR_SUMMARY != 0 {
+                    TraceOutcome::Failed
+                } else {
+                    TraceOutcome::Sent
+                };
+            }
+        }
+    }
+
+    /// Mark all pending frames [`Abandoned`](TraceOutcome::Abandoned)
+    pub(crate) fn abandon_pending(&mut self, now: u32) {
+        for pending in self.pending_mut() {
+            pending.completed = Some(now);
+            pending.outcome = TraceOutcome::Abandoned;
+        }
+    }
+
+    fn pending_mut(&mut self) -> impl Iterator<Item = &mut FrameTrace> {
+        self.entries
+            .iter_mut()
+            .flatten()
+            .filter(|entry| entry.outcome == TraceOutcome::Pending)
+    }
+
+    /// Number of entries currently held
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.iter().filter(|e| e.is_some()).count()
+    }
+
+    /// True if nothing has been traced since creation or [`clear`](Self::clear)
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.iter().all(Option::is_none)
+    }
+
+    /// Number of attempts traced overall, including overwritten ones
+    #[must_use]
+    pub const fn total(&self) -> u32 {
+        self.total
+    }
+
+    /// Number of frames the DMA has not released yet
+    #[must_use]
+    pub fn pending(&self) -> usize {
+        self.iter()
+            .filter(|entry| entry.outcome == TraceOutcome::Pending)
+            .count()
+    }
+
+    /// Entry with frame id `id`, if still held
+    #[must_use]
+    pub fn get(&self, id: u32) -> Option<FrameTrace> {
+        self.iter().find(|entry| entry.id == id)
+    }
+
+    /// Entries from oldest to newest
+    pub fn iter(&self) -> impl Iterator<Item = FrameTrace> + '_ {
+        (0..FRAME_TRACE_LEN).filter_map(move |i| {
+            self.entries
+                .get((self.next + i) % FRAME_TRACE_LEN)
+                .copied()
+                .flatten()
+        })
+    }
+
+    /// Drop all entries (the total count is kept)
+    pub fn clear(&mut self) {
+        self.entries = [None; FRAME_TRACE_LEN];
+        self.next = 0;
+    }
+}
+
+impl Default for FrameTraceLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl core::fmt::Display for FrameTraceLog {
+    /// One line per entry, oldest first
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for entry in self.iter() {
+            writeln!(f, "{entry}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::std_instead_of_alloc)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::driver::error::DmaError;
+    use std::format;
+    use std::vec::Vec;
+
+    #[test]
+    fn submit_complete_and_fail() {
+        let mut trace = FrameTraceLog::new();
+        trace.record_submit(10, 60, 0, 1, 4);
+        trace.record_submit(11, 3000, 1, 2, 4);
+        assert_eq!(trace.pending(), 2);
+
+        // Only descriptor 2, the second frame's last, is released
+        trace.update(20, 4, |idx| (idx == 2).then_some(tdes0::ERR_SUMMARY));
+        let first = trace.get(0).unwrap();
+        assert_eq!(first.outcome, TraceOutcome::Pending);
+        let second = trace.get(1).unwrap();
+        assert_eq!(second.outcome, TraceOutcome::Failed);
+        assert_eq!(second.completed, Some(20));
+        assert_eq!(second.last_desc(4), 2);
+
+        trace.update(30, 4, |_| Some(0));
+        assert_eq!(trace.get(0).unwrap().outcome, TraceOutcome::Sent);
+        assert_eq!(trace.pending(), 0);
+        assert_eq!(
+            format!("{}", trace.get(0).unwrap()),
+            "#0 len=60 submit@10 desc=0+1 done@30 tdes0=0x00000000 sent"
+        );
+    }
+
+    #[test]
+    fn rejected_superseded_and_abandoned() {
+        let mut trace = FrameTraceLog::new();
+        trace.record_rejected(5, 1600, DmaError::NoDescriptorsAvailable.into());
+        // Wraps around the end of a 4-slot ring
+        trace.record_submit(6, 3000, 3, 2, 4);
+        trace.record_submit(7, 60, 1, 1, 4);
+        // Descriptor 0 reused before its completion was observed
+        trace.record_submit(8, 60, 0, 1, 4);
+
+        let outcomes: Vec<_> = trace.iter().map(|e| e.outcome).collect();
+        assert_eq!(
+            outcomes,
+            [
+                TraceOutcome::Rejected(DmaError::NoDescriptorsAvailable.into()),
+                TraceOutcome::Superseded,
+                TraceOutcome::Pending,
+                TraceOutcome::Pending,
+            ]
+        );
+        assert!(format!("{trace}").starts_with("#0 len=1600 submit@5 rejected ("));
+
+        trace.abandon_pending(9);
+        assert_eq!(trace.pending(), 0);
+        assert_eq!(trace.get(3).unwrap().outcome, TraceOutcome::Abandoned);
+    }
+
+    #[test]
+    fn keeps_most_recent_entries() {
+        let mut trace = FrameTraceLog::new();
+        for i in 0..FRAME_TRACE_LEN + 3 {
+            trace.record_rejected(i as u32, 60, DmaError::InvalidLength.into());
+        }
+        assert_eq!(trace.len(), FRAME_TRACE_LEN);
+        assert_eq!(trace.total(), FRAME_TRACE_LEN as u32 + 3);
+        assert_eq!(trace.iter().next().unwrap().id, 3);
+        assert!(trace.get(2).is_none());
+        trace.clear();
+        assert!(trace.is_empty());
+    }
+}
@@ -0,0 +1,333 @@
+//! Gratuitous ARP announcements on address and link changes.
+//!
+//! Routers and neighbours cache IP-to-MAC bindings for minutes. After a
+//! device is replaced (same IP, new MAC) or moved to another port, they keep
+//! sending to the old binding until the entry ages out, and the new device
+//! looks unreachable. A gratuitous ARP (an ARP request for our own address,
+//! RFC 5227 "ARP Announcement") makes every host that already has an entry
+//! for the address update it at once.
+//!
+//! [`GarpAnnouncer`] decides when to announce: whenever the IPv4 address is
+//! (re)acquired and whenever the link comes back up with an address
+//! assigned. Announcements go out through the [`RawEthernet`] transmit path,
+//! so they work next to any IP stack; [`gratuitous_arp`] builds the frame
+//! for callers that send it themselves.
+//!
+//! # smoltcp
+//!
+//! Report the address after DHCP and the link from the PHY poll, then poll
+//! the announcer from the main loop:
+//!
+//! ```ignore
+//! use ph_esp32_mac::GarpAnnouncer;
+//!
+//! let mut garp = GarpAnnouncer::new();
+//!
+//! loop {
+//!     garp.set_link_up(phy.link_status(&mut mdio)?.is_some());
+//!     garp.set_ipv4(iface.ipv4_addr().map(|ip| ip.octets()));
+//!     garp.poll(&mut emac, now_ms())?;
+//!     iface.poll(timestamp, &mut emac, &mut sockets);
+//! }
+//! ```
+//!
+//! # embassy-net
+//!
+//! The driver owns the EMAC, so the announcer lives in
+//! `EmbassyEmacState` (`embassy-net` feature), which sees link changes
+//! already; report the address when the stack configuration changes:
+//!
+//! ```ignore
+//! stack.wait_config_up().await;
+//! let ip = stack.config_v4().map(|c| c.address.address().octets());
+//! EMAC_STATE.announce_ipv4(ip);
+//! ```
+//!
+//! Repeats need an EMAC clock and a periodic call to
+//! `EmbassyEmacState::wake_for_garp`; see the integration module docs.
+
+use super::raw::RawEthernet;
+use crate::internal::constants::{ETH_HEADER_SIZE, MAC_ADDR_LEN, MIN_FRAME_SIZE};
+
+/// EtherType of ARP
+const ETHERTYPE_ARP: u16 = 0x0806;
+
+/// Announcements sent per trigger (RFC 5227 `ANNOUNCE_NUM`)
+pub const GARP_DEFAULT_REPEATS: u8 = 2;
+
+/// Spacing between announcements in milliseconds (RFC 5227
+/// `ANNOUNCE_INTERVAL`)
+pub const GARP_DEFAULT_SPACING_MS: u32 = 2000;
+
+/// Broadcast gratuitous ARP request for `ip` from `mac`, padded to the
+/// minimum frame size (the MAC appends the FCS)
+///
+/// Sender and target protocol address are both `ip`; the target hardware
+/// address is zero.
+#[must_use]
+pub fn gratuitous_arp(mac: &[u8; 6], ip: [u8; 4]) -> [u8; MIN_FRAME_SIZE] {
+    let mut frame = [0u8; MIN_FRAME_SIZE];
+    frame[..MAC_ADDR_LEN].fill(0xFF);
+    frame[MAC_ADDR_LEN..2 * MAC_ADDR_LEN].copy_from_slice(mac);
+    frame[12..ETH_HEADER_SIZE].copy_from_slice(&ETHERTYPE_ARP.to_be_bytes());
+    let arp = &mut frame[ETH_HEADER_SIZE..];
+    // Ethernet/IPv4, request
+    arp[..8].copy_from_slice(&[0x00, 0x01, 0x08, 0x00, 6, 4, 0x00, 0x01]);
+    arp[8..14].copy_from_slice(mac);
+    arp[14..18].copy_from_slice(&ip);
+    // Target hardware address stays zero
+    arp[24..28].copy_from_slice(&ip);
+    frame
+}
+
+/// Schedules gratuitous ARPs when the address or link changes
+///
+/// Starts with no address and the link down. Each trigger queues
+/// [`repeats`](Self::with_repeats) announcements; the first goes out on the
+/// next [`poll`](Self::poll), the rest [`spacing`](Self::with_spacing_ms)
+/// apart. Nothing is sent while the link is down or without an address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct GarpAnnouncer {
+    ip: Option<[u8; 4]>,
+    link_up: bool,
+    repeats: u8,
+    spacing_ms: u32,
+    pending: u8,
+    last_sent: Option<u32>,
+}
+
+impl Default for GarpAnnouncer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GarpAnnouncer {
+    /// Announcer with the RFC 5227 defaults
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            ip: None,
+            link_up: false,
+            repeats: GARP_DEFAULT_REPEATS,
+            spacing_ms: GARP_DEFAULT_SPACING_MS,
+            pending: 0,
+            last_sent: None,
+        }
+    }
+
+    /// Announcements per trigger (0 disables announcing)
+    #[must_use]
+    pub const fn with_repeats(mut self, repeats: u8) -> Self {
+        self.repeats = repeats;
+        self
+    }
+
+    /// Milliseconds between the announcements of one trigger
+    #[must_use]
+    pub const fn with_spacing_ms(mut self, spacing_ms: u32) -> Self {
+        self.spacing_ms = spacing_ms;
+        self
+    }
+
+    /// Report the current IPv4 address
+    ///
+    /// A new address, or one acquired after having none, triggers an
+    /// announcement; reporting the same address again does not.
+    pub fn set_ipv4(&mut self, ip: Option<[u8; 4]>) {
+        if ip.is_some() && ip != self.ip {
+            self.trigger();
+        }
+        self.ip = ip;
+    }
+
+    /// Report the link state
+    ///
+    /// A down-to-up transition triggers an announcement.
+    pub fn set_link_up(&mut self, up: bool) {
+        if up && !self.link_up {
+            self.trigger();
+        }
+        self.link_up = up;
+    }
+
+    /// Queue a fresh round of announcements
+    pub fn trigger(&mut self) {
+        self.pending = self.repeats;
+        self.last_sent = None;
+    }
+
+    /// Announcements still queued
+    #[must_use]
+    pub const fn pending(&self) -> u8 {
+        self.pending
+    }
+
+    /// Address to announce at `now`, if one is due
+    ///
+    /// `now` is in milliseconds and may wrap.
+    #[must_use]
+    pub const fn due(&self, now: u32) -> Option<[u8; 4]> {
+        match self.due_in(now) {
+            Some(0) => self.ip,
+            _ => None,
+        }
+    }
+
+    /// Milliseconds from `now` until the next announcement is due
+    ///
+    /// `Some(0)` if one is due now; `None` if nothing will be sent until the
+    /// address or link changes. Repeats are spaced by the clock passed to
+    /// [`poll`](Self::poll): with a clock stuck at 0 only the first
+    /// announcement of a trigger ever goes out.
+    #[must_use]
+    pub const fn due_in(&self, now: u32) -> Option<u32> {
+        if !self.link_up || self.pending == 0 || self.ip.is_none() {
+            return None;
+        }
+        match self.last_sent {
+            Some(last) => Some(self.spacing_ms.saturating_sub(now.wrapping_sub(last))),
+            None => Some(0),
+        }
+    }
+
+    /// Send the next announcement through `eth` if one is due
+    ///
+    /// Returns `Ok(true)` if a frame was sent. A failed send stays queued
+    /// and is retried on the next poll.
+    ///
+    /// # Errors
+    /// Those of [`RawEthernet::send`].
+    pub fn poll<T: RawEthernet + ?Sized>(
+        &mut self,
+        eth: &mut T,
+        now: u32,
+    ) -> core::result::Result<bool, T::Error> {
+        let Some(ip) = self.due(now) else {
+            return Ok(false);
+        };
+        eth.send(&gratuitous_arp(&eth.mac_address(), ip))?;
+        self.pending -= 1;
+        self.last_sent = Some(now);
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+
+    use alloc::vec::Vec;
+
+    use super::*;
+
+    const MAC: [u8; 6] = [0x02, 0x11, 0x22, 0x33, 0x44, 0x55];
+    const IP: [u8; 4] = [192, 168, 1, 50];
+
+    /// Records sent frames; fails while `busy`
+    #[derive(Default)]
+    struct Recorder {
+        sent: Vec<[u8; MIN_FRAME_SIZE]>,
+        busy: bool,
+    }
+
+    impl RawEthernet for Recorder {
+        type Error = ();
+
+        fn send(&mut self, frame: &[u8]) -> core::result::Result<usize, ()> {
+            if self.busy {
+                return Err(());
+            }
+            self.sent.push(frame.try_into().unwrap());
+            Ok(frame.len())
+        }
+
+        fn recv(&mut self, _buffer: &mut [u8]) -> core::result::Result<Option<usize>, ()> {
+            Ok(None)
+        }
+
+        fn frame_available(&mut self) -> bool {
+            false
+        }
+
+        fn mac_address(&self) -> [u8; 6] {
+            MAC
+        }
+    }
+
+    #[test]
+    fn frame_layout() {
+        let frame = gratuitous_arp(&MAC, IP);
+        assert_eq!(frame[..6], [0xFF; 6]);
+        assert_eq!(frame[6..12], MAC);
+        assert_eq!(frame[12..14], [0x08, 0x06]);
+        assert_eq!(frame[14..22], [0, 1, 8, 0, 6, 4, 0, 1]);
+        assert_eq!(frame[22..28], MAC);
+        assert_eq!(frame[28..32], IP);
+        assert_eq!(frame[32..38], [0; 6]);
+        assert_eq!(frame[38..42], IP);
+        assert!(frame[42..].iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn announces_on_address_and_link_changes() {
+        let mut eth = Recorder::default();
+        let mut garp = GarpAnnouncer::new().with_repeats(1);
+
+        // Address without link: held until the link comes up
+        garp.set_ipv4(Some(IP));
+        assert_eq!(garp.poll(&mut eth, 0), Ok(false));
+        garp.set_link_up(true);
+        assert_eq!(garp.poll(&mut eth, 0), Ok(true));
+        assert_eq!(garp.poll(&mut eth, 10_000), Ok(false));
+
+        // Same address again: nothing; new address: announced
+        garp.set_ipv4(Some(IP));
+        assert_eq!(garp.pending(), 0);
+        garp.set_ipv4(Some([192, 168, 1, 51]));
+        assert_eq!(garp.poll(&mut eth, 10_000), Ok(true));
+
+        // Link flap re-announces, lease loss alone does not
+        garp.set_link_up(false);
+        garp.set_link_up(true);
+        assert_eq!(garp.poll(&mut eth, 10_001), Ok(true));
+        garp.set_ipv4(None);
+        assert_eq!(garp.pending(), 0);
+
+        assert_eq!(eth.sent.len(), 3);
+        assert_eq!(eth.sent[0][38..42], IP);
+        assert_eq!(eth.sent[2][38..42], [192, 168, 1, 51]);
+    }
+
+    #[test]
+    fn repeats_are_spaced_and_failures_retried() {
+        let mut eth = Recorder::default();
+        let mut garp = GarpAnnouncer::new().with_spacing_ms(1000);
+        garp.set_link_up(true);
+        garp.set_ipv4(Some(IP));
+
+        eth.busy = true;
+        assert_eq!(garp.poll(&mut eth, u32::MAX - 10), Err(()));
+        eth.busy = false;
+        assert_eq!(garp.poll(&mut eth, u32::MAX - 10), Ok(true));
+        assert_eq!(garp.due_in(900), Some(89));
+        assert_eq!(garp.poll(&mut eth, 900), Ok(false));
+        assert_eq!(garp.poll(&mut eth, 989), Ok(true));
+        assert_eq!(garp.poll(&mut eth, 5000), Ok(false));
+        assert_eq!(garp.due_in(5000), None);
+        assert_eq!(eth.sent.len(), usize::from(GARP_DEFAULT_REPEATS));
+    }
+
+    #[test]
+    fn due_in_needs_link_and_address() {
+        let mut garp = GarpAnnouncer::new();
+        garp.set_ipv4(Some(IP));
+        assert_eq!(garp.due_in(0), None);
+        garp.set_link_up(true);
+        assert_eq!(garp.due_in(0), Some(0));
+        assert_eq!(garp.due(0), Some(IP));
+        garp.set_ipv4(None);
+        assert_eq!(garp.due_in(0), None);
+    }
+}
@@ -0,0 +1,102 @@
+//! Register state checks for light-sleep survival.
+//!
+//! Depending on the sleep configuration, ESP32 light sleep can power down or
+//! reset parts of the EMAC while the driver still believes it is running:
+//! the extension clock and interface registers, the MAC configuration, the
+//! DMA bus and operation mode. Traffic then stops without any error, much
+//! like a stalled DMA (see [`liveness`](super::liveness)).
+//!
+//! [`Emac::verify_hw_state`](super::emac::Emac::verify_hw_state) compares
+//! the registers that matter against what the driver last programmed and
+//! reports the groups that no longer match as an [`HwStateLoss`].
+//! [`Emac::fast_resume`](super::emac::Emac::fast_resume) reprograms them
+//! without resetting the descriptor rings, so frames already received and
+//! frames still queued for transmission survive.
+//!
+//! `fast_resume` rebuilds the receive filters from `EmacConfig::filters`.
+//! Applications that change filters at run time take a
+//! [`FilterSnapshot`](super::filtering::FilterSnapshot) before sleeping and
+//! put it back with
+//! [`Emac::restore_filters`](super::emac::Emac::restore_filters) after the
+//! resume.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let filters = emac.filter_snapshot();
+//! rtc.sleep_light(&[&timer]);
+//!
+//! if emac.verify_hw_state().any() {
+//!     emac.fast_resume(&mut delay)?;
+//!     emac.restore_filters(&filters);
+//! }
+//! ```
+
+use core::fmt;
+
+/// Register groups that no longer hold what the driver programmed
+///
+/// All `false` means the hardware state is intact.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct HwStateLoss {
+    /// DPORT peripheral clock or extension clock enables are off
+    pub clock: bool,
+    /// PHY interface selection (MII/RMII) differs from the configuration
+    pub interface: bool,
+    /// MAC configuration: port, speed, duplex, transmitter/receiver enable,
+    /// or the station address
+    pub mac: bool,
+    /// Promiscuous or control frame bits of the frame filter
+    pub filter: bool,
+    /// DMA bus mode, operation mode, interrupt enables or descriptor lists
+    pub dma: bool,
+}
+
+impl HwStateLoss {
+    /// True if any register group was lost
+    #[must_use]
+    pub const fn any(&self) -> bool {
+        self.clock || self.interface || self.mac || self.filter || self.dma
+    }
+}
+
+impl fmt::Display for HwStateLoss {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.any() {
+            return f.write_str("intact");
+        }
+        let groups = [
+            (self.clock, "clock"),
+            (self.interface, "interface"),
+            (self.mac, "mac"),
+            (self.filter, "filter"),
+            (self.dma, "dma"),
+        ];
+        f.write_str("lost:")?;
+        for (_, name) in groups.iter().filter(|(lost, _)| *lost) {
+            write!(f, " {name}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use std::format;
+
+    use super::*;
+
+    #[test]
+    fn display_lists_lost_groups() {
+        assert_eq!(format!("{}", HwStateLoss::default()), "intact");
+        let loss = HwStateLoss {
+            mac: true,
+            dma: true,
+            ..HwStateLoss::default()
+        };
+        assert!(loss.any());
+        assert_eq!(format!("{loss}"), "lost: mac dma");
+    }
+}
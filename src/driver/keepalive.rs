@@ -0,0 +1,152 @@
+//! Link-layer keepalive for switch MAC address tables.
+//!
+//! Switches forget a station's port after a few minutes without a frame
+//! from it (300 s is a common default). A device that only answers, like a
+//! sensor polled once an hour, then has its first reply preceded by flooded
+//! requests, and in some networks the first packets after a long silence
+//! are delayed or lost.
+//!
+//! With a [`Keepalive`] configured,
+//! [`Emac::poll_keepalive`](super::emac::Emac::poll_keepalive) sends a
+//! minimal broadcast frame whenever nothing has been transmitted for the
+//! interval, keeping the entry fresh without touching the IP stack. Call it
+//! from the main loop; any other transmit restarts the interval, so a busy
+//! link never sees a keepalive.
+//!
+//! Idle time is measured with the driver's activity clock
+//! ([`Emac::set_event_clock`](super::emac::Emac::set_event_clock) or
+//! [`Emac::set_clock`](super::emac::Emac::set_clock)); without one no
+//! keepalive is sent after the first.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use ph_esp32_mac::{EmacConfig, Keepalive};
+//!
+//! // Announce ourselves every two minutes of silence
+//! let config = EmacConfig::new().with_keepalive(Some(
+//!     Keepalive::new(120_000).with_gratuitous_arp([192, 168, 1, 50]),
+//! ));
+//!
+//! loop {
+//!     emac.poll_keepalive()?;
+//!     // ...
+//! }
+//! ```
+
+use super::garp::gratuitous_arp;
+use crate::internal::constants::{ETH_HEADER_SIZE, MAC_ADDR_LEN, MIN_FRAME_SIZE};
+
+/// EtherType of [`KeepaliveFrame::Broadcast`]: IEEE 802 Local
+/// Experimental EtherType 1
+pub const KEEPALIVE_ETHERTYPE: u16 = 0x88B5;
+
+/// Frame sent as a keepalive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum KeepaliveFrame {
+    /// Empty broadcast frame with [`KEEPALIVE_ETHERTYPE`]; hosts drop it
+    #[default]
+    Broadcast,
+    /// Gratuitous ARP request for this IPv4 address, which also refreshes
+    /// neighbours' ARP caches
+    GratuitousArp {
+        /// Our IPv4 address
+        ip: [u8; 4],
+    },
+}
+
+/// Keepalive transmitter configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Keepalive {
+    /// Transmit silence, in milliseconds, after which a keepalive is sent
+    pub interval_ms: u32,
+    /// Frame to send
+    pub frame: KeepaliveFrame,
+}
+
+impl Keepalive {
+    /// Broadcast keepalive after `interval_ms` without a transmit
+    ///
+    /// Pick an interval well below the switch's aging time.
+    #[must_use]
+    pub const fn new(interval_ms: u32) -> Self {
+        Self {
+            interval_ms,
+            frame: KeepaliveFrame::Broadcast,
+        }
+    }
+
+    /// Send a gratuitous ARP for `ip` instead of the plain broadcast
+    #[must_use]
+    pub const fn with_gratuitous_arp(mut self, ip: [u8; 4]) -> Self {
+        self.frame = KeepaliveFrame::GratuitousArp { ip };
+        self
+    }
+
+    /// True once `interval_ms` has passed since `last_tx` at `now`
+    ///
+    /// Never having transmitted counts as idle. Wraps with the clock.
+    #[must_use]
+    pub const fn is_due(&self, last_tx: Option<u32>, now: u32) -> bool {
+        match last_tx {
+            Some(last) => now.wrapping_sub(last) >= self.interval_ms,
+            None => true,
+        }
+    }
+
+    /// The keepalive frame from source address `mac`, padded to the
+    /// minimum frame size (the MAC appends the FCS)
+    #[must_use]
+    pub fn build(&self, mac: &[u8; 6]) -> [u8; MIN_FRAME_SIZE] {
+        match self.frame {
+            KeepaliveFrame::Broadcast => {
+                let mut frame = [0u8; MIN_FRAME_SIZE];
+                frame[..MAC_ADDR_LEN].fill(0xFF);
+                frame[MAC_ADDR_LEN..2 * MAC_ADDR_LEN].copy_from_slice(mac);
+                frame[12..ETH_HEADER_SIZE].copy_from_slice(&KEEPALIVE_ETHERTYPE.to_be_bytes());
+                frame
+            }
+            KeepaliveFrame::GratuitousArp { ip } => gratuitous_arp(mac, ip),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAC: [u8; 6] = [0x02, 0x11, 0x22, 0x33, 0x44, 0x55];
+
+    #[test]
+    fn broadcast_frame_layout() {
+        let frame = Keepalive::new(1000).build(&MAC);
+        assert_eq!(frame[..6], [0xFF; 6]);
+        assert_eq!(frame[6..12], MAC);
+        assert_eq!(frame[12..14], [0x88, 0xB5]);
+        assert!(frame[14..].iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn gratuitous_arp_layout() {
+        let ip = [10, 0, 0, 7];
+        let frame = Keepalive::new(1000).with_gratuitous_arp(ip).build(&MAC);
+        assert_eq!(frame[12..14], [0x08, 0x06]);
+        assert_eq!(frame[14..22], [0, 1, 8, 0, 6, 4, 0, 1]);
+        assert_eq!(frame[22..28], MAC);
+        assert_eq!(frame[28..32], ip);
+        assert_eq!(frame[32..38], [0; 6]);
+        assert_eq!(frame[38..42], ip);
+    }
+
+    #[test]
+    fn due_after_interval_across_wrap() {
+        let keepalive = Keepalive::new(500);
+        assert!(keepalive.is_due(None, 0));
+        assert!(!keepalive.is_due(Some(1000), 1499));
+        assert!(keepalive.is_due(Some(1000), 1500));
+        assert!(!keepalive.is_due(Some(u32::MAX - 100), 100));
+        assert!(keepalive.is_due(Some(u32::MAX - 100), 400));
+    }
+}
@@ -0,0 +1,327 @@
+//! Type-state view of the EMAC lifecycle.
+//!
+//! [`Emac`] tracks its state at runtime, so calling `transmit()` before
+//! `start()` only fails with `IoError::InvalidState` once the code runs.
+//! [`TypedEmac`] borrows an `Emac` and carries the state in its type
+//! instead: `init()` is only available while [`Uninitialized`], `start()`
+//! while [`Ready`], and `transmit()`/`receive()` only while [`Running`].
+//! Each transition consumes the handle and returns one in the next state.
+//!
+//! The handle borrows rather than owns the EMAC because the descriptor
+//! chain points into the `Emac` itself, which therefore must not move once
+//! initialized. Read-only accessors are available through `Deref`.
+//!
+//! # Dynamic Escape Hatch
+//!
+//! The shared, ISR and async wrappers keep working on the dynamic `Emac`.
+//! [`TypedEmac::into_dynamic`] gives the `&mut Emac` back for everything the
+//! typed API does not cover (filters, PHY access, runtime settings), and
+//! [`TypedEmac::from_dynamic`] re-enters the typed API in whatever state the
+//! EMAC is in.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use ph_esp32_mac::{TypedEmac, TypedState};
+//!
+//! let TypedState::Uninitialized(emac) = TypedEmac::from_dynamic(&mut emac) else {
+//!     unreachable!()
+//! };
+//! let emac = emac.init(config, &mut delay).map_err(|(_, e)| e)?;
+//! let mut emac = emac.start().map_err(|(_, e)| e)?;
+//! emac.transmit(&frame)?;
+//! ```
+//!
+//! Transmitting on an EMAC that has not been started does not compile:
+//!
+//! ```compile_fail
+//! use ph_esp32_mac::driver::lifecycle::{Ready, TypedEmac};
+//!
+//! fn send(emac: &mut TypedEmac<'_, 4, 4, 1600, Ready>) {
+//!     let _ = emac.transmit(&[0u8; 60]);
+//! }
+//! ```
+
+use core::marker::PhantomData;
+use core::ops::Deref;
+
+use embedded_hal::delay::DelayNs;
+
+use super::config::{EmacConfig, State};
+use super::emac::Emac;
+use super::error::{Error, Result};
+
+// =============================================================================
+// State Markers
+// =============================================================================
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Lifecycle state carried by a [`TypedEmac`]
+///
+/// Sealed; implemented only by [`Uninitialized`], [`Ready`] and [`Running`].
+pub trait LifecycleState: sealed::Sealed {}
+
+/// Not initialized yet (`State::Uninitialized`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Uninitialized;
+
+/// Initialized or stopped, not passing traffic (`State::Initialized` or
+/// `State::Stopped`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Ready;
+
+/// Started (`State::Running`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Running;
+
+impl sealed::Sealed for Uninitialized {}
+impl sealed::Sealed for Ready {}
+impl sealed::Sealed for Running {}
+impl LifecycleState for Uninitialized {}
+impl LifecycleState for Ready {}
+impl LifecycleState for Running {}
+
+// =============================================================================
+// TypedEmac
+// =============================================================================
+
+/// Result of a state transition; on failure the handle comes back in its
+/// original state together with the error
+pub type TransitionResult<T, S> = core::result::Result<T, (S, Error)>;
+
+/// Borrowed [`Emac`] whose lifecycle state is part of its type
+///
+/// See the [module documentation](self).
+pub struct TypedEmac<
+    'a,
+    const RX_BUFS: usize,
+    const TX_BUFS: usize,
+    const BUF_SIZE: usize,
+    S: LifecycleState,
+> {
+    emac: &'a mut Emac<RX_BUFS, TX_BUFS, BUF_SIZE>,
+    _state: PhantomData<S>,
+}
+
+/// A [`TypedEmac`] in whichever state the EMAC was found
+pub enum TypedState<'a, const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize> {
+    /// The EMAC is not initialized
+    Uninitialized(TypedEmac<'a, RX_BUFS, TX_BUFS, BUF_SIZE, Uninitialized>),
+    /// The EMAC is initialized or stopped
+    Ready(TypedEmac<'a, RX_BUFS, TX_BUFS, BUF_SIZE, Ready>),
+    /// The EMAC is running
+    Running(TypedEmac<'a, RX_BUFS, TX_BUFS, BUF_SIZE, Running>),
+    /// The EMAC was started in one direction only (`State::RxOnly` or
+    /// `State::TxOnly`), which the typed API does not model; the EMAC is
+    /// handed back unchanged
+    OneWay(&'a mut Emac<RX_BUFS, TX_BUFS, BUF_SIZE>),
+}
+
+impl<'a, const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize, S: LifecycleState>
+    TypedEmac<'a, RX_BUFS, TX_BUFS, BUF_SIZE, S>
+{
+    /// Wrap `emac`; the caller guarantees its runtime state matches `T`
+    fn wrap<T: LifecycleState>(
+        emac: &'a mut Emac<RX_BUFS, TX_BUFS, BUF_SIZE>,
+    ) -> TypedEmac<'a, RX_BUFS, TX_BUFS, BUF_SIZE, T> {
+        TypedEmac {
+            emac,
+            _state: PhantomData,
+        }
+    }
+
+    /// Give the EMAC back for dynamic-state use
+    ///
+    /// Use [`from_dynamic`](TypedEmac::from_dynamic) to return to the typed
+    /// API afterwards.
+    pub fn into_dynamic(self) -> &'a mut Emac<RX_BUFS, TX_BUFS, BUF_SIZE> {
+        self.emac
+    }
+}
+
+impl<'a, const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
+    TypedEmac<'a, RX_BUFS, TX_BUFS, BUF_SIZE, Uninitialized>
+{
+    /// Enter the typed API in the EMAC's current runtime state
+    pub fn from_dynamic(
+        emac: &'a mut Emac<RX_BUFS, TX_BUFS, BUF_SIZE>,
+    ) -> TypedState<'a, RX_BUFS, TX_BUFS, BUF_SIZE> {
+        match emac.state() {
+            State::Uninitialized => TypedState::Uninitialized(Self::wrap(emac)),
+            State::Initialized | State::Stopped => TypedState::Ready(Self::wrap(emac)),
+            State::Running => TypedState::Running(Self::wrap(emac)),
+            State::RxOnly | State::TxOnly => TypedState::OneWay(emac),
+        }
+    }
+
+    /// Initialize the EMAC, see [`Emac::init`]
+    ///
+    /// # Errors
+    /// Same as [`Emac::init`]; the EMAC stays uninitialized.
+    pub fn init<D: DelayNs>(
+        self,
+        config: EmacConfig,
+        delay: D,
+    ) -> TransitionResult<TypedEmac<'a, RX_BUFS, TX_BUFS, BUF_SIZE, Ready>, Self> {
+        match self.emac.init(config, delay) {
+            Ok(()) => Ok(Self::wrap(self.emac)),
+            Err(e) => Err((self, e)),
+        }
+    }
+}
+
+impl<'a, const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
+    TypedEmac<'a, RX_BUFS, TX_BUFS, BUF_SIZE, Ready>
+{
+    /// Start TX and RX, see [`Emac::start`]
+    ///
+    /// # Errors
+    /// Same as [`Emac::start`].
+    pub fn start(
+        self,
+    ) -> TransitionResult<TypedEmac<'a, RX_BUFS, TX_BUFS, BUF_SIZE, Running>, Self> {
+        match self.emac.start() {
+            Ok(()) => Ok(Self::wrap(self.emac)),
+            Err(e) => Err((self, e)),
+        }
+    }
+}
+
+impl<'a, const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
+    TypedEmac<'a, RX_BUFS, TX_BUFS, BUF_SIZE, Running>
+{
+    /// Stop TX and RX, see [`Emac::stop`]
+    ///
+    /// # Errors
+    /// Same as [`Emac::stop`]. The handle stays `Running`, as does the
+    /// driver state, but the hardware is partly stopped: after
+    /// `TxStopTimeout` the TX DMA has been told to stop, after
+    /// `FifoFlushTimeout` the MAC and both DMA channels are off too.
+    /// Call `stop()` again or re-initialize the EMAC.
+    pub fn stop(self) -> TransitionResult<TypedEmac<'a, RX_BUFS, TX_BUFS, BUF_SIZE, Ready>, Self> {
+        match self.emac.stop() {
+            Ok(()) => Ok(Self::wrap(self.emac)),
+            Err(e) => Err((self, e)),
+        }
+    }
+
+    /// Transmit a frame, see [`Emac::transmit`]
+    ///
+    /// # Errors
+    /// Same as [`Emac::transmit`], except that `InvalidState` cannot occur.
+    pub fn transmit(&mut self, data: &[u8]) -> Result<usize> {
+        self.emac.transmit(data)
+    }
+
+    /// Transmit the last frame of a burst, see [`Emac::transmit_burst_end`]
+    ///
+    /// # Errors
+    /// Same as [`transmit`](Self::transmit).
+    pub fn transmit_burst_end(&mut self, data: &[u8]) -> Result<usize> {
+        self.emac.transmit_burst_end(data)
+    }
+
+    /// Transmit a high-priority frame, see [`Emac::transmit_reserved`]
+    ///
+    /// # Errors
+    /// Same as [`transmit`](Self::transmit).
+    pub fn transmit_reserved(&mut self, data: &[u8]) -> Result<usize> {
+        self.emac.transmit_reserved(data)
+    }
+
+    /// Receive a frame, see [`Emac::receive`]
+    ///
+    /// # Errors
+    /// Same as [`Emac::receive`], except that `InvalidState` cannot occur.
+    pub fn receive(&mut self, buffer: &mut [u8]) -> Result<usize> {
+        self.emac.receive(buffer)
+    }
+
+    /// Enable RX after a deferred start, see [`Emac::enable_rx`]
+    ///
+    /// # Errors
+    /// - `NoRxBuffers` - There is no RX ring (`RX_BUFS = 0`)
+    pub fn enable_rx(&mut self) -> Result<()> {
+        self.emac.enable_rx()
+    }
+}
+
+impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize, S: LifecycleState> Deref
+    for TypedEmac<'_, RX_BUFS, TX_BUFS, BUF_SIZE, S>
+{
+    type Target = Emac<RX_BUFS, TX_BUFS, BUF_SIZE>;
+
+    fn deref(&self) -> &Self::Target {
+        self.emac
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::internal::register::sim;
+
+    #[test]
+    fn from_dynamic_follows_runtime_state() {
+        sim::reset();
+        let mut emac: Emac<4, 4, 256> = Emac::new();
+        assert!(matches!(
+            TypedEmac::from_dynamic(&mut emac),
+            TypedState::Uninitialized(_)
+        ));
+
+        emac.sim_init(EmacConfig::new());
+        assert!(matches!(
+            TypedEmac::from_dynamic(&mut emac),
+            TypedState::Ready(_)
+        ));
+
+        emac.start().unwrap();
+        assert!(matches!(
+            TypedEmac::from_dynamic(&mut emac),
+            TypedState::Running(_)
+        ));
+    }
+
+    #[test]
+    fn typed_start_then_transfer() {
+        sim::reset();
+        let mut emac: Emac<4, 4, 256> = Emac::new();
+        emac.sim_init(EmacConfig::new());
+
+        let TypedState::Ready(ready) = TypedEmac::from_dynamic(&mut emac) else {
+            panic!("EMAC should be ready");
+        };
+        let mut running = ready.start().map_err(|(_, e)| e).unwrap();
+        assert_eq!(running.state(), State::Running);
+        assert_eq!(running.transmit(&[0x5A; 60]), Ok(60));
+
+        let emac = running.into_dynamic();
+        assert!(emac.dma.sim_rx_frame(&[0xFF; 60], 0));
+        let TypedState::Running(mut running) = TypedEmac::from_dynamic(emac) else {
+            panic!("EMAC should be running");
+        };
+        let mut buf = [0u8; 256];
+        assert_eq!(running.receive(&mut buf), Ok(60));
+    }
+
+    #[test]
+    fn typed_enable_rx_reports_missing_ring() {
+        use crate::driver::error::DmaError;
+
+        sim::reset();
+        let mut emac: Emac<0, 4, 256> = Emac::new();
+        emac.sim_init(EmacConfig::new());
+        let TypedState::Ready(ready) = TypedEmac::from_dynamic(&mut emac) else {
+            panic!("EMAC should be ready");
+        };
+        let mut running = ready.start().map_err(|(_, e)| e).unwrap();
+        assert_eq!(running.enable_rx(), Err(DmaError::NoRxBuffers.into()));
+    }
+}
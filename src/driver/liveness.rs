@@ -0,0 +1,237 @@
+//! DMA process states and RX/TX liveness for system watchdogs.
+//!
+//! [`TxProcessState`] and [`RxProcessState`] decode the process state fields
+//! of the DMA status register;
+//! [`Emac::dma_states`](super::emac::Emac::dma_states) reads them, which is
+//! the first thing to look at when traffic stops.
+//!
+//! A stalled DMA does not make the driver fail loudly: after an unhandled
+//! receive-buffer-unavailable (RU) condition or a fatal bus error the rings
+//! simply stop moving, `receive()` keeps returning `IncompleteFrame`, and
+//! the network interface is dead until the next reboot.
+//!
+//! [`Emac::liveness`](super::emac::Emac::liveness) takes a
+//! [`LivenessReport`]: when frames were last received and queued, and what
+//! the DMA receive and transmit processes are doing according to the status
+//! register. [`LivenessReport::fault`] turns it into a verdict; feed the
+//! system watchdog only while there is none, so a hung interface ends in a
+//! controlled reset instead of going unnoticed.
+//!
+//! Activity ticks come from the event clock
+//! ([`Emac::set_event_clock`](super::emac::Emac::set_event_clock)); without
+//! one every tick is `0` and the RX silence check never fires.
+//!
+//! # Example
+//!
+//! ```ignore
+//! emac.set_event_clock(Some(millis));
+//!
+//! // In the supervisor loop:
+//! match emac.liveness().fault(Some(60_000)) {
+//!     None => watchdog.feed(),
+//!     Some(fault) => log::error!("EMAC: {fault}"),
+//! }
+//! ```
+
+pub use crate::internal::register::dma::{RxProcessState, TxProcessState};
+
+// =============================================================================
+// Liveness Report
+// =============================================================================
+
+/// Reason a [`LivenessReport`] fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum LivenessFault {
+    /// The DMA hit a fatal bus error and halted; only a reset recovers
+    FatalBusError,
+    /// The receiver is enabled but the receive DMA has stopped
+    RxDmaStopped,
+    /// The receive DMA is suspended although descriptors were handed back,
+    /// i.e. an RU condition was never followed by a poll demand
+    RxSuspended,
+    /// The transmitter is enabled but the transmit DMA has stopped
+    TxDmaStopped,
+    /// Nothing was received for longer than the allowed silence
+    RxSilent {
+        /// Ticks since the last received frame
+        idle: u32,
+    },
+}
+
+impl LivenessFault {
+    /// Short description
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::FatalBusError => "DMA fatal bus error",
+            Self::RxDmaStopped => "receive DMA stopped",
+            Self::RxSuspended => "receive DMA suspended with free descriptors",
+            Self::TxDmaStopped => "transmit DMA stopped",
+            Self::RxSilent { .. } => "no frame received",
+        }
+    }
+}
+
+impl core::fmt::Display for LivenessFault {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::RxSilent { idle } => write!(f, "{} for {idle} ticks", self.as_str()),
+            _ => f.write_str(self.as_str()),
+        }
+    }
+}
+
+/// Snapshot of RX/TX activity and DMA process states
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct LivenessReport {
+    /// Event clock when the report was taken
+    pub now: u32,
+    /// Tick of the last frame returned by `receive()`
+    pub last_rx: Option<u32>,
+    /// Tick of the last frame queued for transmission
+    pub last_tx: Option<u32>,
+    /// Receive DMA process state
+    pub rx_state: RxProcessState,
+    /// Transmit DMA process state
+    pub tx_state: TxProcessState,
+    /// Fatal bus error flag still set in the status register
+    pub fatal_bus_error: bool,
+    /// The driver has the receiver enabled
+    pub rx_enabled: bool,
+    /// The driver has the transmitter enabled
+    pub tx_enabled: bool,
+    /// RX descriptors owned by the DMA, ready for new frames
+    pub rx_descriptors_free: usize,
+    /// TX descriptors still owned by the DMA
+    pub tx_in_flight: usize,
+}
+
+impl LivenessReport {
+    /// Ticks since the last received frame (`None` before the first one)
+    #[must_use]
+    pub const fn rx_idle(&self) -> Option<u32> {
+        match self.last_rx {
+            Some(at) => Some(self.now.wrapping_sub(at)),
+            None => None,
+        }
+    }
+
+    /// First fault found, or `None` if the interface looks alive
+    ///
+    /// `rx_silence` additionally fails the check when the receiver has
+    /// been enabled and idle for longer than that many ticks; only use it
+    /// on networks with regular traffic (ARP, mDNS, a periodic ping).
+    #[must_use]
+    pub const fn fault(&self, rx_silence: Option<u32>) -> Option<LivenessFault> {
+        if self.fatal_bus_error {
+            return Some(LivenessFault::FatalBusError);
+        }
+        if self.rx_enabled {
+            match self.rx_state {
+                RxProcessState::Stopped => return Some(LivenessFault::RxDmaStopped),
+                RxProcessState::Suspended if self.rx_descriptors_free > 0 => {
+                    return Some(LivenessFault::RxSuspended);
+                }
+                _ => {}
+            }
+        }
+        if self.tx_enabled && matches!(self.tx_state, TxProcessState::Stopped) {
+            return Some(LivenessFault::TxDmaStopped);
+        }
+        if let (true, Some(limit), Some(idle)) = (self.rx_enabled, rx_silence, self.rx_idle())
+            && idle > limit
+        {
+            return Some(LivenessFault::RxSilent { idle });
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::std_instead_of_alloc)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::internal::register::dma::{DMASTATUS_RS_SHIFT, DMASTATUS_TS_SHIFT};
+    use std::format;
+
+    fn healthy() -> LivenessReport {
+        LivenessReport {
+            now: 1000,
+            last_rx: Some(900),
+            last_tx: Some(950),
+            rx_state: RxProcessState::WaitingForPacket,
+            tx_state: TxProcessState::Suspended,
+            fatal_bus_error: false,
+            rx_enabled: true,
+            tx_enabled: true,
+            rx_descriptors_free: 4,
+            tx_in_flight: 0,
+        }
+    }
+
+    #[test]
+    fn decodes_process_states() {
+        let status = (4 << DMASTATUS_RS_SHIFT) | (3 << DMASTATUS_TS_SHIFT);
+        assert_eq!(
+            RxProcessState::from_status(status),
+            RxProcessState::Suspended
+        );
+        assert_eq!(
+            TxProcessState::from_status(status),
+            TxProcessState::ReadingData
+        );
+        let reserved = RxProcessState::from_status(6 << DMASTATUS_RS_SHIFT);
+        assert_eq!(reserved, RxProcessState::Reserved6);
+        assert_eq!(format!("{reserved}"), "reserved (6)");
+        assert_eq!(format!("{}", TxProcessState::Suspended), "suspended");
+    }
+
+    #[test]
+    fn faults_in_priority_order() {
+        assert_eq!(healthy().fault(Some(500)), None);
+        assert_eq!(
+            healthy().fault(Some(50)),
+            Some(LivenessFault::RxSilent { idle: 100 })
+        );
+
+        let suspended = LivenessReport {
+            rx_state: RxProcessState::Suspended,
+            ..healthy()
+        };
+        assert_eq!(suspended.fault(None), Some(LivenessFault::RxSuspended));
+        // A full ring is ordinary backpressure, not a stall
+        let full = LivenessReport {
+            rx_descriptors_free: 0,
+            ..suspended
+        };
+        assert_eq!(full.fault(None), None);
+
+        let halted = LivenessReport {
+            fatal_bus_error: true,
+            rx_state: RxProcessState::Stopped,
+            tx_state: TxProcessState::Stopped,
+            ..healthy()
+        };
+        assert_eq!(halted.fault(None), Some(LivenessFault::FatalBusError));
+        let stopped = LivenessReport {
+            rx_enabled: false,
+            fatal_bus_error: false,
+            ..halted
+        };
+        assert_eq!(stopped.fault(None), Some(LivenessFault::TxDmaStopped));
+    }
+
+    #[test]
+    fn idle_wraps_with_the_clock() {
+        let report = LivenessReport {
+            now: 5,
+            last_rx: Some(u32::MAX - 4),
+            ..healthy()
+        };
+        assert_eq!(report.rx_idle(), Some(10));
+    }
+}
@@ -0,0 +1,128 @@
+//! DMA memory footprint of an EMAC instantiation.
+//!
+//! Every `Emac<RX_BUFS, TX_BUFS, BUF_SIZE>` statically holds its descriptor
+//! rings and DMA buffers, about 32 KB with the default 10/10/1600 geometry.
+//! [`MemoryReport`] breaks that down and is computed at compile time, and
+//! [`emac_memory_report!`](crate::emac_memory_report) turns it into a named
+//! constant that can fail the build when the footprint exceeds a budget.
+//!
+//! The report covers DMA-capable RAM only; the driver state next to it is
+//! included in [`Emac::memory_usage`](super::emac::Emac::memory_usage).
+
+use core::fmt;
+
+use crate::internal::dma::DmaEngine;
+
+/// DMA RAM used by one EMAC geometry, in bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct MemoryReport {
+    /// RX descriptor ring
+    pub rx_descriptors: usize,
+    /// TX descriptor ring
+    pub tx_descriptors: usize,
+    /// RX data buffers
+    pub rx_buffers: usize,
+    /// TX data buffers
+    pub tx_buffers: usize,
+}
+
+impl MemoryReport {
+    /// Report for `Emac<RX_BUFS, TX_BUFS, BUF_SIZE>`
+    #[must_use]
+    pub const fn of<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>() -> Self {
+        DmaEngine::<RX_BUFS, TX_BUFS, BUF_SIZE>::memory_report()
+    }
+
+    /// Both descriptor rings
+    #[must_use]
+    pub const fn descriptors(&self) -> usize {
+        self.rx_descriptors + self.tx_descriptors
+    }
+
+    /// All data buffers
+    #[must_use]
+    pub const fn buffers(&self) -> usize {
+        self.rx_buffers + self.tx_buffers
+    }
+
+    /// Total DMA RAM
+    #[must_use]
+    pub const fn total(&self) -> usize {
+        self.descriptors() + self.buffers()
+    }
+
+    /// Return `self`, failing compilation in const context if the total
+    /// exceeds `budget` bytes
+    ///
+    /// # Panics
+    /// If `total()` is above `budget`.
+    #[must_use]
+    pub const fn within_budget(self, budget: usize) -> Self {
+        assert!(
+            self.total() <= budget,
+            "EMAC DMA memory exceeds the configured budget; reduce RX_BUFS, TX_BUFS or BUF_SIZE"
+        );
+        self
+    }
+}
+
+impl fmt::Display for MemoryReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "EMAC DMA memory: {} bytes (descriptors: RX {} + TX {}, buffers: RX {} + TX {})",
+            self.total(),
+            self.rx_descriptors,
+            self.tx_descriptors,
+            self.rx_buffers,
+            self.tx_buffers
+        )
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::std_instead_of_alloc)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::driver::emac::Emac;
+    use std::format;
+
+    crate::emac_memory_report!(DEFAULT_REPORT, 10, 10, 1600, budget = 40 * 1024);
+
+    #[test]
+    fn default_geometry_breakdown() {
+        let buffers = 10 * 1600;
+        assert_eq!(DEFAULT_REPORT.rx_buffers, buffers);
+        assert_eq!(DEFAULT_REPORT.tx_buffers, buffers);
+        assert_eq!(DEFAULT_REPORT.rx_descriptors, DEFAULT_REPORT.tx_descriptors);
+        assert_eq!(
+            DEFAULT_REPORT.total(),
+            DmaEngine::<10, 10, 1600>::memory_usage()
+        );
+        assert!(Emac::<10, 10, 1600>::memory_usage() > DEFAULT_REPORT.total());
+    }
+
+    #[test]
+    fn buffers_round_up_to_words_and_rx_can_be_empty() {
+        let report = MemoryReport::of::<0, 2, 1522>();
+        assert_eq!((report.rx_descriptors, report.rx_buffers), (0, 0));
+        assert_eq!(report.tx_buffers, 2 * 1524);
+        assert_eq!(
+            format!("{report}"),
+            format!(
+                "EMAC DMA memory: {} bytes (descriptors: RX 0 + TX {}, buffers: RX 0 + TX 3048)",
+                report.total(),
+                report.tx_descriptors
+            )
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds the configured budget")]
+    fn over_budget_panics_at_runtime() {
+        let _ = MemoryReport::of::<10, 10, 1600>().within_budget(16 * 1024);
+    }
+}
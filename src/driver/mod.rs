@@ -6,11 +6,35 @@
 //! # Overview
 //!
 //! - [`config`]: Configuration types and builder patterns
+//! - [`borrowed_tx`]: Zero-copy transmit from `'static` memory
+//! - [`bring_up`]: Step-by-step bring-up report with failure hints
+//! - [`capabilities`]: Self-describing capability report
+//! - [`checksum`]: Per-frame RX checksum offload status
+//! - [`duplex`]: Heuristic duplex mismatch detection
 //! - [`error`]: Error types and result aliases
 //! - [`emac`]: The main EMAC controller implementation
 //! - [`interrupt`]: Interrupt status handling
 //! - [`filtering`]: MAC address, hash, and VLAN filtering
 //! - [`flow`]: IEEE 802.3 flow control
+//! - [`event_log`]: Fixed-size driver event log for postmortem analysis
+//! - [`fcs`]: Per-frame FCS status and optional software CRC-32 check
+//! - [`frame_buf`]: Caller-owned frame buffers for batched receive
+//! - [`frame_format`]: Ethernet II versus IEEE 802.3/LLC frame classification
+//! - `frame_trace`: Descriptor-level trace of transmitted frames
+//!   (`frame-trace` feature)
+//! - [`garp`]: Gratuitous ARP announcements on address and link changes
+//! - [`hw_state`]: Register state checks and fast resume after light sleep
+//! - [`keepalive`]: Periodic broadcast keeping switch MAC tables fresh
+//! - [`lifecycle`]: Type-state [`TypedEmac`] handle for compile-time state checks
+//! - [`liveness`]: Decoded DMA process states and watchdog liveness report
+//! - [`memory`]: Compile-time DMA memory report and budget check
+//! - [`ops`]: Object-safe [`EmacOps`] trait and type-erased [`EmacHandle`]
+//! - [`raw`]: Minimal [`RawEthernet`] trait shared with SPI Ethernet drivers
+//! - [`rx_timestamp`]: Software RX timestamps taken on the RX interrupt
+//! - [`shaper`]: Token-bucket transmit rate shaping
+//! - [`stats`]: Frame counters with per-destination RX classification
+//! - [`tx_queue`]: Software TX queue with per-frame deadlines
+//! - [`tx_rewrite`]: Per-destination source MAC and VLAN tag rewrite on TX
 //!
 //! # Usage
 //!
@@ -28,19 +52,82 @@
 //! - Integration facades (feature-gated modules under `integration`)
 
 // Submodules
+pub mod borrowed_tx;
+pub mod bring_up;
+pub mod capabilities;
+pub mod checksum;
 pub mod config;
+pub mod duplex;
 pub mod emac;
 pub mod error;
+pub mod event_log;
+pub mod fcs;
 pub mod filtering;
 pub mod flow;
+pub mod frame_buf;
+#[cfg(feature = "frame-trace")]
+#[cfg_attr(docsrs, doc(cfg(feature = "frame-trace")))]
+pub mod frame_trace;
+pub mod garp;
+pub mod hw_state;
 pub mod interrupt;
+pub mod keepalive;
+pub mod lifecycle;
+pub mod liveness;
+pub mod memory;
+pub mod ops;
+pub mod raw;
+pub mod rx_timestamp;
+pub mod shaper;
+pub mod tx_queue;
+pub mod tx_rewrite;
+
+// Target-independent modules, kept under their driver paths
+pub use crate::logic::{frame_format, stats};
 
 // Re-exports for convenience
+pub use borrowed_tx::BorrowedTx;
+pub use bring_up::{
+    BringUpReport, BringUpStep, NoResetPin, PhyBringUpError, PhyBringUpStage, PhyResetTiming,
+    StepOutcome, reset_and_probe_phy,
+};
+pub use capabilities::{BufferLocation, CompiledFeatures, DriverCapabilities};
+pub use checksum::{IpPayloadType, RxChecksumStatus};
 pub use config::{
-    ChecksumConfig, DmaBurstLen, Duplex, EmacConfig, FlowControlConfig, MAC_FILTER_SLOTS,
-    MacAddressFilter, MacFilterType, PauseLowThreshold, PhyInterface, RmiiClockMode, Speed, State,
-    TxChecksumMode,
+    ChecksumConfig, ControlFrameFilter, DmaBurstLen, DmaBurstPreset, DriveStrength, Duplex,
+    EmacConfig, FilterConfig, FlowControlConfig, LlcFrameFilter, MAC_FILTER_SLOTS,
+    MacAddressFilter, MacFilterType, PadPull, PauseLowThreshold, PhyInterface, RmiiClockMode,
+    RmiiTiming, RuntimeSettings, Speed, State, TokenStrategy, TxChecksumMode, TxInterruptPolicy,
+    VlanFilter, ipv4_multicast_mac, is_multicast_mac, mac_from_unique_id,
 };
+pub use duplex::{DEFAULT_MISMATCH_THRESHOLD, DuplexMismatch, DuplexMismatchDetector};
 pub use emac::{Emac, EmacDefault, EmacLarge, EmacSmall};
-pub use error::{ConfigError, ConfigResult, DmaError, DmaResult, Error, IoError, IoResult, Result};
+pub use error::{
+    ConfigError, ConfigResult, DmaError, DmaResult, Error, IoError, IoResult, PhyError, Result,
+};
+pub use event_log::{
+    AlarmCounter, EVENT_LOG_LEN, EmacEventSink, Event, EventKind, EventLog, EventThresholds,
+    RATE_ALARM_SLOTS, RateAlarm, ThresholdMetric,
+};
+pub use fcs::RxFcsStatus;
+pub use filtering::{FilterSnapshot, FrameFilterUpdate};
+pub use frame_buf::FrameBuf;
+pub use frame_format::{FRAME_FORMAT_HEAD_LEN, FrameFormat, MAX_8023_LENGTH, MIN_ETHER_TYPE};
+#[cfg(feature = "frame-trace")]
+pub use frame_trace::{FRAME_TRACE_LEN, FrameTrace, FrameTraceLog, TraceOutcome};
+pub use garp::{GARP_DEFAULT_REPEATS, GARP_DEFAULT_SPACING_MS, GarpAnnouncer, gratuitous_arp};
+pub use hw_state::HwStateLoss;
 pub use interrupt::InterruptStatus;
+pub use keepalive::{KEEPALIVE_ETHERTYPE, Keepalive, KeepaliveFrame};
+pub use lifecycle::{TypedEmac, TypedState};
+pub use liveness::{LivenessFault, LivenessReport, RxProcessState, TxProcessState};
+pub use memory::MemoryReport;
+pub use ops::{EmacHandle, EmacOps};
+pub use raw::RawEthernet;
+pub use rx_timestamp::SoftRxTimestamp;
+pub use shaper::Shaper;
+pub use stats::{RxFrameClass, RxOccupancy, Statistics, TxDiagnostics, TxErrorKind};
+pub use tx_queue::TxQueue;
+pub use tx_rewrite::{
+    TX_REWRITE_HEAD_LEN, TX_REWRITE_SLOTS, TxRewriteRule, TxRewriteTable, VlanTag,
+};
@@ -0,0 +1,282 @@
+//! Object-safe EMAC operations.
+//!
+//! [`Emac`] is generic over its buffer counts and size, so application code
+//! cannot hold "some EMAC" without naming those parameters. [`EmacOps`] covers
+//! the day-to-day operations (TX/RX, interrupts, link configuration) and is
+//! implemented for every `Emac<RX, TX, BUF>`, so HAL-agnostic code can take
+//! `&mut dyn EmacOps` or an [`EmacHandle`] instead.
+//!
+//! Initialization stays on the concrete type: `init()` is generic over the
+//! delay provider and is done once by whoever owns the static.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use ph_esp32_mac::{EmacHandle, EmacOps};
+//!
+//! fn pump(emac: &mut dyn EmacOps, buf: &mut [u8]) {
+//!     while emac.rx_available() {
+//!         if let Ok(len) = emac.receive(buf) {
+//!             let _ = emac.transmit(&buf[..len]);
+//!         }
+//!     }
+//! }
+//!
+//! pump(&mut emac, &mut buf);
+//! let mut handle = EmacHandle::new(&mut emac);
+//! pump(&mut *handle, &mut buf);
+//! ```
+
+use core::ops::{Deref, DerefMut};
+
+use super::config::{Duplex, RuntimeSettings, Speed, State};
+use super::emac::Emac;
+use super::error::Result;
+use super::interrupt::InterruptStatus;
+
+// =============================================================================
+// EmacOps Trait
+// =============================================================================
+
+/// Object-safe subset of the [`Emac`] API
+///
+/// Every method mirrors the inherent `Emac` method of the same name; see
+/// there for the error conditions.
+pub trait EmacOps {
+    /// Current driver state
+    fn state(&self) -> State;
+
+    /// Current MAC address
+    fn mac_address(&self) -> [u8; 6];
+
+    /// Size of each DMA buffer in bytes
+    fn buffer_size(&self) -> usize;
+
+    /// Start TX and RX
+    fn start(&mut self) -> Result<()>;
+
+    /// Stop TX and RX
+    fn stop(&mut self) -> Result<()>;
+
+    /// Enable RX after a deferred-RX start
+    fn enable_rx(&mut self) -> Result<()>;
+
+    /// Start RX only (listen-only)
+    fn enable_rx_only(&mut self) -> Result<()>;
+
+    /// Start TX only
+    fn enable_tx_only(&mut self) -> Result<()>;
+
+    /// Queue a frame for transmission
+    fn transmit(&mut self, data: &[u8]) -> Result<usize>;
+
+    /// Check if TX has a free descriptor
+    fn tx_ready(&self) -> bool;
+
+    /// Check if TX can accept a frame of given size
+    fn can_transmit(&self, len: usize) -> bool;
+
+    /// Receive the next frame into `buffer`
+    fn receive(&mut self, buffer: &mut [u8]) -> Result<usize>;
+
+    /// Check if a frame is available for receiving
+    fn rx_available(&self) -> bool;
+
+    /// Length of the next available frame
+    fn peek_rx_length(&self) -> Option<usize>;
+
+    /// Read the interrupt status without clearing it
+    fn interrupt_status(&self) -> InterruptStatus;
+
+    /// Clear the given interrupt flags
+    fn clear_interrupts(&self, status: InterruptStatus);
+
+    /// Read and clear the interrupt status (call from ISR)
+    fn handle_interrupt(&self) -> InterruptStatus;
+
+    /// Current link speed
+    fn speed(&self) -> Speed;
+
+    /// Current duplex mode
+    fn duplex(&self) -> Duplex;
+
+    /// Reprogram the MAC for new link parameters
+    fn update_link(&mut self, speed: Speed, duplex: Duplex);
+
+    /// Live runtime-tunable settings
+    fn settings(&self) -> RuntimeSettings;
+
+    /// Apply new runtime-tunable settings
+    fn apply_settings(&mut self, settings: RuntimeSettings) -> Result<()>;
+}
+
+impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize> EmacOps
+    for Emac<RX_BUFS, TX_BUFS, BUF_SIZE>
+{
+    fn state(&self) -> State {
+        Emac::state(self)
+    }
+
+    fn mac_address(&self) -> [u8; 6] {
+        *Emac::mac_address(self)
+    }
+
+    fn buffer_size(&self) -> usize {
+        Self::buffer_size()
+    }
+
+    fn start(&mut self) -> Result<()> {
+        Emac::start(self)
+    }
+
+    fn stop(&mut self) -> Result<()> {
+        Emac::stop(self)
+    }
+
+    fn enable_rx(&mut self) -> Result<()> {
+        Emac::enable_rx(self)
+    }
+
+    fn enable_rx_only(&mut self) -> Result<()> {
+        Emac::enable_rx_only(self)
+    }
+
+    fn enable_tx_only(&mut self) -> Result<()> {
+        Emac::enable_tx_only(self)
+    }
+
+    fn transmit(&mut self, data: &[u8]) -> Result<usize> {
+        Emac::transmit(self, data)
+    }
+
+    fn tx_ready(&self) -> bool {
+        Emac::tx_ready(self)
+    }
+
+    fn can_transmit(&self, len: usize) -> bool {
+        Emac::can_transmit(self, len)
+    }
+
+    fn receive(&mut self, buffer: &mut [u8]) -> Result<usize> {
+        Emac::receive(self, buffer)
+    }
+
+    fn rx_available(&self) -> bool {
+        Emac::rx_available(self)
+    }
+
+    fn peek_rx_length(&self) -> Option<usize> {
+        Emac::peek_rx_length(self)
+    }
+
+    fn interrupt_status(&self) -> InterruptStatus {
+        Emac::interrupt_status(self)
+    }
+
+    fn clear_interrupts(&self, status: InterruptStatus) {
+        Emac::clear_interrupts(self, status);
+    }
+
+    fn handle_interrupt(&self) -> InterruptStatus {
+        Emac::handle_interrupt(self)
+    }
+
+    fn speed(&self) -> Speed {
+        Emac::speed(self)
+    }
+
+    fn duplex(&self) -> Duplex {
+        Emac::duplex(self)
+    }
+
+    fn update_link(&mut self, speed: Speed, duplex: Duplex) {
+        Emac::update_link(self, speed, duplex);
+    }
+
+    fn settings(&self) -> RuntimeSettings {
+        Emac::settings(self)
+    }
+
+    fn apply_settings(&mut self, settings: RuntimeSettings) -> Result<()> {
+        Emac::apply_settings(self, settings)
+    }
+}
+
+// =============================================================================
+// Type-Erased Handle
+// =============================================================================
+
+/// Type-erased mutable borrow of an EMAC
+///
+/// Stores `&mut dyn EmacOps` so a struct can hold an EMAC without carrying
+/// its const generics. Dereferences to `dyn EmacOps`.
+pub struct EmacHandle<'a> {
+    emac: &'a mut dyn EmacOps,
+}
+
+impl<'a> EmacHandle<'a> {
+    /// Wrap any EMAC
+    pub fn new(emac: &'a mut dyn EmacOps) -> Self {
+        Self { emac }
+    }
+}
+
+impl<'a, const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
+    From<&'a mut Emac<RX_BUFS, TX_BUFS, BUF_SIZE>> for EmacHandle<'a>
+{
+    fn from(emac: &'a mut Emac<RX_BUFS, TX_BUFS, BUF_SIZE>) -> Self {
+        Self::new(emac)
+    }
+}
+
+impl<'a> Deref for EmacHandle<'a> {
+    type Target = dyn EmacOps + 'a;
+
+    fn deref(&self) -> &Self::Target {
+        self.emac
+    }
+}
+
+impl DerefMut for EmacHandle<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.emac
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::driver::error::{Error, IoError};
+
+    #[test]
+    fn different_instantiations_share_one_trait_object_type() {
+        let mut small: Emac<2, 2, 512> = Emac::new();
+        let mut large: Emac<10, 10, 1600> = Emac::new();
+        let emacs: [&mut dyn EmacOps; 2] = [&mut small, &mut large];
+
+        assert_eq!(emacs[0].buffer_size(), 512);
+        assert_eq!(emacs[1].buffer_size(), 1600);
+        for emac in emacs {
+            assert_eq!(emac.state(), State::Uninitialized);
+            assert!(!emac.rx_available());
+        }
+    }
+
+    #[test]
+    fn handle_forwards_to_emac() {
+        let mut emac: Emac<4, 4, 1600> = Emac::new();
+        let mut handle = EmacHandle::from(&mut emac);
+
+        assert_eq!(handle.speed(), Speed::Mbps100);
+        assert_eq!(handle.duplex(), Duplex::Full);
+        assert_eq!(handle.start(), Err(Error::Io(IoError::InvalidState)));
+        assert_eq!(
+            handle.transmit(&[0u8; 64]),
+            Err(Error::Io(IoError::InvalidState))
+        );
+        assert_eq!(
+            handle.apply_settings(RuntimeSettings::new()),
+            Err(Error::Io(IoError::InvalidState))
+        );
+    }
+}
@@ -0,0 +1,168 @@
+//! Minimal raw Ethernet interface shared with other MAC drivers.
+//!
+//! [`RawEthernet`] is the smallest frame-level API a bridge or forwarder
+//! needs: send a frame, receive a frame, ask whether one is waiting, and
+//! read the interface's MAC address. [`Emac`] implements it, and so can SPI
+//! Ethernet drivers (W5500 in MACRAW mode, ENC28J60), so code that moves
+//! frames between the on-chip EMAC and a secondary interface can be written
+//! once, generic over both.
+//!
+//! Errors are an associated type: each driver keeps its own error enum
+//! (for the EMAC, [`Error`]).
+//!
+//! # Example
+//!
+//! ```ignore
+//! use ph_esp32_mac::RawEthernet;
+//!
+//! fn forward<A: RawEthernet, B: RawEthernet>(from: &mut A, to: &mut B, buf: &mut [u8]) {
+//!     while from.frame_available() {
+//!         if let Ok(Some(len)) = from.recv(buf) {
+//!             let _ = to.send(&buf[..len]);
+//!         }
+//!     }
+//! }
+//!
+//! forward(&mut emac, &mut w5500, &mut buf);
+//! forward(&mut w5500, &mut emac, &mut buf);
+//! ```
+
+use super::emac::Emac;
+use super::error::{Error, IoError};
+
+/// Frame-level send/receive API common to Ethernet interfaces
+pub trait RawEthernet {
+    /// Error reported by the interface
+    type Error;
+
+    /// Queue `frame` (destination MAC onward, without FCS) for transmission
+    ///
+    /// Returns the number of bytes queued.
+    fn send(&mut self, frame: &[u8]) -> Result<usize, Self::Error>;
+
+    /// Copy the next received frame into `buf`
+    ///
+    /// Returns `Ok(None)` if no frame is waiting.
+    fn recv(&mut self, buf: &mut [u8]) -> Result<Option<usize>, Self::Error>;
+
+    /// True if [`recv`](Self::recv) has a frame to return
+    ///
+    /// Takes `&mut self` because SPI devices answer this with a bus
+    /// transaction; an implementation that cannot reach its device reports
+    /// `false`.
+    fn frame_available(&mut self) -> bool;
+
+    /// MAC address the interface sends from and filters on
+    fn mac_address(&self) -> [u8; 6];
+}
+
+impl<T: RawEthernet + ?Sized> RawEthernet for &mut T {
+    type Error = T::Error;
+
+    fn send(&mut self, frame: &[u8]) -> Result<usize, Self::Error> {
+        (**self).send(frame)
+    }
+
+    fn recv(&mut self, buf: &mut [u8]) -> Result<Option<usize>, Self::Error> {
+        (**self).recv(buf)
+    }
+
+    fn frame_available(&mut self) -> bool {
+        (**self).frame_available()
+    }
+
+    fn mac_address(&self) -> [u8; 6] {
+        (**self).mac_address()
+    }
+}
+
+impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize> RawEthernet
+    for Emac<RX_BUFS, TX_BUFS, BUF_SIZE>
+{
+    type Error = Error;
+
+    fn send(&mut self, frame: &[u8]) -> Result<usize, Error> {
+        self.transmit(frame)
+    }
+
+    /// [`Emac::receive`], with `IncompleteFrame` reported as `Ok(None)`
+    fn recv(&mut self, buf: &mut [u8]) -> Result<Option<usize>, Error> {
+        match self.receive(buf) {
+            Ok(len) => Ok(Some(len)),
+            Err(Error::Io(IoError::IncompleteFrame)) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn frame_available(&mut self) -> bool {
+        self.rx_available()
+    }
+
+    fn mac_address(&self) -> [u8; 6] {
+        *Emac::mac_address(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::driver::config::EmacConfig;
+    use crate::internal::register::sim;
+
+    /// Loopback reflector, standing in for an SPI interface
+    struct Reflector {
+        frame: Option<([u8; 64], usize)>,
+    }
+
+    impl RawEthernet for Reflector {
+        type Error = ();
+
+        fn send(&mut self, frame: &[u8]) -> Result<usize, ()> {
+            let mut copy = [0u8; 64];
+            copy[..frame.len()].copy_from_slice(frame);
+            self.frame = Some((copy, frame.len()));
+            Ok(frame.len())
+        }
+
+        fn recv(&mut self, buf: &mut [u8]) -> Result<Option<usize>, ()> {
+            Ok(self.frame.take().map(|(data, len)| {
+                buf[..len].copy_from_slice(&data[..len]);
+                len
+            }))
+        }
+
+        fn frame_available(&mut self) -> bool {
+            self.frame.is_some()
+        }
+
+        fn mac_address(&self) -> [u8; 6] {
+            [0x02, 0, 0, 0, 0, 0x55]
+        }
+    }
+
+    fn forward_one<A: RawEthernet, B: RawEthernet>(from: &mut A, to: &mut B) -> Option<usize> {
+        let mut buf = [0u8; 256];
+        let len = from.recv(&mut buf).ok()??;
+        to.send(&buf[..len]).ok()
+    }
+
+    #[test]
+    fn forwards_between_emac_and_other_interface() {
+        sim::reset();
+        let mut emac: Emac<4, 4, 256> = Emac::new();
+        emac.sim_init(EmacConfig::new());
+        emac.start().unwrap();
+        let mut other = Reflector { frame: None };
+
+        assert!(!RawEthernet::frame_available(&mut emac));
+        assert_eq!(RawEthernet::recv(&mut emac, &mut [0u8; 64]), Ok(None));
+        assert_eq!(forward_one(&mut emac, &mut other), None);
+
+        assert!(emac.dma.sim_rx_frame(&[0xFF; 60], 0));
+        assert!(RawEthernet::frame_available(&mut emac));
+        assert_eq!(forward_one(&mut emac, &mut other), Some(60));
+        assert!(other.frame_available());
+        assert_eq!(forward_one(&mut other, &mut &mut emac), Some(60));
+        assert_eq!(other.mac_address(), [0x02, 0, 0, 0, 0, 0x55]);
+    }
+}
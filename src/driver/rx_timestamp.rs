@@ -0,0 +1,210 @@
+//! Software RX timestamps for EMACs without PTP.
+//!
+//! The ESP32 EMAC can stamp frames in hardware only with IEEE 1588 set up.
+//! Data loggers that just need to line received frames up with other events
+//! to the millisecond can use a software stamp instead: with
+//! [`RuntimeSettings::rx_soft_timestamps`](super::config::RuntimeSettings::rx_soft_timestamps)
+//! on and a [`Clock`](crate::hal::Clock) installed,
+//! [`Emac::handle_interrupt`](super::emac::Emac::handle_interrupt) reads the
+//! clock when it sees the RX interrupt, and `receive()` attaches that time to
+//! the frames it pops. Read it with
+//! [`Emac::last_rx_timestamp`](super::emac::Emac::last_rx_timestamp).
+//!
+//! # Accuracy
+//!
+//! A stamp is the time the first RX interrupt was serviced after the previous
+//! stamp was taken by `receive()`. Compared with the frame's arrival it is:
+//!
+//! - late by the interrupt latency (entry, plus any higher-priority work or
+//!   critical sections that delay the handler), typically tens of
+//!   microseconds;
+//! - shared by every frame completed before that interrupt was serviced.
+//!   Those frames are marked [`SoftRxTimestamp::shared`], and the earlier
+//!   ones arrived before the stamp by up to the burst length;
+//! - early for frames that complete after the interrupt but are received
+//!   before the next one is serviced (a task that polls faster than the ISR
+//!   runs). Those are marked `shared` too;
+//! - given to whichever frame `receive()` returns next, which is the oldest
+//!   one queued and not necessarily one the interrupt announced. A task that
+//!   falls behind the ISR hands a later interrupt's stamp to a frame that
+//!   arrived before it, by up to the queue depth. Drain the ring after each
+//!   interrupt to keep stamps with their frames.
+//!
+//! Frames received with no RX interrupt serviced since the EMAC started
+//! (polling only, or RX interrupt disabled) get no stamp. Use the hardware
+//! PTP timestamps for anything below a millisecond.
+//!
+//! Applications that acknowledge interrupts through the async or shared
+//! handlers instead of `Emac::handle_interrupt` call
+//! [`Emac::stamp_rx_interrupt`](super::emac::Emac::stamp_rx_interrupt) from
+//! their ISR.
+
+use core::cell::UnsafeCell;
+use core::sync::atomic::{AtomicU8, Ordering};
+
+/// Software timestamp of a received frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SoftRxTimestamp {
+    /// [`Clock::now_us`](crate::hal::Clock::now_us) when the RX interrupt
+    /// was serviced
+    pub us: u64,
+    /// No new interrupt was serviced for this frame; the stamp is the one
+    /// given to the previous frame
+    pub shared: bool,
+}
+
+impl SoftRxTimestamp {
+    /// Timestamp in milliseconds, wrapping like
+    /// [`Clock::now_ms`](crate::hal::Clock::now_ms)
+    #[must_use]
+    pub const fn ms(&self) -> u32 {
+        (self.us / 1_000) as u32
+    }
+}
+
+/// Interrupt time handed from the ISR to `receive()`
+///
+/// A single slot: the ISR fills it only when it is empty, so it holds the
+/// first RX interrupt since `receive()` last took a stamp.
+pub(crate) struct RxStampSlot {
+    /// [`EMPTY`](Self::EMPTY), [`WRITING`](Self::WRITING) or
+    /// [`FULL`](Self::FULL)
+    state: AtomicU8,
+    /// Written by the caller that moved `state` to `WRITING`, read by
+    /// `receive()` while it is `FULL`
+    us: UnsafeCell<u64>,
+    /// Stamp given to the last frame, reused while no new one arrives
+    last: Option<u64>,
+}
+
+// SAFETY: `us` is only written by the one `record` call that won the
+// EMPTY -> WRITING exchange, and only read by `take` while the state is
+// FULL, which `record` publishes with Release after its write. Nothing else
+// leaves FULL or WRITING except the owner of that state, so no two accesses
+// to `us` overlap.
+unsafe impl Sync for RxStampSlot {}
+
+impl RxStampSlot {
+    /// No stamp waiting
+    const EMPTY: u8 = 0;
+    /// A `record` call is writing the stamp
+    const WRITING: u8 = 1;
+    /// A stamp is waiting for `take`
+    const FULL: u8 = 2;
+
+    /// Empty slot
+    pub(crate) const fn new() -> Self {
+        Self {
+            state: AtomicU8::new(Self::EMPTY),
+            us: UnsafeCell::new(0),
+            last: None,
+        }
+    }
+
+    iram! {
+        /// Record an RX interrupt at `us` (ISR side)
+        ///
+        /// Ignored while an earlier stamp is still waiting to be taken or
+        /// another caller is recording one, so concurrent callers on either core
+        /// are safe.
+        pub(crate) fn record(&self, us: u64) {
+            if self
+                .state
+                .compare_exchange(
+                    Self::EMPTY,
+                    Self::WRITING,
+                    Ordering::Acquire,
+                    Ordering::Relaxed,
+                )
+                .is_err()
+            {
+                return;
+            }
+            // SAFETY: winning the exchange above makes this the only writer,
+            // and `take` does not read `us` until the state is FULL.
+            unsafe { *self.us.get() = us };
+            self.state.store(Self::FULL, Ordering::Release);
+        }
+    }
+
+    /// Stamp for the frame `receive()` is returning (task side)
+    #[inline(always)]
+    pub(crate) fn take(&mut self) -> Option<SoftRxTimestamp> {
+        if self.state.load(Ordering::Acquire) == Self::FULL {
+            // SAFETY: the state is FULL, so no `record` writes `us` until it
+            // is set back to EMPTY below.
+            let us = unsafe { *self.us.get() };
+            self.state.store(Self::EMPTY, Ordering::Release);
+            self.last = Some(us);
+            return Some(SoftRxTimestamp { us, shared: false });
+        }
+        self.last.map(|us| SoftRxTimestamp { us, shared: true })
+    }
+
+    /// Forget all stamps, e.g. when the EMAC restarts
+    ///
+    /// A stamp being recorded concurrently is left to complete.
+    #[inline(always)]
+    pub(crate) fn clear(&mut self) {
+        let _ = self.state.compare_exchange(
+            Self::FULL,
+            Self::EMPTY,
+            Ordering::AcqRel,
+            Ordering::Relaxed,
+        );
+        self.last = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_interrupt_wins_and_is_shared_until_the_next() {
+        let mut slot = RxStampSlot::new();
+        assert_eq!(slot.take(), None);
+
+        slot.record(1_000);
+        slot.record(1_500); // still full: ignored
+        assert_eq!(
+            slot.take(),
+            Some(SoftRxTimestamp {
+                us: 1_000,
+                shared: false
+            })
+        );
+        // Second frame of the same burst
+        assert_eq!(
+            slot.take(),
+            Some(SoftRxTimestamp {
+                us: 1_000,
+                shared: true
+            })
+        );
+
+        slot.record(2_250);
+        let stamp = slot.take().unwrap();
+        assert!(!stamp.shared);
+        assert_eq!(stamp.ms(), 2);
+
+        slot.clear();
+        assert_eq!(slot.take(), None);
+    }
+
+    #[test]
+    fn record_skips_a_slot_being_written() {
+        let mut slot = RxStampSlot::new();
+        // Another caller won the slot and is mid-write
+        slot.state.store(RxStampSlot::WRITING, Ordering::Relaxed);
+        slot.record(1_000);
+        slot.clear();
+        assert_eq!(slot.state.load(Ordering::Relaxed), RxStampSlot::WRITING);
+        assert_eq!(slot.take(), None);
+
+        slot.state.store(RxStampSlot::EMPTY, Ordering::Relaxed);
+        slot.record(2_000);
+        assert_eq!(slot.take().map(|stamp| stamp.us), Some(2_000));
+    }
+}
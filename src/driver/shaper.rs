@@ -0,0 +1,248 @@
+//! Transmit rate shaping.
+//!
+//! [`Shaper`] is a token bucket placed in front of `transmit()`: it refills at
+//! `rate_bps` and holds at most `burst` bytes, so a device on a shared
+//! industrial segment can cap its own egress rate instead of relying on a
+//! switch to police it. Frames that do not conform are refused with
+//! [`IoError::RateLimited`] rather than delayed, leaving the retry policy to
+//! the caller (or to a [`TxQueue`](super::tx_queue::TxQueue) in front).
+//!
+//! The driver has no clock; every call takes the current time in
+//! microseconds from the caller. Elapsed time is computed with wrapping
+//! arithmetic, so a free-running 32-bit microsecond counter works as long as
+//! the shaper is consulted at least once per wrap (~71 minutes); a longer gap
+//! only under-refills a bucket that is already full.
+//!
+//! # Example
+//!
+//! ```ignore
+//! // 2 Mbit/s, bursts of up to four full-size frames
+//! let mut shaper = Shaper::new(2_000_000, 4 * 1518);
+//!
+//! match shaper.transmit(&mut emac, &frame, now_us()) {
+//!     Ok(_) => {}
+//!     Err(Error::Io(IoError::RateLimited)) => { /* retry later */ }
+//!     Err(e) => return Err(e),
+//! }
+//! ```
+
+use super::error::{IoError, Result};
+use super::ops::EmacOps;
+
+/// Bucket units per byte: tokens are kept in bit-microseconds so refills at
+/// any rate are exact integers
+const UNITS_PER_BYTE: u64 = 8 * 1_000_000;
+
+/// Token-bucket egress rate limiter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Shaper {
+    rate_bps: u32,
+    burst: u32,
+    frame_overhead: u32,
+    tokens: u64,
+    last_us: Option<u32>,
+    limited: u32,
+}
+
+impl Shaper {
+    /// Create a shaper allowing `rate_bps` bits per second with bursts of up
+    /// to `burst` bytes
+    ///
+    /// The bucket starts full. `burst` should be at least one maximum-size
+    /// frame; a larger frame is charged a full bucket.
+    #[must_use]
+    pub const fn new(rate_bps: u32, burst: u32) -> Self {
+        Self {
+            rate_bps,
+            burst,
+            frame_overhead: 0,
+            tokens: burst as u64 * UNITS_PER_BYTE,
+            last_us: None,
+            limited: 0,
+        }
+    }
+
+    /// Charge `bytes` extra per frame on top of its length
+    ///
+    /// Use 24 (preamble, SFD, FCS and inter-frame gap) to shape the rate on
+    /// the wire rather than the frame payload rate.
+    #[must_use]
+    pub const fn with_frame_overhead(mut self, bytes: u32) -> Self {
+        self.frame_overhead = bytes;
+        self
+    }
+
+    /// Configured rate in bits per second
+    #[must_use]
+    pub const fn rate_bps(&self) -> u32 {
+        self.rate_bps
+    }
+
+    /// Configured burst size in bytes
+    #[must_use]
+    pub const fn burst(&self) -> u32 {
+        self.burst
+    }
+
+    /// Frames refused because they did not conform
+    #[must_use]
+    pub const fn limited(&self) -> u32 {
+        self.limited
+    }
+
+    /// Bytes that may be sent right now
+    pub fn available(&mut self, now_us: u32) -> u32 {
+        self.refill(now_us);
+        (self.tokens / UNITS_PER_BYTE) as u32
+    }
+
+    /// Take tokens for a frame of `len` bytes if it conforms
+    ///
+    /// Returns `false` (and counts the frame as limited) if the bucket does
+    /// not hold enough tokens yet.
+    pub fn try_acquire(&mut self, len: usize, now_us: u32) -> bool {
+        self.refill(now_us);
+        let cost = self.cost(len);
+        if self.tokens < cost {
+            self.limited = self.limited.wrapping_add(1);
+            return false;
+        }
+        self.tokens -= cost;
+        true
+    }
+
+    /// Microseconds until a frame of `len` bytes would conform (0 if now)
+    pub fn delay_us(&mut self, len: usize, now_us: u32) -> u32 {
+        self.refill(now_us);
+        let missing = self.cost(len).saturating_sub(self.tokens);
+        if missing == 0 {
+            return 0;
+        }
+        if self.rate_bps == 0 {
+            return u32::MAX;
+        }
+        missing.div_ceil(self.rate_bps as u64).min(u32::MAX as u64) as u32
+    }
+
+    /// Transmit `data` through `emac` if the frame conforms to the rate
+    ///
+    /// Tokens are refunded if the EMAC refuses the frame.
+    ///
+    /// # Errors
+    /// - `RateLimited` - Not enough tokens yet; see [`delay_us`](Self::delay_us)
+    /// - Any error from the EMAC's `transmit`
+    pub fn transmit<E: EmacOps + ?Sized>(
+        &mut self,
+        emac: &mut E,
+        data: &[u8],
+        now_us: u32,
+    ) -> Result<usize> {
+        if !self.try_acquire(data.len(), now_us) {
+            return Err(IoError::RateLimited.into());
+        }
+        let result = emac.transmit(data);
+        if result.is_err() {
+            self.tokens += self.cost(data.len());
+        }
+        result
+    }
+
+    /// Refill the bucket to full and forget the last timestamp
+    pub fn reset(&mut self) {
+        self.tokens = self.capacity();
+        self.last_us = None;
+    }
+
+    fn capacity(&self) -> u64 {
+        self.burst as u64 * UNITS_PER_BYTE
+    }
+
+    fn cost(&self, len: usize) -> u64 {
+        let bytes = len as u64 + self.frame_overhead as u64;
+        (bytes * UNITS_PER_BYTE).min(self.capacity())
+    }
+
+    fn refill(&mut self, now_us: u32) {
+        if let Some(last) = self.last_us {
+            let elapsed = now_us.wrapping_sub(last) as u64;
+            let added = elapsed.saturating_mul(self.rate_bps as u64);
+            self.tokens = self.tokens.saturating_add(added).min(self.capacity());
+        }
+        self.last_us = Some(now_us);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::driver::error::{DmaError, Error};
+    use crate::testing::MockEmac;
+
+    #[test]
+    fn burst_then_limited() {
+        let mut shaper = Shaper::new(8_000, 300);
+        assert!(shaper.try_acquire(100, 0));
+        assert!(shaper.try_acquire(200, 0));
+        assert!(!shaper.try_acquire(1, 0));
+        assert_eq!(shaper.limited(), 1);
+    }
+
+    #[test]
+    fn refills_at_rate() {
+        // 8 kbit/s = 1 byte per millisecond
+        let mut shaper = Shaper::new(8_000, 100);
+        assert!(shaper.try_acquire(100, 0));
+        assert_eq!(shaper.available(50_000), 50);
+        assert!(!shaper.try_acquire(60, 50_000));
+        assert_eq!(shaper.delay_us(60, 50_000), 10_000);
+        assert!(shaper.try_acquire(60, 60_000));
+    }
+
+    #[test]
+    fn refill_caps_at_burst() {
+        let mut shaper = Shaper::new(1_000_000, 1000);
+        assert!(shaper.try_acquire(1000, 0));
+        assert_eq!(shaper.available(u32::MAX / 2), 1000);
+    }
+
+    #[test]
+    fn elapsed_time_wraps() {
+        let mut shaper = Shaper::new(8_000, 100);
+        assert!(shaper.try_acquire(100, u32::MAX - 4_999));
+        assert_eq!(shaper.available(5_000), 10);
+    }
+
+    #[test]
+    fn frame_overhead_is_charged() {
+        let mut shaper = Shaper::new(8_000, 100).with_frame_overhead(24);
+        assert!(shaper.try_acquire(76, 0));
+        assert_eq!(shaper.available(0), 0);
+    }
+
+    #[test]
+    fn oversized_frame_costs_full_bucket() {
+        let mut shaper = Shaper::new(8_000, 100);
+        assert!(shaper.try_acquire(1500, 0));
+        assert!(!shaper.try_acquire(1, 0));
+    }
+
+    #[test]
+    fn transmit_refunds_on_emac_error() {
+        let mut shaper = Shaper::new(8_000, 100);
+        let mut emac = MockEmac::new(0);
+        assert_eq!(
+            shaper.transmit(&mut emac, &[0; 60], 0),
+            Err(Error::Dma(DmaError::NoDescriptorsAvailable))
+        );
+        assert_eq!(shaper.available(0), 100);
+
+        emac.tx_free = 2;
+        assert_eq!(shaper.transmit(&mut emac, &[0; 60], 0), Ok(60));
+        assert_eq!(
+            shaper.transmit(&mut emac, &[0; 60], 0),
+            Err(Error::Io(IoError::RateLimited))
+        );
+        assert_eq!(emac.sent.len(), 1);
+    }
+}
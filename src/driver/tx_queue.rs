@@ -0,0 +1,318 @@
+//! Software TX queue with per-frame deadlines.
+//!
+//! The DMA ring only holds a handful of frames. [`TxQueue`] sits in front of
+//! it so the application can keep queuing while the ring is full or the link
+//! is down, and lets each frame carry an optional deadline. A frame still
+//! queued when its deadline passes is dropped and counted instead of being
+//! sent late, so a backlog of stale telemetry built up during a link stall
+//! does not delay fresh data once the link returns.
+//!
+//! Time is whatever the caller passes as `now` (typically milliseconds since
+//! boot). Comparisons wrap, so deadlines must stay within `i32::MAX` ticks of
+//! the current time.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let mut queue: TxQueue<8, 1518> = TxQueue::new();
+//!
+//! // Control traffic is useless after 20 ms
+//! queue.push_with_ttl(&frame, now_ms(), 20)?;
+//!
+//! // From the main loop or TX-complete handling
+//! queue.flush(&mut emac, now_ms())?;
+//! if queue.stale_dropped() > 0 { /* link stalled */ }
+//! ```
+
+use super::error::{DmaError, Error, Result};
+use super::ops::EmacOps;
+
+/// Fixed-capacity FIFO of frames waiting for TX descriptors
+///
+/// Holds up to `N` frames of at most `MTU` bytes each, copied in on push.
+pub struct TxQueue<const N: usize, const MTU: usize> {
+    frames: [[u8; MTU]; N],
+    lens: [usize; N],
+    deadlines: [Option<u32>; N],
+    head: usize,
+    len: usize,
+    stale_dropped: u32,
+}
+
+impl<const N: usize, const MTU: usize> TxQueue<N, MTU> {
+    /// Create an empty queue
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            frames: [[0u8; MTU]; N],
+            lens: [0; N],
+            deadlines: [None; N],
+            head: 0,
+            len: 0,
+            stale_dropped: 0,
+        }
+    }
+
+    /// Maximum number of queued frames
+    #[must_use]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Number of queued frames
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// True if no frames are queued
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// True if another push would fail
+    #[must_use]
+    pub const fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// Frames dropped because their deadline passed before they were sent
+    #[must_use]
+    pub const fn stale_dropped(&self) -> u32 {
+        self.stale_dropped
+    }
+
+    /// Queue a frame without a deadline
+    ///
+    /// # Errors
+    /// Same as [`push_with_deadline`](Self::push_with_deadline).
+    pub fn push(&mut self, frame: &[u8]) -> Result<()> {
+        self.enqueue(frame, None)
+    }
+
+    /// Queue a frame that must be handed to the DMA by `deadline`
+    ///
+    /// # Errors
+    /// - `InvalidLength` - Frame is empty
+    /// - `FrameTooLarge` - Frame exceeds `MTU`
+    /// - `NoDescriptorsAvailable` - Queue is full
+    pub fn push_with_deadline(&mut self, frame: &[u8], deadline: u32) -> Result<()> {
+        self.enqueue(frame, Some(deadline))
+    }
+
+    /// Queue a frame that expires `ttl` ticks after `now`
+    ///
+    /// # Errors
+    /// Same as [`push_with_deadline`](Self::push_with_deadline).
+    pub fn push_with_ttl(&mut self, frame: &[u8], now: u32, ttl: u32) -> Result<()> {
+        self.enqueue(frame, Some(now.wrapping_add(ttl)))
+    }
+
+    fn enqueue(&mut self, frame: &[u8], deadline: Option<u32>) -> Result<()> {
+        if frame.is_empty() {
+            return Err(DmaError::InvalidLength.into());
+        }
+        if frame.len() > MTU {
+            return Err(DmaError::FrameTooLarge.into());
+        }
+        if self.is_full() {
+            return Err(DmaError::NoDescriptorsAvailable.into());
+        }
+
+        let slot = (self.head + self.len) % N;
+        self.frames[slot][..frame.len()].copy_from_slice(frame);
+        self.lens[slot] = frame.len();
+        self.deadlines[slot] = deadline;
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Drop every expired frame, wherever it sits in the queue
+    ///
+    /// Returns the number of frames dropped. Order of the remaining frames is
+    /// preserved.
+    pub fn drop_expired(&mut self, now: u32) -> usize {
+        let mut kept = 0;
+        for i in 0..self.len {
+            let from = (self.head + i) % N;
+            if is_expired(self.deadlines[from], now) {
+                continue;
+            }
+            let to = (self.head + kept) % N;
+            if to != from {
+                self.frames[to] = self.frames[from];
+                self.lens[to] = self.lens[from];
+                self.deadlines[to] = self.deadlines[from];
+            }
+            kept += 1;
+        }
+
+        let dropped = self.len - kept;
+        self.len = kept;
+        self.stale_dropped = self.stale_dropped.wrapping_add(dropped as u32);
+        dropped
+    }
+
+    /// Hand queued frames to the EMAC until the queue or the TX ring is empty
+    ///
+    /// Expired frames at the front are dropped instead of sent. Returns the
+    /// number of frames transmitted; a full TX ring is not an error and
+    /// leaves the remaining frames queued.
+    ///
+    /// # Errors
+    /// Any other `transmit` error (e.g. `InvalidState`); the failed frame
+    /// stays at the front of the queue.
+    pub fn flush<E: EmacOps + ?Sized>(&mut self, emac: &mut E, now: u32) -> Result<usize> {
+        let mut sent = 0;
+        while self.len > 0 {
+            let slot = self.head;
+            if is_expired(self.deadlines[slot], now) {
+                self.stale_dropped = self.stale_dropped.wrapping_add(1);
+                self.pop_front();
+                continue;
+            }
+
+            match emac.transmit(&self.frames[slot][..self.lens[slot]]) {
+                Ok(_) => {
+                    self.pop_front();
+                    sent += 1;
+                }
+                Err(Error::Dma(DmaError::NoDescriptorsAvailable | DmaError::DescriptorBusy)) => {
+                    break;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(sent)
+    }
+
+    /// Discard all queued frames (the stale counter is kept)
+    pub fn clear(&mut self) {
+        self.head = 0;
+        self.len = 0;
+    }
+
+    fn pop_front(&mut self) {
+        self.head = (self.head + 1) % N;
+        self.len -= 1;
+    }
+}
+
+impl<const N: usize, const MTU: usize> Default for TxQueue<N, MTU> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// True if `deadline` is set and `now` is past it (wrapping)
+fn is_expired(deadline: Option<u32>, now: u32) -> bool {
+    deadline.is_some_and(|d| (now.wrapping_sub(d) as i32) > 0)
+}
+
+#[cfg(test)]
+#[allow(clippy::std_instead_of_alloc)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::driver::config::State;
+    use crate::driver::error::IoError;
+    use crate::testing::MockEmac;
+    use std::vec;
+
+    #[test]
+    fn push_validates_frame() {
+        let mut queue: TxQueue<2, 64> = TxQueue::new();
+        assert_eq!(queue.push(&[]), Err(DmaError::InvalidLength.into()));
+        assert_eq!(queue.push(&[0; 65]), Err(DmaError::FrameTooLarge.into()));
+
+        queue.push(&[1; 64]).unwrap();
+        queue.push(&[2; 10]).unwrap();
+        assert!(queue.is_full());
+        assert_eq!(
+            queue.push(&[3]),
+            Err(DmaError::NoDescriptorsAvailable.into())
+        );
+    }
+
+    #[test]
+    fn flush_sends_in_order_until_ring_full() {
+        let mut queue: TxQueue<4, 64> = TxQueue::new();
+        for i in 1..=3u8 {
+            queue.push(&[i; 8]).unwrap();
+        }
+
+        let mut emac = MockEmac::new(2);
+        assert_eq!(queue.flush(&mut emac, 0), Ok(2));
+        assert_eq!(queue.len(), 1);
+        assert_eq!(emac.sent, [vec![1u8; 8], vec![2u8; 8]]);
+
+        emac.tx_free = 4;
+        assert_eq!(queue.flush(&mut emac, 0), Ok(1));
+        assert!(queue.is_empty());
+        assert_eq!(emac.sent[2], [3u8; 8]);
+    }
+
+    #[test]
+    fn flush_drops_expired_frames() {
+        let mut queue: TxQueue<4, 64> = TxQueue::new();
+        queue.push_with_ttl(&[1; 8], 100, 10).unwrap();
+        queue.push(&[2; 8]).unwrap();
+        queue.push_with_deadline(&[3; 8], 200).unwrap();
+
+        let mut emac = MockEmac::new(8);
+        // At the deadline a frame is still on time
+        assert_eq!(queue.flush(&mut emac, 110), Ok(3));
+        assert_eq!(queue.stale_dropped(), 0);
+
+        queue.push_with_ttl(&[4; 8], 100, 10).unwrap();
+        queue.push(&[5; 8]).unwrap();
+        assert_eq!(queue.flush(&mut emac, 111), Ok(1));
+        assert_eq!(queue.stale_dropped(), 1);
+        assert_eq!(emac.sent.last().unwrap(), &[5u8; 8]);
+    }
+
+    #[test]
+    fn drop_expired_compacts_queue() {
+        let mut queue: TxQueue<4, 64> = TxQueue::new();
+        // Rotate the ring so the compaction wraps around
+        queue.push(&[0; 8]).unwrap();
+        queue.flush(&mut MockEmac::new(1), 0).unwrap();
+
+        queue.push_with_deadline(&[1; 8], 10).unwrap();
+        queue.push(&[2; 8]).unwrap();
+        queue.push_with_deadline(&[3; 8], 5).unwrap();
+        queue.push_with_deadline(&[4; 8], 50).unwrap();
+
+        assert_eq!(queue.drop_expired(20), 2);
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue.stale_dropped(), 2);
+
+        let mut emac = MockEmac::new(8);
+        assert_eq!(queue.flush(&mut emac, 20), Ok(2));
+        assert_eq!(emac.sent, [vec![2u8; 8], vec![4u8; 8]]);
+    }
+
+    #[test]
+    fn deadlines_wrap() {
+        let mut queue: TxQueue<2, 64> = TxQueue::new();
+        queue.push_with_ttl(&[1; 8], u32::MAX - 5, 10).unwrap();
+        assert_eq!(queue.drop_expired(2), 0);
+        assert_eq!(queue.drop_expired(5), 1);
+    }
+
+    #[test]
+    fn flush_reports_hard_errors_and_keeps_frame() {
+        let mut queue: TxQueue<2, 64> = TxQueue::new();
+        queue.push(&[1; 8]).unwrap();
+
+        let mut emac = MockEmac::new(2);
+        emac.state = State::Stopped;
+        assert_eq!(
+            queue.flush(&mut emac, 0),
+            Err(Error::Io(IoError::InvalidState))
+        );
+        assert_eq!(queue.len(), 1);
+    }
+}
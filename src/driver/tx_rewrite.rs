@@ -0,0 +1,330 @@
+//! Per-destination TX header rewrite.
+//!
+//! Gateways and bridges forward frames they did not build: the source MAC
+//! has to become the EMAC's own, and traffic towards some peers needs a
+//! VLAN tag. Doing that in the application costs a full copy of every
+//! frame before `transmit()` copies it again into the DMA buffers.
+//!
+//! A [`TxRewriteTable`] installed with
+//! [`Emac::tx_rewrite_mut`](super::emac::Emac::tx_rewrite_mut) does it
+//! during that copy instead. Each frame is matched against the rules by
+//! destination MAC; the first matching rule replaces the source address
+//! and/or inserts an 802.1Q tag after it, and the payload is copied
+//! unchanged. Frames that match no rule are sent as they are.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use ph_esp32_mac::{TxRewriteRule, VlanTag};
+//!
+//! let table = emac.tx_rewrite_mut();
+//! // Frames to the PLC go out on VLAN 20 with our address
+//! table.add(TxRewriteRule::for_destination(PLC_MAC)
+//!     .with_source(OUR_MAC)
+//!     .with_vlan(VlanTag::new(20)))?;
+//! // Everything else only gets our address
+//! table.add(TxRewriteRule::any().with_source(OUR_MAC))?;
+//! ```
+
+use super::error::{DmaError, Result};
+use crate::internal::constants::{MAC_ADDR_LEN, VLAN_TAG_SIZE};
+
+/// Number of rules in a [`TxRewriteTable`]
+pub const TX_REWRITE_SLOTS: usize = 8;
+
+/// Largest header a rule produces: both addresses and one VLAN tag
+pub const TX_REWRITE_HEAD_LEN: usize = 2 * MAC_ADDR_LEN + VLAN_TAG_SIZE;
+
+/// IEEE 802.1Q tag to insert after the source address
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct VlanTag {
+    /// Tag protocol identifier: `0x8100` (C-VLAN) or `0x88A8` (S-VLAN)
+    pub tpid: u16,
+    /// Tag control information: priority (3 bits), DEI (1 bit), VLAN ID
+    /// (12 bits)
+    pub tci: u16,
+}
+
+impl VlanTag {
+    /// C-VLAN tag for `vid` with priority 0
+    #[must_use]
+    pub const fn new(vid: u16) -> Self {
+        Self {
+            tpid: 0x8100,
+            tci: vid & 0x0FFF,
+        }
+    }
+
+    /// Set the priority code point (0-7)
+    #[must_use]
+    pub const fn with_priority(mut self, pcp: u8) -> Self {
+        self.tci = (self.tci & 0x1FFF) | (((pcp & 0x07) as u16) << 13);
+        self
+    }
+
+    /// Use the S-VLAN (802.1ad) TPID
+    #[must_use]
+    pub const fn svlan(mut self) -> Self {
+        self.tpid = 0x88A8;
+        self
+    }
+
+    /// VLAN identifier
+    #[must_use]
+    pub const fn vid(&self) -> u16 {
+        self.tci & 0x0FFF
+    }
+
+    /// Tag as it appears on the wire
+    #[must_use]
+    pub const fn to_bytes(&self) -> [u8; VLAN_TAG_SIZE] {
+        let [t0, t1] = self.tpid.to_be_bytes();
+        let [c0, c1] = self.tci.to_be_bytes();
+        [t0, t1, c0, c1]
+    }
+}
+
+/// One rewrite rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TxRewriteRule {
+    /// Destination MAC the rule applies to; `None` matches every frame
+    pub destination: Option<[u8; 6]>,
+    /// Replacement source MAC; `None` keeps the frame's own
+    pub source: Option<[u8; 6]>,
+    /// Tag to insert after the source address
+    pub vlan: Option<VlanTag>,
+}
+
+impl TxRewriteRule {
+    /// Rule for frames to `destination`, rewriting nothing yet
+    #[must_use]
+    pub const fn for_destination(destination: [u8; 6]) -> Self {
+        Self {
+            destination: Some(destination),
+            source: None,
+            vlan: None,
+        }
+    }
+
+    /// Rule for every frame, rewriting nothing yet
+    #[must_use]
+    pub const fn any() -> Self {
+        Self {
+            destination: None,
+            source: None,
+            vlan: None,
+        }
+    }
+
+    /// Replace the source MAC
+    #[must_use]
+    pub const fn with_source(mut self, source: [u8; 6]) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    /// Insert a VLAN tag
+    #[must_use]
+    pub const fn with_vlan(mut self, tag: VlanTag) -> Self {
+        self.vlan = Some(tag);
+        self
+    }
+
+    /// True if the rule applies to a frame sent to `destination`
+    #[must_use]
+    pub fn matches(&self, destination: &[u8]) -> bool {
+        self.destination
+            .is_none_or(|mac| mac.as_slice() == destination)
+    }
+
+    /// Bytes the rule adds to a frame
+    #[must_use]
+    pub const fn growth(&self) -> usize {
+        if self.vlan.is_some() {
+            VLAN_TAG_SIZE
+        } else {
+            0
+        }
+    }
+
+    /// Build the rewritten header of `frame` into `head`
+    ///
+    /// Returns the new header and the rest of `frame` to send after it, or
+    /// `None` if `frame` has no complete address header.
+    #[must_use]
+    pub fn rewrite<'a>(
+        &self,
+        frame: &'a [u8],
+        head: &'a mut [u8; TX_REWRITE_HEAD_LEN],
+    ) -> Option<(&'a [u8], &'a [u8])> {
+        let (addrs, body) = frame.split_at_checked(2 * MAC_ADDR_LEN)?;
+        let (dst, src) = addrs.split_at(MAC_ADDR_LEN);
+        let (head_dst, rest) = head.split_at_mut(MAC_ADDR_LEN);
+        let (head_src, head_tag) = rest.split_at_mut(MAC_ADDR_LEN);
+        head_dst.copy_from_slice(dst);
+        head_src.copy_from_slice(self.source.as_ref().map_or(src, |mac| mac.as_slice()));
+        let len = match self.vlan {
+            Some(tag) => {
+                head_tag.copy_from_slice(&tag.to_bytes());
+                TX_REWRITE_HEAD_LEN
+            }
+            None => 2 * MAC_ADDR_LEN,
+        };
+        Some((head.get(..len)?, body))
+    }
+}
+
+/// Fixed-size table of [`TxRewriteRule`]s
+///
+/// Rules are tried in slot order, so put specific destinations before a
+/// catch-all [`TxRewriteRule::any`]. An empty table costs one check per
+/// transmitted frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TxRewriteTable {
+    rules: [Option<TxRewriteRule>; TX_REWRITE_SLOTS],
+    rewritten: u32,
+}
+
+impl Default for TxRewriteTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TxRewriteTable {
+    /// Empty table
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            rules: [None; TX_REWRITE_SLOTS],
+            rewritten: 0,
+        }
+    }
+
+    /// Add `rule` in the first free slot
+    ///
+    /// # Returns
+    /// * `Ok(slot)` - The slot the rule was added to
+    /// * `Err(NoDescriptorsAvailable)` - All slots are in use
+    pub fn add(&mut self, rule: TxRewriteRule) -> Result<usize> {
+        let (slot, entry) = self
+            .rules
+            .iter_mut()
+            .enumerate()
+            .find(|(_, entry)| entry.is_none())
+            .ok_or(DmaError::NoDescriptorsAvailable)?;
+        *entry = Some(rule);
+        Ok(slot)
+    }
+
+    /// Remove the rule in `slot`, returning it
+    pub fn remove(&mut self, slot: usize) -> Option<TxRewriteRule> {
+        self.rules.get_mut(slot)?.take()
+    }
+
+    /// Remove every rule
+    pub fn clear(&mut self) {
+        self.rules = [None; TX_REWRITE_SLOTS];
+    }
+
+    /// True if the table has no rules
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.rules.iter().all(Option::is_none)
+    }
+
+    /// Rule in `slot`
+    #[must_use]
+    pub fn get(&self, slot: usize) -> Option<&TxRewriteRule> {
+        self.rules.get(slot)?.as_ref()
+    }
+
+    /// First rule that applies to `frame`
+    #[must_use]
+    pub fn lookup(&self, frame: &[u8]) -> Option<&TxRewriteRule> {
+        let destination = frame.get(..MAC_ADDR_LEN)?;
+        self.rules
+            .iter()
+            .flatten()
+            .find(|rule| rule.matches(destination))
+    }
+
+    /// Frames rewritten since the table was created
+    #[must_use]
+    pub const fn rewritten(&self) -> u32 {
+        self.rewritten
+    }
+
+    /// Count a rewritten frame
+    pub(crate) fn record_rewrite(&mut self) {
+        self.rewritten = self.rewritten.wrapping_add(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PEER: [u8; 6] = [0x02, 0, 0, 0, 0, 0x10];
+    const OURS: [u8; 6] = [0x02, 0, 0, 0, 0, 0x01];
+
+    fn frame() -> [u8; 20] {
+        let mut f = [0u8; 20];
+        f[..6].copy_from_slice(&PEER);
+        f[6..12].copy_from_slice(&[0x02, 0, 0, 0, 0, 0x99]);
+        f[12..14].copy_from_slice(&[0x08, 0x00]);
+        f[14..].copy_from_slice(&[1, 2, 3, 4, 5, 6]);
+        f
+    }
+
+    #[test]
+    fn rewrite_source_and_insert_tag() {
+        let rule = TxRewriteRule::for_destination(PEER)
+            .with_source(OURS)
+            .with_vlan(VlanTag::new(20).with_priority(5));
+        let frame = frame();
+        let mut head = [0u8; TX_REWRITE_HEAD_LEN];
+        let (h, body) = rule.rewrite(&frame, &mut head).unwrap();
+
+        assert_eq!(h[..6], PEER);
+        assert_eq!(h[6..12], OURS);
+        assert_eq!(h[12..], [0x81, 0x00, 0xA0, 20]);
+        assert_eq!(body, &frame[12..]);
+        assert_eq!(h.len() + body.len(), frame.len() + rule.growth());
+    }
+
+    #[test]
+    fn lookup_uses_first_matching_rule() {
+        let mut table = TxRewriteTable::new();
+        assert!(table.is_empty());
+        assert_eq!(table.add(TxRewriteRule::any().with_source(OURS)), Ok(0));
+        assert_eq!(
+            table.add(TxRewriteRule::for_destination(PEER).with_vlan(VlanTag::new(5))),
+            Ok(1)
+        );
+
+        // The catch-all in slot 0 shadows the specific rule
+        assert_eq!(table.lookup(&frame()).unwrap().vlan, None);
+        table.remove(0);
+        assert_eq!(table.lookup(&frame()).unwrap().vlan, Some(VlanTag::new(5)));
+        assert_eq!(table.lookup(&[0xFF; 14]), None);
+        assert_eq!(table.lookup(&[0u8; 4]), None);
+    }
+
+    #[test]
+    fn full_table_rejects_rule() {
+        let mut table = TxRewriteTable::new();
+        for slot in 0..TX_REWRITE_SLOTS {
+            assert_eq!(table.add(TxRewriteRule::any()), Ok(slot));
+        }
+        assert_eq!(
+            table.add(TxRewriteRule::any()),
+            Err(DmaError::NoDescriptorsAvailable.into())
+        );
+        table.clear();
+        assert!(table.is_empty());
+    }
+}
@@ -155,3 +155,40 @@ impl Default for ClockController {
         Self::new()
     }
 }
+
+/// Gate for the EMAC's system-level peripheral clock
+///
+/// By default the driver sets and clears the EMAC enable bit in the DPORT
+/// `WIFI_CLK_EN` register itself. A power or sleep manager that tracks which
+/// peripherals are clocked (and turns them back on after light sleep)
+/// installs its own gate with
+/// [`Emac::set_clock_gate`](crate::driver::emac::Emac::set_clock_gate), so
+/// the EMAC's clock goes through the same bookkeeping as every other
+/// peripheral's.
+///
+/// `enable` runs during `init()` before any EMAC register is touched, and
+/// `disable` at the end of `full_hardware_reset()`. Both must leave the
+/// DPORT bit in the requested state when they return.
+pub trait PeripheralClockGate {
+    /// Clock the EMAC peripheral
+    fn enable(&self);
+
+    /// Stop clocking the EMAC peripheral
+    fn disable(&self);
+}
+
+/// [`PeripheralClockGate`] that writes the DPORT register directly
+///
+/// What the driver does when no gate is installed.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DportClockGate;
+
+impl PeripheralClockGate for DportClockGate {
+    fn enable(&self) {
+        ExtRegs::enable_peripheral_clock();
+    }
+
+    fn disable(&self) {
+        ExtRegs::disable_peripheral_clock();
+    }
+}
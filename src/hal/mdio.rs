@@ -91,6 +91,16 @@ pub trait MdioBus {
 
     /// Check if the MDIO bus is busy
     fn is_busy(&self) -> bool;
+
+    /// Wait `us` microseconds between register polls
+    ///
+    /// Used by helpers that poll a PHY register against a time budget, such
+    /// as [`ieee802_3::soft_reset`](crate::phy::generic::ieee802_3::soft_reset).
+    /// The default returns at once, so on such a bus each poll counts as
+    /// having waited and the budget runs out early rather than never.
+    fn delay_us(&mut self, us: u32) {
+        let _ = us;
+    }
 }
 
 // =============================================================================
@@ -145,7 +155,7 @@ impl<D: DelayNs> MdioController<D> {
         let mut elapsed = 0u32;
         while MacRegs::mii_address() & GMACMIIADDR_GB != 0 {
             if elapsed >= self.timeout_us {
-                return Err(IoError::Timeout.into());
+                return Err(IoError::MiiBusyTimeout.into());
             }
             self.delay.delay_us(10);
             elapsed += 10;
@@ -229,6 +239,10 @@ impl<D: DelayNs> MdioBus for MdioController<D> {
     fn is_busy(&self) -> bool {
         (MacRegs::mii_address() & GMACMIIADDR_GB) != 0
     }
+
+    fn delay_us(&mut self, us: u32) {
+        self.delay.delay_us(us);
+    }
 }
 
 // =============================================================================
@@ -0,0 +1,423 @@
+//! Software (bit-banged) MDIO
+//!
+//! [`BitbangMdio`] drives the IEEE 802.3 clause 22 management frame on two
+//! GPIOs instead of the EMAC's SMI engine. Use it when a board routes
+//! MDC/MDIO to pins the SMI engine cannot reach, or when the SMI engine is
+//! unusable (e.g. the EMAC clock is not running yet). It implements
+//! [`MdioBus`], so PHY drivers work with it unchanged.
+//!
+//! # Wiring
+//!
+//! MDIO is a shared, bidirectional line: the PHY drives it during the read
+//! turnaround and data phase. The MDIO pin must therefore be configured as
+//! open-drain output with its input enabled, and the line needs a pull-up
+//! (1.5 kΩ per IEEE 802.3; most PHY boards already have one). Driving the
+//! pin high releases the line. MDC is a plain push-pull output.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use esp_hal::gpio::{Flex, Output, Level, OutputConfig, DriveMode, Pull};
+//! use ph_esp32_mac::hal::BitbangMdio;
+//!
+//! let mdc = Output::new(peripherals.GPIO23, Level::Low, OutputConfig::default());
+//! let mut mdio = Flex::new(peripherals.GPIO18);
+//! mdio.apply_output_config(
+//!     &OutputConfig::default()
+//!         .with_drive_mode(DriveMode::OpenDrain)
+//!         .with_pull(Pull::Up),
+//! );
+//! mdio.set_input_enable(true);
+//! mdio.set_output_enable(true);
+//!
+//! let mut bus = BitbangMdio::new(mdc, mdio, Delay::new());
+//! phy.init(&mut bus)?;
+//! ```
+
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::{InputPin, OutputPin};
+
+use super::mdio::{MAX_PHY_ADDR, MAX_REG_ADDR, MdioBus};
+use crate::driver::error::{ConfigError, PhyError, Result};
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Default MDC half period in nanoseconds (MDC of about 500 kHz)
+///
+/// IEEE 802.3 allows up to 2.5 MHz (200 ns high and low); the default
+/// leaves margin for slow GPIO edges and long traces.
+pub const DEFAULT_MDC_HALF_PERIOD_NS: u32 = 1_000;
+
+/// Preamble length (all ones)
+const PREAMBLE_BITS: u32 = 32;
+
+/// Start of frame (`01`)
+const START: u16 = 0b01;
+
+/// Read opcode (`10`)
+const OP_READ: u16 = 0b10;
+
+/// Write opcode (`01`)
+const OP_WRITE: u16 = 0b01;
+
+/// Write turnaround (`10`)
+const TURNAROUND_WRITE: u16 = 0b10;
+
+// =============================================================================
+// Bit-Banged MDIO Bus
+// =============================================================================
+
+/// Clause 22 MDIO master on two GPIOs
+///
+/// `MDIO` must be an open-drain pin that can also be read back; see the
+/// [module documentation](self) for the wiring. Operations run to completion
+/// in the calling context, so [`is_busy`](MdioBus::is_busy) is always
+/// `false`.
+#[derive(Debug)]
+pub struct BitbangMdio<MDC, MDIO, D> {
+    mdc: MDC,
+    mdio: MDIO,
+    delay: D,
+    half_period_ns: u32,
+}
+
+impl<MDC, MDIO, D> BitbangMdio<MDC, MDIO, D>
+where
+    MDC: OutputPin,
+    MDIO: OutputPin + InputPin,
+    D: DelayNs,
+{
+    /// Create a bus on `mdc` and `mdio` with the default MDC rate
+    pub fn new(mdc: MDC, mdio: MDIO, delay: D) -> Self {
+        Self {
+            mdc,
+            mdio,
+            delay,
+            half_period_ns: DEFAULT_MDC_HALF_PERIOD_NS,
+        }
+    }
+
+    /// Set the MDC half period in nanoseconds
+    ///
+    /// Values below 200 ns would exceed the 2.5 MHz MDC limit and are
+    /// raised to it.
+    #[must_use]
+    pub fn with_half_period_ns(mut self, half_period_ns: u32) -> Self {
+        self.half_period_ns = half_period_ns.max(200);
+        self
+    }
+
+    /// Release the pins and the delay provider
+    pub fn release(self) -> (MDC, MDIO, D) {
+        (self.mdc, self.mdio, self.delay)
+    }
+
+    fn set_mdc(&mut self, high: bool) -> Result<()> {
+        let result = if high {
+            self.mdc.set_high()
+        } else {
+            self.mdc.set_low()
+        };
+        result.map_err(|_| PhyError::Communication.into())
+    }
+
+    /// Drive MDIO; `true` releases the open-drain line
+    fn set_mdio(&mut self, high: bool) -> Result<()> {
+        let result = if high {
+            self.mdio.set_high()
+        } else {
+            self.mdio.set_low()
+        };
+        result.map_err(|_| PhyError::Communication.into())
+    }
+
+    /// Shift one bit out: the PHY samples MDIO on the rising MDC edge
+    fn send_bit(&mut self, bit: bool) -> Result<()> {
+        self.set_mdio(bit)?;
+        self.delay.delay_ns(self.half_period_ns);
+        self.set_mdc(true)?;
+        self.delay.delay_ns(self.half_period_ns);
+        self.set_mdc(false)
+    }
+
+    /// Clock one bit in: the PHY changes MDIO after the rising MDC edge
+    fn get_bit(&mut self) -> Result<bool> {
+        self.delay.delay_ns(self.half_period_ns);
+        self.set_mdc(true)?;
+        self.delay.delay_ns(self.half_period_ns);
+        self.set_mdc(false)?;
+        self.mdio
+            .is_high()
+            .map_err(|_| PhyError::Communication.into())
+    }
+
+    /// Shift out the low `bits` bits of `value`, MSB first
+    fn send_bits(&mut self, value: u16, bits: u32) -> Result<()> {
+        for i in (0..bits).rev() {
+            self.send_bit((value >> i) & 1 != 0)?;
+        }
+        Ok(())
+    }
+
+    /// Preamble, start, opcode, PHY and register address
+    fn send_header(&mut self, op: u16, phy_addr: u8, reg_addr: u8) -> Result<()> {
+        if phy_addr > MAX_PHY_ADDR {
+            return Err(ConfigError::InvalidPhyAddress.into());
+        }
+        if reg_addr > MAX_REG_ADDR {
+            return Err(ConfigError::InvalidConfig.into());
+        }
+        for _ in 0..PREAMBLE_BITS {
+            self.send_bit(true)?;
+        }
+        self.send_bits(START, 2)?;
+        self.send_bits(op, 2)?;
+        self.send_bits(u16::from(phy_addr), 5)?;
+        self.send_bits(u16::from(reg_addr), 5)
+    }
+}
+
+impl<MDC, MDIO, D> MdioBus for BitbangMdio<MDC, MDIO, D>
+where
+    MDC: OutputPin,
+    MDIO: OutputPin + InputPin,
+    D: DelayNs,
+{
+    fn read(&mut self, phy_addr: u8, reg_addr: u8) -> Result<u16> {
+        self.send_header(OP_READ, phy_addr, reg_addr)?;
+
+        // Release the line; a present PHY pulls the second turnaround bit low
+        self.set_mdio(true)?;
+        if self.get_bit()? {
+            // Nobody answered: clock out whatever frame might follow
+            for _ in 0..16 {
+                self.get_bit()?;
+            }
+            return Err(PhyError::Communication.into());
+        }
+
+        let mut value = 0u16;
+        for _ in 0..16 {
+            value = (value << 1) | u16::from(self.get_bit()?);
+        }
+        // Idle cycle so the PHY releases the line
+        self.get_bit()?;
+        Ok(value)
+    }
+
+    fn write(&mut self, phy_addr: u8, reg_addr: u8, value: u16) -> Result<()> {
+        self.send_header(OP_WRITE, phy_addr, reg_addr)?;
+        self.send_bits(TURNAROUND_WRITE, 2)?;
+        self.send_bits(value, 16)?;
+        self.set_mdio(true)?;
+        self.get_bit()?;
+        Ok(())
+    }
+
+    fn delay_us(&mut self, us: u32) {
+        self.delay.delay_us(us);
+    }
+
+    fn is_busy(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::std_instead_of_alloc)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use core::cell::RefCell;
+    use core::convert::Infallible;
+    use embedded_hal::digital::ErrorType;
+    use std::rc::Rc;
+
+    /// Clause 22 PHY model, clocked by the MDC pin
+    #[derive(Default)]
+    struct SimPhy {
+        addr: u8,
+        regs: [u16; 32],
+        /// Level the master drives (open drain: `true` = released)
+        master: bool,
+        /// Level the PHY drives, `None` when released
+        drive: Option<bool>,
+        ones: u32,
+        header: Option<(u32, u16)>,
+        read: Option<(u32, u16)>,
+        write: Option<(u32, u8, u32)>,
+        rising_edges: u32,
+    }
+
+    impl SimPhy {
+        fn line(&self) -> bool {
+            self.master && self.drive.unwrap_or(true)
+        }
+
+        /// Rising MDC edge: sample the line, then update the PHY's output
+        fn clock(&mut self) {
+            self.rising_edges += 1;
+            let bit = self.line();
+
+            if let Some((n, value)) = self.read {
+                self.drive = match n {
+                    0 => Some(false),
+                    1..=16 => Some((value >> (16 - n)) & 1 != 0),
+                    _ => None,
+                };
+                self.read = (n < 17).then_some((n + 1, value));
+                return;
+            }
+            if let Some((n, reg, data)) = self.write {
+                let data = (data << 1) | u32::from(bit);
+                if n == 17 {
+                    self.regs[reg as usize] = data as u16;
+                    self.write = None;
+                } else {
+                    self.write = Some((n + 1, reg, data));
+                }
+                return;
+            }
+            if let Some((n, bits)) = self.header {
+                let bits = (bits << 1) | u16::from(bit);
+                if n < 13 {
+                    self.header = Some((n + 1, bits));
+                    return;
+                }
+                self.header = None;
+                // ST(2) OP(2) PHYAD(5) REGAD(5)
+                let op = (bits >> 10) & 0b11;
+                let phy = ((bits >> 5) & 0x1F) as u8;
+                let reg = (bits & 0x1F) as u8;
+                if phy != self.addr {
+                    return;
+                }
+                match op {
+                    OP_READ => {
+                        self.read = Some((0, self.regs[reg as usize]));
+                        self.drive = None;
+                    }
+                    OP_WRITE => self.write = Some((0, reg, 0)),
+                    _ => {}
+                }
+                return;
+            }
+            if bit {
+                self.ones += 1;
+            } else {
+                // Start bit `0` after a full preamble begins a frame
+                if self.ones >= PREAMBLE_BITS {
+                    self.header = Some((1, 0));
+                }
+                self.ones = 0;
+            }
+        }
+    }
+
+    struct Mdc(Rc<RefCell<SimPhy>>);
+    struct Mdio(Rc<RefCell<SimPhy>>);
+
+    impl ErrorType for Mdc {
+        type Error = Infallible;
+    }
+
+    impl OutputPin for Mdc {
+        fn set_low(&mut self) -> core::result::Result<(), Infallible> {
+            Ok(())
+        }
+
+        fn set_high(&mut self) -> core::result::Result<(), Infallible> {
+            self.0.borrow_mut().clock();
+            Ok(())
+        }
+    }
+
+    impl ErrorType for Mdio {
+        type Error = Infallible;
+    }
+
+    impl OutputPin for Mdio {
+        fn set_low(&mut self) -> core::result::Result<(), Infallible> {
+            self.0.borrow_mut().master = false;
+            Ok(())
+        }
+
+        fn set_high(&mut self) -> core::result::Result<(), Infallible> {
+            self.0.borrow_mut().master = true;
+            Ok(())
+        }
+    }
+
+    impl InputPin for Mdio {
+        fn is_high(&mut self) -> core::result::Result<bool, Infallible> {
+            Ok(self.0.borrow().line())
+        }
+
+        fn is_low(&mut self) -> core::result::Result<bool, Infallible> {
+            Ok(!self.0.borrow().line())
+        }
+    }
+
+    struct NoDelay;
+
+    impl DelayNs for NoDelay {
+        fn delay_ns(&mut self, _ns: u32) {}
+    }
+
+    fn bus(addr: u8) -> (BitbangMdio<Mdc, Mdio, NoDelay>, Rc<RefCell<SimPhy>>) {
+        let phy = Rc::new(RefCell::new(SimPhy {
+            addr,
+            master: true,
+            ..SimPhy::default()
+        }));
+        let bus = BitbangMdio::new(Mdc(phy.clone()), Mdio(phy.clone()), NoDelay);
+        (bus, phy)
+    }
+
+    #[test]
+    fn read_and_write_round_trip() {
+        let (mut bus, phy) = bus(1);
+        phy.borrow_mut().regs[2] = 0x0007;
+        phy.borrow_mut().regs[3] = 0xC0F1;
+
+        assert_eq!(bus.read(1, 2), Ok(0x0007));
+        assert_eq!(bus.read(1, 3), Ok(0xC0F1));
+        assert_eq!(bus.write(1, 4, 0x01E1), Ok(()));
+        assert_eq!(phy.borrow().regs[4], 0x01E1);
+        assert_eq!(bus.read(1, 4), Ok(0x01E1));
+        assert!(!bus.is_busy());
+        assert_eq!(phy.borrow().drive, None);
+    }
+
+    #[test]
+    fn frames_are_64_clocks_plus_idle() {
+        let (mut bus, phy) = bus(0);
+        // Preamble 32, header 14, turnaround 1 sampled, data 16, idle 1
+        bus.read(0, 1).unwrap();
+        assert_eq!(phy.borrow().rising_edges, 64);
+        // Preamble 32, header 14, turnaround 2, data 16, idle 1
+        bus.write(0, 0, 0x8000).unwrap();
+        assert_eq!(phy.borrow().rising_edges, 64 + 65);
+    }
+
+    #[test]
+    fn absent_phy_is_an_error() {
+        let (mut bus, phy) = bus(5);
+        phy.borrow_mut().regs[1] = 0x7809;
+
+        assert_eq!(bus.read(4, 1), Err(PhyError::Communication.into()));
+        // The bus still works afterwards
+        assert_eq!(bus.read(5, 1), Ok(0x7809));
+    }
+
+    #[test]
+    fn rejects_out_of_range_addresses() {
+        let (mut bus, phy) = bus(0);
+        assert_eq!(bus.read(32, 0), Err(ConfigError::InvalidPhyAddress.into()));
+        assert_eq!(bus.write(0, 32, 0), Err(ConfigError::InvalidConfig.into()));
+        assert_eq!(phy.borrow().rising_edges, 0);
+    }
+}
@@ -5,9 +5,13 @@
 //!
 //! # Overview
 //!
-//! - [`clock`]: Clock configuration and control
+//! - [`clock`]: Clock configuration and control, and the peripheral clock
+//!   gate a power manager can take over
 //! - [`mdio`]: MDIO/SMI bus for PHY communication
+//! - [`mdio_bitbang`]: Software MDIO on two GPIOs, for pins the SMI engine
+//!   cannot reach
 //! - [`reset`]: Reset controller for the EMAC peripheral
+//! - [`time`]: Monotonic [`Clock`] for time-based timeouts and timestamps
 //!
 //! # Usage
 //!
@@ -29,9 +33,13 @@
 
 pub mod clock;
 pub mod mdio;
+pub mod mdio_bitbang;
 pub mod reset;
+pub mod time;
 
 // Re-export commonly used types
-pub use clock::{ClockController, ClockState};
+pub use clock::{ClockController, ClockState, DportClockGate, PeripheralClockGate};
 pub use mdio::{MdcClockDivider, MdioBus, MdioController, PhyStatus};
+pub use mdio_bitbang::BitbangMdio;
 pub use reset::{ResetController, ResetManager, ResetState};
+pub use time::Clock;
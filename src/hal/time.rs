@@ -0,0 +1,224 @@
+//! Monotonic time source.
+//!
+//! The driver has no timer of its own. Hardware polls such as waiting for
+//! the TX FIFO flush have microsecond budgets (see `EmacConfig`), measured
+//! with a clock if one is installed, else with a delay provider if the
+//! caller passes one. With neither, a budget becomes an iteration count
+//! whose real duration depends on the CPU frequency and optimization level,
+//! and events can only be ordered by sequence number.
+//!
+//! Implement [`Clock`] on top of any free-running microsecond timer (the
+//! esp-hal `Instant`, `esp_timer_get_time`, a SysTick counter) and install it
+//! with [`Emac::set_clock`](crate::driver::emac::Emac::set_clock). The EMAC
+//! then bounds its register polls by time and stamps the event log, the
+//! liveness report and the frame trace in milliseconds unless a separate
+//! event clock is set.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use ph_esp32_mac::hal::Clock;
+//!
+//! struct SystemClock;
+//!
+//! impl Clock for SystemClock {
+//!     fn now_us(&self) -> u64 {
+//!         esp_hal::time::Instant::now().duration_since_epoch().as_micros()
+//!     }
+//! }
+//!
+//! static CLOCK: SystemClock = SystemClock;
+//! emac.set_clock(Some(&CLOCK));
+//! ```
+
+use embedded_hal::delay::DelayNs;
+
+use crate::internal::constants::{POLL_DELAY_US, POLL_SPINS_PER_US};
+
+// =============================================================================
+// Clock
+// =============================================================================
+
+/// Monotonic microsecond time source
+///
+/// Values must never go backwards except by wrapping around `u64::MAX`.
+pub trait Clock {
+    /// Microseconds since an arbitrary fixed point
+    fn now_us(&self) -> u64;
+
+    /// Microseconds elapsed since `since` (a previous `now_us()`)
+    fn elapsed_us(&self, since: u64) -> u64 {
+        self.now_us().wrapping_sub(since)
+    }
+
+    /// Milliseconds since the same fixed point, wrapping at `u32::MAX`
+    fn now_ms(&self) -> u32 {
+        (self.now_us() / 1_000) as u32
+    }
+}
+
+impl Clock for fn() -> u64 {
+    fn now_us(&self) -> u64 {
+        self()
+    }
+}
+
+// =============================================================================
+// Poll Timeout
+// =============================================================================
+
+/// How a [`PollTimeout`] measures its budget
+enum Meter<'a> {
+    /// Elapsed time read from a clock
+    Clock { clock: &'a dyn Clock, start: u64 },
+    /// Time spent in fixed delays between polls
+    Delay {
+        delay: &'a mut dyn DelayNs,
+        waited_us: u32,
+    },
+    /// Poll count, assuming `POLL_SPINS_PER_US` polls per microsecond
+    Spin { spins_left: u32 },
+}
+
+/// Time budget for polling a hardware condition
+///
+/// Measured with the clock if there is one, else by delaying
+/// `POLL_DELAY_US` between polls if a delay provider is given. Only with
+/// neither does it fall back to a poll count, scaled from the budget by
+/// `POLL_SPINS_PER_US`.
+pub(crate) struct PollTimeout<'a> {
+    meter: Meter<'a>,
+    budget_us: u32,
+}
+
+impl<'a> PollTimeout<'a> {
+    /// Start a budget of `budget_us` microseconds
+    pub(crate) fn start(
+        clock: Option<&'a dyn Clock>,
+        delay: Option<&'a mut dyn DelayNs>,
+        budget_us: u32,
+    ) -> Self {
+        let meter = match (clock, delay) {
+            (Some(clock), _) => Meter::Clock {
+                clock,
+                start: clock.now_us(),
+            },
+            (None, Some(delay)) => Meter::Delay {
+                delay,
+                waited_us: 0,
+            },
+            (None, None) => Meter::Spin {
+                spins_left: budget_us.saturating_mul(POLL_SPINS_PER_US),
+            },
+        };
+        Self { meter, budget_us }
+    }
+
+    /// Call once per poll; `true` once the budget is spent
+    pub(crate) fn expired(&mut self) -> bool {
+        match &mut self.meter {
+            Meter::Clock { clock, start } => clock.elapsed_us(*start) > u64::from(self.budget_us),
+            Meter::Delay { delay, waited_us } => {
+                if *waited_us >= self.budget_us {
+                    return true;
+                }
+                delay.delay_us(POLL_DELAY_US);
+                *waited_us = waited_us.saturating_add(POLL_DELAY_US);
+                false
+            }
+            Meter::Spin { spins_left } => {
+                if *spins_left == 0 {
+                    return true;
+                }
+                *spins_left -= 1;
+                core::hint::spin_loop();
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cell::Cell;
+
+    /// Advances by `step` microseconds on every read
+    struct SteppingClock {
+        now: Cell<u64>,
+        step: u64,
+    }
+
+    impl Clock for SteppingClock {
+        fn now_us(&self) -> u64 {
+            let now = self.now.get();
+            self.now.set(now.wrapping_add(self.step));
+            now
+        }
+    }
+
+    #[test]
+    fn clock_budget_is_time_based() {
+        let clock = SteppingClock {
+            now: Cell::new(u64::MAX - 50),
+            step: 100,
+        };
+        let mut timeout = PollTimeout::start(Some(&clock), None, 1_000);
+        let polls = (0..100).take_while(|_| !timeout.expired()).count();
+        // Reads at +100, +200, ... +1000 are within budget, across the wrap
+        assert_eq!(polls, 10);
+    }
+
+    #[test]
+    fn delay_budget_counts_waited_time() {
+        /// Records the total requested delay
+        struct CountingDelay(u64);
+        impl DelayNs for CountingDelay {
+            fn delay_ns(&mut self, ns: u32) {
+                self.0 += u64::from(ns);
+            }
+        }
+
+        let mut delay = CountingDelay(0);
+        let mut timeout = PollTimeout::start(None, Some(&mut delay), 50);
+        let polls = (0..100).take_while(|_| !timeout.expired()).count();
+        assert_eq!(polls as u32, 50 / POLL_DELAY_US);
+        assert_eq!(delay.0, 50_000);
+    }
+
+    #[test]
+    fn clock_takes_precedence_over_delay() {
+        struct PanicDelay;
+        impl DelayNs for PanicDelay {
+            fn delay_ns(&mut self, _: u32) {
+                panic!("delay used despite a clock");
+            }
+        }
+
+        let clock = SteppingClock {
+            now: Cell::new(0),
+            step: 10,
+        };
+        let mut delay = PanicDelay;
+        let mut timeout = PollTimeout::start(Some(&clock), Some(&mut delay), 30);
+        assert_eq!((0..10).take_while(|_| !timeout.expired()).count(), 3);
+    }
+
+    #[test]
+    fn spin_fallback_scales_with_budget() {
+        let mut timeout = PollTimeout::start(None, None, 3);
+        let polls = (0..100).take_while(|_| !timeout.expired()).count();
+        assert_eq!(polls as u32, 3 * POLL_SPINS_PER_US);
+    }
+
+    #[test]
+    fn function_pointer_clock() {
+        fn fixed() -> u64 {
+            42_500
+        }
+        let clock: fn() -> u64 = fixed;
+        assert_eq!(clock.now_us(), 42_500);
+        assert_eq!(clock.now_ms(), 42);
+        assert_eq!(clock.elapsed_us(42_000), 500);
+    }
+}
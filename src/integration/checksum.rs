@@ -0,0 +1,33 @@
+//! Checksum work left to the network stack, shared by the smoltcp and
+//! embassy-net adapters
+//!
+//! Both stacks take one `Checksum` setting per protocol for every frame.
+//! With RX checksum offload the MAC drops frames with a bad IPv4 header,
+//! TCP, UDP or ICMP checksum before they reach the RX ring, so the adapters
+//! report `Checksum::Tx`: the stack computes checksums for outgoing packets
+//! and verifies none on receive. That also covers frames the checksum
+//! engine did not check, such as fragmented IP datagrams and packets it
+//! bypassed (see `RxChecksumStatus::verified`); those then rely on the
+//! Ethernet FCS alone. Turn RX checksum offload off where that is not
+//! enough, and the stack verifies every received checksum itself.
+
+/// Build a stack's `ChecksumCapabilities` for RX offload `$rx_offload`
+///
+/// `$caps` and `$checksum` are the stack's own capability struct and
+/// per-protocol enum; smoltcp and embassy-net-driver define identical but
+/// separate types.
+macro_rules! checksum_capabilities {
+    ($caps:ty, $checksum:ident, $rx_offload:expr) => {{
+        let side = if $rx_offload {
+            $checksum::Tx
+        } else {
+            $checksum::Both
+        };
+        let mut caps = <$caps>::default();
+        caps.ipv4 = side;
+        caps.udp = side;
+        caps.tcp = side;
+        caps.icmpv4 = side;
+        caps
+    }};
+}
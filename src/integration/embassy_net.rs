@@ -61,6 +61,31 @@
 //! network stack informed of link changes. This method polls the PHY and updates
 //! the cached [`LinkState`], waking the stack on transitions.
 //!
+//! # Gratuitous ARP
+//!
+//! The state carries a [`GarpAnnouncer`]. Report the address whenever the
+//! stack configuration changes with [`EmbassyEmacState::announce_ipv4`];
+//! the driver then announces it, and again each time the link comes back
+//! up, so neighbours drop ARP entries pointing at a replaced device. See
+//! [`garp`](crate::driver::garp).
+//!
+//! Announcements go out while embassy-net polls the driver, and the
+//! repeats of one trigger are spaced in time, which needs an EMAC clock
+//! ([`Emac::set_clock`] or [`Emac::set_event_clock`]); without one only
+//! the first announcement is sent. On a quiet network nothing polls the
+//! driver when a repeat falls due, so call
+//! [`EmbassyEmacState::wake_for_garp`] from a periodic task, such as the
+//! one running [`EmbassyEmacState::update_link_from_phy`]:
+//!
+//! ```ignore
+//! loop {
+//!     EMAC_STATE.update_link_from_phy(&mut phy, &mut mdio).ok();
+//!     let now_ms = Instant::now().as_millis() as u32;
+//!     let wait_ms = EMAC_STATE.wake_for_garp(now_ms).unwrap_or(1000).clamp(1, 1000);
+//!     Timer::after_millis(wait_ms.into()).await;
+//! }
+//! ```
+//!
 //! # esp-hal + Embassy Runtime
 //!
 //! With `esp-hal` 1.0.0, the recommended Embassy runtime integration is via
@@ -70,10 +95,12 @@
 use core::{marker::PhantomData, task::Context};
 
 use embassy_net_driver::{
-    Capabilities, ChecksumCapabilities, Driver, HardwareAddress, LinkState, RxToken, TxToken,
+    Capabilities, Checksum, ChecksumCapabilities, Driver, HardwareAddress, LinkState, RxToken,
+    TxToken,
 };
 
 use crate::driver::error::Result;
+use crate::driver::garp::GarpAnnouncer;
 use crate::hal::mdio::MdioBus;
 use crate::internal::constants::{MAX_FRAME_SIZE, MTU};
 use crate::internal::register::dma::DmaRegs;
@@ -93,6 +120,7 @@ pub struct EmbassyEmacState {
     tx_waker: AtomicWaker,
     link_waker: AtomicWaker,
     link_state: CriticalSectionCell<LinkState>,
+    garp: CriticalSectionCell<GarpAnnouncer>,
 }
 
 impl EmbassyEmacState {
@@ -107,6 +135,7 @@ impl EmbassyEmacState {
             tx_waker: AtomicWaker::new(),
             link_waker: AtomicWaker::new(),
             link_state: CriticalSectionCell::new(initial_link),
+            garp: CriticalSectionCell::new(GarpAnnouncer::new()),
         }
     }
 
@@ -120,9 +149,58 @@ impl EmbassyEmacState {
         self.link_state.with(|current| {
             *current = state;
         });
+        self.garp
+            .with(|garp| garp.set_link_up(state == LinkState::Up));
         self.link_waker.wake();
     }
 
+    /// Report the stack's IPv4 address for gratuitous ARP announcements
+    ///
+    /// Call after `wait_config_up` and on every configuration change; a new
+    /// address is announced on the next driver poll. `None` after losing
+    /// the lease.
+    pub fn announce_ipv4(&self, ip: Option<[u8; 4]>) {
+        self.garp.with(|garp| garp.set_ipv4(ip));
+        self.rx_waker.wake();
+    }
+
+    /// Replace the announcer, e.g. to change repeats or spacing
+    ///
+    /// The address and link state already reported are lost; set this up
+    /// before handing the driver to the stack.
+    pub fn set_garp(&self, announcer: GarpAnnouncer) {
+        self.garp.with(|garp| *garp = announcer);
+    }
+
+    /// Wake the driver if a gratuitous ARP is due at `now_ms`
+    ///
+    /// Returns the milliseconds until the next announcement (0 if one is
+    /// due now), or `None` if none is queued, for sizing the caller's sleep.
+    /// `now_ms` must come from the same clock as the EMAC's
+    /// ([`Emac::set_clock`] or [`Emac::set_event_clock`]).
+    pub fn wake_for_garp(&self, now_ms: u32) -> Option<u32> {
+        let due_in = self.garp.with_ref(|garp| garp.due_in(now_ms));
+        if due_in == Some(0) {
+            self.rx_waker.wake();
+        }
+        due_in
+    }
+
+    /// Send a due announcement, ignoring transmit errors (retried on the
+    /// next poll)
+    ///
+    /// Runs from [`Driver::receive`]; see
+    /// [`wake_for_garp`](Self::wake_for_garp) for polls on a quiet network.
+    fn poll_garp<const RX: usize, const TX: usize, const BUF: usize>(
+        &self,
+        emac: &mut Emac<RX, TX, BUF>,
+    ) {
+        let now = emac.now();
+        let _ = self
+            .garp
+            .with(|garp| emac.timed_hold(|emac| garp.poll(emac, now)));
+    }
+
     /// Poll the PHY and update the cached link state.
     ///
     /// This is intended for periodic link-state polling in async tasks.
@@ -192,6 +270,7 @@ impl EmbassyEmacState {
 pub struct EmbassyEmac<'a, const RX: usize, const TX: usize, const BUF: usize> {
     emac: *mut Emac<RX, TX, BUF>,
     state: &'a EmbassyEmacState,
+    initial_rx_drain: Option<usize>,
     _marker: PhantomData<&'a mut Emac<RX, TX, BUF>>,
 }
 
@@ -206,10 +285,22 @@ impl<'a, const RX: usize, const TX: usize, const BUF: usize> EmbassyEmac<'a, RX,
         Self {
             emac: emac as *mut Emac<RX, TX, BUF>,
             state,
+            initial_rx_drain: None,
             _marker: PhantomData,
         }
     }
 
+    /// Discard up to `max_frames` stale frames when the stack first polls
+    ///
+    /// Frames buffered between `start()` and the runner's first poll are
+    /// dropped with [`Emac::drain_rx_discarding`], so DHCP starts from an
+    /// empty ring.
+    #[must_use]
+    pub fn with_initial_rx_drain(mut self, max_frames: usize) -> Self {
+        self.initial_rx_drain = Some(max_frames);
+        self
+    }
+
     /// Get the shared Embassy state.
     pub fn state(&self) -> &EmbassyEmacState {
         self.state
@@ -292,6 +383,14 @@ impl<const RX: usize, const TX: usize, const BUF: usize> Driver for EmbassyEmac<
         // SAFETY: The raw pointer is valid for the driver lifetime.
         let emac = unsafe { &mut *self.emac };
 
+        // Retried on the next poll if the ring was busy
+        if let Some(max_frames) = self.initial_rx_drain
+            && emac.drain_rx_discarding(max_frames).is_ok()
+        {
+            self.initial_rx_drain = None;
+        }
+        self.state.poll_garp(emac);
+
         if !emac.rx_available() {
             self.state.rx_waker.register(cx.waker());
             if !emac.rx_available() {
@@ -337,7 +436,10 @@ impl<const RX: usize, const TX: usize, const BUF: usize> Driver for EmbassyEmac<
         let mut caps = Capabilities::default();
         caps.max_transmission_unit = MTU;
         caps.max_burst_size = Some(1);
-        caps.checksum = ChecksumCapabilities::default();
+        // SAFETY: The raw pointer is valid for the driver lifetime.
+        let emac = unsafe { &*self.emac };
+        caps.checksum =
+            checksum_capabilities!(ChecksumCapabilities, Checksum, emac.rx_checksum_offload());
         caps
     }
 
@@ -7,7 +7,8 @@
 //! - [`EmacExt`]: Extension trait for interrupt handler registration
 //! - [`emac_isr!`]: Macro for defining EMAC interrupt handlers with esp-hal semantics
 //! - [`emac_async_isr!`]: Macro for defining EMAC async ISR handlers
-//! - [`EmacBuilder`]: Builder for minimal-boilerplate esp-hal bring-up
+//! - [`EmacBuilder`]: Builder for minimal-boilerplate esp-hal bring-up, with an
+//!   optional step-by-step [`BringUpReport`]
 //! - [`EmacPhyBundle`]: Convenience wrapper for PHY + MDIO bring-up
 //! - [`Wt32Eth01`]: Board helper for the canonical WT32-ETH01 bring-up (ESP32 only)
 //! - Re-exports for common esp-hal types
@@ -60,6 +61,15 @@
 //! let _status = emac_phy.wait_link_up(&mut delay, 10_000, 200).unwrap();
 //! ```
 //!
+//! With a PHY reset GPIO, let the bundle own it and sequence the reset:
+//!
+//! ```ignore
+//! let mut emac_phy = EmacPhyBundle::new(emac, phy, mdio).with_reset_pin(phy_reset);
+//! if let Err(e) = emac_phy.bring_up(&mut delay) {
+//!     esp_println::println!("PHY bring-up failed at {e}");
+//! }
+//! ```
+//!
 //! # WT32-ETH01 Canonical Happy Path (ESP32)
 //!
 //! This is the recommended esp-hal bring-up path for this crate.
@@ -97,6 +107,36 @@
 //! let len = emac.receive_async(&ASYNC_STATE, &mut buffer).await?;
 //! ```
 //!
+//! # Peripheral Clock and Sleep
+//!
+//! esp-hal 1.0 manages peripheral clocks through a crate-private
+//! `PeripheralGuard`, and its ESP32 peripheral list has no EMAC entry, so
+//! there is no esp-hal type to route the EMAC clock through yet. The driver
+//! writes the DPORT enable bit itself unless a
+//! [`PeripheralClockGate`](crate::hal::PeripheralClockGate) is installed. A
+//! sleep or power manager that accounts for clocked peripherals implements
+//! the trait on its own bookkeeping and installs it before `init()`:
+//!
+//! ```ignore
+//! use ph_esp32_mac::hal::{DportClockGate, PeripheralClockGate};
+//!
+//! struct PowerManagedGate;
+//!
+//! impl PeripheralClockGate for PowerManagedGate {
+//!     fn enable(&self) {
+//!         POWER.acquire(PowerDomain::Emac);
+//!         DportClockGate.enable();
+//!     }
+//!     fn disable(&self) {
+//!         DportClockGate.disable();
+//!         POWER.release(PowerDomain::Emac);
+//!     }
+//! }
+//!
+//! static GATE: PowerManagedGate = PowerManagedGate;
+//! emac.set_clock_gate(Some(&GATE));
+//! ```
+//!
 //! # Feature Detection
 //!
 //! This module is only available when the `esp-hal` feature is enabled:
@@ -115,7 +155,11 @@ pub use esp_hal::interrupt::{InterruptHandler, Priority};
 pub use esp_hal::peripherals::Interrupt;
 
 use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::OutputPin;
 
+use crate::driver::bring_up::{
+    BringUpReport, NoResetPin, PhyBringUpError, PhyResetTiming, reset_and_probe_phy,
+};
 use crate::driver::error::{ConfigError, IoError};
 use crate::hal::mdio::MdioBus;
 #[cfg(feature = "esp32")]
@@ -241,6 +285,17 @@ impl<'a, const RX: usize, const TX: usize, const BUF: usize> EmacBuilder<'a, RX,
         self
     }
 
+    /// Set the RMII reference clock timing.
+    ///
+    /// # Arguments
+    ///
+    /// * `timing` - Clock delay and pad drive strength
+    #[must_use]
+    pub const fn with_rmii_timing(mut self, timing: crate::RmiiTiming) -> Self {
+        self.config = self.config.with_rmii_timing(timing);
+        self
+    }
+
     /// Set the RMII clock to an external 50 MHz input on the given GPIO.
     ///
     /// # Arguments
@@ -302,16 +357,76 @@ impl<'a, const RX: usize, const TX: usize, const BUF: usize> EmacBuilder<'a, RX,
         self.emac.start()?;
         Ok(self.emac)
     }
+
+    /// Initialize the EMAC and PHY step by step, recording each result.
+    ///
+    /// Runs `Emac::init`, then reads the PHY ID, initializes the PHY and
+    /// waits up to `link_timeout_ms` for auto-negotiation. Stops at the
+    /// first failure; [`BringUpReport::hint`] names the likely cause (no
+    /// REF_CLK, wrong PHY address, cable unplugged). On success the
+    /// negotiated speed and duplex are applied to the EMAC, which is left
+    /// initialized but not started.
+    ///
+    /// # Arguments
+    ///
+    /// * `delay` - esp-hal delay provider
+    /// * `phy` - PHY driver for the board
+    /// * `mdio` - MDIO bus the PHY is attached to
+    /// * `link_timeout_ms` - How long to wait for link-up
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let (emac, report) = EmacBuilder::wt32_eth01(emac).bring_up(
+    ///     &mut delay,
+    ///     &mut Wt32Eth01::lan8720a(),
+    ///     &mut MdioController::new(Delay::new()),
+    ///     5_000,
+    /// );
+    /// if let Some(hint) = report.hint() {
+    ///     esp_println::println!("bring-up failed:\n{report}hint: {hint}");
+    /// }
+    /// ```
+    pub fn bring_up<P: PhyDriver, M: MdioBus>(
+        self,
+        delay: &mut Delay,
+        phy: &mut P,
+        mdio: &mut M,
+        link_timeout_ms: u32,
+    ) -> (&'a mut crate::Emac<RX, TX, BUF>, BringUpReport) {
+        let mut report = BringUpReport::new();
+        report.record_init(&self.emac.init(self.config, &mut *delay));
+
+        if report.first_failure().is_none()
+            && let Some(link) = report.probe_phy(phy, mdio, delay, link_timeout_ms)
+        {
+            self.emac.update_link(link.speed, link.duplex);
+        }
+        (self.emac, report)
+    }
 }
 
 /// Convenience wrapper for EMAC + PHY + MDIO bring-up with esp-hal.
 ///
 /// This helper reduces boilerplate by bundling PHY initialization and
-/// link-up polling while keeping EMAC ownership explicit.
-pub struct EmacPhyBundle<'a, const RX: usize, const TX: usize, const BUF: usize, P, M> {
+/// link-up polling while keeping EMAC ownership explicit. A bundle can also
+/// own the PHY's nRST GPIO ([`with_reset_pin`](Self::with_reset_pin)), in
+/// which case [`bring_up`](Self::bring_up) sequences the reset against
+/// REF_CLK and MDIO access.
+pub struct EmacPhyBundle<
+    'a,
+    const RX: usize,
+    const TX: usize,
+    const BUF: usize,
+    P,
+    M,
+    R = NoResetPin,
+> {
     emac: &'a mut crate::Emac<RX, TX, BUF>,
     phy: P,
     mdio: M,
+    reset: Option<R>,
+    reset_timing: PhyResetTiming,
 }
 
 impl<'a, const RX: usize, const TX: usize, const BUF: usize, P, M>
@@ -328,7 +443,76 @@ where
     /// * `phy` - PHY driver instance
     /// * `mdio` - MDIO bus implementation
     pub fn new(emac: &'a mut crate::Emac<RX, TX, BUF>, phy: P, mdio: M) -> Self {
-        Self { emac, phy, mdio }
+        Self {
+            emac,
+            phy,
+            mdio,
+            reset: None,
+            reset_timing: PhyResetTiming::LAN8720A,
+        }
+    }
+
+    /// Hand the PHY's active-low reset GPIO to the bundle.
+    ///
+    /// The pin is driven high (PHY out of reset) immediately and pulsed by
+    /// [`bring_up`](EmacPhyBundle::bring_up).
+    pub fn with_reset_pin<R: OutputPin>(
+        self,
+        mut pin: R,
+    ) -> EmacPhyBundle<'a, RX, TX, BUF, P, M, R> {
+        let _ = pin.set_high();
+        EmacPhyBundle {
+            emac: self.emac,
+            phy: self.phy,
+            mdio: self.mdio,
+            reset: Some(pin),
+            reset_timing: self.reset_timing,
+        }
+    }
+}
+
+impl<'a, const RX: usize, const TX: usize, const BUF: usize, P, M, R>
+    EmacPhyBundle<'a, RX, TX, BUF, P, M, R>
+where
+    P: PhyDriver,
+    M: MdioBus,
+    R: OutputPin,
+{
+    /// Set the nRST pulse and recovery times (LAN8720A timing by default).
+    #[must_use]
+    pub fn with_reset_timing(mut self, timing: PhyResetTiming) -> Self {
+        self.reset_timing = timing;
+        self
+    }
+
+    /// Reset the PHY in datasheet order, then initialize it.
+    ///
+    /// Checks that the EMAC is initialized (REF_CLK stable), pulses nRST if
+    /// the bundle owns a reset pin, waits for the PHY to recover, probes its
+    /// identifier over MDIO and finally runs [`init_phy`](Self::init_phy).
+    ///
+    /// # Returns
+    ///
+    /// The PHY identifier.
+    ///
+    /// # Errors
+    ///
+    /// The failing [`PhyBringUpStage`](crate::PhyBringUpStage) with its
+    /// error; a failing `init_phy` is reported as the MDIO probe stage.
+    pub fn bring_up<D: DelayNs>(&mut self, delay: &mut D) -> Result<u32, PhyBringUpError> {
+        let id = reset_and_probe_phy(
+            &*self.emac,
+            self.reset.as_mut(),
+            self.reset_timing,
+            &self.phy,
+            &mut self.mdio,
+            delay,
+        )?;
+        self.init_phy().map_err(|error| PhyBringUpError {
+            stage: crate::PhyBringUpStage::MdioProbe,
+            error,
+        })?;
+        Ok(id)
     }
 
     /// Borrow the EMAC instance.
@@ -459,10 +643,14 @@ where
         (self.emac, self.phy, self.mdio)
     }
 
+    /// Take the reset pin back, if the bundle owns one.
+    pub fn take_reset_pin(&mut self) -> Option<R> {
+        self.reset.take()
+    }
+
     fn apply_link(&mut self, status: Option<LinkStatus>) {
         if let Some(status) = status {
-            self.emac.set_speed(status.speed);
-            self.emac.set_duplex(status.duplex);
+            self.emac.apply_link_status(&status);
         }
     }
 }
@@ -35,6 +35,10 @@
 //!
 //! - [`crate::esp_hal`] - re-exported esp-hal facade at the crate root
 
+#[cfg(any(feature = "smoltcp", feature = "embassy-net"))]
+#[macro_use]
+mod checksum;
+
 #[cfg(feature = "esp-hal")]
 #[cfg_attr(docsrs, doc(cfg(feature = "esp-hal")))]
 pub mod esp_hal;
@@ -54,7 +58,9 @@ pub use esp_hal::{EMAC_INTERRUPT, EmacBuilder, EmacExt, EmacPhyBundle};
 
 #[cfg(feature = "smoltcp")]
 #[cfg_attr(docsrs, doc(cfg(feature = "smoltcp")))]
-pub use smoltcp::{EmacRxToken, EmacTxToken, ethernet_address};
+pub use smoltcp::{
+    EmacRxToken, EmacTxToken, PrefilterDevice, PrefilterRxToken, RxFilterAction, ethernet_address,
+};
 
 #[cfg(feature = "embassy-net")]
 #[cfg_attr(docsrs, doc(cfg(feature = "embassy-net")))]
@@ -50,9 +50,53 @@
 //!    the `consume()` method takes `self` by value, preventing concurrent use.
 //!
 //! This pattern is common in embedded networking crates (see embassy-net, esp-wifi).
+//!
+//! # Token Strategy
+//!
+//! By default every frame is copied through a stack buffer: the RX
+//! descriptor goes back to the DMA before smoltcp parses the frame, and
+//! smoltcp builds outgoing frames on the stack before they are copied into
+//! the TX ring. [`TokenStrategy::ZeroCopy`] skips both copies by letting
+//! the token closures work on the ring buffers directly, at the price of
+//! holding a descriptor for as long as the closure runs. Switch at run time
+//! to compare the two, or to fall back if the ring starves:
+//!
+//! ```ignore
+//! use ph_esp32_mac::TokenStrategy;
+//!
+//! emac.apply_settings(emac.settings().with_token_strategy(TokenStrategy::ZeroCopy))?;
+//! ```
+//!
+//! Frames that do not fit in one DMA buffer, and TX frames while header
+//! rewrite rules are installed, take the copy path either way.
+//! [`PrefilterDevice`] always copies received frames, since its callback
+//! may transmit.
+//!
+//! # Raw Frame Pre-Filter
+//!
+//! [`PrefilterDevice`] wraps the EMAC so a callback sees every received frame
+//! before smoltcp does. Returning [`RxFilterAction::Consume`] hides the frame
+//! from smoltcp, which lets one EMAC serve both an IP stack and raw protocol
+//! handlers (LLDP, PTP, custom EtherTypes):
+//!
+//! ```ignore
+//! use ph_esp32_mac::integration::smoltcp::{PrefilterDevice, RxFilterAction};
+//!
+//! let mut device = PrefilterDevice::new(&mut emac, |frame: &[u8], _emac: &mut _| {
+//!     if frame.len() >= 14 && frame[12..14] == [0x88, 0xCC] {
+//!         handle_lldp(frame);
+//!         RxFilterAction::Consume
+//!     } else {
+//!         RxFilterAction::Pass
+//!     }
+//! });
+//!
+//! iface.poll(now, &mut device, &mut sockets);
+//! ```
 
-use crate::driver::config::State;
+use crate::driver::config::{State, TokenStrategy};
 use crate::driver::emac::Emac;
+use crate::driver::error::{Error, IoError};
 use crate::internal::constants::{MAX_FRAME_SIZE, MTU};
 
 use smoltcp::phy::{Checksum, ChecksumCapabilities, Device, DeviceCapabilities, Medium};
@@ -89,21 +133,41 @@ impl<'a, const RX: usize, const TX: usize, const BUF: usize> smoltcp::phy::RxTok
     where
         F: FnOnce(&[u8]) -> R,
     {
-        // Use a stack-allocated buffer for the frame
-        // This avoids heap allocation while being compatible with smoltcp's API
-        let mut buffer = [0u8; MAX_FRAME_SIZE];
-
         // SAFETY: The pointer is valid for 'a; token is consumed by value, so no aliasing, and RX/TX rings are separate.
         let emac = unsafe { &mut *self.emac };
 
-        // Receive the frame
-        let len = emac.receive(&mut buffer).unwrap_or_default();
-
-        // Call the consumer function with the received data
-        f(&buffer[..len])
+        if emac.settings().token_strategy == TokenStrategy::ZeroCopy {
+            let mut f = Some(f);
+            let lent = emac
+                .receive_in_place(|frame| f.take().map(|f| f(frame)))
+                .flatten();
+            return match (lent, f) {
+                (Some(result), _) => result,
+                // Not lendable in place (multi-buffer or errored frame)
+                (None, Some(f)) => receive_copied(emac, f),
+                (None, None) => unreachable!("RX consumer taken without a frame"),
+            };
+        }
+        receive_copied(emac, f)
     }
 }
 
+/// Copy-through receive: the frame leaves the RX ring before `f` sees it
+fn receive_copied<R, const RX: usize, const TX: usize, const BUF: usize>(
+    emac: &mut Emac<RX, TX, BUF>,
+    f: impl FnOnce(&[u8]) -> R,
+) -> R {
+    // Use a stack-allocated buffer for the frame
+    // This avoids heap allocation while being compatible with smoltcp's API
+    let mut buffer = [0u8; MAX_FRAME_SIZE];
+
+    // Receive the frame
+    let len = emac.receive(&mut buffer).unwrap_or_default();
+
+    // Call the consumer function with the received data
+    f(&buffer[..len])
+}
+
 // =============================================================================
 // TX Token
 // =============================================================================
@@ -138,22 +202,42 @@ impl<'a, const RX: usize, const TX: usize, const BUF: usize> smoltcp::phy::TxTok
         // Validate length
         let len = len.min(MAX_FRAME_SIZE);
 
-        // Use a stack-allocated buffer
-        let mut buffer = [0u8; MAX_FRAME_SIZE];
-
-        // Let smoltcp fill in the frame data
-        let result = f(&mut buffer[..len]);
-
         // SAFETY: The pointer is valid for 'a; token is consumed by value, so no aliasing, and TX/RX rings are separate.
         let emac = unsafe { &mut *self.emac };
 
-        // Transmit the frame (ignore errors, smoltcp will retry)
-        let _ = emac.transmit(&buffer[..len]);
-
-        result
+        if emac.settings().token_strategy == TokenStrategy::ZeroCopy {
+            let mut f = Some(f);
+            let built = emac.transmit_in_place(len, |buffer| f.take().map(|f| f(buffer)));
+            return match (built.flatten(), f) {
+                (Some(result), _) => result,
+                // No room in place; build the frame on the stack instead
+                (None, Some(f)) => transmit_copied(emac, len, f),
+                (None, None) => unreachable!("TX consumer taken without a buffer"),
+            };
+        }
+        transmit_copied(emac, len, f)
     }
 }
 
+/// Copy-through transmit: `f` builds the frame on the stack, then it is
+/// copied into the TX ring
+fn transmit_copied<R, const RX: usize, const TX: usize, const BUF: usize>(
+    emac: &mut Emac<RX, TX, BUF>,
+    len: usize,
+    f: impl FnOnce(&mut [u8]) -> R,
+) -> R {
+    // Use a stack-allocated buffer
+    let mut buffer = [0u8; MAX_FRAME_SIZE];
+
+    // Let smoltcp fill in the frame data
+    let result = f(&mut buffer[..len]);
+
+    // Transmit the frame (ignore errors, smoltcp will retry)
+    let _ = emac.transmit(&buffer[..len]);
+
+    result
+}
+
 // =============================================================================
 // Device Implementation
 // =============================================================================
@@ -233,23 +317,169 @@ impl<const RX: usize, const TX: usize, const BUF: usize> Device for Emac<RX, TX,
         // Single frame at a time (no scatter-gather for smoltcp)
         caps.max_burst_size = Some(1);
 
-        // Checksum capabilities
-        // The ESP32 EMAC supports hardware checksum, but we let smoltcp handle it
-        // for maximum compatibility. Set to None to use software checksums.
-        caps.checksum = ChecksumCapabilities::default();
-
-        // If hardware checksum is enabled in config, indicate that
-        // Note: This would need to be checked at runtime based on config
-        // For now, we use software checksums which are always correct
-        caps.checksum.ipv4 = Checksum::Both;
-        caps.checksum.udp = Checksum::Both;
-        caps.checksum.tcp = Checksum::Both;
-        caps.checksum.icmpv4 = Checksum::Both;
+        caps.checksum =
+            checksum_capabilities!(ChecksumCapabilities, Checksum, self.rx_checksum_offload());
 
         caps
     }
 }
 
+// =============================================================================
+// Pre-Filter Device
+// =============================================================================
+
+/// Decision returned by a [`PrefilterDevice`] callback
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum RxFilterAction {
+    /// Hand the frame to smoltcp
+    #[default]
+    Pass,
+    /// The callback handled the frame; smoltcp never sees it
+    Consume,
+}
+
+/// Receive token holding a frame already taken from the RX ring
+///
+/// Returned by [`PrefilterDevice`] for frames the callback passed on.
+pub struct PrefilterRxToken {
+    buffer: [u8; MAX_FRAME_SIZE],
+    len: usize,
+}
+
+impl smoltcp::phy::RxToken for PrefilterRxToken {
+    fn consume<R, F>(self, f: F) -> R
+    where
+        F: FnOnce(&[u8]) -> R,
+    {
+        f(&self.buffer[..self.len])
+    }
+}
+
+/// smoltcp device that offers every received frame to a callback first
+///
+/// The callback receives the frame and the EMAC (so it can transmit a
+/// response) and decides whether smoltcp should see the frame. Transmit
+/// and capabilities are identical to using the [`Emac`] directly.
+///
+/// Frames are copied out of the RX ring before the callback runs, so the
+/// callback may transmit freely.
+///
+/// Frames that fail to receive (receive errors, software FCS mismatch) are
+/// skipped, counted in [`Statistics`](crate::Statistics) and logged in the
+/// [`EventLog`](crate::EventLog) like any other `Emac::receive` failure.
+/// Errors that are not about one frame (ring busy, EMAC not receiving) end
+/// the poll until the next `receive()`.
+pub struct PrefilterDevice<'a, const RX: usize, const TX: usize, const BUF: usize, F>
+where
+    F: FnMut(&[u8], &mut Emac<RX, TX, BUF>) -> RxFilterAction,
+{
+    emac: &'a mut Emac<RX, TX, BUF>,
+    filter: F,
+    initial_rx_drain: Option<usize>,
+}
+
+impl<'a, const RX: usize, const TX: usize, const BUF: usize, F> PrefilterDevice<'a, RX, TX, BUF, F>
+where
+    F: FnMut(&[u8], &mut Emac<RX, TX, BUF>) -> RxFilterAction,
+{
+    /// Wrap an EMAC with a receive pre-filter
+    pub fn new(emac: &'a mut Emac<RX, TX, BUF>, filter: F) -> Self {
+        Self {
+            emac,
+            filter,
+            initial_rx_drain: None,
+        }
+    }
+
+    /// Discard up to `max_frames` stale frames on the first `receive()`
+    ///
+    /// Frames buffered before the interface is first polled are dropped
+    /// with [`Emac::drain_rx_discarding`] instead of reaching the filter or
+    /// smoltcp, so DHCP starts from an empty ring.
+    #[must_use]
+    pub fn with_initial_rx_drain(mut self, max_frames: usize) -> Self {
+        self.initial_rx_drain = Some(max_frames);
+        self
+    }
+
+    /// Access the wrapped EMAC
+    pub fn emac(&mut self) -> &mut Emac<RX, TX, BUF> {
+        self.emac
+    }
+
+    /// Release the wrapped EMAC
+    pub fn into_inner(self) -> &'a mut Emac<RX, TX, BUF> {
+        self.emac
+    }
+}
+
+impl<const RX: usize, const TX: usize, const BUF: usize, F> Device
+    for PrefilterDevice<'_, RX, TX, BUF, F>
+where
+    F: FnMut(&[u8], &mut Emac<RX, TX, BUF>) -> RxFilterAction,
+{
+    type RxToken<'b>
+        = PrefilterRxToken
+    where
+        Self: 'b;
+    type TxToken<'b>
+        = EmacTxToken<'b, RX, TX, BUF>
+    where
+        Self: 'b;
+
+    fn receive(&mut self, _timestamp: Instant) -> Option<(Self::RxToken<'_>, Self::TxToken<'_>)> {
+        if self.emac.state() != State::Running {
+            return None;
+        }
+
+        // Retried on the next poll if the ring was busy
+        if let Some(max_frames) = self.initial_rx_drain
+            && self.emac.drain_rx_discarding(max_frames).is_ok()
+        {
+            self.initial_rx_drain = None;
+        }
+
+        let mut buffer = [0u8; MAX_FRAME_SIZE];
+
+        // Bounded by the ring size so a flood of consumed frames cannot
+        // starve the caller's poll loop
+        for _ in 0..RX {
+            if !self.emac.rx_available() {
+                return None;
+            }
+
+            let len = match self.emac.receive(&mut buffer) {
+                Ok(len) => len,
+                Err(Error::Io(
+                    IoError::FrameError | IoError::FcsMismatch | IoError::BufferTooSmall,
+                )) => continue,
+                Err(_) => return None,
+            };
+
+            if (self.filter)(&buffer[..len], self.emac) == RxFilterAction::Pass {
+                return Some((
+                    PrefilterRxToken { buffer, len },
+                    EmacTxToken {
+                        emac: self.emac as *mut _,
+                        _marker: core::marker::PhantomData,
+                    },
+                ));
+            }
+        }
+
+        None
+    }
+
+    fn transmit(&mut self, timestamp: Instant) -> Option<Self::TxToken<'_>> {
+        Device::transmit(self.emac, timestamp)
+    }
+
+    fn capabilities(&self) -> DeviceCapabilities {
+        Device::capabilities(self.emac)
+    }
+}
+
 // =============================================================================
 // Helper Functions
 // =============================================================================
@@ -324,6 +554,27 @@ mod tests {
         let _icmpv4 = caps.icmpv4;
     }
 
+    #[test]
+    fn rx_checksum_offload_skips_rx_validation() {
+        let caps = checksum_capabilities!(ChecksumCapabilities, Checksum, true);
+        for side in [caps.ipv4, caps.udp, caps.tcp, caps.icmpv4] {
+            assert!(matches!(side, Checksum::Tx));
+        }
+        let caps = checksum_capabilities!(ChecksumCapabilities, Checksum, false);
+        for side in [caps.ipv4, caps.udp, caps.tcp, caps.icmpv4] {
+            assert!(matches!(side, Checksum::Both));
+        }
+    }
+
+    #[test]
+    fn emac_capabilities_default_to_software_checksums() {
+        let emac: Emac<4, 4, 1600> = Emac::new();
+        assert!(matches!(
+            Device::capabilities(&emac).checksum.tcp,
+            Checksum::Both
+        ));
+    }
+
     #[test]
     fn device_capabilities_default_has_medium_ethernet() {
         let caps = DeviceCapabilities::default();
@@ -336,6 +587,143 @@ mod tests {
         assert_eq!(caps.max_burst_size, None);
     }
 
+    // =========================================================================
+    // Pre-Filter Tests
+    // =========================================================================
+
+    #[test]
+    fn rx_filter_action_default_is_pass() {
+        assert_eq!(RxFilterAction::default(), RxFilterAction::Pass);
+    }
+
+    #[test]
+    fn prefilter_rx_token_yields_stored_frame() {
+        use smoltcp::phy::RxToken;
+
+        let mut buffer = [0u8; MAX_FRAME_SIZE];
+        buffer[..4].copy_from_slice(&[1, 2, 3, 4]);
+        let token = PrefilterRxToken { buffer, len: 4 };
+
+        let seen = token.consume(|frame| {
+            assert_eq!(frame, &[1, 2, 3, 4]);
+            frame.len()
+        });
+        assert_eq!(seen, 4);
+    }
+
+    #[test]
+    fn prefilter_device_not_running_yields_nothing() {
+        let mut emac: Emac<4, 4, 1600> = Emac::new();
+        let mut device = PrefilterDevice::new(&mut emac, |_frame: &[u8], _emac: &mut _| {
+            panic!("filter must not run while the EMAC is stopped")
+        });
+
+        assert!(device.receive(Instant::ZERO).is_none());
+        assert!(device.transmit(Instant::ZERO).is_none());
+    }
+
+    #[test]
+    fn prefilter_device_skips_errored_frames() {
+        use crate::internal::dma::bits::rdes0;
+
+        let mut emac = sim_running(TokenStrategy::Copy);
+        assert!(
+            emac.sim_dma()
+                .sim_rx_frame(&ipv4_frame(0x11), rdes0::ERR_SUMMARY | rdes0::CRC_ERR)
+        );
+        assert!(emac.sim_dma().sim_rx_frame(&ipv4_frame(0x22), 0));
+
+        let mut device = PrefilterDevice::new(&mut emac, |frame: &[u8], _emac: &mut _| {
+            assert_eq!(frame[20], 0x22);
+            RxFilterAction::Pass
+        });
+        assert!(device.receive(Instant::ZERO).is_some());
+
+        let emac = device.into_inner();
+        assert_eq!(emac.stats().rx_errors, 1);
+        assert_eq!(emac.stats().rx_frames, 1);
+    }
+
+    #[test]
+    fn prefilter_device_capabilities_match_emac() {
+        let mut emac: Emac<4, 4, 1600> = Emac::new();
+        let expected = Device::capabilities(&emac);
+        let device = PrefilterDevice::new(&mut emac, |_: &[u8], _: &mut _| RxFilterAction::Pass);
+        let caps = device.capabilities();
+
+        assert_eq!(caps.max_transmission_unit, expected.max_transmission_unit);
+        assert_eq!(caps.max_burst_size, expected.max_burst_size);
+        assert_eq!(caps.medium, expected.medium);
+    }
+
+    // =========================================================================
+    // Token Strategy Tests
+    // =========================================================================
+
+    fn sim_running(strategy: TokenStrategy) -> Emac<4, 4, 1600> {
+        use crate::driver::config::EmacConfig;
+        use crate::internal::register::sim;
+
+        sim::reset();
+        let mut emac = Emac::new();
+        emac.sim_init(EmacConfig::new().with_token_strategy(strategy));
+        emac.start().unwrap();
+        emac
+    }
+
+    fn ipv4_frame(fill: u8) -> [u8; 60] {
+        let mut frame = [fill; 60];
+        frame[..6].copy_from_slice(&[0xFF; 6]);
+        frame[12..14].copy_from_slice(&[0x08, 0x00]);
+        frame
+    }
+
+    #[test]
+    fn token_strategies_deliver_the_same_frames() {
+        use smoltcp::phy::{RxToken, TxToken};
+
+        for strategy in [TokenStrategy::Copy, TokenStrategy::ZeroCopy] {
+            let mut emac = sim_running(strategy);
+            let frame = ipv4_frame(0x11);
+            assert!(emac.sim_dma().sim_rx_frame(&frame, 0));
+
+            let (rx, _) = Device::receive(&mut emac, Instant::ZERO).unwrap();
+            let len = rx.consume(|seen| {
+                assert_eq!(seen, &frame[..]);
+                seen.len()
+            });
+            assert_eq!(len, 60, "{strategy:?}");
+            assert_eq!(emac.stats().rx_frames, 1, "{strategy:?}");
+            assert!(!emac.rx_available());
+
+            let tx = Device::transmit(&mut emac, Instant::ZERO).unwrap();
+            tx.consume(60, |buffer| buffer.copy_from_slice(&frame));
+            assert_eq!(emac.sim_dma().tx_buffer(0)[..60], frame, "{strategy:?}");
+            assert_eq!(emac.sim_dma().tx_available(), 3, "{strategy:?}");
+        }
+    }
+
+    #[test]
+    fn zero_copy_token_returns_descriptor_after_consume() {
+        use crate::internal::register::dma::DMARXPOLLDEMAND_OFFSET;
+        use crate::internal::register::{DMA_BASE, sim};
+        use smoltcp::phy::RxToken;
+
+        let polls = || sim::write_count(DMA_BASE + DMARXPOLLDEMAND_OFFSET);
+        let mut emac = sim_running(TokenStrategy::Copy);
+        for (strategy, polls_in_closure) in [(TokenStrategy::Copy, 1), (TokenStrategy::ZeroCopy, 0)]
+        {
+            emac.apply_settings(emac.settings().with_token_strategy(strategy))
+                .unwrap();
+            assert!(emac.sim_dma().sim_rx_frame(&ipv4_frame(0x22), 0));
+            let before = polls();
+
+            let (rx, _) = Device::receive(&mut emac, Instant::ZERO).unwrap();
+            rx.consume(|_| assert_eq!(polls() - before, polls_in_closure, "{strategy:?}"));
+            assert_eq!(polls() - before, 1, "{strategy:?}");
+        }
+    }
+
     // =========================================================================
     // Token Marker Tests
     // =========================================================================
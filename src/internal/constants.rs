@@ -63,11 +63,28 @@ pub const SOFT_RESET_TIMEOUT_MS: u32 = 100;
 /// Reset poll interval in microseconds
 pub const RESET_POLL_INTERVAL_US: u32 = 100;
 
-/// Maximum iterations waiting for MII/MDIO operation
-pub const MII_BUSY_TIMEOUT: u32 = 100_000;
+/// Register polls assumed per microsecond when neither a clock nor a delay
+/// is available to measure a timeout
+pub const POLL_SPINS_PER_US: u32 = 10;
 
-/// Maximum iterations waiting for TX FIFO flush
-pub const FLUSH_TIMEOUT: u32 = 100_000;
+/// Interval between register polls when timing with a delay provider
+pub const POLL_DELAY_US: u32 = 1;
+
+/// Default time waiting for MII/MDIO operation, in microseconds
+pub const MII_BUSY_TIMEOUT_US: u32 = 10_000;
+
+/// Iterations equivalent to the default MII/MDIO timeout (without a clock)
+pub const MII_BUSY_TIMEOUT: u32 = MII_BUSY_TIMEOUT_US * POLL_SPINS_PER_US;
+
+/// Default time waiting for TX DMA stop or FIFO flush, in microseconds
+pub const FLUSH_TIMEOUT_US: u32 = 10_000;
+
+/// Iterations equivalent to the default TX FIFO flush timeout (without a
+/// clock)
+pub const FLUSH_TIMEOUT: u32 = FLUSH_TIMEOUT_US * POLL_SPINS_PER_US;
+
+/// Maximum status read/acknowledge rounds per interrupt
+pub const INTERRUPT_ACK_ROUNDS: usize = 4;
 
 // =============================================================================
 // Clock Frequencies
@@ -151,11 +168,9 @@ pub const TX_DMA_STATE_SHIFT: u32 = 20;
 pub const TX_DMA_STATE_MASK: u32 = 0x7;
 
 /// RX DMA state shift in status register
-#[allow(dead_code)]
 pub const RX_DMA_STATE_SHIFT: u32 = 17;
 
 /// RX DMA state mask (3 bits)
-#[allow(dead_code)]
 pub const RX_DMA_STATE_MASK: u32 = 0x7;
 
 // =============================================================================
@@ -247,6 +262,14 @@ mod tests {
         assert!(FLUSH_TIMEOUT > 0);
     }
 
+    #[test]
+    fn time_budgets_cover_slowest_operations() {
+        // An MDIO frame at the slowest MDC (about 1 MHz) takes 64 us; a full
+        // 1518-byte frame leaves the FIFO in 1.2 ms at 10 Mbps
+        assert!(MII_BUSY_TIMEOUT_US >= 1_000);
+        assert!(FLUSH_TIMEOUT_US >= 2_000);
+    }
+
     // =========================================================================
     // Clock Frequency Validation
     // =========================================================================
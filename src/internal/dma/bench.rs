@@ -0,0 +1,208 @@
+//! Host microbenchmarks for the DMA hot path.
+//!
+//! Runs the real transmit, receive and reclaim code against the simulated
+//! register file, with the DMA's side of the descriptor handshake done by the
+//! engine's `sim_*` hooks outside the timed region. Frame contents come from
+//! a fixed-seed generator, so runs differ only by the code under test.
+//!
+//! ```bash
+//! cargo test --lib --release --features bench bench_ -- --nocapture --test-threads=1
+//! ```
+//!
+//! Each benchmark prints the median and fastest of [`SAMPLES`] samples in
+//! nanoseconds per frame (or per call). Host numbers are only meaningful as
+//! before/after deltas for the same machine; they say nothing about the
+//! ESP32 itself.
+
+extern crate std;
+
+use core::hint::black_box;
+use core::time::Duration;
+use std::println;
+use std::time::Instant;
+
+use super::DmaEngine;
+use crate::internal::register::sim;
+
+/// Timed samples per benchmark
+const SAMPLES: usize = 15;
+
+/// Untimed warmup rounds before sampling
+const WARMUP_ROUNDS: usize = 50;
+
+/// Ring rounds per sample
+const ROUNDS: usize = 200;
+
+/// Seed for the frame generator
+const SEED: u32 = 0x2545_F491;
+
+/// Deterministic frame of `len` bytes (xorshift32)
+fn frame<const N: usize>(len: usize) -> [u8; N] {
+    let mut state = SEED ^ len as u32;
+    let mut out = [0u8; N];
+    for b in out.iter_mut().take(len) {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        *b = state as u8;
+    }
+    out
+}
+
+/// Run `round` [`WARMUP_ROUNDS`] times, then [`SAMPLES`] samples of
+/// [`ROUNDS`] rounds each, and print per-operation timings.
+///
+/// `round` returns the time spent in the measured part and how many
+/// operations it covered, so setup inside a round stays untimed.
+fn run(name: &str, mut round: impl FnMut() -> (Duration, usize)) {
+    for _ in 0..WARMUP_ROUNDS {
+        round();
+    }
+
+    let mut per_op = [0u128; SAMPLES];
+    for sample in &mut per_op {
+        let mut elapsed = Duration::ZERO;
+        let mut ops = 0usize;
+        for _ in 0..ROUNDS {
+            let (t, n) = round();
+            elapsed += t;
+            ops += n;
+        }
+        *sample = elapsed.as_nanos() / ops.max(1) as u128;
+    }
+    per_op.sort_unstable();
+
+    println!(
+        "bench {name:<32} median {:>6} ns/op  min {:>6} ns/op",
+        per_op[SAMPLES / 2],
+        per_op[0]
+    );
+}
+
+/// Queue a full ring of `len`-byte frames, timing only `transmit()`
+fn bench_tx_prepare<const RX: usize, const TX: usize, const BUF: usize>(
+    name: &str,
+    dual_buffer: bool,
+    len: usize,
+) {
+    sim::reset();
+    let mut dma: DmaEngine<RX, TX, BUF> = DmaEngine::new();
+    dma.set_dual_buffer(dual_buffer);
+    dma.init();
+    let data = frame::<1600>(len);
+    let data = &data[..len];
+
+    run(name, || {
+        let slots = dma.tx_available();
+        let start = Instant::now();
+        for _ in 0..slots {
+            black_box(dma.transmit(black_box(data))).unwrap();
+        }
+        let elapsed = start.elapsed();
+        dma.sim_tx_complete();
+        (elapsed, slots)
+    });
+}
+
+/// Drain a full ring of `len`-byte frames, timing only `receive()`
+fn bench_rx_copy<const RX: usize, const TX: usize, const BUF: usize>(
+    name: &str,
+    dual_buffer: bool,
+    len: usize,
+) {
+    sim::reset();
+    let mut dma: DmaEngine<RX, TX, BUF> = DmaEngine::new();
+    dma.set_dual_buffer(dual_buffer);
+    dma.init();
+    let data = frame::<1600>(len);
+    let data = &data[..len];
+    let mut buf = [0u8; 1600];
+
+    run(name, || {
+        let mut frames = 0;
+        while dma.sim_rx_frame(data, 0) {
+            frames += 1;
+        }
+        let start = Instant::now();
+        for _ in 0..frames {
+            black_box(dma.receive(black_box(&mut buf))).unwrap();
+        }
+        (start.elapsed(), frames)
+    });
+}
+
+/// Drain a ring the way `Emac::receive` does: poll, peek the status and
+/// length, then receive, timing all four calls
+fn bench_rx_poll_receive<const RX: usize, const TX: usize, const BUF: usize>(
+    name: &str,
+    len: usize,
+) {
+    sim::reset();
+    let mut dma: DmaEngine<RX, TX, BUF> = DmaEngine::new();
+    dma.init();
+    let data = frame::<1600>(len);
+    let data = &data[..len];
+    let mut buf = [0u8; 1600];
+
+    run(name, || {
+        let mut frames = 0;
+        while dma.sim_rx_frame(data, 0) {
+            frames += 1;
+        }
+        let start = Instant::now();
+        while black_box(&dma).rx_available() {
+            black_box(dma.peek_frame_status());
+            black_box(dma.peek_frame_length());
+            black_box(dma.receive(black_box(&mut buf))).unwrap();
+        }
+        (start.elapsed(), frames)
+    });
+}
+
+/// Scan a ring of completed descriptors with `tx_reclaim()`
+fn bench_tx_reclaim<const RX: usize, const TX: usize, const BUF: usize>(name: &str) {
+    sim::reset();
+    let mut dma: DmaEngine<RX, TX, BUF> = DmaEngine::new();
+    dma.init();
+    let data = frame::<64>(64);
+    while dma.transmit(&data).is_ok() {}
+    dma.sim_tx_complete();
+
+    run(name, || {
+        let start = Instant::now();
+        black_box(black_box(&mut dma).tx_reclaim());
+        (start.elapsed(), 1)
+    });
+}
+
+#[test]
+fn bench_generator_is_reproducible() {
+    assert_eq!(frame::<64>(64), frame::<64>(64));
+    assert_ne!(frame::<64>(64)[..60], frame::<64>(60)[..60]);
+}
+
+#[test]
+fn bench_transmit_prepare() {
+    bench_tx_prepare::<10, 10, 1600>("tx_prepare/chained/64", false, 64);
+    bench_tx_prepare::<10, 10, 1600>("tx_prepare/chained/1514", false, 1514);
+    bench_tx_prepare::<10, 10, 800>("tx_prepare/dual_buffer/1514", true, 1514);
+}
+
+#[test]
+fn bench_receive_copy() {
+    bench_rx_copy::<10, 10, 1600>("rx_copy/chained/64", false, 64);
+    bench_rx_copy::<10, 10, 1600>("rx_copy/chained/1514", false, 1514);
+    bench_rx_copy::<10, 10, 800>("rx_copy/dual_buffer/1514", true, 1514);
+}
+
+#[test]
+fn bench_receive_poll_peek() {
+    bench_rx_poll_receive::<10, 10, 1600>("rx_poll_peek_receive/64", 64);
+    bench_rx_poll_receive::<32, 32, 256>("rx_poll_peek_receive/32_slots/64", 64);
+}
+
+#[test]
+fn bench_reclaim() {
+    bench_tx_reclaim::<10, 10, 1600>("tx_reclaim/10_slots");
+    bench_tx_reclaim::<32, 32, 256>("tx_reclaim/32_slots");
+}
@@ -3,13 +3,33 @@
 //! Each descriptor points to a data buffer and contains status/control bits
 //! for CPU/DMA ownership coordination.
 
-pub mod bits;
+pub use crate::logic::descriptor as bits;
 pub mod rx;
 pub mod tx;
 
-pub use rx::RxDescriptor;
+pub use rx::{RxDescriptor, RxStatus};
 pub use tx::TxDescriptor;
 
+use core::sync::atomic::{Ordering, fence};
+
+/// Order prior buffer and descriptor writes before handing a descriptor to
+/// the DMA
+///
+/// Volatile accesses are only ordered by the compiler. On a dual-core ESP32
+/// the other core (and the DMA) can observe plain stores out of order, so
+/// the OWN bit must not become visible before the data it publishes.
+#[inline(always)]
+pub(crate) fn release_to_dma() {
+    fence(Ordering::Release);
+}
+
+/// Order descriptor and buffer reads after observing that the DMA returned
+/// a descriptor; counterpart of [`release_to_dma`]
+#[inline(always)]
+pub(crate) fn acquire_from_dma() {
+    fence(Ordering::Acquire);
+}
+
 /// Volatile cell wrapper for descriptor fields
 ///
 /// Ensures all accesses are volatile to prevent compiler optimization
@@ -34,6 +54,8 @@ impl<T: Copy> VolatileCell<T> {
     /// Read the value (volatile read)
     #[inline(always)]
     pub fn get(&self) -> T {
+        #[cfg(test)]
+        access_count::record_read();
         // SAFETY: Volatile access to a valid UnsafeCell-backed pointer.
         unsafe { core::ptr::read_volatile(self.value.get()) }
     }
@@ -61,3 +83,27 @@ impl<T: Copy + Default> Default for VolatileCell<T> {
         Self::new(T::default())
     }
 }
+
+/// Count of descriptor word reads on the current thread, for tests that
+/// pin down how often the hot paths touch descriptor memory.
+#[cfg(test)]
+pub(crate) mod access_count {
+    extern crate std;
+
+    use core::cell::Cell;
+
+    std::thread_local! {
+        static READS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    pub(crate) fn record_read() {
+        READS.with(|reads| reads.set(reads.get() + 1));
+    }
+
+    /// Reads made by `f`
+    pub(crate) fn reads_in<R>(f: impl FnOnce() -> R) -> (R, usize) {
+        let before = READS.with(Cell::get);
+        let result = f();
+        (result, READS.with(Cell::get) - before)
+    }
+}
@@ -1,7 +1,7 @@
 //! RX DMA descriptor for frame reception.
 
-use super::VolatileCell;
 use super::bits::{rdes0, rdes1, rdes4};
+use super::{VolatileCell, acquire_from_dma, release_to_dma};
 
 /// RX DMA descriptor (32 bytes on ESP32, 64 bytes on ESP32-P4).
 #[repr(C)]
@@ -62,8 +62,7 @@ impl RxDescriptor {
         self.buffer2_next_desc.set(next_desc as u32);
         self.rdes1
             .set(rdes1::SECOND_ADDR_CHAINED | ((buffer_size as u32) & rdes1::BUFFER1_SIZE_MASK));
-        // Give ownership to DMA
-        self.rdes0.set(rdes0::OWN);
+        self.set_owned();
     }
 
     /// Configure as end of ring (wraps to first descriptor).
@@ -80,20 +79,68 @@ impl RxDescriptor {
                 | rdes1::RX_END_OF_RING
                 | ((buffer_size as u32) & rdes1::BUFFER1_SIZE_MASK),
         );
-        // Give ownership to DMA
-        self.rdes0.set(rdes0::OWN);
+        self.set_owned();
+    }
+
+    /// Configure in ring mode with two buffers of `buffer_size` bytes each.
+    ///
+    /// `end_of_ring` marks the last descriptor so the DMA wraps back to the
+    /// list base address.
+    pub fn setup_ring(
+        &self,
+        buffer1: *mut u8,
+        buffer2: *mut u8,
+        buffer_size: usize,
+        end_of_ring: bool,
+    ) {
+        let size = (buffer_size as u32) & rdes1::BUFFER1_SIZE_MASK;
+        let mut ctrl = size | ((size << rdes1::BUFFER2_SIZE_SHIFT) & rdes1::BUFFER2_SIZE_MASK);
+        if end_of_ring {
+            ctrl |= rdes1::RX_END_OF_RING;
+        }
+        self.buffer1_addr.set(buffer1 as u32);
+        self.buffer2_next_desc.set(buffer2 as u32);
+        self.rdes1.set(ctrl);
+        self.set_owned();
+    }
+
+    /// Zero the descriptor and keep it from the DMA.
+    pub fn clear(&self) {
+        self.rdes0.set(0);
+        self.rdes1.set(0);
+        self.buffer1_addr.set(0);
+        self.buffer2_next_desc.set(0);
     }
 
     /// Returns true if DMA owns this descriptor.
     #[inline(always)]
     #[must_use]
     pub fn is_owned(&self) -> bool {
-        (self.rdes0.get() & rdes0::OWN) != 0
+        let owned = (self.rdes0.get() & rdes0::OWN) != 0;
+        if !owned {
+            acquire_from_dma();
+        }
+        owned
+    }
+
+    /// Snapshot of RDES0 from a single volatile read.
+    ///
+    /// Decode ownership, frame boundaries, errors and length from the
+    /// snapshot instead of re-reading the descriptor for each field.
+    #[inline(always)]
+    #[must_use]
+    pub fn status(&self) -> RxStatus {
+        let status = RxStatus(self.rdes0.get());
+        if !status.is_owned() {
+            acquire_from_dma();
+        }
+        status
     }
 
     /// Give ownership to DMA.
     #[inline(always)]
     pub fn set_owned(&self) {
+        release_to_dma();
         self.rdes0.set(rdes0::OWN);
     }
 
@@ -258,8 +305,16 @@ impl RxDescriptor {
         (self.rdes1.get() & rdes1::BUFFER1_SIZE_MASK) as usize
     }
 
+    /// Configured buffer 2 size (ring mode).
+    #[inline(always)]
+    #[must_use]
+    pub fn buffer2_size(&self) -> usize {
+        ((self.rdes1.get() & rdes1::BUFFER2_SIZE_MASK) >> rdes1::BUFFER2_SIZE_SHIFT) as usize
+    }
+
     /// Clear status and return to DMA.
     pub fn recycle(&self) {
+        release_to_dma();
         self.rdes0.set(rdes0::OWN);
     }
 
@@ -270,6 +325,18 @@ impl RxDescriptor {
         self.rdes0.get()
     }
 
+    /// Overwrite RDES0 the way the DMA does on frame completion.
+    #[cfg(any(test, feature = "test-hooks"))]
+    pub(crate) fn set_raw_rdes0(&self, value: u32) {
+        self.rdes0.set(value);
+    }
+
+    /// Overwrite RDES4 the way the DMA does when extended status is valid.
+    #[cfg(test)]
+    pub(crate) fn set_raw_rdes4(&self, value: u32) {
+        self.extended_status.set(value);
+    }
+
     /// Raw RDES1 (for debugging).
     #[inline(always)]
     #[must_use]
@@ -289,6 +356,64 @@ unsafe impl Sync for RxDescriptor {}
 // SAFETY: RxDescriptor uses volatile cells for all DMA-accessed fields.
 unsafe impl Send for RxDescriptor {}
 
+/// Copy of an RX descriptor's RDES0 word.
+///
+/// Once the DMA clears OWN it no longer writes the descriptor, so a
+/// snapshot taken then stays valid until the descriptor is recycled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RxStatus(u32);
+
+impl RxStatus {
+    /// Wrap a raw RDES0 value.
+    #[inline(always)]
+    #[must_use]
+    pub const fn from_raw(raw: u32) -> Self {
+        Self(raw)
+    }
+
+    /// Raw RDES0 value.
+    #[inline(always)]
+    #[must_use]
+    pub const fn raw(self) -> u32 {
+        self.0
+    }
+
+    /// DMA owned the descriptor when the snapshot was taken.
+    #[inline(always)]
+    #[must_use]
+    pub const fn is_owned(self) -> bool {
+        (self.0 & rdes0::OWN) != 0
+    }
+
+    /// First descriptor of a frame.
+    #[inline(always)]
+    #[must_use]
+    pub const fn is_first(self) -> bool {
+        (self.0 & rdes0::FIRST_DESC) != 0
+    }
+
+    /// Last descriptor of a frame.
+    #[inline(always)]
+    #[must_use]
+    pub const fn is_last(self) -> bool {
+        (self.0 & rdes0::LAST_DESC) != 0
+    }
+
+    /// Error summary bit set.
+    #[inline(always)]
+    #[must_use]
+    pub const fn has_error(self) -> bool {
+        (self.0 & rdes0::ERR_SUMMARY) != 0
+    }
+
+    /// Frame length excluding 4-byte CRC (valid on last descriptor).
+    #[inline(always)]
+    #[must_use]
+    pub const fn payload_length(self) -> usize {
+        (((self.0 & rdes0::FRAME_LEN_MASK) >> rdes0::FRAME_LEN_SHIFT) as usize).saturating_sub(4)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -491,4 +616,41 @@ mod tests {
         // Buffer size should be preserved in rdes1
         assert_eq!(desc.buffer_size(), 1600);
     }
+
+    #[test]
+    fn rx_descriptor_setup_ring() {
+        let desc = RxDescriptor::new();
+        let mut buf1 = [0u8; 16];
+        let mut buf2 = [0u8; 16];
+
+        desc.setup_ring(buf1.as_mut_ptr(), buf2.as_mut_ptr(), 512, false);
+
+        assert!(desc.is_owned());
+        assert_eq!(desc.buffer_size(), 512);
+        assert_eq!(desc.buffer2_size(), 512);
+        assert_eq!(desc.buffer_addr(), buf1.as_ptr() as u32);
+        assert_eq!(desc.next_desc_addr(), buf2.as_ptr() as u32);
+        assert_eq!(desc.raw_rdes1() & rdes1::SECOND_ADDR_CHAINED, 0);
+        assert_eq!(desc.raw_rdes1() & rdes1::RX_END_OF_RING, 0);
+
+        desc.setup_ring(buf1.as_mut_ptr(), buf2.as_mut_ptr(), 512, true);
+        assert_ne!(desc.raw_rdes1() & rdes1::RX_END_OF_RING, 0);
+
+        // Recycle keeps both buffer sizes
+        desc.recycle();
+        assert_eq!(desc.buffer2_size(), 512);
+    }
+
+    #[test]
+    fn rx_descriptor_clear() {
+        let desc = RxDescriptor::new();
+        let mut buf = [0u8; 16];
+        desc.setup_chained(buf.as_mut_ptr(), 16, core::ptr::null());
+
+        desc.clear();
+
+        assert!(!desc.is_owned());
+        assert_eq!(desc.raw_rdes1(), 0);
+        assert_eq!(desc.buffer_addr(), 0);
+    }
 }
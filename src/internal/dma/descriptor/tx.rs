@@ -1,7 +1,7 @@
 //! TX DMA descriptor for frame transmission.
 
-use super::VolatileCell;
 use super::bits::{tdes0, tdes1};
+use super::{VolatileCell, acquire_from_dma, release_to_dma};
 
 /// TX DMA descriptor (32 bytes on ESP32, 64 bytes on ESP32-P4).
 #[repr(C)]
@@ -68,16 +68,49 @@ impl TxDescriptor {
         self.tdes1.set(0);
     }
 
+    /// Initialize descriptor for ring mode with two data buffers.
+    ///
+    /// `end_of_ring` marks the last descriptor so the DMA wraps back to the
+    /// list base address.
+    pub fn setup_ring(&self, buffer1: *const u8, buffer2: *const u8, end_of_ring: bool) {
+        self.buffer1_addr.set(buffer1 as u32);
+        self.buffer2_next_desc.set(buffer2 as u32);
+        self.tdes0.set(if end_of_ring {
+            tdes0::TX_END_OF_RING
+        } else {
+            0
+        });
+        self.tdes1.set(0);
+    }
+
+    /// Point buffer 1 at `buffer`. Only valid before `set_owned`.
+    pub fn set_buffer1(&self, buffer: *const u8) {
+        self.buffer1_addr.set(buffer as u32);
+    }
+
+    /// Zero the descriptor and keep it from the DMA.
+    pub fn clear(&self) {
+        self.tdes0.set(0);
+        self.tdes1.set(0);
+        self.buffer1_addr.set(0);
+        self.buffer2_next_desc.set(0);
+    }
+
     /// Check if descriptor is owned by DMA.
     #[inline(always)]
     #[must_use]
     pub fn is_owned(&self) -> bool {
-        (self.tdes0.get() & tdes0::OWN) != 0
+        let owned = (self.tdes0.get() & tdes0::OWN) != 0;
+        if !owned {
+            acquire_from_dma();
+        }
+        owned
     }
 
     /// Give ownership to DMA for transmission.
     #[inline(always)]
     pub fn set_owned(&self) {
+        release_to_dma();
         self.tdes0.update(|v| v | tdes0::OWN);
     }
 
@@ -104,6 +137,39 @@ impl TxDescriptor {
         self.tdes0.set(flags);
     }
 
+    /// Prepare a ring-mode descriptor whose data spans both buffers.
+    ///
+    /// Keeps the end-of-ring bit set by `setup_ring`.
+    pub fn prepare_split(&self, len1: usize, len2: usize, first: bool, last: bool) {
+        let mut flags = self.tdes0.get() & tdes0::TX_END_OF_RING;
+
+        if first {
+            flags |= tdes0::FIRST_SEGMENT;
+        }
+        if last {
+            flags |= tdes0::LAST_SEGMENT | tdes0::INTERRUPT_ON_COMPLETE;
+        }
+
+        self.tdes1.set(
+            ((len1 as u32) & tdes1::BUFFER1_SIZE_MASK)
+                | (((len2 as u32) << tdes1::BUFFER2_SIZE_SHIFT) & tdes1::BUFFER2_SIZE_MASK),
+        );
+
+        // Set flags (but not OWN yet)
+        self.tdes0.set(flags);
+    }
+
+    /// Set or clear interrupt-on-completion. Only valid before `set_owned`.
+    pub fn set_interrupt_on_complete(&self, enable: bool) {
+        self.tdes0.update(|v| {
+            if enable {
+                v | tdes0::INTERRUPT_ON_COMPLETE
+            } else {
+                v & !tdes0::INTERRUPT_ON_COMPLETE
+            }
+        });
+    }
+
     /// Prepare and submit to DMA in one operation.
     pub fn prepare_and_submit(&self, len: usize, first: bool, last: bool) {
         self.prepare(len, first, last);
@@ -137,6 +203,19 @@ impl TxDescriptor {
         self.tdes0.get() & tdes0::ALL_ERRORS
     }
 
+    /// Check if this descriptor holds the last segment of a frame.
+    #[inline(always)]
+    #[must_use]
+    pub fn is_last(&self) -> bool {
+        (self.tdes0.get() & tdes0::LAST_SEGMENT) != 0
+    }
+
+    /// Clear the written-back error status. Only valid while CPU-owned.
+    pub fn clear_errors(&self) {
+        self.tdes0
+            .update(|v| v & !(tdes0::ALL_ERRORS | tdes0::ERR_SUMMARY));
+    }
+
     /// Get collision count for half-duplex mode.
     #[inline(always)]
     #[must_use]
@@ -186,10 +265,11 @@ impl TxDescriptor {
         self.buffer2_next_desc.get()
     }
 
-    /// Reset descriptor to initial state.
+    /// Reset descriptor to initial state, keeping its chain/ring layout.
     pub fn reset(&self) {
         let next = self.buffer2_next_desc.get();
-        self.tdes0.set(tdes0::SECOND_ADDR_CHAINED);
+        let layout = self.tdes0.get() & (tdes0::SECOND_ADDR_CHAINED | tdes0::TX_END_OF_RING);
+        self.tdes0.set(layout);
         self.tdes1.set(0);
         self.buffer2_next_desc.set(next);
     }
@@ -201,6 +281,12 @@ impl TxDescriptor {
         self.tdes0.get()
     }
 
+    /// Overwrite TDES0 the way the DMA does on transmit completion.
+    #[cfg(test)]
+    pub(crate) fn set_raw_tdes0(&self, value: u32) {
+        self.tdes0.set(value);
+    }
+
     /// Get raw TDES1 value for debugging.
     #[inline(always)]
     #[must_use]
@@ -341,6 +427,20 @@ mod tests {
         assert!(raw & tdes0::INTERRUPT_ON_COMPLETE != 0);
     }
 
+    #[test]
+    fn tx_descriptor_clear_interrupt_on_complete() {
+        let desc = TxDescriptor::new();
+        desc.prepare(1500, true, true);
+        desc.set_interrupt_on_complete(false);
+
+        let raw = desc.raw_tdes0();
+        assert!(raw & tdes0::INTERRUPT_ON_COMPLETE == 0);
+        assert!(raw & tdes0::LAST_SEGMENT != 0, "segment flags must be kept");
+
+        desc.set_interrupt_on_complete(true);
+        assert!(desc.raw_tdes0() & tdes0::INTERRUPT_ON_COMPLETE != 0);
+    }
+
     #[test]
     fn tx_descriptor_frame_length() {
         let desc = TxDescriptor::new();
@@ -471,4 +571,54 @@ mod tests {
         // Chain flag should still be set
         assert!(desc.raw_tdes0() & tdes0::SECOND_ADDR_CHAINED != 0);
     }
+
+    #[test]
+    fn tx_descriptor_setup_ring() {
+        let desc = TxDescriptor::new();
+        let buf1 = [0u8; 16];
+        let buf2 = [0u8; 16];
+
+        desc.setup_ring(buf1.as_ptr(), buf2.as_ptr(), true);
+
+        assert!(!desc.is_owned());
+        assert_eq!(desc.buffer_addr(), buf1.as_ptr() as u32);
+        assert_eq!(desc.next_desc_addr(), buf2.as_ptr() as u32);
+        assert_eq!(desc.raw_tdes0(), tdes0::TX_END_OF_RING);
+    }
+
+    #[test]
+    fn tx_descriptor_prepare_split() {
+        let desc = TxDescriptor::new();
+        let buf = [0u8; 16];
+        desc.setup_ring(buf.as_ptr(), buf.as_ptr(), true);
+
+        desc.prepare_split(1600, 200, true, true);
+
+        let tdes0_val = desc.raw_tdes0();
+        assert_ne!(tdes0_val & tdes0::TX_END_OF_RING, 0);
+        assert_eq!(tdes0_val & tdes0::SECOND_ADDR_CHAINED, 0);
+        assert_ne!(tdes0_val & tdes0::FIRST_SEGMENT, 0);
+        assert_ne!(tdes0_val & tdes0::LAST_SEGMENT, 0);
+
+        let tdes1_val = desc.raw_tdes1();
+        assert_eq!(tdes1_val & tdes1::BUFFER1_SIZE_MASK, 1600);
+        assert_eq!(
+            (tdes1_val & tdes1::BUFFER2_SIZE_MASK) >> tdes1::BUFFER2_SIZE_SHIFT,
+            200
+        );
+    }
+
+    #[test]
+    fn tx_descriptor_reset_keeps_ring_layout() {
+        let desc = TxDescriptor::new();
+        let buf = [0u8; 16];
+        desc.setup_ring(buf.as_ptr(), buf.as_ptr(), true);
+        desc.prepare_split(100, 0, true, true);
+        desc.set_owned();
+
+        desc.reset();
+
+        assert_eq!(desc.raw_tdes0(), tdes0::TX_END_OF_RING);
+        assert_eq!(desc.raw_tdes1(), 0);
+    }
 }
@@ -1,18 +1,23 @@
 //! DMA engine managing TX/RX descriptor rings and buffers.
 
-use super::descriptor::{RxDescriptor, TxDescriptor};
+use core::sync::atomic::{AtomicU32, Ordering, fence};
+
+use super::descriptor::bits::rdes0;
+use super::descriptor::{RxDescriptor, RxStatus, TxDescriptor};
 use super::ring::DescriptorRing;
+use crate::driver::capabilities::BufferLocation;
 use crate::driver::error::{DmaError, IoError, Result};
+use crate::driver::memory::MemoryReport;
+use crate::driver::stats::TxDiagnostics;
+use crate::internal::constants::CRC_SIZE;
 use crate::internal::register::dma::DmaRegs;
 
 #[cfg(feature = "log")]
 use log::warn;
 
 #[cfg(feature = "log")]
-fn log_rx_error(desc: &RxDescriptor) {
-    use crate::internal::dma::descriptor::bits::rdes0;
-
-    let raw = desc.raw_rdes0();
+fn log_rx_error(status: RxStatus) {
+    let raw = status.raw();
     let error_flags = raw & (rdes0::ALL_ERRORS | rdes0::SA_FILTER_FAIL | rdes0::DA_FILTER_FAIL);
     let sa_fail = (raw & rdes0::SA_FILTER_FAIL) != 0;
     let da_fail = (raw & rdes0::DA_FILTER_FAIL) != 0;
@@ -22,95 +27,624 @@ fn log_rx_error(desc: &RxDescriptor) {
         raw, error_flags, sa_fail, da_fail
     );
 }
+
+/// Slot `offset` positions after `base` in a ring of `len` slots.
+///
+/// An empty ring maps to slot 0, which the checked descriptor accesses then
+/// reject, rather than dividing by zero: the TX/RX paths must not panic.
+#[inline(always)]
+fn ring_slot(base: usize, offset: usize, len: usize) -> usize {
+    base.wrapping_add(offset).checked_rem(len).unwrap_or(0)
+}
+
+/// Bytes `start..end` of `head` followed by `body`, as the part that falls
+/// in `head` and the part that falls in `body`.
+#[inline(always)]
+fn split_chunk<'a>(
+    head: &'a [u8],
+    body: &'a [u8],
+    start: usize,
+    end: usize,
+) -> (&'a [u8], &'a [u8]) {
+    let split = head.len();
+    (
+        head.get(start.min(split)..end.min(split))
+            .unwrap_or_default(),
+        body.get(start.saturating_sub(split)..end.saturating_sub(split))
+            .unwrap_or_default(),
+    )
+}
+
+/// Copy a received frame out of a DMA buffer.
+///
+/// When both slices start on a 4-byte boundary the bulk of the frame is moved
+/// one word at a time, with any trailing bytes copied individually. Otherwise
+/// this falls back to a plain byte copy. Copies as many bytes as the
+/// shorter slice holds.
+#[inline]
+fn copy_rx_data(dst: &mut [u8], src: &[u8]) {
+    // The word loop below relies on both slices holding `len` bytes
+    let len = dst.len().min(src.len());
+    let (Some(dst), Some(src)) = (dst.get_mut(..len), src.get(..len)) else {
+        return;
+    };
+    let words = len / 4;
+    let aligned = ((dst.as_ptr() as usize) | (src.as_ptr() as usize)) & 0x3 == 0;
+
+    if !aligned || words == 0 {
+        dst.copy_from_slice(src);
+        return;
+    }
+
+    let dst_words = dst.as_mut_ptr().cast::<u32>();
+    let src_words = src.as_ptr().cast::<u32>();
+    for i in 0..words {
+        // SAFETY: Both pointers are 4-byte aligned and `i < len / 4`, so every
+        // word lies inside its slice. The source is read volatile because the
+        // buffer was last written by the DMA engine.
+        unsafe { dst_words.add(i).write(src_words.add(i).read_volatile()) };
+    }
+
+    let tail = words * 4;
+    dst[tail..len].copy_from_slice(&src[tail..len]);
+}
+
+/// Smallest supported `BUF_SIZE` (one minimum-size Ethernet frame)
+pub const MIN_BUF_SIZE: usize = 64;
+
+/// Largest buffer size the 13-bit descriptor size field can hold while
+/// staying a multiple of 4
+pub const MAX_DESC_BUF_SIZE: usize = 0x1FFC;
+
+/// Class of the frame an RX slot belongs to, for the unicast reserve
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RxClass {
+    /// Not yet classified by [`DmaEngine::tally_rx`]
+    Untallied,
+    /// Accepted by the reserve
+    Reserved,
+    /// Outside the reserve
+    Other,
+    /// Outside the reserve and shed; dropped once it reaches the ring head
+    Shed,
+}
+
+/// Descriptors held by the tallied RX frames, per [`RxClass`]
+///
+/// Tallied frames always form a prefix of the waiting frames, so the sum
+/// is also the offset of the first untallied descriptor from the ring head.
+#[derive(Debug, Clone, Copy, Default)]
+struct RxTally {
+    reserved: usize,
+    other: usize,
+    shed: usize,
+}
+
+impl RxTally {
+    /// Descriptors tallied in total
+    #[inline(always)]
+    const fn total(&self) -> usize {
+        self.reserved + self.other + self.shed
+    }
+
+    /// Counter for `class`, if it has one
+    #[inline(always)]
+    fn count_mut(&mut self, class: RxClass) -> Option<&mut usize> {
+        match class {
+            RxClass::Untallied => None,
+            RxClass::Reserved => Some(&mut self.reserved),
+            RxClass::Other => Some(&mut self.other),
+            RxClass::Shed => Some(&mut self.shed),
+        }
+    }
+}
+
+/// Word-aligned DMA data buffer.
+///
+/// The alignment keeps every buffer in an array on a 4-byte boundary even
+/// when `N` is not a multiple of 4; the padding is never handed to the DMA.
+#[repr(C, align(4))]
+struct DmaBuffer<const N: usize>([u8; N]);
+
+impl<const N: usize> core::ops::Deref for DmaBuffer<N> {
+    type Target = [u8; N];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<const N: usize> core::ops::DerefMut for DmaBuffer<N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
 /// DMA Engine with statically allocated buffers.
 ///
 /// # Type Parameters
 /// * `RX_BUFS` - Number of receive buffers/descriptors
 /// * `TX_BUFS` - Number of transmit buffers/descriptors
 /// * `BUF_SIZE` - Size of each buffer in bytes (>= 1600 for standard frames)
+///
+/// `BUF_SIZE` must be at least [`MIN_BUF_SIZE`] (checked at compile time).
+/// The size programmed into descriptors is [`Self::DESC_BUF_SIZE`]: rounded
+/// down to a multiple of 4 and capped at [`MAX_DESC_BUF_SIZE`].
+///
+/// `RX_BUFS` may be 0 for TX-only use: no RX ring is programmed and
+/// [`receive`](Self::receive) fails with `DmaError::NoRxBuffers`.
+/// `TX_BUFS` must be at least 1 (checked at compile time).
 pub struct DmaEngine<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize> {
     /// RX descriptor ring
     rx_ring: DescriptorRing<RxDescriptor, RX_BUFS>,
+    /// RDES0 of each RX slot as last seen released by the DMA
+    ///
+    /// Entries with `rdes0::OWN` set are unknown and must be read from the
+    /// descriptor; see [`rx_status`](Self::rx_status). Atomic because the
+    /// cache is refreshed through `&self`, which either core may hold.
+    rx_meta: [AtomicU32; RX_BUFS],
+    /// Class of each RX slot's frame for the unicast reserve
+    rx_class: [RxClass; RX_BUFS],
+    /// Per-class descriptor counts of the slots in `rx_class`
+    rx_tally: RxTally,
     /// TX descriptor ring
     tx_ring: DescriptorRing<TxDescriptor, TX_BUFS>,
     /// RX data buffers
-    rx_buffers: [[u8; BUF_SIZE]; RX_BUFS],
+    rx_buffers: [DmaBuffer<BUF_SIZE>; RX_BUFS],
     /// TX data buffers
-    tx_buffers: [[u8; BUF_SIZE]; TX_BUFS],
+    tx_buffers: [DmaBuffer<BUF_SIZE>; TX_BUFS],
     /// TX control flags to apply to frames
     tx_ctrl_flags: u32,
+    /// Frames per TX completion interrupt (1 = every frame)
+    tx_ic_interval: u16,
+    /// Frames submitted since the last one that requested an interrupt
+    tx_frames_since_ic: u16,
+    /// TX descriptors held back for `transmit_reserved()`
+    tx_reserved: usize,
+    /// Oldest submitted TX descriptor whose status is not yet collected
+    tx_dirty: usize,
+    /// Submitted TX descriptors from `tx_dirty` on not yet collected
+    tx_uncollected: usize,
+    /// Ring mode with two buffers per descriptor (buffer1 + buffer2)
+    dual_buffer: bool,
+    /// RX buffers linked into the ring (at most `RX_BUFS`)
+    rx_active: usize,
+    /// TX buffers linked into the ring (at most `TX_BUFS`)
+    tx_active: usize,
+    /// RX poll demands are held back until `end_rx_batch()`
+    rx_poll_deferred: bool,
+    /// An RX poll demand was held back
+    rx_poll_pending: bool,
     /// Whether the engine has been initialized
     initialized: bool,
+    /// Whether `(addr, len)` is memory the DMA can read, for borrowed TX
+    dma_capable: fn(usize, usize) -> bool,
 }
 
 impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
     DmaEngine<RX_BUFS, TX_BUFS, BUF_SIZE>
 {
+    /// Bytes per buffer as programmed into the descriptor size fields.
+    pub const DESC_BUF_SIZE: usize = if BUF_SIZE & !3 > MAX_DESC_BUF_SIZE {
+        MAX_DESC_BUF_SIZE
+    } else {
+        BUF_SIZE & !3
+    };
+
+    /// Whether the engine has an RX ring (`RX_BUFS > 0`).
+    pub const HAS_RX: bool = RX_BUFS > 0;
+
+    const BUF_SIZE_CHECK: () = assert!(
+        BUF_SIZE >= MIN_BUF_SIZE,
+        "BUF_SIZE must be at least MIN_BUF_SIZE (64) bytes"
+    );
+
+    const TX_BUFS_CHECK: () = assert!(TX_BUFS > 0, "TX_BUFS must be at least 1");
+
     /// Create a new DMA engine with zeroed buffers. Const-compatible.
     #[must_use]
     pub const fn new() -> Self {
+        let () = Self::BUF_SIZE_CHECK;
+        let () = Self::TX_BUFS_CHECK;
         Self {
             rx_ring: DescriptorRing {
                 descriptors: [const { RxDescriptor::new() }; RX_BUFS],
                 current: 0,
             },
+            rx_meta: [const { AtomicU32::new(rdes0::OWN) }; RX_BUFS],
+            rx_class: [RxClass::Untallied; RX_BUFS],
+            rx_tally: RxTally {
+                reserved: 0,
+                other: 0,
+                shed: 0,
+            },
             tx_ring: DescriptorRing {
                 descriptors: [const { TxDescriptor::new() }; TX_BUFS],
                 current: 0,
             },
-            rx_buffers: [[0u8; BUF_SIZE]; RX_BUFS],
-            tx_buffers: [[0u8; BUF_SIZE]; TX_BUFS],
+            rx_buffers: [const { DmaBuffer([0u8; BUF_SIZE]) }; RX_BUFS],
+            tx_buffers: [const { DmaBuffer([0u8; BUF_SIZE]) }; TX_BUFS],
             tx_ctrl_flags: 0,
+            tx_ic_interval: 1,
+            tx_frames_since_ic: 0,
+            tx_reserved: 0,
+            tx_dirty: 0,
+            tx_uncollected: 0,
+            dual_buffer: false,
+            rx_active: RX_BUFS,
+            tx_active: TX_BUFS,
+            rx_poll_deferred: false,
+            rx_poll_pending: false,
             initialized: false,
+            dma_capable: BufferLocation::dma_capable,
         }
     }
 
     /// Total memory usage in bytes.
     #[must_use]
     pub const fn memory_usage() -> usize {
-        let rx_desc_size = RX_BUFS * RxDescriptor::SIZE;
-        let tx_desc_size = TX_BUFS * TxDescriptor::SIZE;
-        let rx_buf_size = RX_BUFS * BUF_SIZE;
-        let tx_buf_size = TX_BUFS * BUF_SIZE;
-        rx_desc_size + tx_desc_size + rx_buf_size + tx_buf_size
+        Self::memory_report().total()
+    }
+
+    /// Memory usage broken down by ring and buffer pool.
+    #[must_use]
+    pub const fn memory_report() -> MemoryReport {
+        let buf = core::mem::size_of::<DmaBuffer<BUF_SIZE>>();
+        MemoryReport {
+            rx_descriptors: RX_BUFS * RxDescriptor::SIZE,
+            tx_descriptors: TX_BUFS * TxDescriptor::SIZE,
+            rx_buffers: RX_BUFS * buf,
+            tx_buffers: TX_BUFS * buf,
+        }
+    }
+
+    /// Descriptor skip length (DSL) for ring mode, in 32-bit words.
+    ///
+    /// Alternate (8-word) descriptors are laid out back to back, so this is
+    /// only non-zero when descriptors are padded for cache alignment.
+    #[must_use]
+    pub const fn ring_skip_words() -> u32 {
+        ((TxDescriptor::SIZE - 32) / 4) as u32
+    }
+
+    /// Select dual-buffer ring mode. Must be called before `init()`.
+    ///
+    /// Each descriptor then owns two consecutive buffers, so a slot holds
+    /// `2 * DESC_BUF_SIZE` bytes and only half of the descriptors are used.
+    /// Returns `false` (and stays in chained mode) if either ring has fewer
+    /// than two buffers; a missing RX ring (`RX_BUFS = 0`) does not count.
+    pub fn set_dual_buffer(&mut self, enabled: bool) -> bool {
+        self.dual_buffer = enabled && (!Self::HAS_RX || self.rx_active >= 2) && self.tx_active >= 2;
+        self.dual_buffer == enabled
+    }
+
+    /// Limit the rings to the first `rx` RX and `tx` TX buffers.
+    ///
+    /// Takes effect at the next `init()`. Returns `false` (and changes
+    /// nothing) if a count is zero, above the const-generic size, or below
+    /// two in dual-buffer mode. Without an RX ring `rx` must be 0.
+    pub fn set_active_buffers(&mut self, rx: usize, tx: usize) -> bool {
+        if !Self::ring_sizes_valid(rx, tx, self.dual_buffer) {
+            return false;
+        }
+        self.rx_active = rx;
+        self.tx_active = tx;
+        true
+    }
+
+    /// Whether `rx`/`tx` buffers form valid rings for this geometry.
+    #[must_use]
+    pub const fn ring_sizes_valid(rx: usize, tx: usize, dual_buffer: bool) -> bool {
+        let min = if dual_buffer { 2 } else { 1 };
+        let rx_ok = if Self::HAS_RX {
+            rx >= min && rx <= RX_BUFS
+        } else {
+            rx == 0
+        };
+        rx_ok && tx >= min && tx <= TX_BUFS
+    }
+
+    /// RX buffers in use
+    #[inline(always)]
+    pub fn rx_active_buffers(&self) -> usize {
+        self.rx_active
+    }
+
+    /// TX buffers in use
+    #[inline(always)]
+    pub fn tx_active_buffers(&self) -> usize {
+        self.tx_active
+    }
+
+    /// Whether dual-buffer ring mode is active
+    #[inline(always)]
+    pub fn is_dual_buffer(&self) -> bool {
+        self.dual_buffer
+    }
+
+    /// Number of RX descriptors in use
+    #[inline(always)]
+    pub fn rx_slots(&self) -> usize {
+        if self.dual_buffer {
+            self.rx_active / 2
+        } else {
+            self.rx_active
+        }
+    }
+
+    /// Number of TX descriptors in use
+    #[inline(always)]
+    pub fn tx_slots(&self) -> usize {
+        if self.dual_buffer {
+            self.tx_active / 2
+        } else {
+            self.tx_active
+        }
+    }
+
+    /// Bytes one descriptor can carry
+    #[inline(always)]
+    pub fn slot_capacity(&self) -> usize {
+        if self.dual_buffer {
+            2 * Self::DESC_BUF_SIZE
+        } else {
+            Self::DESC_BUF_SIZE
+        }
     }
 
     /// Initialize descriptor chains and DMA registers.
     /// Must be called before any DMA operations.
     pub fn init(&mut self) {
+        if self.dual_buffer {
+            self.init_dual_buffer();
+        } else {
+            self.init_chained();
+        }
+
+        self.invalidate_rx_meta();
+        self.rx_ring.reset();
+        self.tx_ring.reset();
+        self.tx_dirty = 0;
+        self.tx_uncollected = 0;
+        self.program_ring_bases();
+        self.initialized = true;
+    }
+
+    /// Point the DMA at the rings; the RX list is left unset without RX
+    /// buffers.
+    fn program_ring_bases(&self) {
+        if Self::HAS_RX {
+            DmaRegs::set_rx_desc_list_addr(self.rx_ring.base_addr_u32());
+        }
+        DmaRegs::set_tx_desc_list_addr(self.tx_ring.base_addr_u32());
+    }
+
+    /// Chained mode: one buffer per descriptor, buffer2 points to the next.
+    ///
+    /// The last active descriptor links back to the first; inactive ones are
+    /// left zeroed and never reached by the DMA.
+    fn init_chained(&mut self) {
+        let rx_slots = self.rx_slots();
         for i in 0..RX_BUFS {
-            let next_idx = (i + 1) % RX_BUFS;
-            let buffer_ptr = self.rx_buffers[i].as_mut_ptr();
-            let next_desc = &self.rx_ring.descriptors[next_idx] as *const RxDescriptor;
-            self.rx_ring.descriptors[i].setup_chained(buffer_ptr, BUF_SIZE, next_desc);
+            if i < rx_slots {
+                let next_idx = (i + 1) % rx_slots;
+                let buffer_ptr = self.rx_buffers[i].as_mut_ptr();
+                let next_desc = &self.rx_ring.descriptors[next_idx] as *const RxDescriptor;
+                self.rx_ring.descriptors[i].setup_chained(
+                    buffer_ptr,
+                    Self::DESC_BUF_SIZE,
+                    next_desc,
+                );
+            } else {
+                self.rx_ring.descriptors[i].clear();
+            }
         }
 
+        let tx_slots = self.tx_slots();
         for i in 0..TX_BUFS {
-            let next_idx = (i + 1) % TX_BUFS;
-            let buffer_ptr = self.tx_buffers[i].as_ptr();
-            let next_desc = &self.tx_ring.descriptors[next_idx] as *const TxDescriptor;
-            self.tx_ring.descriptors[i].setup_chained(buffer_ptr, next_desc);
+            if i < tx_slots {
+                let next_idx = (i + 1) % tx_slots;
+                let buffer_ptr = self.tx_buffers[i].as_ptr();
+                let next_desc = &self.tx_ring.descriptors[next_idx] as *const TxDescriptor;
+                self.tx_ring.descriptors[i].setup_chained(buffer_ptr, next_desc);
+            } else {
+                self.tx_ring.descriptors[i].clear();
+            }
         }
+    }
 
-        self.rx_ring.reset();
-        self.tx_ring.reset();
-        DmaRegs::set_rx_desc_list_addr(self.rx_ring.base_addr_u32());
-        DmaRegs::set_tx_desc_list_addr(self.tx_ring.base_addr_u32());
-        self.initialized = true;
+    /// Ring mode: descriptor `k` uses buffers `2k` and `2k + 1`.
+    ///
+    /// Descriptors past the last slot are left zeroed; the end-of-ring bit
+    /// on the last slot keeps the DMA from reaching them.
+    fn init_dual_buffer(&mut self) {
+        let rx_slots = self.rx_slots();
+        for k in 0..RX_BUFS {
+            let desc = &self.rx_ring.descriptors[k];
+            if k < rx_slots {
+                let buf1 = self.rx_buffers[2 * k].as_mut_ptr();
+                let buf2 = self.rx_buffers[2 * k + 1].as_mut_ptr();
+                desc.setup_ring(buf1, buf2, Self::DESC_BUF_SIZE, k == rx_slots - 1);
+            } else {
+                desc.clear();
+            }
+        }
+
+        let tx_slots = self.tx_slots();
+        for k in 0..TX_BUFS {
+            let desc = &self.tx_ring.descriptors[k];
+            if k < tx_slots {
+                let buf1 = self.tx_buffers[2 * k].as_ptr();
+                let buf2 = self.tx_buffers[2 * k + 1].as_ptr();
+                desc.setup_ring(buf1, buf2, k == tx_slots - 1);
+            } else {
+                desc.clear();
+            }
+        }
+    }
+
+    iram! {
+        /// Copy `dst.len()` bytes from the start of RX slot `slot`.
+        fn copy_from_rx_slot(&self, slot: usize, dst: &mut [u8]) {
+            debug_check!(slot < self.rx_slots(), "RX slot {slot} out of range");
+            debug_check!(
+                !self.rx_status(slot).is_owned(),
+                "RX slot {slot} read while owned by DMA"
+            );
+            debug_check!(
+                dst.len() <= self.slot_capacity(),
+                "RX copy of {} bytes exceeds slot capacity",
+                dst.len()
+            );
+            if self.dual_buffer {
+                let first = dst.len().min(Self::DESC_BUF_SIZE);
+                let (head, tail) = dst.split_at_mut(first);
+                self.copy_from_rx_buffer(2 * slot, head);
+                if !tail.is_empty() {
+                    self.copy_from_rx_buffer(2 * slot + 1, tail);
+                }
+            } else {
+                self.copy_from_rx_buffer(slot, dst);
+            }
+        }
+    }
+
+    /// Copy the start of RX buffer `index` into `dst`; out-of-range
+    /// requests copy nothing.
+    #[inline(always)]
+    fn copy_from_rx_buffer(&self, index: usize, dst: &mut [u8]) {
+        if let Some(src) = self.rx_buffers.get(index).and_then(|b| b.get(..dst.len())) {
+            copy_rx_data(dst, src);
+        }
+    }
+
+    /// Copy `src` into TX slot `slot`. Returns the buffer1 and buffer2 lengths.
+    fn fill_tx_slot(&mut self, slot: usize, src: &[u8]) -> (usize, usize) {
+        self.fill_tx_slot_split(slot, &[], src)
+    }
+
+    /// Copy `head` followed by `body` into TX slot `slot`. Returns the
+    /// buffer1 and buffer2 lengths.
+    fn fill_tx_slot_split(&mut self, slot: usize, head: &[u8], body: &[u8]) -> (usize, usize) {
+        debug_check!(slot < self.tx_slots(), "TX slot {slot} out of range");
+        debug_check!(
+            !self.tx_ring.descriptors[slot].is_owned(),
+            "TX slot {slot} written while owned by DMA"
+        );
+        debug_check!(
+            head.len() + body.len() <= self.slot_capacity(),
+            "TX chunk of {} bytes exceeds slot capacity",
+            head.len() + body.len()
+        );
+        let copied = self.write_tx_slot(slot, 0, head) + self.write_tx_slot(slot, head.len(), body);
+        if self.dual_buffer {
+            let len1 = copied.min(Self::DESC_BUF_SIZE);
+            (len1, copied - len1)
+        } else {
+            (copied, 0)
+        }
+    }
+
+    iram! {
+        /// Copy `src` to position `pos` of TX slot `slot`, spanning both
+        /// buffers in ring mode. Returns the bytes copied.
+        fn write_tx_slot(&mut self, slot: usize, pos: usize, src: &[u8]) -> usize {
+            if !self.dual_buffer {
+                return self.copy_to_tx_buffer(slot, pos, src);
+            }
+            let split = Self::DESC_BUF_SIZE.saturating_sub(pos).min(src.len());
+            let Some((first, second)) = src.split_at_checked(split) else {
+                return 0;
+            };
+            self.copy_to_tx_buffer(2 * slot, pos, first)
+                + self.copy_to_tx_buffer(
+                    2 * slot + 1,
+                    pos.saturating_sub(Self::DESC_BUF_SIZE),
+                    second,
+                )
+        }
+    }
+
+    /// Copy `src` to TX buffer `index` at `offset`. Returns the bytes
+    /// copied: `src.len()`, or 0 if it does not fit.
+    #[inline(always)]
+    fn copy_to_tx_buffer(&mut self, index: usize, offset: usize, src: &[u8]) -> usize {
+        match self
+            .tx_buffers
+            .get_mut(index)
+            .and_then(|b| b.get_mut(offset..))
+            .and_then(|b| b.get_mut(..src.len()))
+        {
+            Some(dst) => {
+                dst.copy_from_slice(src);
+                src.len()
+            }
+            None => 0,
+        }
     }
 
     /// Reset to initial state. Caller should stop DMA first.
     pub fn reset(&mut self) {
-        for i in 0..RX_BUFS {
-            self.rx_ring.descriptors[i].recycle();
+        for i in 0..self.rx_slots() {
+            self.recycle_rx(i);
         }
-        for i in 0..TX_BUFS {
+        self.invalidate_rx_meta();
+        for i in 0..self.tx_slots() {
             self.tx_ring.descriptors[i].reset();
         }
         self.rx_ring.reset();
         self.tx_ring.reset();
-        DmaRegs::set_rx_desc_list_addr(self.rx_ring.base_addr_u32());
-        DmaRegs::set_tx_desc_list_addr(self.tx_ring.base_addr_u32());
+        self.tx_frames_since_ic = 0;
+        self.tx_dirty = 0;
+        self.tx_uncollected = 0;
+        self.rx_poll_deferred = false;
+        self.rx_poll_pending = false;
+        self.program_ring_bases();
+    }
+
+    /// Addresses of the RX and TX descriptors the DMA processes next
+    ///
+    /// The first DMA-owned descriptor from the current index: on RX that
+    /// skips the frames waiting for `receive()`, on TX it is the oldest frame
+    /// not yet sent. Without one, the current index, where the DMA continues
+    /// after the next poll demand.
+    pub fn next_dma_descriptors(&self) -> (u32, u32) {
+        let rx_slots = self.rx_slots();
+        let rx_current = self.rx_ring.current_index();
+        let rx = (0..rx_slots)
+            .map(|offset| (rx_current + offset) % rx_slots)
+            .find(|&idx| self.rx_status(idx).is_owned())
+            .unwrap_or(rx_current);
+
+        let tx_slots = self.tx_slots();
+        let tx_current = self.tx_ring.current_index();
+        let tx = (0..tx_slots)
+            .map(|offset| (tx_current + offset) % tx_slots)
+            .find(|&idx| self.tx_ring.descriptors[idx].is_owned())
+            .unwrap_or(tx_current);
+
+        (self.rx_ring.addr_u32(rx), self.tx_ring.addr_u32(tx))
+    }
+
+    /// Point the DMA at [`next_dma_descriptors`](Self::next_dma_descriptors)
+    ///
+    /// Used to resume after the list base registers were lost, without
+    /// resetting the rings. The RX list is left unset without RX buffers.
+    pub fn program_resume_bases(&self) {
+        let (rx, tx) = self.next_dma_descriptors();
+        if Self::HAS_RX {
+            DmaRegs::set_rx_desc_list_addr(rx);
+        }
+        DmaRegs::set_tx_desc_list_addr(tx);
+    }
+
+    /// True if `addr` is a descriptor of the active RX (`rx`) or TX ring
+    pub fn is_ring_descriptor(&self, addr: u32, rx: bool) -> bool {
+        if rx {
+            (0..self.rx_slots()).any(|idx| self.rx_ring.addr_u32(idx) == addr)
+        } else {
+            (0..self.tx_slots()).any(|idx| self.tx_ring.addr_u32(idx) == addr)
+        }
     }
 
     /// Check if the DMA engine has been initialized
@@ -130,12 +664,63 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
         self.tx_ctrl_flags
     }
 
-    /// Count available TX descriptors (not owned by DMA).
+    /// Set how many frames share one TX completion interrupt (0 is treated as 1).
+    pub fn set_tx_ic_interval(&mut self, interval: u16) {
+        self.tx_ic_interval = interval.max(1);
+        self.tx_frames_since_ic = 0;
+    }
+
+    /// Frames per TX completion interrupt
+    #[inline(always)]
+    pub fn tx_ic_interval(&self) -> u16 {
+        self.tx_ic_interval
+    }
+
+    /// Decide whether the next frame requests a completion interrupt.
+    ///
+    /// `force` marks the end of a burst; `ring_full` means no descriptors
+    /// remain after this frame, so a waiter needs the interrupt to resume.
+    fn tx_needs_ic(&mut self, force: bool, ring_full: bool) -> bool {
+        self.tx_frames_since_ic = self.tx_frames_since_ic.saturating_add(1);
+        let ic = force || ring_full || self.tx_frames_since_ic >= self.tx_ic_interval;
+        if ic {
+            self.tx_frames_since_ic = 0;
+        }
+        ic
+    }
+
+    /// Hold back `count` TX descriptors for [`transmit_reserved`](Self::transmit_reserved).
+    pub fn set_tx_reserved(&mut self, count: usize) {
+        self.tx_reserved = count;
+    }
+
+    /// TX descriptors held back for reserved frames.
+    ///
+    /// Capped so at least one descriptor is left for ordinary frames, even
+    /// after the ring was shrunk below the configured reserve.
+    pub fn tx_reserved(&self) -> usize {
+        self.tx_reserved.min(self.tx_slots() - 1)
+    }
+
+    /// Count TX descriptors available to ordinary frames.
+    ///
+    /// Excludes the [reserved](Self::tx_reserved) descriptors.
     pub fn tx_available(&self) -> usize {
+        self.tx_free().saturating_sub(self.tx_reserved())
+    }
+
+    /// Count free TX descriptors (not owned by DMA), reserved ones included.
+    pub fn tx_free(&self) -> usize {
+        let slots = self.tx_slots();
         let mut count = 0;
-        for i in 0..TX_BUFS {
-            let idx = (self.tx_ring.current + i) % TX_BUFS;
-            if !self.tx_ring.descriptors[idx].is_owned() {
+        for i in 0..slots {
+            let idx = ring_slot(self.tx_ring.current, i, slots);
+            if self
+                .tx_ring
+                .descriptors
+                .get(idx)
+                .is_some_and(|d| !d.is_owned())
+            {
                 count += 1;
             } else {
                 break;
@@ -146,286 +731,845 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
 
     /// Check if enough descriptors available for frame of given size.
     pub fn can_transmit(&self, len: usize) -> bool {
-        if len == 0 || len > BUF_SIZE * TX_BUFS {
+        let capacity = self.slot_capacity();
+        if len == 0 || len > capacity * self.tx_slots() {
             return false;
         }
-        let needed = len.div_ceil(BUF_SIZE);
+        let needed = len.div_ceil(capacity);
         self.tx_available() >= needed
     }
 
     /// Transmit a frame. Supports scatter-gather for large frames.
     pub fn transmit(&mut self, data: &[u8]) -> Result<usize> {
-        if data.is_empty() {
-            return Err(DmaError::InvalidLength.into());
-        }
+        self.transmit_with_ic(data, false)
+    }
 
-        let total_capacity = BUF_SIZE * TX_BUFS;
-        if data.len() > total_capacity {
-            return Err(DmaError::FrameTooLarge.into());
-        }
+    /// Transmit a frame, forcing a completion interrupt if `force_ic` is set.
+    pub fn transmit_with_ic(&mut self, data: &[u8], force_ic: bool) -> Result<usize> {
+        self.queue_tx(&[], data, force_ic, self.tx_reserved())
+    }
 
-        let desc_count = data.len().div_ceil(BUF_SIZE);
-        if self.tx_available() < desc_count {
-            return Err(DmaError::NoDescriptorsAvailable.into());
-        }
+    /// Transmit a high-priority frame, which may use the reserved descriptors.
+    ///
+    /// The frame still queues behind frames already in the ring; the reserve
+    /// only guarantees it is accepted. Always requests a completion interrupt.
+    pub fn transmit_reserved(&mut self, data: &[u8]) -> Result<usize> {
+        self.queue_tx(&[], data, true, 0)
+    }
 
-        let mut remaining = data.len();
-        let mut offset = 0usize;
+    /// Transmit `head` followed by `body` as one frame.
+    ///
+    /// Lets a caller replace the header without copying the payload first.
+    /// `reserved` has the meaning of [`transmit_reserved`](Self::transmit_reserved).
+    pub fn transmit_split(
+        &mut self,
+        head: &[u8],
+        body: &[u8],
+        force_ic: bool,
+        reserved: bool,
+    ) -> Result<usize> {
+        let keep_free = if reserved { 0 } else { self.tx_reserved() };
+        self.queue_tx(head, body, force_ic || reserved, keep_free)
+    }
 
-        // Prepare descriptors
-        for i in 0..desc_count {
-            let idx = (self.tx_ring.current + i) % TX_BUFS;
-            let desc = &self.tx_ring.descriptors[idx];
+    iram! {
+        /// Queue `head` followed by `body` as one frame if it fits while
+        /// leaving `keep_free` descriptors unused.
+        fn queue_tx(
+            &mut self,
+            head: &[u8],
+            body: &[u8],
+            force_ic: bool,
+            keep_free: usize,
+        ) -> Result<usize> {
+            let len = head.len() + body.len();
+            if len == 0 {
+                return Err(DmaError::InvalidLength.into());
+            }
 
-            if desc.is_owned() {
-                return Err(DmaError::DescriptorBusy.into());
+            let slots = self.tx_slots();
+            let capacity = self.slot_capacity();
+            if len > capacity * slots {
+                return Err(DmaError::FrameTooLarge.into());
+            }
+
+            let desc_count = len.div_ceil(capacity);
+            let free = self.tx_free();
+            if free < desc_count + keep_free {
+                return Err(DmaError::NoDescriptorsAvailable.into());
+            }
+            // Ordinary senders stall once only reserved descriptors are left
+            let ic = self.tx_needs_ic(force_ic, free - desc_count <= self.tx_reserved());
+
+            // Prepare descriptors
+            for i in 0..desc_count {
+                let start = i * capacity;
+                let end = len.min(start + capacity);
+                let (chunk_head, chunk_body) = split_chunk(head, body, start, end);
+                let idx = ring_slot(self.tx_ring.current, i, slots);
+                if self
+                    .tx_ring
+                    .descriptors
+                    .get(idx)
+                    .is_none_or(TxDescriptor::is_owned)
+                {
+                    return Err(DmaError::DescriptorBusy.into());
+                }
+
+                let (len1, len2) = self.fill_tx_slot_split(idx, chunk_head, chunk_body);
+                debug_check!(len1 + len2 == end - start, "TX buffer split lost bytes");
+                debug_check!(
+                    len1 <= Self::DESC_BUF_SIZE && len2 <= Self::DESC_BUF_SIZE,
+                    "TX buffer length exceeds descriptor field"
+                );
+                let (Some(desc), Some(buffer)) =
+                    (self.tx_ring.descriptors.get(idx), self.tx_slot_buffer(idx))
+                else {
+                    return Err(DmaError::DescriptorBusy.into());
+                };
+                // Undo a previous transmit_borrowed in this slot
+                desc.set_buffer1(buffer);
+                let last = i + 1 == desc_count;
+                if self.dual_buffer {
+                    desc.prepare_split(len1, len2, i == 0, last);
+                } else {
+                    desc.prepare(len1, i == 0, last);
+                }
+                if last && !ic {
+                    desc.set_interrupt_on_complete(false);
+                }
             }
 
-            let chunk_size = core::cmp::min(remaining, BUF_SIZE);
-            self.tx_buffers[idx][..chunk_size].copy_from_slice(&data[offset..offset + chunk_size]);
-            desc.prepare(chunk_size, i == 0, i == desc_count - 1);
+            // Give to DMA in reverse order (prevents race)
+            for i in (0..desc_count).rev() {
+                let idx = ring_slot(self.tx_ring.current, i, slots);
+                if let Some(desc) = self.tx_ring.descriptors.get(idx) {
+                    desc.set_owned();
+                }
+            }
 
-            remaining -= chunk_size;
-            offset += chunk_size;
+            self.advance_tx(desc_count);
+            debug_check!(self.tx_ring.current < slots, "TX ring index out of range");
+            DmaRegs::tx_poll_demand();
+            Ok(len)
         }
+    }
 
-        // Give to DMA in reverse order (prevents race)
-        for i in (0..desc_count).rev() {
-            let idx = (self.tx_ring.current + i) % TX_BUFS;
-            self.tx_ring.descriptors[idx].set_owned();
+    /// Queue `data` as one frame sent straight from caller memory.
+    ///
+    /// The slot's descriptor points at `data` instead of its ring buffer
+    /// until the next frame queued in that slot points it back. `data` must
+    /// be 4-byte aligned in internal SRAM. Returns the slot used, for
+    /// [`tx_borrowed_pending`](Self::tx_borrowed_pending).
+    pub fn transmit_borrowed(&mut self, data: &'static [u8], keep_free: usize) -> Result<usize> {
+        let len = data.len();
+        if len == 0 {
+            return Err(DmaError::InvalidLength.into());
+        }
+        if len > MAX_DESC_BUF_SIZE {
+            return Err(DmaError::FrameTooLarge.into());
+        }
+        let addr = data.as_ptr() as usize;
+        if !addr.is_multiple_of(4) || !(self.dma_capable)(addr, len) {
+            return Err(DmaError::BufferNotDmaCapable.into());
         }
 
-        self.tx_ring.advance_by(desc_count);
+        let free = self.tx_free();
+        if free < 1 + keep_free {
+            return Err(DmaError::NoDescriptorsAvailable.into());
+        }
+        let ic = self.tx_needs_ic(false, free - 1 <= self.tx_reserved());
+        let idx = self.tx_ring.current;
+        let Some(desc) = self
+            .tx_ring
+            .descriptors
+            .get(idx)
+            .filter(|desc| !desc.is_owned())
+        else {
+            return Err(DmaError::DescriptorBusy.into());
+        };
+        desc.set_buffer1(data.as_ptr());
+        self.submit_single(idx, len, ic);
+        Ok(idx)
+    }
+
+    /// Queue a `len`-byte frame that `fill` writes straight into the next
+    /// TX slot's first buffer.
+    ///
+    /// Returns `None`, without calling `fill`, if the frame does not fit
+    /// one buffer or no descriptor is free beyond `keep_free`.
+    pub fn transmit_in_place<R>(
+        &mut self,
+        len: usize,
+        keep_free: usize,
+        fill: impl FnOnce(&mut [u8]) -> R,
+    ) -> Option<R> {
+        if len == 0 || len > Self::DESC_BUF_SIZE {
+            return None;
+        }
+        let free = self.tx_free();
+        if free < 1 + keep_free {
+            return None;
+        }
+        let ic = self.tx_needs_ic(false, free - 1 <= self.tx_reserved());
+        let idx = self.tx_ring.current;
+        let index = if self.dual_buffer { 2 * idx } else { idx };
+        let buffer = self.tx_buffers.get_mut(index)?.get_mut(..len)?;
+        let result = fill(buffer);
+
+        let desc = self.tx_ring.descriptors.get(idx)?;
+        debug_check!(!desc.is_owned(), "TX slot {idx} written while owned by DMA");
+        desc.set_buffer1(self.tx_slot_buffer(idx)?);
+        self.submit_single(idx, len, ic);
+        Some(result)
+    }
+
+    /// Hand slot `idx`, already pointing at its frame, to the DMA as a
+    /// one-descriptor frame of `len` bytes.
+    fn submit_single(&mut self, idx: usize, len: usize, ic: bool) {
+        let Some(desc) = self.tx_ring.descriptors.get(idx) else {
+            return;
+        };
+        if self.dual_buffer {
+            desc.prepare_split(len, 0, true, true);
+        } else {
+            desc.prepare(len, true, true);
+        }
+        if !ic {
+            desc.set_interrupt_on_complete(false);
+        }
+        desc.set_owned();
+
+        self.advance_tx(1);
         DmaRegs::tx_poll_demand();
-        Ok(data.len())
+    }
+
+    /// True while the DMA still owns slot `slot` pointing at `addr`, i.e.
+    /// a borrowed frame at `addr` has not been sent yet.
+    pub fn tx_borrowed_pending(&self, slot: usize, addr: u32) -> bool {
+        self.tx_ring
+            .descriptors
+            .get(slot)
+            .is_some_and(|desc| desc.is_owned() && desc.buffer_addr() == addr)
+    }
+
+    /// First ring buffer of TX slot `slot`, `None` if out of range.
+    fn tx_slot_buffer(&self, slot: usize) -> Option<*const u8> {
+        let index = if self.dual_buffer { 2 * slot } else { slot };
+        self.tx_buffers.get(index).map(|b| b.as_ptr())
     }
 
     /// Check if previous transmission completed.
     pub fn tx_complete(&self) -> bool {
         let prev_idx = if self.tx_ring.current == 0 {
-            TX_BUFS - 1
+            self.tx_slots() - 1
         } else {
             self.tx_ring.current - 1
         };
         !self.tx_ring.descriptors[prev_idx].is_owned()
     }
 
-    /// Reclaim completed TX descriptors. Returns (count, error_flags).
-    pub fn tx_reclaim(&mut self) -> (usize, u32) {
-        let mut reclaimed = 0;
-        let mut errors = 0u32;
+    iram! {
+        /// Reclaim completed TX descriptors. Returns (count, error_flags).
+        pub fn tx_reclaim(&mut self) -> (usize, u32) {
+            let mut reclaimed = 0;
+            let mut errors = 0u32;
+
+            for desc in self.tx_ring.iter().take(self.tx_slots()) {
+                if !desc.is_owned() {
+                    if desc.has_error() {
+                        errors |= desc.error_flags();
+                    }
+                    reclaimed += 1;
+                }
+            }
 
-        for desc in self.tx_ring.iter() {
-            if !desc.is_owned() {
-                if desc.has_error() {
-                    errors |= desc.error_flags();
+            (reclaimed, errors)
+        }
+    }
+
+    /// Record and clear the error status of TX frames completed since the
+    /// last call.
+    ///
+    /// The DMA writes a frame's status back into its last descriptor and
+    /// releases descriptors in ring order, so this walks from the oldest
+    /// uncollected one to the first the DMA still owns; each descriptor is
+    /// looked at once. Clearing the status keeps it out of
+    /// [`tx_reclaim`](Self::tx_reclaim) later on.
+    pub fn collect_tx_errors(&mut self, diag: &mut TxDiagnostics) {
+        let slots = self.tx_slots();
+        while self.tx_uncollected > 0 {
+            let Some(desc) = self.tx_ring.descriptors.get(self.tx_dirty) else {
+                break;
+            };
+            if desc.is_owned() {
+                break;
+            }
+            if desc.is_last() && desc.has_error() {
+                diag.record(desc.error_flags());
+                desc.clear_errors();
+            }
+            self.tx_dirty = ring_slot(self.tx_dirty, 1, slots);
+            self.tx_uncollected -= 1;
+        }
+    }
+
+    /// Note `count` descriptors handed to the DMA at the ring head and move
+    /// the head past them
+    #[inline(always)]
+    fn advance_tx(&mut self, count: usize) {
+        let slots = self.tx_slots();
+        self.tx_ring.advance_within(count, slots);
+        self.tx_uncollected += count;
+        if self.tx_uncollected > slots {
+            // Slots were reused before their status was collected; the
+            // oldest still in flight is the one after the new head
+            self.tx_uncollected = slots;
+            self.tx_dirty = self.tx_ring.current;
+        }
+    }
+
+    /// TDES0 of TX slot `idx`, or `None` while the DMA owns it.
+    #[cfg(feature = "frame-trace")]
+    pub fn tx_released_status(&self, idx: usize) -> Option<u32> {
+        let desc = self.tx_ring.descriptors.get(idx)?;
+        (!desc.is_owned()).then(|| desc.raw_tdes0())
+    }
+
+    iram! {
+        /// RDES0 of RX slot `idx`, from the metadata cache if possible.
+        ///
+        /// The DMA does not write a descriptor it has released, so once a read
+        /// sees OWN clear the word is stable until [`recycle_rx`](Self::recycle_rx)
+        /// returns the slot. Polling, peeking and receiving the same frame
+        /// therefore costs one volatile read per descriptor.
+        #[inline]
+        fn rx_status(&self, idx: usize) -> RxStatus {
+            let (Some(meta), Some(desc)) =
+                (self.rx_meta.get(idx), self.rx_ring.descriptors.get(idx))
+            else {
+                // A slot outside the ring never holds a frame
+                return RxStatus::from_raw(rdes0::OWN);
+            };
+            let cached = meta.load(Ordering::Relaxed);
+            if cached & rdes0::OWN == 0 {
+                return RxStatus::from_raw(cached);
+            }
+            let status = desc.status();
+            if !status.is_owned() {
+                meta.store(status.raw(), Ordering::Relaxed);
+            }
+            status
+        }
+
+        /// Return RX slot `idx` to the DMA, dropping its cached status.
+        #[inline]
+        fn recycle_rx(&mut self, idx: usize) {
+            if let Some(class) = self.rx_class.get_mut(idx) {
+                if let Some(count) = self.rx_tally.count_mut(*class) {
+                    *count = count.saturating_sub(1);
                 }
-                reclaimed += 1;
+                *class = RxClass::Untallied;
+            }
+            if let (Some(meta), Some(desc)) =
+                (self.rx_meta.get(idx), self.rx_ring.descriptors.get(idx))
+            {
+                meta.store(rdes0::OWN, Ordering::Relaxed);
+                desc.recycle();
             }
         }
+    }
 
-        (reclaimed, errors)
+    /// Forget all cached RX statuses and classes (after the ring was rebuilt).
+    fn invalidate_rx_meta(&mut self) {
+        for meta in &self.rx_meta {
+            meta.store(rdes0::OWN, Ordering::Relaxed);
+        }
+        self.rx_class = [RxClass::Untallied; RX_BUFS];
+        self.rx_tally = RxTally::default();
     }
 
     /// Count free RX descriptors (owned by DMA).
     pub fn rx_free_count(&self) -> usize {
-        let mut count = 0;
-        for desc in &self.rx_ring.descriptors {
-            if desc.is_owned() {
-                count += 1;
-            }
-        }
-        count
+        (0..self.rx_slots())
+            .filter(|&idx| self.rx_status(idx).is_owned())
+            .count()
     }
 
     /// Check if a complete frame is available.
     pub fn rx_available(&self) -> bool {
-        let desc = self.rx_ring.current();
-        !desc.is_owned() && desc.is_last()
+        // A frame spanning several descriptors is complete once its last
+        // one is released, not its first
+        Self::HAS_RX && self.next_frame_last().is_some()
     }
 
-    /// Peek next frame length without consuming.
-    pub fn peek_frame_length(&self) -> Option<usize> {
-        let desc = self.rx_ring.current();
+    iram! {
+        /// Peek next frame length without consuming.
+        pub fn peek_frame_length(&self) -> Option<usize> {
+            if !Self::HAS_RX {
+                return None;
+            }
+            let first = self.rx_status(self.rx_ring.current_index());
+            if first.is_owned() || first.has_error() || !first.is_first() {
+                return None;
+            }
+            // Multi-descriptor frames carry the length in their last descriptor
+            self.next_frame_last()
+                .map(|(_, status)| status.payload_length())
+        }
+    }
 
-        if desc.is_owned() {
+    /// The next frame, in place in its RX buffer, if it is complete,
+    /// error-free and fits in one buffer.
+    ///
+    /// The descriptor stays with the CPU until
+    /// [`flush_rx_frame`](Self::flush_rx_frame) releases it.
+    pub fn peek_frame_in_place(&self) -> Option<&[u8]> {
+        if !Self::HAS_RX {
             return None;
         }
-
-        if desc.has_error() {
+        let idx = self.rx_ring.current_index();
+        let status = self.rx_status(idx);
+        if status.is_owned() || status.has_error() || !status.is_first() || !status.is_last() {
             return None;
         }
+        let index = if self.dual_buffer { 2 * idx } else { idx };
+        let frame = self.rx_buffers.get(index)?.get(..status.payload_length())?;
+        // Order the buffer reads after the OWN bit the DMA cleared last
+        fence(Ordering::Acquire);
+        Some(frame)
+    }
 
-        // For a complete single-descriptor frame
-        if desc.is_first() && desc.is_last() {
-            return Some(desc.payload_length());
+    iram! {
+        /// Peek the RDES0 status of the next complete frame without consuming it.
+        ///
+        /// Status bits are only valid in the frame's last descriptor, so this
+        /// walks to it like [`peek_frame_length`](Self::peek_frame_length).
+        pub fn peek_frame_status(&self) -> Option<u32> {
+            self.next_frame_last().map(|(_, status)| status.raw())
         }
 
-        // For multi-descriptor frames, we need to find the last descriptor
-        // to get the total length
-        if desc.is_first() {
-            // Walk through descriptors to find the last one
-            for i in 1..RX_BUFS {
-                let idx = (self.rx_ring.current + i) % RX_BUFS;
-                let d = &self.rx_ring.descriptors[idx];
+        /// Peek the RDES4 extended status of the next complete frame.
+        ///
+        /// Only meaningful if its RDES0 has `EXT_STATUS` set.
+        pub fn peek_frame_ext_status(&self) -> Option<u32> {
+            let (idx, _) = self.next_frame_last()?;
+            self.rx_ring
+                .descriptors
+                .get(idx)
+                .map(RxDescriptor::extended_status)
+        }
 
-                if d.is_owned() {
-                    // Frame not complete yet
+        /// Slot and status of the next complete frame's last descriptor, if the
+        /// DMA has released it.
+        fn next_frame_last(&self) -> Option<(usize, RxStatus)> {
+            let slots = self.rx_slots();
+            for i in 0..slots {
+                let idx = ring_slot(self.rx_ring.current, i, slots);
+                let status = self.rx_status(idx);
+                if status.is_owned() {
                     return None;
                 }
-
-                if d.is_last() {
-                    return Some(d.payload_length());
+                if status.is_last() {
+                    return Some((idx, status));
                 }
             }
+            None
         }
 
-        None
+        /// Peek the FCS bytes the DMA stored after the next complete frame.
+        ///
+        /// Returns `None` if no complete, error-free frame is pending. Only
+        /// meaningful when the MAC did not strip the FCS.
+        pub fn peek_frame_fcs(&self) -> Option<[u8; 4]> {
+            let end = self.peek_frame_length()? + CRC_SIZE;
+            let slots = self.rx_slots();
+            let capacity = self.slot_capacity();
+            let mut fcs = [0u8; CRC_SIZE];
+            for (i, byte) in fcs.iter_mut().enumerate() {
+                let offset = end - CRC_SIZE + i;
+                let slot = ring_slot(self.rx_ring.current, offset / capacity, slots);
+                *byte = self.rx_slot_byte(slot, offset % capacity);
+            }
+            Some(fcs)
+        }
+
+        /// Peek the first `N` bytes of the next complete frame.
+        ///
+        /// Returns `None` if no complete, error-free frame of at least `N`
+        /// bytes is pending.
+        pub fn peek_frame_head<const N: usize>(&self) -> Option<[u8; N]> {
+            if self.peek_frame_length()? < N {
+                return None;
+            }
+            let slots = self.rx_slots();
+            let capacity = self.slot_capacity();
+            Some(core::array::from_fn(|i| {
+                self.rx_slot_byte(
+                    ring_slot(self.rx_ring.current, i / capacity, slots),
+                    i % capacity,
+                )
+            }))
+        }
+    }
+
+    /// Byte `pos` of RX slot `slot`, spanning both buffers in ring mode.
+    ///
+    /// Out-of-range positions read as 0.
+    #[inline(always)]
+    fn rx_slot_byte(&self, slot: usize, pos: usize) -> u8 {
+        let (index, pos) = if !self.dual_buffer {
+            (slot, pos)
+        } else if pos < Self::DESC_BUF_SIZE {
+            (2 * slot, pos)
+        } else {
+            (2 * slot + 1, pos - Self::DESC_BUF_SIZE)
+        };
+        self.rx_buffers
+            .get(index)
+            .and_then(|buf| buf.get(pos))
+            .copied()
+            .unwrap_or(0)
     }
 
     /// Count remaining complete frames in the RX ring
     pub fn rx_frame_count(&self) -> usize {
         let mut count = 0;
         let mut idx = self.rx_ring.current;
+        let slots = self.rx_slots();
 
-        for _ in 0..RX_BUFS {
-            let desc = &self.rx_ring.descriptors[idx];
+        for _ in 0..slots {
+            let status = self.rx_status(idx);
 
-            if desc.is_owned() {
+            if status.is_owned() {
                 break;
             }
 
-            if desc.is_last() {
+            if status.is_last() {
                 count += 1;
             }
 
-            idx = (idx + 1) % RX_BUFS;
+            idx = ring_slot(idx, 1, slots);
         }
 
         count
     }
 
-    /// Receive a frame into buffer. Returns length excluding CRC.
-    pub fn receive(&mut self, buffer: &mut [u8]) -> Result<usize> {
-        let first_desc = self.rx_ring.current();
+    iram! {
+        /// Classify the frames completed since the last call for the unicast
+        /// reserve.
+        ///
+        /// `reserved` sees each frame's last RDES0 and first byte. Frames are
+        /// tallied in ring order and untallied as their descriptors are
+        /// recycled, so each one is looked at once.
+        pub fn tally_rx(&mut self, reserved: impl Fn(u32, u8) -> bool) {
+            let slots = self.rx_slots();
+            while self.rx_tally.total() < slots {
+                let first = ring_slot(self.rx_ring.current, self.rx_tally.total(), slots);
+                let remaining = slots - self.rx_tally.total();
+                let Some((count, status)) = self.rx_frame_extent(first, remaining) else {
+                    return;
+                };
+                let class = if reserved(status.raw(), self.rx_slot_byte(first, 0)) {
+                    RxClass::Reserved
+                } else {
+                    RxClass::Other
+                };
+                self.set_rx_class(first, count, class);
+            }
+        }
+
+        /// Descriptors held by the complete frames waiting in the RX ring: those
+        /// `reserved` accepts, the others, and those already shed.
+        ///
+        /// Frames not yet seen by [`tally_rx`](Self::tally_rx) are classified
+        /// on the fly.
+        pub fn rx_occupancy(&self, reserved: impl Fn(u32, u8) -> bool) -> (usize, usize, usize) {
+            let RxTally {
+                reserved: mut held,
+                mut other,
+                shed,
+            } = self.rx_tally;
+            let slots = self.rx_slots();
+            let mut offset = self.rx_tally.total();
+            while offset < slots {
+                let first = ring_slot(self.rx_ring.current, offset, slots);
+                let Some((count, status)) = self.rx_frame_extent(first, slots - offset) else {
+                    break;
+                };
+                if reserved(status.raw(), self.rx_slot_byte(first, 0)) {
+                    held += count;
+                } else {
+                    other += count;
+                }
+                offset += count;
+            }
+            (held, other, shed)
+        }
+
+        /// Mark the oldest tallied frame outside the reserve as shed.
+        ///
+        /// A frame at the head of the ring is dropped right away; one behind
+        /// frames still waiting is dropped by
+        /// [`drop_shed_rx_frame`](Self::drop_shed_rx_frame) once it reaches the
+        /// head, as the DMA can only reuse descriptors in ring order. Returns
+        /// `false` if every tallied frame is reserved or shed.
+        pub fn shed_rx_frame(&mut self) -> bool {
+            if self.rx_tally.other == 0 {
+                return false;
+            }
+            let slots = self.rx_slots();
+            let mut offset = 0;
+            while offset < self.rx_tally.total() {
+                let first = ring_slot(self.rx_ring.current, offset, slots);
+                let Some((count, _)) = self.rx_frame_extent(first, slots - offset) else {
+                    return false;
+                };
+                if self.rx_class.get(first) == Some(&RxClass::Other) {
+                    self.set_rx_class(first, count, RxClass::Shed);
+                    self.drop_shed_rx_frame();
+                    return true;
+                }
+                offset += count;
+            }
+            false
+        }
+
+        /// Drop the frame at the head of the ring if it was shed.
+        ///
+        /// Returns `true` if a frame was dropped.
+        pub fn drop_shed_rx_frame(&mut self) -> bool {
+            if self.rx_class.get(self.rx_ring.current_index()) != Some(&RxClass::Shed) {
+                return false;
+            }
+            self.flush_rx_frame();
+            true
+        }
+
+        /// Descriptor count and last status of the frame starting at slot
+        /// `first`, if the DMA released all of it within `max` descriptors
+        #[inline]
+        fn rx_frame_extent(&self, first: usize, max: usize) -> Option<(usize, RxStatus)> {
+            let slots = self.rx_slots();
+            for i in 0..max {
+                let status = self.rx_status(ring_slot(first, i, slots));
+                if status.is_owned() {
+                    return None;
+                }
+                if status.is_last() {
+                    return Some((i + 1, status));
+                }
+            }
+            None
+        }
+
+        /// Move the `count` slots from `first` to `class` in the tally
+        fn set_rx_class(&mut self, first: usize, count: usize, class: RxClass) {
+            let slots = self.rx_slots();
+            for i in 0..count {
+                let Some(slot) = self.rx_class.get_mut(ring_slot(first, i, slots)) else {
+                    continue;
+                };
+                if let Some(old) = self.rx_tally.count_mut(*slot) {
+                    *old = old.saturating_sub(1);
+                }
+                *slot = class;
+                if let Some(new) = self.rx_tally.count_mut(class) {
+                    *new += 1;
+                }
+            }
+        }
+
+        /// Receive a frame into buffer. Returns length excluding CRC.
+        pub fn receive(&mut self, buffer: &mut [u8]) -> Result<usize> {
+            self.receive_into(buffer, false).map(|(len, _)| len)
+        }
+    }
+
+    /// Receive a frame, keeping its head if `buffer` is too small
+    ///
+    /// Returns the bytes copied and the frame length excluding CRC; the
+    /// frame is consumed either way.
+    pub fn receive_truncating(&mut self, buffer: &mut [u8]) -> Result<(usize, usize)> {
+        self.receive_into(buffer, true)
+    }
 
-        if first_desc.is_owned() {
+    /// Body of [`receive`](Self::receive) and
+    /// [`receive_truncating`](Self::receive_truncating): the bytes copied
+    /// and the frame length
+    #[inline(always)]
+    fn receive_into(&mut self, buffer: &mut [u8], truncate: bool) -> Result<(usize, usize)> {
+        if !Self::HAS_RX {
+            return Err(DmaError::NoRxBuffers.into());
+        }
+        let slots = self.rx_slots();
+        debug_check!(
+            self.rx_ring.current_index() < slots,
+            "RX ring index out of range"
+        );
+        let first_idx = self.rx_ring.current_index();
+        let first = self.rx_status(first_idx);
+
+        if first.is_owned() {
             return Err(IoError::IncompleteFrame.into());
         }
 
         // Single-descriptor frame (common case)
-        if first_desc.is_first() && first_desc.is_last() {
-            if first_desc.has_error() {
+        if first.is_first() && first.is_last() {
+            if first.has_error() {
                 #[cfg(feature = "log")]
-                log_rx_error(first_desc);
-                first_desc.recycle();
-                self.rx_ring.advance();
-                DmaRegs::rx_poll_demand();
+                log_rx_error(first);
+                self.recycle_rx(first_idx);
+                self.rx_ring.advance_within(1, slots);
+                self.rx_poll_demand();
                 return Err(IoError::FrameError.into());
             }
 
-            let frame_len = first_desc.payload_length();
-            if buffer.len() < frame_len {
-                first_desc.recycle();
-                self.rx_ring.advance();
-                DmaRegs::rx_poll_demand();
+            let frame_len = first.payload_length();
+            let copy_len = if truncate {
+                frame_len.min(buffer.len())
+            } else {
+                frame_len
+            };
+            let Some(dst) = buffer.get_mut(..copy_len) else {
+                self.recycle_rx(first_idx);
+                self.rx_ring.advance_within(1, slots);
+                self.rx_poll_demand();
                 return Err(IoError::BufferTooSmall.into());
-            }
+            };
 
-            let idx = self.rx_ring.current_index();
-            buffer[..frame_len].copy_from_slice(&self.rx_buffers[idx][..frame_len]);
-            first_desc.recycle();
-            self.rx_ring.advance();
-            DmaRegs::rx_poll_demand();
-            return Ok(frame_len);
+            self.copy_from_rx_slot(first_idx, dst);
+            self.recycle_rx(first_idx);
+            self.rx_ring.advance_within(1, slots);
+            self.rx_poll_demand();
+            return Ok((copy_len, frame_len));
         }
 
         // Multi-descriptor frame
-        if !first_desc.is_first() {
+        if !first.is_first() {
             self.flush_rx_frame();
             return Err(IoError::IncompleteFrame.into());
         }
 
-        if first_desc.has_error() {
+        if first.has_error() {
             #[cfg(feature = "log")]
-            log_rx_error(first_desc);
+            log_rx_error(first);
             self.flush_rx_frame();
             return Err(IoError::FrameError.into());
         }
 
         let mut frame_len = 0usize;
         let mut desc_count = 0usize;
-        let mut last_idx = self.rx_ring.current_index();
 
-        for i in 0..RX_BUFS {
-            let idx = (self.rx_ring.current_index() + i) % RX_BUFS;
-            let desc = &self.rx_ring.descriptors[idx];
+        for i in 0..slots {
+            let idx = ring_slot(first_idx, i, slots);
+            let status = self.rx_status(idx);
 
-            if desc.is_owned() {
+            if status.is_owned() {
                 // Frame not complete
                 return Err(IoError::IncompleteFrame.into());
             }
 
             desc_count += 1;
-            last_idx = idx;
 
-            if desc.is_last() {
-                frame_len = desc.payload_length();
+            if status.is_last() {
+                // Error bits are only valid in the last descriptor
+                if status.has_error() {
+                    #[cfg(feature = "log")]
+                    log_rx_error(status);
+                    self.flush_rx_frame();
+                    return Err(IoError::FrameError.into());
+                }
+                frame_len = status.payload_length();
                 break;
             }
         }
 
-        if buffer.len() < frame_len {
+        let copy_len = if truncate {
+            frame_len.min(buffer.len())
+        } else {
+            frame_len
+        };
+        let Some(dst) = buffer.get_mut(..copy_len) else {
             self.flush_rx_frame();
             return Err(IoError::BufferTooSmall.into());
-        }
-
-        // Copy data from all descriptors
-        let mut copied = 0usize;
-
+        };
+        debug_check!(
+            frame_len <= desc_count * self.slot_capacity(),
+            "RX frame length {frame_len} exceeds {desc_count} descriptors"
+        );
+
+        // Copy data from all descriptors, one slot's worth at a time; a
+        // truncated copy runs out of chunks early
+        let mut chunks = dst.chunks_mut(self.slot_capacity());
         for i in 0..desc_count {
-            let idx = (self.rx_ring.current_index() + i) % RX_BUFS;
-            let desc = &self.rx_ring.descriptors[idx];
-            let buf_data_len = if idx == last_idx {
-                frame_len - copied
-            } else {
-                BUF_SIZE
-            };
-            let copy_len = core::cmp::min(buf_data_len, frame_len - copied);
-
-            if copy_len > 0 {
-                buffer[copied..copied + copy_len]
-                    .copy_from_slice(&self.rx_buffers[idx][..copy_len]);
-                copied += copy_len;
+            let idx = ring_slot(first_idx, i, slots);
+            if let Some(chunk) = chunks.next() {
+                self.copy_from_rx_slot(idx, chunk);
             }
-            desc.recycle();
+            self.recycle_rx(idx);
         }
+        debug_check!(chunks.next().is_none(), "RX frame copy incomplete");
 
-        self.rx_ring.advance_by(desc_count);
-        DmaRegs::rx_poll_demand();
+        self.rx_ring.advance_within(desc_count, slots);
+        self.rx_poll_demand();
 
-        Ok(frame_len)
+        Ok((copy_len, frame_len))
     }
 
-    /// Discard current RX frame (for errors or small buffer).
-    pub fn flush_rx_frame(&mut self) {
-        loop {
-            let desc = self.rx_ring.current();
+    iram! {
+        /// Discard current RX frame (for errors or small buffer).
+        pub fn flush_rx_frame(&mut self) {
+            if !Self::HAS_RX {
+                return;
+            }
+            let slots = self.rx_slots();
+            loop {
+                let idx = self.rx_ring.current_index();
+                let status = self.rx_status(idx);
+
+                if status.is_owned() {
+                    break;
+                }
 
-            if desc.is_owned() {
-                break;
+                self.recycle_rx(idx);
+                self.rx_ring.advance_within(1, slots);
+
+                if status.is_last() {
+                    break;
+                }
             }
 
-            let is_last = desc.is_last();
-            desc.recycle();
-            self.rx_ring.advance();
+            self.rx_poll_demand();
+        }
+    }
+
+    /// Hold back RX poll demands until [`end_rx_batch`](Self::end_rx_batch)
+    ///
+    /// Frames taken off the ring in between then cost one register write
+    /// in total instead of one each.
+    pub fn begin_rx_batch(&mut self) {
+        self.rx_poll_deferred = true;
+    }
 
-            if is_last {
-                break;
-            }
+    /// Issue the RX poll demand held back since
+    /// [`begin_rx_batch`](Self::begin_rx_batch), if any
+    pub fn end_rx_batch(&mut self) {
+        self.rx_poll_deferred = false;
+        if core::mem::take(&mut self.rx_poll_pending) {
+            DmaRegs::rx_poll_demand();
         }
+    }
 
-        DmaRegs::rx_poll_demand();
+    /// Tell the DMA that RX descriptors were handed back, now or at the
+    /// end of the batch
+    #[inline(always)]
+    fn rx_poll_demand(&mut self) {
+        if self.rx_poll_deferred {
+            self.rx_poll_pending = true;
+        } else {
+            DmaRegs::rx_poll_demand();
+        }
     }
 
     /// RX ring base address (for debugging).
@@ -449,6 +1593,9 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
     }
 
     /// RX buffer at index.
+    ///
+    /// # Panics
+    /// If there are no RX buffers (`RX_BUFS = 0`).
     pub fn rx_buffer(&self, index: usize) -> &[u8; BUF_SIZE] {
         &self.rx_buffers[index % RX_BUFS]
     }
@@ -483,9 +1630,171 @@ unsafe impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize> S
 // Tests
 // =============================================================================
 
+// =============================================================================
+// RX Frame Injection (host tests, `test-hooks`)
+// =============================================================================
+
+#[cfg(any(test, feature = "test-hooks"))]
+impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
+    DmaEngine<RX_BUFS, TX_BUFS, BUF_SIZE>
+{
+    /// Deliver `frame` followed by `fcs` into the next DMA-owned RX slot
+    /// and hand it to the CPU with `extra_status` ORed into RDES0, as the
+    /// DMA does on frame completion.
+    ///
+    /// Returns `false` if no slot is free. The frame must fit one slot.
+    pub(crate) fn inject_rx_frame(
+        &mut self,
+        frame: &[u8],
+        fcs: [u8; 4],
+        extra_status: u32,
+    ) -> bool {
+        let total = frame.len() + fcs.len();
+        assert!(total <= self.slot_capacity(), "injected frame spans slots");
+        let slots = self.rx_slots();
+        let Some(slot) = (0..slots)
+            .map(|i| ring_slot(self.rx_ring.current, i, slots))
+            .find(|&idx| self.rx_status(idx).is_owned())
+        else {
+            return false;
+        };
+
+        for (pos, &byte) in frame.iter().chain(&fcs).enumerate() {
+            if !self.dual_buffer {
+                self.rx_buffers[slot][pos] = byte;
+            } else if pos < Self::DESC_BUF_SIZE {
+                self.rx_buffers[2 * slot][pos] = byte;
+            } else {
+                self.rx_buffers[2 * slot + 1][pos - Self::DESC_BUF_SIZE] = byte;
+            }
+        }
+        self.rx_ring.descriptors[slot].set_raw_rdes0(
+            rdes0::FIRST_DESC
+                | rdes0::LAST_DESC
+                | ((total as u32) << rdes0::FRAME_LEN_SHIFT)
+                | extra_status,
+        );
+        true
+    }
+}
+
+// =============================================================================
+// DMA-Side Simulation (host tests)
+// =============================================================================
+
+/// Hooks standing in for the DMA engine's half of the descriptor protocol.
+///
+/// Together with the simulated register file these let host tests and
+/// benchmarks run the real transmit, receive and reclaim paths.
+#[cfg(test)]
+impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
+    DmaEngine<RX_BUFS, TX_BUFS, BUF_SIZE>
+{
+    /// Accept borrowed TX buffers anywhere, since host memory lies outside
+    /// the chip's address map.
+    pub(crate) fn sim_dma_capable_anywhere(&mut self) {
+        self.dma_capable = |_, _| true;
+    }
+
+    /// Deliver `frame` (plus a zero FCS) like
+    /// [`inject_rx_frame`](Self::inject_rx_frame).
+    pub(crate) fn sim_rx_frame(&mut self, frame: &[u8], extra_status: u32) -> bool {
+        self.inject_rx_frame(frame, [0; 4], extra_status)
+    }
+
+    /// Deliver `frame` (plus a zero FCS) across as many RX slots as it
+    /// needs, with `extra_status` ORed into the last descriptor's RDES0.
+    ///
+    /// Returns `false` if fewer slots are free.
+    pub(crate) fn sim_rx_frame_spanning(&mut self, frame: &[u8], extra_status: u32) -> bool {
+        let capacity = self.slot_capacity();
+        let total = frame.len() + CRC_SIZE;
+        let count = total.div_ceil(capacity);
+        let slots = self.rx_slots();
+        let Some(start) = (0..slots)
+            .map(|i| ring_slot(self.rx_ring.current, i, slots))
+            .find(|&idx| self.rx_status(idx).is_owned())
+        else {
+            return false;
+        };
+        if count > slots
+            || (0..count).any(|i| !self.rx_status(ring_slot(start, i, slots)).is_owned())
+        {
+            return false;
+        }
+
+        for (pos, &byte) in frame.iter().chain(&[0; CRC_SIZE]).enumerate() {
+            let slot = ring_slot(start, pos / capacity, slots);
+            let pos = pos % capacity;
+            if !self.dual_buffer {
+                self.rx_buffers[slot][pos] = byte;
+            } else if pos < Self::DESC_BUF_SIZE {
+                self.rx_buffers[2 * slot][pos] = byte;
+            } else {
+                self.rx_buffers[2 * slot + 1][pos - Self::DESC_BUF_SIZE] = byte;
+            }
+        }
+        for i in 0..count {
+            let mut status = if i == 0 { rdes0::FIRST_DESC } else { 0 };
+            if i + 1 == count {
+                status |=
+                    rdes0::LAST_DESC | ((total as u32) << rdes0::FRAME_LEN_SHIFT) | extra_status;
+            }
+            self.rx_ring.descriptors[ring_slot(start, i, slots)].set_raw_rdes0(status);
+        }
+        true
+    }
+
+    /// Complete the `count` oldest pending TX descriptors, in ring order.
+    /// Returns how many were completed.
+    pub(crate) fn sim_tx_complete_oldest(&mut self, count: usize) -> usize {
+        let slots = self.tx_slots();
+        let mut done = 0;
+        for i in 0..slots {
+            let desc = &self.tx_ring.descriptors[ring_slot(self.tx_ring.current, i, slots)];
+            if done == count {
+                break;
+            }
+            if desc.is_owned() {
+                desc.clear_owned();
+                done += 1;
+            }
+        }
+        done
+    }
+
+    /// Complete every pending TX descriptor. Returns how many were pending.
+    pub(crate) fn sim_tx_complete(&mut self) -> usize {
+        self.sim_tx_complete_with_errors(0)
+    }
+
+    /// Complete every pending TX descriptor, writing `errors` (TDES0 error
+    /// bits) plus the error summary into each frame's last descriptor.
+    pub(crate) fn sim_tx_complete_with_errors(&mut self, errors: u32) -> usize {
+        use super::descriptor::bits::tdes0;
+
+        let mut done = 0;
+        for desc in self.tx_ring.iter().take(self.tx_slots()) {
+            if desc.is_owned() {
+                desc.clear_owned();
+                if errors != 0 && desc.is_last() {
+                    desc.set_raw_tdes0(desc.raw_tdes0() | tdes0::ERR_SUMMARY | errors);
+                }
+                done += 1;
+            }
+        }
+        done
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::internal::dma::descriptor::bits::{rdes1, tdes0, tdes1};
+    use crate::internal::register::dma::{
+        DMARXBASEADDR_OFFSET, DMARXPOLLDEMAND_OFFSET, DMATXPOLLDEMAND_OFFSET,
+    };
+    use crate::internal::register::{DMA_BASE, sim};
     use crate::testing::MockDescriptor;
 
     #[test]
@@ -529,6 +1838,303 @@ mod tests {
         assert_eq!(dma.tx_ctrl_flags(), 0x1234);
     }
 
+    // =========================================================================
+    // TX Interrupt Batching Tests
+    // =========================================================================
+
+    #[test]
+    fn tx_ic_default_every_frame() {
+        let mut dma: DmaEngine<4, 4, 1600> = DmaEngine::new();
+        assert_eq!(dma.tx_ic_interval(), 1);
+        for _ in 0..5 {
+            assert!(dma.tx_needs_ic(false, false));
+        }
+    }
+
+    #[test]
+    fn tx_ic_every_nth_frame() {
+        let mut dma: DmaEngine<4, 4, 1600> = DmaEngine::new();
+        dma.set_tx_ic_interval(4);
+
+        let pattern: [bool; 8] = core::array::from_fn(|_| dma.tx_needs_ic(false, false));
+        assert_eq!(
+            pattern,
+            [false, false, false, true, false, false, false, true]
+        );
+    }
+
+    #[test]
+    fn tx_ic_zero_interval_is_every_frame() {
+        let mut dma: DmaEngine<4, 4, 1600> = DmaEngine::new();
+        dma.set_tx_ic_interval(0);
+        assert_eq!(dma.tx_ic_interval(), 1);
+        assert!(dma.tx_needs_ic(false, false));
+    }
+
+    #[test]
+    fn tx_ic_forced_restarts_batch() {
+        let mut dma: DmaEngine<4, 4, 1600> = DmaEngine::new();
+        dma.set_tx_ic_interval(3);
+
+        assert!(!dma.tx_needs_ic(false, false));
+        assert!(dma.tx_needs_ic(true, false));
+        assert!(!dma.tx_needs_ic(false, false));
+        assert!(!dma.tx_needs_ic(false, false));
+        assert!(dma.tx_needs_ic(false, false));
+    }
+
+    #[test]
+    fn tx_ic_set_when_ring_fills() {
+        let mut dma: DmaEngine<4, 4, 1600> = DmaEngine::new();
+        dma.set_tx_ic_interval(100);
+
+        assert!(!dma.tx_needs_ic(false, false));
+        assert!(dma.tx_needs_ic(false, true));
+        assert!(!dma.tx_needs_ic(false, false));
+    }
+
+    // =========================================================================
+    // Borrowed TX Tests
+    // =========================================================================
+
+    #[repr(align(4))]
+    struct Aligned([u8; 64]);
+
+    static BORROWED: Aligned = Aligned([0x5A; 64]);
+
+    #[test]
+    fn borrowed_frame_is_sent_in_place_and_slot_restored() {
+        sim::reset();
+        let mut dma: DmaEngine<2, 2, 256> = DmaEngine::new();
+        dma.init();
+        dma.sim_dma_capable_anywhere();
+        let addr = BORROWED.0.as_ptr() as u32;
+
+        let slot = dma.transmit_borrowed(&BORROWED.0, 0).unwrap();
+        assert_eq!(slot, 0);
+        let desc = &dma.tx_ring.descriptors[0];
+        assert_eq!(desc.buffer_addr(), addr);
+        assert_eq!(desc.raw_tdes1() & tdes1::BUFFER1_SIZE_MASK, 64);
+        assert!(dma.tx_borrowed_pending(slot, addr));
+        assert_eq!(dma.tx_buffer(0)[0], 0);
+
+        dma.sim_tx_complete();
+        assert!(!dma.tx_borrowed_pending(slot, addr));
+
+        // The next copied frame in slot 0 uses the ring buffer again
+        dma.transmit(&[0xAB; 60]).unwrap();
+        dma.transmit(&[0xCD; 60]).unwrap();
+        assert_eq!(
+            dma.tx_ring.descriptors[0].buffer_addr(),
+            dma.tx_buffers[0].as_ptr() as u32
+        );
+    }
+
+    #[test]
+    fn borrowed_frame_must_be_in_dma_memory() {
+        sim::reset();
+        let mut dma: DmaEngine<2, 2, 256> = DmaEngine::new();
+        dma.init();
+        // Host memory is nowhere near the chip's internal SRAM
+        assert_eq!(
+            dma.transmit_borrowed(&BORROWED.0, 0),
+            Err(DmaError::BufferNotDmaCapable.into())
+        );
+        assert_eq!(dma.tx_free(), 2);
+    }
+
+    #[test]
+    fn borrowed_frame_must_be_aligned() {
+        sim::reset();
+        let mut dma: DmaEngine<2, 2, 256> = DmaEngine::new();
+        dma.init();
+        dma.sim_dma_capable_anywhere();
+        assert_eq!(
+            dma.transmit_borrowed(&BORROWED.0[1..61], 0),
+            Err(DmaError::BufferNotDmaCapable.into())
+        );
+        assert_eq!(
+            dma.transmit_borrowed(&BORROWED.0[..0], 0),
+            Err(DmaError::InvalidLength.into())
+        );
+        assert_eq!(dma.tx_free(), 2);
+    }
+
+    // =========================================================================
+    // Reserved TX Descriptor Tests
+    // =========================================================================
+
+    #[test]
+    fn tx_reserve_held_back_for_reserved_frames() {
+        sim::reset();
+        let mut dma: DmaEngine<4, 4, 256> = DmaEngine::new();
+        dma.init();
+        dma.set_tx_ic_interval(100);
+        dma.set_tx_reserved(1);
+        assert_eq!((dma.tx_available(), dma.tx_free()), (3, 4));
+
+        for _ in 0..3 {
+            dma.transmit(&[0xAB; 64]).unwrap();
+        }
+        // Last ordinary slot taken: waiters need the interrupt
+        assert_eq!(dma.tx_frames_since_ic, 0);
+        assert!(!dma.can_transmit(64));
+        assert_eq!(
+            dma.transmit(&[0xAB; 64]),
+            Err(DmaError::NoDescriptorsAvailable.into())
+        );
+
+        dma.transmit_reserved(&[0xCD; 64]).unwrap();
+        assert_eq!(dma.tx_free(), 0);
+        assert_eq!(
+            dma.transmit_reserved(&[0xCD; 64]),
+            Err(DmaError::NoDescriptorsAvailable.into())
+        );
+
+        dma.sim_tx_complete();
+        assert_eq!(dma.tx_available(), 3);
+    }
+
+    #[test]
+    fn tx_reserve_leaves_an_ordinary_slot() {
+        let mut dma: DmaEngine<4, 4, 256> = DmaEngine::new();
+        dma.set_tx_reserved(8);
+        assert_eq!(dma.tx_reserved(), 3);
+        assert!(dma.set_active_buffers(4, 2));
+        assert_eq!(dma.tx_reserved(), 1);
+    }
+
+    // =========================================================================
+    // Dual-Buffer Ring Mode Tests
+    // =========================================================================
+
+    #[test]
+    fn dual_buffer_halves_slots() {
+        let mut dma: DmaEngine<4, 6, 512> = DmaEngine::new();
+        assert!(!dma.is_dual_buffer());
+        assert_eq!((dma.rx_slots(), dma.tx_slots()), (4, 6));
+        assert_eq!(dma.slot_capacity(), 512);
+
+        assert!(dma.set_dual_buffer(true));
+        assert!(dma.is_dual_buffer());
+        assert_eq!((dma.rx_slots(), dma.tx_slots()), (2, 3));
+        assert_eq!(dma.slot_capacity(), 1024);
+    }
+
+    #[test]
+    fn dual_buffer_rejected_with_single_buffer() {
+        let mut dma: DmaEngine<1, 4, 512> = DmaEngine::new();
+        assert!(!dma.set_dual_buffer(true));
+        assert!(!dma.is_dual_buffer());
+        assert!(dma.set_dual_buffer(false));
+    }
+
+    #[test]
+    fn dual_buffer_ring_layout() {
+        let mut dma: DmaEngine<4, 4, 512> = DmaEngine::new();
+        dma.set_dual_buffer(true);
+        dma.init_dual_buffer();
+
+        let first = &dma.rx_ring.descriptors[0];
+        assert!(first.is_owned());
+        assert_eq!(first.buffer_addr(), dma.rx_buffers[0].as_ptr() as u32);
+        assert_eq!(first.next_desc_addr(), dma.rx_buffers[1].as_ptr() as u32);
+        assert_eq!(first.buffer2_size(), 512);
+
+        let last = &dma.rx_ring.descriptors[1];
+        assert_eq!(last.buffer_addr(), dma.rx_buffers[2].as_ptr() as u32);
+        assert_ne!(last.raw_rdes1() & rdes1::RX_END_OF_RING, 0);
+
+        // Unused descriptors stay with the CPU
+        assert!(!dma.rx_ring.descriptors[2].is_owned());
+        assert_eq!(dma.tx_ring.descriptors[3].buffer_addr(), 0);
+        assert_ne!(
+            dma.tx_ring.descriptors[1].raw_tdes0() & tdes0::TX_END_OF_RING,
+            0
+        );
+    }
+
+    #[test]
+    fn dual_buffer_tx_fill_splits_at_buffer_size() {
+        let mut dma: DmaEngine<4, 4, 64> = DmaEngine::new();
+        dma.set_dual_buffer(true);
+
+        let data: [u8; 100] = core::array::from_fn(|i| i as u8);
+        assert_eq!(dma.fill_tx_slot(1, &data), (64, 36));
+        assert_eq!(dma.tx_buffers[2][..], data[..64]);
+        assert_eq!(dma.tx_buffers[3][..36], data[64..]);
+
+        assert_eq!(dma.fill_tx_slot(0, &data[..10]), (10, 0));
+    }
+
+    #[test]
+    fn dual_buffer_rx_copy_joins_buffers() {
+        let mut dma: DmaEngine<4, 4, 64> = DmaEngine::new();
+        dma.set_dual_buffer(true);
+        dma.rx_buffers[2].fill(0xAA);
+        dma.rx_buffers[3].fill(0xBB);
+
+        let mut out = [0u8; 100];
+        dma.copy_from_rx_slot(1, &mut out);
+        assert!(out[..64].iter().all(|&b| b == 0xAA));
+        assert!(out[64..].iter().all(|&b| b == 0xBB));
+    }
+
+    // =========================================================================
+    // Active Ring Size Tests
+    // =========================================================================
+
+    #[test]
+    fn active_buffers_validated() {
+        let mut dma: DmaEngine<4, 4, 512> = DmaEngine::new();
+        assert!(!dma.set_active_buffers(0, 4));
+        assert!(!dma.set_active_buffers(5, 4));
+        assert!(dma.set_active_buffers(1, 3));
+        assert_eq!((dma.rx_slots(), dma.tx_slots()), (1, 3));
+
+        // Dual-buffer mode needs at least one full descriptor
+        assert!(!dma.set_dual_buffer(true));
+        assert!(dma.set_active_buffers(2, 4));
+        assert!(dma.set_dual_buffer(true));
+        assert!(!dma.set_active_buffers(1, 4));
+        assert_eq!((dma.rx_slots(), dma.tx_slots()), (1, 2));
+    }
+
+    #[test]
+    fn shrunk_chain_wraps_early() {
+        let mut dma: DmaEngine<4, 4, 512> = DmaEngine::new();
+        assert!(dma.set_active_buffers(2, 3));
+        dma.init_chained();
+
+        let rx = &dma.rx_ring.descriptors;
+        assert_eq!(rx[1].next_desc_addr(), &rx[0] as *const _ as u32);
+        assert!(rx[1].is_owned());
+        assert!(!rx[2].is_owned());
+        assert_eq!(rx[3].buffer_addr(), 0);
+
+        let tx = &dma.tx_ring.descriptors;
+        assert_eq!(tx[2].next_desc_addr(), &tx[0] as *const _ as u32);
+        assert_eq!(tx[3].buffer_addr(), 0);
+        assert_eq!(dma.tx_available(), 3);
+
+        // Restoring the full size relinks every descriptor
+        assert!(dma.set_active_buffers(4, 4));
+        dma.init_chained();
+        assert_eq!(
+            dma.rx_ring.descriptors[3].next_desc_addr(),
+            &dma.rx_ring.descriptors[0] as *const _ as u32
+        );
+        assert_eq!(dma.rx_free_count(), 4);
+    }
+
+    #[test]
+    fn ring_skip_words_matches_descriptor_size() {
+        #[cfg(not(feature = "esp32p4"))]
+        assert_eq!(DmaEngine::<4, 4, 512>::ring_skip_words(), 0);
+        #[cfg(feature = "esp32p4")]
+        assert_eq!(DmaEngine::<4, 4, 512>::ring_skip_words(), 8);
+    }
+
     // =========================================================================
     // Buffer Size and Alignment Tests
     // =========================================================================
@@ -582,6 +2188,61 @@ mod tests {
         assert_eq!(dma.tx_current_index(), 0);
     }
 
+    #[test]
+    fn desc_buf_size_rounds_down_to_word() {
+        assert_eq!(DmaEngine::<2, 2, 1600>::DESC_BUF_SIZE, 1600);
+        assert_eq!(DmaEngine::<2, 2, 1601>::DESC_BUF_SIZE, 1600);
+        assert_eq!(DmaEngine::<2, 2, 1603>::DESC_BUF_SIZE, 1600);
+        assert_eq!(DmaEngine::<2, 2, 66>::DESC_BUF_SIZE, 64);
+    }
+
+    #[test]
+    fn desc_buf_size_capped_at_size_field() {
+        assert_eq!(DmaEngine::<1, 1, 8188>::DESC_BUF_SIZE, 8188);
+        assert_eq!(DmaEngine::<1, 1, 8192>::DESC_BUF_SIZE, MAX_DESC_BUF_SIZE);
+        assert_eq!(DmaEngine::<1, 1, 9000>::DESC_BUF_SIZE, MAX_DESC_BUF_SIZE);
+    }
+
+    #[test]
+    fn unaligned_buf_size_buffers_are_word_aligned() {
+        let dma: DmaEngine<3, 3, 1601> = DmaEngine::new();
+        for i in 0..3 {
+            assert_eq!(dma.rx_buffer(i).as_ptr() as usize % 4, 0);
+            assert_eq!(dma.tx_buffer(i).as_ptr() as usize % 4, 0);
+        }
+        assert_eq!(dma.rx_buffer(0).len(), 1601);
+    }
+
+    #[test]
+    fn unaligned_buf_size_programs_rounded_size() {
+        let mut dma: DmaEngine<2, 2, 1601> = DmaEngine::new();
+        dma.init_chained();
+        assert_eq!(dma.rx_ring.descriptors[0].buffer_size(), 1600);
+        assert_eq!(dma.slot_capacity(), 1600);
+
+        let data = [0x5Au8; 1601];
+        dma.set_dual_buffer(true);
+        assert_eq!(dma.fill_tx_slot(0, &data), (1600, 1));
+    }
+
+    #[cfg(feature = "debug-checks")]
+    #[test]
+    #[should_panic(expected = "written while owned by DMA")]
+    fn debug_checks_reject_tx_fill_of_owned_slot() {
+        let mut dma: DmaEngine<4, 4, 64> = DmaEngine::new();
+        dma.tx_ring.descriptors[2].set_owned();
+        dma.fill_tx_slot(2, &[0u8; 16]);
+    }
+
+    #[cfg(feature = "debug-checks")]
+    #[test]
+    #[should_panic(expected = "exceeds slot capacity")]
+    fn debug_checks_reject_oversized_rx_copy() {
+        let dma: DmaEngine<4, 4, 64> = DmaEngine::new();
+        let mut out = [0u8; 100];
+        dma.copy_from_rx_slot(0, &mut out);
+    }
+
     // =========================================================================
     // Frame Processing Simulation Tests (using DescriptorRing with MockDescriptor)
     // =========================================================================
@@ -724,4 +2385,389 @@ mod tests {
         assert!(!dma2.is_initialized());
         assert_eq!(dma1.tx_ctrl_flags(), dma2.tx_ctrl_flags());
     }
+
+    // =========================================================================
+    // RX Copy Tests
+    // =========================================================================
+
+    #[repr(align(4))]
+    struct AlignedBuf([u8; 68]);
+
+    fn pattern() -> AlignedBuf {
+        let mut buf = AlignedBuf([0u8; 68]);
+        for (i, b) in buf.0.iter_mut().enumerate() {
+            *b = (i as u8).wrapping_mul(7).wrapping_add(1);
+        }
+        buf
+    }
+
+    #[test]
+    fn copy_rx_data_aligned_word_multiple() {
+        let src = pattern();
+        let mut dst = AlignedBuf([0u8; 68]);
+        copy_rx_data(&mut dst.0[..64], &src.0[..64]);
+        assert_eq!(dst.0[..64], src.0[..64]);
+        assert_eq!(dst.0[64..], [0u8; 4]);
+    }
+
+    #[test]
+    fn copy_rx_data_aligned_with_tail() {
+        let src = pattern();
+        for len in [1usize, 2, 3, 5, 61, 62, 63] {
+            let mut dst = AlignedBuf([0u8; 68]);
+            copy_rx_data(&mut dst.0[..len], &src.0[..len]);
+            assert_eq!(dst.0[..len], src.0[..len], "len {len}");
+            assert!(dst.0[len..].iter().all(|&b| b == 0), "len {len}");
+        }
+    }
+
+    #[test]
+    fn copy_rx_data_unaligned_destination() {
+        let src = pattern();
+        let mut dst = AlignedBuf([0u8; 68]);
+        copy_rx_data(&mut dst.0[1..61], &src.0[..60]);
+        assert_eq!(dst.0[0], 0);
+        assert_eq!(dst.0[1..61], src.0[..60]);
+    }
+
+    #[test]
+    fn copy_rx_data_unaligned_source() {
+        let src = pattern();
+        let mut dst = AlignedBuf([0u8; 68]);
+        copy_rx_data(&mut dst.0[..60], &src.0[3..63]);
+        assert_eq!(dst.0[..60], src.0[3..63]);
+    }
+
+    #[test]
+    fn copy_rx_data_stops_at_the_shorter_slice() {
+        let src = pattern();
+        let mut dst = AlignedBuf([0u8; 68]);
+        copy_rx_data(&mut dst.0[..64], &src.0[..9]);
+        assert_eq!(dst.0[..9], src.0[..9]);
+        assert!(dst.0[9..].iter().all(|&b| b == 0));
+
+        let mut dst = AlignedBuf([0u8; 68]);
+        copy_rx_data(&mut dst.0[..9], &src.0[..64]);
+        assert_eq!(dst.0[..9], src.0[..9]);
+        assert!(dst.0[9..].iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn copy_rx_data_empty() {
+        let src = pattern();
+        let mut dst = AlignedBuf([0u8; 68]);
+        copy_rx_data(&mut dst.0[..0], &src.0[..0]);
+        assert!(dst.0.iter().all(|&b| b == 0));
+    }
+
+    // =========================================================================
+    // Simulated Register Tests
+    // =========================================================================
+
+    #[test]
+    fn sim_transmit_rings_doorbell_and_reclaims() {
+        sim::reset();
+        let mut dma: DmaEngine<4, 4, 256> = DmaEngine::new();
+        dma.init();
+
+        assert_eq!(dma.transmit(&[0xAB; 100]), Ok(100));
+        assert_eq!(sim::write_count(DMA_BASE + DMATXPOLLDEMAND_OFFSET), 1);
+        assert_eq!(dma.tx_available(), 3);
+        assert!(!dma.tx_complete());
+
+        assert_eq!(dma.sim_tx_complete(), 1);
+        assert!(dma.tx_complete());
+        assert_eq!(dma.tx_reclaim(), (4, 0));
+    }
+
+    #[test]
+    fn collect_tx_errors_walks_only_released_descriptors() {
+        use super::super::descriptor::bits::tdes0;
+        use crate::internal::dma::descriptor::access_count;
+
+        sim::reset();
+        let mut dma: DmaEngine<4, 4, 256> = DmaEngine::new();
+        dma.init();
+        let mut diag = TxDiagnostics::new();
+
+        // Nothing submitted: no descriptor is read
+        let ((), reads) = access_count::reads_in(|| dma.collect_tx_errors(&mut diag));
+        assert_eq!(reads, 0);
+
+        for _ in 0..3 {
+            assert_eq!(dma.transmit(&[0xAB; 60]), Ok(60));
+        }
+        assert_eq!(dma.sim_tx_complete_oldest(1), 1);
+        let desc = &dma.tx_ring.descriptors[0];
+        desc.set_raw_tdes0(desc.raw_tdes0() | tdes0::ERR_SUMMARY | tdes0::NO_CARRIER);
+
+        // Stops at the first frame the DMA still owns
+        dma.collect_tx_errors(&mut diag);
+        assert_eq!(diag.no_carrier, 1);
+        assert_eq!((dma.tx_dirty, dma.tx_uncollected), (1, 2));
+
+        // The rest is collected once released, wrapping around the ring
+        assert_eq!(dma.transmit(&[0xAB; 60]), Ok(60));
+        assert_eq!(dma.transmit(&[0xAB; 60]), Ok(60));
+        assert_eq!(dma.sim_tx_complete_with_errors(tdes0::LATE_COLLISION), 4);
+        dma.collect_tx_errors(&mut diag);
+        dma.collect_tx_errors(&mut diag);
+        assert_eq!(diag.late_collisions, 4);
+        assert_eq!((dma.tx_dirty, dma.tx_uncollected), (1, 0));
+    }
+
+    #[test]
+    fn rx_status_read_once_per_frame() {
+        use crate::internal::dma::descriptor::access_count;
+
+        sim::reset();
+        let mut dma: DmaEngine<4, 4, 256> = DmaEngine::new();
+        dma.init();
+        assert!(dma.sim_rx_frame(&[0x5A; 60], 0));
+
+        let mut buf = [0u8; 256];
+        let (len, reads) = access_count::reads_in(|| {
+            assert!(dma.rx_available());
+            assert!(dma.peek_frame_status().is_some());
+            assert_eq!(dma.peek_frame_length(), Some(60));
+            dma.receive(&mut buf)
+        });
+        assert_eq!(len, Ok(60));
+        // One RDES0 read; everything after comes from the metadata cache
+        assert_eq!(reads, 1);
+    }
+
+    #[test]
+    fn rx_status_cache_dropped_on_recycle() {
+        sim::reset();
+        let mut dma: DmaEngine<2, 2, 256> = DmaEngine::new();
+        dma.init();
+        let mut buf = [0u8; 256];
+
+        // Each slot is reused with a different frame length
+        for len in [60, 100, 80, 120] {
+            assert!(dma.sim_rx_frame(&[0x11; 128][..len], 0));
+            assert_eq!(dma.peek_frame_length(), Some(len));
+            assert_eq!(dma.receive(&mut buf), Ok(len));
+            assert!(!dma.rx_available());
+        }
+
+        assert!(dma.sim_rx_frame(&[0x22; 60], 0));
+        assert_eq!(dma.rx_free_count(), 1);
+        dma.reset();
+        assert_eq!(dma.rx_free_count(), 2);
+        assert_eq!(dma.peek_frame_length(), None);
+    }
+
+    #[test]
+    fn sim_receive_round_trip() {
+        sim::reset();
+        let mut dma: DmaEngine<4, 4, 256> = DmaEngine::new();
+        dma.init();
+
+        let frame: [u8; 60] = core::array::from_fn(|i| i as u8);
+        assert!(dma.sim_rx_frame(&frame, 0));
+        assert_eq!(dma.peek_frame_length(), Some(60));
+
+        let mut buf = [0u8; 256];
+        assert_eq!(dma.receive(&mut buf), Ok(60));
+        assert_eq!(buf[..60], frame);
+        assert_eq!(sim::write_count(DMA_BASE + DMARXPOLLDEMAND_OFFSET), 1);
+        assert_eq!(dma.rx_free_count(), 4);
+    }
+
+    #[test]
+    fn sim_receive_dual_buffer_spans_both_buffers() {
+        sim::reset();
+        let mut dma: DmaEngine<4, 4, 64> = DmaEngine::new();
+        dma.set_dual_buffer(true);
+        dma.init();
+
+        let frame: [u8; 100] = core::array::from_fn(|i| (i * 7) as u8);
+        assert!(dma.sim_rx_frame(&frame, 0));
+        let mut buf = [0u8; 128];
+        assert_eq!(dma.receive(&mut buf), Ok(100));
+        assert_eq!(buf[..100], frame);
+    }
+
+    #[test]
+    fn rx_available_waits_for_the_last_descriptor() {
+        sim::reset();
+        let mut dma: DmaEngine<4, 4, 64> = DmaEngine::new();
+        dma.init();
+
+        let frame: [u8; 100] = core::array::from_fn(|i| i as u8);
+        assert!(dma.sim_rx_frame_spanning(&frame, 0));
+
+        // Still writing the second descriptor
+        let last = dma.rx_ring.descriptors[1].status().raw();
+        dma.rx_ring.descriptors[1].set_owned();
+        assert!(!dma.rx_available());
+
+        dma.rx_ring.descriptors[1].set_raw_rdes0(last);
+        assert!(dma.rx_available());
+    }
+
+    #[test]
+    fn receive_checks_errors_in_the_last_descriptor() {
+        sim::reset();
+        let mut dma: DmaEngine<4, 4, 64> = DmaEngine::new();
+        dma.init();
+
+        let frame = [0x5Au8; 100];
+        assert!(dma.sim_rx_frame_spanning(&frame, rdes0::ERR_SUMMARY | rdes0::CRC_ERR));
+        let mut buf = [0u8; 128];
+        assert_eq!(dma.receive(&mut buf), Err(IoError::FrameError.into()));
+        assert_eq!(dma.rx_free_count(), 4);
+    }
+
+    #[test]
+    fn sim_frame_lent_in_place_until_flushed() {
+        sim::reset();
+        let mut dma: DmaEngine<4, 4, 64> = DmaEngine::new();
+        dma.set_dual_buffer(true);
+        dma.init();
+
+        let frame: [u8; 40] = core::array::from_fn(|i| i as u8);
+        assert!(dma.sim_rx_frame(&frame, 0));
+        assert_eq!(dma.peek_frame_in_place(), Some(&frame[..]));
+        assert_eq!(dma.rx_free_count(), 1);
+        dma.flush_rx_frame();
+        assert_eq!(dma.rx_free_count(), 2);
+
+        // Spills into the second buffer: only a copy can deliver it
+        assert!(dma.sim_rx_frame(&[0x33; 100], 0));
+        assert_eq!(dma.peek_frame_in_place(), None);
+        assert_eq!(dma.peek_frame_length(), Some(100));
+    }
+
+    #[test]
+    fn sim_receive_truncating_keeps_frame_head() {
+        sim::reset();
+        let mut dma: DmaEngine<4, 4, 64> = DmaEngine::new();
+        dma.init();
+
+        let frame: [u8; 150] = core::array::from_fn(|i| i as u8);
+        assert!(dma.sim_rx_frame_spanning(&frame, 0));
+        assert!(dma.sim_rx_frame(&frame[..40], 0));
+
+        // Spanning three descriptors, cut inside the second
+        let mut head = [0u8; 70];
+        assert_eq!(dma.receive_truncating(&mut head), Ok((70, 150)));
+        assert_eq!(head, frame[..70]);
+        let mut short = [0u8; 16];
+        assert_eq!(dma.receive_truncating(&mut short), Ok((16, 40)));
+        assert_eq!(short, frame[..16]);
+        assert_eq!(dma.rx_free_count(), 4);
+
+        // A frame that fits is copied whole
+        assert!(dma.sim_rx_frame(&frame[..40], 0));
+        assert_eq!(dma.receive_truncating(&mut head), Ok((40, 40)));
+    }
+
+    #[test]
+    fn sim_transmit_in_place_fills_ring_buffer() {
+        sim::reset();
+        let mut dma: DmaEngine<2, 2, 256> = DmaEngine::new();
+        dma.init();
+
+        assert_eq!(dma.transmit_in_place(60, 0, |buf| buf.fill(0x5A)), Some(()));
+        assert_eq!(dma.tx_buffer(0)[..60], [0x5A; 60]);
+        assert_eq!(
+            dma.tx_ring.descriptors[0].raw_tdes1() & tdes1::BUFFER1_SIZE_MASK,
+            60
+        );
+        assert_eq!(dma.tx_free(), 1);
+
+        // Too large for one buffer, or no descriptor left: fill is not run
+        assert_eq!(dma.transmit_in_place(300, 0, |_| unreachable!()), None);
+        assert_eq!(dma.transmit_in_place(60, 1, |_| unreachable!()), None);
+        assert_eq!(dma.tx_free(), 1);
+    }
+
+    #[test]
+    fn sim_peek_frame_fcs() {
+        sim::reset();
+        let mut dma: DmaEngine<4, 4, 256> = DmaEngine::new();
+        dma.init();
+        assert_eq!(dma.peek_frame_fcs(), None);
+
+        assert!(dma.sim_rx_frame(&[0x11; 60], 0));
+        dma.rx_buffers[0][60..64].copy_from_slice(&[1, 2, 3, 4]);
+        assert_eq!(dma.peek_frame_fcs(), Some([1, 2, 3, 4]));
+    }
+
+    #[test]
+    fn sim_peek_frame_fcs_across_buffers() {
+        sim::reset();
+        let mut dma: DmaEngine<4, 4, 64> = DmaEngine::new();
+        dma.set_dual_buffer(true);
+        dma.init();
+
+        // FCS straddles buffer1 and buffer2 of the first slot
+        assert!(dma.sim_rx_frame(&[0x22; 62], 0));
+        dma.rx_buffers[0][62..64].copy_from_slice(&[1, 2]);
+        dma.rx_buffers[1][..2].copy_from_slice(&[3, 4]);
+        assert_eq!(dma.peek_frame_fcs(), Some([1, 2, 3, 4]));
+    }
+
+    #[test]
+    fn sim_peek_frame_ext_status() {
+        use crate::internal::dma::descriptor::bits::{rdes0, rdes4};
+
+        sim::reset();
+        let mut dma: DmaEngine<4, 4, 256> = DmaEngine::new();
+        dma.init();
+        assert_eq!(dma.peek_frame_ext_status(), None);
+
+        assert!(dma.sim_rx_frame(&[0x33; 60], rdes0::EXT_STATUS));
+        dma.rx_ring.descriptors[0].set_raw_rdes4(rdes4::IPV4_PKT | 1);
+        assert_eq!(dma.peek_frame_ext_status(), Some(rdes4::IPV4_PKT | 1));
+        assert_ne!(dma.peek_frame_status().unwrap() & rdes0::EXT_STATUS, 0);
+    }
+
+    #[test]
+    fn sim_tx_only_engine_has_no_rx_ring() {
+        sim::reset();
+        assert_eq!(
+            DmaEngine::<0, 4, 256>::memory_usage(),
+            4 * TxDescriptor::SIZE + 4 * 256
+        );
+
+        let mut dma: DmaEngine<0, 4, 256> = DmaEngine::new();
+        assert!(dma.set_dual_buffer(true));
+        assert!(dma.set_dual_buffer(false));
+        dma.init();
+        assert_eq!(sim::write_count(DMA_BASE + DMARXBASEADDR_OFFSET), 0);
+
+        let mut buf = [0u8; 256];
+        assert!(!dma.rx_available());
+        assert_eq!(dma.peek_frame_length(), None);
+        assert_eq!(dma.peek_frame_head::<14>(), None);
+        assert_eq!((dma.rx_frame_count(), dma.rx_free_count()), (0, 0));
+        assert_eq!(dma.receive(&mut buf), Err(DmaError::NoRxBuffers.into()));
+        dma.flush_rx_frame();
+        dma.reset();
+
+        assert_eq!(dma.transmit(&[0xAB; 100]), Ok(100));
+        assert!(!dma.set_active_buffers(1, 2));
+        assert!(dma.set_active_buffers(0, 2));
+    }
+
+    #[test]
+    fn sim_single_rx_buffer_round_trip() {
+        sim::reset();
+        let mut dma: DmaEngine<1, 4, 256> = DmaEngine::new();
+        assert!(!dma.set_dual_buffer(true));
+        dma.init();
+
+        let mut buf = [0u8; 256];
+        for fill in [0x11, 0x22] {
+            assert!(dma.sim_rx_frame(&[fill; 60], 0));
+            assert!(!dma.sim_rx_frame(&[fill; 60], 0));
+            assert_eq!(dma.receive(&mut buf), Ok(60));
+            assert_eq!(buf[..60], [fill; 60]);
+            assert_eq!(dma.rx_free_count(), 1);
+        }
+    }
 }
@@ -0,0 +1,372 @@
+//! Randomized descriptor ownership tests.
+//!
+//! Drives the DMA engine through long random sequences of application calls
+//! (transmit, receive, truncating receive, in-place access, flush, reclaim)
+//! interleaved with the DMA's half of the handshake (frame delivery, TX
+//! completion) and injected errors, all against the simulated register
+//! file. After every step the engine is compared with a model of who owns
+//! each descriptor:
+//!
+//! - no descriptor leaks or is counted twice: free plus pending equals the
+//!   ring size in both directions;
+//! - the ring indices stay in range and advance by exactly the descriptors
+//!   consumed;
+//! - a received length never exceeds the caller's buffer, and the bytes
+//!   match what the DMA delivered.
+//!
+//! Sequences come from fixed seeds, so a failure reproduces exactly; the
+//! panic message names the seed and step.
+
+extern crate alloc;
+
+use alloc::collections::VecDeque;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::DmaEngine;
+use super::bits::{rdes0, tdes0};
+use crate::driver::error::{DmaError, Error, IoError};
+use crate::internal::constants::CRC_SIZE;
+use crate::internal::register::sim;
+
+/// Seeds run for each ring geometry
+const SEEDS: [u32; 6] = [
+    0x2545_F491,
+    0x9E37_79B9,
+    0x0BAD_F00D,
+    0x1234_5678,
+    0xDEAD_BEEF,
+    0x7FFF_FFFF,
+];
+
+/// Steps per seed
+const STEPS: usize = 3000;
+
+/// xorshift32
+struct Rng(u32);
+
+impl Rng {
+    fn next(&mut self) -> u32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        self.0
+    }
+
+    /// Value in `0..n`
+    fn below(&mut self, n: usize) -> usize {
+        self.next() as usize % n
+    }
+
+    /// True one time in `n`
+    fn one_in(&mut self, n: usize) -> bool {
+        self.below(n) == 0
+    }
+}
+
+/// A frame the simulated DMA delivered and the application has not taken
+struct RxFrame {
+    data: Vec<u8>,
+    descriptors: usize,
+    errored: bool,
+}
+
+/// Expected descriptor ownership
+#[derive(Default)]
+struct Model {
+    /// Delivered frames, oldest first
+    rx: VecDeque<RxFrame>,
+    /// RX descriptors handed back to the DMA since init
+    rx_consumed: usize,
+    /// TX descriptors the DMA owns
+    tx_owned: usize,
+    /// TX descriptors queued since init
+    tx_queued: usize,
+}
+
+impl Model {
+    fn rx_pending(&self) -> usize {
+        self.rx.iter().map(|f| f.descriptors).sum()
+    }
+
+    /// Take the oldest frame, its descriptors going back to the DMA
+    fn rx_pop(&mut self) -> Option<RxFrame> {
+        let frame = self.rx.pop_front()?;
+        self.rx_consumed += frame.descriptors;
+        Some(frame)
+    }
+}
+
+/// Engine under test plus everything needed to predict it
+struct Harness<const RX: usize, const TX: usize, const BUF: usize> {
+    dma: DmaEngine<RX, TX, BUF>,
+    model: Model,
+    rng: Rng,
+    reserved: usize,
+    seed: u32,
+    step: usize,
+}
+
+impl<const RX: usize, const TX: usize, const BUF: usize> Harness<RX, TX, BUF> {
+    fn new(seed: u32, dual_buffer: bool, reserved: usize) -> Self {
+        sim::reset();
+        let mut dma = DmaEngine::new();
+        dma.set_dual_buffer(dual_buffer);
+        dma.set_tx_reserved(reserved);
+        dma.init();
+        Self {
+            dma,
+            model: Model::default(),
+            rng: Rng(seed),
+            reserved,
+            seed,
+            step: 0,
+        }
+    }
+
+    /// Largest frame either ring can hold
+    fn ring_bytes(&self) -> usize {
+        self.dma.slot_capacity() * self.dma.rx_slots().max(self.dma.tx_slots())
+    }
+
+    fn run(&mut self) {
+        for step in 0..STEPS {
+            self.step = step;
+            match self.rng.below(12) {
+                0..=2 => self.deliver_rx(),
+                3..=4 => self.receive(),
+                5 => self.receive_in_place(),
+                6..=7 => self.transmit(false),
+                8 => self.transmit(true),
+                9 => self.transmit_in_place(),
+                _ => self.complete_tx(),
+            }
+            self.check();
+        }
+    }
+
+    /// DMA side: write a frame, sometimes with receive errors
+    fn deliver_rx(&mut self) {
+        let capacity = self.dma.slot_capacity();
+        // Mostly one-descriptor frames, now and then up to the whole ring
+        let max = if self.rng.one_in(4) {
+            self.dma.rx_slots() * capacity
+        } else {
+            capacity
+        } - CRC_SIZE;
+        let len = 1 + self.rng.below(max);
+        let data: Vec<u8> = (0..len).map(|_| self.rng.next() as u8).collect();
+        let errored = self.rng.one_in(8);
+        let status = if errored {
+            rdes0::ERR_SUMMARY | rdes0::CRC_ERR
+        } else {
+            0
+        };
+        let descriptors = (len + CRC_SIZE).div_ceil(capacity);
+        let fits = descriptors <= self.dma.rx_slots() - self.model.rx_pending();
+
+        let delivered = self.dma.sim_rx_frame_spanning(&data, status);
+        assert_eq!(delivered, fits, "{}: RX delivery of {len} bytes", self.at());
+        if delivered {
+            self.model.rx.push_back(RxFrame {
+                data,
+                descriptors,
+                errored,
+            });
+        }
+    }
+
+    /// Application: copy the next frame out, sometimes into a short buffer
+    /// or truncated
+    fn receive(&mut self) {
+        let mut buffer = vec![0u8; self.ring_bytes()];
+        let size = if self.rng.one_in(4) {
+            self.rng.below(buffer.len())
+        } else {
+            buffer.len()
+        };
+        let truncate = self.rng.one_in(3);
+        let result = if truncate {
+            self.dma
+                .receive_truncating(&mut buffer[..size])
+                .map(|(copied, len)| {
+                    assert_eq!(copied, len.min(size), "{}: truncated copy", self.at());
+                    copied
+                })
+        } else {
+            self.dma.receive(&mut buffer[..size])
+        };
+
+        let Some(frame) = self.model.rx.front() else {
+            assert_eq!(
+                result,
+                Err(IoError::IncompleteFrame.into()),
+                "{}: receive from an empty ring",
+                self.at()
+            );
+            return;
+        };
+        let expected = if frame.errored {
+            Err(Error::Io(IoError::FrameError))
+        } else if frame.data.len() > size && !truncate {
+            Err(Error::Io(IoError::BufferTooSmall))
+        } else {
+            Ok(frame.data.len().min(size))
+        };
+        assert_eq!(result, expected, "{}: receive", self.at());
+        if let Ok(len) = result {
+            assert!(len <= size, "{}: {len} bytes into {size}", self.at());
+            assert_eq!(buffer[..len], frame.data[..len], "{}: RX data", self.at());
+        }
+        self.model.rx_pop();
+    }
+
+    /// Application: read the next frame in place, then release it
+    fn receive_in_place(&mut self) {
+        let lent = self.dma.peek_frame_in_place().map(<[u8]>::to_vec);
+        let expected = self.model.rx.front().and_then(|frame| {
+            let single = frame.descriptors == 1
+                && frame.data.len() <= DmaEngine::<RX, TX, BUF>::DESC_BUF_SIZE;
+            (single && !frame.errored).then(|| frame.data.clone())
+        });
+        assert_eq!(lent, expected, "{}: frame lent in place", self.at());
+        self.dma.flush_rx_frame();
+        self.model.rx_pop();
+    }
+
+    /// Application: copy a frame into the TX ring
+    fn transmit(&mut self, reserved: bool) {
+        let capacity = self.dma.slot_capacity();
+        let ring = capacity * self.dma.tx_slots();
+        let len = self.rng.below(ring + capacity + 1);
+        let data: Vec<u8> = (0..len).map(|_| self.rng.next() as u8).collect();
+        let result = if reserved {
+            self.dma.transmit_reserved(&data)
+        } else {
+            self.dma.transmit(&data)
+        };
+
+        let needed = len.div_ceil(capacity);
+        let keep_free = if reserved { 0 } else { self.reserved };
+        let free = self.dma.tx_slots() - self.model.tx_owned;
+        let expected = if len == 0 {
+            Err(DmaError::InvalidLength.into())
+        } else if len > ring {
+            Err(DmaError::FrameTooLarge.into())
+        } else if free < needed + keep_free {
+            Err(DmaError::NoDescriptorsAvailable.into())
+        } else {
+            Ok(len)
+        };
+        assert_eq!(result, expected, "{}: transmit of {len} bytes", self.at());
+        if result.is_ok() {
+            self.model.tx_owned += needed;
+            self.model.tx_queued += needed;
+        }
+    }
+
+    /// Application: build a frame straight in the TX ring
+    fn transmit_in_place(&mut self) {
+        let max = DmaEngine::<RX, TX, BUF>::DESC_BUF_SIZE;
+        let len = self.rng.below(max + 8);
+        let mut filled = false;
+        let result = self.dma.transmit_in_place(len, self.reserved, |buffer| {
+            assert_eq!(buffer.len(), len);
+            filled = true;
+        });
+
+        let free = self.dma.tx_slots() - self.model.tx_owned;
+        let fits = len != 0 && len <= max && free > self.reserved;
+        assert_eq!(
+            result.is_some(),
+            fits,
+            "{}: in-place TX of {len}",
+            self.at()
+        );
+        assert_eq!(filled, fits, "{}: fill ran without a slot", self.at());
+        if fits {
+            self.model.tx_owned += 1;
+            self.model.tx_queued += 1;
+        }
+    }
+
+    /// DMA side: finish some of the oldest frames, occasionally all of
+    /// them with errors
+    fn complete_tx(&mut self) {
+        if self.rng.one_in(6) {
+            let done = self.dma.sim_tx_complete_with_errors(tdes0::UNDERFLOW_ERR);
+            assert_eq!(done, self.model.tx_owned, "{}: TX completion", self.at());
+            self.model.tx_owned = 0;
+            return;
+        }
+        let count = self.rng.below(self.model.tx_owned + 1);
+        let done = self.dma.sim_tx_complete_oldest(count);
+        assert_eq!(done, count, "{}: TX completion", self.at());
+        self.model.tx_owned -= done;
+    }
+
+    fn check(&mut self) {
+        let at = self.at();
+        let dma = &mut self.dma;
+        let model = &self.model;
+        let (rx_slots, tx_slots) = (dma.rx_slots(), dma.tx_slots());
+
+        assert_eq!(
+            dma.rx_free_count(),
+            rx_slots - model.rx_pending(),
+            "{at}: RX descriptors leaked"
+        );
+        assert_eq!(
+            dma.rx_current_index(),
+            model.rx_consumed % rx_slots,
+            "{at}: RX index"
+        );
+        assert_eq!(dma.rx_available(), !model.rx.is_empty(), "{at}: RX ready");
+
+        let tx_free = tx_slots - model.tx_owned;
+        assert_eq!(dma.tx_free(), tx_free, "{at}: TX descriptors leaked");
+        assert_eq!(
+            dma.tx_available(),
+            tx_free.saturating_sub(self.reserved),
+            "{at}: TX available"
+        );
+        assert_eq!(
+            dma.tx_current_index(),
+            model.tx_queued % tx_slots,
+            "{at}: TX index"
+        );
+        assert_eq!(dma.tx_reclaim().0, tx_free, "{at}: TX reclaim");
+    }
+
+    fn at(&self) -> String {
+        format!("seed {:#010x} step {}", self.seed, self.step)
+    }
+}
+
+fn fuzz<const RX: usize, const TX: usize, const BUF: usize>(dual_buffer: bool, reserved: usize) {
+    for seed in SEEDS {
+        Harness::<RX, TX, BUF>::new(seed, dual_buffer, reserved).run();
+    }
+}
+
+#[test]
+fn fuzz_chained_large_buffers() {
+    fuzz::<4, 4, 1024>(false, 0);
+}
+
+#[test]
+fn fuzz_chained_small_buffers() {
+    fuzz::<6, 6, 128>(false, 0);
+}
+
+#[test]
+fn fuzz_dual_buffer_frames() {
+    fuzz::<8, 8, 96>(true, 0);
+}
+
+#[test]
+fn fuzz_with_reserved_tx_descriptors() {
+    fuzz::<5, 5, 200>(false, 2);
+}
@@ -6,8 +6,14 @@
 // Allow dead code - methods reserved for future async/interrupt-driven use
 #![allow(dead_code)]
 
+#[cfg(all(test, feature = "bench"))]
+mod bench;
 mod descriptor;
 mod engine;
+#[cfg(test)]
+mod fuzz;
 mod ring;
 
+pub(crate) use descriptor::bits;
 pub use engine::DmaEngine;
+pub use ring::DescriptorRing;
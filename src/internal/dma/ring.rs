@@ -1,6 +1,34 @@
 //! Generic circular ring buffer for DMA descriptors.
+//!
+//! [`DescriptorRing`] holds `N` descriptors of any type `D` plus the index
+//! of the one software processes next. It knows nothing about ownership
+//! bits or buffer layout: the DMA engine instantiates it with the hardware
+//! RX/TX descriptor types, which stay internal, and tests instantiate it
+//! with plain values or the test-only `MockDescriptor`.
+//!
+//! Public as [`ph_esp32_mac::ring`](crate::ring).
 
 /// Circular descriptor ring with wraparound index.
+///
+/// Every index is taken modulo the ring length, so the methods that use
+/// the index (`advance`, `current`, `get`, `at_offset`, ...) panic on a
+/// zero-length ring.
+///
+/// # Example
+///
+/// ```
+/// use ph_esp32_mac::ring::DescriptorRing;
+///
+/// let mut ring = DescriptorRing::from_array([10u32, 20, 30]);
+/// ring.advance_by(2);
+/// assert_eq!(*ring.current(), 30);
+/// assert_eq!(*ring.at_offset(1), 10); // wraps
+///
+/// // Only the first two entries form the ring
+/// ring.reset();
+/// ring.advance_within(3, 2);
+/// assert_eq!(ring.current_index(), 1);
+/// ```
 pub struct DescriptorRing<D, const N: usize> {
     /// Array of descriptors
     pub(super) descriptors: [D; N],
@@ -25,7 +53,7 @@ impl<D, const N: usize> DescriptorRing<D, N> {
         N
     }
 
-    /// Check if the ring is empty (always false for fixed-size ring)
+    /// Check if the ring has no descriptors (`N == 0`)
     #[inline(always)]
     #[must_use]
     pub const fn is_empty(&self) -> bool {
@@ -51,6 +79,15 @@ impl<D, const N: usize> DescriptorRing<D, N> {
         self.current = (self.current + n) % N;
     }
 
+    /// Advance the current index by n, wrapping at `len` (at most `N`)
+    ///
+    /// Used when only the first `len` descriptors form the ring. A `len`
+    /// of zero resets the index to 0 instead of panicking.
+    #[inline(always)]
+    pub fn advance_within(&mut self, n: usize, len: usize) {
+        self.current = self.current.wrapping_add(n).checked_rem(len).unwrap_or(0);
+    }
+
     /// Reset the current index to 0
     #[inline(always)]
     pub fn reset(&mut self) {
@@ -99,6 +136,12 @@ impl<D, const N: usize> DescriptorRing<D, N> {
         self.descriptors.as_ptr() as u32
     }
 
+    /// Address of descriptor `index` as u32 (for DMA register)
+    #[inline(always)]
+    pub fn addr_u32(&self, index: usize) -> u32 {
+        self.descriptors.as_ptr().wrapping_add(index) as u32
+    }
+
     /// Iterate over all descriptors
     pub fn iter(&self) -> impl Iterator<Item = &D> {
         self.descriptors.iter()
@@ -231,6 +274,20 @@ mod tests {
         assert_eq!(ring.current_index(), 3); // (6 + 5) % 8 = 3
     }
 
+    #[test]
+    fn descriptor_ring_advance_within_shorter_ring() {
+        let mut ring = DescriptorRing::from_array([0u32; 8]);
+        ring.advance_within(2, 3);
+        assert_eq!(ring.current_index(), 2);
+        ring.advance_within(2, 3);
+        assert_eq!(ring.current_index(), 1); // (2 + 2) % 3 = 1
+        ring.advance_within(5, 8);
+        assert_eq!(ring.current_index(), 6);
+        // An empty ring does not divide by zero
+        ring.advance_within(1, 0);
+        assert_eq!(ring.current_index(), 0);
+    }
+
     #[test]
     fn descriptor_ring_reset() {
         let mut ring = DescriptorRing::from_array([0u32; 4]);
@@ -17,10 +17,30 @@
 //! or functions in this module from external code. They are subject to change
 //! without notice.
 
+/// Assert a driver invariant when the `debug-checks` feature is enabled.
+///
+/// Takes the same arguments as [`assert!`]. Without the feature the check
+/// and its arguments are compiled out entirely.
+#[cfg(any(feature = "esp32", feature = "esp32p4"))]
+macro_rules! debug_check {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "debug-checks")]
+        {
+            assert!($($arg)*);
+        }
+    };
+}
+
+// Only the constants are shared with `logic`; the rest needs a chip
+#[cfg_attr(not(any(feature = "esp32", feature = "esp32p4")), allow(dead_code))]
 pub(crate) mod constants;
+#[cfg(any(feature = "esp32", feature = "esp32p4"))]
 pub(crate) mod dma;
+#[cfg(any(feature = "esp32", feature = "esp32p4"))]
 pub(crate) mod gpio_pins;
+#[cfg(any(feature = "esp32", feature = "esp32p4"))]
 pub(crate) mod phy_regs;
+#[cfg(any(feature = "esp32", feature = "esp32p4"))]
 pub(crate) mod register;
 
 // Register types are accessed via submodules: register::dma::DmaRegs, etc.
@@ -44,8 +44,6 @@ pub mod phy_id {
 
 /// Internal timing constants
 pub mod timing {
-    /// Maximum reset attempts
-    pub const RESET_MAX_ATTEMPTS: u32 = 1000;
     /// Maximum auto-negotiation polling iterations
     pub const AN_MAX_ATTEMPTS: u32 = 5000;
     /// Hardware reset pulse duration in microseconds (minimum 100µs per datasheet)
@@ -114,13 +112,13 @@ pub mod smr {
 
 /// Special Control/Status Indication Register (27) bits
 pub mod scsir {
-    /// AMDIXCTRL - Auto-MDIX control
+    /// AMDIXCTRL - Disable Auto-MDIX and use CH_SELECT
     pub const AMDIXCTRL: u16 = 1 << 15;
-    /// CH_SELECT - Manual crossover (when AMDIXCTRL=1)
+    /// CH_SELECT - Manual crossover: 0 = MDI, 1 = MDI-X (when AMDIXCTRL=1)
     pub const CH_SELECT: u16 = 1 << 13;
     /// SQEOFF - Disable SQE test
     pub const SQEOFF: u16 = 1 << 11;
-    /// XPOL - Invert polarity (10BASE-T only)
+    /// XPOL - 10BASE-T polarity reversed (read-only)
     pub const XPOL: u16 = 1 << 4;
 }
 
@@ -127,6 +127,8 @@ pub mod anar {
     pub const ACK: u16 = 1 << 14;
     /// Remote fault
     pub const REMOTE_FAULT: u16 = 1 << 13;
+    /// Asymmetric pause
+    pub const PAUSE_ASYM: u16 = 1 << 11;
     /// Pause capable
     pub const PAUSE: u16 = 1 << 10;
     /// 100BASE-T4 advertised
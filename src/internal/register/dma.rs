@@ -3,7 +3,13 @@
 //! The EMAC DMA controller manages data transfers between the MAC and system memory
 //! using descriptor-based scatter-gather DMA.
 
-use super::{DMA_BASE, read_reg, reg_bit_check_clear, reg_bit_ops, reg_ro, reg_rw, write_reg};
+use super::{
+    DMA_BASE, read_reg, reg_bit_check_clear, reg_bit_ops, reg_fields, reg_ro, reg_rw, reg_typed,
+    write_reg,
+};
+use crate::internal::constants::{
+    RX_DMA_STATE_MASK, RX_DMA_STATE_SHIFT, TX_DMA_STATE_MASK, TX_DMA_STATE_SHIFT,
+};
 
 // =============================================================================
 // Register Offsets
@@ -108,13 +114,13 @@ pub const DMASTATUS_AIS: u32 = 1 << 15;
 /// Normal Interrupt Summary
 pub const DMASTATUS_NIS: u32 = 1 << 16;
 /// Receive Process State shift
-pub const DMASTATUS_RS_SHIFT: u32 = 17;
+pub const DMASTATUS_RS_SHIFT: u32 = RX_DMA_STATE_SHIFT;
 /// Receive Process State mask
-pub const DMASTATUS_RS_MASK: u32 = 0x7 << 17;
+pub const DMASTATUS_RS_MASK: u32 = RX_DMA_STATE_MASK << RX_DMA_STATE_SHIFT;
 /// Transmit Process State shift
-pub const DMASTATUS_TS_SHIFT: u32 = 20;
+pub const DMASTATUS_TS_SHIFT: u32 = TX_DMA_STATE_SHIFT;
 /// Transmit Process State mask
-pub const DMASTATUS_TS_MASK: u32 = 0x7 << 20;
+pub const DMASTATUS_TS_MASK: u32 = TX_DMA_STATE_MASK << TX_DMA_STATE_SHIFT;
 /// Error Bits shift (type of bus error)
 pub const DMASTATUS_EB_SHIFT: u32 = 23;
 /// Error Bits mask
@@ -137,6 +143,21 @@ pub const DMASTATUS_ALL_INTERRUPTS: u32 = DMASTATUS_TI
     | DMASTATUS_AIS
     | DMASTATUS_NIS;
 
+/// Status bits summarized by NIS
+pub const DMASTATUS_NORMAL_INTERRUPTS: u32 =
+    DMASTATUS_TI | DMASTATUS_TU | DMASTATUS_RI | DMASTATUS_ERI;
+
+/// Status bits summarized by AIS
+pub const DMASTATUS_ABNORMAL_INTERRUPTS: u32 = DMASTATUS_TPS
+    | DMASTATUS_TJT
+    | DMASTATUS_OVF
+    | DMASTATUS_UNF
+    | DMASTATUS_RU
+    | DMASTATUS_RPS
+    | DMASTATUS_RWT
+    | DMASTATUS_ETI
+    | DMASTATUS_FBI;
+
 // =============================================================================
 // Operation Mode Register (DMAOPERATION) Bits
 // =============================================================================
@@ -241,6 +262,139 @@ pub const DMAINTEN_NIE: u32 = 1 << 16;
 pub const DMAINTEN_DEFAULT: u32 =
     DMAINTEN_TIE | DMAINTEN_RIE | DMAINTEN_FBE | DMAINTEN_AIE | DMAINTEN_NIE;
 
+// =============================================================================
+// Typed Register Views
+// =============================================================================
+
+reg_fields! {
+    /// Bus Mode register (DMABUSMODE) fields
+    pub struct DmaBusMode;
+    flags {
+        /// Software reset
+        software_reset, with_software_reset = DMABUSMODE_SW_RST;
+        /// Fixed-priority arbitration
+        fixed_arbitration, with_fixed_arbitration = DMABUSMODE_DMA_ARB;
+        /// Alternate (8-word) descriptor size
+        alt_desc_size, with_alt_desc_size = DMABUSMODE_ATDS;
+        /// Fixed burst
+        fixed_burst, with_fixed_burst = DMABUSMODE_FB;
+        /// Use separate RX PBL
+        separate_pbl, with_separate_pbl = DMABUSMODE_USP;
+        /// Multiply PBL/RPBL by 8
+        pbl_x8, with_pbl_x8 = DMABUSMODE_PBL_X8;
+        /// Address-aligned beats
+        address_aligned, with_address_aligned = DMABUSMODE_AAL;
+        /// Mixed burst
+        mixed_burst, with_mixed_burst = DMABUSMODE_MB;
+        /// TX has priority over RX
+        tx_priority, with_tx_priority = DMABUSMODE_TXPR;
+    }
+    fields {
+        /// Descriptor skip length in 32-bit words
+        desc_skip_len, with_desc_skip_len = DMABUSMODE_DSL_MASK, DMABUSMODE_DSL_SHIFT;
+        /// Programmable burst length (TX, or both when `separate_pbl` is clear)
+        burst_len, with_burst_len = DMABUSMODE_PBL_MASK, DMABUSMODE_PBL_SHIFT;
+        /// RX programmable burst length
+        rx_burst_len, with_rx_burst_len = DMABUSMODE_RPBL_MASK, DMABUSMODE_RPBL_SHIFT;
+    }
+}
+
+reg_fields! {
+    /// Operation Mode register (DMAOPERATION) fields
+    pub struct DmaOperation;
+    flags {
+        /// Start receive DMA
+        start_rx, with_start_rx = DMAOPERATION_SR;
+        /// Operate on second frame
+        second_frame, with_second_frame = DMAOPERATION_OSF;
+        /// Forward undersized good frames
+        forward_undersized, with_forward_undersized = DMAOPERATION_FUF;
+        /// Forward error frames
+        forward_errors, with_forward_errors = DMAOPERATION_FEF;
+        /// Start transmit DMA
+        start_tx, with_start_tx = DMAOPERATION_ST;
+        /// Flush transmit FIFO
+        flush_tx_fifo, with_flush_tx_fifo = DMAOPERATION_FTF;
+        /// Transmit store and forward
+        tx_store_forward, with_tx_store_forward = DMAOPERATION_TSF;
+        /// Disable flushing of received frames
+        disable_rx_flush, with_disable_rx_flush = DMAOPERATION_DFF;
+        /// Receive store and forward
+        rx_store_forward, with_rx_store_forward = DMAOPERATION_RSF;
+        /// Keep frames with TCP/IP checksum errors
+        keep_checksum_errors, with_keep_checksum_errors = DMAOPERATION_DT;
+    }
+    fields {
+        /// Receive threshold (see [`rtc`])
+        rx_threshold, with_rx_threshold = DMAOPERATION_RTC_MASK, DMAOPERATION_RTC_SHIFT;
+        /// Transmit threshold (see [`ttc`])
+        tx_threshold, with_tx_threshold = DMAOPERATION_TTC_MASK, DMAOPERATION_TTC_SHIFT;
+    }
+}
+
+reg_fields! {
+    /// Status register (DMASTATUS) fields
+    ///
+    /// Interrupt bits are write-1-to-clear; write back only the bits to clear.
+    pub struct DmaStatus;
+    flags {
+        /// Transmit interrupt
+        tx_interrupt, with_tx_interrupt = DMASTATUS_TI;
+        /// Transmit process stopped
+        tx_stopped, with_tx_stopped = DMASTATUS_TPS;
+        /// Transmit buffer unavailable
+        tx_buf_unavailable, with_tx_buf_unavailable = DMASTATUS_TU;
+        /// Transmit jabber timeout
+        tx_jabber_timeout, with_tx_jabber_timeout = DMASTATUS_TJT;
+        /// Receive overflow
+        rx_overflow, with_rx_overflow = DMASTATUS_OVF;
+        /// Transmit underflow
+        tx_underflow, with_tx_underflow = DMASTATUS_UNF;
+        /// Receive interrupt
+        rx_interrupt, with_rx_interrupt = DMASTATUS_RI;
+        /// Receive buffer unavailable
+        rx_buf_unavailable, with_rx_buf_unavailable = DMASTATUS_RU;
+        /// Receive process stopped
+        rx_stopped, with_rx_stopped = DMASTATUS_RPS;
+        /// Receive watchdog timeout
+        rx_watchdog_timeout, with_rx_watchdog_timeout = DMASTATUS_RWT;
+        /// Early transmit interrupt
+        early_tx, with_early_tx = DMASTATUS_ETI;
+        /// Fatal bus error
+        fatal_bus_error, with_fatal_bus_error = DMASTATUS_FBI;
+        /// Early receive interrupt
+        early_rx, with_early_rx = DMASTATUS_ERI;
+        /// Abnormal interrupt summary
+        abnormal_summary, with_abnormal_summary = DMASTATUS_AIS;
+        /// Normal interrupt summary
+        normal_summary, with_normal_summary = DMASTATUS_NIS;
+    }
+    fields {
+        /// Receive process state (see [`RxProcessState`])
+        rx_state_bits, with_rx_state_bits = DMASTATUS_RS_MASK, DMASTATUS_RS_SHIFT;
+        /// Transmit process state (see [`TxProcessState`])
+        tx_state_bits, with_tx_state_bits = DMASTATUS_TS_MASK, DMASTATUS_TS_SHIFT;
+        /// Bus error type
+        error_bits, with_error_bits = DMASTATUS_EB_MASK, DMASTATUS_EB_SHIFT;
+    }
+}
+
+impl DmaStatus {
+    /// Receive process state
+    #[inline(always)]
+    #[must_use]
+    pub fn rx_state(self) -> RxProcessState {
+        RxProcessState::from(self.bits())
+    }
+
+    /// Transmit process state
+    #[inline(always)]
+    #[must_use]
+    pub fn tx_state(self) -> TxProcessState {
+        TxProcessState::from(self.bits())
+    }
+}
+
 // =============================================================================
 // DMA Register Access Functions
 // =============================================================================
@@ -280,6 +434,33 @@ impl DmaRegs {
         DMAOPERATION_OFFSET,
         "Operation Mode register"
     );
+    reg_typed!(
+        bus_mode_fields,
+        set_bus_mode_fields,
+        modify_bus_mode_fields,
+        DMA_BASE,
+        DMABUSMODE_OFFSET,
+        DmaBusMode,
+        "Bus Mode register"
+    );
+    reg_typed!(
+        status_fields,
+        set_status_fields,
+        modify_status_fields,
+        DMA_BASE,
+        DMASTATUS_OFFSET,
+        DmaStatus,
+        "Status register"
+    );
+    reg_typed!(
+        operation_mode_fields,
+        set_operation_mode_fields,
+        modify_operation_mode_fields,
+        DMA_BASE,
+        DMAOPERATION_OFFSET,
+        DmaOperation,
+        "Operation Mode register"
+    );
     reg_rw!(
         interrupt_enable,
         set_interrupt_enable,
@@ -366,6 +547,8 @@ impl DmaRegs {
     /// Issue TX poll demand (wake up TX DMA)
     #[inline(always)]
     pub fn tx_poll_demand() {
+        // Descriptor ownership changes must land before the DMA is woken
+        core::sync::atomic::fence(core::sync::atomic::Ordering::Release);
         // SAFETY: DMA register address is valid for this SoC.
         unsafe { write_reg(DMA_BASE + DMATXPOLLDEMAND_OFFSET, 0) }
     }
@@ -373,6 +556,8 @@ impl DmaRegs {
     /// Issue RX poll demand (wake up RX DMA)
     #[inline(always)]
     pub fn rx_poll_demand() {
+        // Descriptor ownership changes must land before the DMA is woken
+        core::sync::atomic::fence(core::sync::atomic::Ordering::Release);
         // SAFETY: DMA register address is valid for this SoC.
         unsafe { write_reg(DMA_BASE + DMARXPOLLDEMAND_OFFSET, 0) }
     }
@@ -391,12 +576,68 @@ impl DmaRegs {
         unsafe { write_reg(DMA_BASE + DMATXBASEADDR_OFFSET, addr) }
     }
 
+    /// RX descriptor list base address
+    #[inline(always)]
+    pub fn rx_desc_list_addr() -> u32 {
+        // SAFETY: DMA register address is valid for this SoC.
+        unsafe { read_reg(DMA_BASE + DMARXBASEADDR_OFFSET) }
+    }
+
+    /// TX descriptor list base address
+    #[inline(always)]
+    pub fn tx_desc_list_addr() -> u32 {
+        // SAFETY: DMA register address is valid for this SoC.
+        unsafe { read_reg(DMA_BASE + DMATXBASEADDR_OFFSET) }
+    }
+
     /// Clear all interrupt status flags
     #[inline(always)]
     pub fn clear_all_interrupts() {
         Self::set_status(DMASTATUS_ALL_INTERRUPTS);
     }
 
+    iram! {
+        /// Acknowledge interrupt status until no interrupt bit is left set
+        ///
+        /// Each round clears exactly the event bits it read, then clears NIS
+        /// and AIS only if no bit of their group is still set, so a summary is
+        /// never cleared under a pending event. An event that arrives between
+        /// the read and the write is picked up by the next round rather than
+        /// left set without its summary. After `max_rounds` anything still set
+        /// stays pending, and keeps the interrupt asserted, for the next call.
+        ///
+        /// Returns every status bit that was acknowledged.
+        pub fn acknowledge_interrupts(max_rounds: usize) -> u32 {
+            let mut seen = 0;
+            for _ in 0..max_rounds {
+                let status = Self::status() & DMASTATUS_ALL_INTERRUPTS;
+                if status == 0 {
+                    break;
+                }
+                seen |= status;
+
+                let events = status & !(DMASTATUS_NIS | DMASTATUS_AIS);
+                if events != 0 {
+                    Self::set_status(events);
+                }
+
+                let after = Self::status();
+                let mut summary = after & (DMASTATUS_NIS | DMASTATUS_AIS);
+                if after & DMASTATUS_NORMAL_INTERRUPTS != 0 {
+                    summary &= !DMASTATUS_NIS;
+                }
+                if after & DMASTATUS_ABNORMAL_INTERRUPTS != 0 {
+                    summary &= !DMASTATUS_AIS;
+                }
+                if summary != 0 {
+                    seen |= summary;
+                    Self::set_status(summary);
+                }
+            }
+            seen
+        }
+    }
+
     /// Flush TX FIFO
     #[inline(always)]
     pub fn flush_tx_fifo() {
@@ -433,8 +674,9 @@ impl DmaRegs {
     }
 }
 
-/// Receive process states
+/// Receive DMA process state (DMA status register RS field)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[repr(u8)]
 pub enum RxProcessState {
     /// Stopped: Reset or Stop Receive Command issued
@@ -455,9 +697,11 @@ pub enum RxProcessState {
     TransferringData = 7,
 }
 
-impl From<u32> for RxProcessState {
-    fn from(value: u32) -> Self {
-        match (value & DMASTATUS_RS_MASK) >> DMASTATUS_RS_SHIFT {
+impl RxProcessState {
+    /// Decode from a raw DMA status register value
+    #[must_use]
+    pub const fn from_status(status: u32) -> Self {
+        match (status & DMASTATUS_RS_MASK) >> DMASTATUS_RS_SHIFT {
             0 => RxProcessState::Stopped,
             1 => RxProcessState::FetchingDescriptor,
             2 => RxProcessState::Reserved2,
@@ -468,10 +712,40 @@ impl From<u32> for RxProcessState {
             _ => RxProcessState::TransferringData,
         }
     }
+
+    /// Short description
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            RxProcessState::Stopped => "stopped",
+            RxProcessState::FetchingDescriptor => "fetching descriptor",
+            RxProcessState::WaitingForPacket => "waiting for frame",
+            RxProcessState::Suspended => "suspended",
+            RxProcessState::ClosingDescriptor => "closing descriptor",
+            RxProcessState::TransferringData => "transferring data",
+            RxProcessState::Reserved2 | RxProcessState::Reserved6 => "reserved",
+        }
+    }
+}
+
+impl From<u32> for RxProcessState {
+    fn from(value: u32) -> Self {
+        Self::from_status(value)
+    }
 }
 
-/// Transmit process states
+impl core::fmt::Display for RxProcessState {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Reserved2 | Self::Reserved6 => write!(f, "reserved ({})", *self as u8),
+            _ => f.write_str(self.as_str()),
+        }
+    }
+}
+
+/// Transmit DMA process state (DMA status register TS field)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[repr(u8)]
 pub enum TxProcessState {
     /// Stopped: Reset or Stop Transmit Command issued
@@ -492,9 +766,11 @@ pub enum TxProcessState {
     ClosingDescriptor = 7,
 }
 
-impl From<u32> for TxProcessState {
-    fn from(value: u32) -> Self {
-        match (value & DMASTATUS_TS_MASK) >> DMASTATUS_TS_SHIFT {
+impl TxProcessState {
+    /// Decode from a raw DMA status register value
+    #[must_use]
+    pub const fn from_status(status: u32) -> Self {
+        match (status & DMASTATUS_TS_MASK) >> DMASTATUS_TS_SHIFT {
             0 => TxProcessState::Stopped,
             1 => TxProcessState::FetchingDescriptor,
             2 => TxProcessState::WaitingForStatus,
@@ -505,4 +781,131 @@ impl From<u32> for TxProcessState {
             _ => TxProcessState::ClosingDescriptor,
         }
     }
+
+    /// Short description
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            TxProcessState::Stopped => "stopped",
+            TxProcessState::FetchingDescriptor => "fetching descriptor",
+            TxProcessState::WaitingForStatus => "waiting for status",
+            TxProcessState::ReadingData => "reading data",
+            TxProcessState::Suspended => "suspended",
+            TxProcessState::ClosingDescriptor => "closing descriptor",
+            TxProcessState::Reserved4 | TxProcessState::Reserved5 => "reserved",
+        }
+    }
+}
+
+impl From<u32> for TxProcessState {
+    fn from(value: u32) -> Self {
+        Self::from_status(value)
+    }
+}
+
+impl core::fmt::Display for TxProcessState {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Reserved4 | Self::Reserved5 => write!(f, "reserved ({})", *self as u8),
+            _ => f.write_str(self.as_str()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::internal::register::sim;
+
+    const STATUS: usize = DMA_BASE + DMASTATUS_OFFSET;
+
+    fn sim_status(bits: u32) {
+        sim::reset();
+        sim::write_one_to_clear(STATUS);
+        sim::raise(STATUS, bits);
+    }
+
+    #[test]
+    fn bus_mode_fields_match_raw_bits() {
+        let bus = DmaBusMode::default()
+            .with_fixed_burst(true)
+            .with_alt_desc_size(true)
+            .with_burst_len(32)
+            .with_rx_burst_len(16);
+        assert_eq!(
+            bus.bits(),
+            DMABUSMODE_FB
+                | DMABUSMODE_ATDS
+                | (32 << DMABUSMODE_PBL_SHIFT)
+                | (16 << DMABUSMODE_RPBL_SHIFT)
+        );
+        assert_eq!(bus.burst_len(), 32);
+        assert_eq!(bus.rx_burst_len(), 16);
+        assert_eq!(bus.desc_skip_len(), 0);
+    }
+
+    #[test]
+    fn operation_mode_thresholds() {
+        let op = DmaOperation::from_bits(DMAOPERATION_TSF)
+            .with_rx_threshold(rtc::RTC_128)
+            .with_tx_threshold(ttc::TTC_16);
+        assert!(op.tx_store_forward());
+        assert!(!op.start_tx());
+        assert_eq!(op.rx_threshold(), rtc::RTC_128);
+        assert_eq!(op.tx_threshold(), ttc::TTC_16);
+
+        let op = op.with_start_tx(true).with_start_rx(true);
+        assert_eq!(
+            op.bits() & (DMAOPERATION_ST | DMAOPERATION_SR),
+            DMAOPERATION_ST | DMAOPERATION_SR
+        );
+    }
+
+    #[test]
+    fn status_process_states() {
+        let status = DmaStatus::from_bits(
+            DMASTATUS_RI | DMASTATUS_NIS | (3 << DMASTATUS_RS_SHIFT) | (6 << DMASTATUS_TS_SHIFT),
+        );
+        assert!(status.rx_interrupt());
+        assert!(status.normal_summary());
+        assert!(!status.fatal_bus_error());
+        assert_eq!(status.rx_state(), RxProcessState::WaitingForPacket);
+        assert_eq!(status.tx_state(), TxProcessState::Suspended);
+    }
+
+    #[test]
+    fn acknowledge_picks_up_event_raised_before_the_write() {
+        sim_status(DMASTATUS_RI | DMASTATUS_NIS);
+        sim::raise_on_read(STATUS, 1, DMASTATUS_TI | DMASTATUS_NIS);
+
+        let seen = DmaRegs::acknowledge_interrupts(4);
+        assert_eq!(seen, DMASTATUS_RI | DMASTATUS_TI | DMASTATUS_NIS);
+        assert_eq!(DmaRegs::status(), 0);
+        assert_eq!(DmaRegs::acknowledge_interrupts(4), 0);
+    }
+
+    #[test]
+    fn acknowledge_picks_up_event_whose_summary_it_cleared() {
+        sim_status(DMASTATUS_RI | DMASTATUS_NIS);
+        // Lands after the re-read, so the NIS write clears its summary
+        sim::raise_on_read(STATUS, 2, DMASTATUS_TI | DMASTATUS_NIS);
+
+        let seen = DmaRegs::acknowledge_interrupts(4);
+        assert_eq!(seen & DMASTATUS_TI, DMASTATUS_TI);
+        assert_eq!(DmaRegs::status(), 0);
+    }
+
+    #[test]
+    fn summary_stays_set_while_its_group_is_pending() {
+        sim_status(DMASTATUS_RI | DMASTATUS_NIS | DMASTATUS_RU | DMASTATUS_AIS);
+        sim::raise_on_read(STATUS, 1, DMASTATUS_TI | DMASTATUS_NIS);
+
+        // Out of rounds with TI pending: NIS must survive, AIS may go
+        let seen = DmaRegs::acknowledge_interrupts(1);
+        assert_eq!(
+            seen,
+            DMASTATUS_RI | DMASTATUS_NIS | DMASTATUS_RU | DMASTATUS_AIS
+        );
+        assert_eq!(DmaRegs::status(), DMASTATUS_TI | DMASTATUS_NIS);
+    }
 }
@@ -10,9 +10,9 @@
 //! - pd_sel         (0x10): Power down select (RAM power down)
 
 use super::{
-    DPORT_WIFI_CLK_EMAC_EN, DPORT_WIFI_CLK_EN_REG, EXT_BASE, IO_MUX_BASE, IO_MUX_FUN_IE,
-    IO_MUX_GPIO0_FUNC_EMAC_TX_CLK, IO_MUX_GPIO0_OFFSET, IO_MUX_MCU_SEL_MASK, IO_MUX_MCU_SEL_SHIFT,
-    read_reg, reg_ro, reg_rw, write_reg,
+    DPORT_CORE_RST_EN_REG, DPORT_EMAC_RST, DPORT_WIFI_CLK_EMAC_EN, DPORT_WIFI_CLK_EN_REG, EXT_BASE,
+    IO_MUX_BASE, IO_MUX_FUN_IE, IO_MUX_GPIO0_FUNC_EMAC_TX_CLK, IO_MUX_GPIO0_OFFSET,
+    IO_MUX_MCU_SEL_MASK, IO_MUX_MCU_SEL_SHIFT, read_reg, reg_ro, reg_rw, write_reg,
 };
 
 // =============================================================================
@@ -213,6 +213,40 @@ impl ExtRegs {
         }
     }
 
+    /// Disable EMAC peripheral clock at system level (DPORT)
+    ///
+    /// EMAC registers are inaccessible afterwards until
+    /// `enable_peripheral_clock()` is called again.
+    #[inline(always)]
+    pub fn disable_peripheral_clock() {
+        // SAFETY: DPORT register address is valid for this SoC.
+        unsafe {
+            let current = read_reg(DPORT_WIFI_CLK_EN_REG);
+            write_reg(DPORT_WIFI_CLK_EN_REG, current & !DPORT_WIFI_CLK_EMAC_EN);
+        }
+    }
+
+    /// True if the EMAC enable bit in DPORT `WIFI_CLK_EN` is set
+    #[inline(always)]
+    pub fn is_peripheral_clock_enabled() -> bool {
+        // SAFETY: DPORT register address is valid for this SoC.
+        unsafe { read_reg(DPORT_WIFI_CLK_EN_REG) & DPORT_WIFI_CLK_EMAC_EN != 0 }
+    }
+
+    /// Pulse the EMAC module reset in DPORT
+    ///
+    /// Returns every DMA, MAC and extension register to its power-on value.
+    /// The peripheral clock must be enabled for the reset to take effect.
+    #[inline(always)]
+    pub fn reset_peripheral() {
+        // SAFETY: DPORT register address is valid for this SoC.
+        unsafe {
+            let current = read_reg(DPORT_CORE_RST_EN_REG);
+            write_reg(DPORT_CORE_RST_EN_REG, current | DPORT_EMAC_RST);
+            write_reg(DPORT_CORE_RST_EN_REG, current & !DPORT_EMAC_RST);
+        }
+    }
+
     /// Enable EMAC clocks (extension register clocks)
     ///
     /// Note: `enable_peripheral_clock()` must be called first to enable
@@ -386,6 +420,21 @@ impl ExtRegs {
         }
     }
 
+    /// Set the output clock delay (`dly_num`, 0-3)
+    ///
+    /// Shifts the phase of the internally generated RMII reference clock.
+    /// Has no effect with an external clock.
+    #[inline(always)]
+    pub fn set_clkout_delay(delay: u32) {
+        // SAFETY: Extension register addresses are valid for this SoC.
+        unsafe {
+            let clkout = read_reg(EXT_BASE + EX_CLKOUT_CONF_OFFSET);
+            let new_clkout = (clkout & !EX_CLKOUT_DLY_NUM_MASK)
+                | ((delay << EX_CLKOUT_DLY_NUM_SHIFT) & EX_CLKOUT_DLY_NUM_MASK);
+            write_reg(EXT_BASE + EX_CLKOUT_CONF_OFFSET, new_clkout);
+        }
+    }
+
     // -------------------------------------------------------------------------
     // Power management helpers
     // -------------------------------------------------------------------------
@@ -108,10 +108,16 @@ pub const IO_MUX_MCU_SEL_MASK: u32 = 0x7 << 12;
 /// IO_MUX function value for GPIO Matrix routing
 pub const IO_MUX_FUNC_GPIO: u32 = 2;
 
+/// IO_MUX weak pull-down enable (bit 7)
+pub const IO_MUX_FUN_WPD: u32 = 1 << 7;
+
+/// IO_MUX weak pull-up enable (bit 8)
+pub const IO_MUX_FUN_WPU: u32 = 1 << 8;
+
 /// IO_MUX input enable (bit 9)
 pub const IO_MUX_FUN_IE: u32 = 1 << 9;
 
-/// IO_MUX output enable (bit 8) - for some GPIOs, need to check
+/// IO_MUX pad drive strength field (bits 11:10)
 pub const IO_MUX_FUN_DRV_SHIFT: u32 = 10;
 pub const IO_MUX_FUN_DRV_MASK: u32 = 0x3 << 10;
 
@@ -260,22 +266,40 @@ impl GpioMatrix {
     /// | CRS_DV   | 27   | Input     |
     ///
     /// This function MUST be called during EMAC initialization for packet TX/RX to work.
+    /// TX pads get maximum drive strength and RX pads are left floating; use
+    /// [`configure_rmii_pins_with`](Self::configure_rmii_pins_with) to change that.
     pub fn configure_rmii_pins() {
+        Self::configure_rmii_pins_with(3, 0);
+    }
+
+    /// Configure RMII data pins via IO_MUX with explicit pad settings
+    ///
+    /// # Arguments
+    /// * `tx_drive` - `FUN_DRV` value for the TX pads (0 = ~5 mA .. 3 = ~40 mA)
+    /// * `rx_pull` - `0`, [`IO_MUX_FUN_WPU`] or [`IO_MUX_FUN_WPD`] for the RX pads
+    ///
+    /// The ESP32 IO_MUX has no input filter or Schmitt trigger control;
+    /// drive strength and weak pulls are the only pad settings available.
+    pub fn configure_rmii_pins_with(tx_drive: u32, rx_pull: u32) {
         // EMAC function is function 5 for all RMII pins on ESP32
         const EMAC_FUNC: u32 = 5;
 
         // TX pins (output)
-        Self::configure_iomux_output(19, EMAC_FUNC); // TXD0
-        Self::configure_iomux_output(22, EMAC_FUNC); // TXD1
-        Self::configure_iomux_output(21, EMAC_FUNC); // TX_EN
+        Self::configure_iomux_output(19, EMAC_FUNC, tx_drive); // TXD0
+        Self::configure_iomux_output(22, EMAC_FUNC, tx_drive); // TXD1
+        Self::configure_iomux_output(21, EMAC_FUNC, tx_drive); // TX_EN
 
         // RX pins (input)
-        Self::configure_iomux_input(25, EMAC_FUNC); // RXD0
-        Self::configure_iomux_input(26, EMAC_FUNC); // RXD1
-        Self::configure_iomux_input(27, EMAC_FUNC); // CRS_DV
+        Self::configure_iomux_input(25, EMAC_FUNC, rx_pull); // RXD0
+        Self::configure_iomux_input(26, EMAC_FUNC, rx_pull); // RXD1
+        Self::configure_iomux_input(27, EMAC_FUNC, rx_pull); // CRS_DV
 
         #[cfg(feature = "defmt")]
-        defmt::info!("RMII data pins configured via IO_MUX (function 5)");
+        defmt::info!(
+            "RMII data pins configured via IO_MUX (function 5, drv={}, pull={:#x})",
+            tx_drive,
+            rx_pull
+        );
     }
 
     /// Configure a GPIO as IO_MUX output for EMAC
@@ -283,7 +307,7 @@ impl GpioMatrix {
     /// For IO_MUX peripheral functions, we ONLY set the MCU_SEL field.
     /// The peripheral itself controls the output enable - we should NOT
     /// manipulate GPIO_ENABLE registers as that's for GPIO Matrix mode.
-    fn configure_iomux_output(gpio_num: u8, func: u32) {
+    fn configure_iomux_output(gpio_num: u8, func: u32, drive: u32) {
         let iomux_addr = Self::iomux_addr_for_gpio(gpio_num);
         if iomux_addr == 0 {
             return;
@@ -295,15 +319,15 @@ impl GpioMatrix {
             // Set MCU_SEL field to specified function
             // Clear pull-up/pull-down (bits 7, 8)
             // For outputs, we still set FUN_IE=0 (bit 9) since it's output only
-            // Also set FUN_DRV (bits 10-11) to maximum drive strength (3)
+            // Also set FUN_DRV (bits 10-11) to the requested drive strength
             let new_val = (current
                 & !IO_MUX_MCU_SEL_MASK
-                & !(1 << 7)
-                & !(1 << 8)
+                & !IO_MUX_FUN_WPD
+                & !IO_MUX_FUN_WPU
                 & !IO_MUX_FUN_IE
-                & !(3 << 10))
+                & !IO_MUX_FUN_DRV_MASK)
                 | (func << IO_MUX_MCU_SEL_SHIFT)
-                | (3 << 10); // Maximum drive strength
+                | ((drive << IO_MUX_FUN_DRV_SHIFT) & IO_MUX_FUN_DRV_MASK);
             write_reg(iomux_addr, new_val);
 
             // Disconnect GPIO Matrix output by setting output signal to SIG_GPIO_OUT_IDX (256)
@@ -317,7 +341,7 @@ impl GpioMatrix {
     ///
     /// For IO_MUX peripheral functions, we set MCU_SEL and enable input.
     /// The GPIO Matrix is bypassed by using the IO_MUX function directly.
-    fn configure_iomux_input(gpio_num: u8, func: u32) {
+    fn configure_iomux_input(gpio_num: u8, func: u32, pull: u32) {
         let iomux_addr = Self::iomux_addr_for_gpio(gpio_num);
         if iomux_addr == 0 {
             return;
@@ -328,9 +352,10 @@ impl GpioMatrix {
             let current = read_reg(iomux_addr);
             // Set MCU_SEL field to specified function
             // Enable input (bit 9) - critical for receiving data
-            // Clear pull-up/pull-down (bits 7, 8) for floating input
-            let new_val = (current & !IO_MUX_MCU_SEL_MASK & !(1 << 7) & !(1 << 8))
+            // Replace pull-up/pull-down (bits 7, 8) with the requested pull
+            let new_val = (current & !IO_MUX_MCU_SEL_MASK & !IO_MUX_FUN_WPD & !IO_MUX_FUN_WPU)
                 | (func << IO_MUX_MCU_SEL_SHIFT)
+                | (pull & (IO_MUX_FUN_WPD | IO_MUX_FUN_WPU))
                 | IO_MUX_FUN_IE; // Enable input
             write_reg(iomux_addr, new_val);
 
@@ -343,6 +368,24 @@ impl GpioMatrix {
         }
     }
 
+    /// Set the IO_MUX pad drive strength of a GPIO (0 = ~5 mA .. 3 = ~40 mA)
+    ///
+    /// Leaves the pin function untouched. Unsupported GPIOs are ignored.
+    pub fn set_drive_strength(gpio_num: u8, strength: u32) {
+        let iomux_addr = Self::iomux_addr_for_gpio(gpio_num);
+        if iomux_addr == 0 {
+            return;
+        }
+
+        // SAFETY: Accesses fixed ESP32 IOMUX registers via volatile reads/writes.
+        unsafe {
+            let current = read_reg(iomux_addr);
+            let new_val = (current & !IO_MUX_FUN_DRV_MASK)
+                | ((strength << IO_MUX_FUN_DRV_SHIFT) & IO_MUX_FUN_DRV_MASK);
+            write_reg(iomux_addr, new_val);
+        }
+    }
+
     /// Get IO_MUX register address for a GPIO
     ///
     /// Returns 0 if GPIO is not supported
@@ -2,7 +2,10 @@
 //!
 //! The MAC core handles frame transmission and reception according to IEEE 802.3.
 
-use super::{MAC_BASE, read_reg, reg_bit_ops, reg_ro, reg_rw, write_reg};
+use super::{
+    MAC_BASE, read_reg, reg_bit_ops, reg_fields, reg_ro, reg_rw, reg_typed, with_register_lock,
+    write_reg,
+};
 
 // =============================================================================
 // Register Offsets
@@ -218,6 +221,102 @@ pub mod pcf {
     pub const FILTERED: u32 = 3;
 }
 
+// =============================================================================
+// Typed Register Views
+// =============================================================================
+
+reg_fields! {
+    /// GMAC Configuration register (GMACCONFIG) fields
+    pub struct GmacConfig;
+    flags {
+        /// Receiver enable
+        rx_enable, with_rx_enable = GMACCONFIG_RE;
+        /// Transmitter enable
+        tx_enable, with_tx_enable = GMACCONFIG_TE;
+        /// Deferral check
+        deferral_check, with_deferral_check = GMACCONFIG_DC;
+        /// Automatic pad/CRC stripping
+        auto_pad_crc_strip, with_auto_pad_crc_strip = GMACCONFIG_ACS;
+        /// Link up/down
+        link_up, with_link_up = GMACCONFIG_LUD;
+        /// Disable retry
+        disable_retry, with_disable_retry = GMACCONFIG_DR;
+        /// Checksum offload
+        checksum_offload, with_checksum_offload = GMACCONFIG_IPC;
+        /// Full duplex mode
+        full_duplex, with_full_duplex = GMACCONFIG_DM;
+        /// Loopback mode
+        loopback, with_loopback = GMACCONFIG_LM;
+        /// Disable receive own
+        disable_rx_own, with_disable_rx_own = GMACCONFIG_DO;
+        /// Speed: 100 Mbps when set, 10 Mbps when clear
+        speed_100, with_speed_100 = GMACCONFIG_FES;
+        /// Port select (MII)
+        port_select, with_port_select = GMACCONFIG_PS;
+        /// Disable carrier sense during transmission
+        disable_carrier_sense, with_disable_carrier_sense = GMACCONFIG_DCRS;
+        /// Jumbo frame enable
+        jumbo, with_jumbo = GMACCONFIG_JE;
+        /// Frame burst enable
+        frame_burst, with_frame_burst = GMACCONFIG_BE;
+        /// Jabber disable
+        jabber_disable, with_jabber_disable = GMACCONFIG_JD;
+        /// Watchdog disable
+        watchdog_disable, with_watchdog_disable = GMACCONFIG_WD;
+        /// CRC stripping for type frames
+        crc_strip_type, with_crc_strip_type = GMACCONFIG_CST;
+        /// IEEE 802.3as 2K packets
+        two_k_packets, with_two_k_packets = GMACCONFIG_TWOKPE;
+    }
+    fields {
+        /// Preamble length
+        preamble_len, with_preamble_len = GMACCONFIG_PRELEN_MASK, GMACCONFIG_PRELEN_SHIFT;
+        /// Back-off limit
+        backoff_limit, with_backoff_limit = GMACCONFIG_BL_MASK, GMACCONFIG_BL_SHIFT;
+        /// Inter-frame gap (see [`ifg`])
+        inter_frame_gap, with_inter_frame_gap = GMACCONFIG_IFG_MASK, GMACCONFIG_IFG_SHIFT;
+        /// Source address insertion/replacement control
+        sa_control, with_sa_control = GMACCONFIG_SARC_MASK, GMACCONFIG_SARC_SHIFT;
+    }
+}
+
+reg_fields! {
+    /// GMAC Frame Filter register (GMACFF) fields
+    pub struct GmacFrameFilter;
+    flags {
+        /// Promiscuous mode
+        promiscuous, with_promiscuous = GMACFF_PR;
+        /// Hash unicast
+        hash_unicast, with_hash_unicast = GMACFF_HUC;
+        /// Hash multicast
+        hash_multicast, with_hash_multicast = GMACFF_HMC;
+        /// DA inverse filtering
+        da_inverse, with_da_inverse = GMACFF_DAIF;
+        /// Pass all multicast
+        pass_all_multicast, with_pass_all_multicast = GMACFF_PM;
+        /// Disable broadcast frames
+        disable_broadcast, with_disable_broadcast = GMACFF_DBF;
+        /// SA inverse filtering
+        sa_inverse, with_sa_inverse = GMACFF_SAIF;
+        /// Source address filter enable
+        sa_filter, with_sa_filter = GMACFF_SAF;
+        /// Hash or perfect filter
+        hash_or_perfect, with_hash_or_perfect = GMACFF_HPF;
+        /// VLAN tag filter enable
+        vlan_filter, with_vlan_filter = GMACFF_VTFE;
+        /// Layer 3/4 filter enable
+        l3l4_filter, with_l3l4_filter = GMACFF_IPFE;
+        /// Drop non-TCP/UDP over IP frames
+        drop_non_tcp_udp, with_drop_non_tcp_udp = GMACFF_DNTU;
+        /// Receive all
+        receive_all, with_receive_all = GMACFF_RA;
+    }
+    fields {
+        /// Pass control frames mode (see [`pcf`])
+        pass_control, with_pass_control = GMACFF_PCF_MASK, GMACFF_PCF_SHIFT;
+    }
+}
+
 // =============================================================================
 // GMAC VLAN Tag Register (GMACVLAN) Bits
 // =============================================================================
@@ -361,6 +460,24 @@ impl MacRegs {
         GMACFF_OFFSET,
         "Frame Filter register"
     );
+    reg_typed!(
+        config_fields,
+        set_config_fields,
+        modify_config_fields,
+        MAC_BASE,
+        GMACCONFIG_OFFSET,
+        GmacConfig,
+        "GMAC Configuration register"
+    );
+    reg_typed!(
+        frame_filter_fields,
+        set_frame_filter_fields,
+        modify_frame_filter_fields,
+        MAC_BASE,
+        GMACFF_OFFSET,
+        GmacFrameFilter,
+        "Frame Filter register"
+    );
     reg_rw!(
         hash_table_high,
         set_hash_table_high,
@@ -507,19 +624,25 @@ impl MacRegs {
         }
     }
 
+    /// Read-modify-write the frame filter with interrupts excluded
+    ///
+    /// Applies every change `f` makes in one write, so a batch of filter
+    /// updates cannot interleave with another writer, e.g. an interrupt
+    /// handler. Returns the value written.
+    pub fn update_frame_filter(
+        f: impl FnOnce(GmacFrameFilter) -> GmacFrameFilter,
+    ) -> GmacFrameFilter {
+        with_register_lock(|| {
+            let ff = f(Self::frame_filter_fields());
+            Self::set_frame_filter_fields(ff);
+            ff
+        })
+    }
+
     /// Enable promiscuous mode
     #[inline(always)]
     pub fn set_promiscuous(enable: bool) {
-        // SAFETY: Accesses fixed MAC register addresses using volatile reads/writes.
-        unsafe {
-            let ff = read_reg(MAC_BASE + GMACFF_OFFSET);
-            let ff = if enable {
-                ff | GMACFF_PR
-            } else {
-                ff & !GMACFF_PR
-            };
-            write_reg(MAC_BASE + GMACFF_OFFSET, ff);
-        }
+        Self::update_frame_filter(|ff| ff.with_promiscuous(enable));
     }
 
     // -------------------------------------------------------------------------
@@ -548,34 +671,6 @@ impl MacRegs {
         Self::set_hash_table_high(0);
     }
 
-    /// Compute hash index for a MAC address
-    ///
-    /// Uses the Ethernet CRC-32 polynomial to compute a 6-bit hash index.
-    /// The MAC hardware uses the upper 6 bits of the CRC-32 as the hash.
-    ///
-    /// # Arguments
-    /// * `addr` - 6-byte MAC address
-    ///
-    /// # Returns
-    /// A value 0-63 representing the bit position in the 64-bit hash table
-    pub fn compute_hash_index(addr: &[u8; 6]) -> u8 {
-        const CRC32_POLY: u32 = 0xEDB8_8320;
-        let mut crc: u32 = 0xFFFF_FFFF;
-
-        for byte in addr {
-            let mut data = *byte;
-            for _ in 0..8 {
-                if ((crc ^ data as u32) & 1) != 0 {
-                    crc = (crc >> 1) ^ CRC32_POLY;
-                } else {
-                    crc >>= 1;
-                }
-                data >>= 1;
-            }
-        }
-        (crc & 0x3F) as u8
-    }
-
     /// Set a bit in the hash table
     pub fn set_hash_bit(index: u8) {
         let index = index & 0x3F;
@@ -612,16 +707,7 @@ impl MacRegs {
 
     /// Enable hash unicast filtering
     pub fn enable_hash_unicast(enable: bool) {
-        // SAFETY: Accesses fixed MAC register addresses using volatile reads/writes.
-        unsafe {
-            let ff = read_reg(MAC_BASE + GMACFF_OFFSET);
-            let ff = if enable {
-                ff | GMACFF_HUC
-            } else {
-                ff & !GMACFF_HUC
-            };
-            write_reg(MAC_BASE + GMACFF_OFFSET, ff);
-        }
+        Self::update_frame_filter(|ff| ff.with_hash_unicast(enable));
     }
 
     /// Enable hash multicast filtering
@@ -630,16 +716,7 @@ impl MacRegs {
     /// This is more efficient than "pass all multicast" for subscribing
     /// to specific multicast groups.
     pub fn enable_hash_multicast(enable: bool) {
-        // SAFETY: Accesses fixed MAC register addresses using volatile reads/writes.
-        unsafe {
-            let ff = read_reg(MAC_BASE + GMACFF_OFFSET);
-            let ff = if enable {
-                ff | GMACFF_HMC
-            } else {
-                ff & !GMACFF_HMC
-            };
-            write_reg(MAC_BASE + GMACFF_OFFSET, ff);
-        }
+        Self::update_frame_filter(|ff| ff.with_hash_multicast(enable));
     }
 
     /// Set Hash or Perfect filter mode
@@ -647,16 +724,7 @@ impl MacRegs {
     /// When enabled (HPF=1): Perfect filter for unicast, hash for multicast
     /// When disabled (HPF=0): Hash filter OR perfect filter passes frame
     pub fn set_hash_perfect_filter(enable: bool) {
-        // SAFETY: Accesses fixed MAC register addresses using volatile reads/writes.
-        unsafe {
-            let ff = read_reg(MAC_BASE + GMACFF_OFFSET);
-            let ff = if enable {
-                ff | GMACFF_HPF
-            } else {
-                ff & !GMACFF_HPF
-            };
-            write_reg(MAC_BASE + GMACFF_OFFSET, ff);
-        }
+        Self::update_frame_filter(|ff| ff.with_hash_or_perfect(enable));
     }
 
     // =========================================================================
@@ -667,16 +735,7 @@ impl MacRegs {
     ///
     /// When enabled, frames are filtered based on the VLAN tag.
     pub fn enable_vlan_filter(enable: bool) {
-        // SAFETY: Accesses fixed MAC register addresses using volatile reads/writes.
-        unsafe {
-            let ff = read_reg(MAC_BASE + GMACFF_OFFSET);
-            let ff = if enable {
-                ff | GMACFF_VTFE
-            } else {
-                ff & !GMACFF_VTFE
-            };
-            write_reg(MAC_BASE + GMACFF_OFFSET, ff);
-        }
+        Self::update_frame_filter(|ff| ff.with_vlan_filter(enable));
     }
 
     /// Configure VLAN tag filter
@@ -843,16 +902,7 @@ impl MacRegs {
     /// Enable pass all multicast frames
     #[inline(always)]
     pub fn set_pass_all_multicast(enable: bool) {
-        // SAFETY: Accesses fixed MAC register addresses using volatile reads/writes.
-        unsafe {
-            let ff = read_reg(MAC_BASE + GMACFF_OFFSET);
-            let ff = if enable {
-                ff | GMACFF_PM
-            } else {
-                ff & !GMACFF_PM
-            };
-            write_reg(MAC_BASE + GMACFF_OFFSET, ff);
-        }
+        Self::update_frame_filter(|ff| ff.with_pass_all_multicast(enable));
     }
 
     /// Enable or disable broadcast frame reception.
@@ -860,16 +910,7 @@ impl MacRegs {
     /// When disabled, broadcast frames are filtered out.
     #[inline(always)]
     pub fn set_broadcast_enabled(enable: bool) {
-        // SAFETY: Accesses fixed MAC register addresses using volatile reads/writes.
-        unsafe {
-            let ff = read_reg(MAC_BASE + GMACFF_OFFSET);
-            let ff = if enable {
-                ff & !GMACFF_DBF
-            } else {
-                ff | GMACFF_DBF
-            };
-            write_reg(MAC_BASE + GMACFF_OFFSET, ff);
-        }
+        Self::update_frame_filter(|ff| ff.with_disable_broadcast(!enable));
     }
 
     /// Set the primary MAC address (6 bytes)
@@ -1025,6 +1066,34 @@ impl MacRegs {
         Some((addr, enabled))
     }
 
+    /// Raw (high, low) register pair of a MAC address filter slot (1-4)
+    ///
+    /// Returns `None` if the slot is invalid.
+    pub fn mac_filter_raw(slot: usize) -> Option<(u32, u32)> {
+        let (high_off, low_off) = Self::addr_filter_offsets(slot)?;
+        // SAFETY: Accesses fixed MAC register addresses using volatile reads.
+        Some(unsafe { (read_reg(MAC_BASE + high_off), read_reg(MAC_BASE + low_off)) })
+    }
+
+    /// Write the raw (high, low) register pair of a MAC address filter slot
+    ///
+    /// # Returns
+    /// `true` if successful, `false` if slot is invalid
+    pub fn set_mac_filter_raw(slot: usize, high: u32, low: u32) -> bool {
+        let Some((high_off, low_off)) = Self::addr_filter_offsets(slot) else {
+            return false;
+        };
+
+        // SAFETY: Accesses fixed MAC register addresses using volatile writes.
+        unsafe {
+            // The filter takes effect when the high register (AE) is written
+            write_reg(MAC_BASE + low_off, low);
+            write_reg(MAC_BASE + high_off, high);
+        }
+
+        true
+    }
+
     /// Clear all MAC address filters (slots 1-4)
     pub fn clear_all_mac_filters() {
         for slot in 1..=MAC_ADDR_FILTER_COUNT {
@@ -1056,3 +1125,56 @@ impl MacRegs {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gmac_config_flags_match_raw_bits() {
+        let cfg = GmacConfig::default()
+            .with_rx_enable(true)
+            .with_tx_enable(true)
+            .with_full_duplex(true)
+            .with_speed_100(true);
+        assert_eq!(
+            cfg.bits(),
+            GMACCONFIG_RE | GMACCONFIG_TE | GMACCONFIG_DM | GMACCONFIG_FES
+        );
+        assert!(cfg.full_duplex());
+        assert!(!cfg.loopback());
+
+        let cfg = cfg.with_tx_enable(false);
+        assert!(!cfg.tx_enable());
+        assert!(cfg.rx_enable());
+    }
+
+    #[test]
+    fn gmac_config_multi_bit_fields() {
+        let cfg = GmacConfig::from_bits(GMACCONFIG_RE).with_inter_frame_gap(ifg::IFG_64);
+        assert_eq!(cfg.inter_frame_gap(), ifg::IFG_64);
+        assert_eq!(
+            cfg.bits(),
+            GMACCONFIG_RE | (ifg::IFG_64 << GMACCONFIG_IFG_SHIFT)
+        );
+
+        // Values wider than the field are truncated, neighbours untouched
+        let cfg = cfg.with_backoff_limit(0xFF);
+        assert_eq!(cfg.backoff_limit(), 0x3);
+        assert_eq!(cfg.inter_frame_gap(), ifg::IFG_64);
+        assert!(cfg.rx_enable());
+    }
+
+    #[test]
+    fn gmac_frame_filter_fields() {
+        let ff = GmacFrameFilter::default()
+            .with_promiscuous(true)
+            .with_pass_control(pcf::ALL);
+        assert!(ff.promiscuous());
+        assert!(!ff.receive_all());
+        assert_eq!(ff.pass_control(), pcf::ALL);
+        assert_eq!(ff.bits(), GMACFF_PR | (pcf::ALL << GMACFF_PCF_SHIFT));
+        assert_eq!(u32::from(ff), ff.bits());
+        assert!(GmacFrameFilter::from(GMACFF_RA).receive_all());
+    }
+}
@@ -9,6 +9,8 @@ pub mod dma;
 pub mod ext;
 pub mod gpio;
 pub mod mac;
+#[cfg(test)]
+pub mod sim;
 
 // ESP32 and ESP32-P4 are mutually exclusive (enforced at crate root).
 
@@ -33,6 +35,15 @@ pub const DPORT_WIFI_CLK_EN_REG: usize = 0x3FF0_00CC;
 #[cfg(feature = "esp32")]
 pub const DPORT_WIFI_CLK_EMAC_EN: u32 = 1 << 14;
 
+/// DPORT core reset register (WiFi/BT/EMAC module resets)
+/// Note: DPORT base is 0x3FF00000, CORE_RST_EN offset is 0x0D0
+#[cfg(feature = "esp32")]
+pub const DPORT_CORE_RST_EN_REG: usize = 0x3FF0_00D0;
+
+/// EMAC module reset bit in DPORT_CORE_RST_EN_REG
+#[cfg(feature = "esp32")]
+pub const DPORT_EMAC_RST: u32 = 1 << 7;
+
 // =============================================================================
 // IO_MUX Register Definitions (ESP32)
 // =============================================================================
@@ -77,8 +88,13 @@ pub const EXT_BASE: usize = 0x5008_4800;
 /// The caller must ensure the address is valid and properly aligned.
 #[inline(always)]
 pub unsafe fn read_reg(addr: usize) -> u32 {
+    #[cfg(test)]
+    return sim::read(addr);
     // SAFETY: Caller guarantees `addr` is valid and properly aligned.
-    unsafe { core::ptr::read_volatile(addr as *const u32) }
+    #[cfg(not(test))]
+    unsafe {
+        core::ptr::read_volatile(addr as *const u32)
+    }
 }
 
 /// Write a 32-bit value to a register at the given address
@@ -87,8 +103,13 @@ pub unsafe fn read_reg(addr: usize) -> u32 {
 /// The caller must ensure the address is valid and properly aligned.
 #[inline(always)]
 pub unsafe fn write_reg(addr: usize, value: u32) {
+    #[cfg(test)]
+    sim::write(addr, value);
     // SAFETY: Caller guarantees `addr` is valid and properly aligned.
-    unsafe { core::ptr::write_volatile(addr as *mut u32, value) }
+    #[cfg(not(test))]
+    unsafe {
+        core::ptr::write_volatile(addr as *mut u32, value);
+    }
 }
 
 /// Modify a register using a read-modify-write operation
@@ -106,6 +127,22 @@ where
     unsafe { write_reg(addr, f(value)) }
 }
 
+/// Run a register read-modify-write with interrupts excluded
+///
+/// Uses a critical section with the `critical-section` feature. Without it
+/// the caller must make sure no interrupt handler writes the same register.
+#[inline(always)]
+pub fn with_register_lock<R>(f: impl FnOnce() -> R) -> R {
+    #[cfg(feature = "critical-section")]
+    {
+        critical_section::with(|_| f())
+    }
+    #[cfg(not(feature = "critical-section"))]
+    {
+        f()
+    }
+}
+
 /// Set bits in a register (read-modify-write)
 ///
 /// # Safety
@@ -208,8 +245,151 @@ macro_rules! reg_bit_check_clear {
     };
 }
 
+/// Generate a typed value wrapper with field-level accessors for a register.
+///
+/// Single-bit fields are listed under `flags` and read as `bool`; multi-bit
+/// fields are listed under `fields` with their mask and shift and read as
+/// `u32`. Each field gets a getter and a `with_*` builder; `bits()` and
+/// `from_bits()` keep raw access available.
+///
+/// # Example
+/// ```ignore
+/// reg_fields! {
+///     /// Operation Mode register
+///     pub struct DmaOperation;
+///     flags {
+///         /// Start/Stop Receive
+///         start_rx, with_start_rx = DMAOPERATION_SR;
+///     }
+///     fields {
+///         /// Receive Threshold Control
+///         rx_threshold, with_rx_threshold = DMAOPERATION_RTC_MASK, DMAOPERATION_RTC_SHIFT;
+///     }
+/// }
+/// ```
+macro_rules! reg_fields {
+    (
+        $(#[$meta:meta])*
+        pub struct $name:ident;
+        flags {
+            $(
+                $(#[$flag_meta:meta])*
+                $flag:ident, $with_flag:ident = $bit:expr;
+            )*
+        }
+        fields {
+            $(
+                $(#[$field_meta:meta])*
+                $field:ident, $with_field:ident = $mask:expr, $shift:expr;
+            )*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+        pub struct $name(u32);
+
+        impl $name {
+            /// Wrap a raw register value
+            #[inline(always)]
+            #[must_use]
+            pub const fn from_bits(bits: u32) -> Self {
+                Self(bits)
+            }
+
+            /// Raw register value
+            #[inline(always)]
+            #[must_use]
+            pub const fn bits(self) -> u32 {
+                self.0
+            }
+
+            $(
+                $(#[$flag_meta])*
+                #[inline(always)]
+                #[must_use]
+                pub const fn $flag(self) -> bool {
+                    (self.0 & $bit) != 0
+                }
+
+                #[doc = concat!("Set or clear `", stringify!($flag), "`")]
+                #[inline(always)]
+                #[must_use]
+                pub const fn $with_flag(self, enabled: bool) -> Self {
+                    if enabled {
+                        Self(self.0 | $bit)
+                    } else {
+                        Self(self.0 & !$bit)
+                    }
+                }
+            )*
+
+            $(
+                $(#[$field_meta])*
+                #[inline(always)]
+                #[must_use]
+                pub const fn $field(self) -> u32 {
+                    (self.0 & $mask) >> $shift
+                }
+
+                #[doc = concat!("Set `", stringify!($field), "` (truncated to the field width)")]
+                #[inline(always)]
+                #[must_use]
+                pub const fn $with_field(self, value: u32) -> Self {
+                    Self((self.0 & !$mask) | ((value << $shift) & $mask))
+                }
+            )*
+        }
+
+        impl From<u32> for $name {
+            fn from(bits: u32) -> Self {
+                Self(bits)
+            }
+        }
+
+        impl From<$name> for u32 {
+            fn from(reg: $name) -> u32 {
+                reg.0
+            }
+        }
+    };
+}
+
+/// Generate typed read/write/modify accessors for a register.
+///
+/// The value type must come from [`reg_fields!`].
+macro_rules! reg_typed {
+    ($read_fn:ident, $write_fn:ident, $modify_fn:ident, $base:expr, $offset:expr, $ty:ty, $doc:expr) => {
+        #[doc = concat!("Read ", $doc, " as typed fields")]
+        #[inline(always)]
+        pub fn $read_fn() -> $ty {
+            // SAFETY: Register address is valid for this peripheral.
+            <$ty>::from_bits(unsafe { $crate::internal::register::read_reg($base + $offset) })
+        }
+
+        #[doc = concat!("Write ", $doc, " from typed fields")]
+        #[inline(always)]
+        pub fn $write_fn(value: $ty) {
+            // SAFETY: Register address is valid for this peripheral.
+            unsafe { $crate::internal::register::write_reg($base + $offset, value.bits()) }
+        }
+
+        #[doc = concat!("Read-modify-write ", $doc, " through typed fields")]
+        #[inline(always)]
+        pub fn $modify_fn<F: FnOnce($ty) -> $ty>(f: F) {
+            // SAFETY: Register address is valid for this peripheral.
+            unsafe {
+                $crate::internal::register::modify_reg($base + $offset, |v| {
+                    f(<$ty>::from_bits(v)).bits()
+                })
+            }
+        }
+    };
+}
+
 // Export macros for use in submodules
 pub(crate) use reg_bit_check_clear;
 pub(crate) use reg_bit_ops;
+pub(crate) use reg_fields;
 pub(crate) use reg_ro;
 pub(crate) use reg_rw;
+pub(crate) use reg_typed;
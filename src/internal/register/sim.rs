@@ -0,0 +1,107 @@
+//! Simulated register file for host tests.
+//!
+//! Under `cfg(test)` [`read_reg`](super::read_reg) and
+//! [`write_reg`](super::write_reg) go here instead of to MMIO, so driver
+//! paths that touch registers (poll demands, interrupt acknowledges) run on
+//! the host. Every test thread gets its own register file, starting zeroed;
+//! registers hold whatever was last written, with no side effects unless a
+//! test opts in: [`write_one_to_clear`] gives a register status semantics,
+//! [`self_clearing`] makes command bits such as a reset complete at once,
+//! and [`raise_on_read`] sets bits behind the driver's back, the way a
+//! hardware event landing between a read and the following write would.
+
+extern crate alloc;
+extern crate std;
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::vec::Vec;
+use core::cell::RefCell;
+
+#[derive(Default)]
+struct RegisterFile {
+    values: BTreeMap<usize, u32>,
+    writes: BTreeMap<usize, usize>,
+    w1c: BTreeSet<usize>,
+    /// Bits that read back as 0 right after being written
+    self_clear: BTreeMap<usize, u32>,
+    /// (address, reads left, bits to set)
+    pending: Vec<(usize, usize, u32)>,
+}
+
+std::thread_local! {
+    static REGS: RefCell<RegisterFile> = RefCell::new(RegisterFile::default());
+}
+
+/// Read the simulated register at `addr` (zero if never written)
+pub fn read(addr: usize) -> u32 {
+    REGS.with(|r| {
+        let mut r = r.borrow_mut();
+        let value = r.values.get(&addr).copied().unwrap_or(0);
+        let mut raised = 0;
+        r.pending.retain_mut(|(at, reads, bits)| {
+            if *at != addr {
+                return true;
+            }
+            *reads -= 1;
+            if *reads == 0 {
+                raised |= *bits;
+            }
+            *reads != 0
+        });
+        if raised != 0 {
+            *r.values.entry(addr).or_insert(0) |= raised;
+        }
+        value
+    })
+}
+
+/// Write the simulated register at `addr`
+pub fn write(addr: usize, value: u32) {
+    REGS.with(|r| {
+        let mut r = r.borrow_mut();
+        if r.w1c.contains(&addr) {
+            *r.values.entry(addr).or_insert(0) &= !value;
+        } else {
+            let cleared = r.self_clear.get(&addr).copied().unwrap_or(0);
+            r.values.insert(addr, value & !cleared);
+        }
+        *r.writes.entry(addr).or_insert(0) += 1;
+    });
+}
+
+/// Make writes to `addr` clear the bits written as 1 instead of storing
+/// the value, like a hardware status register
+pub fn write_one_to_clear(addr: usize) {
+    REGS.with(|r| {
+        r.borrow_mut().w1c.insert(addr);
+    });
+}
+
+/// Make `bits` of `addr` clear themselves as soon as they are written, like
+/// a command bit whose operation completes instantly
+pub fn self_clearing(addr: usize, bits: u32) {
+    REGS.with(|r| {
+        *r.borrow_mut().self_clear.entry(addr).or_insert(0) |= bits;
+    });
+}
+
+/// Set `bits` in `addr`, as hardware raising a status flag would
+pub fn raise(addr: usize, bits: u32) {
+    REGS.with(|r| *r.borrow_mut().values.entry(addr).or_insert(0) |= bits);
+}
+
+/// Set `bits` in `addr` right after its `nth` read from now (1 = the next
+/// read), which still returns the old value
+pub fn raise_on_read(addr: usize, nth: usize, bits: u32) {
+    REGS.with(|r| r.borrow_mut().pending.push((addr, nth.max(1), bits)));
+}
+
+/// Number of writes to `addr` since the last [`reset`]
+pub fn write_count(addr: usize) -> usize {
+    REGS.with(|r| r.borrow().writes.get(&addr).copied().unwrap_or(0))
+}
+
+/// Zero every register and write counter on this thread
+pub fn reset() {
+    REGS.with(|r| *r.borrow_mut() = RegisterFile::default());
+}
@@ -64,6 +64,8 @@
 //!
 //! - `esp32` (default): Target the original ESP32
 //! - `esp32p4`: Experimental placeholder (not supported)
+//! - Neither: only the target-independent [`logic`] module is built, for
+//!   host tools and tests (`default-features = false`)
 //! - `defmt`: Enable defmt formatting for error types
 //! - `log`: Enable log facade support
 //! - `smoltcp`: Enable smoltcp network stack integration
@@ -71,6 +73,19 @@
 //! - `async`: Enable async/await support with wakers
 //! - `esp-hal`: Enable esp-hal ergonomic integration
 //! - `embassy-net`: Enable embassy-net-driver integration
+//! - `diag`: Enable the ARP/ICMP ping responder in `diag`
+//! - `debug-console`: Enable the authenticated Ethernet debug console in `diag`
+//! - `debug-checks`: Assert DMA descriptor invariants (slot bounds, ownership,
+//!   length consistency); panics on violation, compiled out otherwise
+//! - `crc-verify`: Optional software CRC-32 check of received frames
+//! - `frame-dump`: Rate-limited frame hexdumps in `debug` for defmt/log
+//! - `frame-trace`: Descriptor-level trace of recent transmit attempts
+//!   (`Emac::frame_trace`)
+//! - `test-hooks`: `Emac::inject_rx_frame` for integration-testing code above
+//!   the driver
+//! - `bench`: Host microbenchmarks for the DMA hot path (`cargo test` only)
+//! - `iram`: Place the TX/RX/ISR hot paths in IRAM instead of flash (see
+//!   `docs/ARCHITECTURE.md`, "Code Placement")
 //!
 //! # Supported PHY Chips
 //!
@@ -137,10 +152,61 @@
 #[cfg(all(feature = "esp32", feature = "esp32p4"))]
 compile_error!("Features 'esp32' and 'esp32p4' are mutually exclusive.");
 
-#[cfg(not(any(feature = "esp32", feature = "esp32p4")))]
-compile_error!("Either feature 'esp32' or 'esp32p4' must be enabled. The default is 'esp32'.");
+#[cfg(all(
+    not(any(feature = "esp32", feature = "esp32p4")),
+    any(
+        feature = "defmt",
+        feature = "log",
+        feature = "smoltcp",
+        feature = "critical-section",
+        feature = "esp-hal",
+        feature = "async",
+        feature = "embassy-net",
+        feature = "diag",
+        feature = "debug-console",
+        feature = "debug-checks",
+        feature = "crc-verify",
+        feature = "frame-dump",
+        feature = "frame-trace",
+        feature = "test-hooks",
+        feature = "bench",
+        feature = "iram",
+    )
+))]
+compile_error!(
+    "Driver features need a chip feature ('esp32'); without one only the `logic` module is built."
+);
 // #![allow(dead_code)] // Temporarily disabled to identify unused code
 
+/// Link the wrapped functions into IRAM (`.rwtext`) with the `iram` feature
+///
+/// Only applies on Xtensa; host builds ignore it. See "Code Placement" in
+/// `docs/ARCHITECTURE.md` for what belongs here.
+macro_rules! iram {
+    ($($item:item)*) => {
+        $(
+            #[cfg_attr(
+                all(feature = "iram", target_arch = "xtensa"),
+                unsafe(link_section = ".rwtext")
+            )]
+            $item
+        )*
+    };
+}
+
+/// Build the wrapped items only with a chip feature (`esp32` or `esp32p4`)
+///
+/// Everything but the `logic` module touches the EMAC registers; the
+/// chip-independent build compiles none of it.
+macro_rules! chip {
+    ($($item:item)*) => {
+        $(
+            #[cfg(any(feature = "esp32", feature = "esp32p4"))]
+            $item
+        )*
+    };
+}
+
 // =============================================================================
 // Modules
 // =============================================================================
@@ -148,9 +214,12 @@ compile_error!("Either feature 'esp32' or 'esp32p4' must be enabled. The default
 #[cfg(feature = "esp32")]
 #[cfg_attr(docsrs, doc(cfg(feature = "esp32")))]
 pub mod boards;
-pub mod driver;
-pub mod hal;
-pub mod phy;
+chip! {
+    pub mod driver;
+    pub mod hal;
+    pub mod phy;
+}
+pub mod logic;
 
 // Internal implementation details (pub(crate) only)
 mod internal;
@@ -166,43 +235,104 @@ pub mod integration;
 #[cfg_attr(docsrs, doc(cfg(feature = "critical-section")))]
 pub mod sync;
 
+#[cfg(any(feature = "diag", feature = "debug-console"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "diag", feature = "debug-console"))))]
+pub mod diag;
+
+#[cfg(feature = "frame-dump")]
+#[cfg_attr(docsrs, doc(cfg(feature = "frame-dump")))]
+pub mod debug;
+
 // Test utilities (only available during testing)
-#[cfg(test)]
+#[cfg(all(test, any(feature = "esp32", feature = "esp32p4")))]
 pub mod testing;
 
 // =============================================================================
 // Re-exports
 // =============================================================================
 
-pub use driver::config::{
-    ChecksumConfig, DmaBurstLen, Duplex, EmacConfig, FlowControlConfig, MAC_FILTER_SLOTS,
-    MacAddressFilter, MacFilterType, PauseLowThreshold, PhyInterface, RmiiClockMode, Speed, State,
-    TxChecksumMode,
-};
-pub use driver::emac::{Emac, EmacDefault, EmacLarge, EmacSmall};
-pub use driver::error::{
-    ConfigError, ConfigResult, DmaError, DmaResult, Error, IoError, IoResult, Result,
-};
-pub use driver::interrupt::InterruptStatus;
+chip! {
+    pub use driver::borrowed_tx::BorrowedTx;
+    pub use driver::bring_up::{
+        BringUpReport, BringUpStep, NoResetPin, PhyBringUpError, PhyBringUpStage, PhyResetTiming,
+        StepOutcome, reset_and_probe_phy,
+    };
+    pub use driver::capabilities::{BufferLocation, CompiledFeatures, DriverCapabilities};
+    pub use driver::checksum::{IpPayloadType, RxChecksumStatus};
+    pub use driver::config::{
+        ChecksumConfig, ControlFrameFilter, DmaBurstLen, DmaBurstPreset, DriveStrength, Duplex,
+        EmacConfig, FilterConfig, FlowControlConfig, LlcFrameFilter, MAC_FILTER_SLOTS,
+        MacAddressFilter, MacFilterType, PadPull, PauseLowThreshold, PhyInterface, RmiiClockMode,
+        RmiiTiming, RuntimeSettings, Speed, State, TokenStrategy, TxChecksumMode, TxInterruptPolicy,
+        VlanFilter, ipv4_multicast_mac, is_multicast_mac, mac_from_unique_id,
+    };
+    pub use driver::duplex::{DEFAULT_MISMATCH_THRESHOLD, DuplexMismatch, DuplexMismatchDetector};
+    pub use driver::emac::{Emac, EmacDefault, EmacLarge, EmacSmall};
+    pub use driver::error::{
+        ConfigError, ConfigResult, DmaError, DmaResult, Error, IoError, IoResult, PhyError, Result,
+    };
+    pub use driver::event_log::{
+        AlarmCounter, EVENT_LOG_LEN, EmacEventSink, Event, EventKind, EventLog, EventThresholds,
+        RATE_ALARM_SLOTS, RateAlarm, ThresholdMetric,
+    };
+    pub use driver::fcs::RxFcsStatus;
+    pub use driver::filtering::{FilterSnapshot, FrameFilterUpdate};
+    pub use driver::frame_buf::FrameBuf;
+    pub use driver::frame_format::{
+        FRAME_FORMAT_HEAD_LEN, FrameFormat, MAX_8023_LENGTH, MIN_ETHER_TYPE,
+    };
+    #[cfg(feature = "frame-trace")]
+    pub use driver::frame_trace::{FRAME_TRACE_LEN, FrameTrace, FrameTraceLog, TraceOutcome};
+    pub use driver::garp::{
+        GARP_DEFAULT_REPEATS, GARP_DEFAULT_SPACING_MS, GarpAnnouncer, gratuitous_arp,
+    };
+    pub use driver::hw_state::HwStateLoss;
+    pub use driver::interrupt::InterruptStatus;
+    pub use driver::keepalive::{KEEPALIVE_ETHERTYPE, Keepalive, KeepaliveFrame};
+    pub use driver::lifecycle::{TypedEmac, TypedState};
+    pub use driver::liveness::{LivenessFault, LivenessReport, RxProcessState, TxProcessState};
+    pub use driver::memory::MemoryReport;
+    pub use driver::ops::{EmacHandle, EmacOps};
+    pub use driver::raw::RawEthernet;
+    pub use driver::rx_timestamp::SoftRxTimestamp;
+    pub use driver::shaper::Shaper;
+    pub use driver::stats::{RxFrameClass, RxOccupancy, Statistics, TxDiagnostics, TxErrorKind};
+    pub use driver::tx_queue::TxQueue;
+    pub use driver::tx_rewrite::{
+        TX_REWRITE_HEAD_LEN, TX_REWRITE_SLOTS, TxRewriteRule, TxRewriteTable, VlanTag,
+    };
 
-/// Low-level register accessors for advanced use.
-///
-/// These are intentionally separated from the primary facade. Most users should
-/// prefer the safe driver APIs instead of touching registers directly.
-///
-/// # Safety
-///
-/// Direct register access bypasses driver invariants. Use only if you fully
-/// understand the ESP32 EMAC hardware and accept responsibility for correct
-/// sequencing and synchronization.
-pub mod unsafe_registers {
-    pub use crate::internal::register::dma::DmaRegs;
-    pub use crate::internal::register::ext::ExtRegs;
-    pub use crate::internal::register::mac::MacRegs;
-}
+    /// Low-level register accessors for advanced use.
+    ///
+    /// These are intentionally separated from the primary facade. Most users should
+    /// prefer the safe driver APIs instead of touching registers directly.
+    ///
+    /// # Safety
+    ///
+    /// Direct register access bypasses driver invariants. Use only if you fully
+    /// understand the ESP32 EMAC hardware and accept responsibility for correct
+    /// sequencing and synchronization.
+    pub mod unsafe_registers {
+        pub use crate::internal::register::dma::DmaRegs;
+        pub use crate::internal::register::ext::ExtRegs;
+        pub use crate::internal::register::mac::MacRegs;
+    }
 
-// Re-export PHY types
-pub use phy::{Lan8720a, Lan8720aWithReset, LinkStatus, PhyCapabilities, PhyDriver};
+    /// Generic descriptor ring.
+    ///
+    /// [`DescriptorRing`](ring::DescriptorRing) is the circular index the DMA
+    /// engine runs its RX and TX descriptors through. It is generic over the
+    /// descriptor type; the hardware descriptor layouts stay internal.
+    pub mod ring {
+        pub use crate::internal::dma::DescriptorRing;
+    }
+
+    // Re-export PHY types
+    pub use phy::{
+        FaultKind, FlapDamping, Lan8720a, Lan8720aWithReset, LinkQuality, LinkStatus, NetLinkStatus,
+        PhyCapabilities, PhyDriver, PhyDump, PhyEvent, PhyState, PhyStateMachine,
+    };
+}
 
 // Re-export sync types when critical-section is enabled
 #[cfg(feature = "critical-section")]
@@ -236,47 +366,88 @@ pub use sync::asynch::{AsyncEmacExt, AsyncEmacState, async_interrupt_handler};
 #[cfg_attr(docsrs, doc(cfg(feature = "embassy-net")))]
 pub use integration::embassy_net::{EmbassyEmac, EmbassyEmacState, EmbassyRxToken, EmbassyTxToken};
 
-/// Shared driver constants.
-///
-/// These are grouped into a dedicated module to keep the top-level facade
-/// focused on driver types and integration points.
-pub mod constants {
-    pub use crate::internal::constants::{
-        // Frame/buffer sizes
-        CRC_SIZE,
-        DEFAULT_BUFFER_SIZE,
-        // Flow control
-        DEFAULT_FLOW_HIGH_WATER,
-        DEFAULT_FLOW_LOW_WATER,
-        // MAC address
-        DEFAULT_MAC_ADDR,
-        // Buffer counts
-        DEFAULT_RX_BUFFERS,
-        DEFAULT_TX_BUFFERS,
-        ETH_HEADER_SIZE,
-        // Timing
-        FLUSH_TIMEOUT,
-        MAC_ADDR_LEN,
-        MAX_FRAME_SIZE,
-        // Clocks
-        MDC_MAX_FREQ_HZ,
-        MII_10M_CLK_HZ,
-        MII_100M_CLK_HZ,
-        MII_BUSY_TIMEOUT,
-        MIN_FRAME_SIZE,
-        MTU,
-        PAUSE_TIME_MAX,
-        RESET_POLL_INTERVAL_US,
-        RMII_CLK_HZ,
-        SOFT_RESET_TIMEOUT_MS,
-        VLAN_TAG_SIZE,
-    };
+chip! {
+    /// Shared driver constants.
+    ///
+    /// These are grouped into a dedicated module to keep the top-level facade
+    /// focused on driver types and integration points.
+    pub mod constants {
+        pub use crate::internal::constants::{
+            // Frame/buffer sizes
+            CRC_SIZE,
+            DEFAULT_BUFFER_SIZE,
+            // Flow control
+            DEFAULT_FLOW_HIGH_WATER,
+            DEFAULT_FLOW_LOW_WATER,
+            // MAC address
+            DEFAULT_MAC_ADDR,
+            // Buffer counts
+            DEFAULT_RX_BUFFERS,
+            DEFAULT_TX_BUFFERS,
+            ETH_HEADER_SIZE,
+            // Timing
+            FLUSH_TIMEOUT,
+            FLUSH_TIMEOUT_US,
+            MAC_ADDR_LEN,
+            MAX_FRAME_SIZE,
+            // Clocks
+            MDC_MAX_FREQ_HZ,
+            MII_10M_CLK_HZ,
+            MII_100M_CLK_HZ,
+            MII_BUSY_TIMEOUT,
+            MII_BUSY_TIMEOUT_US,
+            MIN_FRAME_SIZE,
+            MTU,
+            PAUSE_TIME_MAX,
+            POLL_DELAY_US,
+            POLL_SPINS_PER_US,
+            RESET_POLL_INTERVAL_US,
+            RMII_CLK_HZ,
+            SOFT_RESET_TIMEOUT_MS,
+            VLAN_TAG_SIZE,
+        };
+    }
 }
 
 // =============================================================================
 // Macro Helpers
 // =============================================================================
 
+/// Name the DMA memory footprint of an EMAC geometry as a constant.
+///
+/// Defines `const $name: MemoryReport` for `Emac<$rx, $tx, $buf>`, computed
+/// at compile time, with the exact byte counts per ring and buffer pool to
+/// log at boot or check in tests. With `budget = bytes` the build fails if
+/// the total exceeds the budget.
+///
+/// # Examples
+///
+/// ```
+/// ph_esp32_mac::emac_memory_report!(EMAC_MEMORY, 10, 10, 1600, budget = 40 * 1024);
+///
+/// assert_eq!(EMAC_MEMORY.buffers(), 2 * 10 * 1600);
+/// // "EMAC DMA memory: 32640 bytes (descriptors: RX 320 + TX 320, ...)"
+/// println!("{EMAC_MEMORY}");
+/// ```
+///
+/// Exceeding the budget is a compile error:
+///
+/// ```compile_fail
+/// ph_esp32_mac::emac_memory_report!(EMAC_MEMORY, 10, 10, 1600, budget = 16 * 1024);
+/// ```
+#[macro_export]
+macro_rules! emac_memory_report {
+    ($name:ident, $rx:expr, $tx:expr, $buf:expr) => {
+        #[allow(dead_code)]
+        const $name: $crate::MemoryReport = $crate::MemoryReport::of::<$rx, $tx, $buf>();
+    };
+    ($name:ident, $rx:expr, $tx:expr, $buf:expr, budget = $budget:expr) => {
+        #[allow(dead_code)]
+        const $name: $crate::MemoryReport =
+            $crate::MemoryReport::of::<$rx, $tx, $buf>().within_budget($budget);
+    };
+}
+
 /// Declare a static, ISR-safe EMAC instance for synchronous use.
 ///
 /// This macro expands to a `SharedEmac` static placed in DMA-capable memory on
@@ -0,0 +1,179 @@
+//! Ethernet address helpers and the MAC hash filter index.
+//!
+//! The driver programs the hash filter with [`hash_index`]; host tools can
+//! run the same function over captured destination addresses to see which
+//! hash bit a group needs, or which groups share a bit.
+
+// =============================================================================
+// MAC Address Helpers
+// =============================================================================
+
+/// Returns `true` if the address has the multicast (group) bit set
+///
+/// The broadcast address `FF:FF:FF:FF:FF:FF` is also a multicast address.
+#[must_use]
+#[inline(always)]
+pub const fn is_multicast_mac(addr: &[u8; 6]) -> bool {
+    addr[0] & 0x01 != 0
+}
+
+/// Ethernet address of IPv4 multicast group `group` (RFC 1112)
+///
+/// The low 23 bits of the group are placed under `01:00:5E`, so 32 groups
+/// share each address. Returns `None` if `group` is not in 224.0.0.0/4.
+#[must_use]
+pub const fn ipv4_multicast_mac(group: [u8; 4]) -> Option<[u8; 6]> {
+    if group[0] & 0xF0 != 0xE0 {
+        return None;
+    }
+    Some([0x01, 0x00, 0x5E, group[1] & 0x7F, group[2], group[3]])
+}
+
+/// Derive a stable locally-administered unicast MAC from a chip-unique value
+///
+/// The input (for example the eFuse MAC, a serial number, or a flash unique
+/// ID) is hashed with 64-bit FNV-1a, and the first six bytes of the hash
+/// become the address. The multicast bit is cleared and the locally
+/// administered bit is set, so the result never collides with vendor
+/// (OUI-assigned) addresses and is always valid as a source address.
+///
+/// The same input always yields the same address, so a fleet of boards
+/// gets distinct, reproducible MACs without a central allocator.
+///
+/// # Example
+///
+/// ```
+/// use ph_esp32_mac::mac_from_unique_id;
+///
+/// let mac = mac_from_unique_id(&[0x24, 0x0A, 0xC4, 0x12, 0x34, 0x56]);
+/// assert_eq!(mac[0] & 0x03, 0x02); // unicast, locally administered
+/// ```
+#[must_use]
+pub const fn mac_from_unique_id(bytes: &[u8]) -> [u8; 6] {
+    const FNV_OFFSET: u64 = 0xCBF2_9CE4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01B3;
+
+    let mut hash = FNV_OFFSET;
+    let mut i = 0;
+    while i < bytes.len() {
+        hash ^= bytes[i] as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+        i += 1;
+    }
+
+    let h = hash.to_be_bytes();
+    [(h[0] & 0xFC) | 0x02, h[1], h[2], h[3], h[4], h[5]]
+}
+
+// =============================================================================
+// Hash Filter Index
+// =============================================================================
+
+/// Hash table bit the MAC checks for destination `addr`
+///
+/// Uses the Ethernet CRC-32 polynomial to compute a 6-bit hash index.
+/// The MAC hardware uses the upper 6 bits of the CRC-32 as the hash.
+/// Returns a value 0-63, the bit position in the 64-bit hash table.
+#[must_use]
+pub const fn hash_index(addr: &[u8; 6]) -> u8 {
+    const CRC32_POLY: u32 = 0xEDB8_8320;
+    let mut crc: u32 = 0xFFFF_FFFF;
+
+    // `while` rather than `for` so this stays usable in const context
+    let mut i = 0;
+    while i < addr.len() {
+        let mut data = addr[i];
+        let mut bit = 0;
+        while bit < 8 {
+            if ((crc ^ data as u32) & 1) != 0 {
+                crc = (crc >> 1) ^ CRC32_POLY;
+            } else {
+                crc >>= 1;
+            }
+            data >>= 1;
+            bit += 1;
+        }
+        i += 1;
+    }
+    (crc & 0x3F) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEFAULT_MAC_ADDR: [u8; 6] = [0x02, 0x00, 0x00, 0x00, 0x00, 0x01];
+
+    #[test]
+    fn is_multicast_mac_checks_group_bit() {
+        assert!(is_multicast_mac(&[0x01, 0, 0, 0, 0, 0]));
+        assert!(is_multicast_mac(&[0x33, 0x33, 0, 0, 0, 1]));
+        assert!(!is_multicast_mac(&[0x02, 0, 0, 0, 0, 1]));
+        assert!(!is_multicast_mac(&DEFAULT_MAC_ADDR));
+    }
+
+    #[test]
+    fn ipv4_multicast_mac_maps_low_23_bits() {
+        assert_eq!(
+            ipv4_multicast_mac([224, 0, 0, 251]),
+            Some([0x01, 0x00, 0x5E, 0x00, 0x00, 0xFB])
+        );
+        // 239.129.1.2 and 224.1.1.2 differ only above bit 23
+        assert_eq!(
+            ipv4_multicast_mac([239, 129, 1, 2]),
+            ipv4_multicast_mac([224, 1, 1, 2])
+        );
+        assert_eq!(ipv4_multicast_mac([192, 168, 1, 1]), None);
+        assert_eq!(ipv4_multicast_mac([240, 0, 0, 1]), None);
+    }
+
+    #[test]
+    fn mac_from_unique_id_is_local_unicast() {
+        for id in [
+            &[][..],
+            &[0x00][..],
+            &[0xFF; 16][..],
+            &[0x24, 0x0A, 0xC4, 0x12, 0x34, 0x56][..],
+        ] {
+            let mac = mac_from_unique_id(id);
+            assert_eq!(mac[0] & 0x01, 0x00, "multicast bit set for {id:?}");
+            assert_eq!(mac[0] & 0x02, 0x02, "local bit clear for {id:?}");
+        }
+    }
+
+    #[test]
+    fn mac_from_unique_id_is_deterministic() {
+        let id = [0x24, 0x0A, 0xC4, 0x12, 0x34, 0x56];
+        assert_eq!(mac_from_unique_id(&id), mac_from_unique_id(&id));
+    }
+
+    #[test]
+    fn mac_from_unique_id_differs_for_adjacent_ids() {
+        let a = mac_from_unique_id(&[0x24, 0x0A, 0xC4, 0x12, 0x34, 0x56]);
+        let b = mac_from_unique_id(&[0x24, 0x0A, 0xC4, 0x12, 0x34, 0x57]);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn mac_from_unique_id_known_value() {
+        // FNV-1a 64 of the empty input is 0xCBF29CE484222325
+        assert_eq!(
+            mac_from_unique_id(&[]),
+            [0xCA, 0xF2, 0x9C, 0xE4, 0x84, 0x22]
+        );
+    }
+
+    #[test]
+    fn mac_from_unique_id_is_const() {
+        const MAC: [u8; 6] = mac_from_unique_id(b"board-01");
+        assert!(!is_multicast_mac(&MAC));
+    }
+
+    #[test]
+    fn hash_index_known_values() {
+        const MDNS: u8 = hash_index(&[0x01, 0x00, 0x5E, 0x00, 0x00, 0xFB]);
+        assert_eq!(MDNS, 60);
+        assert_eq!(hash_index(&[0xFF; 6]), 63);
+        assert_eq!(hash_index(&[0x33, 0x33, 0x00, 0x00, 0x00, 0x01]), 31);
+    }
+}
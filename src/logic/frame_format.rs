@@ -0,0 +1,242 @@
+//! Ethernet II versus IEEE 802.3 length-field frames.
+//!
+//! The two bytes after the source address (and any VLAN tags) are an
+//! EtherType in Ethernet II frames and a payload length in IEEE 802.3
+//! frames, which then carry an 802.2 LLC header instead. Industrial
+//! networks still carry plenty of the latter (spanning tree BPDUs, Profibus
+//! and older fieldbus gateways, NetBIOS), and code that reads the field as
+//! an EtherType misdispatches them.
+//!
+//! `Emac::last_rx_format` reports the [`FrameFormat`] of the last frame
+//! returned by `receive()`, and `LlcFrameFilter` lets the driver drop
+//! 802.3 frames before they reach the application.
+
+use crate::internal::constants::{ETH_HEADER_SIZE, VLAN_TAG_SIZE};
+
+/// Largest value of the type/length field that is a length
+pub const MAX_8023_LENGTH: u16 = 0x05DC;
+
+/// Smallest value of the type/length field that is an EtherType
+pub const MIN_ETHER_TYPE: u16 = 0x0600;
+
+/// LLC SAP value announcing a SNAP header
+const SNAP_SAP: u8 = 0xAA;
+
+/// LLC control value of the unnumbered information frames SNAP uses
+const LLC_UI: u8 = 0x03;
+
+/// Offset of the type/length field in an untagged frame
+const TYPE_OFFSET: usize = ETH_HEADER_SIZE - 2;
+
+/// VLAN tags skipped before the type/length field (802.1ad plus 802.1Q)
+const MAX_VLAN_TAGS: usize = 2;
+
+/// Header bytes [`FrameFormat::parse`] may look at: two VLAN tags, the
+/// type/length field and an LLC + SNAP header
+pub const FRAME_FORMAT_HEAD_LEN: usize = TYPE_OFFSET + MAX_VLAN_TAGS * VLAN_TAG_SIZE + 2 + 8;
+
+/// Encapsulation of a received frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum FrameFormat {
+    /// Ethernet II: the type/length field is an EtherType
+    EthernetII {
+        /// EtherType, after any VLAN tags
+        ether_type: u16,
+    },
+    /// IEEE 802.3 with an 802.2 LLC header
+    Llc {
+        /// Payload length from the type/length field
+        length: u16,
+        /// Destination service access point
+        dsap: u8,
+        /// Source service access point
+        ssap: u8,
+    },
+    /// IEEE 802.3 with an LLC + SNAP header, which carries an EtherType
+    /// after the organization code
+    Snap {
+        /// Payload length from the type/length field
+        length: u16,
+        /// Organizationally unique identifier, `00-00-00` for
+        /// encapsulated Ethernet
+        oui: [u8; 3],
+        /// Protocol identifier; an EtherType when `oui` is zero
+        protocol: u16,
+    },
+}
+
+impl FrameFormat {
+    iram! {
+        /// Classify a frame from its header
+        ///
+        /// Skips up to two 802.1Q/802.1ad VLAN tags. Returns `None` if the
+        /// frame is too short to tell, or if the type/length field falls in
+        /// the undefined range between [`MAX_8023_LENGTH`] and
+        /// [`MIN_ETHER_TYPE`].
+        #[must_use]
+        pub fn parse(frame: &[u8]) -> Option<Self> {
+            let mut offset = TYPE_OFFSET;
+            let mut field = read_u16(frame, offset)?;
+            for _ in 0..MAX_VLAN_TAGS {
+                if !matches!(field, 0x8100 | 0x88A8) {
+                    break;
+                }
+                offset += VLAN_TAG_SIZE;
+                field = read_u16(frame, offset)?;
+            }
+
+            if field >= MIN_ETHER_TYPE {
+                return Some(Self::EthernetII { ether_type: field });
+            }
+            if field > MAX_8023_LENGTH {
+                return None;
+            }
+
+            let llc = frame.get(offset + 2..)?;
+            match *llc {
+                [SNAP_SAP, SNAP_SAP, LLC_UI, o0, o1, o2, p0, p1, ..] => Some(Self::Snap {
+                    length: field,
+                    oui: [o0, o1, o2],
+                    protocol: u16::from_be_bytes([p0, p1]),
+                }),
+                [dsap, ssap, ..] => Some(Self::Llc {
+                    length: field,
+                    dsap,
+                    ssap,
+                }),
+                _ => None,
+            }
+        }
+    }
+
+    /// True for IEEE 802.3 length-field frames, with or without SNAP
+    #[must_use]
+    pub const fn is_8023(&self) -> bool {
+        !matches!(self, Self::EthernetII { .. })
+    }
+
+    /// EtherType of the payload, if the frame has one
+    ///
+    /// Ethernet II frames and SNAP frames with a zero OUI carry one; plain
+    /// LLC frames and vendor-specific SNAP protocols do not. This is the
+    /// value to dispatch on instead of bytes 12-13 of the frame.
+    #[must_use]
+    pub const fn ether_type(&self) -> Option<u16> {
+        match *self {
+            Self::EthernetII { ether_type } => Some(ether_type),
+            Self::Snap {
+                oui: [0, 0, 0],
+                protocol,
+                ..
+            } => Some(protocol),
+            Self::Snap { .. } | Self::Llc { .. } => None,
+        }
+    }
+
+    /// Short name of the encapsulation
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::EthernetII { .. } => "Ethernet II",
+            Self::Llc { .. } => "802.3 LLC",
+            Self::Snap { .. } => "802.3 SNAP",
+        }
+    }
+}
+
+impl core::fmt::Display for FrameFormat {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            Self::EthernetII { ether_type } => write!(f, "{} 0x{ether_type:04x}", self.as_str()),
+            Self::Llc { length, dsap, ssap } => write!(
+                f,
+                "{} len={length} dsap=0x{dsap:02x} ssap=0x{ssap:02x}",
+                self.as_str()
+            ),
+            Self::Snap {
+                length, protocol, ..
+            } => write!(f, "{} len={length} proto=0x{protocol:04x}", self.as_str()),
+        }
+    }
+}
+
+fn read_u16(frame: &[u8], offset: usize) -> Option<u16> {
+    match frame.get(offset..offset + 2)? {
+        &[hi, lo] => Some(u16::from_be_bytes([hi, lo])),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(type_len: u16, rest: &[u8]) -> [u8; 64] {
+        let mut f = [0u8; 64];
+        f[..6].copy_from_slice(&[0x01, 0x80, 0xC2, 0, 0, 0]);
+        f[6..12].copy_from_slice(&[0x02, 0, 0, 0, 0, 1]);
+        f[12..14].copy_from_slice(&type_len.to_be_bytes());
+        f[14..14 + rest.len()].copy_from_slice(rest);
+        f
+    }
+
+    #[test]
+    fn ethernet_ii_and_vlan_tagged() {
+        let ipv4 = header(0x0800, &[]);
+        assert_eq!(
+            FrameFormat::parse(&ipv4),
+            Some(FrameFormat::EthernetII { ether_type: 0x0800 })
+        );
+
+        // 802.1ad outer tag, 802.1Q inner tag, then an 802.3 length
+        let tagged = header(
+            0x88A8,
+            &[0, 10, 0x81, 0x00, 0, 20, 0x00, 0x26, 0x42, 0x42, 0x03],
+        );
+        let format = FrameFormat::parse(&tagged).unwrap();
+        assert_eq!(
+            format,
+            FrameFormat::Llc {
+                length: 0x26,
+                dsap: 0x42,
+                ssap: 0x42
+            }
+        );
+        assert!(format.is_8023());
+        assert_eq!(format.ether_type(), None);
+    }
+
+    #[test]
+    fn llc_and_snap() {
+        // Spanning tree BPDU
+        let bpdu = header(0x0026, &[0x42, 0x42, 0x03]);
+        assert_eq!(
+            FrameFormat::parse(&bpdu),
+            Some(FrameFormat::Llc {
+                length: 0x26,
+                dsap: 0x42,
+                ssap: 0x42
+            })
+        );
+
+        // SNAP-encapsulated IPv4
+        let snap = header(46, &[0xAA, 0xAA, 0x03, 0, 0, 0, 0x08, 0x00]);
+        let format = FrameFormat::parse(&snap).unwrap();
+        assert!(format.is_8023());
+        assert_eq!(format.ether_type(), Some(0x0800));
+
+        // Vendor SNAP protocol (Cisco CDP)
+        let cdp = header(46, &[0xAA, 0xAA, 0x03, 0x00, 0x00, 0x0C, 0x20, 0x00]);
+        assert_eq!(FrameFormat::parse(&cdp).unwrap().ether_type(), None);
+    }
+
+    #[test]
+    fn undefined_or_short_is_unknown() {
+        assert_eq!(FrameFormat::parse(&header(0x05DD, &[])), None);
+        assert_eq!(FrameFormat::parse(&header(0x05FF, &[])), None);
+        assert_eq!(FrameFormat::parse(&[0u8; 13]), None);
+        assert_eq!(FrameFormat::parse(&[0u8; 15]), None);
+        assert!(FRAME_FORMAT_HEAD_LEN <= 60);
+    }
+}
@@ -0,0 +1,29 @@
+//! Target-independent driver logic.
+//!
+//! Everything in this module is plain computation over bytes and status
+//! words: no register access, no descriptors in DMA memory, no chip
+//! addresses. The driver uses it as is, and it is the only part of the
+//! crate built when no chip feature is enabled, so host tools and tests can
+//! reuse the exact device logic:
+//!
+//! ```toml
+//! [dependencies]
+//! ph-esp32-mac = { version = "0.1", default-features = false }
+//! ```
+//!
+//! ```ignore
+//! use ph_esp32_mac::logic::frame::hash_index;
+//!
+//! // Which hash bit does a captured multicast destination need?
+//! let bit = hash_index(&captured[..6].try_into()?);
+//! ```
+//!
+//! - [`descriptor`]: RX/TX descriptor status and control bits
+//! - [`frame`]: MAC address helpers and the hash filter index
+//! - [`frame_format`]: Ethernet II versus IEEE 802.3/LLC classification
+//! - [`stats`]: Statistics counters and RX/TX status decoding
+
+pub mod descriptor;
+pub mod frame;
+pub mod frame_format;
+pub mod stats;
@@ -0,0 +1,612 @@
+//! Driver statistics.
+//!
+//! The EMAC counts every frame handed out by `receive()` and classifies it
+//! by destination: unicast to one of our addresses, broadcast, multicast, or
+//! (in promiscuous mode) traffic for somebody else. The DWMAC sets the
+//! "destination address filter fail" bit in the RX status of frames it only
+//! delivered because filtering is bypassed, so the last class comes straight
+//! from the descriptor; the others are told apart by the destination MAC.
+//!
+//! Counters wrap on overflow. Read them with `Emac::stats` and clear them
+//! with `Emac::reset_stats`.
+//!
+//! When the EMAC is shared with an interrupt handler, take a copy with
+//! `SharedEmac::stats_snapshot()`, which reads every counter inside one
+//! critical section, and compute rates from two copies with
+//! [`Statistics::since`].
+//!
+//! # TX Diagnostics
+//!
+//! Carrier and collision errors on transmit are the main symptom of a
+//! duplex mismatch: a MAC running half duplex against a full-duplex link
+//! partner sees late collisions, and a MAC running full duplex against a
+//! half-duplex partner leaves the partner reporting them. The DMA writes
+//! these errors into the TX descriptor status, which the driver collects
+//! into [`TxDiagnostics`] (`Statistics::tx_diagnostics`) whenever it queues
+//! a frame, or on demand with `Emac::collect_tx_status`.
+
+use super::descriptor::{rdes0, tdes0};
+use super::frame::is_multicast_mac;
+use crate::internal::constants::{CRC_SIZE, MIN_FRAME_SIZE};
+
+// =============================================================================
+// Missed Frame Counter Register Bits (DMAMISSEDFR, clear on read)
+// =============================================================================
+
+/// Frames missed because no RX descriptor was available
+pub const DMAMISSEDFR_MISFRMCNT_MASK: u32 = 0xFFFF;
+/// Missed frame counter overflowed
+pub const DMAMISSEDFR_MISCNTOVF: u32 = 1 << 16;
+/// Frames missed because the RX FIFO overflowed, shift
+pub const DMAMISSEDFR_OVFFRMCNT_SHIFT: u32 = 17;
+/// Frames missed because the RX FIFO overflowed, mask (after shift)
+pub const DMAMISSEDFR_OVFFRMCNT_MASK: u32 = 0x7FF;
+/// FIFO overflow counter overflowed
+pub const DMAMISSEDFR_OVFCNTOVF: u32 = 1 << 28;
+
+/// Destination class of a received frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum RxFrameClass {
+    /// Addressed to one of our unicast addresses
+    Unicast,
+    /// Addressed to `ff:ff:ff:ff:ff:ff`
+    Broadcast,
+    /// Addressed to a multicast group
+    Multicast,
+    /// Failed the destination filter; only delivered in promiscuous mode
+    PromiscuousOther,
+}
+
+impl RxFrameClass {
+    /// Classify a frame from its RDES0 status and its bytes
+    ///
+    /// A frame too short to hold a destination address counts as unicast.
+    #[must_use]
+    #[inline(always)]
+    pub fn classify(rdes0_status: u32, frame: &[u8]) -> Self {
+        if rdes0_status & rdes0::DA_FILTER_FAIL != 0 {
+            return Self::PromiscuousOther;
+        }
+        match frame.first_chunk::<6>() {
+            Some(dst) if *dst == [0xFF; 6] => Self::Broadcast,
+            Some(dst) if is_multicast_mac(dst) => Self::Multicast,
+            _ => Self::Unicast,
+        }
+    }
+}
+
+/// RX descriptors held by frames waiting to be received
+///
+/// Reported by `Emac::rx_occupancy`. Descriptors the DMA still owns, or is
+/// filling, count as free.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RxOccupancy {
+    /// Held by unicast frames addressed to us
+    pub unicast: usize,
+    /// Held by broadcast, multicast and promiscuous frames
+    pub other: usize,
+    /// Held by shed frames still waiting behind frames ahead of them in
+    /// the ring
+    pub shed: usize,
+    /// Not holding a complete frame
+    pub free: usize,
+    /// Descriptors kept for unicast (`EmacConfig::rx_unicast_reserve`)
+    pub reserve: usize,
+}
+
+impl RxOccupancy {
+    /// True if fewer than `reserve` descriptors are free, held by unicast
+    /// or already shed, i.e. other frames have eaten into the reserve
+    #[must_use]
+    pub const fn over_budget(&self) -> bool {
+        self.unicast + self.shed + self.free < self.reserve
+    }
+}
+
+/// Carrier or collision error reported for a transmitted frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TxErrorKind {
+    /// Collision after the first 64 bytes (half duplex only)
+    LateCollision,
+    /// Frame aborted after 16 collisions (half duplex only)
+    ExcessiveCollisions,
+    /// Carrier sense dropped while transmitting
+    LossOfCarrier,
+    /// Carrier sense never asserted during transmission
+    NoCarrier,
+}
+
+impl TxErrorKind {
+    /// Kinds in the order [`TxDiagnostics::record`] picks the last error
+    const BY_SEVERITY: [(u32, Self); 4] = [
+        (tdes0::LATE_COLLISION, Self::LateCollision),
+        (tdes0::EXCESSIVE_COLLISION, Self::ExcessiveCollisions),
+        (tdes0::LOSS_OF_CARRIER, Self::LossOfCarrier),
+        (tdes0::NO_CARRIER, Self::NoCarrier),
+    ];
+
+    /// Short description
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::LateCollision => "late collision",
+            Self::ExcessiveCollisions => "excessive collisions",
+            Self::LossOfCarrier => "loss of carrier",
+            Self::NoCarrier => "no carrier",
+        }
+    }
+}
+
+/// Carrier and collision errors on transmitted frames
+///
+/// Counts frames, not bits: a frame with several error bits set counts once
+/// under each. See the [module documentation](self#tx-diagnostics).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TxDiagnostics {
+    /// Frames with a late collision
+    pub late_collisions: u32,
+    /// Frames aborted after excessive collisions
+    pub excessive_collisions: u32,
+    /// Frames that lost carrier during transmission
+    pub loss_of_carrier: u32,
+    /// Frames sent without carrier
+    pub no_carrier: u32,
+    /// Most recent error; the most severe one if a frame had several
+    pub last_error: Option<TxErrorKind>,
+}
+
+impl TxDiagnostics {
+    /// All counters zero, no error seen
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            late_collisions: 0,
+            excessive_collisions: 0,
+            loss_of_carrier: 0,
+            no_carrier: 0,
+            last_error: None,
+        }
+    }
+
+    /// Count the carrier and collision bits in a frame's TDES0 error flags
+    ///
+    /// Other error bits (underflow, jabber, checksum) are ignored.
+    pub fn record(&mut self, tdes0_errors: u32) {
+        let mut last = None;
+        for (bit, kind) in TxErrorKind::BY_SEVERITY.into_iter().rev() {
+            if tdes0_errors & bit != 0 {
+                let counter = self.counter_mut(kind);
+                *counter = counter.wrapping_add(1);
+                last = Some(kind);
+            }
+        }
+        if last.is_some() {
+            self.last_error = last;
+        }
+    }
+
+    /// Frames counted for `kind`
+    #[must_use]
+    pub const fn count(&self, kind: TxErrorKind) -> u32 {
+        match kind {
+            TxErrorKind::LateCollision => self.late_collisions,
+            TxErrorKind::ExcessiveCollisions => self.excessive_collisions,
+            TxErrorKind::LossOfCarrier => self.loss_of_carrier,
+            TxErrorKind::NoCarrier => self.no_carrier,
+        }
+    }
+
+    /// Sum of all counters
+    #[must_use]
+    pub const fn total(&self) -> u32 {
+        self.late_collisions
+            .wrapping_add(self.excessive_collisions)
+            .wrapping_add(self.loss_of_carrier)
+            .wrapping_add(self.no_carrier)
+    }
+
+    /// Counter increments from `prev` to `self`; `last_error` is `self`'s
+    #[must_use]
+    pub const fn since(&self, prev: &Self) -> Self {
+        Self {
+            late_collisions: self.late_collisions.wrapping_sub(prev.late_collisions),
+            excessive_collisions: self
+                .excessive_collisions
+                .wrapping_sub(prev.excessive_collisions),
+            loss_of_carrier: self.loss_of_carrier.wrapping_sub(prev.loss_of_carrier),
+            no_carrier: self.no_carrier.wrapping_sub(prev.no_carrier),
+            last_error: self.last_error,
+        }
+    }
+
+    fn counter_mut(&mut self, kind: TxErrorKind) -> &mut u32 {
+        match kind {
+            TxErrorKind::LateCollision => &mut self.late_collisions,
+            TxErrorKind::ExcessiveCollisions => &mut self.excessive_collisions,
+            TxErrorKind::LossOfCarrier => &mut self.loss_of_carrier,
+            TxErrorKind::NoCarrier => &mut self.no_carrier,
+        }
+    }
+}
+
+/// Frame counters kept by the EMAC
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Statistics {
+    /// Frames delivered by `receive()`
+    pub rx_frames: u32,
+    /// Bytes delivered by `receive()` (excluding FCS)
+    pub rx_bytes: u64,
+    /// Delivered frames addressed to us
+    pub rx_unicast: u32,
+    /// Delivered broadcast frames
+    pub rx_broadcast: u32,
+    /// Delivered multicast frames
+    pub rx_multicast: u32,
+    /// Delivered frames for other hosts (promiscuous mode)
+    pub rx_promiscuous_other: u32,
+    /// Frames dropped because the DMA reported a receive error
+    pub rx_errors: u32,
+    /// Errored frames with a CRC error
+    ///
+    /// Like `rx_runts`, only counted with `EmacConfig::forward_error_frames`;
+    /// otherwise the MAC drops these frames before the DMA sees them.
+    pub rx_crc_errors: u32,
+    /// Errored frames shorter than the 64-byte Ethernet minimum
+    pub rx_runts: u32,
+    /// Frames whose FCS was checked in software (`crc-verify`)
+    pub rx_fcs_verified: u32,
+    /// Frames dropped because the software FCS check failed
+    pub rx_fcs_mismatch: u32,
+    /// PAUSE frames that reached the driver, delivered or dropped
+    /// according to `ControlFrameFilter`
+    pub rx_pause: u32,
+    /// IEEE 802.3 length-field frames dropped according to
+    /// `LlcFrameFilter`
+    pub rx_llc_dropped: u32,
+    /// Broadcast, multicast and promiscuous frames dropped to keep RX
+    /// descriptors free for unicast (`EmacConfig::rx_unicast_reserve`)
+    pub rx_shed: u32,
+    /// Frames the DMA dropped because no RX descriptor was free
+    ///
+    /// Read from the hardware missed frame counter by
+    /// `Emac::collect_missed_frames`.
+    pub rx_missed: u32,
+    /// Frames dropped because the RX FIFO overflowed
+    ///
+    /// Read from the hardware missed frame counter like `rx_missed`.
+    pub rx_fifo_overflows: u32,
+    /// Keepalive frames queued by `Emac::poll_keepalive`
+    pub tx_keepalives: u32,
+    /// Critical sections timed with `Emac::set_cycle_counter`
+    pub cs_holds: u32,
+    /// Longest timed critical-section hold in CPU cycles
+    ///
+    /// A high-water mark: [`since`](Self::since) keeps the current value,
+    /// `Emac::reset_stats` clears it.
+    pub cs_hold_max_cycles: u32,
+    /// Carrier and collision errors on transmit
+    pub tx_diagnostics: TxDiagnostics,
+}
+
+impl Statistics {
+    /// All counters zero
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            rx_frames: 0,
+            rx_bytes: 0,
+            rx_unicast: 0,
+            rx_broadcast: 0,
+            rx_multicast: 0,
+            rx_promiscuous_other: 0,
+            rx_errors: 0,
+            rx_crc_errors: 0,
+            rx_runts: 0,
+            rx_fcs_verified: 0,
+            rx_fcs_mismatch: 0,
+            rx_pause: 0,
+            rx_llc_dropped: 0,
+            rx_shed: 0,
+            rx_missed: 0,
+            rx_fifo_overflows: 0,
+            tx_keepalives: 0,
+            cs_holds: 0,
+            cs_hold_max_cycles: 0,
+            tx_diagnostics: TxDiagnostics::new(),
+        }
+    }
+
+    /// Count a delivered frame of `len` bytes
+    #[inline(always)]
+    pub fn record_rx(&mut self, class: RxFrameClass, len: usize) {
+        self.rx_frames = self.rx_frames.wrapping_add(1);
+        self.rx_bytes = self.rx_bytes.wrapping_add(len as u64);
+        let counter = match class {
+            RxFrameClass::Unicast => &mut self.rx_unicast,
+            RxFrameClass::Broadcast => &mut self.rx_broadcast,
+            RxFrameClass::Multicast => &mut self.rx_multicast,
+            RxFrameClass::PromiscuousOther => &mut self.rx_promiscuous_other,
+        };
+        *counter = counter.wrapping_add(1);
+    }
+
+    /// Count a frame dropped with a receive error
+    #[inline(always)]
+    pub fn record_rx_error(&mut self) {
+        self.rx_errors = self.rx_errors.wrapping_add(1);
+    }
+
+    /// Count a frame dropped with a receive error, classified by its RDES0
+    /// status
+    #[inline(always)]
+    pub fn record_rx_frame_error(&mut self, rdes0_status: u32) {
+        self.record_rx_error();
+        if rdes0_status & rdes0::CRC_ERR != 0 {
+            self.rx_crc_errors = self.rx_crc_errors.wrapping_add(1);
+        }
+        let len = (rdes0_status & rdes0::FRAME_LEN_MASK) >> rdes0::FRAME_LEN_SHIFT;
+        if (len as usize) < MIN_FRAME_SIZE + CRC_SIZE {
+            self.rx_runts = self.rx_runts.wrapping_add(1);
+        }
+    }
+
+    /// Count a software FCS check and its outcome
+    #[inline(always)]
+    pub fn record_fcs_check(&mut self, ok: bool) {
+        self.rx_fcs_verified = self.rx_fcs_verified.wrapping_add(1);
+        if !ok {
+            self.rx_fcs_mismatch = self.rx_fcs_mismatch.wrapping_add(1);
+        }
+    }
+
+    /// Count a received PAUSE frame
+    #[inline(always)]
+    pub fn record_pause(&mut self) {
+        self.rx_pause = self.rx_pause.wrapping_add(1);
+    }
+
+    /// Count a queued keepalive frame
+    pub fn record_keepalive(&mut self) {
+        self.tx_keepalives = self.tx_keepalives.wrapping_add(1);
+    }
+
+    /// Count a critical-section hold of `cycles` CPU cycles
+    pub fn record_cs_hold(&mut self, cycles: u32) {
+        self.cs_holds = self.cs_holds.wrapping_add(1);
+        self.cs_hold_max_cycles = self.cs_hold_max_cycles.max(cycles);
+    }
+
+    /// Count a dropped IEEE 802.3 length-field frame
+    #[inline(always)]
+    pub fn record_llc_drop(&mut self) {
+        self.rx_llc_dropped = self.rx_llc_dropped.wrapping_add(1);
+    }
+
+    /// Count a frame dropped to protect the unicast RX reserve
+    #[inline(always)]
+    pub fn record_rx_shed(&mut self) {
+        self.rx_shed = self.rx_shed.wrapping_add(1);
+    }
+
+    /// Add a reading of the missed frame counter register (DMAMISSEDFR)
+    ///
+    /// The register clears on read, so every reading is new. A counter that
+    /// overflowed in hardware is taken at its saturated value.
+    pub fn record_missed_frames(&mut self, reg: u32) {
+        let missed = if reg & DMAMISSEDFR_MISCNTOVF != 0 {
+            DMAMISSEDFR_MISFRMCNT_MASK
+        } else {
+            reg & DMAMISSEDFR_MISFRMCNT_MASK
+        };
+        let overflows = if reg & DMAMISSEDFR_OVFCNTOVF != 0 {
+            DMAMISSEDFR_OVFFRMCNT_MASK
+        } else {
+            (reg >> DMAMISSEDFR_OVFFRMCNT_SHIFT) & DMAMISSEDFR_OVFFRMCNT_MASK
+        };
+        self.rx_missed = self.rx_missed.wrapping_add(missed);
+        self.rx_fifo_overflows = self.rx_fifo_overflows.wrapping_add(overflows);
+    }
+
+    /// Counter increments from `prev` to `self`
+    ///
+    /// `prev` is an earlier snapshot of the same counters. Each field is
+    /// subtracted with wrapping arithmetic, so a counter that wrapped once in
+    /// between still yields the right delta. After a `reset_stats()` between
+    /// the two snapshots the result is meaningless.
+    #[must_use]
+    pub const fn since(&self, prev: &Self) -> Self {
+        Self {
+            rx_frames: self.rx_frames.wrapping_sub(prev.rx_frames),
+            rx_bytes: self.rx_bytes.wrapping_sub(prev.rx_bytes),
+            rx_unicast: self.rx_unicast.wrapping_sub(prev.rx_unicast),
+            rx_broadcast: self.rx_broadcast.wrapping_sub(prev.rx_broadcast),
+            rx_multicast: self.rx_multicast.wrapping_sub(prev.rx_multicast),
+            rx_promiscuous_other: self
+                .rx_promiscuous_other
+                .wrapping_sub(prev.rx_promiscuous_other),
+            rx_errors: self.rx_errors.wrapping_sub(prev.rx_errors),
+            rx_crc_errors: self.rx_crc_errors.wrapping_sub(prev.rx_crc_errors),
+            rx_runts: self.rx_runts.wrapping_sub(prev.rx_runts),
+            rx_fcs_verified: self.rx_fcs_verified.wrapping_sub(prev.rx_fcs_verified),
+            rx_fcs_mismatch: self.rx_fcs_mismatch.wrapping_sub(prev.rx_fcs_mismatch),
+            rx_pause: self.rx_pause.wrapping_sub(prev.rx_pause),
+            rx_llc_dropped: self.rx_llc_dropped.wrapping_sub(prev.rx_llc_dropped),
+            rx_shed: self.rx_shed.wrapping_sub(prev.rx_shed),
+            rx_missed: self.rx_missed.wrapping_sub(prev.rx_missed),
+            rx_fifo_overflows: self.rx_fifo_overflows.wrapping_sub(prev.rx_fifo_overflows),
+            tx_keepalives: self.tx_keepalives.wrapping_sub(prev.tx_keepalives),
+            cs_holds: self.cs_holds.wrapping_sub(prev.cs_holds),
+            cs_hold_max_cycles: self.cs_hold_max_cycles,
+            tx_diagnostics: self.tx_diagnostics.since(&prev.tx_diagnostics),
+        }
+    }
+
+    /// Delivered frame count for `class`
+    #[must_use]
+    pub const fn rx_count(&self, class: RxFrameClass) -> u32 {
+        match class {
+            RxFrameClass::Unicast => self.rx_unicast,
+            RxFrameClass::Broadcast => self.rx_broadcast,
+            RxFrameClass::Multicast => self.rx_multicast,
+            RxFrameClass::PromiscuousOther => self.rx_promiscuous_other,
+        }
+    }
+}
+
+/// True if a frame is unicast addressed to us, from its RDES0 status and
+/// the first byte of its destination address
+#[must_use]
+#[inline(always)]
+pub const fn is_own_unicast(rdes0_status: u32, first_byte: u8) -> bool {
+    rdes0_status & rdes0::DA_FILTER_FAIL == 0 && first_byte & 0x01 == 0
+}
+
+/// True if `frame` starts like an IEEE 802.3x PAUSE frame
+///
+/// Checks the MAC control EtherType and the PAUSE opcode; `frame` needs
+/// at least the 16 header bytes.
+#[must_use]
+#[inline(always)]
+pub fn is_pause_frame(frame: &[u8]) -> bool {
+    matches!(frame.get(12..16), Some([0x88, 0x08, 0x00, 0x01]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OURS: [u8; 6] = [0x02, 0x00, 0x00, 0x12, 0x34, 0x56];
+
+    fn frame(dst: [u8; 6]) -> [u8; 14] {
+        let mut f = [0u8; 14];
+        f[..6].copy_from_slice(&dst);
+        f[6..12].copy_from_slice(&[0x02, 0, 0, 0, 0, 1]);
+        f[12..].copy_from_slice(&[0x08, 0x00]);
+        f
+    }
+
+    #[test]
+    fn classifies_by_destination() {
+        assert_eq!(
+            RxFrameClass::classify(0, &frame(OURS)),
+            RxFrameClass::Unicast
+        );
+        assert_eq!(
+            RxFrameClass::classify(0, &frame([0xFF; 6])),
+            RxFrameClass::Broadcast
+        );
+        assert_eq!(
+            RxFrameClass::classify(0, &frame([0x01, 0x00, 0x5E, 0x00, 0x00, 0xFB])),
+            RxFrameClass::Multicast
+        );
+    }
+
+    #[test]
+    fn da_filter_fail_is_promiscuous_other() {
+        // Even a broadcast-looking frame is "other" if the filter rejected it
+        assert_eq!(
+            RxFrameClass::classify(rdes0::DA_FILTER_FAIL, &frame([0xFF; 6])),
+            RxFrameClass::PromiscuousOther
+        );
+        assert_eq!(
+            RxFrameClass::classify(rdes0::DA_FILTER_FAIL, &frame([0x02, 0, 0, 0, 0, 9])),
+            RxFrameClass::PromiscuousOther
+        );
+    }
+
+    #[test]
+    fn detects_pause_frames() {
+        let mut f = [0u8; 60];
+        f[..6].copy_from_slice(&[0x01, 0x80, 0xC2, 0x00, 0x00, 0x01]);
+        f[12..16].copy_from_slice(&[0x88, 0x08, 0x00, 0x01]);
+        assert!(is_pause_frame(&f));
+        // Other MAC control opcodes (e.g. PFC) are not PAUSE
+        f[14..16].copy_from_slice(&[0x01, 0x01]);
+        assert!(!is_pause_frame(&f));
+        assert!(!is_pause_frame(&frame(OURS)));
+    }
+
+    #[test]
+    fn runt_counts_as_unicast() {
+        assert_eq!(RxFrameClass::classify(0, &[0xFF; 4]), RxFrameClass::Unicast);
+    }
+
+    #[test]
+    fn counters_accumulate() {
+        let mut stats = Statistics::new();
+        stats.record_rx(RxFrameClass::Broadcast, 60);
+        stats.record_rx(RxFrameClass::Broadcast, 60);
+        stats.record_rx(RxFrameClass::Unicast, 100);
+        stats.record_rx_error();
+        stats.record_fcs_check(true);
+        stats.record_fcs_check(false);
+        stats.record_pause();
+
+        assert_eq!(stats.rx_frames, 3);
+        assert_eq!(stats.rx_bytes, 220);
+        assert_eq!(stats.rx_count(RxFrameClass::Broadcast), 2);
+        assert_eq!(stats.rx_count(RxFrameClass::Unicast), 1);
+        assert_eq!(stats.rx_count(RxFrameClass::Multicast), 0);
+        assert_eq!(stats.rx_errors, 1);
+        assert_eq!((stats.rx_fcs_verified, stats.rx_fcs_mismatch), (2, 1));
+        assert_eq!(stats.rx_pause, 1);
+    }
+
+    #[test]
+    fn missed_frame_register_decodes_both_counters() {
+        let mut stats = Statistics::new();
+        stats.record_missed_frames(3 | (5 << DMAMISSEDFR_OVFFRMCNT_SHIFT));
+        stats.record_missed_frames(1 | DMAMISSEDFR_OVFCNTOVF);
+        assert_eq!(stats.rx_missed, 4);
+        assert_eq!(stats.rx_fifo_overflows, 5 + DMAMISSEDFR_OVFFRMCNT_MASK);
+    }
+
+    #[test]
+    fn since_gives_wrapping_deltas() {
+        let mut prev = Statistics::new();
+        prev.rx_frames = u32::MAX;
+        prev.rx_bytes = 1000;
+        prev.rx_unicast = u32::MAX;
+        let mut now = prev;
+        now.record_rx(RxFrameClass::Unicast, 60);
+        now.record_rx(RxFrameClass::Broadcast, 64);
+        now.record_rx_error();
+
+        let delta = now.since(&prev);
+        assert_eq!(delta.rx_frames, 2);
+        assert_eq!(delta.rx_bytes, 124);
+        assert_eq!((delta.rx_unicast, delta.rx_broadcast), (1, 1));
+        assert_eq!(delta.rx_errors, 1);
+        assert_eq!(now.since(&now), Statistics::new());
+    }
+
+    #[test]
+    fn tx_diagnostics_count_per_kind() {
+        let mut diag = TxDiagnostics::new();
+        diag.record(tdes0::UNDERFLOW_ERR);
+        assert_eq!((diag.total(), diag.last_error), (0, None));
+
+        diag.record(tdes0::NO_CARRIER | tdes0::LOSS_OF_CARRIER);
+        assert_eq!(diag.last_error, Some(TxErrorKind::LossOfCarrier));
+        diag.record(tdes0::LATE_COLLISION | tdes0::EXCESSIVE_COLLISION);
+        assert_eq!(diag.last_error, Some(TxErrorKind::LateCollision));
+        diag.record(tdes0::NO_CARRIER);
+        assert_eq!(diag.last_error, Some(TxErrorKind::NoCarrier));
+
+        assert_eq!(diag.count(TxErrorKind::NoCarrier), 2);
+        assert_eq!(diag.count(TxErrorKind::LateCollision), 1);
+        assert_eq!(diag.total(), 5);
+
+        let prev = diag;
+        diag.record(tdes0::LATE_COLLISION);
+        let delta = diag.since(&prev);
+        assert_eq!((delta.late_collisions, delta.total()), (1, 1));
+        assert_eq!(delta.last_error, Some(TxErrorKind::LateCollision));
+        assert_eq!(TxErrorKind::LateCollision.as_str(), "late collision");
+    }
+}
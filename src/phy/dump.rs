@@ -0,0 +1,281 @@
+//! PHY register snapshot for bug reports.
+//!
+//! [`PhyDriver::debug_dump`](super::PhyDriver::debug_dump) reads the IEEE
+//! 802.3 registers that matter for link negotiation (BMCR, BMSR, PHYIDR,
+//! ANAR, ANLPAR, ANER) plus whatever vendor registers the concrete driver
+//! adds, in one go. The resulting [`PhyDump`] keeps the raw values, decodes
+//! the interesting bits (link, auto-negotiation complete, both sides'
+//! abilities) and prints as a readable block with `Display` or `defmt`.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let dump = phy.debug_dump(&mut mdio)?;
+//! println!("{dump}");
+//! if !dump.an_complete() { /* ... */ }
+//! ```
+
+use super::generic::PhyCapabilities;
+use crate::driver::error::Result;
+use crate::hal::mdio::MdioBus;
+use crate::internal::phy_regs::standard::{anar, aner, anlpar, bmcr, bmsr, phy_reg};
+
+/// Maximum number of vendor registers in a [`PhyDump`]
+pub const PHY_DUMP_VENDOR_REGS: usize = 8;
+
+/// One vendor-specific register captured in a [`PhyDump`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct VendorRegister {
+    /// Register address (16-31)
+    pub reg: u8,
+    /// Short datasheet name
+    pub name: &'static str,
+    /// Raw value
+    pub value: u16,
+}
+
+/// Raw PHY register snapshot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PhyDump {
+    /// PHY address the snapshot was taken from
+    pub phy_addr: u8,
+    /// Basic Mode Control Register
+    pub bmcr: u16,
+    /// Basic Mode Status Register
+    pub bmsr: u16,
+    /// PHY identifier, `(PHYIDR1 << 16) | PHYIDR2`
+    pub phy_id: u32,
+    /// Auto-Negotiation Advertisement Register
+    pub anar: u16,
+    /// Auto-Negotiation Link Partner Ability Register
+    pub anlpar: u16,
+    /// Auto-Negotiation Expansion Register
+    pub aner: u16,
+    vendor: [Option<VendorRegister>; PHY_DUMP_VENDOR_REGS],
+}
+
+impl PhyDump {
+    /// Read the standard registers of the PHY at `phy_addr`
+    ///
+    /// BMSR is read twice: its link bit latches low, so the first read only
+    /// reports whether the link dropped since the last one.
+    pub fn read_standard<M: MdioBus>(mdio: &mut M, phy_addr: u8) -> Result<Self> {
+        let bmcr = mdio.read(phy_addr, phy_reg::BMCR)?;
+        mdio.read(phy_addr, phy_reg::BMSR)?;
+        let bmsr = mdio.read(phy_addr, phy_reg::BMSR)?;
+        let id1 = mdio.read(phy_addr, phy_reg::PHYIDR1)? as u32;
+        let id2 = mdio.read(phy_addr, phy_reg::PHYIDR2)? as u32;
+        Ok(Self {
+            phy_addr,
+            bmcr,
+            bmsr,
+            phy_id: (id1 << 16) | id2,
+            anar: mdio.read(phy_addr, phy_reg::ANAR)?,
+            anlpar: mdio.read(phy_addr, phy_reg::ANLPAR)?,
+            aner: mdio.read(phy_addr, phy_reg::ANER)?,
+            vendor: [None; PHY_DUMP_VENDOR_REGS],
+        })
+    }
+
+    /// Read vendor register `reg` and append it to the snapshot
+    ///
+    /// Registers beyond [`PHY_DUMP_VENDOR_REGS`] are silently dropped.
+    /// Do not pass clear-on-read registers (interrupt sources): dumping
+    /// them would lose pending events.
+    pub fn read_vendor<M: MdioBus>(
+        &mut self,
+        mdio: &mut M,
+        reg: u8,
+        name: &'static str,
+    ) -> Result<()> {
+        let value = mdio.read(self.phy_addr, reg)?;
+        if let Some(slot) = self.vendor.iter_mut().find(|slot| slot.is_none()) {
+            *slot = Some(VendorRegister { reg, name, value });
+        }
+        Ok(())
+    }
+
+    /// Captured vendor registers, in read order
+    pub fn vendor(&self) -> impl Iterator<Item = VendorRegister> + '_ {
+        self.vendor.iter().flatten().copied()
+    }
+
+    /// Link is up (BMSR, second read)
+    #[must_use]
+    pub const fn link_up(&self) -> bool {
+        self.bmsr & bmsr::LINK_STATUS != 0
+    }
+
+    /// Auto-negotiation is enabled in BMCR
+    #[must_use]
+    pub const fn an_enabled(&self) -> bool {
+        self.bmcr & bmcr::AN_ENABLE != 0
+    }
+
+    /// Auto-negotiation has completed
+    #[must_use]
+    pub const fn an_complete(&self) -> bool {
+        self.bmsr & bmsr::AN_COMPLETE != 0
+    }
+
+    /// Remote fault signalled in BMSR or ANLPAR
+    #[must_use]
+    pub const fn remote_fault(&self) -> bool {
+        self.bmsr & bmsr::REMOTE_FAULT != 0 || self.anlpar & anlpar::REMOTE_FAULT != 0
+    }
+
+    /// Link partner is auto-negotiation able (ANER)
+    #[must_use]
+    pub const fn partner_an_able(&self) -> bool {
+        self.aner & aner::LP_AN_ABLE != 0
+    }
+
+    /// Parallel detection fault: the partner could not be identified (ANER)
+    #[must_use]
+    pub const fn parallel_detect_fault(&self) -> bool {
+        self.aner & aner::PDF != 0
+    }
+
+    /// Abilities we advertise (ANAR)
+    #[must_use]
+    pub const fn advertised(&self) -> PhyCapabilities {
+        PhyCapabilities {
+            speed_100_fd: self.anar & anar::TX_FD != 0,
+            speed_100_hd: self.anar & anar::TX_HD != 0,
+            speed_10_fd: self.anar & anar::T10_FD != 0,
+            speed_10_hd: self.anar & anar::T10_HD != 0,
+            auto_negotiation: self.an_enabled(),
+            pause: self.anar & anar::PAUSE != 0,
+            pause_asymmetric: false,
+        }
+    }
+
+    /// Abilities the link partner advertised (ANLPAR)
+    ///
+    /// Only meaningful once auto-negotiation has completed.
+    #[must_use]
+    pub const fn partner(&self) -> PhyCapabilities {
+        PhyCapabilities {
+            speed_100_fd: self.anlpar & anlpar::CAN_100_FD != 0,
+            speed_100_hd: self.anlpar & anlpar::CAN_100_HD != 0,
+            speed_10_fd: self.anlpar & anlpar::CAN_10_FD != 0,
+            speed_10_hd: self.anlpar & anlpar::CAN_10_HD != 0,
+            auto_negotiation: self.partner_an_able(),
+            pause: self.anlpar & anlpar::PAUSE != 0,
+            pause_asymmetric: self.anlpar & anlpar::PAUSE_ASYM != 0,
+        }
+    }
+}
+
+/// Write the set abilities as `100FD 100HD ... pause`, or `none`
+fn write_abilities(f: &mut core::fmt::Formatter<'_>, caps: &PhyCapabilities) -> core::fmt::Result {
+    let mut any = false;
+    for (name, set) in [
+        ("100FD", caps.speed_100_fd),
+        ("100HD", caps.speed_100_hd),
+        ("10FD", caps.speed_10_fd),
+        ("10HD", caps.speed_10_hd),
+        ("pause", caps.pause),
+        ("asym-pause", caps.pause_asymmetric),
+    ] {
+        if set {
+            write!(f, " {name}")?;
+            any = true;
+        }
+    }
+    if !any {
+        write!(f, " none")?;
+    }
+    Ok(())
+}
+
+impl core::fmt::Display for PhyDump {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let yes = |b: bool| if b { "yes" } else { "no" };
+
+        writeln!(f, "PHY {} id {:#010x}", self.phy_addr, self.phy_id)?;
+        writeln!(
+            f,
+            "  BMCR {:#06x}  BMSR {:#06x}  ANAR {:#06x}  ANLPAR {:#06x}  ANER {:#06x}",
+            self.bmcr, self.bmsr, self.anar, self.anlpar, self.aner
+        )?;
+        for r in self.vendor() {
+            writeln!(f, "  {} (reg {}) {:#06x}", r.name, r.reg, r.value)?;
+        }
+        writeln!(
+            f,
+            "  link {}, autoneg {} / complete {}, remote fault {}",
+            yes(self.link_up()),
+            yes(self.an_enabled()),
+            yes(self.an_complete()),
+            yes(self.remote_fault())
+        )?;
+        write!(f, "  advertised:")?;
+        write_abilities(f, &self.advertised())?;
+        writeln!(f)?;
+        write!(f, "  partner:")?;
+        if self.partner_an_able() {
+            write_abilities(f, &self.partner())?;
+        } else {
+            write!(f, " not auto-negotiating")?;
+        }
+        if self.parallel_detect_fault() {
+            write!(f, " (parallel detection fault)")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::std_instead_of_alloc)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::testing::MockMdioBus;
+    use std::format;
+
+    #[test]
+    fn decodes_negotiated_link() {
+        let mut mdio = MockMdioBus::new();
+        mdio.setup_lan8720a(1);
+        mdio.simulate_link_up_100_fd(1);
+        mdio.set_register(1, phy_reg::ANLPAR, anlpar::CAN_100_FD | anlpar::PAUSE);
+        mdio.set_register(1, phy_reg::ANER, aner::LP_AN_ABLE);
+
+        let dump = PhyDump::read_standard(&mut mdio, 1).unwrap();
+        assert_eq!(dump.phy_id, 0x0007_C0F1);
+        assert!(dump.link_up());
+        assert!(dump.an_complete());
+        assert!(dump.partner().speed_100_fd);
+        assert!(dump.partner().pause);
+        assert!(!dump.partner().speed_10_hd);
+        assert!(!dump.remote_fault());
+
+        let text = format!("{dump}");
+        assert!(text.contains("link yes"));
+        assert!(text.contains("partner: 100FD pause"));
+    }
+
+    #[test]
+    fn vendor_registers_are_capped() {
+        let mut mdio = MockMdioBus::new();
+        mdio.set_register(0, 17, 0x1234);
+        let mut dump = PhyDump::read_standard(&mut mdio, 0).unwrap();
+        for _ in 0..PHY_DUMP_VENDOR_REGS + 2 {
+            dump.read_vendor(&mut mdio, 17, "MCSR").unwrap();
+        }
+        assert_eq!(dump.vendor().count(), PHY_DUMP_VENDOR_REGS);
+        assert_eq!(dump.vendor().next().unwrap().value, 0x1234);
+    }
+
+    #[test]
+    fn partner_without_autoneg() {
+        let mdio = &mut MockMdioBus::new();
+        let dump = PhyDump::read_standard(mdio, 0).unwrap();
+        assert!(!dump.link_up());
+        assert!(format!("{dump}").contains("partner: not auto-negotiating"));
+    }
+}
@@ -3,6 +3,7 @@
 //! This module defines the common interface for all Ethernet PHY drivers,
 //! based on IEEE 802.3 Clause 22 standard registers.
 
+use super::dump::PhyDump;
 use crate::driver::config::{Duplex, Speed};
 use crate::driver::error::Result;
 use crate::hal::mdio::MdioBus;
@@ -11,6 +12,26 @@ use crate::hal::mdio::MdioBus;
 // Link Status
 // =============================================================================
 
+/// Link fault reported alongside a [`LinkStatus`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum FaultKind {
+    /// Link partner signalled a remote fault (BMSR or ANLPAR RF bit)
+    Remote,
+    /// Local jabber condition (10BASE-T transmitter stuck on)
+    Jabber,
+    /// Far loopback is enabled; received data is looped back to the partner
+    FarLoopback,
+}
+
+impl FaultKind {
+    /// Returns true if the fault originates at the link partner
+    #[must_use]
+    pub const fn is_remote(&self) -> bool {
+        matches!(self, FaultKind::Remote)
+    }
+}
+
 /// Ethernet link status information
 ///
 /// Contains the negotiated or configured link parameters.
@@ -21,12 +42,45 @@ pub struct LinkStatus {
     pub speed: Speed,
     /// Duplex mode
     pub duplex: Duplex,
+    /// Fault indication, if the PHY reported one
+    pub fault: Option<FaultKind>,
+    /// Resolved: we may send PAUSE frames and the partner honours them
+    pub tx_pause: bool,
+    /// Resolved: we honour PAUSE frames from the partner
+    pub rx_pause: bool,
 }
 
 impl LinkStatus {
     /// Create a new link status
     pub const fn new(speed: Speed, duplex: Duplex) -> Self {
-        Self { speed, duplex }
+        Self {
+            speed,
+            duplex,
+            fault: None,
+            tx_pause: false,
+            rx_pause: false,
+        }
+    }
+
+    /// Attach a fault indication
+    #[must_use]
+    pub const fn with_fault(mut self, fault: Option<FaultKind>) -> Self {
+        self.fault = fault;
+        self
+    }
+
+    /// Attach the resolved PAUSE directions
+    #[must_use]
+    pub const fn with_pause(mut self, tx_pause: bool, rx_pause: bool) -> Self {
+        self.tx_pause = tx_pause;
+        self.rx_pause = rx_pause;
+        self
+    }
+
+    /// Returns true if speed and duplex match, ignoring faults
+    #[must_use]
+    pub fn same_mode(&self, other: &LinkStatus) -> bool {
+        self.speed == other.speed && self.duplex == other.duplex
     }
 
     /// 100 Mbps Full Duplex
@@ -91,6 +145,42 @@ impl PhyCapabilities {
     }
 }
 
+// =============================================================================
+// Link Quality
+// =============================================================================
+
+/// PHY-level link quality metrics
+///
+/// Standard fields come from BMSR and are always present; vendor counters
+/// are `None` when the PHY does not provide them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct LinkQuality {
+    /// Link partner signalled a remote fault
+    pub remote_fault: bool,
+    /// Jabber condition detected (10BASE-T)
+    pub jabber: bool,
+    /// Raw symbol error counter, if the PHY has one
+    pub symbol_errors: Option<u16>,
+    /// Signal energy present on the receive pair, if reported
+    pub energy_detected: Option<bool>,
+}
+
+impl LinkQuality {
+    /// Returns true if any fault bit is set or the symbol error counter
+    /// exceeds `max_symbol_errors`
+    #[must_use]
+    pub const fn is_degraded(&self, max_symbol_errors: u16) -> bool {
+        if self.remote_fault || self.jabber {
+            return true;
+        }
+        match self.symbol_errors {
+            Some(count) => count > max_symbol_errors,
+            None => false,
+        }
+    }
+}
+
 // =============================================================================
 // PHY Driver Trait
 // =============================================================================
@@ -135,7 +225,14 @@ pub trait PhyDriver {
 
     /// Perform a soft reset
     ///
-    /// Writes to BMCR.RESET and waits for it to self-clear.
+    /// Writes to BMCR.RESET and polls it until it self-clears, for at most
+    /// [`ieee802_3::SOFT_RESET_BUDGET_US`] timed by the bus's
+    /// [`delay_us`](MdioBus::delay_us).
+    ///
+    /// # Errors
+    /// - `PhyError(ResetTimeout)` - BMCR.RESET still set after the budget
+    /// - `PhyError(Communication)` - No PHY answers at this address
+    /// - MDIO errors
     fn soft_reset<M: MdioBus>(&mut self, mdio: &mut M) -> Result<()>;
 
     /// Check if the link is up
@@ -183,6 +280,22 @@ pub trait PhyDriver {
 
     /// Get the link partner's advertised abilities (if AN complete)
     fn link_partner_abilities<M: MdioBus>(&self, mdio: &mut M) -> Result<PhyCapabilities>;
+
+    /// Read link quality metrics
+    ///
+    /// The default reports the standard BMSR fault bits only. Drivers with
+    /// vendor counters (symbol errors, energy detect) override this.
+    fn link_quality<M: MdioBus>(&self, mdio: &mut M) -> Result<LinkQuality> {
+        ieee802_3::read_link_quality(mdio, self.address())
+    }
+
+    /// Snapshot the link-related registers for a bug report
+    ///
+    /// The default captures the IEEE 802.3 registers only. Drivers add their
+    /// vendor status registers with [`PhyDump::read_vendor`].
+    fn debug_dump<M: MdioBus>(&self, mdio: &mut M) -> Result<PhyDump> {
+        PhyDump::read_standard(mdio, self.address())
+    }
 }
 
 // =============================================================================
@@ -191,7 +304,10 @@ pub trait PhyDriver {
 
 /// Helper functions using standard IEEE 802.3 registers
 pub mod ieee802_3 {
+    use embedded_hal::delay::DelayNs;
+
     use super::*;
+    use crate::driver::error::PhyError;
     use crate::internal::phy_regs::standard::{anar, bmcr, bmsr, phy_reg};
 
     /// Read BMSR and check link status bit
@@ -206,21 +322,110 @@ pub mod ieee802_3 {
         Ok((bmsr_val & bmsr::AN_COMPLETE) != 0)
     }
 
-    /// Perform soft reset via BMCR
-    pub fn soft_reset<M: MdioBus>(mdio: &mut M, phy_addr: u8, max_attempts: u32) -> Result<()> {
-        // Set reset bit
-        mdio.write(phy_addr, phy_reg::BMCR, bmcr::RESET)?;
+    /// Longest a PHY may take to complete a soft reset (IEEE 802.3
+    /// 22.2.4.1.1: 0.5 s)
+    pub const SOFT_RESET_BUDGET_US: u32 = 500_000;
+
+    /// Delay between BMCR reads in the timed soft reset helpers
+    pub const SOFT_RESET_POLL_US: u32 = 100;
+
+    /// BMCR.RESET polling shared by the soft reset helpers
+    ///
+    /// Each helper writes the reset with [`start`](Self::start), then calls
+    /// [`next`](Self::next) and waits the time it returns until it returns
+    /// `None`, so only the way of waiting differs between them.
+    struct ResetPoll {
+        budget_us: u32,
+        waited_us: u32,
+    }
+
+    impl ResetPoll {
+        /// Set BMCR.RESET
+        fn start<M: MdioBus>(mdio: &mut M, phy_addr: u8, budget_us: u32) -> Result<Self> {
+            mdio.write(phy_addr, phy_reg::BMCR, bmcr::RESET)?;
+            Ok(Self {
+                budget_us,
+                waited_us: 0,
+            })
+        }
 
-        // Wait for reset to complete (bit self-clears)
-        for _ in 0..max_attempts {
+        /// Read BMCR once: `None` once the reset has self-cleared, else the
+        /// microseconds to wait before the next read
+        ///
+        /// A BMCR of all ones is an undriven MDIO line, not a PHY still in
+        /// reset, and fails with `PhyError(Communication)`.
+        fn next<M: MdioBus>(&mut self, mdio: &mut M, phy_addr: u8) -> Result<Option<u32>> {
             let bmcr_val = mdio.read(phy_addr, phy_reg::BMCR)?;
+            if bmcr_val == 0xFFFF {
+                return Err(PhyError::Communication.into());
+            }
             if (bmcr_val & bmcr::RESET) == 0 {
-                return Ok(());
+                return Ok(None);
+            }
+            if self.waited_us >= self.budget_us {
+                return Err(PhyError::ResetTimeout.into());
             }
+            self.waited_us = self.waited_us.saturating_add(SOFT_RESET_POLL_US);
+            Ok(Some(SOFT_RESET_POLL_US))
         }
+    }
+
+    /// Perform soft reset via BMCR, waiting at most
+    /// [`SOFT_RESET_BUDGET_US`]
+    ///
+    /// [`soft_reset_within`] timed by the bus's own
+    /// [`delay_us`](MdioBus::delay_us); used by [`PhyDriver::init`]
+    /// implementations, which have no other delay at hand.
+    ///
+    /// # Errors
+    /// Those of [`soft_reset_within`].
+    pub fn soft_reset<M: MdioBus>(mdio: &mut M, phy_addr: u8) -> Result<()> {
+        let mut poll = ResetPoll::start(mdio, phy_addr, SOFT_RESET_BUDGET_US)?;
+        while let Some(us) = poll.next(mdio, phy_addr)? {
+            mdio.delay_us(us);
+        }
+        Ok(())
+    }
 
-        // If we get here, reset didn't complete - but don't fail
-        // Some PHYs may be slow to clear the bit
+    /// Perform soft reset via BMCR, waiting at most `budget_us`
+    ///
+    /// Reads BMCR every [`SOFT_RESET_POLL_US`], delaying with `delay` in
+    /// between; [`SOFT_RESET_BUDGET_US`] is the standard's limit.
+    ///
+    /// # Errors
+    /// - `PhyError(ResetTimeout)` - BMCR.RESET still set after `budget_us`
+    /// - `PhyError(Communication)` - No PHY answers at `phy_addr`
+    /// - MDIO errors
+    pub fn soft_reset_within<M: MdioBus, D: DelayNs>(
+        mdio: &mut M,
+        phy_addr: u8,
+        delay: &mut D,
+        budget_us: u32,
+    ) -> Result<()> {
+        let mut poll = ResetPoll::start(mdio, phy_addr, budget_us)?;
+        while let Some(us) = poll.next(mdio, phy_addr)? {
+            delay.delay_us(us);
+        }
+        Ok(())
+    }
+
+    /// Async [`soft_reset_within`]: the waits between BMCR reads yield to
+    /// the executor
+    ///
+    /// # Errors
+    /// Those of [`soft_reset_within`].
+    #[cfg(feature = "async")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+    pub async fn soft_reset_async<M: MdioBus, D: embedded_hal_async::delay::DelayNs>(
+        mdio: &mut M,
+        phy_addr: u8,
+        delay: &mut D,
+        budget_us: u32,
+    ) -> Result<()> {
+        let mut poll = ResetPoll::start(mdio, phy_addr, budget_us)?;
+        while let Some(us) = poll.next(mdio, phy_addr)? {
+            delay.delay_us(us).await;
+        }
         Ok(())
     }
 
@@ -292,7 +497,75 @@ pub mod ieee802_3 {
             speed_10_hd: (anlpar_val & anar::T10_HD) != 0,
             auto_negotiation: true, // If we have ANLPAR, partner supports AN
             pause: (anlpar_val & anar::PAUSE) != 0,
-            pause_asymmetric: false,
+            pause_asymmetric: (anlpar_val & anar::PAUSE_ASYM) != 0,
+        })
+    }
+
+    /// Resolve PAUSE directions from our advertisement and the partner's
+    /// (IEEE 802.3 Table 28B-3)
+    ///
+    /// Returns `(tx_pause, rx_pause)`. Only meaningful for a full-duplex
+    /// link that came up through auto-negotiation.
+    #[must_use]
+    pub const fn resolve_pause(local: u16, partner: u16) -> (bool, bool) {
+        let local_pause = (local & anar::PAUSE) != 0;
+        let local_asym = (local & anar::PAUSE_ASYM) != 0;
+        let partner_pause = (partner & anar::PAUSE) != 0;
+        let partner_asym = (partner & anar::PAUSE_ASYM) != 0;
+
+        if local_pause && partner_pause {
+            (true, true)
+        } else if local_asym && partner_pause && partner_asym {
+            (true, false)
+        } else if local_pause && local_asym && partner_asym {
+            (false, true)
+        } else {
+            (false, false)
+        }
+    }
+
+    /// Read ANAR and ANLPAR and resolve the PAUSE directions
+    ///
+    /// Half-duplex links and forced links never pause.
+    pub fn read_pause<M: MdioBus>(
+        mdio: &mut M,
+        phy_addr: u8,
+        duplex: Duplex,
+    ) -> Result<(bool, bool)> {
+        if duplex != Duplex::Full || (mdio.read(phy_addr, phy_reg::BMCR)? & bmcr::AN_ENABLE) == 0 {
+            return Ok((false, false));
+        }
+        let anar_val = mdio.read(phy_addr, phy_reg::ANAR)?;
+        let anlpar_val = mdio.read(phy_addr, phy_reg::ANLPAR)?;
+        Ok(resolve_pause(anar_val, anlpar_val))
+    }
+
+    /// Read fault indications from BMSR and ANLPAR
+    ///
+    /// A remote fault takes precedence over a local jabber condition.
+    pub fn read_fault<M: MdioBus>(mdio: &mut M, phy_addr: u8) -> Result<Option<FaultKind>> {
+        use crate::internal::phy_regs::standard::anlpar;
+
+        let bmsr_val = mdio.read(phy_addr, phy_reg::BMSR)?;
+        let anlpar_val = mdio.read(phy_addr, phy_reg::ANLPAR)?;
+
+        if (bmsr_val & bmsr::REMOTE_FAULT) != 0 || (anlpar_val & anlpar::REMOTE_FAULT) != 0 {
+            Ok(Some(FaultKind::Remote))
+        } else if (bmsr_val & bmsr::JABBER_DETECT) != 0 {
+            Ok(Some(FaultKind::Jabber))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Read remote fault and jabber bits from BMSR
+    pub fn read_link_quality<M: MdioBus>(mdio: &mut M, phy_addr: u8) -> Result<LinkQuality> {
+        let bmsr_val = mdio.read(phy_addr, phy_reg::BMSR)?;
+        Ok(LinkQuality {
+            remote_fault: (bmsr_val & bmsr::REMOTE_FAULT) != 0,
+            jabber: (bmsr_val & bmsr::JABBER_DETECT) != 0,
+            symbol_errors: None,
+            energy_detected: None,
         })
     }
 
@@ -315,3 +588,129 @@ pub mod ieee802_3 {
         Ok(LinkStatus::new(speed, duplex))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::driver::error::PhyError;
+    use crate::testing::{MockDelay, MockMdioBus};
+
+    /// Bus with no PHY attached: the pulled-up line reads all ones
+    struct Floating;
+
+    impl MdioBus for Floating {
+        fn read(&mut self, _phy_addr: u8, _reg_addr: u8) -> Result<u16> {
+            Ok(0xFFFF)
+        }
+
+        fn write(&mut self, _phy_addr: u8, _reg_addr: u8, _value: u16) -> Result<()> {
+            Ok(())
+        }
+
+        fn is_busy(&self) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn resolve_pause_follows_table_28b_3() {
+        use crate::internal::phy_regs::standard::anar::{PAUSE, PAUSE_ASYM};
+
+        let both = PAUSE | PAUSE_ASYM;
+        for (local, partner, expected) in [
+            (PAUSE, PAUSE, (true, true)),
+            (PAUSE, both, (true, true)),
+            (both, PAUSE, (true, true)),
+            (PAUSE_ASYM, both, (true, false)),
+            (both, PAUSE_ASYM, (false, true)),
+            (PAUSE_ASYM, PAUSE_ASYM, (false, false)),
+            (PAUSE_ASYM, PAUSE, (false, false)),
+            (PAUSE, PAUSE_ASYM, (false, false)),
+            (0, both, (false, false)),
+        ] {
+            assert_eq!(
+                ieee802_3::resolve_pause(local, partner),
+                expected,
+                "local {local:#06x} partner {partner:#06x}"
+            );
+        }
+    }
+
+    #[test]
+    fn soft_reset_within_completes_without_waiting() {
+        let mut mdio = MockMdioBus::new();
+        let mut delay = MockDelay::new();
+        ieee802_3::soft_reset_within(&mut mdio, 0, &mut delay, 1000).unwrap();
+        assert_eq!(delay.total_ns(), 0);
+    }
+
+    #[test]
+    fn soft_reset_within_times_out_after_budget() {
+        let mut mdio = MockMdioBus::new();
+        mdio.set_reset_stuck(true);
+        let mut delay = MockDelay::new();
+        assert_eq!(
+            ieee802_3::soft_reset_within(&mut mdio, 0, &mut delay, 1000),
+            Err(PhyError::ResetTimeout.into())
+        );
+        assert_eq!(delay.total_ns(), 1_000_000);
+    }
+
+    #[test]
+    fn soft_reset_times_out_on_the_bus_delay() {
+        let mut mdio = MockMdioBus::new();
+        ieee802_3::soft_reset(&mut mdio, 0).unwrap();
+        assert_eq!(mdio.waited_us(), 0);
+
+        mdio.set_reset_stuck(true);
+        assert_eq!(
+            ieee802_3::soft_reset(&mut mdio, 0),
+            Err(PhyError::ResetTimeout.into())
+        );
+        assert_eq!(mdio.waited_us(), u64::from(ieee802_3::SOFT_RESET_BUDGET_US));
+    }
+
+    #[test]
+    fn soft_reset_without_phy_is_phy_error() {
+        assert_eq!(
+            ieee802_3::soft_reset(&mut Floating, 0),
+            Err(PhyError::Communication.into())
+        );
+        assert_eq!(
+            ieee802_3::soft_reset_within(&mut Floating, 0, &mut MockDelay::new(), 1000),
+            Err(PhyError::Communication.into())
+        );
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn soft_reset_async_times_out_after_budget() {
+        use core::pin::pin;
+        use core::task::{Context, Poll, Waker};
+
+        /// Completes every delay at once, summing the requested time
+        struct Instant(u32);
+
+        impl embedded_hal_async::delay::DelayNs for Instant {
+            async fn delay_ns(&mut self, ns: u32) {
+                self.0 += ns / 1000;
+            }
+
+            async fn delay_us(&mut self, us: u32) {
+                self.0 += us;
+            }
+        }
+
+        let mut mdio = MockMdioBus::new();
+        mdio.set_reset_stuck(true);
+        let mut delay = Instant(0);
+        let result = {
+            let mut future = pin!(ieee802_3::soft_reset_async(&mut mdio, 0, &mut delay, 1000));
+            future
+                .as_mut()
+                .poll(&mut Context::from_waker(Waker::noop()))
+        };
+        assert_eq!(result, Poll::Ready(Err(PhyError::ResetTimeout.into())));
+        assert_eq!(delay.0, 1000);
+    }
+}
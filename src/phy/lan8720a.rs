@@ -84,7 +84,8 @@ use crate::driver::error::Result;
 use crate::hal::mdio::MdioBus;
 use crate::internal::phy_regs::lan8720a as regs_int;
 
-use super::generic::{LinkStatus, PhyCapabilities, PhyDriver, ieee802_3};
+use super::dump::PhyDump;
+use super::generic::{FaultKind, LinkQuality, LinkStatus, PhyCapabilities, PhyDriver, ieee802_3};
 
 // =============================================================================
 // LAN8720A Constants
@@ -103,7 +104,6 @@ pub const LAN8720A_PHY_ID_MASK: u32 = regs_int::phy_id::MASK;
 
 // Internal timing constants
 use regs_int::timing::AN_MAX_ATTEMPTS;
-use regs_int::timing::RESET_MAX_ATTEMPTS;
 use regs_int::timing::RESET_PULSE_US;
 use regs_int::timing::RESET_RECOVERY_US;
 
@@ -195,13 +195,13 @@ pub mod smr {
 pub mod scsir {
     use super::regs_int::scsir as scsir_int;
 
-    /// AMDIXCTRL - Auto-MDIX control
+    /// AMDIXCTRL - Disable Auto-MDIX and use CH_SELECT
     pub const AMDIXCTRL: u16 = scsir_int::AMDIXCTRL;
-    /// CH_SELECT - Manual crossover (when AMDIXCTRL=1)
+    /// CH_SELECT - Manual crossover: 0 = MDI, 1 = MDI-X (when AMDIXCTRL=1)
     pub const CH_SELECT: u16 = scsir_int::CH_SELECT;
     /// SQEOFF - Disable SQE test
     pub const SQEOFF: u16 = scsir_int::SQEOFF;
-    /// XPOL - Invert polarity (10BASE-T only)
+    /// XPOL - 10BASE-T polarity reversed (read-only)
     pub const XPOL: u16 = scsir_int::XPOL;
 }
 
@@ -253,6 +253,60 @@ pub mod pscsr {
     pub const HCDSPEED_100FD: u16 = pscsr_int::HCDSPEED_100FD;
 }
 
+// =============================================================================
+// Auto-MDIX
+// =============================================================================
+
+/// Cable crossover (MDI/MDI-X) mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum MdixMode {
+    /// Detect straight-through vs crossover cabling automatically
+    #[default]
+    Auto,
+    /// Force MDI (straight-through, TX on pins 1/2)
+    Mdi,
+    /// Force MDI-X (crossover, TX on pins 3/6)
+    Mdix,
+}
+
+/// Crossover state read back from the PHY
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct MdixStatus {
+    /// Configured mode
+    pub mode: MdixMode,
+    /// Pair assignment in use (`Mdi` or `Mdix`), if known
+    ///
+    /// The LAN8720A does not report the Auto-MDIX decision, so this is
+    /// `None` in [`MdixMode::Auto`].
+    pub resolved: Option<MdixMode>,
+    /// 10BASE-T receive polarity is reversed
+    pub polarity_reversed: bool,
+}
+
+impl MdixStatus {
+    /// Decode from a SCSIR register value
+    #[must_use]
+    pub const fn from_scsir(value: u16) -> Self {
+        let mode = if (value & scsir::AMDIXCTRL) == 0 {
+            MdixMode::Auto
+        } else if (value & scsir::CH_SELECT) != 0 {
+            MdixMode::Mdix
+        } else {
+            MdixMode::Mdi
+        };
+        Self {
+            mode,
+            resolved: match mode {
+                MdixMode::Auto => None,
+                forced => Some(forced),
+            },
+            polarity_reversed: (value & scsir::XPOL) != 0,
+        }
+    }
+}
+
 // =============================================================================
 // LAN8720A Driver (without reset pin)
 // =============================================================================
@@ -320,6 +374,33 @@ impl Lan8720a {
         Ok(Some(link))
     }
 
+    /// Read fault indications (remote fault, jabber, far loopback)
+    pub fn read_fault<M: MdioBus>(&self, mdio: &mut M) -> Result<Option<FaultKind>> {
+        if let Some(fault) = ieee802_3::read_fault(mdio, self.addr)? {
+            return Ok(Some(fault));
+        }
+        let mcsr = mdio.read(self.addr, reg::MCSR)?;
+        if (mcsr & mcsr::FARLOOPBACK) != 0 {
+            return Ok(Some(FaultKind::FarLoopback));
+        }
+        Ok(None)
+    }
+
+    /// Read speed/duplex and attach any fault indication and the resolved
+    /// PAUSE directions
+    fn read_link_with_fault<M: MdioBus>(&self, mdio: &mut M) -> Result<Option<LinkStatus>> {
+        match self.read_speed_indication(mdio)? {
+            Some(link) => {
+                let (tx_pause, rx_pause) = ieee802_3::read_pause(mdio, self.addr, link.duplex)?;
+                Ok(Some(
+                    link.with_fault(self.read_fault(mdio)?)
+                        .with_pause(tx_pause, rx_pause),
+                ))
+            }
+            None => Ok(None),
+        }
+    }
+
     /// Enable or disable Energy Detect Power Down mode
     ///
     /// When enabled, the PHY will enter a low-power state when no link
@@ -365,6 +446,26 @@ impl Lan8720a {
         mdio.read(self.addr, reg::SECR)
     }
 
+    /// Select automatic or forced MDI/MDI-X crossover
+    ///
+    /// Takes effect on the next link negotiation.
+    pub fn set_mdix<M: MdioBus>(&mut self, mdio: &mut M, mode: MdixMode) -> Result<()> {
+        let mut value = mdio.read(self.addr, reg::SCSIR)?;
+        value &= !(scsir::AMDIXCTRL | scsir::CH_SELECT);
+        match mode {
+            MdixMode::Auto => {}
+            MdixMode::Mdi => value |= scsir::AMDIXCTRL,
+            MdixMode::Mdix => value |= scsir::AMDIXCTRL | scsir::CH_SELECT,
+        }
+        mdio.write(self.addr, reg::SCSIR, value)
+    }
+
+    /// Read the crossover configuration and 10BASE-T polarity
+    pub fn mdix_status<M: MdioBus>(&self, mdio: &mut M) -> Result<MdixStatus> {
+        let value = mdio.read(self.addr, reg::SCSIR)?;
+        Ok(MdixStatus::from_scsir(value))
+    }
+
     /// Configure advertisement for auto-negotiation
     ///
     /// # Arguments
@@ -393,6 +494,9 @@ impl Lan8720a {
         if caps.pause {
             anar_val |= anar::PAUSE;
         }
+        if caps.pause_asymmetric {
+            anar_val |= anar::PAUSE_ASYM;
+        }
 
         mdio.write(self.addr, phy_reg::ANAR, anar_val)
     }
@@ -418,7 +522,7 @@ impl PhyDriver for Lan8720a {
     }
 
     fn soft_reset<M: MdioBus>(&mut self, mdio: &mut M) -> Result<()> {
-        ieee802_3::soft_reset(mdio, self.addr, RESET_MAX_ATTEMPTS)
+        ieee802_3::soft_reset(mdio, self.addr)
     }
 
     fn is_link_up<M: MdioBus>(&self, mdio: &mut M) -> Result<bool> {
@@ -431,7 +535,7 @@ impl PhyDriver for Lan8720a {
         }
 
         // Use vendor-specific register for accurate speed/duplex
-        self.read_speed_indication(mdio)
+        self.read_link_with_fault(mdio)
     }
 
     fn poll_link<M: MdioBus>(&mut self, mdio: &mut M) -> Result<Option<LinkStatus>> {
@@ -440,7 +544,7 @@ impl PhyDriver for Lan8720a {
         if link_up && !self.last_link_up {
             // Link just came up - get status
             self.last_link_up = true;
-            return self.read_speed_indication(mdio);
+            return self.read_link_with_fault(mdio);
         }
 
         if !link_up && self.last_link_up {
@@ -479,6 +583,29 @@ impl PhyDriver for Lan8720a {
     fn link_partner_abilities<M: MdioBus>(&self, mdio: &mut M) -> Result<PhyCapabilities> {
         ieee802_3::read_link_partner(mdio, self.addr)
     }
+
+    fn link_quality<M: MdioBus>(&self, mdio: &mut M) -> Result<LinkQuality> {
+        let mut quality = ieee802_3::read_link_quality(mdio, self.addr)?;
+        quality.symbol_errors = Some(self.symbol_error_count(mdio)?);
+        quality.energy_detected = Some(self.is_energy_on(mdio)?);
+        Ok(quality)
+    }
+
+    fn debug_dump<M: MdioBus>(&self, mdio: &mut M) -> Result<PhyDump> {
+        let mut dump = PhyDump::read_standard(mdio, self.addr)?;
+        // ISR is clear-on-read and deliberately left out
+        for (r, name) in [
+            (reg::MCSR, "MCSR"),
+            (reg::SMR, "SMR"),
+            (reg::SECR, "SECR"),
+            (reg::SCSIR, "SCSIR"),
+            (reg::IMR, "IMR"),
+            (reg::PSCSR, "PSCSR"),
+        ] {
+            dump.read_vendor(mdio, r, name)?;
+        }
+        Ok(dump)
+    }
 }
 
 // =============================================================================
@@ -602,6 +729,11 @@ impl<RST: OutputPin> Lan8720aWithReset<RST> {
         self.inner.read_speed_indication(mdio)
     }
 
+    /// Read fault indications (remote fault, jabber, far loopback)
+    pub fn read_fault<M: MdioBus>(&self, mdio: &mut M) -> Result<Option<FaultKind>> {
+        self.inner.read_fault(mdio)
+    }
+
     /// Enable or disable Energy Detect Power Down mode
     pub fn set_energy_detect_powerdown<M: MdioBus>(
         &mut self,
@@ -636,6 +768,16 @@ impl<RST: OutputPin> Lan8720aWithReset<RST> {
         self.inner.symbol_error_count(mdio)
     }
 
+    /// Select automatic or forced MDI/MDI-X crossover
+    pub fn set_mdix<M: MdioBus>(&mut self, mdio: &mut M, mode: MdixMode) -> Result<()> {
+        self.inner.set_mdix(mdio, mode)
+    }
+
+    /// Read the crossover configuration and 10BASE-T polarity
+    pub fn mdix_status<M: MdioBus>(&self, mdio: &mut M) -> Result<MdixStatus> {
+        self.inner.mdix_status(mdio)
+    }
+
     /// Configure advertisement for auto-negotiation
     pub fn configure_advertisement<M: MdioBus>(
         &mut self,
@@ -694,6 +836,14 @@ impl<RST: OutputPin> PhyDriver for Lan8720aWithReset<RST> {
     fn link_partner_abilities<M: MdioBus>(&self, mdio: &mut M) -> Result<PhyCapabilities> {
         self.inner.link_partner_abilities(mdio)
     }
+
+    fn link_quality<M: MdioBus>(&self, mdio: &mut M) -> Result<LinkQuality> {
+        self.inner.link_quality(mdio)
+    }
+
+    fn debug_dump<M: MdioBus>(&self, mdio: &mut M) -> Result<PhyDump> {
+        self.inner.debug_dump(mdio)
+    }
 }
 
 // =============================================================================
@@ -736,6 +886,7 @@ mod tests {
 
     use super::*;
     use crate::driver::config::{Duplex, Speed};
+    use crate::driver::error::PhyError;
     use crate::internal::phy_regs::standard::{bmcr, phy_reg};
     use crate::testing::MockMdioBus;
     use std::vec::Vec;
@@ -860,16 +1011,15 @@ mod tests {
     }
 
     #[test]
-    fn test_soft_reset_waits_for_reset_clear() {
+    fn test_init_reports_reset_timeout() {
         let mut mdio = MockMdioBus::new();
         mdio.setup_lan8720a(0);
-        // Reset bit stays set (simulates slow reset)
-        mdio.set_register(0, phy_reg::BMCR, bmcr::RESET);
+        // Reset bit never self-clears
+        mdio.set_reset_stuck(true);
 
         let mut phy = Lan8720a::new(0);
-        // This should poll multiple times and eventually return
-        // (implementation doesn't fail even if reset doesn't clear)
-        phy.soft_reset(&mut mdio).unwrap();
+        assert_eq!(phy.init(&mut mdio), Err(PhyError::ResetTimeout.into()));
+        assert_eq!(mdio.waited_us(), u64::from(ieee802_3::SOFT_RESET_BUDGET_US));
     }
 
     // =========================================================================
@@ -956,6 +1106,28 @@ mod tests {
         assert_eq!(link.duplex, Duplex::Full);
     }
 
+    #[test]
+    fn test_poll_link_resolves_pause() {
+        use crate::internal::phy_regs::standard::{anar, anlpar};
+
+        let mut mdio = MockMdioBus::new();
+        mdio.setup_lan8720a(0);
+        mdio.set_register(0, reg::PSCSR, pscsr::AUTODONE | pscsr::HCDSPEED_100FD);
+        mdio.set_register(0, phy_reg::ANAR, 0x01E1 | anar::PAUSE);
+        mdio.simulate_link_up_100_fd(0);
+        let partner = mdio.get_register(0, phy_reg::ANLPAR).unwrap();
+        mdio.set_register(0, phy_reg::ANLPAR, partner | anlpar::PAUSE);
+
+        let mut phy = Lan8720a::new(0);
+        let link = phy.poll_link(&mut mdio).unwrap().unwrap();
+        assert!(link.tx_pause && link.rx_pause);
+
+        // Forced links do not pause, whatever the registers say
+        mdio.set_register(0, phy_reg::BMCR, 0x2100);
+        let link = phy.link_status(&mut mdio).unwrap().unwrap();
+        assert!(!link.tx_pause && !link.rx_pause);
+    }
+
     #[test]
     fn test_poll_link_returns_none_when_link_stays_up() {
         let mut mdio = MockMdioBus::new();
@@ -1406,6 +1578,136 @@ mod tests {
         assert!(anar & anar::PAUSE != 0, "Should advertise PAUSE");
     }
 
+    #[test]
+    fn test_link_quality_reads_vendor_counters() {
+        let mut mdio = MockMdioBus::new();
+        mdio.setup_lan8720a(0);
+        mdio.simulate_link_up_100_fd(0);
+        mdio.set_register(0, reg::SECR, 42);
+        mdio.set_register(0, reg::MCSR, mcsr::ENERGYON);
+
+        let phy = Lan8720a::new(0);
+        let quality = phy.link_quality(&mut mdio).unwrap();
+
+        assert_eq!(quality.symbol_errors, Some(42));
+        assert_eq!(quality.energy_detected, Some(true));
+        assert!(!quality.remote_fault);
+        assert!(!quality.jabber);
+        assert!(!quality.is_degraded(100));
+        assert!(quality.is_degraded(10));
+    }
+
+    #[test]
+    fn test_link_quality_remote_fault_is_degraded() {
+        use crate::internal::phy_regs::standard::bmsr;
+
+        let mut mdio = MockMdioBus::new();
+        mdio.setup_lan8720a(0);
+        let bmsr_val = mdio.get_register(0, phy_reg::BMSR).unwrap();
+        mdio.set_register(0, phy_reg::BMSR, bmsr_val | bmsr::REMOTE_FAULT);
+        mdio.set_register(0, reg::SECR, 0);
+
+        let phy = Lan8720a::new(0);
+        let quality = phy.link_quality(&mut mdio).unwrap();
+
+        assert!(quality.remote_fault);
+        assert!(quality.is_degraded(u16::MAX));
+    }
+
+    #[test]
+    fn test_debug_dump_includes_vendor_registers() {
+        let mut mdio = MockMdioBus::new();
+        mdio.setup_lan8720a(0);
+        mdio.set_register(0, reg::PSCSR, pscsr::AUTODONE | pscsr::HCDSPEED_100FD);
+
+        let dump = Lan8720a::new(0).debug_dump(&mut mdio).unwrap();
+        let pscsr_val = dump.vendor().find(|r| r.name == "PSCSR").unwrap();
+        assert_eq!(pscsr_val.value, pscsr::AUTODONE | pscsr::HCDSPEED_100FD);
+        // Reading ISR would clear pending interrupts
+        assert!(dump.vendor().all(|r| r.reg != reg::ISR));
+    }
+
+    #[test]
+    fn test_link_status_reports_remote_fault() {
+        use crate::internal::phy_regs::standard::anlpar;
+
+        let mut mdio = MockMdioBus::new();
+        mdio.setup_lan8720a(0);
+        mdio.simulate_link_up_100_fd(0);
+        mdio.set_register(0, reg::PSCSR, pscsr::AUTODONE | pscsr::HCDSPEED_100FD);
+
+        let phy = Lan8720a::new(0);
+        let link = phy.link_status(&mut mdio).unwrap().unwrap();
+        assert_eq!(link.fault, None);
+
+        let anlpar_val = mdio.get_register(0, phy_reg::ANLPAR).unwrap();
+        mdio.set_register(0, phy_reg::ANLPAR, anlpar_val | anlpar::REMOTE_FAULT);
+        let link = phy.link_status(&mut mdio).unwrap().unwrap();
+        assert_eq!(link.fault, Some(FaultKind::Remote));
+        assert!(link.fault.unwrap().is_remote());
+        assert!(link.same_mode(&LinkStatus::fast_full()));
+    }
+
+    #[test]
+    fn test_read_fault_far_loopback() {
+        let mut mdio = MockMdioBus::new();
+        mdio.setup_lan8720a(0);
+        mdio.set_register(0, reg::MCSR, mcsr::FARLOOPBACK);
+
+        let phy = Lan8720a::new(0);
+        let fault = phy.read_fault(&mut mdio).unwrap();
+        assert_eq!(fault, Some(FaultKind::FarLoopback));
+        assert!(!fault.unwrap().is_remote());
+    }
+
+    #[test]
+    fn test_set_mdix_modes() {
+        let mut mdio = MockMdioBus::new();
+        mdio.setup_lan8720a(0);
+        mdio.set_register(0, reg::SCSIR, scsir::SQEOFF);
+        let mut phy = Lan8720a::new(0);
+
+        phy.set_mdix(&mut mdio, MdixMode::Mdix).unwrap();
+        let value = mdio.get_register(0, reg::SCSIR).unwrap();
+        assert_eq!(
+            value,
+            scsir::SQEOFF | scsir::AMDIXCTRL | scsir::CH_SELECT,
+            "Other SCSIR bits should be preserved"
+        );
+
+        phy.set_mdix(&mut mdio, MdixMode::Mdi).unwrap();
+        let value = mdio.get_register(0, reg::SCSIR).unwrap();
+        assert_eq!(value, scsir::SQEOFF | scsir::AMDIXCTRL);
+
+        phy.set_mdix(&mut mdio, MdixMode::Auto).unwrap();
+        let value = mdio.get_register(0, reg::SCSIR).unwrap();
+        assert_eq!(value, scsir::SQEOFF);
+    }
+
+    #[test]
+    fn test_mdix_status() {
+        let mut mdio = MockMdioBus::new();
+        mdio.setup_lan8720a(0);
+        let mut phy = Lan8720a::new(0);
+
+        mdio.set_register(0, reg::SCSIR, 0);
+        let status = phy.mdix_status(&mut mdio).unwrap();
+        assert_eq!(status.mode, MdixMode::Auto);
+        assert_eq!(status.resolved, None);
+        assert!(!status.polarity_reversed);
+
+        phy.set_mdix(&mut mdio, MdixMode::Mdix).unwrap();
+        mdio.set_register(
+            0,
+            reg::SCSIR,
+            mdio.get_register(0, reg::SCSIR).unwrap() | scsir::XPOL,
+        );
+        let status = phy.mdix_status(&mut mdio).unwrap();
+        assert_eq!(status.mode, MdixMode::Mdix);
+        assert_eq!(status.resolved, Some(MdixMode::Mdix));
+        assert!(status.polarity_reversed);
+    }
+
     // =========================================================================
     // PHY Address Tests
     // =========================================================================
@@ -31,6 +31,31 @@
 //! }
 //! ```
 //!
+//! # Link State Machine
+//!
+//! [`PhyStateMachine`] is an optional layer over [`PhyDriver`] that tracks
+//! `Down` / `Negotiating` / `Up` / `Fault` and reports [`PhyEvent`]s, with
+//! debounce and negotiation timeouts built in:
+//!
+//! ```ignore
+//! use ph_esp32_mac::phy::{PhyEvent, PhyStateMachine};
+//!
+//! let mut link = PhyStateMachine::new();
+//! if let Some(PhyEvent::LinkUp(status)) = link.poll(&phy, &mut mdio) {
+//!     // update MAC config
+//! }
+//! ```
+//!
+//! For uplink failover, implement [`NetLinkStatus`] and poll with
+//! [`PhyStateMachine::poll_notify`]; it is called on every debounced link
+//! up/down (see the `eth_failover` example).
+//!
+//! # Register Dump
+//!
+//! [`PhyDriver::debug_dump`] snapshots BMCR/BMSR/ANAR/ANLPAR/ANER plus the
+//! driver's vendor registers into a printable [`PhyDump`] for link
+//! negotiation bug reports.
+//!
 //! # Reset Pin Support
 //!
 //! `Lan8720aWithReset` accepts any `embedded_hal::digital::OutputPin`:
@@ -48,11 +73,15 @@
 //!
 //! - [`crate::hal::mdio`] - MDIO bus abstraction
 
+pub mod dump;
 pub mod generic;
 pub mod lan8720a;
+pub mod state;
 
-pub use generic::{LinkStatus, PhyCapabilities, PhyDriver};
-pub use lan8720a::{Lan8720a, Lan8720aWithReset};
+pub use dump::{PHY_DUMP_VENDOR_REGS, PhyDump, VendorRegister};
+pub use generic::{FaultKind, LinkQuality, LinkStatus, PhyCapabilities, PhyDriver};
+pub use lan8720a::{Lan8720a, Lan8720aWithReset, MdixMode, MdixStatus};
+pub use state::{FlapDamping, NetLinkStatus, PhyEvent, PhyState, PhyStateMachine};
 
 // Re-export IEEE 802.3 standard register definitions from internal module
 // These are implementation details for PHY drivers
@@ -0,0 +1,807 @@
+//! PHY Link State Machine
+//!
+//! [`PhyStateMachine`] turns raw link samples (from [`PhyDriver::link_status`]
+//! or a PHY interrupt) into explicit states and transition events, with
+//! up/down debounce, flap damping, fault detection, and auto-negotiation
+//! timeouts handled in one place.
+//!
+//! ```text
+//!            renegotiate()
+//!   Down ──────────────────▶ Negotiating
+//!    ▲ │                         │
+//!    │ │ link up                 │ link up
+//!    │ ▼                         ▼
+//!    └─── Up { speed, duplex } ◀─┘
+//!
+//!   any state ── N consecutive MDIO errors ──▶ Fault
+//! ```
+//!
+//! # Example
+//!
+//! ```ignore
+//! use ph_esp32_mac::phy::{PhyEvent, PhyStateMachine};
+//!
+//! let mut link = PhyStateMachine::new().with_down_debounce(2);
+//!
+//! loop {
+//!     match link.poll(&mut phy, &mut mdio) {
+//!         Some(PhyEvent::LinkUp(status)) => emac.set_speed(status.speed),
+//!         Some(PhyEvent::NegotiationTimeout) => { let _ = link.renegotiate(&mut phy, &mut mdio); }
+//!         _ => {}
+//!     }
+//!     delay.delay_ms(100);
+//! }
+//! ```
+
+use super::generic::{FaultKind, LinkStatus, PhyDriver};
+use crate::driver::error::Result;
+use crate::hal::mdio::MdioBus;
+
+// =============================================================================
+// States and Events
+// =============================================================================
+
+/// Link state tracked by [`PhyStateMachine`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PhyState {
+    /// No link
+    #[default]
+    Down,
+    /// Auto-negotiation restarted, waiting for link
+    Negotiating,
+    /// Link established with the given parameters
+    Up(LinkStatus),
+    /// Repeated MDIO failures; PHY is not responding
+    Fault,
+}
+
+impl PhyState {
+    /// Returns true if the link is up
+    #[must_use]
+    pub const fn is_up(&self) -> bool {
+        matches!(self, PhyState::Up(_))
+    }
+
+    /// Link parameters if the link is up
+    #[must_use]
+    pub const fn link(&self) -> Option<LinkStatus> {
+        match self {
+            PhyState::Up(status) => Some(*status),
+            _ => None,
+        }
+    }
+}
+
+/// Transition reported by [`PhyStateMachine::update`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PhyEvent {
+    /// Entered `Negotiating`
+    NegotiationStarted,
+    /// Entered `Up` from another state
+    LinkUp(LinkStatus),
+    /// Still `Up`, but speed, duplex, or fault indication changed
+    LinkChanged(LinkStatus),
+    /// Still `Up` with the same speed/duplex, but the PHY now reports a fault
+    LinkFault(FaultKind),
+    /// Left `Up` for `Down` (after debounce)
+    LinkDown,
+    /// `Negotiating` timed out without link; now `Down`
+    NegotiationTimeout,
+    /// Entered `Fault`
+    Fault,
+    /// Left `Fault` with the link still down
+    Recovered,
+}
+
+// =============================================================================
+// Link Notifications
+// =============================================================================
+
+/// Receiver of link up/down notifications from [`PhyStateMachine`]
+///
+/// Implement this on whatever decides which uplink the application uses:
+/// [`PhyStateMachine::poll_notify`] calls it only on debounced transitions,
+/// so an Ethernet-primary / WiFi-fallback policy can switch stacks directly
+/// from these callbacks without its own debounce.
+///
+/// A PHY [`Fault`](PhyState::Fault) while the link was up is reported as
+/// link down.
+pub trait NetLinkStatus {
+    /// The link came up with the given negotiated parameters
+    fn link_up(&mut self, status: LinkStatus);
+
+    /// The link went down (cable, link partner, PHY fault, or flap hold-down)
+    fn link_down(&mut self);
+
+    /// The link stayed up but speed, duplex or fault indication changed
+    ///
+    /// Defaults to [`link_up`](Self::link_up).
+    fn link_changed(&mut self, status: LinkStatus) {
+        self.link_up(status);
+    }
+}
+
+impl<T: NetLinkStatus + ?Sized> NetLinkStatus for &mut T {
+    fn link_up(&mut self, status: LinkStatus) {
+        (**self).link_up(status);
+    }
+
+    fn link_down(&mut self) {
+        (**self).link_down();
+    }
+
+    fn link_changed(&mut self, status: LinkStatus) {
+        (**self).link_changed(status);
+    }
+}
+
+// =============================================================================
+// State Machine
+// =============================================================================
+
+/// Default number of consecutive down samples before leaving `Up`
+pub const DEFAULT_DOWN_DEBOUNCE: u8 = 2;
+
+/// Default number of consecutive MDIO errors before entering `Fault`
+pub const DEFAULT_FAULT_THRESHOLD: u8 = 3;
+
+/// Default number of samples to wait in `Negotiating` before timing out
+pub const DEFAULT_NEGOTIATION_TIMEOUT: u16 = 50;
+
+/// Flap damping parameters for [`PhyStateMachine`]
+///
+/// When the link goes down `max_flaps` times within `window` samples, up
+/// samples are ignored for the next `hold_down` samples so a noisy link
+/// does not keep reconfiguring the MAC and the network stack.
+///
+/// The window is fixed, not sliding: the first flap opens it, and once
+/// `window` samples have passed the count starts over at the next flap.
+/// `max_flaps` flaps less than `window` samples apart therefore do not
+/// trigger the hold-down when a window ends between them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FlapDamping {
+    /// Link-down transitions that trigger the hold-down (min 1)
+    pub max_flaps: u8,
+    /// Samples from the first flap over which flaps are counted
+    pub window: u16,
+    /// Samples to keep reporting `Down` once damping triggers
+    pub hold_down: u16,
+}
+
+impl FlapDamping {
+    /// Create flap damping parameters
+    #[must_use]
+    pub const fn new(max_flaps: u8, window: u16, hold_down: u16) -> Self {
+        Self {
+            max_flaps: if max_flaps == 0 { 1 } else { max_flaps },
+            window,
+            hold_down,
+        }
+    }
+}
+
+/// Link state machine driven by periodic PHY samples
+///
+/// Purely software: it never touches the MDIO bus except through
+/// [`poll`](Self::poll) and [`renegotiate`](Self::renegotiate), so it can be
+/// fed from any source (polling loop, PHY interrupt handler, tests).
+#[derive(Debug, Clone)]
+pub struct PhyStateMachine {
+    state: PhyState,
+    up_debounce: u8,
+    down_debounce: u8,
+    fault_threshold: u8,
+    negotiation_timeout: u16,
+    flap_damping: Option<FlapDamping>,
+    up_samples: u8,
+    down_samples: u8,
+    error_samples: u8,
+    negotiation_samples: u16,
+    flap_count: u8,
+    flap_window_samples: u16,
+    hold_down_remaining: u16,
+    interrupt_pending: bool,
+}
+
+impl PhyStateMachine {
+    /// Create a state machine in `Down` with default thresholds
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            state: PhyState::Down,
+            up_debounce: 1,
+            down_debounce: DEFAULT_DOWN_DEBOUNCE,
+            fault_threshold: DEFAULT_FAULT_THRESHOLD,
+            negotiation_timeout: DEFAULT_NEGOTIATION_TIMEOUT,
+            flap_damping: None,
+            up_samples: 0,
+            down_samples: 0,
+            error_samples: 0,
+            negotiation_samples: 0,
+            flap_count: 0,
+            flap_window_samples: 0,
+            hold_down_remaining: 0,
+            interrupt_pending: false,
+        }
+    }
+
+    /// Consecutive up samples required before reporting `LinkUp` (min 1)
+    #[must_use]
+    pub const fn with_up_debounce(mut self, samples: u8) -> Self {
+        self.up_debounce = if samples == 0 { 1 } else { samples };
+        self
+    }
+
+    /// Consecutive down samples required before `Up` → `Down` (min 1)
+    #[must_use]
+    pub const fn with_down_debounce(mut self, samples: u8) -> Self {
+        self.down_debounce = if samples == 0 { 1 } else { samples };
+        self
+    }
+
+    /// Consecutive MDIO errors required before entering `Fault` (min 1)
+    #[must_use]
+    pub const fn with_fault_threshold(mut self, errors: u8) -> Self {
+        self.fault_threshold = if errors == 0 { 1 } else { errors };
+        self
+    }
+
+    /// Samples to wait in `Negotiating` before timing out (min 1)
+    #[must_use]
+    pub const fn with_negotiation_timeout(mut self, samples: u16) -> Self {
+        self.negotiation_timeout = if samples == 0 { 1 } else { samples };
+        self
+    }
+
+    /// Enable flap damping (disabled by default)
+    #[must_use]
+    pub const fn with_flap_damping(mut self, damping: FlapDamping) -> Self {
+        self.flap_damping = Some(damping);
+        self
+    }
+
+    /// Returns true while flap damping is holding the link down
+    #[must_use]
+    pub const fn is_held_down(&self) -> bool {
+        self.hold_down_remaining > 0
+    }
+
+    /// Samples left before the flap hold-down expires
+    #[must_use]
+    pub const fn hold_down_remaining(&self) -> u16 {
+        self.hold_down_remaining
+    }
+
+    /// Current state
+    #[must_use]
+    pub const fn state(&self) -> PhyState {
+        self.state
+    }
+
+    /// Return to `Down` and clear all counters
+    pub fn reset(&mut self) {
+        self.state = PhyState::Down;
+        self.interrupt_pending = false;
+        self.flap_count = 0;
+        self.flap_window_samples = 0;
+        self.hold_down_remaining = 0;
+        self.clear_counters();
+    }
+
+    /// Note that the PHY raised a link interrupt
+    ///
+    /// The next sample is taken as authoritative and skips debounce.
+    pub fn on_interrupt(&mut self) {
+        self.interrupt_pending = true;
+    }
+
+    /// Mark that auto-negotiation was restarted externally
+    ///
+    /// Returns `NegotiationStarted` unless already negotiating.
+    pub fn negotiation_started(&mut self) -> Option<PhyEvent> {
+        if self.state == PhyState::Negotiating {
+            return None;
+        }
+        self.clear_counters();
+        self.state = PhyState::Negotiating;
+        Some(PhyEvent::NegotiationStarted)
+    }
+
+    /// Feed one link sample and return the resulting transition, if any
+    ///
+    /// `sample` is the result of [`PhyDriver::link_status`]: `Ok(Some(_))`
+    /// when the link is up, `Ok(None)` when down, `Err(_)` on MDIO failure.
+    pub fn update(&mut self, sample: Result<Option<LinkStatus>>) -> Option<PhyEvent> {
+        let interrupt = core::mem::take(&mut self.interrupt_pending);
+        let held_down = self.is_held_down();
+        self.tick_flap_window();
+
+        let Ok(link) = sample else {
+            self.error_samples = self.error_samples.saturating_add(1);
+            if self.state != PhyState::Fault && self.error_samples >= self.fault_threshold {
+                self.clear_counters();
+                self.state = PhyState::Fault;
+                return Some(PhyEvent::Fault);
+            }
+            return None;
+        };
+        self.error_samples = 0;
+
+        match (self.state, link) {
+            (PhyState::Up(current), Some(status)) => {
+                self.down_samples = 0;
+                if current == status {
+                    return None;
+                }
+                self.state = PhyState::Up(status);
+                match status.fault {
+                    Some(kind) if current.same_mode(&status) && current.fault.is_none() => {
+                        Some(PhyEvent::LinkFault(kind))
+                    }
+                    _ => Some(PhyEvent::LinkChanged(status)),
+                }
+            }
+            (_, Some(status)) => {
+                if held_down {
+                    self.up_samples = 0;
+                    return None;
+                }
+                self.up_samples = self.up_samples.saturating_add(1);
+                if interrupt || self.up_samples >= self.up_debounce {
+                    self.clear_counters();
+                    self.state = PhyState::Up(status);
+                    Some(PhyEvent::LinkUp(status))
+                } else {
+                    None
+                }
+            }
+            (PhyState::Up(_), None) => {
+                self.down_samples = self.down_samples.saturating_add(1);
+                if interrupt || self.down_samples >= self.down_debounce {
+                    self.clear_counters();
+                    self.state = PhyState::Down;
+                    self.record_flap();
+                    Some(PhyEvent::LinkDown)
+                } else {
+                    None
+                }
+            }
+            (PhyState::Negotiating, None) => {
+                self.up_samples = 0;
+                self.negotiation_samples = self.negotiation_samples.saturating_add(1);
+                if self.negotiation_samples >= self.negotiation_timeout {
+                    self.clear_counters();
+                    self.state = PhyState::Down;
+                    Some(PhyEvent::NegotiationTimeout)
+                } else {
+                    None
+                }
+            }
+            (PhyState::Fault, None) => {
+                self.up_samples = 0;
+                self.state = PhyState::Down;
+                Some(PhyEvent::Recovered)
+            }
+            (PhyState::Down, None) => {
+                self.up_samples = 0;
+                None
+            }
+        }
+    }
+
+    /// Sample the PHY over MDIO and feed the result to [`update`](Self::update)
+    pub fn poll<P: PhyDriver, M: MdioBus>(&mut self, phy: &P, mdio: &mut M) -> Option<PhyEvent> {
+        self.update(phy.link_status(mdio))
+    }
+
+    /// [`update`](Self::update), then notify `listener` if the link went
+    /// up, down, or changed parameters
+    pub fn update_notify<N: NetLinkStatus + ?Sized>(
+        &mut self,
+        sample: Result<Option<LinkStatus>>,
+        listener: &mut N,
+    ) -> Option<PhyEvent> {
+        let was_up = self.state.is_up();
+        let event = self.update(sample);
+        match (was_up, self.state) {
+            (false, PhyState::Up(status)) => listener.link_up(status),
+            (true, PhyState::Up(status)) if event.is_some() => listener.link_changed(status),
+            (true, PhyState::Up(_)) | (false, _) => {}
+            (true, _) => listener.link_down(),
+        }
+        event
+    }
+
+    /// [`poll`](Self::poll) and notify `listener` of link transitions
+    pub fn poll_notify<P: PhyDriver, M: MdioBus, N: NetLinkStatus + ?Sized>(
+        &mut self,
+        phy: &P,
+        mdio: &mut M,
+        listener: &mut N,
+    ) -> Option<PhyEvent> {
+        self.update_notify(phy.link_status(mdio), listener)
+    }
+
+    /// Restart auto-negotiation on the PHY and enter `Negotiating`
+    ///
+    /// # Errors
+    /// Returns the MDIO error if the restart could not be written.
+    pub fn renegotiate<P: PhyDriver, M: MdioBus>(
+        &mut self,
+        phy: &mut P,
+        mdio: &mut M,
+    ) -> Result<Option<PhyEvent>> {
+        phy.enable_auto_negotiation(mdio)?;
+        Ok(self.negotiation_started())
+    }
+
+    /// Advance the flap window and hold-down timer by one sample
+    fn tick_flap_window(&mut self) {
+        let Some(damping) = self.flap_damping else {
+            return;
+        };
+        self.hold_down_remaining = self.hold_down_remaining.saturating_sub(1);
+        self.flap_window_samples = self.flap_window_samples.saturating_add(1);
+        if self.flap_window_samples > damping.window {
+            self.flap_window_samples = 0;
+            self.flap_count = 0;
+        }
+    }
+
+    /// Count an `Up` → `Down` transition and start hold-down if needed
+    fn record_flap(&mut self) {
+        let Some(damping) = self.flap_damping else {
+            return;
+        };
+        if self.flap_count == 0 {
+            self.flap_window_samples = 0;
+        }
+        self.flap_count = self.flap_count.saturating_add(1);
+        if self.flap_count >= damping.max_flaps {
+            self.flap_count = 0;
+            self.hold_down_remaining = damping.hold_down;
+        }
+    }
+
+    fn clear_counters(&mut self) {
+        self.up_samples = 0;
+        self.down_samples = 0;
+        self.error_samples = 0;
+        self.negotiation_samples = 0;
+    }
+}
+
+impl Default for PhyStateMachine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::driver::error::{Error, IoError};
+    use crate::phy::Lan8720a;
+    use crate::phy::lan8720a::{pscsr, reg};
+    use crate::testing::MockMdioBus;
+
+    const UP: Result<Option<LinkStatus>> = Ok(Some(LinkStatus::fast_full()));
+    const DOWN: Result<Option<LinkStatus>> = Ok(None);
+
+    fn mdio_err() -> Result<Option<LinkStatus>> {
+        Err(Error::Io(IoError::Timeout))
+    }
+
+    #[test]
+    fn starts_down() {
+        let sm = PhyStateMachine::new();
+        assert_eq!(sm.state(), PhyState::Down);
+        assert!(!sm.state().is_up());
+        assert_eq!(sm.state().link(), None);
+    }
+
+    #[test]
+    fn link_up_from_down() {
+        let mut sm = PhyStateMachine::new();
+        assert_eq!(
+            sm.update(UP),
+            Some(PhyEvent::LinkUp(LinkStatus::fast_full()))
+        );
+        assert_eq!(sm.state().link(), Some(LinkStatus::fast_full()));
+        assert_eq!(sm.update(UP), None);
+    }
+
+    #[test]
+    fn link_change_while_up() {
+        let mut sm = PhyStateMachine::new();
+        sm.update(UP);
+        assert_eq!(
+            sm.update(Ok(Some(LinkStatus::slow_half()))),
+            Some(PhyEvent::LinkChanged(LinkStatus::slow_half()))
+        );
+    }
+
+    #[test]
+    fn fault_while_up_is_reported() {
+        let mut sm = PhyStateMachine::new();
+        sm.update(UP);
+
+        let faulted = LinkStatus::fast_full().with_fault(Some(FaultKind::Remote));
+        assert_eq!(
+            sm.update(Ok(Some(faulted))),
+            Some(PhyEvent::LinkFault(FaultKind::Remote))
+        );
+        assert_eq!(sm.update(Ok(Some(faulted))), None);
+        assert_eq!(
+            sm.update(UP),
+            Some(PhyEvent::LinkChanged(LinkStatus::fast_full()))
+        );
+    }
+
+    #[test]
+    fn link_down_is_debounced() {
+        let mut sm = PhyStateMachine::new().with_down_debounce(3);
+        sm.update(UP);
+
+        assert_eq!(sm.update(DOWN), None);
+        assert_eq!(sm.update(DOWN), None);
+        assert!(sm.state().is_up());
+        assert_eq!(sm.update(DOWN), Some(PhyEvent::LinkDown));
+        assert_eq!(sm.state(), PhyState::Down);
+    }
+
+    #[test]
+    fn link_glitch_resets_debounce() {
+        let mut sm = PhyStateMachine::new().with_down_debounce(2);
+        sm.update(UP);
+
+        assert_eq!(sm.update(DOWN), None);
+        assert_eq!(sm.update(UP), None);
+        assert_eq!(sm.update(DOWN), None);
+        assert!(sm.state().is_up());
+    }
+
+    #[test]
+    fn interrupt_skips_debounce() {
+        let mut sm = PhyStateMachine::new().with_down_debounce(5);
+        sm.update(UP);
+
+        sm.on_interrupt();
+        assert_eq!(sm.update(DOWN), Some(PhyEvent::LinkDown));
+    }
+
+    #[test]
+    fn negotiation_times_out() {
+        let mut sm = PhyStateMachine::new().with_negotiation_timeout(2);
+        assert_eq!(sm.negotiation_started(), Some(PhyEvent::NegotiationStarted));
+        assert_eq!(sm.negotiation_started(), None);
+
+        assert_eq!(sm.update(DOWN), None);
+        assert_eq!(sm.update(DOWN), Some(PhyEvent::NegotiationTimeout));
+        assert_eq!(sm.state(), PhyState::Down);
+    }
+
+    #[test]
+    fn negotiation_completes() {
+        let mut sm = PhyStateMachine::new();
+        sm.negotiation_started();
+        assert_eq!(
+            sm.update(UP),
+            Some(PhyEvent::LinkUp(LinkStatus::fast_full()))
+        );
+    }
+
+    #[test]
+    fn errors_enter_fault_after_threshold() {
+        let mut sm = PhyStateMachine::new().with_fault_threshold(2);
+        sm.update(UP);
+
+        assert_eq!(sm.update(mdio_err()), None);
+        assert!(sm.state().is_up());
+        assert_eq!(sm.update(mdio_err()), Some(PhyEvent::Fault));
+        assert_eq!(sm.update(mdio_err()), None);
+        assert_eq!(sm.state(), PhyState::Fault);
+    }
+
+    #[test]
+    fn fault_recovers() {
+        let mut sm = PhyStateMachine::new().with_fault_threshold(1);
+        sm.update(mdio_err());
+        assert_eq!(sm.update(DOWN), Some(PhyEvent::Recovered));
+        assert_eq!(sm.state(), PhyState::Down);
+
+        sm.update(mdio_err());
+        assert_eq!(
+            sm.update(UP),
+            Some(PhyEvent::LinkUp(LinkStatus::fast_full()))
+        );
+    }
+
+    #[test]
+    fn zero_thresholds_clamp_to_one() {
+        let mut sm = PhyStateMachine::new()
+            .with_down_debounce(0)
+            .with_fault_threshold(0);
+        sm.update(UP);
+        assert_eq!(sm.update(DOWN), Some(PhyEvent::LinkDown));
+        assert_eq!(sm.update(mdio_err()), Some(PhyEvent::Fault));
+    }
+
+    #[test]
+    fn reset_returns_to_down() {
+        let mut sm = PhyStateMachine::new().with_down_debounce(4);
+        sm.update(UP);
+        sm.reset();
+        assert_eq!(sm.state(), PhyState::Down);
+        // Thresholds survive reset
+        sm.update(UP);
+        assert_eq!(sm.update(DOWN), None);
+    }
+
+    #[test]
+    fn link_up_is_debounced() {
+        let mut sm = PhyStateMachine::new().with_up_debounce(3);
+        assert_eq!(sm.update(UP), None);
+        assert_eq!(sm.update(UP), None);
+        assert_eq!(sm.state(), PhyState::Down);
+        assert_eq!(
+            sm.update(UP),
+            Some(PhyEvent::LinkUp(LinkStatus::fast_full()))
+        );
+    }
+
+    #[test]
+    fn up_debounce_restarts_on_down_sample() {
+        let mut sm = PhyStateMachine::new().with_up_debounce(2);
+        assert_eq!(sm.update(UP), None);
+        assert_eq!(sm.update(DOWN), None);
+        assert_eq!(sm.update(UP), None);
+        assert!(sm.update(UP).is_some());
+    }
+
+    fn flap(sm: &mut PhyStateMachine) {
+        assert!(matches!(sm.update(UP), Some(PhyEvent::LinkUp(_))));
+        assert_eq!(sm.update(DOWN), Some(PhyEvent::LinkDown));
+    }
+
+    #[test]
+    fn flap_damping_holds_link_down() {
+        let mut sm = PhyStateMachine::new()
+            .with_down_debounce(1)
+            .with_flap_damping(FlapDamping::new(2, 10, 3));
+
+        flap(&mut sm);
+        assert!(!sm.is_held_down());
+        flap(&mut sm);
+        assert!(sm.is_held_down());
+
+        // Up samples are ignored until the hold-down expires
+        assert_eq!(sm.update(UP), None);
+        assert_eq!(sm.update(UP), None);
+        assert_eq!(sm.update(UP), None);
+        assert!(!sm.is_held_down());
+        assert!(matches!(sm.update(UP), Some(PhyEvent::LinkUp(_))));
+    }
+
+    #[test]
+    fn flap_window_expires() {
+        let mut sm = PhyStateMachine::new()
+            .with_down_debounce(1)
+            .with_flap_damping(FlapDamping::new(2, 3, 10));
+
+        flap(&mut sm);
+        for _ in 0..4 {
+            sm.update(DOWN);
+        }
+        flap(&mut sm);
+        assert!(!sm.is_held_down());
+    }
+
+    #[test]
+    fn flap_damping_disabled_by_default() {
+        let mut sm = PhyStateMachine::new().with_down_debounce(1);
+        for _ in 0..10 {
+            flap(&mut sm);
+        }
+        assert!(!sm.is_held_down());
+        assert_eq!(sm.hold_down_remaining(), 0);
+    }
+
+    #[test]
+    fn poll_reads_phy_over_mdio() {
+        let mut mdio = MockMdioBus::new();
+        mdio.setup_lan8720a(0);
+        let mut phy = Lan8720a::new(0);
+        let mut sm = PhyStateMachine::new().with_down_debounce(1);
+
+        assert_eq!(
+            sm.renegotiate(&mut phy, &mut mdio).unwrap(),
+            Some(PhyEvent::NegotiationStarted)
+        );
+        assert_eq!(sm.poll(&phy, &mut mdio), None);
+
+        mdio.simulate_link_up_100_fd(0);
+        mdio.set_register(0, reg::PSCSR, pscsr::AUTODONE | pscsr::HCDSPEED_100FD);
+        assert_eq!(
+            sm.poll(&phy, &mut mdio),
+            Some(PhyEvent::LinkUp(LinkStatus::fast_full()))
+        );
+
+        mdio.simulate_link_down(0);
+        assert_eq!(sm.poll(&phy, &mut mdio), Some(PhyEvent::LinkDown));
+    }
+
+    /// Records notifications as `(up, down, changed)` counts
+    #[derive(Default)]
+    struct Listener {
+        up: u32,
+        down: u32,
+        changed: u32,
+        last: Option<LinkStatus>,
+    }
+
+    impl NetLinkStatus for Listener {
+        fn link_up(&mut self, status: LinkStatus) {
+            self.up += 1;
+            self.last = Some(status);
+        }
+
+        fn link_down(&mut self) {
+            self.down += 1;
+            self.last = None;
+        }
+
+        fn link_changed(&mut self, status: LinkStatus) {
+            self.changed += 1;
+            self.last = Some(status);
+        }
+    }
+
+    #[test]
+    fn notify_on_debounced_transitions_only() {
+        let mut sm = PhyStateMachine::new().with_down_debounce(2);
+        let mut listener = Listener::default();
+
+        sm.update_notify(UP, &mut listener);
+        sm.update_notify(UP, &mut listener);
+        assert_eq!((listener.up, listener.down), (1, 0));
+        assert_eq!(listener.last, Some(LinkStatus::fast_full()));
+
+        // One down sample is debounced away
+        sm.update_notify(DOWN, &mut listener);
+        sm.update_notify(UP, &mut listener);
+        assert_eq!((listener.up, listener.down), (1, 0));
+
+        sm.update_notify(Ok(Some(LinkStatus::slow_half())), &mut listener);
+        assert_eq!(listener.changed, 1);
+
+        sm.update_notify(DOWN, &mut listener);
+        sm.update_notify(DOWN, &mut listener);
+        assert_eq!((listener.up, listener.down), (1, 1));
+        assert_eq!(listener.last, None);
+    }
+
+    #[test]
+    fn fault_while_up_notifies_down() {
+        let mut sm = PhyStateMachine::new().with_fault_threshold(1);
+        let mut listener = Listener::default();
+
+        sm.update_notify(UP, &mut listener);
+        assert_eq!(
+            sm.update_notify(Err(IoError::Timeout.into()), &mut listener),
+            Some(PhyEvent::Fault)
+        );
+        assert_eq!((listener.up, listener.down), (1, 1));
+
+        // Recovering to Down is not a second link-down
+        sm.update_notify(DOWN, &mut listener);
+        assert_eq!(listener.down, 1);
+    }
+}
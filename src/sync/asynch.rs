@@ -2,14 +2,39 @@
 #![cfg_attr(docsrs, doc(cfg(feature = "async")))]
 //!
 //! Provides futures, per-instance wakers, and an interrupt handler for async I/O.
+//!
+//! # Timeouts
+//!
+//! [`AsyncEmacExt::receive_timeout`] and [`AsyncEmacExt::transmit_timeout`]
+//! give up with `IoError::Timeout` once a deadline passes, so a task does
+//! not hang forever on a dead link. The deadline comes from any
+//! [`embedded_hal_async::delay::DelayNs`] implementation (embassy-time's
+//! `Delay`, esp-hal's `Delay`, an RTOS timer), so the driver does not tie
+//! applications to one time source. [`with_timeout`] applies the same
+//! deadline to any other driver future.
+//!
+//! ```ignore
+//! use core::time::Duration;
+//! use embassy_time::Delay;
+//!
+//! match emac.receive_timeout(&STATE, &mut buf, &mut Delay, Duration::from_secs(2)).await {
+//!     Ok(len) => handle(&buf[..len]),
+//!     Err(Error::Io(IoError::Timeout)) => check_link(),
+//!     Err(e) => return Err(e),
+//! }
+//! ```
 
 use core::{
-    future::Future,
-    pin::Pin,
+    future::{Future, poll_fn},
+    pin::{Pin, pin},
     task::{Context, Poll, Waker},
+    time::Duration,
 };
 
+use embedded_hal_async::delay::DelayNs;
+
 use super::primitives::AtomicWaker;
+use crate::internal::constants::INTERRUPT_ACK_ROUNDS;
 use crate::internal::register::dma::DmaRegs;
 use crate::{Emac, Error, InterruptStatus, IoError, Result};
 
@@ -67,35 +92,37 @@ impl AsyncEmacState {
         self.err_waker.wake();
     }
 
-    /// Wake RX/TX/error tasks based on an interrupt status snapshot.
-    ///
-    /// # Arguments
-    ///
-    /// * `status` - Interrupt status snapshot to interpret
-    pub fn on_interrupt(&self, status: InterruptStatus) {
-        if status.rx_complete || status.rx_buf_unavailable {
-            self.rx_waker.wake();
-        }
+    iram! {
+        /// Wake RX/TX/error tasks based on an interrupt status snapshot.
+        ///
+        /// # Arguments
+        ///
+        /// * `status` - Interrupt status snapshot to interpret
+        pub fn on_interrupt(&self, status: InterruptStatus) {
+            if status.rx_complete || status.rx_buf_unavailable {
+                self.rx_waker.wake();
+            }
 
-        if status.tx_complete || status.tx_buf_unavailable {
-            self.tx_waker.wake();
-        }
+            if status.tx_complete || status.tx_buf_unavailable {
+                self.tx_waker.wake();
+            }
 
-        if status.has_error() {
-            self.err_waker.wake();
-            self.rx_waker.wake();
-            self.tx_waker.wake();
+            if status.has_error() {
+                self.err_waker.wake();
+                self.rx_waker.wake();
+                self.tx_waker.wake();
+            }
         }
-    }
 
-    /// Handle the EMAC interrupt and wake async tasks.
-    ///
-    /// This reads the DMA interrupt status, wakes any waiting tasks, and
-    /// clears the interrupt flags.
-    pub fn handle_interrupt(&self) {
-        let status = InterruptStatus::from_raw(DmaRegs::status());
-        self.on_interrupt(status);
-        DmaRegs::set_status(status.to_raw());
+        /// Handle the EMAC interrupt and wake async tasks.
+        ///
+        /// This acknowledges the DMA interrupt status the same way as
+        /// `Emac::handle_interrupt` and wakes the tasks waiting on it.
+        pub fn handle_interrupt(&self) {
+            let status =
+                InterruptStatus::from_raw(DmaRegs::acknowledge_interrupts(INTERRUPT_ACK_ROUNDS));
+            self.on_interrupt(status);
+        }
     }
 }
 
@@ -277,6 +304,45 @@ impl Future for ErrorFuture<'_> {
     }
 }
 
+/// Run `op` until it completes or `timer` fires
+///
+/// The operation is polled first, so one that is ready when the timer
+/// fires still completes. `op` is dropped on timeout; the driver futures
+/// only touch the rings when they complete, so nothing is lost.
+///
+/// # Errors
+///
+/// `IoError::Timeout` if `timer` completes first, otherwise those of `op`.
+pub async fn with_timeout<T>(
+    op: impl Future<Output = Result<T>>,
+    timer: impl Future<Output = ()>,
+) -> Result<T> {
+    let mut op = pin!(op);
+    let mut timer = pin!(timer);
+    poll_fn(|cx| {
+        if let Poll::Ready(result) = op.as_mut().poll(cx) {
+            return Poll::Ready(result);
+        }
+        match timer.as_mut().poll(cx) {
+            Poll::Ready(()) => Poll::Ready(Err(IoError::Timeout.into())),
+            Poll::Pending => Poll::Pending,
+        }
+    })
+    .await
+}
+
+/// Wait `timeout` on `delay`, in microseconds where they fit in a `u32`
+/// (about 71 minutes) and in milliseconds beyond
+pub(crate) async fn sleep<D: DelayNs>(delay: &mut D, timeout: Duration) {
+    match u32::try_from(timeout.as_micros()) {
+        Ok(us) => delay.delay_us(us).await,
+        Err(_) => {
+            let ms = u32::try_from(timeout.as_millis()).unwrap_or(u32::MAX);
+            delay.delay_ms(ms).await;
+        }
+    }
+}
+
 /// Extension trait providing async methods for EMAC.
 pub trait AsyncEmacExt {
     /// Receive a frame asynchronously.
@@ -315,6 +381,52 @@ pub trait AsyncEmacExt {
     where
         'b: 'a;
 
+    /// Receive a frame asynchronously, giving up after `timeout`.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - Async waker state for this EMAC instance
+    /// * `buffer` - Destination buffer for the received frame
+    /// * `delay` - Async delay providing the deadline
+    /// * `timeout` - Longest time to wait for a frame
+    ///
+    /// # Errors
+    ///
+    /// `IoError::Timeout` if no frame arrived in time, otherwise those of
+    /// [`Emac::receive`].
+    fn receive_timeout<'a, 'b, D: DelayNs + 'a>(
+        &'a mut self,
+        state: &'a AsyncEmacState,
+        buffer: &'b mut [u8],
+        delay: &'a mut D,
+        timeout: Duration,
+    ) -> impl Future<Output = Result<usize>> + 'a
+    where
+        'b: 'a;
+
+    /// Transmit a frame asynchronously, giving up after `timeout`.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - Async waker state for this EMAC instance
+    /// * `data` - Frame data to transmit
+    /// * `delay` - Async delay providing the deadline
+    /// * `timeout` - Longest time to wait for a free TX descriptor
+    ///
+    /// # Errors
+    ///
+    /// `IoError::Timeout` if no descriptor came free in time (the frame
+    /// was not queued), otherwise those of [`Emac::transmit`].
+    fn transmit_timeout<'a, 'b, D: DelayNs + 'a>(
+        &'a mut self,
+        state: &'a AsyncEmacState,
+        data: &'b [u8],
+        delay: &'a mut D,
+        timeout: Duration,
+    ) -> impl Future<Output = Result<usize>> + 'a
+    where
+        'b: 'a;
+
     /// Wait for any error condition.
     ///
     /// # Arguments
@@ -349,6 +461,32 @@ impl<const RX: usize, const TX: usize, const BUF: usize> AsyncEmacExt for Emac<R
         TxFuture::new(self, state, data)
     }
 
+    fn receive_timeout<'a, 'b, D: DelayNs + 'a>(
+        &'a mut self,
+        state: &'a AsyncEmacState,
+        buffer: &'b mut [u8],
+        delay: &'a mut D,
+        timeout: Duration,
+    ) -> impl Future<Output = Result<usize>> + 'a
+    where
+        'b: 'a,
+    {
+        with_timeout(RxFuture::new(self, state, buffer), sleep(delay, timeout))
+    }
+
+    fn transmit_timeout<'a, 'b, D: DelayNs + 'a>(
+        &'a mut self,
+        state: &'a AsyncEmacState,
+        data: &'b [u8],
+        delay: &'a mut D,
+        timeout: Duration,
+    ) -> impl Future<Output = Result<usize>> + 'a
+    where
+        'b: 'a,
+    {
+        with_timeout(TxFuture::new(self, state, data), sleep(delay, timeout))
+    }
+
     fn wait_for_error<'a>(
         &'a self,
         state: &'a AsyncEmacState,
@@ -463,6 +601,124 @@ mod tests {
         assert_eq!(err_counter.count(), 1);
     }
 
+    /// Delay that expires after being polled `polls` times, recording the
+    /// requested time
+    struct PollDelay {
+        polls: usize,
+        requested: Option<Duration>,
+    }
+
+    impl PollDelay {
+        async fn wait(&mut self, requested: Duration) {
+            self.requested = Some(requested);
+            let mut left = self.polls;
+            poll_fn(|cx| {
+                if left == 0 {
+                    return Poll::Ready(());
+                }
+                left -= 1;
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            })
+            .await;
+        }
+    }
+
+    impl DelayNs for PollDelay {
+        async fn delay_ns(&mut self, ns: u32) {
+            self.wait(Duration::from_nanos(u64::from(ns))).await;
+        }
+
+        async fn delay_us(&mut self, us: u32) {
+            self.wait(Duration::from_micros(u64::from(us))).await;
+        }
+
+        async fn delay_ms(&mut self, ms: u32) {
+            self.wait(Duration::from_millis(u64::from(ms))).await;
+        }
+    }
+
+    /// Poll `future` until it completes, at most 100 times
+    fn run<F: Future>(future: F) -> F::Output {
+        let waker = test_waker(WakeCounter::new());
+        let mut cx = Context::from_waker(&waker);
+        let mut future = pin!(future);
+        for _ in 0..100 {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+        panic!("future did not complete");
+    }
+
+    #[test]
+    fn receive_times_out_without_frame() {
+        use crate::EmacConfig;
+        use crate::internal::register::dma::DMASTATUS_OFFSET;
+        use crate::internal::register::{DMA_BASE, sim};
+
+        sim::reset();
+        sim::write_one_to_clear(DMA_BASE + DMASTATUS_OFFSET);
+        let state = AsyncEmacState::new();
+        let mut emac: Emac<4, 4, 256> = Emac::new();
+        emac.sim_init(EmacConfig::new());
+        emac.start().unwrap();
+        let mut delay = PollDelay {
+            polls: 3,
+            requested: None,
+        };
+        let mut buf = [0u8; 256];
+
+        let result =
+            run(emac.receive_timeout(&state, &mut buf, &mut delay, Duration::from_millis(20)));
+        assert_eq!(result, Err(IoError::Timeout.into()));
+        assert_eq!(delay.requested, Some(Duration::from_millis(20)));
+
+        // A waiting frame wins even against an expired timer
+        emac.sim_dma().sim_rx_frame(&[0xAB; 60], 0);
+        delay.polls = 0;
+        let result = run(emac.receive_timeout(&state, &mut buf, &mut delay, Duration::ZERO));
+        assert_eq!(result, Ok(60));
+    }
+
+    #[test]
+    fn transmit_times_out_on_full_ring() {
+        use crate::EmacConfig;
+        use crate::internal::register::dma::DMASTATUS_OFFSET;
+        use crate::internal::register::{DMA_BASE, sim};
+
+        sim::reset();
+        sim::write_one_to_clear(DMA_BASE + DMASTATUS_OFFSET);
+        let state = AsyncEmacState::new();
+        let mut emac: Emac<2, 2, 256> = Emac::new();
+        emac.sim_init(EmacConfig::new());
+        emac.start().unwrap();
+        let mut delay = PollDelay {
+            polls: 2,
+            requested: None,
+        };
+        let frame = [0u8; 60];
+        let timeout = Duration::from_secs(u64::from(u32::MAX));
+
+        assert_eq!(
+            run(emac.transmit_timeout(&state, &frame, &mut delay, timeout)),
+            Ok(60)
+        );
+        assert_eq!(
+            run(emac.transmit_timeout(&state, &frame, &mut delay, timeout)),
+            Ok(60)
+        );
+        assert_eq!(
+            run(emac.transmit_timeout(&state, &frame, &mut delay, timeout)),
+            Err(IoError::Timeout.into())
+        );
+        // Too long for microseconds: waited in milliseconds, saturated
+        assert_eq!(
+            delay.requested,
+            Some(Duration::from_millis(u64::from(u32::MAX)))
+        );
+    }
+
     #[test]
     fn error_future_new() {
         let state = AsyncEmacState::new();
@@ -67,5 +67,5 @@ pub mod asynch;
 #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
 pub use asynch::{
     AsyncEmacExt, AsyncEmacState, ErrorFuture, RxFuture, TxFuture, async_interrupt_handler,
-    peek_interrupt_status, reset_async_state,
+    peek_interrupt_status, reset_async_state, with_timeout,
 };
@@ -2,11 +2,49 @@
 //!
 //! Provides [`SharedEmac`] for synchronous ISR-safe access and
 //! [`AsyncSharedEmac`] for async-capable ISR-safe access.
+//!
+//! # Dual-core ESP32
+//!
+//! The EMAC interrupt may be serviced on one core while the application
+//! drives the EMAC from the other, e.g. by binding the interrupt from a
+//! task started on the app core. This works under two conditions:
+//!
+//! - The `critical-section` implementation must be multicore-aware. On a
+//!   single core, masking interrupts is enough; with two cores the other
+//!   core keeps running, so the implementation must also take a cross-core
+//!   lock. esp-hal's implementation does this; a custom one that only
+//!   masks interrupts silently allows both cores into
+//!   [`SharedEmac::with`] at once.
+//! - All access goes through the wrappers in this module. The wrappers
+//!   serialize the cores, and the driver fences every descriptor handoff
+//!   (before setting OWN and before a poll demand, after observing OWN
+//!   cleared), so buffer and descriptor writes made on one core are visible
+//!   to the DMA and to the other core in order.
+//!
+//! Register accessors that take `&self` outside the wrappers (reading the
+//! interrupt status, for instance) remain safe to call from either core;
+//! they only read.
+//!
+//! # Hold Times
+//!
+//! Every critical section taken here masks all other interrupts (I2S, motor
+//! control, ...) for as long as the closure runs. Install a cycle counter
+//! with [`Emac::set_cycle_counter`] to have each hold timed; the count and
+//! the longest hold appear in [`Statistics`]:
+//!
+//! ```ignore
+//! EMAC.with(|emac| emac.set_cycle_counter(Some(xtensa_lx::timer::get_cycle_count)));
+//! // ...
+//! let stats = EMAC.stats_snapshot();
+//! let worst_us = stats.cs_hold_max_cycles / 240;
+//! ```
 
 #[cfg(feature = "async")]
 use super::asynch::AsyncEmacState;
 use super::primitives::CriticalSectionCell;
 use crate::driver::emac::Emac;
+use crate::driver::event_log::EventLog;
+use crate::driver::stats::Statistics;
 
 /// ISR-safe EMAC wrapper using critical sections.
 ///
@@ -38,13 +76,15 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
 
     /// Execute a closure with exclusive access to the EMAC.
     ///
-    /// Interrupts are disabled for the duration of the closure.
+    /// Interrupts are disabled for the duration of the closure, which is
+    /// timed if a cycle counter is installed, see
+    /// [`Emac::set_cycle_counter`].
     #[inline]
     pub fn with<R, F>(&self, f: F) -> R
     where
         F: FnOnce(&mut Emac<RX_BUFS, TX_BUFS, BUF_SIZE>) -> R,
     {
-        self.inner.with(f)
+        self.inner.with(|emac| emac.timed_hold(f))
     }
 
     /// Try to execute a closure, returning `None` if already borrowed.
@@ -53,7 +93,23 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
     where
         F: FnOnce(&mut Emac<RX_BUFS, TX_BUFS, BUF_SIZE>) -> R,
     {
-        self.inner.try_with(f)
+        self.inner.try_with(|emac| emac.timed_hold(f))
+    }
+
+    /// Copy of the EMAC's event log, taken inside a critical section.
+    ///
+    /// See [`Emac::event_log`].
+    pub fn event_log(&self) -> EventLog {
+        self.with(|emac| *emac.event_log())
+    }
+
+    /// Copy of the EMAC's frame counters, taken inside a critical section.
+    ///
+    /// All counters come from the same instant, so a task never sees a
+    /// frame counted in `rx_frames` but not yet in `rx_bytes` or its class
+    /// counter. Compare two snapshots with [`Statistics::since`].
+    pub fn stats_snapshot(&self) -> Statistics {
+        self.with(|emac| *emac.stats())
     }
 }
 
@@ -109,7 +165,7 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
     where
         F: FnOnce(&mut Emac<RX_BUFS, TX_BUFS, BUF_SIZE>) -> R,
     {
-        self.inner.with(f)
+        self.inner.with(|emac| emac.timed_hold(f))
     }
 
     /// Try to execute a closure, returning `None` if already borrowed.
@@ -118,7 +174,23 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
     where
         F: FnOnce(&mut Emac<RX_BUFS, TX_BUFS, BUF_SIZE>) -> R,
     {
-        self.inner.try_with(f)
+        self.inner.try_with(|emac| emac.timed_hold(f))
+    }
+
+    /// Copy of the EMAC's event log, taken inside a critical section.
+    ///
+    /// See [`Emac::event_log`].
+    pub fn event_log(&self) -> EventLog {
+        self.with(|emac| *emac.event_log())
+    }
+
+    /// Copy of the EMAC's frame counters, taken inside a critical section.
+    ///
+    /// All counters come from the same instant, so a task never sees a
+    /// frame counted in `rx_frames` but not yet in `rx_bytes` or its class
+    /// counter. Compare two snapshots with [`Statistics::since`].
+    pub fn stats_snapshot(&self) -> Statistics {
+        self.with(|emac| *emac.stats())
     }
 
     /// Get the async state used by this wrapper.
@@ -142,7 +214,7 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
         use core::task::Poll;
 
         poll_fn(|cx| {
-            let result = self.inner.with(|emac| {
+            let result = self.with(|emac| {
                 if emac.rx_available() {
                     Some(emac.receive(buffer))
                 } else {
@@ -155,7 +227,7 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
                 Some(Err(e)) => Poll::Ready(Err(e)),
                 None => {
                     self.async_state.register_rx(cx.waker());
-                    let retry = self.inner.with(|emac| {
+                    let retry = self.with(|emac| {
                         if emac.rx_available() {
                             Some(emac.receive(buffer))
                         } else {
@@ -183,7 +255,7 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
         use core::task::Poll;
 
         poll_fn(|cx| {
-            let result = self.inner.with(|emac| {
+            let result = self.with(|emac| {
                 if emac.tx_ready() {
                     Some(emac.transmit(data))
                 } else {
@@ -196,7 +268,7 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
                 Some(Err(e)) => Poll::Ready(Err(e)),
                 None => {
                     self.async_state.register_tx(cx.waker());
-                    let retry = self.inner.with(|emac| {
+                    let retry = self.with(|emac| {
                         if emac.tx_ready() {
                             Some(emac.transmit(data))
                         } else {
@@ -215,14 +287,54 @@ impl<const RX_BUFS: usize, const TX_BUFS: usize, const BUF_SIZE: usize>
         .await
     }
 
+    /// Receive a frame asynchronously, giving up after `timeout`.
+    ///
+    /// See [`receive_async`](Self::receive_async); the deadline comes from
+    /// `delay`, any [`DelayNs`](embedded_hal_async::delay::DelayNs).
+    ///
+    /// # Errors
+    ///
+    /// `IoError::Timeout` if no frame arrived in time, otherwise those of
+    /// [`Emac::receive`].
+    #[cfg(feature = "async")]
+    pub async fn receive_timeout<D: embedded_hal_async::delay::DelayNs>(
+        &self,
+        buffer: &mut [u8],
+        delay: &mut D,
+        timeout: core::time::Duration,
+    ) -> crate::Result<usize> {
+        let timer = super::asynch::sleep(delay, timeout);
+        super::asynch::with_timeout(self.receive_async(buffer), timer).await
+    }
+
+    /// Transmit a frame asynchronously, giving up after `timeout`.
+    ///
+    /// See [`transmit_async`](Self::transmit_async); on timeout the frame
+    /// was not queued.
+    ///
+    /// # Errors
+    ///
+    /// `IoError::Timeout` if no TX descriptor came free in time, otherwise
+    /// those of [`Emac::transmit`].
+    #[cfg(feature = "async")]
+    pub async fn transmit_timeout<D: embedded_hal_async::delay::DelayNs>(
+        &self,
+        data: &[u8],
+        delay: &mut D,
+        timeout: core::time::Duration,
+    ) -> crate::Result<usize> {
+        let timer = super::asynch::sleep(delay, timeout);
+        super::asynch::with_timeout(self.transmit_async(data), timer).await
+    }
+
     /// Check if the EMAC has received frames waiting.
     pub fn rx_available(&self) -> bool {
-        self.inner.with(|emac| emac.rx_available())
+        self.with(|emac| emac.rx_available())
     }
 
     /// Check if the EMAC can accept a frame for transmission.
     pub fn tx_ready(&self) -> bool {
-        self.inner.with(|emac| emac.tx_ready())
+        self.with(|emac| emac.tx_ready())
     }
 }
 
@@ -291,6 +403,40 @@ mod tests {
         assert_eq!(state, State::Uninitialized);
     }
 
+    #[test]
+    fn test_shared_emac_stats_snapshot() {
+        let shared: SharedEmacDefault = SharedEmac::new();
+        let snapshot = shared.stats_snapshot();
+        assert_eq!(snapshot, shared.with(|emac| *emac.stats()));
+        assert_eq!(snapshot.since(&snapshot), Statistics::new());
+    }
+
+    #[test]
+    fn test_shared_emac_times_holds_with_cycle_counter() {
+        use core::sync::atomic::{AtomicU32, Ordering};
+
+        static CYCLES: AtomicU32 = AtomicU32::new(u32::MAX - 50);
+        fn cycles() -> u32 {
+            CYCLES.load(Ordering::Relaxed)
+        }
+        let work = |n: u32| CYCLES.fetch_add(n, Ordering::Relaxed);
+
+        let shared: SharedEmacDefault = SharedEmac::new();
+        shared.with(|_emac| work(1000));
+        assert_eq!(shared.stats_snapshot().cs_holds, 0);
+
+        shared.with(|emac| emac.set_cycle_counter(Some(cycles)));
+        shared.with(|_emac| work(300));
+        shared.try_with(|_emac| work(2500));
+        shared.with(|_emac| work(700));
+
+        let stats = shared.stats_snapshot();
+        assert_eq!(stats.cs_holds, 3);
+        assert_eq!(stats.cs_hold_max_cycles, 2500);
+        shared.with(|emac| emac.reset_stats());
+        assert_eq!(shared.stats_snapshot().cs_hold_max_cycles, 0);
+    }
+
     #[test]
     fn test_shared_emac_with_closure_executed() {
         let shared: SharedEmacDefault = SharedEmac::new();
@@ -16,11 +16,14 @@ use std::collections::HashMap;
 use std::vec;
 use std::vec::Vec;
 
-use crate::driver::error::Result;
+use crate::driver::config::{Duplex, RuntimeSettings, Speed, State};
+use crate::driver::error::{DmaError, IoError, Result};
+use crate::driver::interrupt::InterruptStatus;
+use crate::driver::ops::EmacOps;
 use crate::hal::mdio::MdioBus;
 
 // Use the internal PHY register constants
-use crate::internal::phy_regs::standard::{anlpar, bmsr, phy_reg};
+use crate::internal::phy_regs::standard::{anlpar, bmcr, bmsr, phy_reg};
 
 // =============================================================================
 // Mock MDIO Bus
@@ -47,6 +50,10 @@ pub struct MockMdioBus {
     write_log: RefCell<Vec<(u8, u8, u16)>>,
     /// Whether the bus should report as busy
     busy: RefCell<bool>,
+    /// Whether BMCR.RESET stays set after being written
+    reset_stuck: RefCell<bool>,
+    /// Total microseconds passed to `delay_us`
+    waited_us: u64,
 }
 
 impl MockMdioBus {
@@ -82,6 +89,19 @@ impl MockMdioBus {
         *self.busy.borrow_mut() = busy;
     }
 
+    /// Keep BMCR.RESET set after it is written (a PHY stuck in reset)
+    ///
+    /// By default the bit self-clears at once, like a PHY that finishes
+    /// its reset before the next read.
+    pub fn set_reset_stuck(&self, stuck: bool) {
+        *self.reset_stuck.borrow_mut() = stuck;
+    }
+
+    /// Total microseconds the bus was asked to wait between polls
+    pub fn waited_us(&self) -> u64 {
+        self.waited_us
+    }
+
     /// Setup for a LAN8720A PHY with default register values
     pub fn setup_lan8720a(&self, phy_addr: u8) {
         // PHY ID registers (LAN8720A)
@@ -160,7 +180,12 @@ impl MdioBus for MockMdioBus {
             .borrow_mut()
             .push((phy_addr, reg_addr, value));
 
-        // Actually update the register
+        // Actually update the register; BMCR.RESET self-clears
+        let value = if reg_addr == phy_reg::BMCR && !*self.reset_stuck.borrow() {
+            value & !bmcr::RESET
+        } else {
+            value
+        };
         self.registers
             .borrow_mut()
             .insert((phy_addr, reg_addr), value);
@@ -171,6 +196,10 @@ impl MdioBus for MockMdioBus {
     fn is_busy(&self) -> bool {
         *self.busy.borrow()
     }
+
+    fn delay_us(&mut self, us: u32) {
+        self.waited_us += u64::from(us);
+    }
 }
 
 // =============================================================================
@@ -214,6 +243,139 @@ impl embedded_hal::delay::DelayNs for MockDelay {
     }
 }
 
+// =============================================================================
+// Mock EMAC
+// =============================================================================
+
+/// Mock EMAC implementing [`EmacOps`] for testing code layered on top of it
+///
+/// Transmitted frames are recorded; `tx_free` limits how many frames are
+/// accepted before `transmit` reports a full ring.
+#[derive(Debug)]
+pub struct MockEmac {
+    /// Frames passed to `transmit`, in order
+    pub sent: Vec<Vec<u8>>,
+    /// Frames still accepted before the ring is full
+    pub tx_free: usize,
+    /// Reported driver state
+    pub state: State,
+    settings: RuntimeSettings,
+}
+
+impl MockEmac {
+    /// Create a running mock with room for `tx_free` frames
+    pub fn new(tx_free: usize) -> Self {
+        Self {
+            sent: Vec::new(),
+            tx_free,
+            state: State::Running,
+            settings: RuntimeSettings::new(),
+        }
+    }
+}
+
+impl EmacOps for MockEmac {
+    fn state(&self) -> State {
+        self.state
+    }
+
+    fn mac_address(&self) -> [u8; 6] {
+        [0x02, 0, 0, 0, 0, 1]
+    }
+
+    fn buffer_size(&self) -> usize {
+        1600
+    }
+
+    fn start(&mut self) -> Result<()> {
+        self.state = State::Running;
+        Ok(())
+    }
+
+    fn stop(&mut self) -> Result<()> {
+        self.state = State::Stopped;
+        Ok(())
+    }
+
+    fn enable_rx(&mut self) -> Result<()> {
+        if self.state != State::Running {
+            return Err(IoError::InvalidState.into());
+        }
+        Ok(())
+    }
+
+    fn enable_rx_only(&mut self) -> Result<()> {
+        self.state = State::RxOnly;
+        Ok(())
+    }
+
+    fn enable_tx_only(&mut self) -> Result<()> {
+        self.state = State::TxOnly;
+        Ok(())
+    }
+
+    fn transmit(&mut self, data: &[u8]) -> Result<usize> {
+        if !self.state.tx_allowed() {
+            return Err(IoError::InvalidState.into());
+        }
+        if self.tx_free == 0 {
+            return Err(DmaError::NoDescriptorsAvailable.into());
+        }
+        self.tx_free -= 1;
+        self.sent.push(data.to_vec());
+        Ok(data.len())
+    }
+
+    fn tx_ready(&self) -> bool {
+        self.tx_free > 0
+    }
+
+    fn can_transmit(&self, len: usize) -> bool {
+        self.tx_free > 0 && len <= self.buffer_size()
+    }
+
+    fn receive(&mut self, _buffer: &mut [u8]) -> Result<usize> {
+        Err(IoError::IncompleteFrame.into())
+    }
+
+    fn rx_available(&self) -> bool {
+        false
+    }
+
+    fn peek_rx_length(&self) -> Option<usize> {
+        None
+    }
+
+    fn interrupt_status(&self) -> InterruptStatus {
+        InterruptStatus::default()
+    }
+
+    fn clear_interrupts(&self, _status: InterruptStatus) {}
+
+    fn handle_interrupt(&self) -> InterruptStatus {
+        InterruptStatus::default()
+    }
+
+    fn speed(&self) -> Speed {
+        Speed::Mbps100
+    }
+
+    fn duplex(&self) -> Duplex {
+        Duplex::Full
+    }
+
+    fn update_link(&mut self, _speed: Speed, _duplex: Duplex) {}
+
+    fn settings(&self) -> RuntimeSettings {
+        self.settings
+    }
+
+    fn apply_settings(&mut self, settings: RuntimeSettings) -> Result<()> {
+        self.settings = settings;
+        Ok(())
+    }
+}
+
 // =============================================================================
 // Mock DMA Descriptor
 // =============================================================================
@@ -227,7 +389,7 @@ impl embedded_hal::delay::DelayNs for MockDelay {
 ///
 /// ```ignore
 /// use crate::testing::MockDescriptor;
-/// use crate::internal::dma::DescriptorRing;
+/// use crate::ring::DescriptorRing;
 ///
 /// let mut ring: DescriptorRing<MockDescriptor, 4> = DescriptorRing::from_array(
 ///     [MockDescriptor::new(); 4]
@@ -0,0 +1,5 @@
+fn main() {
+    // Freestanding binary: no C runtime start-up, libc only for memcpy/memset
+    println!("cargo:rustc-link-arg-bins=-nostartfiles");
+    println!("cargo:rustc-link-arg-bins=-lc");
+}
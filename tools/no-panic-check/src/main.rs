@@ -0,0 +1,65 @@
+//! Link-time proof that the driver's hot paths cannot panic.
+//!
+//! This binary calls the transmit, receive and interrupt entry points on
+//! EMAC instances whose state the optimizer cannot see (`black_box`), and
+//! its panic handler calls a symbol that is never defined. If any panic
+//! site survives optimization in those paths, the release build fails with
+//! `undefined symbol: hot_path_can_panic`.
+//!
+//! The binary is never run; only a successful link matters. See the README
+//! for how to find the offending call site when the check fails.
+
+#![no_std]
+#![no_main]
+
+use core::hint::black_box;
+use core::panic::PanicInfo;
+use core::ptr::addr_of_mut;
+
+use ph_esp32_mac::Emac;
+
+/// Default-sized rings, one descriptor per frame
+static mut EMAC: Emac<10, 10, 1600> = Emac::new();
+
+/// Small buffers, so frames span several descriptors
+static mut EMAC_SG: Emac<8, 8, 512> = Emac::new();
+
+fn hot_paths<const RX: usize, const TX: usize, const BUF: usize>(
+    emac: &mut Emac<RX, TX, BUF>,
+    buf: &mut [u8],
+) {
+    let _ = emac.handle_interrupt();
+
+    let len = black_box(buf.len());
+    if let Some(frame) = buf.get(..len) {
+        let _ = emac.transmit(frame);
+        let _ = emac.transmit_burst_end(frame);
+        let _ = emac.transmit_reserved(frame);
+    }
+
+    let _ = emac.rx_available();
+    let _ = emac.peek_rx_length();
+    let _ = emac.receive(buf);
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn _start() -> ! {
+    // SAFETY: single-threaded entry point holding the only references
+    let (emac, emac_sg) =
+        black_box(unsafe { (&mut *addr_of_mut!(EMAC), &mut *addr_of_mut!(EMAC_SG)) });
+    let mut buf = [0u8; 1600];
+    loop {
+        hot_paths(emac, black_box(&mut buf));
+        hot_paths(emac_sg, black_box(&mut buf));
+    }
+}
+
+#[panic_handler]
+fn panic(_: &PanicInfo) -> ! {
+    unsafe extern "C" {
+        fn hot_path_can_panic() -> !;
+    }
+    // SAFETY: deliberately undefined; any call that survives optimization
+    // fails the link, which is the whole point of this crate
+    unsafe { hot_path_can_panic() }
+}
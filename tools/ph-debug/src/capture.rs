@@ -0,0 +1,339 @@
+//! Minimal pcap / pcapng reader and writer.
+//!
+//! Reads classic pcap (either byte order, micro- or nanosecond timestamps)
+//! and pcapng (Enhanced and Simple Packet Blocks) with Ethernet link type,
+//! which covers what `tcpdump -w` and Wireshark save by default. Writes
+//! classic pcap for requests (`tcpreplay` input) and pcapng with one
+//! comment per packet for annotated captures.
+
+use std::error::Error;
+
+/// LINKTYPE_ETHERNET
+const LINKTYPE_ETHERNET: u32 = 1;
+
+/// Classic pcap magic, microsecond timestamps
+const PCAP_MAGIC_US: u32 = 0xA1B2_C3D4;
+
+/// Classic pcap magic, nanosecond timestamps
+const PCAP_MAGIC_NS: u32 = 0xA1B2_3C4D;
+
+/// pcapng block types
+const BLOCK_SHB: u32 = 0x0A0D_0D0A;
+const BLOCK_IDB: u32 = 0x0000_0001;
+const BLOCK_SPB: u32 = 0x0000_0003;
+const BLOCK_EPB: u32 = 0x0000_0006;
+
+/// pcapng byte-order magic
+const BYTE_ORDER_MAGIC: u32 = 0x1A2B_3C4D;
+
+/// pcapng option codes
+const OPT_ENDOFOPT: u16 = 0;
+const OPT_COMMENT: u16 = 1;
+const OPT_IF_TSRESOL: u16 = 9;
+
+/// One captured Ethernet frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Packet {
+    /// Capture time, nanoseconds since the Unix epoch
+    pub ts_ns: u64,
+    /// Frame bytes as captured
+    pub data: Vec<u8>,
+}
+
+/// Parse a pcap or pcapng file
+pub fn read(bytes: &[u8]) -> Result<Vec<Packet>, Box<dyn Error>> {
+    if bytes.len() < 4 {
+        return Err("capture file too short".into());
+    }
+    let magic = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    if magic == BLOCK_SHB {
+        read_pcapng(bytes)
+    } else {
+        read_pcap(bytes)
+    }
+}
+
+/// Byte-order aware field reader
+#[derive(Clone, Copy)]
+struct Endian {
+    big: bool,
+}
+
+impl Endian {
+    fn u16(self, b: &[u8], at: usize) -> Result<u16, Box<dyn Error>> {
+        let s: [u8; 2] = b.get(at..at + 2).ok_or("truncated capture")?.try_into()?;
+        Ok(if self.big {
+            u16::from_be_bytes(s)
+        } else {
+            u16::from_le_bytes(s)
+        })
+    }
+
+    fn u32(self, b: &[u8], at: usize) -> Result<u32, Box<dyn Error>> {
+        let s: [u8; 4] = b.get(at..at + 4).ok_or("truncated capture")?.try_into()?;
+        Ok(if self.big {
+            u32::from_be_bytes(s)
+        } else {
+            u32::from_le_bytes(s)
+        })
+    }
+}
+
+fn read_pcap(bytes: &[u8]) -> Result<Vec<Packet>, Box<dyn Error>> {
+    let le = Endian { big: false };
+    let (endian, nanos) = match le.u32(bytes, 0)? {
+        PCAP_MAGIC_US => (le, false),
+        PCAP_MAGIC_NS => (le, true),
+        m if m.swap_bytes() == PCAP_MAGIC_US => (Endian { big: true }, false),
+        m if m.swap_bytes() == PCAP_MAGIC_NS => (Endian { big: true }, true),
+        _ => return Err("not a pcap or pcapng file".into()),
+    };
+    if endian.u32(bytes, 20)? != LINKTYPE_ETHERNET {
+        return Err("capture is not Ethernet (link type 1)".into());
+    }
+
+    let mut packets = Vec::new();
+    let mut at = 24;
+    while at < bytes.len() {
+        let secs = endian.u32(bytes, at)? as u64;
+        let frac = endian.u32(bytes, at + 4)? as u64;
+        let caplen = endian.u32(bytes, at + 8)? as usize;
+        let data = bytes
+            .get(at + 16..at + 16 + caplen)
+            .ok_or("truncated packet")?;
+        packets.push(Packet {
+            ts_ns: secs * 1_000_000_000 + if nanos { frac } else { frac * 1000 },
+            data: data.to_vec(),
+        });
+        at += 16 + caplen;
+    }
+    Ok(packets)
+}
+
+/// Interface as described by an IDB
+struct Interface {
+    ethernet: bool,
+    /// Timestamp units per second
+    units: u64,
+}
+
+fn read_pcapng(bytes: &[u8]) -> Result<Vec<Packet>, Box<dyn Error>> {
+    let mut packets = Vec::new();
+    let mut endian = Endian { big: false };
+    let mut interfaces: Vec<Interface> = Vec::new();
+    let mut at = 0;
+
+    while at + 12 <= bytes.len() {
+        // The SHB type reads the same in both byte orders; each section
+        // declares its own
+        if bytes[at..at + 4] == BLOCK_SHB.to_le_bytes() {
+            endian.big = u32::from_be_bytes(bytes[at + 8..at + 12].try_into()?) == BYTE_ORDER_MAGIC;
+            interfaces.clear();
+        }
+        let kind = endian.u32(bytes, at)?;
+        let len = endian.u32(bytes, at + 4)? as usize;
+        if len < 12 || at + len > bytes.len() {
+            return Err("malformed pcapng block".into());
+        }
+        let body = &bytes[at + 8..at + len - 4];
+
+        match kind {
+            BLOCK_IDB => {
+                let linktype = endian.u16(body, 0)? as u32;
+                let mut units = 1_000_000;
+                for (code, value) in options(endian, body.get(8..).unwrap_or(&[]))? {
+                    if code == OPT_IF_TSRESOL && !value.is_empty() {
+                        let exp = (value[0] & 0x7F) as u32;
+                        units = if value[0] & 0x80 == 0 {
+                            10u64.checked_pow(exp)
+                        } else {
+                            2u64.checked_pow(exp)
+                        }
+                        .ok_or("unsupported timestamp resolution")?;
+                    }
+                }
+                interfaces.push(Interface {
+                    ethernet: linktype == LINKTYPE_ETHERNET,
+                    units,
+                });
+            }
+            BLOCK_EPB => {
+                let iface = interfaces
+                    .get(endian.u32(body, 0)? as usize)
+                    .ok_or("packet for unknown interface")?;
+                let ts = ((endian.u32(body, 4)? as u64) << 32) | endian.u32(body, 8)? as u64;
+                let caplen = endian.u32(body, 12)? as usize;
+                let data = body.get(20..20 + caplen).ok_or("truncated packet")?;
+                if iface.ethernet {
+                    packets.push(Packet {
+                        ts_ns: (ts as u128 * 1_000_000_000 / iface.units as u128) as u64,
+                        data: data.to_vec(),
+                    });
+                }
+            }
+            BLOCK_SPB => {
+                let iface = interfaces.first().ok_or("packet for unknown interface")?;
+                let origlen = endian.u32(body, 0)? as usize;
+                let data = body
+                    .get(4..4 + origlen)
+                    .or_else(|| body.get(4..))
+                    .ok_or("truncated packet")?;
+                if iface.ethernet {
+                    packets.push(Packet {
+                        ts_ns: 0,
+                        data: data.to_vec(),
+                    });
+                }
+            }
+            _ => {}
+        }
+        at += len;
+    }
+    Ok(packets)
+}
+
+/// Split a pcapng option list into (code, value) pairs
+fn options(endian: Endian, mut b: &[u8]) -> Result<Vec<(u16, &[u8])>, Box<dyn Error>> {
+    let mut out = Vec::new();
+    while b.len() >= 4 {
+        let code = endian.u16(b, 0)?;
+        let len = endian.u16(b, 2)? as usize;
+        if code == OPT_ENDOFOPT {
+            break;
+        }
+        out.push((code, b.get(4..4 + len).ok_or("truncated option")?));
+        b = b.get(4 + len.next_multiple_of(4)..).unwrap_or(&[]);
+    }
+    Ok(out)
+}
+
+/// Classic pcap file (little endian, microseconds) holding `packets`
+pub fn write_pcap(packets: &[Packet]) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.extend_from_slice(&PCAP_MAGIC_US.to_le_bytes());
+    out.extend_from_slice(&2u16.to_le_bytes());
+    out.extend_from_slice(&4u16.to_le_bytes());
+    out.extend_from_slice(&[0; 8]); // thiszone, sigfigs
+    out.extend_from_slice(&65535u32.to_le_bytes());
+    out.extend_from_slice(&LINKTYPE_ETHERNET.to_le_bytes());
+    for p in packets {
+        out.extend_from_slice(&((p.ts_ns / 1_000_000_000) as u32).to_le_bytes());
+        out.extend_from_slice(&((p.ts_ns % 1_000_000_000 / 1000) as u32).to_le_bytes());
+        out.extend_from_slice(&(p.data.len() as u32).to_le_bytes());
+        out.extend_from_slice(&(p.data.len() as u32).to_le_bytes());
+        out.extend_from_slice(&p.data);
+    }
+    out
+}
+
+/// pcapng file (little endian, nanoseconds) with an optional comment per
+/// packet, shown by Wireshark under "Packet comments"
+pub fn write_pcapng(packets: &[(Packet, Option<String>)]) -> Vec<u8> {
+    let mut out = Vec::new();
+
+    let mut shb = Vec::new();
+    shb.extend_from_slice(&BYTE_ORDER_MAGIC.to_le_bytes());
+    shb.extend_from_slice(&1u16.to_le_bytes());
+    shb.extend_from_slice(&0u16.to_le_bytes());
+    shb.extend_from_slice(&(-1i64).to_le_bytes()); // section length unknown
+    block(&mut out, BLOCK_SHB, &shb);
+
+    let mut idb = Vec::new();
+    idb.extend_from_slice(&(LINKTYPE_ETHERNET as u16).to_le_bytes());
+    idb.extend_from_slice(&0u16.to_le_bytes());
+    idb.extend_from_slice(&0u32.to_le_bytes()); // no snaplen limit
+    option(&mut idb, OPT_IF_TSRESOL, &[9]);
+    option(&mut idb, OPT_ENDOFOPT, &[]);
+    block(&mut out, BLOCK_IDB, &idb);
+
+    for (p, comment) in packets {
+        let mut epb = Vec::new();
+        epb.extend_from_slice(&0u32.to_le_bytes());
+        epb.extend_from_slice(&((p.ts_ns >> 32) as u32).to_le_bytes());
+        epb.extend_from_slice(&(p.ts_ns as u32).to_le_bytes());
+        epb.extend_from_slice(&(p.data.len() as u32).to_le_bytes());
+        epb.extend_from_slice(&(p.data.len() as u32).to_le_bytes());
+        epb.extend_from_slice(&p.data);
+        pad(&mut epb);
+        if let Some(c) = comment {
+            option(&mut epb, OPT_COMMENT, c.as_bytes());
+            option(&mut epb, OPT_ENDOFOPT, &[]);
+        }
+        block(&mut out, BLOCK_EPB, &epb);
+    }
+    out
+}
+
+fn block(out: &mut Vec<u8>, kind: u32, body: &[u8]) {
+    let len = (12 + body.len()) as u32;
+    out.extend_from_slice(&kind.to_le_bytes());
+    out.extend_from_slice(&len.to_le_bytes());
+    out.extend_from_slice(body);
+    out.extend_from_slice(&len.to_le_bytes());
+}
+
+fn option(out: &mut Vec<u8>, code: u16, value: &[u8]) {
+    out.extend_from_slice(&code.to_le_bytes());
+    out.extend_from_slice(&(value.len() as u16).to_le_bytes());
+    out.extend_from_slice(value);
+    pad(out);
+}
+
+fn pad(out: &mut Vec<u8>) {
+    out.resize(out.len().next_multiple_of(4), 0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Vec<Packet> {
+        vec![
+            Packet {
+                ts_ns: 1_700_000_000_123_456_000,
+                data: (0..60).collect(),
+            },
+            Packet {
+                ts_ns: 1_700_000_001_000_000_000,
+                data: vec![0xAA; 63],
+            },
+        ]
+    }
+
+    #[test]
+    fn pcap_round_trip() {
+        assert_eq!(read(&write_pcap(&sample())).unwrap(), sample());
+    }
+
+    #[test]
+    fn pcapng_round_trip_with_comments() {
+        let annotated: Vec<_> = sample()
+            .into_iter()
+            .zip([Some("hello".to_string()), None])
+            .collect();
+        let bytes = write_pcapng(&annotated);
+        assert_eq!(bytes.len() % 4, 0);
+        assert_eq!(read(&bytes).unwrap(), sample());
+    }
+
+    #[test]
+    fn big_endian_pcap() {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&PCAP_MAGIC_NS.to_be_bytes());
+        bytes.extend_from_slice(&[0, 2, 0, 4, 0, 0, 0, 0, 0, 0, 0, 0]);
+        bytes.extend_from_slice(&65535u32.to_be_bytes());
+        bytes.extend_from_slice(&LINKTYPE_ETHERNET.to_be_bytes());
+        for v in [5u32, 7, 2, 2] {
+            bytes.extend_from_slice(&v.to_be_bytes());
+        }
+        bytes.extend_from_slice(&[1, 2]);
+        let packets = read(&bytes).unwrap();
+        assert_eq!(packets[0].ts_ns, 5_000_000_007);
+        assert_eq!(packets[0].data, [1, 2]);
+    }
+
+    #[test]
+    fn rejects_other_formats() {
+        assert!(read(b"not a capture").is_err());
+    }
+}
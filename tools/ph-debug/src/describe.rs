@@ -0,0 +1,173 @@
+//! Human-readable rendering of console frames.
+//!
+//! Payload layouts follow `DebugConsole` in `src/diag/console.rs`; keep the
+//! two in step when an opcode changes.
+
+use ph_esp32_mac::diag::console::{
+    CONSOLE_ETHERTYPE, Opcode, Status, decode_reply, decode_request,
+};
+
+/// One line describing `frame`, or `None` if it is not a console frame
+///
+/// Console frames whose tag does not verify under `key` are still
+/// reported, so a wrong key or a forged request is visible.
+pub fn frame(key: &[u8; 16], frame: &[u8]) -> Option<String> {
+    if frame.len() < 14 || u16::from_be_bytes([frame[12], frame[13]]) != CONSOLE_ETHERTYPE {
+        return None;
+    }
+    let route = format!("{} > {}", mac(&frame[6..12]), mac(&frame[0..6]));
+
+    if let Some(req) = decode_request(key, frame) {
+        return Some(format!(
+            "{route} request seq={} challenge={:#018x} {}{}",
+            req.seq,
+            req.challenge,
+            req.opcode,
+            request_args(req.opcode, req.payload)
+        ));
+    }
+    if let Some(reply) = decode_reply(key, frame) {
+        let data = if reply.status == Status::Ok {
+            reply_data(reply.opcode, reply.data)
+        } else {
+            String::new()
+        };
+        return Some(format!(
+            "{route} reply seq={} {} {}{data} next={:#018x}",
+            reply.seq, reply.opcode, reply.status, reply.challenge
+        ));
+    }
+    Some(format!(
+        "{route} console frame, tag check failed (wrong key?)"
+    ))
+}
+
+/// Formatted request arguments, with a leading space
+fn request_args(opcode: Opcode, payload: &[u8]) -> String {
+    match (opcode, words(payload).as_slice()) {
+        (Opcode::ReadReg, [addr]) => format!(" {addr:#010x}"),
+        (Opcode::WriteReg, [addr, value]) => format!(" {addr:#010x}={value:#010x}"),
+        (_, []) => String::new(),
+        _ => format!(" (payload {})", hex(payload)),
+    }
+}
+
+/// Formatted reply data, with a leading space
+pub fn reply_data(opcode: Opcode, data: &[u8]) -> String {
+    match opcode {
+        Opcode::Hello if data.len() == 2 => format!(
+            " version={} writes={}",
+            data[0],
+            if data[1] != 0 { "on" } else { "off" }
+        ),
+        Opcode::Stats if data.len() == 40 => {
+            let rx_frames = u32::from_be_bytes(data[0..4].try_into().unwrap());
+            let rx_bytes = u64::from_be_bytes(data[4..12].try_into().unwrap());
+            let w = words(&data[12..]);
+            format!(
+                " rx_frames={rx_frames} rx_bytes={rx_bytes} unicast={} broadcast={} \
+                 multicast={} other={} errors={} fcs_verified={} fcs_mismatch={}",
+                w[0], w[1], w[2], w[3], w[4], w[5], w[6]
+            )
+        }
+        Opcode::ReadReg if data.len() == 4 => format!(" value={:#010x}", words(data)[0]),
+        Opcode::DumpRings if data.len() == 11 => {
+            let v: Vec<u16> = data[..10]
+                .chunks_exact(2)
+                .map(|c| u16::from_be_bytes([c[0], c[1]]))
+                .collect();
+            let state = match data[10] {
+                0 => "uninitialized",
+                1 => "initialized",
+                2 => "running",
+                3 => "stopped",
+                4 => "rx-only",
+                5 => "tx-only",
+                _ => "unknown",
+            };
+            format!(
+                " rx_active={} tx_active={} rx_free={} rx_waiting={} tx_available={} state={state}",
+                v[0], v[1], v[2], v[3], v[4]
+            )
+        }
+        _ if data.is_empty() => String::new(),
+        _ => format!(" (data {})", hex(data)),
+    }
+}
+
+/// Big-endian u32 words; empty if `b` is not a whole number of them
+fn words(b: &[u8]) -> Vec<u32> {
+    if !b.len().is_multiple_of(4) {
+        return Vec::new();
+    }
+    b.chunks_exact(4)
+        .map(|c| u32::from_be_bytes([c[0], c[1], c[2], c[3]]))
+        .collect()
+}
+
+/// Colon-separated MAC address
+pub fn mac(b: &[u8]) -> String {
+    b.iter()
+        .map(|x| format!("{x:02x}"))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+fn hex(b: &[u8]) -> String {
+    b.iter().map(|x| format!("{x:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ph_esp32_mac::diag::console::encode_request;
+
+    const KEY: [u8; 16] = [3; 16];
+    const DEVICE: [u8; 6] = [0x02, 0, 0, 0x12, 0x34, 0x56];
+    const HOST: [u8; 6] = [0x02, 0, 0, 0, 0, 0x01];
+
+    fn request(op: Opcode, payload: &[u8]) -> Vec<u8> {
+        let mut f = [0u8; 128];
+        let len = encode_request(&KEY, DEVICE, HOST, op, 7, 0xC0FFEE, payload, &mut f).unwrap();
+        f[..len].to_vec()
+    }
+
+    #[test]
+    fn describes_requests() {
+        let f = request(Opcode::WriteReg, &[0x3F, 0xF6, 0x90, 0x00, 0, 0, 0, 1]);
+        assert_eq!(
+            frame(&KEY, &f).unwrap(),
+            "02:00:00:00:00:01 > 02:00:00:12:34:56 request seq=7 challenge=0x0000000000c0ffee \
+             write-reg 0x3ff69000=0x00000001"
+        );
+        assert!(
+            frame(&[0; 16], &f)
+                .unwrap()
+                .ends_with("tag check failed (wrong key?)")
+        );
+    }
+
+    #[test]
+    fn ignores_other_frames() {
+        let mut f = request(Opcode::Hello, &[]);
+        f[12..14].copy_from_slice(&0x0800u16.to_be_bytes());
+        assert_eq!(frame(&KEY, &f), None);
+    }
+
+    #[test]
+    fn formats_reply_payloads() {
+        let mut stats = vec![0, 0, 0, 3, 0, 0, 0, 0, 0, 0, 1, 0];
+        stats.extend((1..=7u32).flat_map(u32::to_be_bytes));
+        assert_eq!(
+            reply_data(Opcode::Stats, &stats),
+            " rx_frames=3 rx_bytes=256 unicast=1 broadcast=2 multicast=3 other=4 errors=5 \
+             fcs_verified=6 fcs_mismatch=7"
+        );
+        assert_eq!(
+            reply_data(Opcode::DumpRings, &[0, 10, 0, 10, 0, 9, 0, 1, 0, 10, 2]),
+            " rx_active=10 tx_active=10 rx_free=9 rx_waiting=1 tx_available=10 state=running"
+        );
+        assert_eq!(reply_data(Opcode::Hello, &[1, 0]), " version=1 writes=off");
+        assert_eq!(reply_data(Opcode::ReadReg, &[1, 2]), " (data 0102)");
+    }
+}
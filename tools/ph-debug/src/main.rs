@@ -0,0 +1,316 @@
+//! Host companion for the ph-esp32-mac Ethernet debug console.
+//!
+//! Decodes console traffic from packet captures and builds request frames,
+//! so a device running `DebugConsole` (`debug-console` feature) can be
+//! inspected with nothing but tcpdump, tcpreplay and Wireshark.
+//!
+//! # Usage
+//!
+//! ```text
+//! ph-debug decode <capture> --key <hex> [--annotate <out.pcapng>]
+//! ph-debug request <opcode> [args] --key <hex> --device <mac> --host <mac>
+//!                  --seq <n> [--challenge <hex>] -o <out.pcap>
+//! ```
+//!
+//! # Commands
+//!
+//! - `decode`: Print every console frame in a pcap or pcapng capture, with
+//!   tags checked against the key. `--annotate` also writes the capture as
+//!   pcapng with the decoded text as a packet comment on each console
+//!   frame, for reading alongside everything else in Wireshark.
+//! - `request`: Write one tagged request to a pcap file for `tcpreplay`.
+//!   Opcodes: `hello`, `stats`, `read-reg <addr>`, `write-reg <addr>
+//!   <value>`, `restart-autoneg`, `dump-rings`.
+//!
+//! # Notes
+//!
+//! - The key is 32 hex digits; `PH_DEBUG_KEY` is used if `--key` is absent.
+//! - Every request except `hello` must echo the challenge from the
+//!   device's last reply (`next=` in `decode` output) with `--challenge`.
+//!   Each challenge is good for one request.
+
+mod capture;
+mod describe;
+
+use std::{
+    env,
+    error::Error,
+    fs,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use capture::Packet;
+use ph_esp32_mac::diag::console::{CONSOLE_MAX_PAYLOAD, Opcode, encode_request};
+
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("ph-debug: {err}");
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let Some((command, rest)) = args.split_first() else {
+        print_usage();
+        return Ok(());
+    };
+    let opts = Options::parse(rest)?;
+
+    match command.as_str() {
+        "decode" => decode(&opts),
+        "request" => request(&opts),
+        "help" | "--help" | "-h" => {
+            print_usage();
+            Ok(())
+        }
+        other => Err(format!("unknown command `{other}`").into()),
+    }
+}
+
+fn print_usage() {
+    eprintln!(
+        "Usage:\n  \
+         ph-debug decode <capture> --key <hex> [--annotate <out.pcapng>]\n  \
+         ph-debug request <opcode> [args] --key <hex> --device <mac> --host <mac> --seq <n> \
+         [--challenge <hex>] -o <out.pcap>"
+    );
+}
+
+/// Parsed command line after the command name
+#[derive(Debug, Default)]
+struct Options {
+    positional: Vec<String>,
+    key: Option<String>,
+    device: Option<String>,
+    host: Option<String>,
+    seq: Option<String>,
+    challenge: Option<String>,
+    output: Option<String>,
+    annotate: Option<String>,
+}
+
+impl Options {
+    fn parse(args: &[String]) -> Result<Self, Box<dyn Error>> {
+        let mut opts = Self::default();
+        let mut it = args.iter();
+        while let Some(arg) = it.next() {
+            let slot = match arg.as_str() {
+                "--key" => &mut opts.key,
+                "--device" => &mut opts.device,
+                "--host" => &mut opts.host,
+                "--seq" => &mut opts.seq,
+                "--challenge" => &mut opts.challenge,
+                "-o" | "--output" => &mut opts.output,
+                "--annotate" => &mut opts.annotate,
+                _ => {
+                    opts.positional.push(arg.clone());
+                    continue;
+                }
+            };
+            *slot = Some(it.next().ok_or(format!("{arg} needs a value"))?.clone());
+        }
+        Ok(opts)
+    }
+
+    fn key(&self) -> Result<[u8; 16], Box<dyn Error>> {
+        let text = match &self.key {
+            Some(k) => k.clone(),
+            None => env::var("PH_DEBUG_KEY").map_err(|_| "--key or PH_DEBUG_KEY is required")?,
+        };
+        parse_key(&text)
+    }
+
+    fn required<'a>(&'a self, value: &'a Option<String>, name: &str) -> Result<&'a str, String> {
+        value.as_deref().ok_or(format!("{name} is required"))
+    }
+}
+
+// =============================================================================
+// Commands
+// =============================================================================
+
+fn decode(opts: &Options) -> Result<(), Box<dyn Error>> {
+    let [path] = opts.positional.as_slice() else {
+        return Err("decode takes exactly one capture file".into());
+    };
+    let key = opts.key()?;
+    let packets = capture::read(&fs::read(path)?)?;
+
+    let mut annotated = Vec::with_capacity(packets.len());
+    let mut console_frames = 0;
+    for (i, p) in packets.into_iter().enumerate() {
+        let text = describe::frame(&key, &p.data);
+        if let Some(line) = &text {
+            console_frames += 1;
+            println!(
+                "{:>5} {}.{:06} {line}",
+                i + 1,
+                p.ts_ns / 1_000_000_000,
+                p.ts_ns % 1_000_000_000 / 1000
+            );
+        }
+        annotated.push((p, text));
+    }
+    eprintln!(
+        "{console_frames} console frames in {} packets",
+        annotated.len()
+    );
+
+    if let Some(out) = &opts.annotate {
+        fs::write(out, capture::write_pcapng(&annotated))?;
+        eprintln!("wrote {out}");
+    }
+    Ok(())
+}
+
+fn request(opts: &Options) -> Result<(), Box<dyn Error>> {
+    let (opcode, payload) = parse_request(&opts.positional)?;
+    let key = opts.key()?;
+    let device = parse_mac(opts.required(&opts.device, "--device")?)?;
+    let host = parse_mac(opts.required(&opts.host, "--host")?)?;
+    let seq: u16 = opts.required(&opts.seq, "--seq")?.parse()?;
+    let challenge = match (&opts.challenge, opcode) {
+        (Some(text), _) => parse_u64_hex(text)?,
+        (None, Opcode::Hello) => 0,
+        (None, _) => return Err("--challenge is required for all but hello".into()),
+    };
+    let out = opts.required(&opts.output, "-o")?;
+
+    let mut frame = [0u8; 64 + CONSOLE_MAX_PAYLOAD];
+    let len = encode_request(
+        &key, device, host, opcode, seq, challenge, &payload, &mut frame,
+    )
+    .ok_or("request does not fit in a console frame")?;
+    let packet = Packet {
+        ts_ns: SystemTime::now().duration_since(UNIX_EPOCH)?.as_nanos() as u64,
+        data: frame[..len].to_vec(),
+    };
+    fs::write(out, capture::write_pcap(&[packet]))?;
+    eprintln!(
+        "wrote {out}: {}",
+        describe::frame(&key, &frame[..len]).unwrap_or_default()
+    );
+    Ok(())
+}
+
+// =============================================================================
+// Argument Parsing
+// =============================================================================
+
+/// Opcode and payload from `<opcode> [args]`
+fn parse_request(args: &[String]) -> Result<(Opcode, Vec<u8>), Box<dyn Error>> {
+    let (name, rest) = args.split_first().ok_or("request needs an opcode")?;
+    let opcode = [
+        Opcode::Hello,
+        Opcode::Stats,
+        Opcode::ReadReg,
+        Opcode::WriteReg,
+        Opcode::RestartAutoneg,
+        Opcode::DumpRings,
+    ]
+    .into_iter()
+    .find(|op| op.as_str() == name)
+    .ok_or(format!("unknown opcode `{name}`"))?;
+
+    let expected = match opcode {
+        Opcode::ReadReg => 1,
+        Opcode::WriteReg => 2,
+        _ => 0,
+    };
+    if rest.len() != expected {
+        return Err(format!("{name} takes {expected} argument(s)").into());
+    }
+    let mut payload = Vec::new();
+    for arg in rest {
+        payload.extend_from_slice(&parse_u32(arg)?.to_be_bytes());
+    }
+    Ok((opcode, payload))
+}
+
+/// Decimal or `0x`-prefixed hex
+fn parse_u32(s: &str) -> Result<u32, Box<dyn Error>> {
+    Ok(
+        match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+            Some(hex) => u32::from_str_radix(&hex.replace('_', ""), 16)?,
+            None => s.parse()?,
+        },
+    )
+}
+
+/// Hex, optionally `0x`-prefixed
+fn parse_u64_hex(s: &str) -> Result<u64, Box<dyn Error>> {
+    let s = s.trim();
+    let hex = s
+        .strip_prefix("0x")
+        .or_else(|| s.strip_prefix("0X"))
+        .unwrap_or(s);
+    Ok(u64::from_str_radix(&hex.replace('_', ""), 16)?)
+}
+
+/// 32 hex digits, optionally `0x`-prefixed
+fn parse_key(s: &str) -> Result<[u8; 16], Box<dyn Error>> {
+    let s = s.trim().trim_start_matches("0x");
+    if s.len() != 32 || !s.is_ascii() {
+        return Err("key must be 32 hex digits".into());
+    }
+    let mut key = [0u8; 16];
+    for (i, b) in key.iter_mut().enumerate() {
+        *b = u8::from_str_radix(&s[2 * i..2 * i + 2], 16)?;
+    }
+    Ok(key)
+}
+
+/// `aa:bb:cc:dd:ee:ff` (or `-` separated)
+fn parse_mac(s: &str) -> Result<[u8; 6], Box<dyn Error>> {
+    let parts: Vec<&str> = s.split([':', '-']).collect();
+    let [a, b, c, d, e, f] = parts.as_slice() else {
+        return Err(format!("invalid MAC address `{s}`").into());
+    };
+    let mut mac = [0u8; 6];
+    for (out, part) in mac.iter_mut().zip([a, b, c, d, e, f]) {
+        *out = u8::from_str_radix(part, 16)?;
+    }
+    Ok(mac)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_requests() {
+        let (op, payload) = parse_request(&strings(&["write-reg", "0x3FF6_9000", "1"])).unwrap();
+        assert_eq!(op, Opcode::WriteReg);
+        assert_eq!(payload, [0x3F, 0xF6, 0x90, 0x00, 0, 0, 0, 1]);
+        assert!(parse_request(&strings(&["read-reg"])).is_err());
+        assert!(parse_request(&strings(&["reboot"])).is_err());
+    }
+
+    #[test]
+    fn parses_key_and_mac() {
+        let key = parse_key("000102030405060708090a0b0c0d0e0f").unwrap();
+        assert_eq!(key, core::array::from_fn(|i| i as u8));
+        assert!(parse_key("0011").is_err());
+        assert_eq!(parse_u64_hex("0x00c0_ffee").unwrap(), 0xC0FFEE);
+        assert!(parse_u64_hex("xyz").is_err());
+        assert_eq!(
+            parse_mac("02-00-00-12-34-5a").unwrap(),
+            [0x02, 0, 0, 0x12, 0x34, 0x5A]
+        );
+        assert!(parse_mac("02:00:00").is_err());
+    }
+
+    #[test]
+    fn options_take_values() {
+        let opts = Options::parse(&strings(&["stats", "--seq", "4", "-o", "r.pcap"])).unwrap();
+        assert_eq!(opts.positional, ["stats"]);
+        assert_eq!(opts.seq.as_deref(), Some("4"));
+        assert_eq!(opts.output.as_deref(), Some("r.pcap"));
+        assert!(Options::parse(&strings(&["--key"])).is_err());
+    }
+}
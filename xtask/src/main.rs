@@ -17,20 +17,36 @@
 //! cargo xtask build qa-runner
 //! cargo xtask run ex-embassy-net --debug
 //! cargo xtask run ex-esp-hal -- --extra-arg
+//! cargo xtask run qa-runner --features structured-results
+//! cargo xtask size qa-runner
+//! cargo xtask check-features
+//! cargo xtask expect qa-runner --timeout 600
+//! cargo xtask expect ex-smoltcp --pass "DHCP address:"
 //! ```
 //!
 //! # Targets
 //!
 //! - qa-runner | qa
 //! - ex-esp-hal | ex-esp-hal-async
-//! - ex-smoltcp
+//! - ex-smoltcp | ex-dual-stack | ex-ota
 //! - ex-embassy | ex-embassy-net
+//! - ex-mqtt | ex-failover | ex-multicast
 //!
 //! # Notes
 //!
 //! - If no command is supplied, `build` is assumed.
 //! - `--debug` selects a debug build (release is the default).
+//! - `--features <list>` enables extra crate features (comma-separated).
 //! - `--` passes arguments to the target binary.
+//! - `expect` builds, flashes and monitors like `run`, then watches the serial
+//!   output for pass/fail markers (see [`Expectations`]) and exits non-zero on
+//!   a failure marker, a timeout, or the monitor exiting first.
+//! - `size` builds like `build`, then prints the section sizes of the binary
+//!   and the estimated EMAC DMA share (see the `size` module).
+//! - `check-features` builds the library for every combination of the
+//!   optional integration features (see [`MATRIX_FEATURES`]) and prints a
+//!   pass/fail table. Combinations that enable the same effective feature
+//!   set are built once.
 //! - `ESP_LOG`, `ESP_IDF_VERSION`, and `CARGO_TARGET_DIR` are defaulted
 //!   if not set by the caller.
 
@@ -38,17 +54,44 @@ use std::{
     env,
     error::Error,
     fs,
+    io::{BufRead, BufReader},
     path::{Path, PathBuf},
-    process::Command,
+    process::{Child, Command, Stdio},
+    sync::mpsc::{self, RecvTimeoutError},
+    thread,
+    time::{Duration, Instant},
 };
 
+mod size;
+
 const XTASK_MANIFEST_DIR: &str = env!("CARGO_MANIFEST_DIR");
 
+/// Optional library features combined by `check-features`, on top of `esp32`.
+const MATRIX_FEATURES: [&str; 6] = [
+    "async",
+    "smoltcp",
+    "embassy-net",
+    "critical-section",
+    "defmt",
+    "iram",
+];
+
+/// Features from [`MATRIX_FEATURES`] that already enable `critical-section`.
+const IMPLIES_CRITICAL_SECTION: [&str; 2] = ["async", "embassy-net"];
+
+/// Serial output that fails an `expect` run for every target.
+const CRASH_MARKERS: [&str; 3] = ["panicked at", "Exception occurred", "Guru Meditation"];
+
+/// Default `expect` time budget, counted from the start of flashing.
+const DEFAULT_EXPECT_TIMEOUT: Duration = Duration::from_secs(300);
+
 /// Operational mode for the xtask invocation.
 #[derive(Clone, Copy)]
 enum Mode {
     Run,
     Build,
+    Expect,
+    Size,
 }
 
 /// Cargo build profile selection.
@@ -65,6 +108,17 @@ struct BinInfo {
     required_features: Vec<String>,
 }
 
+/// Serial markers and time budget for an `expect` run.
+///
+/// A line containing any `fail` marker (or one of [`CRASH_MARKERS`]) fails the
+/// run; a line containing any `pass` marker ends it successfully. Failure
+/// markers are checked first.
+struct Expectations {
+    pass: Vec<String>,
+    fail: Vec<String>,
+    timeout: Duration,
+}
+
 /// A resolved binary target with metadata needed for the cargo invocation.
 struct ResolvedBin {
     manifest_path: PathBuf,
@@ -92,11 +146,24 @@ fn run() -> Result<(), Box<dyn Error>> {
         return Ok(());
     }
 
+    if args[0] == "check-features" {
+        let mut profile = Profile::Debug;
+        for arg in args.iter().skip(1) {
+            match arg.as_str() {
+                "--debug" => profile = Profile::Debug,
+                "--release" => profile = Profile::Release,
+                _ => return Err(format!("unexpected argument: {arg}").into()),
+            }
+        }
+        return check_features(profile);
+    }
+
     let mut mode: Option<Mode> = None;
-    if matches!(args[0].as_str(), "run" | "build") {
+    if matches!(args[0].as_str(), "run" | "build" | "expect" | "size") {
         mode = Some(match args.remove(0).as_str() {
             "run" => Mode::Run,
-            "build" => Mode::Build,
+            "expect" => Mode::Expect,
+            "size" => Mode::Size,
             _ => Mode::Build,
         });
     }
@@ -104,14 +171,42 @@ fn run() -> Result<(), Box<dyn Error>> {
     let mut profile = Profile::Release;
     let mut path: Option<PathBuf> = None;
     let mut pass_args: Vec<String> = Vec::new();
+    let mut features: Vec<String> = Vec::new();
+    let mut pass_markers: Vec<String> = Vec::new();
+    let mut fail_markers: Vec<String> = Vec::new();
+    let mut timeout: Option<Duration> = None;
 
     let mut iter = args.into_iter();
     while let Some(arg) = iter.next() {
         match arg.as_str() {
             "run" => mode = Some(Mode::Run),
             "build" => mode = Some(Mode::Build),
+            "expect" => mode = Some(Mode::Expect),
+            "size" => mode = Some(Mode::Size),
             "--debug" => profile = Profile::Debug,
             "--release" => profile = Profile::Release,
+            "--features" => {
+                let list = iter.next().ok_or("missing value for --features")?;
+                features.extend(
+                    list.split(',')
+                        .map(str::trim)
+                        .filter(|feat| !feat.is_empty())
+                        .map(str::to_string),
+                );
+            }
+            "--pass" => {
+                pass_markers.push(iter.next().ok_or("missing value for --pass")?);
+            }
+            "--fail" => {
+                fail_markers.push(iter.next().ok_or("missing value for --fail")?);
+            }
+            "--timeout" => {
+                let secs = iter.next().ok_or("missing value for --timeout")?;
+                let secs: u64 = secs
+                    .parse()
+                    .map_err(|_| format!("invalid --timeout seconds: {secs}"))?;
+                timeout = Some(Duration::from_secs(secs));
+            }
             "--" => {
                 pass_args.extend(iter);
                 break;
@@ -127,15 +222,52 @@ fn run() -> Result<(), Box<dyn Error>> {
     }
 
     let mode = mode.unwrap_or(Mode::Build);
+    if !matches!(mode, Mode::Expect)
+        && (!pass_markers.is_empty() || !fail_markers.is_empty() || timeout.is_some())
+    {
+        return Err("--pass, --fail and --timeout are only valid with `expect`".into());
+    }
+
     let path = path.ok_or("missing <target>")?;
-    let resolved = resolve_bin(&path)?;
+    let mut resolved = resolve_bin(&path)?;
+    for feat in features {
+        if !resolved.required_features.contains(&feat) {
+            resolved.required_features.push(feat);
+        }
+    }
+
+    match mode {
+        Mode::Expect => {}
+        Mode::Size => {
+            if !pass_args.is_empty() {
+                return Err("`size` does not take target arguments".into());
+            }
+            return report_size(profile, &path, &resolved);
+        }
+        Mode::Run | Mode::Build => return run_cargo(mode, profile, &resolved, &pass_args),
+    }
+
+    let mut expect = default_expectations(resolved.package_name.as_deref());
+    if !pass_markers.is_empty() {
+        expect.pass = pass_markers;
+    }
+    expect.fail.extend(fail_markers);
+    if let Some(timeout) = timeout {
+        expect.timeout = timeout;
+    }
+    if expect.pass.is_empty() {
+        return Err("no default pass marker for this target; use `--pass <text>`".into());
+    }
 
-    run_cargo(mode, profile, &resolved, &pass_args)
+    // Build first so that compile time does not count against the timeout
+    run_cargo(Mode::Build, profile, &resolved, &[])?;
+    let command = cargo_command(Mode::Expect, profile, &resolved, &pass_args)?;
+    run_expect(command, &expect)
 }
 
 fn print_usage() {
     eprintln!(
-        "Usage:\n  cargo xtask run <target> [--debug|--release] [--] [args...]\n  cargo xtask build <target> [--debug|--release]\n\nTargets:\n  qa-runner | qa\n  ex-esp-hal | ex-esp-hal-async | ex-smoltcp | ex-embassy | ex-embassy-net\n  (or a path to a .rs entry file)\n\nNotes:\n  - If no command is supplied, `build` is assumed (no flashing).\n  - Use `--` to pass args to the target binary.\n",
+        "Usage:\n  cargo xtask run <target> [--debug|--release] [--features <list>] [--] [args...]\n  cargo xtask build <target> [--debug|--release] [--features <list>]\n  cargo xtask expect <target> [--pass <text>]... [--fail <text>]... [--timeout <secs>] [--debug|--release] [--features <list>] [--] [args...]\n  cargo xtask size <target> [--debug|--release] [--features <list>]\n  cargo xtask check-features [--debug|--release]\n\nTargets:\n  qa-runner | qa\n  ex-esp-hal | ex-esp-hal-async | ex-smoltcp | ex-dual-stack | ex-ota\n  ex-embassy | ex-embassy-net | ex-mqtt | ex-failover | ex-multicast\n  (or a path to a .rs entry file)\n\nNotes:\n  - If no command is supplied, `build` is assumed (no flashing).\n  - Use `--features a,b` to enable extra crate features.\n  - Use `--` to pass args to the target binary.\n  - `expect` fails on a --fail marker or a panic, passes on a --pass marker\n    (qa-runner has defaults), and times out after 300 s unless --timeout is given.\n  - `check-features` builds the library across the feature matrix (debug by default).\n",
     );
 }
 
@@ -152,20 +284,22 @@ fn resolve_target_arg(arg: &str) -> Result<PathBuf, Box<dyn Error>> {
         "ex-esp-hal" | "esp-hal" | "ex-esp-hal-integration" => {
             "apps/examples/esp_hal_integration.rs"
         }
-        "ex-esp-hal-async" | "esp-hal-async" | "ex-async" => {
-            "apps/examples/esp_hal_async.rs"
-        }
+        "ex-esp-hal-async" | "esp-hal-async" | "ex-async" => "apps/examples/esp_hal_async.rs",
         "ex-smoltcp" | "smoltcp" | "ex-smoltcp-echo" => "apps/examples/smoltcp_echo.rs",
+        "ex-dual-stack" | "dual-stack" => "apps/examples/dual_stack.rs",
+        "ex-ota" | "ota" => "apps/examples/ota.rs",
         "ex-embassy" | "embassy" | "ex-embassy-net" | "embassy-net" => {
             "apps/examples/embassy_net.rs"
         }
+        "ex-mqtt" | "mqtt" | "ex-embassy-mqtt" => "apps/examples/embassy_mqtt.rs",
+        "ex-failover" | "failover" | "ex-eth-failover" => "apps/examples/eth_failover.rs",
+        "ex-multicast" | "multicast" | "ex-udp-multicast" => "apps/examples/udp_multicast.rs",
         "apps/examples" | "examples" => "apps/examples/esp_hal_integration.rs",
         "apps/qa-runner" => "apps/qa-runner/qa_runner.rs",
         _ => {
-            return Err(format!(
-                "unknown target: {arg}\nUse `cargo xtask --help` to list targets."
-            )
-            .into())
+            return Err(
+                format!("unknown target: {arg}\nUse `cargo xtask --help` to list targets.").into(),
+            );
         }
     };
 
@@ -179,8 +313,8 @@ fn resolve_bin(path: &Path) -> Result<ResolvedBin, Box<dyn Error>> {
     } else {
         cwd.join(path)
     };
-    let file_path = fs::canonicalize(&path)
-        .map_err(|_| format!("file not found: {}", path.display()))?;
+    let file_path =
+        fs::canonicalize(&path).map_err(|_| format!("file not found: {}", path.display()))?;
 
     let manifest_path = find_manifest(&file_path)?;
     let manifest_dir = manifest_path
@@ -277,7 +411,10 @@ fn parse_bins(manifest: &toml::Value, manifest_dir: &Path) -> Vec<BinInfo> {
         let path = if let Some(path) = bin.get("path").and_then(|path| path.as_str()) {
             manifest_dir.join(path)
         } else if !name.is_empty() {
-            manifest_dir.join("src").join("bin").join(format!("{name}.rs"))
+            manifest_dir
+                .join("src")
+                .join("bin")
+                .join(format!("{name}.rs"))
         } else {
             continue;
         };
@@ -328,11 +465,25 @@ fn run_cargo(
     resolved: &ResolvedBin,
     pass_args: &[String],
 ) -> Result<(), Box<dyn Error>> {
+    let status = cargo_command(mode, profile, resolved, pass_args)?.status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("cargo failed (status: {status:?})").into())
+    }
+}
+
+fn cargo_command(
+    mode: Mode,
+    profile: Profile,
+    resolved: &ResolvedBin,
+    pass_args: &[String],
+) -> Result<Command, Box<dyn Error>> {
     let mut cargo_args = Vec::new();
 
     match mode {
-        Mode::Run => cargo_args.push("run".to_string()),
-        Mode::Build => cargo_args.push("build".to_string()),
+        Mode::Run | Mode::Expect => cargo_args.push("run".to_string()),
+        Mode::Build | Mode::Size => cargo_args.push("build".to_string()),
     }
 
     cargo_args.push("--manifest-path".to_string());
@@ -357,9 +508,20 @@ fn run_cargo(
         cargo_args.push(resolved.required_features.join(","));
     }
 
-    if matches!(mode, Mode::Run) {
-        cargo_args.push("--config".to_string());
-        cargo_args.push("target.xtensa-esp32-none-elf.runner='espflash flash --monitor'".to_string());
+    match mode {
+        Mode::Run => {
+            cargo_args.push("--config".to_string());
+            cargo_args
+                .push("target.xtensa-esp32-none-elf.runner='espflash flash --monitor'".to_string());
+        }
+        Mode::Expect => {
+            cargo_args.push("--config".to_string());
+            cargo_args.push(
+                "target.xtensa-esp32-none-elf.runner='espflash flash --monitor --non-interactive'"
+                    .to_string(),
+            );
+        }
+        Mode::Build | Mode::Size => {}
     }
 
     if needs_linkall(
@@ -379,9 +541,147 @@ fn run_cargo(
         cargo_args.extend(pass_args.iter().cloned());
     }
 
+    println!("xtask: rustup run esp cargo {}", cargo_args.join(" "));
+
+    esp_cargo(&cargo_args)
+}
+
+/// Build the target and print its section-size report.
+fn report_size(
+    profile: Profile,
+    entry: &Path,
+    resolved: &ResolvedBin,
+) -> Result<(), Box<dyn Error>> {
+    run_cargo(Mode::Build, profile, resolved, &[])?;
+
+    let bin_name = resolved
+        .bin_name
+        .as_deref()
+        .or(resolved.package_name.as_deref())
+        .ok_or("cannot determine the binary name")?;
+    let target_dir = match env::var_os("CARGO_TARGET_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => repo_root()?.join("target"),
+    };
+    let profile_dir = match profile {
+        Profile::Release => "release",
+        Profile::Debug => "debug",
+    };
+    let binary = target_dir
+        .join("xtensa-esp32-none-elf")
+        .join(profile_dir)
+        .join(bin_name);
+
+    let elf =
+        fs::read(&binary).map_err(|err| format!("cannot read {}: {err}", binary.display()))?;
+    let sections = size::read_sections(&elf)?;
+    size::print_report(&binary, &sections, size::find_emac_params(entry));
+    Ok(())
+}
+
+/// Markers the target prints on its own, plus [`CRASH_MARKERS`].
+fn default_expectations(package_name: Option<&str>) -> Expectations {
+    let (pass, fail): (&[&str], &[&str]) = match package_name {
+        Some("ph-esp32-mac-qa-runner") => (&["ALL TESTS PASSED"], &["SOME TESTS FAILED"]),
+        _ => (&[], &[]),
+    };
+
+    Expectations {
+        pass: pass.iter().map(|marker| marker.to_string()).collect(),
+        fail: fail
+            .iter()
+            .chain(CRASH_MARKERS.iter())
+            .map(|marker| marker.to_string())
+            .collect(),
+        timeout: DEFAULT_EXPECT_TIMEOUT,
+    }
+}
+
+/// Run the flash-and-monitor command and judge its serial output.
+///
+/// Monitor lines are echoed as they arrive. The monitor is killed as soon as
+/// a marker decides the run or the timeout expires.
+fn run_expect(mut command: Command, expect: &Expectations) -> Result<(), Box<dyn Error>> {
+    // rustup and cargo sit between us and espflash, which holds the serial
+    // port; a process group lets `kill_monitor` reach all of them
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut command, 0);
+
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .spawn()?;
+    let stdout = child.stdout.take().ok_or("monitor output not captured")?;
+
+    let (lines_tx, lines_rx) = mpsc::channel();
+    thread::spawn(move || {
+        for line in BufReader::new(stdout).lines() {
+            let Ok(line) = line else { break };
+            if lines_tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+
+    let deadline = Instant::now() + expect.timeout;
+    let outcome = loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let line = match lines_rx.recv_timeout(remaining) {
+            Ok(line) => line,
+            Err(RecvTimeoutError::Timeout) => {
+                break Err(format!(
+                    "no pass marker within {} s",
+                    expect.timeout.as_secs()
+                ));
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                break Err("monitor exited before a pass marker".to_string());
+            }
+        };
+        println!("{line}");
+
+        if let Some(marker) = expect.fail.iter().find(|m| line.contains(m.as_str())) {
+            break Err(format!("failure marker seen: {marker}"));
+        }
+        if let Some(marker) = expect.pass.iter().find(|m| line.contains(m.as_str())) {
+            break Ok(marker.clone());
+        }
+    };
+
+    // The monitor never exits on its own once the target is running
+    kill_monitor(&mut child);
+
+    match outcome {
+        Ok(marker) => {
+            println!("xtask: expect passed ({marker})");
+            Ok(())
+        }
+        Err(reason) => Err(format!("expect failed: {reason}").into()),
+    }
+}
+
+/// Kill the monitor started by [`run_expect`] and everything it spawned.
+///
+/// On Unix the whole process group goes, so espflash releases the serial
+/// port; elsewhere only the direct child is killed.
+fn kill_monitor(child: &mut Child) {
+    #[cfg(unix)]
+    {
+        let _ = Command::new("kill")
+            .arg("-KILL")
+            .arg("--")
+            .arg(format!("-{}", child.id()))
+            .status();
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// `rustup run esp cargo <args>` with the environment defaults applied.
+fn esp_cargo(cargo_args: &[String]) -> Result<Command, Box<dyn Error>> {
     let mut command = Command::new("rustup");
     command.arg("run").arg("esp").arg("cargo");
-    command.args(&cargo_args);
+    command.args(cargo_args);
 
     if env::var_os("ESP_LOG").is_none() {
         command.env("ESP_LOG", "info");
@@ -390,19 +690,123 @@ fn run_cargo(
         command.env("ESP_IDF_VERSION", "v5.1");
     }
     if env::var_os("CARGO_TARGET_DIR").is_none() {
-        let repo_root = Path::new(XTASK_MANIFEST_DIR)
-            .parent()
-            .ok_or("xtask manifest directory has no parent")?;
-        command.env("CARGO_TARGET_DIR", repo_root.join("target"));
+        command.env("CARGO_TARGET_DIR", repo_root()?.join("target"));
     }
 
-    println!("xtask: rustup run esp cargo {}", cargo_args.join(" "));
+    Ok(command)
+}
 
-    let status = command.status()?;
-    if status.success() {
+fn repo_root() -> Result<&'static Path, Box<dyn Error>> {
+    Ok(Path::new(XTASK_MANIFEST_DIR)
+        .parent()
+        .ok_or("xtask manifest directory has no parent")?)
+}
+
+/// Every distinct combination of [`MATRIX_FEATURES`], each prefixed with `esp32`.
+///
+/// A combination that lists `critical-section` next to a feature that already
+/// enables it builds the same crate as the one without it, so it is skipped.
+fn feature_matrix() -> Vec<Vec<&'static str>> {
+    let mut matrix = Vec::new();
+    for mask in 0..1u32 << MATRIX_FEATURES.len() {
+        let mut combo = vec!["esp32"];
+        combo.extend(
+            MATRIX_FEATURES
+                .iter()
+                .enumerate()
+                .filter(|(bit, _)| mask & (1 << bit) != 0)
+                .map(|(_, feat)| *feat),
+        );
+        let redundant = combo.contains(&"critical-section")
+            && IMPLIES_CRITICAL_SECTION
+                .iter()
+                .any(|feat| combo.contains(feat));
+        if !redundant {
+            matrix.push(combo);
+        }
+    }
+    matrix
+}
+
+/// Build the library for every feature combination and print a result table.
+///
+/// All combinations are built even after a failure so that the table shows
+/// every broken one; cargo output is only shown for the failures.
+fn check_features(profile: Profile) -> Result<(), Box<dyn Error>> {
+    let manifest_path = repo_root()?.join("Cargo.toml");
+    let matrix = feature_matrix();
+    let mut results: Vec<(String, Option<String>)> = Vec::new();
+
+    for (index, combo) in matrix.iter().enumerate() {
+        let features = combo.join(",");
+        let mut cargo_args = vec![
+            "build".to_string(),
+            "--lib".to_string(),
+            "--manifest-path".to_string(),
+            manifest_path.display().to_string(),
+            "--target".to_string(),
+            "xtensa-esp32-none-elf".to_string(),
+            "-Zbuild-std=core".to_string(),
+            "--no-default-features".to_string(),
+            "--features".to_string(),
+            features.clone(),
+        ];
+        if matches!(profile, Profile::Release) {
+            cargo_args.push("--release".to_string());
+        }
+
+        println!("xtask: [{}/{}] {features}", index + 1, matrix.len());
+        let output = esp_cargo(&cargo_args)?
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .output()?;
+
+        let failure = if output.status.success() {
+            None
+        } else {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            eprint!("{stderr}");
+            Some(
+                stderr
+                    .lines()
+                    .find(|line| line.starts_with("error"))
+                    .unwrap_or("cargo failed")
+                    .to_string(),
+            )
+        };
+        results.push((features, failure));
+    }
+
+    let width = results
+        .iter()
+        .map(|(features, _)| features.len())
+        .max()
+        .unwrap_or(0)
+        .max("features".len());
+    println!();
+    println!("{:<width$}  result", "features");
+    println!("{:-<width$}  ------", "");
+    for (features, failure) in &results {
+        match failure {
+            None => println!("{features:<width$}  ok"),
+            Some(error) => println!("{features:<width$}  FAILED: {error}"),
+        }
+    }
+
+    let failed = results
+        .iter()
+        .filter(|(_, failure)| failure.is_some())
+        .count();
+    println!();
+    println!(
+        "xtask: {}/{} combinations built",
+        results.len() - failed,
+        results.len()
+    );
+    if failed == 0 {
         Ok(())
     } else {
-        Err(format!("cargo failed (status: {status:?})").into())
+        Err(format!("{failed} feature combination(s) failed to build").into())
     }
 }
 
@@ -422,6 +826,7 @@ fn needs_linkall(
                 "embassy-net-example"
                     | "esp-hal-async-example"
                     | "esp-hal-example"
+                    | "ota-example"
                     | "smoltcp-example"
             )
         }) {
@@ -430,7 +835,17 @@ fn needs_linkall(
 
         if matches!(
             bin_name,
-            Some("embassy_net" | "esp_hal_async" | "esp_hal_integration" | "smoltcp_echo")
+            Some(
+                "dual_stack"
+                    | "embassy_mqtt"
+                    | "embassy_net"
+                    | "esp_hal_async"
+                    | "esp_hal_integration"
+                    | "eth_failover"
+                    | "ota"
+                    | "smoltcp_echo"
+                    | "udp_multicast"
+            )
         ) {
             return true;
         }
@@ -0,0 +1,301 @@
+//! Section-size report for built app binaries.
+//!
+//! Reads the section headers of the Xtensa ELF directly (no `size` binary from
+//! the ESP toolchain needed), groups allocated sections into text, rodata,
+//! data, and bss by their flags, and estimates how much of the RAM footprint is
+//! the EMAC DMA descriptors and buffers for the `Emac<RX, TX, BUF>` parameters
+//! found in the app source.
+
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Section header flag: writable at run time.
+const SHF_WRITE: u32 = 0x1;
+/// Section header flag: occupies memory on the target.
+const SHF_ALLOC: u32 = 0x2;
+/// Section header flag: executable code.
+const SHF_EXECINSTR: u32 = 0x4;
+/// Section type without file contents (zero-initialized).
+const SHT_NOBITS: u32 = 8;
+
+/// Bytes per DMA descriptor (alternate descriptor layout on the ESP32).
+const DESCRIPTOR_SIZE: usize = 32;
+
+/// One allocated section of the ELF.
+pub struct Section {
+    pub name: String,
+    pub size: u64,
+    pub kind: SectionKind,
+}
+
+/// Footprint class of an allocated section.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum SectionKind {
+    Text,
+    Rodata,
+    Data,
+    Bss,
+}
+
+impl SectionKind {
+    fn classify(sh_type: u32, flags: u32) -> Self {
+        if sh_type == SHT_NOBITS {
+            Self::Bss
+        } else if flags & SHF_EXECINSTR != 0 {
+            Self::Text
+        } else if flags & SHF_WRITE != 0 {
+            Self::Data
+        } else {
+            Self::Rodata
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Text => "text",
+            Self::Rodata => "rodata",
+            Self::Data => "data",
+            Self::Bss => "bss",
+        }
+    }
+}
+
+/// `Emac<RX_BUFS, TX_BUFS, BUF_SIZE>` parameters of an app.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct EmacParams {
+    pub rx_bufs: usize,
+    pub tx_bufs: usize,
+    pub buf_size: usize,
+}
+
+impl EmacParams {
+    /// Bytes of descriptors plus buffers, mirroring `DmaEngine::memory_report`.
+    pub fn dma_bytes(self) -> usize {
+        let buf = self.buf_size.next_multiple_of(4);
+        (self.rx_bufs + self.tx_bufs) * (DESCRIPTOR_SIZE + buf)
+    }
+}
+
+/// Allocated sections of a 32-bit little-endian ELF file.
+pub fn read_sections(elf: &[u8]) -> Result<Vec<Section>, Box<dyn Error>> {
+    if elf.get(..4) != Some(b"\x7fELF".as_slice()) {
+        return Err("not an ELF file".into());
+    }
+    if elf.get(4) != Some(&1) || elf.get(5) != Some(&1) {
+        return Err("expected a 32-bit little-endian ELF".into());
+    }
+
+    let shoff = read_u32(elf, 0x20)? as usize;
+    let shentsize = usize::from(read_u16(elf, 0x2E)?);
+    let shnum = usize::from(read_u16(elf, 0x30)?);
+    let shstrndx = usize::from(read_u16(elf, 0x32)?);
+
+    let header = |index: usize| shoff + index * shentsize;
+    let strtab = read_u32(elf, header(shstrndx) + 16)? as usize;
+
+    let mut sections = Vec::new();
+    for index in 0..shnum {
+        let base = header(index);
+        let flags = read_u32(elf, base + 8)?;
+        if flags & SHF_ALLOC == 0 {
+            continue;
+        }
+        let size = u64::from(read_u32(elf, base + 20)?);
+        if size == 0 {
+            continue;
+        }
+        let name_offset = strtab + read_u32(elf, base)? as usize;
+        let name = elf
+            .get(name_offset..)
+            .and_then(|rest| rest.split(|&b| b == 0).next())
+            .map(|raw| String::from_utf8_lossy(raw).into_owned())
+            .unwrap_or_default();
+        sections.push(Section {
+            name,
+            size,
+            kind: SectionKind::classify(read_u32(elf, base + 4)?, flags),
+        });
+    }
+
+    Ok(sections)
+}
+
+fn read_u16(elf: &[u8], offset: usize) -> Result<u16, Box<dyn Error>> {
+    let bytes = elf.get(offset..offset + 2).ok_or("truncated ELF header")?;
+    Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32(elf: &[u8], offset: usize) -> Result<u32, Box<dyn Error>> {
+    let bytes = elf.get(offset..offset + 4).ok_or("truncated ELF header")?;
+    Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// Find the EMAC parameters an app uses.
+///
+/// Looks at the entry file first, then at the `.rs` files in subdirectories of
+/// its directory (the qa-runner keeps its `Emac` in `tests/`). Sibling entry
+/// files are not scanned since they belong to other binaries.
+pub fn find_emac_params(entry: &Path) -> Option<EmacParams> {
+    if let Some(params) = fs::read_to_string(entry)
+        .ok()
+        .and_then(|src| parse_emac_params(&src))
+    {
+        return Some(params);
+    }
+
+    let mut files = Vec::new();
+    for dir in fs::read_dir(entry.parent()?).ok()?.flatten() {
+        if dir.path().is_dir() {
+            collect_rs_files(&dir.path(), &mut files);
+        }
+    }
+    files.sort();
+    files
+        .iter()
+        .filter_map(|file| fs::read_to_string(file).ok())
+        .find_map(|src| parse_emac_params(&src))
+}
+
+fn collect_rs_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_rs_files(&path, files);
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            files.push(path);
+        }
+    }
+}
+
+/// First concrete `Emac` instantiation in `src`.
+///
+/// Recognizes `Emac<..>`, `Emac::<..>`, the `emac_static_sync!` /
+/// `emac_static_async!` macros, and the `EmacDefault`/`EmacSmall`/`EmacLarge`
+/// aliases. Generic uses such as `Emac<RX, TX, BUF>` are skipped.
+pub fn parse_emac_params(src: &str) -> Option<EmacParams> {
+    let patterns: [(&str, char, usize); 4] = [
+        ("Emac<", '>', 0),
+        ("Emac::<", '>', 0),
+        ("emac_static_sync!(", ')', 1),
+        ("emac_static_async!(", ')', 2),
+    ];
+
+    let mut found: Option<(usize, EmacParams)> = None;
+    for (pattern, close, skip) in patterns {
+        for (pos, _) in src.match_indices(pattern) {
+            let args = &src[pos + pattern.len()..];
+            let Some(end) = args.find(close) else {
+                continue;
+            };
+            let numbers: Vec<usize> = args[..end]
+                .split(',')
+                .skip(skip)
+                .filter_map(|arg| arg.trim().parse().ok())
+                .collect();
+            if let [rx_bufs, tx_bufs, buf_size] = numbers[..]
+                && found.is_none_or(|(first, _)| pos < first)
+            {
+                found = Some((
+                    pos,
+                    EmacParams {
+                        rx_bufs,
+                        tx_bufs,
+                        buf_size,
+                    },
+                ));
+            }
+        }
+    }
+
+    for (alias, rx_bufs, tx_bufs) in [
+        ("EmacDefault", 10, 10),
+        ("EmacSmall", 4, 4),
+        ("EmacLarge", 16, 16),
+    ] {
+        if let Some(pos) = src.find(alias)
+            && found.is_none_or(|(first, _)| pos < first)
+        {
+            found = Some((
+                pos,
+                EmacParams {
+                    rx_bufs,
+                    tx_bufs,
+                    buf_size: 1600,
+                },
+            ));
+        }
+    }
+
+    found.map(|(_, params)| params)
+}
+
+/// Print the per-section table and the text/rodata/data/bss totals.
+pub fn print_report(binary: &Path, sections: &[Section], emac: Option<EmacParams>) {
+    let width = sections
+        .iter()
+        .map(|section| section.name.len())
+        .max()
+        .unwrap_or(0)
+        .max("section".len());
+
+    println!();
+    println!("{}", binary.display());
+    println!();
+    println!("{:<width$}  {:>10}  class", "section", "bytes");
+    println!("{:-<width$}  {:->10}  ------", "", "");
+    for section in sections {
+        println!(
+            "{:<width$}  {:>10}  {}",
+            section.name,
+            section.size,
+            section.kind.as_str()
+        );
+    }
+
+    let total = |kind: SectionKind| -> u64 {
+        sections
+            .iter()
+            .filter(|section| section.kind == kind)
+            .map(|section| section.size)
+            .sum()
+    };
+    let text = total(SectionKind::Text);
+    let rodata = total(SectionKind::Rodata);
+    let data = total(SectionKind::Data);
+    let bss = total(SectionKind::Bss);
+    let ram = data + bss;
+
+    println!();
+    println!("text    {text:>10}");
+    println!("rodata  {rodata:>10}");
+    println!("data    {data:>10}");
+    println!("bss     {bss:>10}");
+    println!(
+        "flash   {:>10}  (text + rodata + data)",
+        text + rodata + data
+    );
+    println!("ram     {ram:>10}  (data + bss)");
+
+    println!();
+    match emac {
+        Some(params) => {
+            let dma = params.dma_bytes() as u64;
+            let share = if ram == 0 {
+                0.0
+            } else {
+                dma as f64 * 100.0 / ram as f64
+            };
+            println!(
+                "EMAC DMA (Emac<{}, {}, {}>): {dma} bytes, {share:.1}% of ram (estimate)",
+                params.rx_bufs, params.tx_bufs, params.buf_size
+            );
+        }
+        None => println!("EMAC DMA: no Emac<RX, TX, BUF> found in the app source"),
+    }
+}